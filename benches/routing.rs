@@ -0,0 +1,199 @@
+// ============================================================================
+// 路由基准：A* 之前的封闭探测开 / 关
+//
+// 用法:
+//   cargo bench --bench routing
+//
+// 说明:
+// - `user_case/*`：`ascii_user_case_*` 用的用户复现图（strict / relaxed × ASCII / Unicode），整图 native 渲染；
+//   `no_probe` 用 `ts_compatible`（关掉探测，展开过程与 TS 相同）；
+// - `grid/*`：大网格上的纯寻路（`RoutingGrid::route`，`set_enclosure_probe` 开关探测）；
+//   `*_enclosed` 把终点围死（走不通），对应整图渲染里大量“此路不通”的候选端口，
+//   不带后缀的是走得通的开阔网格，看的是探测的额外开销；
+// - `parallel/*`：多条互不相连的 LR 流水线，逐条走线 vs 并行预演（`parallel_routing`）；
+// - 计时前先断言开 / 关探测的结果相同（整图输出 / 逐对的路径与代价），
+//   完整的回归在 `tests/enclosure_probe.rs`、`tests/parallel_routing.rs`。
+// ============================================================================
+
+use beautiful_mermaid_rs::routing::{GridPoint, Route, RouteConstraints, RouteRules, RoutingGrid};
+use beautiful_mermaid_rs::{AsciiRenderOptions, AsciiRouting, Backend, render_mermaid_ascii};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+const PROBES: [(&str, bool); 2] = [("probe", true), ("no_probe", false)];
+
+/// golden 里 `---` 之前的 Mermaid 文本（这个用例没有配置行）。
+fn user_case() -> String {
+    let raw = include_str!("../tests/testdata/unicode/user_repro_case.txt");
+    let (diagram, _) = raw.split_once("\n---\n").expect("golden 缺少分隔符 `---`");
+    format!("{diagram}\n")
+}
+
+fn bench_user_case(c: &mut Criterion) {
+    let diagram = user_case();
+    let mut group = c.benchmark_group("user_case");
+    // A* 整图一次要几百毫秒到几秒，默认 100 个样本太久
+    group.sample_size(10);
+    for (name, use_ascii, routing) in [
+        ("ascii_strict", true, AsciiRouting::Strict),
+        ("ascii_relaxed", true, AsciiRouting::Relaxed),
+        ("unicode_strict", false, AsciiRouting::Strict),
+        ("unicode_relaxed", false, AsciiRouting::Relaxed),
+    ] {
+        let options = |probe: bool| AsciiRenderOptions {
+            use_ascii: Some(use_ascii),
+            routing: Some(routing),
+            backend: Some(Backend::Native),
+            ts_compatible: Some(!probe),
+            ..Default::default()
+        };
+        assert_eq!(
+            render_mermaid_ascii(&diagram, &options(true)).unwrap(),
+            render_mermaid_ascii(&diagram, &options(false)).unwrap(),
+            "{name}"
+        );
+        for (mode, probe) in PROBES {
+            group.bench_with_input(
+                BenchmarkId::new(name, mode),
+                &options(probe),
+                |b, options| b.iter(|| render_mermaid_ascii(black_box(&diagram), options).unwrap()),
+            );
+        }
+    }
+    group.finish();
+}
+
+/// 固定种子的大网格：随机矩形障碍 + 一批横贯全图的端点对。
+fn large_grid() -> (RoutingGrid, Vec<(GridPoint, GridPoint)>) {
+    let (width, height) = (240, 160);
+    let mut state = 0x5eed_u64;
+    let mut next = |bound: u32| {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((state >> 33) % bound as u64) as u32
+    };
+    let mut grid = RoutingGrid::new(width, height);
+    for _ in 0..400 {
+        let origin = GridPoint::new(next(width), next(height));
+        grid.block_rect(origin, 2 + next(8), 1 + next(4));
+    }
+    let mut pairs = Vec::new();
+    while pairs.len() < 16 {
+        let from = GridPoint::new(next(width / 8), next(height));
+        let to = GridPoint::new(width - 1 - next(width / 8), next(height));
+        if !grid.is_blocked(from) && !grid.is_blocked(to) {
+            pairs.push((from, to));
+        }
+    }
+    (grid, pairs)
+}
+
+/// 在 `large_grid` 上把每对的终点用一圈障碍围死：走不通时单向 A* 要翻遍整张网格。
+fn enclosed_grid() -> (RoutingGrid, Vec<(GridPoint, GridPoint)>) {
+    let (mut grid, pairs) = large_grid();
+    for &(_, to) in &pairs {
+        for dy in -1i64..=1 {
+            for dx in -1i64..=1 {
+                let (x, y) = (to.x as i64 + dx, to.y as i64 + dy);
+                let inside =
+                    (0..grid.width() as i64).contains(&x) && (0..grid.height() as i64).contains(&y);
+                if (dx, dy) != (0, 0) && inside {
+                    grid.set_blocked(GridPoint::new(x as u32, y as u32), true);
+                }
+            }
+        }
+    }
+    (grid, pairs)
+}
+
+fn bench_grid(c: &mut Criterion) {
+    let mut group = c.benchmark_group("grid");
+    for (name, enclosed, rules) in [
+        ("free", false, RouteRules::Free),
+        ("relaxed", false, RouteRules::Relaxed),
+        ("free_enclosed", true, RouteRules::Free),
+        ("relaxed_enclosed", true, RouteRules::Relaxed),
+    ] {
+        let (mut grid, pairs) = if enclosed {
+            enclosed_grid()
+        } else {
+            large_grid()
+        };
+        let constraints = RouteConstraints {
+            rules,
+            source_id: 1,
+            target_id: 2,
+            ..Default::default()
+        };
+        let key = |route: Option<Route>| route.map(|route| (route.points, route.cost));
+        for &(from, to) in &pairs {
+            grid.set_enclosure_probe(false);
+            let plain = key(grid.route(from, to, &constraints));
+            grid.set_enclosure_probe(true);
+            assert_eq!(
+                key(grid.route(from, to, &constraints)),
+                plain,
+                "{from:?} -> {to:?}"
+            );
+        }
+        for (mode, probe) in PROBES {
+            grid.set_enclosure_probe(probe);
+            group.bench_function(BenchmarkId::new(name, mode), |b| {
+                b.iter(|| {
+                    for &(from, to) in &pairs {
+                        black_box(grid.route(from, to, &constraints));
+                    }
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+/// `count` 条互不相连的流水线，每条带一条回边。
+fn pipelines(count: usize) -> String {
+    let mut diagram = String::from("flowchart LR\n");
+    for i in 0..count {
+        diagram += &format!(
+            "  P{i}a[fetch] --> P{i}b[parse] --> P{i}c[validate] --> P{i}d[transform] --> P{i}e[store]\n"
+        );
+        diagram += &format!("  P{i}d -->|retry| P{i}b\n");
+    }
+    diagram
+}
+
+fn bench_parallel(c: &mut Criterion) {
+    let diagram = pipelines(8);
+    let mut group = c.benchmark_group("parallel");
+    group.sample_size(10);
+    for (name, use_ascii, routing) in [
+        ("ascii_relaxed", true, AsciiRouting::Relaxed),
+        ("unicode_relaxed", false, AsciiRouting::Relaxed),
+        ("unicode_global", false, AsciiRouting::Global),
+    ] {
+        let options = |parallel| AsciiRenderOptions {
+            use_ascii: Some(use_ascii),
+            routing: Some(routing),
+            backend: Some(Backend::Native),
+            parallel_routing: Some(parallel),
+            ..Default::default()
+        };
+        assert_eq!(
+            render_mermaid_ascii(&diagram, &options(false)).unwrap(),
+            render_mermaid_ascii(&diagram, &options(true)).unwrap(),
+            "{name}"
+        );
+        for (mode, parallel) in [("serial", false), ("parallel", true)] {
+            group.bench_with_input(
+                BenchmarkId::new(name, mode),
+                &options(parallel),
+                |b, options| b.iter(|| render_mermaid_ascii(black_box(&diagram), options).unwrap()),
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_user_case, bench_grid, bench_parallel);
+criterion_main!(benches);
@@ -0,0 +1,8 @@
+// ============================================================================
+// 构建脚本：目前只给 Node 扩展（feature = "node"）设置平台相关的链接参数。
+// ============================================================================
+
+fn main() {
+    #[cfg(feature = "node")]
+    napi_build::setup();
+}
@@ -0,0 +1,101 @@
+// ============================================================================
+// 调试工具: 逐条边查看 native flowchart 走线时 A* 看到的网格。
+//
+// 为什么要有这个 example:
+// - 某条边绕远 / 走不通时，只看最终字符画很难判断是哪里挡住了它；
+// - `render_mermaid_ascii_with_debug` 对每次走线记录一帧：走线前的 blocked 掩码、
+//   used points 方向位、segment usage 表，以及这条边所有 A* 搜索的展开热度与被拒单步。
+//
+// 用法:
+//   cargo run --release --example debug_routing_grid
+//   cargo run --release --example debug_routing_grid -- --ascii --strict
+//   cargo run --release --example debug_routing_grid -- --svg /tmp/routing.svg diagram.mmd
+//
+// 说明:
+// - 不带文件参数时使用用户复现图（与 `debug_user_case` 相同）；
+// - 每帧的文本视图打印到 stdout；`--svg <path>` 另外写一份逐帧播放的 SVG 热力图，
+//   用浏览器打开即可（每帧 1.5 秒，循环播放）；
+// - `--edge <n>` 只打印第 n 帧（从 0 开始）。
+// ============================================================================
+
+use beautiful_mermaid_rs::routing::debug::animated_svg;
+use beautiful_mermaid_rs::{AsciiRenderOptions, AsciiRouting, render_mermaid_ascii_with_debug};
+
+const USER_CASE: &str = r#"flowchart TD
+Hat_ralph["ralph#1 (coordinator)"]
+Hat_experiment_auditor[<0001f9fe> 结果审计员]
+Hat_experiment_integrator[<0001f9e9> 集成验收员]
+Hat_experiment_runner[<0001f9ea> 实验执行器]
+Start[task.start]
+Start --> Hat_ralph
+Complete[complete]
+Hat_experiment_auditor -->|experiment.reviewed| Hat_ralph
+Hat_experiment_integrator -->|experiment.complete| Complete
+Hat_experiment_integrator -->|experiment.complete| Hat_ralph
+Hat_experiment_integrator -->|integration.applied| Hat_ralph
+Hat_experiment_integrator -->|integration.blocked| Hat_ralph
+Hat_experiment_integrator -->|integration.rejected| Hat_ralph
+Hat_experiment_runner -->|experiment.result| Hat_experiment_auditor
+Hat_ralph -->|experiment.task| Hat_experiment_runner
+Hat_ralph -->|integration.task| Hat_experiment_integrator
+"#;
+
+fn main() {
+    let mut use_ascii = false;
+    let mut routing = None;
+    let mut svg_path = None;
+    let mut only_frame: Option<usize> = None;
+    let mut input = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ascii" => use_ascii = true,
+            "--strict" => routing = Some(AsciiRouting::Strict),
+            "--relaxed" => routing = Some(AsciiRouting::Relaxed),
+            "--global" => routing = Some(AsciiRouting::Global),
+            "--svg" => svg_path = args.next(),
+            "--edge" => only_frame = args.next().and_then(|value| value.parse().ok()),
+            _ => input = Some(arg),
+        }
+    }
+
+    let mermaid = match input {
+        Some(path) => std::fs::read_to_string(&path).unwrap_or_else(|err| {
+            eprintln!("读取 {path} 失败: {err}");
+            std::process::exit(1);
+        }),
+        None => USER_CASE.to_string(),
+    };
+    let options = AsciiRenderOptions {
+        use_ascii: Some(use_ascii),
+        routing,
+        ..Default::default()
+    };
+
+    let rendered = match render_mermaid_ascii_with_debug(&mermaid, &options) {
+        Ok(rendered) => rendered,
+        Err(err) => {
+            eprintln!("[display] {err}");
+            eprintln!("[debug] {err:?}");
+            std::process::exit(1);
+        }
+    };
+
+    print!("{}", rendered.text);
+    for (index, frame) in rendered.frames.iter().enumerate() {
+        if only_frame.is_some_and(|only| only != index) {
+            continue;
+        }
+        println!("\n========== 帧 {index} ==========");
+        print!("{}", frame.to_text());
+    }
+
+    if let Some(path) = svg_path {
+        let svg = animated_svg(&rendered.frames, 1.5);
+        if let Err(err) = std::fs::write(&path, svg) {
+            eprintln!("写入 {path} 失败: {err}");
+            std::process::exit(1);
+        }
+        eprintln!("已写入 {path}（{} 帧）", rendered.frames.len());
+    }
+}
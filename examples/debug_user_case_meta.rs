@@ -62,13 +62,17 @@ Hat_ralph -->|integration.task| Hat_experiment_integrator
         },
     )
     .expect("render_mermaid_ascii_with_meta should work");
+    let meta = result
+        .meta
+        .as_flowchart()
+        .expect("flowchart should produce flowchart meta");
 
     // 先输出文本,便于肉眼对照。
     print!("{}", result.text);
 
     // 再输出 meta,便于定量定位问题。
     eprintln!("\n[meta] nodes:");
-    for node in &result.meta.nodes {
+    for node in &meta.nodes {
         eprintln!(
             "  - id={} box={:?} label={:?}",
             node.id, node.box_rect, node.label
@@ -76,7 +80,7 @@ Hat_ralph -->|integration.task| Hat_experiment_integrator
     }
 
     eprintln!("\n[meta] edges (first/last):");
-    for edge in &result.meta.edges {
+    for edge in &meta.edges {
         let first = edge
             .path
             .first()
@@ -88,8 +92,7 @@ Hat_ralph -->|integration.task| Hat_experiment_integrator
             .copied()
             .unwrap_or(AsciiDrawingCoord { x: -1, y: -1 });
 
-        let target_box = result
-            .meta
+        let target_box = meta
             .nodes
             .iter()
             .find(|n| n.id == edge.to)
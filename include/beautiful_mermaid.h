@@ -0,0 +1,90 @@
+/*
+ * beautiful-mermaid-rs C API（由 cbindgen 从 src/ffi.rs 生成，请勿手改）。
+ *
+ * - 入参字符串为 NUL 结尾 UTF-8；options_json 可为 NULL，形状同 RenderOptions / AsciiRenderOptions（camelCase）；
+ * - 返回 BmStatus；成功时 *out 为结果，失败时 *out 为错误信息，都需要 bm_free_string 释放；
+ * - 线程安全：任意线程可并发调用（JS 引擎按线程各自初始化），返回的字符串可在任意线程释放；
+ * - 渲染函数需要 js-backend 或 native-backend feature，bm_validate 需要 validate feature。
+ */
+
+#ifndef BEAUTIFUL_MERMAID_H
+#define BEAUTIFUL_MERMAID_H
+
+/* Warning: this file is autogenerated by cbindgen. Don't modify this manually. */
+
+#include <stdint.h>
+
+// C API 的状态码（由 `BeautifulMermaidError` 映射而来）。
+typedef enum BmStatus {
+  // 成功。
+  BM_STATUS_OK = 0,
+  // 参数无效：NULL 指针、非 UTF-8 字符串、options JSON 解析失败。
+  BM_STATUS_INVALID_ARGUMENT = 1,
+  // JS 引擎（QuickJS）错误。
+  BM_STATUS_JS = 2,
+  // JS 主动抛出的异常。
+  BM_STATUS_JS_EXCEPTION = 3,
+  // JS bundle 初始化失败。
+  BM_STATUS_INIT = 4,
+  // JS 返回值的 JSON 解析失败。
+  BM_STATUS_JSON = 5,
+  // Mermaid 文本解析失败（native 后端）。
+  BM_STATUS_PARSE = 6,
+  // PNG 光栅化失败。
+  BM_STATUS_RASTER = 7,
+  // 需要的后端没有编译进来。
+  BM_STATUS_BACKEND_UNAVAILABLE = 8,
+  // 内部 panic（不应发生，属于 bug）。
+  BM_STATUS_PANIC = 9,
+  // 有边走不通或要放开约束才走通（options 里 `failOnUnroutable: true`）。
+  BM_STATUS_UNROUTABLE = 10,
+} BmStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// 渲染 Mermaid -> SVG。
+//
+// # Safety
+// - `text` 必须指向 NUL 结尾的 UTF-8 字符串；`options_json` 可为 NULL；
+// - `out` 必须为 NULL 或指向可写的 `char *`，写入的字符串需用 `bm_free_string` 释放。
+enum BmStatus bm_render_svg(const char *text,
+                            const char *options_json,
+                            char **out);
+
+// 渲染 Mermaid -> ASCII/Unicode。
+//
+// # Safety
+// 同 `bm_render_svg`。
+enum BmStatus bm_render_ascii(const char *text, const char *options_json, char **out);
+
+// 渲染 Mermaid -> ASCII/Unicode + meta，`*out` 是 `AsciiRenderWithMeta` 的 JSON（`{"text", "meta"}`）。
+//
+// # Safety
+// 同 `bm_render_svg`。
+enum BmStatus bm_render_ascii_meta_json(const char *text,
+                                        const char *options_json,
+                                        char **out);
+
+// 校验 Mermaid 语法，`*out` 是 `MermaidValidation` 的 JSON（`{"isValid", "error", "details"}`）。
+//
+// 语法无效不算失败：返回 `BM_STATUS_OK`，由 JSON 里的 `isValid` 区分。
+//
+// # Safety
+// - `text` 必须指向 NUL 结尾的 UTF-8 字符串；
+// - `out` 必须为 NULL 或指向可写的 `char *`，写入的字符串需用 `bm_free_string` 释放。
+enum BmStatus bm_validate(const char *text,
+                          char **out);
+
+// 释放本库返回的字符串（NULL 时什么也不做）。
+//
+// # Safety
+// `s` 必须为 NULL 或本库写入 `*out` 的指针，且只能释放一次。
+void bm_free_string(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* BEAUTIFUL_MERMAID_H */
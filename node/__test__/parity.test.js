@@ -0,0 +1,152 @@
+// ============================================================================
+// Node 扩展与 vendored TS bundle 的一致性测试（node:test，无第三方依赖）
+//
+// 运行：`npm run build:debug && npm test`（在 node/ 目录）；
+//       或 `BM_NODE_ADDON=/path/to/libbeautiful_mermaid_rs.so node --test node/__test__/`。
+//
+// 对照组：在 V8（`vm` 上下文）里直接执行 vendor/ 下的 browser bundle，纯 JS A*。
+// - 扩展的输出与 bundle 逐字一致（ASCII golden 全集 + 各类图的 SVG）；
+// - bundle 挂上 `installNativePathfinder` 后输出不变，且确实走了 native A*。
+// ============================================================================
+
+'use strict'
+
+const assert = require('node:assert/strict')
+const fs = require('node:fs')
+const path = require('node:path')
+const test = require('node:test')
+const vm = require('node:vm')
+
+const addon = require('..')
+
+const ROOT = path.join(__dirname, '..', '..')
+const BUNDLE = fs.readFileSync(
+  path.join(ROOT, 'vendor', 'beautiful-mermaid', 'beautiful-mermaid.browser.global.js'),
+  'utf8',
+)
+
+/** 每次新建一个干净的 V8 上下文执行 bundle（`globalThis` 即返回的 context 对象）。 */
+function loadBundle() {
+  const context = vm.createContext({})
+  vm.runInContext(BUNDLE, context)
+  return { context, bundle: context.beautifulMermaid }
+}
+
+/** 解析 tests/testdata 的 golden 文件头：padding 指令 + Mermaid 文本（与 tests/ascii_testdata.rs 一致）。 */
+function parseCase(content, useAscii) {
+  const options = { useAscii, paddingX: 5, paddingY: 5 }
+  const keys = { paddingx: 'paddingX', paddingy: 'paddingY', boxborderpadding: 'boxBorderPadding' }
+  const lines = []
+  for (const line of content.replace(/\r\n/g, '\n').split('\n')) {
+    if (line === '---') break
+    const trimmed = line.trim()
+    if (lines.length === 0) {
+      if (trimmed === '') continue
+      const match = /^(\w+)\s*=\s*(\d+)$/.exec(trimmed)
+      if (match && keys[match[1].toLowerCase()]) {
+        options[keys[match[1].toLowerCase()]] = Number(match[2])
+        continue
+      }
+    }
+    lines.push(line)
+  }
+  return { text: lines.join('\n') + '\n', options }
+}
+
+function goldenCases() {
+  const cases = []
+  for (const [dir, useAscii] of [
+    ['ascii', true],
+    ['unicode', false],
+  ]) {
+    const full = path.join(ROOT, 'tests', 'testdata', dir)
+    for (const file of fs.readdirSync(full).sort()) {
+      if (!file.endsWith('.txt')) continue
+      const parsed = parseCase(fs.readFileSync(path.join(full, file), 'utf8'), useAscii)
+      cases.push({ name: `${dir}/${file}`, ...parsed })
+    }
+  }
+  return cases
+}
+
+const SVG_CASES = {
+  flowchart: 'graph TD\n  A[Start] --> B{Check}\n  B -->|yes| C[Done]\n  B -->|no| A\n',
+  state: 'stateDiagram-v2\n  [*] --> Idle\n  Idle --> Busy: go\n  Busy --> [*]\n',
+  sequence: 'sequenceDiagram\n  Alice->>Bob: hi\n  Bob-->>Alice: ok\n',
+  class: 'classDiagram\n  Animal <|-- Dog\n  Animal : +name string\n',
+  er: 'erDiagram\n  CUSTOMER ||--o{ ORDER : places\n',
+}
+
+test('ASCII/Unicode 与 bundle 逐字一致（golden 全集）', () => {
+  const { bundle } = loadBundle()
+  for (const { name, text, options } of goldenCases()) {
+    assert.equal(addon.renderMermaidAscii(text, options), bundle.renderMermaidAscii(text, options), name)
+  }
+})
+
+test('ASCII meta 与 bundle 一致', () => {
+  const { bundle } = loadBundle()
+  const text = 'graph LR\n  A --> B & C\n'
+  const ours = addon.renderMermaidAsciiWithMeta(text)
+  const theirs = bundle.renderMermaidAsciiWithMeta(text)
+  assert.equal(ours.text, theirs.text)
+  assert.equal(ours.meta.kind, 'flowchart')
+  assert.deepEqual(
+    ours.meta.edges.map((edge) => edge.path),
+    JSON.parse(JSON.stringify(theirs.meta.edges.map((edge) => edge.path))),
+  )
+})
+
+test('SVG 与 bundle 逐字一致', async () => {
+  const { bundle } = loadBundle()
+  for (const [name, text] of Object.entries(SVG_CASES)) {
+    const options = { bg: '#101010', fg: '#eeeeee', idPrefix: 'p-' }
+    assert.equal(await addon.renderMermaid(text, options), await bundle.renderMermaid(text, options), name)
+  }
+  const rendered = await addon.renderMermaidWithMeta(SVG_CASES.flowchart)
+  assert.ok(rendered.svg.startsWith('<svg'))
+  assert.equal(rendered.meta.kind, 'flowchart')
+})
+
+test('THEMES / DEFAULTS / fromShikiTheme 与 bundle 一致', () => {
+  const { bundle } = loadBundle()
+  assert.deepEqual(addon.THEMES, JSON.parse(JSON.stringify(bundle.THEMES)))
+  assert.deepEqual(addon.DEFAULTS, JSON.parse(JSON.stringify(bundle.DEFAULTS)))
+  const shiki = {
+    type: 'dark',
+    colors: { 'editor.background': '#1e1e1e', 'editor.foreground': '#d4d4d4' },
+    tokenColors: [{ scope: 'keyword', settings: { foreground: '#569cd6' } }],
+  }
+  assert.deepEqual(addon.fromShikiTheme(shiki), JSON.parse(JSON.stringify(bundle.fromShikiTheme(shiki))))
+})
+
+test('bundle 挂上 native pathfinder 后输出不变', () => {
+  const { bundle: plain } = loadBundle()
+  const { context, bundle } = loadBundle()
+  const uninstall = addon.installNativePathfinder(context)
+
+  let calls = 0
+  for (const key of ['__bm_getPath', '__bm_getPathStrict', '__bm_getPathRelaxed']) {
+    const native = context[key]
+    context[key] = (...args) => {
+      calls += 1
+      return native(...args)
+    }
+  }
+
+  for (const { name, text, options } of goldenCases()) {
+    assert.equal(bundle.renderMermaidAscii(text, options), plain.renderMermaidAscii(text, options), name)
+  }
+  assert.ok(calls > 0, 'bundle 应当调用 native pathfinder')
+
+  uninstall()
+  assert.equal(context.__bm_getPath, undefined)
+})
+
+test('错误以 Error 抛出 / Promise reject', async () => {
+  assert.throws(() => addon.renderMermaidAscii('   \n', { backend: 'native' }), /Empty mermaid diagram/)
+  assert.throws(() => addon.renderMermaidAscii('graph LR\n A --> B\n', { routing: 'fast' }), /参数错误/)
+  await assert.rejects(addon.renderMermaid('graph LR\n A --> B\n', { padding: 'wide' }), /参数错误/)
+  const validation = addon.validateMermaid('graph LR\n  A -->\n')
+  assert.equal(validation.isValid, false)
+})
@@ -0,0 +1,106 @@
+// 类型声明：形状与 TS 版 beautiful-mermaid 一致（camelCase），额外字段见 README。
+
+export interface DiagramColors {
+  bg: string
+  fg: string
+  line?: string
+  accent?: string
+  muted?: string
+  surface?: string
+  border?: string
+}
+
+export interface RenderOptions extends Partial<DiagramColors> {
+  font?: string
+  padding?: number
+  nodeSpacing?: number
+  layerSpacing?: number
+  transparent?: boolean
+  idPrefix?: string
+  title?: string
+  description?: string
+  /** Rust 扩展独有：`"js"`（默认，内嵌 bundle）或 `"native"`（纯 Rust）。 */
+  backend?: 'js' | 'native'
+}
+
+export interface AsciiRenderOptions {
+  useAscii?: boolean
+  /** `"global"`：Rust 扩展独有，relaxed + 全局 rip-up-and-reroute（总走 native 后端）。 */
+  routing?: 'strict' | 'relaxed' | 'global'
+  /** Rust 扩展独有：走线每次转弯的额外代价（默认 0，与 TS 一致）。 */
+  turnPenalty?: number
+  /** Rust 扩展独有：`true` 时忽略 `turnPenalty`，走线与 TS 逐字节一致。 */
+  tsCompatible?: boolean
+  /** Rust 扩展独有：互不相连的子图在多个线程上并行走线，输出不变（默认自动；`false` 总是逐条走线；native 后端）。 */
+  parallelRouting?: boolean
+  /** Rust 扩展独有：有边走不通、或要放开约束才走通时抛错，而不是照常输出（flowchart/state 总走 native 后端）。 */
+  failOnUnroutable?: boolean
+  paddingX?: number
+  paddingY?: number
+  boxBorderPadding?: number
+  /** Rust 扩展独有：`"js"`（默认，内嵌 bundle）或 `"native"`（纯 Rust）。 */
+  backend?: 'js' | 'native'
+}
+
+/** meta 以 `kind` 区分图类型，字段同 Rust `types.rs` 的 serde 形状。 */
+export interface DiagramMeta {
+  kind: 'flowchart' | 'sequence' | 'class' | 'er'
+  [key: string]: unknown
+}
+
+export interface AsciiRenderWithMeta {
+  text: string
+  meta: DiagramMeta
+}
+
+export interface SvgRenderWithMeta {
+  svg: string
+  viewBox: { x: number; y: number; width: number; height: number }
+  meta: DiagramMeta
+}
+
+export interface MermaidValidation {
+  isValid: boolean
+  error: string | null
+  details: string | null
+}
+
+export function renderMermaid(text: string, options?: RenderOptions): Promise<string>
+export function renderMermaidWithMeta(text: string, options?: RenderOptions): Promise<SvgRenderWithMeta>
+export function renderMermaidAscii(text: string, options?: AsciiRenderOptions): string
+export function renderMermaidAsciiWithMeta(text: string, options?: AsciiRenderOptions): AsciiRenderWithMeta
+export function validateMermaid(text: string): MermaidValidation
+export function fromShikiTheme(theme: unknown): DiagramColors
+
+export const THEMES: Readonly<Record<string, DiagramColors>>
+export const DEFAULTS: Readonly<DiagramColors>
+
+/** 把 native A* 注册成 `target.__bm_getPath*`（默认 `globalThis`），返回撤销函数。 */
+export function installNativePathfinder(target?: object): () => void
+
+export function getPath(
+  stride: number,
+  fromIdx: number,
+  toIdx: number,
+  maxX: number,
+  maxY: number,
+  blocked: Uint8Array,
+): number[] | null
+export function getPathStrict(
+  stride: number,
+  fromIdx: number,
+  toIdx: number,
+  maxX: number,
+  maxY: number,
+  blocked: Uint8Array,
+  constraints: object,
+): number[] | null
+export function getPathRelaxed(
+  stride: number,
+  fromIdx: number,
+  toIdx: number,
+  maxX: number,
+  maxY: number,
+  blocked: Uint8Array,
+  constraints: object,
+): { path: number[]; cost: number } | null
@@ -0,0 +1,91 @@
+// ============================================================================
+// beautiful-mermaid-rs（Node）：加载 napi 扩展并导出与 TS 版同名的 API
+//
+// - `renderMermaid` / `renderMermaidWithMeta`：Promise（与 TS 版一致）；
+// - `renderMermaidAscii` / `renderMermaidAsciiWithMeta`：同步；
+// - `THEMES` / `DEFAULTS` / `fromShikiTheme`：与 TS 版同名同形；
+// - `installNativePathfinder()`：给仍在用 TS 包的代码挂上 Rust A*（见 README）。
+// ============================================================================
+
+'use strict'
+
+const { existsSync } = require('node:fs')
+const { join } = require('node:path')
+
+const NAME = 'beautiful-mermaid-rs'
+
+/** 与 `napi build --platform` 的产物命名一致（linux 区分 gnu / musl）。 */
+function platformSuffix() {
+  const { platform, arch } = process
+  if (platform === 'linux') {
+    const report = process.report && process.report.getReport()
+    const glibc = report && report.header && report.header.glibcVersionRuntime
+    return `linux-${arch}-${glibc ? 'gnu' : 'musl'}`
+  }
+  if (platform === 'win32') {
+    return `win32-${arch}-msvc`
+  }
+  return `${platform}-${arch}`
+}
+
+function loadBinding() {
+  // 调试/测试用：直接指定 .node 路径（例如 cargo build 产出的 cdylib 拷贝）
+  if (process.env.BM_NODE_ADDON) {
+    return require(process.env.BM_NODE_ADDON)
+  }
+  const candidates = [`${NAME}.${platformSuffix()}.node`, `${NAME}.node`]
+  for (const file of candidates) {
+    const path = join(__dirname, file)
+    if (existsSync(path)) {
+      return require(path)
+    }
+  }
+  throw new Error(
+    `${NAME}: 找不到当前平台的原生扩展（尝试过 ${candidates.join(', ')}），请先运行 \`npm run build\``,
+  )
+}
+
+const binding = loadBinding()
+
+/**
+ * 把 native A* 注册成 `target.__bm_getPath*`（默认 `globalThis`）。
+ *
+ * TS 版 bundle 在路由时会检测这几个全局函数：注册之后，同一进程里的 TS `beautiful-mermaid`
+ * 也会改走 Rust 路由（输出不变，只是更快）。返回一个撤销函数，恢复注册前的值。
+ */
+function installNativePathfinder(target = globalThis) {
+  const keys = {
+    __bm_getPath: binding.getPath,
+    __bm_getPathStrict: binding.getPathStrict,
+    __bm_getPathRelaxed: binding.getPathRelaxed,
+  }
+  const previous = {}
+  for (const [key, fn] of Object.entries(keys)) {
+    previous[key] = target[key]
+    target[key] = fn
+  }
+  return function uninstallNativePathfinder() {
+    for (const [key, fn] of Object.entries(previous)) {
+      if (fn === undefined) {
+        delete target[key]
+      } else {
+        target[key] = fn
+      }
+    }
+  }
+}
+
+module.exports = {
+  renderMermaid: binding.renderMermaid,
+  renderMermaidWithMeta: binding.renderMermaidWithMeta,
+  renderMermaidAscii: binding.renderMermaidAscii,
+  renderMermaidAsciiWithMeta: binding.renderMermaidAsciiWithMeta,
+  validateMermaid: binding.validateMermaid,
+  fromShikiTheme: binding.fromShikiTheme,
+  THEMES: Object.freeze(binding.themes()),
+  DEFAULTS: Object.freeze(binding.defaults()),
+  installNativePathfinder,
+  getPath: binding.getPath,
+  getPathStrict: binding.getPathStrict,
+  getPathRelaxed: binding.getPathRelaxed,
+}
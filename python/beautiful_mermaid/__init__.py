@@ -0,0 +1,66 @@
+"""beautiful-mermaid 的 Python 绑定：Mermaid -> SVG / ASCII/Unicode。
+
+>>> import beautiful_mermaid
+>>> svg = beautiful_mermaid.render_svg("graph LR\\n  A --> B", theme="nord")
+>>> print(beautiful_mermaid.render_ascii("graph LR\\n  A --> B"))
+
+渲染在 Rust 侧完成，调用期间释放 GIL（可以放进线程池并行渲染）。
+"""
+
+from __future__ import annotations
+
+import json
+from typing import Any
+
+from . import _native
+from ._native import (
+    THEMES,
+    BackendUnavailableError,
+    InitError,
+    JsError,
+    JsonError,
+    MermaidError,
+    ParseError,
+    RasterError,
+    UnroutableError,
+    render_ascii,
+    render_svg,
+)
+from . import _types
+from ._types import *  # noqa: F403
+from ._types import AsciiRenderWithMeta, MermaidValidation, SvgRenderWithMeta, _from_json
+
+__all__ = [
+    "THEMES",
+    "render_svg",
+    "render_svg_with_meta",
+    "render_ascii",
+    "render_ascii_with_meta",
+    "validate",
+    "MermaidError",
+    "JsError",
+    "InitError",
+    "JsonError",
+    "ParseError",
+    "RasterError",
+    "BackendUnavailableError",
+    "UnroutableError",
+    *_types.__all__,
+]
+
+
+def render_svg_with_meta(text: str, **options: Any) -> SvgRenderWithMeta:
+    """渲染 SVG 并返回元素坐标；关键字参数同 `render_svg`。"""
+    data = json.loads(_native.render_svg_with_meta_json(text, **options))
+    return _from_json(SvgRenderWithMeta, data)
+
+
+def render_ascii_with_meta(text: str, **options: Any) -> AsciiRenderWithMeta:
+    """渲染 ASCII/Unicode 并返回 cell 坐标；关键字参数同 `render_ascii`。"""
+    data = json.loads(_native.render_ascii_with_meta_json(text, **options))
+    return _from_json(AsciiRenderWithMeta, data)
+
+
+def validate(text: str) -> MermaidValidation:
+    """校验 Mermaid 语法；语法无效不抛异常，由 `is_valid` 区分。"""
+    return _from_json(MermaidValidation, json.loads(_native.validate_json(text)))
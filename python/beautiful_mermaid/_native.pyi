@@ -0,0 +1,51 @@
+from typing import Optional
+
+THEMES: dict[str, dict[str, Optional[str]]]
+
+class MermaidError(Exception): ...
+class JsError(MermaidError): ...
+class InitError(MermaidError): ...
+class JsonError(MermaidError): ...
+class ParseError(MermaidError): ...
+class RasterError(MermaidError): ...
+class BackendUnavailableError(MermaidError): ...
+class UnroutableError(MermaidError): ...
+
+def render_svg(
+    text: str,
+    *,
+    theme: Optional[str] = None,
+    bg: Optional[str] = None,
+    fg: Optional[str] = None,
+    line: Optional[str] = None,
+    accent: Optional[str] = None,
+    muted: Optional[str] = None,
+    surface: Optional[str] = None,
+    border: Optional[str] = None,
+    font: Optional[str] = None,
+    padding: Optional[float] = None,
+    node_spacing: Optional[float] = None,
+    layer_spacing: Optional[float] = None,
+    transparent: Optional[bool] = None,
+    id_prefix: Optional[str] = None,
+    title: Optional[str] = None,
+    description: Optional[str] = None,
+    backend: Optional[str] = None,
+) -> str: ...
+def render_svg_with_meta_json(text: str, **options: object) -> str: ...
+def render_ascii(
+    text: str,
+    *,
+    use_ascii: Optional[bool] = None,
+    routing: Optional[str] = None,
+    turn_penalty: Optional[int] = None,
+    ts_compatible: Optional[bool] = None,
+    parallel_routing: Optional[bool] = None,
+    fail_on_unroutable: Optional[bool] = None,
+    padding_x: Optional[int] = None,
+    padding_y: Optional[int] = None,
+    box_border_padding: Optional[int] = None,
+    backend: Optional[str] = None,
+) -> str: ...
+def render_ascii_with_meta_json(text: str, **options: object) -> str: ...
+def validate_json(text: str) -> str: ...
@@ -0,0 +1,471 @@
+"""meta / validation 的 dataclass（与 Rust `types.rs` 一一对应）。
+
+字段名沿用 Rust 侧的 snake_case；JSON 里的 camelCase / `box` / `type`
+在 `_from_json` 里按字段元数据换回来。
+"""
+
+from __future__ import annotations
+
+import dataclasses
+import sys
+import typing
+from dataclasses import dataclass, field
+from typing import Any, Optional, Union
+
+
+def _renamed(json_name: str) -> Any:
+    return field(metadata={"json": json_name})
+
+
+def _camel(name: str) -> str:
+    head, *rest = name.split("_")
+    return head + "".join(part.capitalize() for part in rest)
+
+
+# ============================================================================
+# validation
+# ============================================================================
+
+
+@dataclass(frozen=True)
+class MermaidValidation:
+    is_valid: bool
+    error: Optional[str]
+    details: Optional[str]
+
+
+# ============================================================================
+# ASCII/Unicode meta（坐标单位：终端 cell）
+# ============================================================================
+
+
+@dataclass(frozen=True)
+class AsciiDrawingCoord:
+    x: int
+    y: int
+
+
+@dataclass(frozen=True)
+class AsciiBox:
+    x: int
+    y: int
+    width: int
+    height: int
+
+
+@dataclass(frozen=True)
+class AsciiRenderMetaNode:
+    id: str
+    label: str
+    box_rect: AsciiBox = _renamed("box")
+
+
+@dataclass(frozen=True)
+class AsciiRenderMetaEdge:
+    from_: str = _renamed("from")
+    to: str = _renamed("to")
+    label: str = _renamed("label")
+    path: list[AsciiDrawingCoord] = _renamed("path")
+    # "routed" / "routedWithFallback" / "unroutable"；原因 "strictRules" / "relaxedRules"
+    outcome: str = "routed"
+    reason: Optional[str] = None
+
+
+@dataclass(frozen=True)
+class AsciiRenderMeta:
+    nodes: list[AsciiRenderMetaNode]
+    edges: list[AsciiRenderMetaEdge]
+    kind: str = "flowchart"
+    # 实际走线的后端 "js" / "native"（fail_on_unroutable / routing="global" 会换成 native）
+    backend: str = "js"
+
+
+@dataclass(frozen=True)
+class AsciiSequenceParticipant:
+    id: str
+    label: str
+    participant_type: str = _renamed("type")
+    header_box: AsciiBox = _renamed("headerBox")
+    footer_box: AsciiBox = _renamed("footerBox")
+    lifeline_x: int = _renamed("lifelineX")
+    lifeline_top: int = _renamed("lifelineTop")
+    lifeline_bottom: int = _renamed("lifelineBottom")
+
+
+@dataclass(frozen=True)
+class AsciiSequenceMessage:
+    index: int
+    from_: str = _renamed("from")
+    to: str = _renamed("to")
+    label: str = _renamed("label")
+    line_style: str = _renamed("lineStyle")
+    arrow_head: str = _renamed("arrowHead")
+    is_self: bool = _renamed("isSelf")
+    row: int = _renamed("row")
+    span_start_x: int = _renamed("spanStartX")
+    span_end_x: int = _renamed("spanEndX")
+    label_box: AsciiBox = _renamed("labelBox")
+    path: list[AsciiDrawingCoord] = _renamed("path")
+
+
+@dataclass(frozen=True)
+class AsciiSequenceNote:
+    actor_ids: list[str]
+    position: str
+    text: str
+    box_rect: AsciiBox = _renamed("box")
+
+
+@dataclass(frozen=True)
+class AsciiSequenceDivider:
+    label: str
+    row: int
+
+
+@dataclass(frozen=True)
+class AsciiSequenceBlock:
+    block_type: str = _renamed("type")
+    label: str = _renamed("label")
+    start_index: int = _renamed("startIndex")
+    end_index: int = _renamed("endIndex")
+    box_rect: AsciiBox = _renamed("box")
+    dividers: list[AsciiSequenceDivider] = _renamed("dividers")
+
+
+@dataclass(frozen=True)
+class AsciiSequenceMeta:
+    participants: list[AsciiSequenceParticipant]
+    messages: list[AsciiSequenceMessage]
+    notes: list[AsciiSequenceNote]
+    blocks: list[AsciiSequenceBlock]
+    kind: str = "sequence"
+    # 计算 meta 的后端：总是 "native"（外部 bundle 画的 text 不保证与它对齐）
+    backend: str = "native"
+
+
+@dataclass(frozen=True)
+class AsciiMemberRow:
+    text: str
+    box_rect: AsciiBox = _renamed("box")
+
+
+@dataclass(frozen=True)
+class AsciiClassNode:
+    id: str
+    label: str
+    annotation: Optional[str]
+    box_rect: AsciiBox = _renamed("box")
+    attributes: list[AsciiMemberRow] = _renamed("attributes")
+    methods: list[AsciiMemberRow] = _renamed("methods")
+
+
+@dataclass(frozen=True)
+class AsciiClassRelationship:
+    from_: str = _renamed("from")
+    to: str = _renamed("to")
+    relationship_type: str = _renamed("type")
+    label: Optional[str] = _renamed("label")
+    label_box: Optional[AsciiBox] = _renamed("labelBox")
+    path: list[AsciiDrawingCoord] = _renamed("path")
+    marker: Optional[AsciiDrawingCoord] = _renamed("marker")
+
+
+@dataclass(frozen=True)
+class AsciiClassMeta:
+    classes: list[AsciiClassNode]
+    relationships: list[AsciiClassRelationship]
+    kind: str = "class"
+    # 计算 meta 的后端：总是 "native"（外部 bundle 画的 text 不保证与它对齐）
+    backend: str = "native"
+
+
+@dataclass(frozen=True)
+class AsciiCardinalityMarker:
+    cardinality: str
+    text: str
+    box_rect: AsciiBox = _renamed("box")
+
+
+@dataclass(frozen=True)
+class AsciiErEntity:
+    id: str
+    label: str
+    box_rect: AsciiBox = _renamed("box")
+    attributes: list[AsciiMemberRow] = _renamed("attributes")
+
+
+@dataclass(frozen=True)
+class AsciiErRelationship:
+    from_: str = _renamed("from")
+    to: str = _renamed("to")
+    label: str = _renamed("label")
+    identifying: bool = _renamed("identifying")
+    label_box: Optional[AsciiBox] = _renamed("labelBox")
+    path: list[AsciiDrawingCoord] = _renamed("path")
+    from_cardinality: AsciiCardinalityMarker = _renamed("fromCardinality")
+    to_cardinality: AsciiCardinalityMarker = _renamed("toCardinality")
+
+
+@dataclass(frozen=True)
+class AsciiErMeta:
+    entities: list[AsciiErEntity]
+    relationships: list[AsciiErRelationship]
+    kind: str = "er"
+    # 计算 meta 的后端：总是 "native"（外部 bundle 画的 text 不保证与它对齐）
+    backend: str = "native"
+
+
+AsciiDiagramMeta = Union[AsciiRenderMeta, AsciiSequenceMeta, AsciiClassMeta, AsciiErMeta]
+
+
+@dataclass(frozen=True)
+class AsciiRenderWithMeta:
+    text: str
+    meta: AsciiDiagramMeta
+
+
+# ============================================================================
+# SVG meta（坐标单位：SVG 用户坐标 / 像素）
+# ============================================================================
+
+
+@dataclass(frozen=True)
+class SvgPoint:
+    x: float
+    y: float
+
+
+@dataclass(frozen=True)
+class SvgRect:
+    x: float
+    y: float
+    width: float
+    height: float
+
+
+@dataclass(frozen=True)
+class SvgMetaNode:
+    id: str
+    element_id: str
+    label: str
+    shape: str
+    box_rect: SvgRect = _renamed("box")
+
+
+@dataclass(frozen=True)
+class SvgMetaEdge:
+    from_: str = _renamed("from")
+    to: str = _renamed("to")
+    element_id: str = _renamed("elementId")
+    label: Optional[str] = _renamed("label")
+    style: str = _renamed("style")
+    has_arrow_start: bool = _renamed("hasArrowStart")
+    has_arrow_end: bool = _renamed("hasArrowEnd")
+    points: list[SvgPoint] = _renamed("points")
+    label_position: Optional[SvgPoint] = _renamed("labelPosition")
+
+
+@dataclass(frozen=True)
+class SvgMetaGroup:
+    id: str
+    label: str
+    box_rect: SvgRect = _renamed("box")
+    children: list[SvgMetaGroup] = _renamed("children")
+
+
+@dataclass(frozen=True)
+class SvgFlowchartMeta:
+    nodes: list[SvgMetaNode]
+    edges: list[SvgMetaEdge]
+    groups: list[SvgMetaGroup]
+    kind: str = "flowchart"
+
+
+@dataclass(frozen=True)
+class SvgSequenceParticipant:
+    id: str
+    element_id: str
+    label: str
+    participant_type: str = _renamed("type")
+    box_rect: SvgRect = _renamed("box")
+    lifeline_x: float = _renamed("lifelineX")
+    lifeline_top: float = _renamed("lifelineTop")
+    lifeline_bottom: float = _renamed("lifelineBottom")
+
+
+@dataclass(frozen=True)
+class SvgSequenceMessage:
+    index: int
+    from_: str = _renamed("from")
+    to: str = _renamed("to")
+    element_id: str = _renamed("elementId")
+    label: str = _renamed("label")
+    line_style: str = _renamed("lineStyle")
+    arrow_head: str = _renamed("arrowHead")
+    is_self: bool = _renamed("isSelf")
+    points: list[SvgPoint] = _renamed("points")
+    label_position: SvgPoint = _renamed("labelPosition")
+
+
+@dataclass(frozen=True)
+class SvgSequenceActivation:
+    actor_id: str
+    box_rect: SvgRect = _renamed("box")
+
+
+@dataclass(frozen=True)
+class SvgSequenceNote:
+    text: str
+    box_rect: SvgRect = _renamed("box")
+
+
+@dataclass(frozen=True)
+class SvgSequenceDivider:
+    label: str
+    y: float
+
+
+@dataclass(frozen=True)
+class SvgSequenceBlock:
+    block_type: str = _renamed("type")
+    label: str = _renamed("label")
+    box_rect: SvgRect = _renamed("box")
+    dividers: list[SvgSequenceDivider] = _renamed("dividers")
+
+
+@dataclass(frozen=True)
+class SvgSequenceMeta:
+    participants: list[SvgSequenceParticipant]
+    messages: list[SvgSequenceMessage]
+    activations: list[SvgSequenceActivation]
+    notes: list[SvgSequenceNote]
+    blocks: list[SvgSequenceBlock]
+    kind: str = "sequence"
+
+
+@dataclass(frozen=True)
+class SvgClassNode:
+    id: str
+    element_id: str
+    label: str
+    annotation: Optional[str]
+    box_rect: SvgRect = _renamed("box")
+    header_height: float = _renamed("headerHeight")
+    attributes_height: float = _renamed("attributesHeight")
+    methods_height: float = _renamed("methodsHeight")
+
+
+@dataclass(frozen=True)
+class SvgClassRelationship:
+    from_: str = _renamed("from")
+    to: str = _renamed("to")
+    element_id: str = _renamed("elementId")
+    relationship_type: str = _renamed("type")
+    marker_at: str = _renamed("markerAt")
+    label: Optional[str] = _renamed("label")
+    points: list[SvgPoint] = _renamed("points")
+    label_position: Optional[SvgPoint] = _renamed("labelPosition")
+
+
+@dataclass(frozen=True)
+class SvgClassMeta:
+    classes: list[SvgClassNode]
+    relationships: list[SvgClassRelationship]
+    kind: str = "class"
+
+
+@dataclass(frozen=True)
+class SvgErEntity:
+    id: str
+    element_id: str
+    label: str
+    box_rect: SvgRect = _renamed("box")
+    header_height: float = _renamed("headerHeight")
+    row_height: float = _renamed("rowHeight")
+
+
+@dataclass(frozen=True)
+class SvgErRelationship:
+    from_: str = _renamed("from")
+    to: str = _renamed("to")
+    element_id: str = _renamed("elementId")
+    label: str = _renamed("label")
+    identifying: bool = _renamed("identifying")
+    from_cardinality: str = _renamed("fromCardinality")
+    to_cardinality: str = _renamed("toCardinality")
+    points: list[SvgPoint] = _renamed("points")
+    label_position: Optional[SvgPoint] = _renamed("labelPosition")
+
+
+@dataclass(frozen=True)
+class SvgErMeta:
+    entities: list[SvgErEntity]
+    relationships: list[SvgErRelationship]
+    kind: str = "er"
+
+
+SvgDiagramMeta = Union[SvgFlowchartMeta, SvgSequenceMeta, SvgClassMeta, SvgErMeta]
+
+
+@dataclass(frozen=True)
+class SvgRenderWithMeta:
+    svg: str
+    view_box: SvgRect
+    meta: SvgDiagramMeta
+
+
+# ============================================================================
+# JSON -> dataclass
+# ============================================================================
+
+# 带 `kind` 标签的 meta：按标签选具体类型（与 serde 的 `#[serde(tag = "kind")]` 对应）。
+_TAGGED: dict[Any, dict[str, type]] = {
+    AsciiDiagramMeta: {
+        "flowchart": AsciiRenderMeta,
+        "sequence": AsciiSequenceMeta,
+        "class": AsciiClassMeta,
+        "er": AsciiErMeta,
+    },
+    SvgDiagramMeta: {
+        "flowchart": SvgFlowchartMeta,
+        "sequence": SvgSequenceMeta,
+        "class": SvgClassMeta,
+        "er": SvgErMeta,
+    },
+}
+
+
+def _convert(tp: Any, value: Any) -> Any:
+    if tp in _TAGGED:
+        return _from_json(_TAGGED[tp][value["kind"]], value)
+    origin = typing.get_origin(tp)
+    if origin is list:
+        (item,) = typing.get_args(tp)
+        return [_convert(item, v) for v in value]
+    if origin is Union:
+        # 这里的 Union 只有 Optional[X]
+        if value is None:
+            return None
+        (inner,) = [arg for arg in typing.get_args(tp) if arg is not type(None)]
+        return _convert(inner, value)
+    if dataclasses.is_dataclass(tp):
+        return _from_json(tp, value)
+    return value
+
+
+def _from_json(cls: type, data: dict[str, Any]) -> Any:
+    hints = typing.get_type_hints(cls, vars(sys.modules[__name__]))
+    kwargs = {}
+    for f in dataclasses.fields(cls):
+        key = f.metadata.get("json", _camel(f.name))
+        if key not in data:
+            continue
+        kwargs[f.name] = _convert(hints[f.name], data[key])
+    return cls(**kwargs)
+
+
+__all__ = sorted(
+    name
+    for name, obj in list(globals().items())
+    if not name.startswith("_") and isinstance(obj, type) and dataclasses.is_dataclass(obj)
+) + ["AsciiDiagramMeta", "SvgDiagramMeta"]
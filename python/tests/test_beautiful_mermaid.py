@@ -0,0 +1,135 @@
+"""Python 绑定回归测试（unittest，无第三方依赖）。
+
+运行：`maturin develop && python -m unittest discover -s python/tests`
+"""
+
+import threading
+import unittest
+
+import beautiful_mermaid as bm
+
+FLOWCHART = "graph LR\n  A --> B\n"
+
+
+class RenderTest(unittest.TestCase):
+    def test_render_svg_with_theme(self):
+        svg = bm.render_svg(FLOWCHART, theme="nord")
+        self.assertTrue(svg.startswith("<svg"), svg[:80])
+        self.assertIn(bm.THEMES["nord"]["bg"], svg)
+
+    def test_explicit_color_overrides_theme(self):
+        svg = bm.render_svg(FLOWCHART, theme="nord", bg="#123456")
+        self.assertIn("#123456", svg)
+
+    def test_render_ascii_options(self):
+        text = bm.render_ascii(FLOWCHART, use_ascii=True, padding_x=3)
+        self.assertIn("+", text)
+        self.assertNotIn("┌", text)
+
+    def test_backends_agree_on_flowchart_ascii(self):
+        self.assertEqual(
+            bm.render_ascii(FLOWCHART, backend="js"),
+            bm.render_ascii(FLOWCHART, backend="native"),
+        )
+
+    def test_turn_penalty_reduces_corners(self):
+        diagram = "graph LR\nA --> B\nB --> C\nA --> C\nB --> D\nD --> C\n"
+        plain = bm.render_ascii(diagram, use_ascii=True, backend="native")
+        smooth = bm.render_ascii(diagram, use_ascii=True, backend="native", turn_penalty=2)
+        self.assertLess(smooth.count("+"), plain.count("+"))
+        self.assertEqual(
+            smooth,
+            bm.render_ascii(diagram, use_ascii=True, backend="js", turn_penalty=2),
+        )
+
+    def test_ts_compatible_ignores_turn_penalty(self):
+        diagram = "graph LR\nA --> B\nB --> C\nA --> C\nB --> D\nD --> C\n"
+        self.assertEqual(
+            bm.render_ascii(diagram, use_ascii=True, turn_penalty=2, ts_compatible=True),
+            bm.render_ascii(diagram, use_ascii=True),
+        )
+
+    def test_global_routing(self):
+        diagram = "graph LR\nA-->B\nA-->C\nB-->D\nC-->D\nD-->B\nD-->C\nC-->B\n"
+        relaxed = bm.render_ascii(diagram, use_ascii=True, routing="relaxed", backend="native")
+        rerouted = bm.render_ascii(diagram, use_ascii=True, routing="global")
+        self.assertLess(rerouted.count("+"), relaxed.count("+"))
+        with self.assertRaises(ValueError):
+            bm.render_ascii(diagram, routing="worldwide")
+
+    def test_themes_dict(self):
+        self.assertIn("nord", bm.THEMES)
+        self.assertEqual(
+            set(bm.THEMES["nord"]),
+            {"bg", "fg", "line", "accent", "muted", "surface", "border"},
+        )
+
+
+class MetaTest(unittest.TestCase):
+    def test_ascii_meta_dataclasses(self):
+        rendered = bm.render_ascii_with_meta(FLOWCHART)
+        self.assertIsInstance(rendered, bm.AsciiRenderWithMeta)
+        self.assertIsInstance(rendered.meta, bm.AsciiRenderMeta)
+        self.assertEqual([n.id for n in rendered.meta.nodes], ["A", "B"])
+        edge = rendered.meta.edges[0]
+        self.assertEqual((edge.from_, edge.to), ("A", "B"))
+        self.assertIsInstance(edge.path[0], bm.AsciiDrawingCoord)
+        self.assertIsInstance(rendered.meta.nodes[0].box_rect, bm.AsciiBox)
+
+    def test_ascii_sequence_meta(self):
+        rendered = bm.render_ascii_with_meta(
+            "sequenceDiagram\n  Alice->>Bob: hi\n", backend="native"
+        )
+        self.assertIsInstance(rendered.meta, bm.AsciiSequenceMeta)
+        self.assertEqual(rendered.meta.messages[0].label, "hi")
+        self.assertEqual(rendered.meta.participants[0].participant_type, "participant")
+
+    def test_svg_meta_dataclasses(self):
+        rendered = bm.render_svg_with_meta(FLOWCHART, id_prefix="x-")
+        self.assertIsInstance(rendered, bm.SvgRenderWithMeta)
+        self.assertIsInstance(rendered.view_box, bm.SvgRect)
+        self.assertIsInstance(rendered.meta, bm.SvgFlowchartMeta)
+        self.assertTrue(rendered.meta.nodes[0].element_id.startswith("x-"))
+
+    def test_validate(self):
+        self.assertTrue(bm.validate(FLOWCHART).is_valid)
+        invalid = bm.validate("graph LR\n  A -->")
+        self.assertFalse(invalid.is_valid)
+        self.assertTrue(invalid.error)
+
+
+class ErrorTest(unittest.TestCase):
+    def test_parse_error_maps_to_exception_class(self):
+        with self.assertRaises(bm.ParseError) as ctx:
+            bm.render_ascii("   \n", backend="native")
+        self.assertIsInstance(ctx.exception, bm.MermaidError)
+
+    def test_unknown_theme_and_backend(self):
+        with self.assertRaises(ValueError):
+            bm.render_svg(FLOWCHART, theme="no-such-theme")
+        with self.assertRaises(ValueError):
+            bm.render_ascii(FLOWCHART, backend="rust")
+
+    def test_options_are_keyword_only(self):
+        with self.assertRaises(TypeError):
+            bm.render_ascii(FLOWCHART, True)
+
+
+class ThreadingTest(unittest.TestCase):
+    def test_concurrent_rendering(self):
+        expected = bm.render_ascii(FLOWCHART)
+        results = []
+
+        def work():
+            results.append(bm.render_ascii(FLOWCHART))
+
+        threads = [threading.Thread(target=work) for _ in range(4)]
+        for thread in threads:
+            thread.start()
+        for thread in threads:
+            thread.join()
+        self.assertEqual(results, [expected] * 4)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,102 @@
+// ============================================================================
+// 检查 browser bundle 是否包含本仓库依赖的扩展
+//
+// 为什么要有它？
+// - vendor bundle 里有几处上游 TS 还没有的扩展（见 README“同步上游 bundle”）；
+// - `sync-vendor-bundle.sh` 会整体覆盖 vendor bundle，上游构建产物缺扩展时，
+//   `--skip-rust-tests` 下不会有任何报错，功能就这样静默丢了。
+//
+// 做法：
+// - 在独立的 vm context 里 eval bundle（与 QuickJS 里一样拿全局 `beautifulMermaid`），
+//   真的渲染一个小图检查输出，而不是 grep 压缩后的函数名；
+// - 任一检查失败：列出缺的扩展，退出码 1。
+//
+// 用法（bun / node 都可以）：
+//   bun scripts/check-bundle-extensions.js <bundle.js>
+// ============================================================================
+
+const fs = require("node:fs");
+const vm = require("node:vm");
+
+const PROBE = "graph TD\nA[Start] --> B[End]";
+
+/** 每项：扩展名、说明、检查函数（返回 true 表示通过）。 */
+const CHECKS = [
+  {
+    name: "renderMermaidWithMeta",
+    what: "导出 renderMermaidWithMeta，返回 { svg, kind, layout }（render_mermaid_with_meta）",
+    async check(bm) {
+      if (typeof bm.renderMermaidWithMeta !== "function") return false;
+      const result = await bm.renderMermaidWithMeta(PROBE, {});
+      return (
+        typeof result?.svg === "string" &&
+        result.kind === "flowchart" &&
+        Array.isArray(result.layout?.nodes)
+      );
+    },
+  },
+  {
+    name: "elementIds",
+    what: "SVG 节点/边带稳定 id 与 data-* 属性，支持 idPrefix（svg_element_ids）",
+    async check(bm) {
+      const svg = await bm.renderMermaid(PROBE, { idPrefix: "p-" });
+      return (
+        svg.includes('<g id="p-node-A" class="node" data-node-id="A">') &&
+        svg.includes('<g id="p-edge-A-B-0" class="edge" data-edge-from="A" data-edge-to="B">') &&
+        svg.includes("url(#p-arrowhead)")
+      );
+    },
+  },
+  {
+    name: "accessibility",
+    what: "预处理 accTitle/accDescr，SVG 输出 <title>/<desc> 与 ARIA 属性，支持 title/description（svg_accessibility）",
+    async check(bm) {
+      const fromDirectives = await bm.renderMermaid(`accTitle: Probe\n${PROBE}`, { idPrefix: "p-" });
+      const fromOptions = await bm.renderMermaid(PROBE, { title: "T", description: "D" });
+      return (
+        fromDirectives.startsWith('<svg role="img"') &&
+        fromDirectives.includes('<title id="p-title">Probe</title>') &&
+        fromDirectives.includes('<desc id="p-desc">Flowchart with 2 nodes') &&
+        fromOptions.includes('<title id="title">T</title>') &&
+        fromOptions.includes('<desc id="desc">D</desc>')
+      );
+    },
+  },
+];
+
+async function main() {
+  const path = process.argv[2];
+  if (!path) {
+    console.error("用法: check-bundle-extensions.js <bundle.js>");
+    process.exit(2);
+  }
+
+  const context = {};
+  vm.createContext(context);
+  vm.runInContext(fs.readFileSync(path, "utf8"), context);
+  const bm = context.beautifulMermaid;
+  if (!bm) {
+    console.error(`${path}: 没有定义全局对象 beautifulMermaid（需要 browser IIFE 构建）`);
+    process.exit(1);
+  }
+
+  const missing = [];
+  for (const { name, what, check } of CHECKS) {
+    let ok = false;
+    try {
+      ok = await check(bm);
+    } catch {
+      ok = false;
+    }
+    if (!ok) missing.push(`  - ${name}: ${what}`);
+  }
+
+  if (missing.length > 0) {
+    console.error(`${path} 缺少本仓库依赖的 bundle 扩展：`);
+    console.error(missing.join("\n"));
+    process.exit(1);
+  }
+  console.log(`bundle 扩展检查通过（${CHECKS.map((c) => c.name).join(", ")}）`);
+}
+
+main();
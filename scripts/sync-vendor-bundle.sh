@@ -10,8 +10,10 @@ set -euo pipefail
 #
 # 这个脚本做什么？
 # 1) 在 TS 仓库执行 `bun run build`
-# 2) 把 dist/beautiful-mermaid.browser.global.js 拷贝到 Rust 仓库 vendor
-# 3) （可选）执行 `cargo test` 做端到端验证
+# 2) 检查构建产物包含本仓库依赖的 bundle 扩展（scripts/check-bundle-extensions.js），
+#    缺了就不拷贝、直接失败（不受 --skip-rust-tests 影响）
+# 3) 把 dist/beautiful-mermaid.browser.global.js 拷贝到 Rust 仓库 vendor
+# 4) （可选）执行 `cargo test` 做端到端验证
 # ============================================================================
 
 DEFAULT_TS_DIR="/Users/cuiluming/local_doc/l_dev/ref/typescript/beautiful-mermaid"
@@ -95,7 +97,7 @@ if ! command -v bun >/dev/null 2>&1; then
   exit 1
 fi
 
-echo "==> [1/3] 构建 TS bundle"
+echo "==> [1/4] 构建 TS bundle"
 echo "TS_DIR=${TS_DIR}"
 (
   cd "${TS_DIR}"
@@ -114,7 +116,15 @@ if [[ ! -f "${SRC_BUNDLE}" ]]; then
   exit 1
 fi
 
-echo "==> [2/3] 同步 bundle 到 Rust vendor"
+echo "==> [2/4] 检查 bundle 扩展"
+# 上游还没有这些扩展时，覆盖 vendor 会让 Rust 侧的功能静默丢失：宁可不同步。
+if ! bun "${SCRIPT_DIR}/check-bundle-extensions.js" "${SRC_BUNDLE}"; then
+  echo "构建产物缺少 bundle 扩展，未同步（vendor bundle 保持不变）。" >&2
+  echo "请先把扩展合入 TS 仓库，见 README“同步上游 bundle”。" >&2
+  exit 1
+fi
+
+echo "==> [3/4] 同步 bundle 到 Rust vendor"
 cp "${SRC_BUNDLE}" "${DST_BUNDLE}"
 
 # 用 sha256 做一次“确实同步成功”的确认，避免误拷贝/拷贝失败不自知。
@@ -131,7 +141,7 @@ fi
 echo "bundle sha256: ${DST_SHA256}"
 echo "bundle path:   ${DST_BUNDLE}"
 
-echo "==> [3/3] 端到端验证（Rust）"
+echo "==> [4/4] 端到端验证（Rust）"
 if [[ "${RUN_RUST_TESTS}" == "true" ]]; then
   (
     cd "${RUST_DIR}"
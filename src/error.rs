@@ -15,6 +15,7 @@ pub type Result<T> = std::result::Result<T, BeautifulMermaidError>;
 #[derive(Debug, Error)]
 pub enum BeautifulMermaidError {
     /// JS 引擎（QuickJS）在 eval / 调用 / Promise 执行过程中产生的错误。
+    #[cfg(feature = "js-backend")]
     #[error("JS 引擎错误: {0}")]
     Js(#[from] rquickjs::Error),
 
@@ -29,4 +30,21 @@ pub enum BeautifulMermaidError {
     /// JS 返回值的 JSON 解析失败（通常表示 bundle/API 与 Rust 侧类型不匹配）。
     #[error("JSON 解析失败: {message}")]
     Json { message: String },
+
+    /// Mermaid 文本解析失败（纯 Rust 后端，`Backend::Native`）。
+    #[error("解析失败: {message}")]
+    Parse { message: String },
+
+    /// PNG 光栅化失败（feature = "png"），比如参数无效或 SVG 无法解析。
+    #[error("光栅化失败: {message}")]
+    Raster { message: String },
+
+    /// 有边走不通或要放宽约束才走通（`AsciiRenderOptions.fail_on_unroutable`）。
+    #[error("走线失败: {message}")]
+    Unroutable { message: String },
+
+    /// 需要的后端没有编译进来（比如关掉 `js-backend` 后渲染 native 尚未支持的图类型），
+    /// 或者外部 bundle 缺少要用的扩展（见 `BundleCapabilities`）。
+    #[error("后端不可用: {message}")]
+    BackendUnavailable { message: String },
 }
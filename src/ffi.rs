@@ -0,0 +1,235 @@
+// ============================================================================
+// C ABI（feature = "ffi"）
+//
+// 目标：
+// - 给 Go / Python 等语言直接嵌入（替代 shell out 调 CLI）；
+// - 头文件由 cbindgen 生成：`include/beautiful_mermaid.h`（配置见 `cbindgen.toml`）。
+//
+// 约定（所有函数一致）：
+// - 入参字符串是 NUL 结尾的 UTF-8；`options_json` 可为 NULL（默认参数），
+//   JSON 形状与 `RenderOptions` / `AsciiRenderOptions` 的 serde 形状一致（camelCase）；
+// - 返回 `BmStatus`；成功时 `*out` 是结果，失败时 `*out` 是错误信息
+//   （`out` 本身为 NULL 时只返回状态码）；`*out` 必须用 `bm_free_string` 释放；
+// - 线程安全：任意线程可并发调用；JS 引擎按线程各自初始化（首次调用较慢），
+//   返回的字符串可以在任意线程释放；
+// - panic 不会跨越 FFI 边界，统一返回 `BM_STATUS_PANIC`。
+// ============================================================================
+
+use crate::error::BeautifulMermaidError;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+/// C API 的状态码（由 `BeautifulMermaidError` 映射而来）。
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BmStatus {
+    /// 成功。
+    Ok = 0,
+    /// 参数无效：NULL 指针、非 UTF-8 字符串、options JSON 解析失败。
+    InvalidArgument = 1,
+    /// JS 引擎（QuickJS）错误。
+    Js = 2,
+    /// JS 主动抛出的异常。
+    JsException = 3,
+    /// JS bundle 初始化失败。
+    Init = 4,
+    /// JS 返回值的 JSON 解析失败。
+    Json = 5,
+    /// Mermaid 文本解析失败（native 后端）。
+    Parse = 6,
+    /// PNG 光栅化失败。
+    Raster = 7,
+    /// 需要的后端没有编译进来。
+    BackendUnavailable = 8,
+    /// 内部 panic（不应发生，属于 bug）。
+    Panic = 9,
+    /// 有边走不通或要放开约束才走通（options 里 `failOnUnroutable: true`）。
+    Unroutable = 10,
+}
+
+impl From<&BeautifulMermaidError> for BmStatus {
+    fn from(err: &BeautifulMermaidError) -> Self {
+        match err {
+            #[cfg(feature = "js-backend")]
+            BeautifulMermaidError::Js(_) => Self::Js,
+            BeautifulMermaidError::JsException { .. } => Self::JsException,
+            BeautifulMermaidError::Init { .. } => Self::Init,
+            BeautifulMermaidError::Json { .. } => Self::Json,
+            BeautifulMermaidError::Parse { .. } => Self::Parse,
+            BeautifulMermaidError::Raster { .. } => Self::Raster,
+            BeautifulMermaidError::BackendUnavailable { .. } => Self::BackendUnavailable,
+            BeautifulMermaidError::Unroutable { .. } => Self::Unroutable,
+        }
+    }
+}
+
+type FfiResult<T> = std::result::Result<T, (BmStatus, String)>;
+
+impl From<BeautifulMermaidError> for (BmStatus, String) {
+    fn from(err: BeautifulMermaidError) -> Self {
+        (BmStatus::from(&err), err.to_string())
+    }
+}
+
+/// 读取 NUL 结尾的 UTF-8 字符串。
+///
+/// # Safety
+/// `ptr` 必须为 NULL 或指向有效的 NUL 结尾字符串。
+unsafe fn read_str<'a>(ptr: *const c_char, what: &str) -> FfiResult<&'a str> {
+    if ptr.is_null() {
+        return Err((BmStatus::InvalidArgument, format!("{what} 不能为 NULL")));
+    }
+    // SAFETY: 调用方保证 ptr 指向有效的 NUL 结尾字符串。
+    unsafe { CStr::from_ptr(ptr) }.to_str().map_err(|err| {
+        (
+            BmStatus::InvalidArgument,
+            format!("{what} 不是合法 UTF-8: {err}"),
+        )
+    })
+}
+
+/// 解析 options JSON（NULL / 空串视为默认参数）。
+///
+/// # Safety
+/// 同 [`read_str`]。
+#[cfg(any(feature = "js-backend", feature = "native-backend"))]
+unsafe fn read_options<T: serde::de::DeserializeOwned + Default>(
+    ptr: *const c_char,
+) -> FfiResult<T> {
+    if ptr.is_null() {
+        return Ok(T::default());
+    }
+    // SAFETY: 透传调用方的保证。
+    let json = unsafe { read_str(ptr, "options_json") }?;
+    if json.trim().is_empty() {
+        return Ok(T::default());
+    }
+    serde_json::from_str(json).map_err(|err| {
+        (
+            BmStatus::InvalidArgument,
+            format!("options_json 解析失败: {err}"),
+        )
+    })
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> FfiResult<String> {
+    serde_json::to_string(value).map_err(|err| (BmStatus::Json, err.to_string()))
+}
+
+/// 执行 `body`（捕获 panic），把结果或错误信息写进 `*out`。
+///
+/// # Safety
+/// `out` 必须为 NULL 或指向可写的 `char *`。
+unsafe fn run(out: *mut *mut c_char, body: impl FnOnce() -> FfiResult<String>) -> BmStatus {
+    let (status, text) = match catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(text)) => (BmStatus::Ok, text),
+        Ok(Err((status, message))) => (status, message),
+        Err(_) => (BmStatus::Panic, "内部 panic".to_string()),
+    };
+    if !out.is_null() {
+        // 结果里不应出现 NUL；万一出现就去掉，保证一定能交给 C 侧。
+        let c_string = CString::new(text).unwrap_or_else(|err| {
+            let mut bytes = err.into_vec();
+            bytes.retain(|&byte| byte != 0);
+            CString::new(bytes).expect("已去掉所有 NUL")
+        });
+        // SAFETY: 调用方保证 out 可写。
+        unsafe { *out = c_string.into_raw() };
+    }
+    status
+}
+
+/// 渲染 Mermaid -> SVG。
+///
+/// # Safety
+/// - `text` 必须指向 NUL 结尾的 UTF-8 字符串；`options_json` 可为 NULL；
+/// - `out` 必须为 NULL 或指向可写的 `char *`，写入的字符串需用 `bm_free_string` 释放。
+#[cfg(any(feature = "js-backend", feature = "native-backend"))]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bm_render_svg(
+    text: *const c_char,
+    options_json: *const c_char,
+    out: *mut *mut c_char,
+) -> BmStatus {
+    // SAFETY: 透传调用方的保证。
+    unsafe {
+        run(out, || {
+            let text = read_str(text, "text")?;
+            let options = read_options(options_json)?;
+            Ok(crate::render_mermaid(text, &options)?)
+        })
+    }
+}
+
+/// 渲染 Mermaid -> ASCII/Unicode。
+///
+/// # Safety
+/// 同 `bm_render_svg`。
+#[cfg(any(feature = "js-backend", feature = "native-backend"))]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bm_render_ascii(
+    text: *const c_char,
+    options_json: *const c_char,
+    out: *mut *mut c_char,
+) -> BmStatus {
+    // SAFETY: 透传调用方的保证。
+    unsafe {
+        run(out, || {
+            let text = read_str(text, "text")?;
+            let options = read_options(options_json)?;
+            Ok(crate::render_mermaid_ascii(text, &options)?)
+        })
+    }
+}
+
+/// 渲染 Mermaid -> ASCII/Unicode + meta，`*out` 是 `AsciiRenderWithMeta` 的 JSON（`{"text", "meta"}`）。
+///
+/// # Safety
+/// 同 `bm_render_svg`。
+#[cfg(any(feature = "js-backend", feature = "native-backend"))]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bm_render_ascii_meta_json(
+    text: *const c_char,
+    options_json: *const c_char,
+    out: *mut *mut c_char,
+) -> BmStatus {
+    // SAFETY: 透传调用方的保证。
+    unsafe {
+        run(out, || {
+            let text = read_str(text, "text")?;
+            let options = read_options(options_json)?;
+            to_json(&crate::render_mermaid_ascii_with_meta(text, &options)?)
+        })
+    }
+}
+
+/// 校验 Mermaid 语法，`*out` 是 `MermaidValidation` 的 JSON（`{"isValid", "error", "details"}`）。
+///
+/// 语法无效不算失败：返回 `BM_STATUS_OK`，由 JSON 里的 `isValid` 区分。
+///
+/// # Safety
+/// - `text` 必须指向 NUL 结尾的 UTF-8 字符串；
+/// - `out` 必须为 NULL 或指向可写的 `char *`，写入的字符串需用 `bm_free_string` 释放。
+#[cfg(feature = "validate")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bm_validate(text: *const c_char, out: *mut *mut c_char) -> BmStatus {
+    // SAFETY: 透传调用方的保证。
+    unsafe {
+        run(out, || {
+            let text = read_str(text, "text")?;
+            to_json(&crate::validate_mermaid(text)?)
+        })
+    }
+}
+
+/// 释放本库返回的字符串（NULL 时什么也不做）。
+///
+/// # Safety
+/// `s` 必须为 NULL 或本库写入 `*out` 的指针，且只能释放一次。
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bm_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: 指针来自 `CString::into_raw`，调用方保证只释放一次。
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
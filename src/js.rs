@@ -10,38 +10,235 @@
 // ============================================================================
 
 use crate::error::{BeautifulMermaidError, Result};
+use crate::native::{self, DiagramKind};
 use crate::native_pathfinder::NativeAStar;
-use crate::types::{AsciiRenderOptions, AsciiRenderWithMeta, RenderOptions};
-use once_cell::unsync::OnceCell;
+use crate::svg_meta;
+use crate::types::{
+    AsciiDiagramMeta, AsciiRenderMeta, AsciiRenderOptions, AsciiRenderWithMeta, AsciiRouteOutcome,
+    AsciiRouting, Backend, RenderOptions, SvgRenderWithMeta,
+};
 use rquickjs::FromJs;
 use rquickjs::function::{FromParams, IntoJsFunc, ParamRequirement, Params};
 use rquickjs::{Context, Exception, Function, IntoJs, Object, Promise, Runtime, TypedArray, Value};
 use std::cell::RefCell;
+use std::fmt;
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 
 /// 通过 `include_str!` 内嵌的 browser IIFE bundle（来自 TS 项目的 tsup 输出）。
 const BEAUTIFUL_MERMAID_BUNDLE: &str =
     include_str!("../vendor/beautiful-mermaid/beautiful-mermaid.browser.global.js");
 
+/// bundle 必须导出的函数：eval 之后逐个检查，缺一个就拒绝这份 bundle（握手）。
+const REQUIRED_BUNDLE_API: [&str; 3] = [
+    "renderMermaid",
+    "renderMermaidAscii",
+    "renderMermaidAsciiWithMeta",
+];
+
+/// 探测扩展用的小图（与 `scripts/check-bundle-extensions.js` 相同）。
+const CAPABILITY_PROBE: &str = "graph TD\nA[Start] --> B[End]";
+
+/// bundle 支持哪些本仓库的扩展（上游 TS 还没有，见 README“同步上游 bundle”）。
+///
+/// 内嵌 bundle 全部支持；外部 bundle（`bundle_path` / `bundle_source`）在创建引擎时
+/// 真的渲染一个小图来探测。用到不支持的扩展时渲染返回 `BeautifulMermaidError::BackendUnavailable`，
+/// 而不是调用缺失的函数报 JS 异常，或者把选项静默丢掉。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BundleCapabilities {
+    /// 导出 `renderMermaidWithMeta`（`render_mermaid_svg_with_meta` 需要）。
+    pub svg_meta: bool,
+    /// SVG 元素带稳定 id / `data-*`，认识 `RenderOptions.id_prefix`。
+    pub element_ids: bool,
+    /// SVG 输出 `<title>` / `<desc>`，认识 `RenderOptions.title` / `description`。
+    pub accessibility: bool,
+}
+
+impl BundleCapabilities {
+    const ALL: Self = Self {
+        svg_meta: true,
+        element_ids: true,
+        accessibility: true,
+    };
+}
+
+/// `renderMermaidAsciiWithMeta` 的原始 JS 返回值（meta 固定是 flowchart 形状）。
+#[derive(serde::Deserialize)]
+struct JsAsciiRenderWithMeta {
+    text: String,
+    meta: AsciiRenderMeta,
+}
+
 thread_local! {
     /// 每个线程一个 JS 引擎实例：
     /// - QuickJS Context 不是线程安全的（也不应该跨线程共享）
     /// - 这样能避免频繁初始化带来的开销
-    static JS_ENGINE: OnceCell<JsEngine> = OnceCell::new();
+    ///
+    /// 用 `Rc` 是为了取出引擎后立刻释放 `RefCell` 借用：渲染期间重新配置引擎也不会 panic。
+    static JS_ENGINE: RefCell<Option<Rc<JsEngine>>> = const { RefCell::new(None) };
 }
 
+/// 线程第一次创建引擎时用的配置（`set_default_js_engine_config`；没设置时是 `EngineConfig::default()`）。
+static DEFAULT_ENGINE_CONFIG: Mutex<Option<EngineConfig>> = Mutex::new(None);
+
 /// 在当前线程的 JS 引擎上执行一次操作。
 pub fn with_js_engine<T>(f: impl FnOnce(&JsEngine) -> Result<T>) -> Result<T> {
-    JS_ENGINE.with(|cell| {
-        let engine = cell.get_or_try_init(JsEngine::new)?;
-        f(engine)
-    })
+    let cached = JS_ENGINE.with(|cell| cell.borrow().clone());
+    let engine = match cached {
+        Some(engine) => engine,
+        None => {
+            let config = DEFAULT_ENGINE_CONFIG
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .clone()
+                .unwrap_or_default();
+            let engine = Rc::new(config.build()?);
+            JS_ENGINE.with(|cell| *cell.borrow_mut() = Some(engine.clone()));
+            engine
+        }
+    };
+    f(&engine)
+}
+
+/// 按 `config` 重建当前线程的 JS 引擎；之后这个线程上走 JS 后端的渲染都用它。
+///
+/// 引擎立即创建：配置有问题（比如内存上限小到装不下 bundle）时在这里就返回错误，
+/// 当前线程原来的引擎保持不变。
+pub fn set_js_engine_config(config: &EngineConfig) -> Result<()> {
+    let engine = Rc::new(config.build()?);
+    JS_ENGINE.with(|cell| *cell.borrow_mut() = Some(engine));
+    Ok(())
+}
+
+/// 设置进程级默认配置：之后第一次创建引擎的线程都用它（当前线程的引擎也会在下次渲染时按它重建）。
+///
+/// 其他线程已经创建好的引擎不受影响；要改它们，在那些线程上调用 [`set_js_engine_config`]。
+pub fn set_default_js_engine_config(config: EngineConfig) {
+    *DEFAULT_ENGINE_CONFIG
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(config);
+    JS_ENGINE.with(|cell| *cell.borrow_mut() = None);
+}
+
+/// JS 引擎配置（builder 风格：`EngineConfig::new().native_pathfinder(false).memory_limit(64 << 20)`）。
+///
+/// 没设置的项：
+/// - native pathfinder：读 `BM_DISABLE_NATIVE_PATHFINDER`（`1` / `true` 时关闭），否则开启；
+/// - 内存上限：不限；栈上限：QuickJS 默认（256 KiB）；
+/// - bundle：编译时内嵌的 vendored bundle。
+#[derive(Clone, Default)]
+pub struct EngineConfig {
+    native_pathfinder: Option<bool>,
+    memory_limit: Option<usize>,
+    max_stack_size: Option<usize>,
+    bundle: Bundle,
+}
+
+/// 引擎 eval 哪份 bundle。
+#[derive(Clone, Default)]
+enum Bundle {
+    #[default]
+    Embedded,
+    Source(Arc<str>),
+    /// 创建引擎时才读文件：文件改了之后重新 `set_js_engine_config` 就能用上。
+    Path(PathBuf),
+}
+
+impl Bundle {
+    /// 出错时告诉用户是哪份 bundle。
+    fn describe(&self) -> String {
+        match self {
+            Self::Embedded => "内嵌 bundle".to_string(),
+            Self::Source(source) => format!("bundle_source（{} 字节）", source.len()),
+            Self::Path(path) => path.display().to_string(),
+        }
+    }
+}
+
+impl EngineConfig {
+    /// 全部取默认值（同 `EngineConfig::default()`）。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 是否把 A* 交给 native pathfinder（`false` = bundle 用自己的纯 JS A*，很慢，只用于对照）。
+    pub fn native_pathfinder(mut self, enabled: bool) -> Self {
+        self.native_pathfinder = Some(enabled);
+        self
+    }
+
+    /// QuickJS 堆内存上限（字节）；超出时渲染返回 JS 错误（`out of memory`）而不是无限增长。
+    pub fn memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    /// QuickJS 栈上限（字节）；嵌套很深的图可能需要调大。
+    pub fn max_stack_size(mut self, bytes: usize) -> Self {
+        self.max_stack_size = Some(bytes);
+        self
+    }
+
+    /// 用给定的 JS 源码代替内嵌 bundle（必须是 browser IIFE 构建，eval 后定义全局 `beautifulMermaid`）。
+    ///
+    /// 与 [`EngineConfig::bundle_path`] 互相覆盖，后设置的生效。
+    pub fn bundle_source(mut self, source: impl Into<String>) -> Self {
+        self.bundle = Bundle::Source(Arc::from(source.into()));
+        self
+    }
+
+    /// 从文件加载 bundle（比如上游新 build 的 `beautiful-mermaid.browser.global.js`），不用重新编译。
+    ///
+    /// 创建引擎时读取并 eval，然后检查 `renderMermaid` / `renderMermaidAscii` /
+    /// `renderMermaidAsciiWithMeta` 都在，否则返回 `BeautifulMermaidError::Init`。
+    /// 本仓库的扩展（SVG meta、元素 id、无障碍）是可选的，支持哪些见 [`JsEngine::capabilities`]。
+    pub fn bundle_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.bundle = Bundle::Path(path.into());
+        self
+    }
+
+    /// 按配置创建一个新引擎（只走 JS 后端，不做 native 分派；一般用 [`set_js_engine_config`]）。
+    pub fn build(&self) -> Result<JsEngine> {
+        JsEngine::new(self)
+    }
+
+    /// 实际是否启用 native pathfinder（没设置时读环境变量）。
+    fn native_pathfinder_enabled(&self) -> bool {
+        self.native_pathfinder.unwrap_or_else(|| {
+            let disabled = std::env::var("BM_DISABLE_NATIVE_PATHFINDER")
+                .ok()
+                .map(|v| v.trim().to_ascii_lowercase())
+                .is_some_and(|v| v == "1" || v == "true");
+            !disabled
+        })
+    }
+}
+
+/// 手写 Debug：bundle 源码有几 MB，只打印来源与长度。
+impl fmt::Debug for EngineConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EngineConfig")
+            .field("native_pathfinder", &self.native_pathfinder)
+            .field("memory_limit", &self.memory_limit)
+            .field("max_stack_size", &self.max_stack_size)
+            .field("bundle", &self.bundle.describe())
+            .finish()
+    }
 }
 
 /// JS 引擎实例：包含 Runtime + Context，并在初始化时 eval bundle。
+///
+/// 由 [`EngineConfig::build`] 创建；QuickJS 不能跨线程，所以它既不是 `Send` 也不是 `Sync`。
+/// 方法只走 JS（bundle），不像 crate 根上的 `render_*` 那样按 `backend` 分派到 native。
 pub struct JsEngine {
     runtime: Runtime,
     context: Context,
+    /// 注入给 bundle 的 native A*（禁用 native pathfinder 时为 None），渲染前按 options 设置转弯惩罚。
+    astar: Option<Rc<RefCell<NativeAStar>>>,
+    capabilities: BundleCapabilities,
+    /// 报错时说明是哪份 bundle（`Bundle::describe`）。
+    bundle: String,
 }
 
 // ============================================================================
@@ -360,28 +557,32 @@ impl JsEngine {
         BeautifulMermaidError::Js(err)
     }
 
-    fn new() -> Result<Self> {
+    fn new(config: &EngineConfig) -> Result<Self> {
         let runtime = Runtime::new()?;
+        if let Some(limit) = config.memory_limit {
+            runtime.set_memory_limit(limit);
+        }
+        if let Some(limit) = config.max_stack_size {
+            runtime.set_max_stack_size(limit);
+        }
         let context = Context::full(&runtime)?;
 
         // ----------------------------------------------------------------
-        // 是否禁用 native pathfinder(默认不禁用)
+        // 是否启用 native pathfinder(默认启用)
         //
-        // 背景(用户反馈 + 现象对照):
-        // - Rust CLI 的 `--ascii` 输出出现明显退化(可读性显著下降)。
-        // - 对比 TS(bun) 与 Rust(QuickJS) 渲染同一份 Mermaid 后发现:
-        //   Rust 侧注册的 `globalThis.__bm_getPath*` 会让 bundle 走 native A*,
-        //   从而改变路径选择与 tie-break,导致输出与上游 TS 基线不一致。
+        // 背景:
+        // - 早期 native A* 的 tie-break 与 bundle 不同,`--ascii` 输出与上游 TS 基线不一致；
+        //   现在最小堆(严格 `<` 比较)、邻居顺序与启发式都照搬 bundle,默认参数下与纯 JS A*
+        //   逐字节一致(`tests/ts_compatible_pathfinder.rs` 在全部 testdata 上对照)。
+        // - 只有 Rust 独有的 `turn_penalty` 会改变走线(A* 之前的封闭探测只让走不通的搜索提前返回),
+        //   `AsciiRenderOptions.ts_compatible` 把它固定成 0,并关掉封闭探测。
         //
         // 策略:
         // - native pathfinder 默认保持开启(QuickJS 无 JIT,纯 JS A* 在真实图上可能非常慢)；
-        // - 但提供一个“显式禁用开关”,用于对照上游 TS 基线与定位偏差。
+        // - `EngineConfig::native_pathfinder(false)` 只用于对照纯 JS A* 与定位偏差；
+        //   没设置时读 `BM_DISABLE_NATIVE_PATHFINDER`(CLI 排查用)。
         // ----------------------------------------------------------------
-        let disable_native_pathfinder = std::env::var("BM_DISABLE_NATIVE_PATHFINDER")
-            .ok()
-            .map(|v| v.trim().to_ascii_lowercase())
-            .is_some_and(|v| v == "1" || v == "true");
-        let enable_native_pathfinder = !disable_native_pathfinder;
+        let enable_native_pathfinder = config.native_pathfinder_enabled();
 
         // ----------------------------------------------------------------
         // CLI 加速：注册 native pathfinder
@@ -394,10 +595,9 @@ impl JsEngine {
         // - native 侧维护一份可复用的 A* 缓存（stamp/heap/表），避免每次调用分配大数组
         // - TypedArray 通过 `AsRef<[T]>` 只读访问，不需要 unsafe
         // ----------------------------------------------------------------
-        if enable_native_pathfinder {
+        let astar = enable_native_pathfinder.then(|| Rc::new(RefCell::new(NativeAStar::new())));
+        if let Some(astar) = &astar {
             context.with(|ctx| -> Result<()> {
-                let astar = Rc::new(RefCell::new(NativeAStar::new()));
-
                 let get_path = Function::new(
                     ctx.clone(),
                     NativeGetPathFn {
@@ -416,9 +616,13 @@ impl JsEngine {
                 .with_name("__bm_getPathStrict")?;
                 ctx.globals().set("__bm_getPathStrict", get_path_strict)?;
 
-                let get_path_relaxed =
-                    Function::new(ctx.clone(), NativeGetPathRelaxedFn { astar })?
-                        .with_name("__bm_getPathRelaxed")?;
+                let get_path_relaxed = Function::new(
+                    ctx.clone(),
+                    NativeGetPathRelaxedFn {
+                        astar: astar.clone(),
+                    },
+                )?
+                .with_name("__bm_getPathRelaxed")?;
                 ctx.globals().set("__bm_getPathRelaxed", get_path_relaxed)?;
 
                 Ok(())
@@ -428,17 +632,141 @@ impl JsEngine {
         // ----------------------------------------------------------------
         // 初始化：把 browser IIFE bundle eval 进 Context
         // 这一步会创建全局对象 `beautifulMermaid`
+        //
+        // 外部 bundle（`bundle_path` / `bundle_source`）可能是任意版本：
+        // eval 之后先握手，确认 Rust 侧要调用的函数都在，缺了就直接拒绝，
+        // 而不是等到第一次渲染时才报“undefined 不是函数”。
         // ----------------------------------------------------------------
-        context.with(|ctx| {
-            ctx.eval::<(), _>(BEAUTIFUL_MERMAID_BUNDLE)
-                .map_err(BeautifulMermaidError::from)
+        let from_file;
+        let bundle = match &config.bundle {
+            Bundle::Embedded => BEAUTIFUL_MERMAID_BUNDLE,
+            Bundle::Source(source) => source,
+            Bundle::Path(path) => {
+                from_file =
+                    std::fs::read_to_string(path).map_err(|err| BeautifulMermaidError::Init {
+                        message: format!("读取 bundle {} 失败: {err}", path.display()),
+                    })?;
+                &from_file
+            }
+        };
+        context.with(|ctx| -> Result<()> {
+            ctx.eval::<(), _>(bundle)
+                .map_err(|err| Self::map_quickjs_error(&ctx, err))?;
+            Self::check_bundle_api(&ctx).map_err(|message| BeautifulMermaidError::Init {
+                message: format!("{}: {message}", config.bundle.describe()),
+            })
         })?;
 
-        Ok(Self { runtime, context })
+        let mut engine = Self {
+            runtime,
+            context,
+            astar,
+            capabilities: BundleCapabilities::ALL,
+            bundle: config.bundle.describe(),
+        };
+        if !matches!(config.bundle, Bundle::Embedded) {
+            engine.capabilities = engine.probe_capabilities();
+        }
+        Ok(engine)
+    }
+
+    /// 探测外部 bundle 的扩展：检查方式与 `scripts/check-bundle-extensions.js` 一致，
+    /// 看实际输出而不是函数名（上游 bundle 会把不认识的选项直接忽略）。探测报错按不支持处理。
+    fn probe_capabilities(&self) -> BundleCapabilities {
+        let svg_meta = self.context.with(|ctx| {
+            ctx.globals()
+                .get::<_, Object>("beautifulMermaid")
+                .and_then(|bm| bm.get::<_, Value>("renderMermaidWithMeta"))
+                .is_ok_and(|value| value.is_function())
+        });
+        let probe = |options: RenderOptions| {
+            self.render_svg_unchecked(CAPABILITY_PROBE, &options)
+                .unwrap_or_default()
+        };
+        let with_ids = probe(RenderOptions {
+            id_prefix: Some("p-".to_string()),
+            ..Default::default()
+        });
+        let with_title = probe(RenderOptions {
+            title: Some("T".to_string()),
+            description: Some("D".to_string()),
+            ..Default::default()
+        });
+        BundleCapabilities {
+            svg_meta,
+            element_ids: with_ids.contains(r#"<g id="p-node-A" class="node" data-node-id="A">"#),
+            accessibility: with_title.contains(r#"<title id="title">T</title>"#)
+                && with_title.contains(r#"<desc id="desc">D</desc>"#),
+        }
+    }
+
+    /// 用到 bundle 不支持的扩展时报错（`what` 说明是哪项扩展）。
+    fn require(&self, supported: bool, what: &str) -> Result<()> {
+        if supported {
+            Ok(())
+        } else {
+            Err(BeautifulMermaidError::BackendUnavailable {
+                message: format!(
+                    "{} 不支持 {what}（上游 bundle 没有这项扩展，可用 scripts/check-bundle-extensions.js 检查）",
+                    self.bundle
+                ),
+            })
+        }
+    }
+
+    /// SVG 选项里用到的扩展都要被 bundle 支持。
+    fn require_render_options(&self, options: &RenderOptions) -> Result<()> {
+        self.require(
+            self.capabilities.element_ids || options.id_prefix.is_none(),
+            "`id_prefix`（SVG 元素 id）",
+        )?;
+        self.require(
+            self.capabilities.accessibility
+                || (options.title.is_none() && options.description.is_none()),
+            "`title` / `description`（SVG 无障碍）",
+        )
+    }
+
+    /// 握手：bundle 定义了全局 `beautifulMermaid`，且 `REQUIRED_BUNDLE_API` 都是函数。
+    fn check_bundle_api(ctx: &rquickjs::Ctx<'_>) -> std::result::Result<(), String> {
+        let Ok(beautiful_mermaid) = ctx.globals().get::<_, Object>("beautifulMermaid") else {
+            return Err(
+                "bundle 没有定义全局对象 `beautifulMermaid`（需要 browser IIFE 构建）".to_string(),
+            );
+        };
+        let missing: Vec<&str> = REQUIRED_BUNDLE_API
+            .into_iter()
+            .filter(|name| {
+                !beautiful_mermaid
+                    .get::<_, Value>(*name)
+                    .is_ok_and(|value| value.is_function())
+            })
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "bundle 缺少 API: {}（需要 {}）",
+                missing.join(", "),
+                REQUIRED_BUNDLE_API.join(" / ")
+            ))
+        }
+    }
+
+    /// bundle 的 A* 是否交给 native pathfinder（见 [`EngineConfig::native_pathfinder`]）。
+    pub fn native_pathfinder(&self) -> bool {
+        self.astar.is_some()
+    }
+
+    /// bundle 支持哪些本仓库的扩展（内嵌 bundle 全部支持）。
+    pub fn capabilities(&self) -> BundleCapabilities {
+        self.capabilities
     }
 
     /// 渲染 Mermaid -> ASCII/Unicode（同步）。
     pub fn render_mermaid_ascii(&self, text: &str, options: &AsciiRenderOptions) -> Result<String> {
+        Self::reject_global_routing(text, options)?;
+        self.apply_pathfinder_options(options);
         let rendered = self.context.with(|ctx| -> Result<String> {
             let beautiful_mermaid: Object = ctx.globals().get("beautifulMermaid")?;
             let render_fn: Function = beautiful_mermaid.get("renderMermaidAscii")?;
@@ -462,6 +790,8 @@ impl JsEngine {
         text: &str,
         options: &AsciiRenderOptions,
     ) -> Result<AsciiRenderWithMeta> {
+        Self::reject_global_routing(text, options)?;
+        self.apply_pathfinder_options(options);
         let json = self.context.with(|ctx| -> Result<String> {
             let beautiful_mermaid: Object = ctx.globals().get("beautifulMermaid")?;
             let render_fn: Function = beautiful_mermaid.get("renderMermaidAsciiWithMeta")?;
@@ -485,15 +815,50 @@ impl JsEngine {
         // 保守处理：把可能残留的 Promise job 队列清空，避免跨调用累积。
         self.drain_pending_jobs()?;
 
-        serde_json::from_str::<AsciiRenderWithMeta>(&json).map_err(|err| {
+        let rendered = serde_json::from_str::<JsAsciiRenderWithMeta>(&json).map_err(|err| {
             BeautifulMermaidError::Json {
                 message: format!("解析 renderMermaidAsciiWithMeta 输出失败: {err}"),
             }
+        })?;
+
+        // --------------------------------------------------------------------
+        // JS bundle 只为 flowchart/state 产出 meta（其他图类型返回空的 nodes/edges）。
+        // sequence/class/ER 的坐标由 Rust 侧的同构布局计算（见 `native` 模块），
+        // 与内嵌 bundle 的字符画逐格对齐；外部 bundle 改过布局时对不上，
+        // 所以这几类 meta 的 `backend` 总是 `Native`，如实说明坐标的来源。
+        // --------------------------------------------------------------------
+        let use_ascii = options.use_ascii.unwrap_or(false);
+        let meta = match native::detect_diagram_kind(text) {
+            DiagramKind::Flowchart => {
+                // JS 不报告兜底，只能从 path 是否为空判断有没有走通
+                let mut meta = rendered.meta;
+                meta.backend = Backend::Js;
+                for edge in &mut meta.edges {
+                    if edge.path.is_empty() {
+                        edge.outcome = AsciiRouteOutcome::Unroutable;
+                    }
+                }
+                AsciiDiagramMeta::Flowchart(meta)
+            }
+            DiagramKind::Sequence => AsciiDiagramMeta::Sequence(native::sequence::ascii_meta(text)),
+            DiagramKind::Class => AsciiDiagramMeta::Class(native::class::ascii_meta(text)),
+            DiagramKind::Er => AsciiDiagramMeta::Er(native::er::ascii_meta(text, use_ascii)),
+        };
+
+        Ok(AsciiRenderWithMeta {
+            text: rendered.text,
+            meta,
         })
     }
 
     /// 渲染 Mermaid -> SVG（TS 版返回 Promise，这里同步等待）。
     pub fn render_mermaid_svg(&self, text: &str, options: &RenderOptions) -> Result<String> {
+        self.require_render_options(options)?;
+        self.render_svg_unchecked(text, options)
+    }
+
+    /// 不检查扩展，直接调用 `renderMermaid`（探测扩展时也用它）。
+    fn render_svg_unchecked(&self, text: &str, options: &RenderOptions) -> Result<String> {
         let rendered = self.context.with(|ctx| -> Result<String> {
             let beautiful_mermaid: Object = ctx.globals().get("beautifulMermaid")?;
             let render_fn: Function = beautiful_mermaid.get("renderMermaid")?;
@@ -515,6 +880,60 @@ impl JsEngine {
         Ok(rendered)
     }
 
+    /// 渲染 Mermaid -> SVG + 像素坐标 meta（TS 版返回 Promise，这里同步等待）。
+    pub fn render_mermaid_svg_with_meta(
+        &self,
+        text: &str,
+        options: &RenderOptions,
+    ) -> Result<SvgRenderWithMeta> {
+        self.require(
+            self.capabilities.svg_meta,
+            "SVG meta（`renderMermaidWithMeta`）",
+        )?;
+        self.require_render_options(options)?;
+        let json = self.context.with(|ctx| -> Result<String> {
+            let beautiful_mermaid: Object = ctx.globals().get("beautifulMermaid")?;
+            let render_fn: Function = beautiful_mermaid.get("renderMermaidWithMeta")?;
+
+            let js_options = Self::render_options_to_js(&ctx, options)?;
+
+            let promise: Promise = render_fn
+                .call((text, js_options))
+                .map_err(|err| Self::map_quickjs_error(&ctx, err))?;
+            let result: Value = promise
+                .finish()
+                .map_err(|err| Self::map_quickjs_error(&ctx, err))?;
+
+            // 与 ASCII meta 一致：JSON.stringify 做跨语言传输。
+            let json_obj: Object = ctx.globals().get("JSON")?;
+            let stringify: Function = json_obj.get("stringify")?;
+            let output: String = stringify
+                .call((result,))
+                .map_err(|err| Self::map_quickjs_error(&ctx, err))?;
+            Ok(output)
+        })?;
+
+        self.drain_pending_jobs()?;
+
+        let rendered =
+            serde_json::from_str::<svg_meta::JsSvgRenderWithMeta>(&json).map_err(|err| {
+                BeautifulMermaidError::Json {
+                    message: format!("解析 renderMermaidWithMeta 输出失败: {err}"),
+                }
+            })?;
+        let (view_box, meta) = svg_meta::convert(
+            &rendered.kind,
+            rendered.layout,
+            options.id_prefix.as_deref().unwrap_or_default(),
+        )?;
+
+        Ok(SvgRenderWithMeta {
+            svg: rendered.svg,
+            view_box,
+            meta,
+        })
+    }
+
     fn drain_pending_jobs(&self) -> Result<()> {
         // ----------------------------------------------------------------
         // rquickjs 的 job queue 执行错误类型不是 `rquickjs::Error`，
@@ -584,9 +1003,45 @@ impl JsEngine {
             object.set("transparent", value)?;
         }
 
+        // 元素 id
+        if let Some(value) = &options.id_prefix {
+            object.set("idPrefix", value.as_str())?;
+        }
+
+        // 无障碍
+        if let Some(value) = &options.title {
+            object.set("title", value.as_str())?;
+        }
+        if let Some(value) = &options.description {
+            object.set("description", value.as_str())?;
+        }
+
         Ok(object)
     }
 
+    /// 每次 ASCII 渲染都重新设置（引擎按线程复用，不能沿用上一次调用的值）。
+    fn apply_pathfinder_options(&self, options: &AsciiRenderOptions) {
+        if let Some(astar) = &self.astar {
+            let mut astar = astar.borrow_mut();
+            astar.set_turn_penalty(options.effective_turn_penalty());
+            astar.set_enclosure_probe(options.enclosure_probe());
+        }
+    }
+
+    /// bundle 不会 global 走线：flowchart/state 选了它直接报错，而不是悄悄按 relaxed 画
+    /// （经 crate 顶层函数调用时，有 native 就会改走 native，到不了这里）。
+    fn reject_global_routing(text: &str, options: &AsciiRenderOptions) -> Result<()> {
+        if options.routing == Some(AsciiRouting::Global)
+            && native::detect_diagram_kind(text) == DiagramKind::Flowchart
+        {
+            return Err(BeautifulMermaidError::BackendUnavailable {
+                message: "`routing: Global` 只有 native 实现，需要 `native-backend` feature"
+                    .to_string(),
+            });
+        }
+        Ok(())
+    }
+
     fn ascii_options_to_js<'js>(
         ctx: &rquickjs::Ctx<'js>,
         options: &AsciiRenderOptions,
@@ -597,6 +1052,12 @@ impl JsEngine {
             object.set("useAscii", value)?;
         }
         if let Some(routing) = options.routing {
+            // bundle 不认识 global；flowchart/state 已在 `reject_global_routing` 报错，
+            // 剩下的图类型没有走线，routing 本来就不起作用。
+            let routing = match routing {
+                AsciiRouting::Global => AsciiRouting::Relaxed,
+                other => other,
+            };
             object.set("routing", routing.as_str())?;
         }
         if let Some(value) = options.padding_x {
@@ -4,44 +4,307 @@
 // 目标：
 // - 对外提供与 TS 版 `beautiful-mermaid` 等价的核心能力
 // - 当前实现策略：内嵌 QuickJS 执行打包后的 JS bundle（作为“完整复刻”基线）
+//
+// Features（见 Cargo.toml）：
+// - `js-backend` / `native-backend` 决定渲染 API 是否存在、走哪条管线；
+// - `validate` 决定 `validate_mermaid` 是否存在；
+// - 三者都没开时直接编译报错（crate 里没有任何可用的能力）。
 // ============================================================================
 
+#[cfg(not(any(
+    feature = "js-backend",
+    feature = "native-backend",
+    feature = "validate"
+)))]
+compile_error!(
+    "beautiful-mermaid-rs: 至少需要开启一个 feature：`js-backend`、`native-backend` 或 `validate`"
+);
+
 mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "js-backend")]
 mod js;
+#[cfg(any(feature = "js-backend", feature = "native-backend"))]
+mod native;
+#[cfg(any(feature = "js-backend", feature = "native-backend"))]
 mod native_pathfinder;
+#[cfg(feature = "node")]
+mod node;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "png")]
+mod raster;
+#[cfg(any(feature = "js-backend", feature = "native-backend"))]
+pub mod routing;
+#[cfg(any(feature = "js-backend", feature = "native-backend"))]
+mod svg_meta;
 pub mod theme;
 pub mod types;
+#[cfg(feature = "wasm")]
+mod wasm;
 
 pub use error::{BeautifulMermaidError, Result};
+#[cfg(any(feature = "js-backend", feature = "native-backend"))]
+use native::DiagramKind;
 pub use types::{
-    AsciiBox, AsciiDrawingCoord, AsciiRenderMeta, AsciiRenderMetaEdge, AsciiRenderMetaNode,
-    AsciiRenderOptions, AsciiRenderWithMeta, AsciiRouting, MermaidValidation, RenderOptions,
+    AsciiBox, AsciiCardinalityMarker, AsciiClassMeta, AsciiClassNode, AsciiClassRelationship,
+    AsciiDiagramMeta, AsciiDrawingCoord, AsciiEdgeRoutingStats, AsciiErEntity, AsciiErMeta,
+    AsciiErRelationship, AsciiMemberRow, AsciiRenderMeta, AsciiRenderMetaEdge, AsciiRenderMetaNode,
+    AsciiRenderOptions, AsciiRenderWithMeta, AsciiRenderWithStats, AsciiRouteOutcome,
+    AsciiRouteReason, AsciiRouting, AsciiRoutingStats, AsciiSequenceBlock, AsciiSequenceDivider,
+    AsciiSequenceMessage, AsciiSequenceMeta, AsciiSequenceNote, AsciiSequenceParticipant, Backend,
+    MermaidValidation, RenderOptions, SearchStats, StepRejections, SvgClassMeta, SvgClassNode,
+    SvgClassRelationship, SvgDiagramMeta, SvgErEntity, SvgErMeta, SvgErRelationship,
+    SvgFlowchartMeta, SvgMetaEdge, SvgMetaGroup, SvgMetaNode, SvgPoint, SvgRect, SvgRenderWithMeta,
+    SvgSequenceActivation, SvgSequenceBlock, SvgSequenceDivider, SvgSequenceMessage,
+    SvgSequenceMeta, SvgSequenceNote, SvgSequenceParticipant,
+};
+
+#[cfg(feature = "js-backend")]
+pub use js::{
+    BundleCapabilities, EngineConfig, JsEngine, set_default_js_engine_config, set_js_engine_config,
 };
+#[cfg(feature = "png")]
+pub use types::RasterOptions;
+
+#[cfg(feature = "native-backend")]
+pub use routing::debug::AsciiRenderWithDebug;
 
 /// 渲染 Mermaid -> SVG（阻塞）。
 ///
 /// 说明：
 /// - TS 版 `renderMermaid()` 是 async（返回 Promise）
 /// - Rust 版这里会在内部同步等待 Promise 完成，然后返回 SVG 字符串
+#[cfg(any(feature = "js-backend", feature = "native-backend"))]
 pub fn render_mermaid(text: &str, options: &RenderOptions) -> Result<String> {
-    js::with_js_engine(|engine| engine.render_mermaid_svg(text, options))
+    match native_diagram_kind(text, options.backend) {
+        #[cfg(feature = "native-backend")]
+        Some(DiagramKind::Flowchart) => native::flowchart_svg::render(text, options),
+        #[cfg(feature = "native-backend")]
+        Some(DiagramKind::Sequence) => native::sequence_svg::render(text, options),
+        #[cfg(feature = "native-backend")]
+        Some(DiagramKind::Class) => native::class_svg::render(text, options),
+        #[cfg(feature = "native-backend")]
+        Some(DiagramKind::Er) => native::er_svg::render(text, options),
+        #[cfg(feature = "js-backend")]
+        _ => js::with_js_engine(|engine| engine.render_mermaid_svg(text, options)),
+        #[cfg(not(feature = "js-backend"))]
+        kind => Err(js_backend_required(kind, "SVG")),
+    }
+}
+
+/// 渲染 Mermaid -> SVG + meta（阻塞）。
+///
+/// 说明：
+/// - `svg` 字段等价于 `render_mermaid(...)` 的输出；
+/// - `meta` 提供 node/edge/label 在 SVG 坐标系（像素）里的几何信息，
+///   形状对齐 [`render_mermaid_ascii_with_meta`]，便于 Web UI 做高亮/命中测试。
+#[cfg(any(feature = "js-backend", feature = "native-backend"))]
+pub fn render_mermaid_with_meta(text: &str, options: &RenderOptions) -> Result<SvgRenderWithMeta> {
+    match native_diagram_kind(text, options.backend) {
+        #[cfg(feature = "native-backend")]
+        Some(DiagramKind::Flowchart) => native::flowchart_svg::render_with_meta(text, options),
+        #[cfg(feature = "native-backend")]
+        Some(DiagramKind::Sequence) => native::sequence_svg::render_with_meta(text, options),
+        #[cfg(feature = "native-backend")]
+        Some(DiagramKind::Class) => native::class_svg::render_with_meta(text, options),
+        #[cfg(feature = "native-backend")]
+        Some(DiagramKind::Er) => native::er_svg::render_with_meta(text, options),
+        #[cfg(feature = "js-backend")]
+        _ => js::with_js_engine(|engine| engine.render_mermaid_svg_with_meta(text, options)),
+        #[cfg(not(feature = "js-backend"))]
+        kind => Err(js_backend_required(kind, "SVG")),
+    }
+}
+
+/// 渲染 Mermaid -> PNG 字节（阻塞，需要 `png` feature）。
+///
+/// 说明：
+/// - 先走 [`render_mermaid`] 得到 SVG，再用纯 Rust 的 resvg 光栅化；
+/// - 字体随 crate 打包（DejaVu Sans / Mono），不读系统字体，不同机器输出一致。
+#[cfg(all(
+    feature = "png",
+    any(feature = "js-backend", feature = "native-backend")
+))]
+pub fn render_mermaid_png(
+    text: &str,
+    options: &RenderOptions,
+    raster: &RasterOptions,
+) -> Result<Vec<u8>> {
+    let svg = render_mermaid(text, options)?;
+    raster::svg_to_png(&svg, raster)
+}
+
+/// 把 [`render_mermaid`] 输出的 SVG 光栅化为 PNG 字节（需要 `png` feature）。
+///
+/// 适合已经拿到 SVG（比如做了后处理）的场景；SVG 里的 CSS 变量会先解析成具体颜色。
+#[cfg(feature = "png")]
+pub fn svg_to_png(svg: &str, raster: &RasterOptions) -> Result<Vec<u8>> {
+    raster::svg_to_png(svg, raster)
 }
 
 /// 渲染 Mermaid -> ASCII/Unicode（阻塞，同步）。
+#[cfg(any(feature = "js-backend", feature = "native-backend"))]
 pub fn render_mermaid_ascii(text: &str, options: &AsciiRenderOptions) -> Result<String> {
-    js::with_js_engine(|engine| engine.render_mermaid_ascii(text, options))
+    #[cfg(not(feature = "native-backend"))]
+    require_native_routing_checks(text, options)?;
+    match native_diagram_kind(text, ascii_backend(options)) {
+        #[cfg(feature = "native-backend")]
+        Some(DiagramKind::Flowchart) => native::flowchart_ascii::render(text, options),
+        #[cfg(feature = "native-backend")]
+        Some(DiagramKind::Sequence) => native::sequence_ascii::render(text, options),
+        #[cfg(feature = "native-backend")]
+        Some(DiagramKind::Class) => native::class_ascii::render(text, options),
+        #[cfg(feature = "native-backend")]
+        Some(DiagramKind::Er) => native::er_ascii::render(text, options),
+        #[cfg(feature = "js-backend")]
+        _ => js::with_js_engine(|engine| engine.render_mermaid_ascii(text, options)),
+        #[cfg(not(feature = "js-backend"))]
+        kind => Err(js_backend_required(kind, "ASCII")),
+    }
 }
 
 /// 渲染 Mermaid -> ASCII/Unicode + meta（阻塞，同步）。
 ///
 /// 说明：
 /// - `text` 字段等价于 `render_mermaid_ascii(...)` 的输出；
-/// - `meta` 提供 node/edge 在字符画上的坐标信息，便于上层 UI 做高亮/动画。
+/// - `meta` 提供元素在字符画上的坐标信息，便于上层 UI 做高亮/动画；
+///   按图类型区分（flowchart/state、sequence、class、ER），见 [`AsciiDiagramMeta`]。
+#[cfg(any(feature = "js-backend", feature = "native-backend"))]
 pub fn render_mermaid_ascii_with_meta(
     text: &str,
     options: &AsciiRenderOptions,
 ) -> Result<AsciiRenderWithMeta> {
-    js::with_js_engine(|engine| engine.render_mermaid_ascii_with_meta(text, options))
+    #[cfg(not(feature = "native-backend"))]
+    require_native_routing_checks(text, options)?;
+    match native_diagram_kind(text, ascii_backend(options)) {
+        #[cfg(feature = "native-backend")]
+        Some(DiagramKind::Flowchart) => {
+            let (text, meta) = native::flowchart_ascii::render_with_meta(text, options)?;
+            Ok(AsciiRenderWithMeta {
+                text,
+                meta: AsciiDiagramMeta::Flowchart(meta),
+            })
+        }
+        #[cfg(feature = "native-backend")]
+        Some(DiagramKind::Sequence) => Ok(AsciiRenderWithMeta {
+            text: native::sequence_ascii::render(text, options)?,
+            meta: AsciiDiagramMeta::Sequence(native::sequence::ascii_meta(text)),
+        }),
+        #[cfg(feature = "native-backend")]
+        Some(DiagramKind::Class) => Ok(AsciiRenderWithMeta {
+            text: native::class_ascii::render(text, options)?,
+            meta: AsciiDiagramMeta::Class(native::class::ascii_meta(text)),
+        }),
+        #[cfg(feature = "native-backend")]
+        Some(DiagramKind::Er) => Ok(AsciiRenderWithMeta {
+            text: native::er_ascii::render(text, options)?,
+            meta: AsciiDiagramMeta::Er(native::er::ascii_meta(
+                text,
+                options.use_ascii.unwrap_or(false),
+            )),
+        }),
+        #[cfg(feature = "js-backend")]
+        _ => js::with_js_engine(|engine| engine.render_mermaid_ascii_with_meta(text, options)),
+        #[cfg(not(feature = "js-backend"))]
+        kind => Err(js_backend_required(kind, "ASCII")),
+    }
+}
+
+/// 渲染 Mermaid -> ASCII/Unicode + 走线统计（阻塞，同步）。
+///
+/// 说明：
+/// - 统计来自 native 的 A* 走线，所以 flowchart/state 总是按 `Backend::Native` 渲染；
+///   `text` 与 native 的 `render_mermaid_ascii(...)` 输出逐字节相同；
+/// - 其余图类型没有 A* 走线：照常渲染，`stats` 为空（见 [`AsciiRoutingStats`]）。
+#[cfg(feature = "native-backend")]
+pub fn render_mermaid_ascii_with_stats(
+    text: &str,
+    options: &AsciiRenderOptions,
+) -> Result<AsciiRenderWithStats> {
+    if native_diagram_kind(text, Some(Backend::Native)) == Some(DiagramKind::Flowchart) {
+        let (text, stats) = native::flowchart_ascii::render_with_stats(text, options)?;
+        return Ok(AsciiRenderWithStats { text, stats });
+    }
+    Ok(AsciiRenderWithStats {
+        text: render_mermaid_ascii(text, options)?,
+        stats: AsciiRoutingStats::default(),
+    })
+}
+
+/// 渲染 Mermaid -> ASCII/Unicode + 逐条边的走线调试帧（阻塞，同步）。
+///
+/// 说明：
+/// - 每帧是一条边走线前的网格快照 + 这条边所有 A* 搜索的展开热度与被拒单步 + 最终路径，
+///   用 `DebugFrame::to_text` / `to_svg` 或 `routing::debug::animated_svg` 画出来；
+/// - 与 [`render_mermaid_ascii_with_stats`] 一样总是按 `Backend::Native` 走线，`text` 逐字节不变；
+///   其余图类型没有 A* 走线，`frames` 为空。记录帧很慢，只在调试时使用。
+#[cfg(feature = "native-backend")]
+pub fn render_mermaid_ascii_with_debug(
+    text: &str,
+    options: &AsciiRenderOptions,
+) -> Result<AsciiRenderWithDebug> {
+    if native_diagram_kind(text, Some(Backend::Native)) == Some(DiagramKind::Flowchart) {
+        let (text, frames) = native::flowchart_ascii::render_with_debug(text, options)?;
+        return Ok(AsciiRenderWithDebug { text, frames });
+    }
+    Ok(AsciiRenderWithDebug {
+        text: render_mermaid_ascii(text, options)?,
+        frames: Vec::new(),
+    })
+}
+
+/// `AsciiRouting::Global` 与 `fail_on_unroutable` 只有 native 实现：选了就按 `Backend::Native` 处理
+/// （其余图类型的 native 输出与 JS 一致，不受影响）。
+#[cfg(any(feature = "js-backend", feature = "native-backend"))]
+fn ascii_backend(options: &AsciiRenderOptions) -> Option<Backend> {
+    if options.routing == Some(AsciiRouting::Global) || options.fail_on_unroutable == Some(true) {
+        Some(Backend::Native)
+    } else {
+        options.backend
+    }
+}
+
+/// 走 native 时返回图类型（由调用方决定哪些类型已有 native 实现，其余继续走 JS）；
+/// 走 JS 时返回 `None`。
+///
+/// 只编译了一个后端时忽略 `backend`，直接用编译进来的那个。
+#[cfg(any(feature = "js-backend", feature = "native-backend"))]
+fn native_diagram_kind(text: &str, backend: Option<Backend>) -> Option<DiagramKind> {
+    let use_native = if cfg!(not(feature = "js-backend")) {
+        true
+    } else if cfg!(not(feature = "native-backend")) {
+        false
+    } else {
+        backend == Some(Backend::Native)
+    };
+    use_native.then(|| native::detect_diagram_kind(text))
+}
+
+/// `fail_on_unroutable` 要靠 native 走线判断：没编译 `native-backend` 时 flowchart/state 直接报错，
+/// 而不是静默忽略（其余图类型没有走线，照常渲染）。
+#[cfg(all(feature = "js-backend", not(feature = "native-backend")))]
+fn require_native_routing_checks(text: &str, options: &AsciiRenderOptions) -> Result<()> {
+    if options.fail_on_unroutable == Some(true)
+        && native::detect_diagram_kind(text) == DiagramKind::Flowchart
+    {
+        return Err(BeautifulMermaidError::BackendUnavailable {
+            message: "`fail_on_unroutable` 需要 `native-backend` feature".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// 没编译 JS 后端、native 又还不支持该图类型时的错误。
+#[cfg(all(feature = "native-backend", not(feature = "js-backend")))]
+fn js_backend_required(kind: Option<DiagramKind>, output: &str) -> BeautifulMermaidError {
+    BeautifulMermaidError::BackendUnavailable {
+        message: format!(
+            "{} 的 {output} 渲染需要 `js-backend` feature",
+            kind.map_or("该图类型", DiagramKind::name)
+        ),
+    }
 }
 
 /// 校验 Mermaid 语法是否有效（阻塞，同步）。
@@ -51,8 +314,12 @@ pub fn render_mermaid_ascii_with_meta(
 /// - `Ok(MermaidValidation { is_valid: false, .. })`：无效（无法被 parser 解析）
 ///
 /// 实现说明：
-/// - 当前版本使用纯 Rust 的 `selkie::parse` 做语法校验（不依赖 Node）。
+/// - 当前版本使用纯 Rust 的 `selkie::parse` 做语法校验（不依赖 Node）；
+///   编译了 `native-backend` 时再跑一遍 native parser，`Backend::Native` 渲染失败的输入不会判为有效，
+///   native 渲染器也会先过 selkie，两边接受与拒绝的输入一致（JS 后端更宽松，不受这条约束）。
 /// - 当前实现不会返回 `Err`；保留 `Result` 只是为了未来可替换后端时仍能表达“内部错误”。
+/// - 需要 `validate` feature。
+#[cfg(feature = "validate")]
 pub fn validate_mermaid(text: &str) -> Result<MermaidValidation> {
     // --------------------------------------------------------------------
     // 这里把“空输入”视为“无效 Mermaid”而不是内部错误：
@@ -67,16 +334,27 @@ pub fn validate_mermaid(text: &str) -> Result<MermaidValidation> {
         });
     }
 
-    match selkie::parse(text) {
-        Ok(_diagram) => Ok(MermaidValidation {
-            is_valid: true,
-            error: None,
-            details: None,
-        }),
-        Err(err) => Ok(MermaidValidation {
+    if let Err(err) = selkie::parse(text) {
+        return Ok(MermaidValidation {
             is_valid: false,
             error: Some(err.to_string()),
             details: Some(format!("{err:?}")),
-        }),
+        });
     }
+
+    // native 渲染器拒绝的输入（例如 header 写错）也算无效：与 `Backend::Native` 的判断保持一致
+    #[cfg(feature = "native-backend")]
+    if let Err(err) = native::check_model(text) {
+        return Ok(MermaidValidation {
+            is_valid: false,
+            error: Some(err.to_string()),
+            details: Some(format!("{err:?}")),
+        });
+    }
+
+    Ok(MermaidValidation {
+        is_valid: true,
+        error: None,
+        details: None,
+    })
 }
@@ -4,6 +4,7 @@ fn main() {
     // - 默认从 stdin 读取 Mermaid 文本
     // - 默认输出 SVG
     // - 传 `--ascii` 切换为 ASCII/Unicode 文本输出
+    // - 传 `--png` 输出 PNG 二进制（需要 `png` feature）
     // - 传 `--help/-h` 输出帮助并退出
     // - 传 `--version/-V` 输出版本并退出
     //
@@ -19,18 +20,18 @@ fn main() {
     // 1) 始终补齐末尾换行，避免 zsh 把提示符（通常是 `%`）粘在输出后面。
     // 2) pipe 场景下如果下游提前关闭（BrokenPipe），按 Unix 习惯静默退出，不 panic。
     // --------------------------------------------------------------------
-    fn write_stdout_with_trailing_newline(text: &str) {
-        use std::io::{self, Write};
+    fn handle_write_error(err: io::Error) -> ! {
+        if err.kind() == io::ErrorKind::BrokenPipe {
+            // 下游已关闭（例如 `| head -n 1`），这不是“程序错误”，直接 0 退出即可。
+            std::process::exit(0);
+        }
 
-        fn handle_write_error(err: io::Error) -> ! {
-            if err.kind() == io::ErrorKind::BrokenPipe {
-                // 下游已关闭（例如 `| head -n 1`），这不是“程序错误”，直接 0 退出即可。
-                std::process::exit(0);
-            }
+        eprintln!("写入 stdout 失败: {err}");
+        std::process::exit(1);
+    }
 
-            eprintln!("写入 stdout 失败: {err}");
-            std::process::exit(1);
-        }
+    fn write_stdout_with_trailing_newline(text: &str) {
+        use std::io::Write;
 
         let mut stdout = io::stdout().lock();
         if let Err(err) = stdout.write_all(text.as_bytes()) {
@@ -44,6 +45,19 @@ fn main() {
         }
     }
 
+    // --------------------------------------------------------------------
+    // 二进制输出（PNG）：原样写出，不补换行（否则会破坏文件）。
+    // --------------------------------------------------------------------
+    #[cfg_attr(not(feature = "png"), allow(dead_code))]
+    fn write_stdout_bytes(bytes: &[u8]) {
+        use std::io::Write;
+
+        let mut stdout = io::stdout().lock();
+        if let Err(err) = stdout.write_all(bytes).and_then(|()| stdout.flush()) {
+            handle_write_error(err);
+        }
+    }
+
     // --------------------------------------------------------------------
     // 输出 CLI 帮助：
     // - 允许 code agent 在陌生环境里快速自发现用法
@@ -69,6 +83,18 @@ fn main() {
   # 强制使用 strict 路由(与默认 relaxed 不同,可用于对照可读性)
   beautiful-mermaid-rs --ascii --routing strict < diagram.mmd
 
+  # 每次转弯额外计 2 步代价：线条拐点更少
+  beautiful-mermaid-rs --ascii --turn-penalty 2 < diagram.mmd
+
+  # 字符画照常输出到 stdout，每条边的 A* 走线统计输出到 stderr
+  beautiful-mermaid-rs --ascii --stats < diagram.mmd
+
+  # 输出 PNG（需要 `cargo build --features png`），2 倍分辨率、白底
+  beautiful-mermaid-rs --png --scale 2 --background white < diagram.mmd > diagram.png
+
+  # 用上游新 build 的 bundle 渲染（不用重新编译）
+  beautiful-mermaid-rs --bundle dist/beautiful-mermaid.browser.global.js < diagram.mmd
+
   # 仅校验 Mermaid 语法（stdout 输出 true/false）
   beautiful-mermaid-rs --validate < diagram.mmd
 
@@ -78,8 +104,28 @@ fn main() {
 选项：
   --ascii         输出 ASCII/Unicode 文本（默认输出 SVG）
   --use-ascii     仅在 --ascii 模式下生效：强制使用纯 ASCII 字符
-  --routing <strict|relaxed>
-                 仅在 --ascii 模式下生效：strict/relaxed 两种路由策略(输出可能差异很大)
+  --routing <strict|relaxed|global>
+                 仅在 --ascii 模式下生效：strict/relaxed 两种路由策略(输出可能差异很大)；
+                 global = relaxed + 全局 rip-up-and-reroute（总走 native 后端）
+  --turn-penalty <n>
+                 仅在 --ascii 模式下生效：走线每次转弯的额外代价（默认 0，与 TS 一致）
+  --ts-compatible
+                 仅在 --ascii 模式下生效：TS 兼容模式，忽略 --turn-penalty，
+                 走线与 TS 版逐字节一致
+  --fail-on-unroutable
+                 仅在 --ascii 模式下生效：有边走不通、或要放开约束才走通时报错退出（退出码 1），
+                 不输出字符画（flowchart/state 总走 native 后端）
+  --stats         仅在 --ascii 模式下生效：stderr 输出每条边的走线统计
+                 （A* 次数 / 展开格子数 / 封闭探测格子数 / 被拒绝的单步 / 路径代价；
+                 flowchart/state 总走 native 后端）
+  --png           输出 PNG 二进制（需要 png feature）
+  --scale <n>     仅在 --png 模式下生效：缩放倍数（默认 1）
+  --dpi <n>       仅在 --png 模式下生效：目标 DPI（默认 96，写入 PNG 元数据）
+  --background <color>
+                 仅在 --png 模式下生效：背景色（默认沿用 SVG 背景）
+  --bundle <path> 用外部 JS bundle 代替内嵌的那份（browser IIFE 构建，
+                 必须导出 renderMermaid / renderMermaidAscii / renderMermaidAsciiWithMeta；
+                 不能与 --routing global / --fail-on-unroutable 同时使用）
   --validate      校验 Mermaid 语法（不输出 SVG/ASCII），stdout 输出 true/false
   --validate-markdown
                  扫描 stdin 的 Markdown，校验其中所有 ```mermaid 代码块
@@ -91,6 +137,105 @@ fn main() {
         write_stdout_with_trailing_newline(&help);
     }
 
+    // --------------------------------------------------------------------
+    // `--stats`：每条边一行，最后一行是合计；全部写到 stderr，stdout 仍然只有字符画。
+    // --------------------------------------------------------------------
+    #[cfg(feature = "native-backend")]
+    fn print_routing_stats(stats: &beautiful_mermaid_rs::AsciiRoutingStats) {
+        if stats.edges.is_empty() {
+            eprintln!("走线统计：该图类型没有 A* 走线。");
+            return;
+        }
+        let names: Vec<String> = stats
+            .edges
+            .iter()
+            .map(|edge| {
+                if edge.label.is_empty() {
+                    format!("{} -> {}", edge.from, edge.to)
+                } else {
+                    format!("{} -> {} ({})", edge.from, edge.to, edge.label)
+                }
+            })
+            .collect();
+        let width = names
+            .iter()
+            .map(|name| name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+
+        eprintln!("走线统计（布局尝试 {} 次）：", stats.layout_attempts);
+        eprintln!(
+            "{:<width$}  {:>8}  {:>6}  {:>10}  {:>6}  {:>8}  {:>18}  {:>6}  notes",
+            "edge",
+            "searches",
+            "failed",
+            "expansions",
+            "probe",
+            "heapPeak",
+            "blocked/cross/seg",
+            "cost"
+        );
+        let mut total = beautiful_mermaid_rs::AsciiEdgeRoutingStats::default();
+        for (edge, name) in stats.edges.iter().zip(&names) {
+            let mut notes = Vec::new();
+            if !edge.routed {
+                notes.push("未走通");
+            }
+            if edge.shared_path {
+                notes.push("复用平行边");
+            }
+            if edge.end_segment_reuse {
+                notes.push("终点线段复用");
+            }
+            if edge.unconstrained {
+                notes.push("无约束兜底");
+            }
+            let exhausted = format!("{} 次预算用完", edge.budget_exhausted);
+            if edge.budget_exhausted > 0 {
+                notes.push(&exhausted);
+            }
+            let rejections = edge.rejections;
+            let line = format!(
+                "{name:<width$}  {:>8}  {:>6}  {:>10}  {:>6}  {:>8}  {:>18}  {:>6}  {}",
+                edge.searches,
+                edge.unreachable,
+                edge.expansions,
+                edge.probe_cells,
+                edge.heap_peak,
+                format!(
+                    "{}/{}/{}",
+                    rejections.blocked, rejections.crossing, rejections.segment
+                ),
+                edge.path_cost
+                    .map_or("-".to_string(), |cost| cost.to_string()),
+                notes.join(", ")
+            );
+            eprintln!("{}", line.trim_end());
+            total.searches += edge.searches;
+            total.unreachable += edge.unreachable;
+            total.expansions += edge.expansions;
+            total.probe_cells += edge.probe_cells;
+            total.heap_peak = total.heap_peak.max(edge.heap_peak);
+            total.rejections.blocked += rejections.blocked;
+            total.rejections.crossing += rejections.crossing;
+            total.rejections.segment += rejections.segment;
+        }
+        eprintln!(
+            "{:<width$}  {:>8}  {:>6}  {:>10}  {:>6}  {:>8}  {:>18}",
+            "total",
+            total.searches,
+            total.unreachable,
+            total.expansions,
+            total.probe_cells,
+            total.heap_peak,
+            format!(
+                "{}/{}/{}",
+                total.rejections.blocked, total.rejections.crossing, total.rejections.segment
+            ),
+        );
+    }
+
     // --------------------------------------------------------------------
     // 校验 Markdown 内的 Mermaid code fence：
     // - 只识别 ```mermaid ... ``` 这种 fenced code block（与 GitHub/常见 Markdown 行为一致）
@@ -201,6 +346,15 @@ fn main() {
         validate: bool,
         validate_markdown: bool,
         routing: Option<beautiful_mermaid_rs::AsciiRouting>,
+        turn_penalty: Option<u32>,
+        ts_compatible: bool,
+        stats: bool,
+        fail_on_unroutable: bool,
+        png: bool,
+        scale: Option<f32>,
+        dpi: Option<f32>,
+        background: Option<String>,
+        bundle: Option<String>,
         help: bool,
         version: bool,
     }
@@ -209,10 +363,24 @@ fn main() {
         match value.trim().to_ascii_lowercase().as_str() {
             "strict" => Some(beautiful_mermaid_rs::AsciiRouting::Strict),
             "relaxed" => Some(beautiful_mermaid_rs::AsciiRouting::Relaxed),
+            "global" => Some(beautiful_mermaid_rs::AsciiRouting::Global),
             _ => None,
         }
     }
 
+    // `--scale` / `--dpi` 的值：必须是正数，解析失败直接报用法错误。
+    fn parse_positive_number(flag: &str, value: Option<&String>) -> f32 {
+        let value = value.map(|s| s.as_str()).unwrap_or("");
+        match value.trim().parse::<f32>() {
+            Ok(number) if number.is_finite() && number > 0.0 => number,
+            _ => {
+                eprintln!("参数错误：`{flag}` 需要一个正数，当前为: {value:?}");
+                eprintln!("提示：例如 `beautiful-mermaid-rs --png {flag} 2 < diagram.mmd`。");
+                std::process::exit(2);
+            }
+        }
+    }
+
     // --------------------------------------------------------------------
     // 做一点点“强约束”：
     // - 仅支持极少量参数，避免 typo 静默被忽略，浪费排查时间
@@ -228,10 +396,44 @@ fn main() {
             "--use-ascii" => cli.use_ascii = true,
             "--validate" => cli.validate = true,
             "--validate-markdown" => cli.validate_markdown = true,
+            "--ts-compatible" => cli.ts_compatible = true,
+            "--stats" => cli.stats = true,
+            "--fail-on-unroutable" => cli.fail_on_unroutable = true,
+            "--png" => cli.png = true,
+            "--scale" => {
+                cli.scale = Some(parse_positive_number("--scale", args.get(idx + 1)));
+                idx += 1;
+            }
+            "--bundle" => {
+                match args.get(idx + 1) {
+                    Some(path) if !path.trim().is_empty() => cli.bundle = Some(path.clone()),
+                    _ => {
+                        eprintln!("参数错误：`--bundle` 需要一个文件路径。");
+                        eprintln!(
+                            "提示：例如 `beautiful-mermaid-rs --bundle beautiful-mermaid.browser.global.js < diagram.mmd`。"
+                        );
+                        std::process::exit(2);
+                    }
+                }
+                idx += 1;
+            }
+            "--dpi" => {
+                cli.dpi = Some(parse_positive_number("--dpi", args.get(idx + 1)));
+                idx += 1;
+            }
+            "--background" => {
+                let value = args.get(idx + 1).map(|s| s.as_str()).unwrap_or("");
+                if value.is_empty() {
+                    eprintln!("参数错误：`--background` 需要一个颜色值，例如 white 或 #ffffff。");
+                    std::process::exit(2);
+                }
+                cli.background = Some(value.to_string());
+                idx += 1;
+            }
             "--routing" => {
                 let value = args.get(idx + 1).map(|s| s.as_str()).unwrap_or("");
                 if value.is_empty() {
-                    eprintln!("参数错误：`--routing` 需要一个值：strict 或 relaxed。");
+                    eprintln!("参数错误：`--routing` 需要一个值：strict、relaxed 或 global。");
                     eprintln!("提示：可以先运行 `beautiful-mermaid-rs --help` 查看完整用法。");
                     std::process::exit(2);
                 }
@@ -239,7 +441,21 @@ fn main() {
                     Some(routing) => cli.routing = Some(routing),
                     None => {
                         eprintln!("参数错误：`--routing` 的值无效: {value}");
-                        eprintln!("可选值: strict | relaxed");
+                        eprintln!("可选值: strict | relaxed | global");
+                        std::process::exit(2);
+                    }
+                }
+                idx += 1;
+            }
+            "--turn-penalty" => {
+                let value = args.get(idx + 1).map(|s| s.as_str()).unwrap_or("");
+                match value.trim().parse::<u32>() {
+                    Ok(penalty) => cli.turn_penalty = Some(penalty),
+                    Err(_) => {
+                        eprintln!("参数错误：`--turn-penalty` 需要一个非负整数，当前为: {value:?}");
+                        eprintln!(
+                            "提示：例如 `beautiful-mermaid-rs --ascii --turn-penalty 2 < diagram.mmd`。"
+                        );
                         std::process::exit(2);
                     }
                 }
@@ -251,7 +467,7 @@ fn main() {
                     Some(routing) => cli.routing = Some(routing),
                     None => {
                         eprintln!("参数错误：`--routing` 的值无效: {value}");
-                        eprintln!("可选值: strict | relaxed");
+                        eprintln!("可选值: strict | relaxed | global");
                         std::process::exit(2);
                     }
                 }
@@ -289,12 +505,80 @@ fn main() {
         std::process::exit(2);
     }
 
+    if cli.turn_penalty.is_some() && !cli.ascii {
+        eprintln!("参数错误：`--turn-penalty` 仅在 `--ascii` 模式下生效。");
+        eprintln!("提示：例如 `beautiful-mermaid-rs --ascii --turn-penalty 2 < diagram.mmd`。");
+        std::process::exit(2);
+    }
+
+    if cli.stats && !cli.ascii {
+        eprintln!("参数错误：`--stats` 仅在 `--ascii` 模式下生效。");
+        eprintln!("提示：例如 `beautiful-mermaid-rs --ascii --stats < diagram.mmd`。");
+        std::process::exit(2);
+    }
+
+    if cli.ts_compatible && !cli.ascii {
+        eprintln!("参数错误：`--ts-compatible` 仅在 `--ascii` 模式下生效。");
+        eprintln!("提示：例如 `beautiful-mermaid-rs --ascii --ts-compatible < diagram.mmd`。");
+        std::process::exit(2);
+    }
+
+    if cli.fail_on_unroutable && !cli.ascii {
+        eprintln!("参数错误：`--fail-on-unroutable` 仅在 `--ascii` 模式下生效。");
+        eprintln!("提示：例如 `beautiful-mermaid-rs --ascii --fail-on-unroutable < diagram.mmd`。");
+        std::process::exit(2);
+    }
+
+    if cli.stats && cfg!(not(feature = "native-backend")) {
+        eprintln!("参数错误：当前二进制未启用 native-backend feature，`--stats` 不可用。");
+        eprintln!("提示：走线统计来自 native 后端，请用默认 features 重新构建。");
+        std::process::exit(2);
+    }
+
+    if (cli.scale.is_some() || cli.dpi.is_some() || cli.background.is_some()) && !cli.png {
+        eprintln!("参数错误：`--scale/--dpi/--background` 仅在 `--png` 模式下生效。");
+        eprintln!(
+            "提示：例如 `beautiful-mermaid-rs --png --scale 2 < diagram.mmd > diagram.png`。"
+        );
+        std::process::exit(2);
+    }
+
+    if cli.png && (cli.ascii || cli.validate || cli.validate_markdown) {
+        eprintln!("参数错误：`--png` 不能与 `--ascii` 或 `--validate*` 同时使用。");
+        eprintln!("提示：可以先运行 `beautiful-mermaid-rs --help` 查看完整用法。");
+        std::process::exit(2);
+    }
+
+    if cli.png && cfg!(not(feature = "png")) {
+        eprintln!("参数错误：当前二进制未启用 png feature，`--png` 不可用。");
+        eprintln!("提示：请用 `cargo build --release --features png` 重新构建。");
+        std::process::exit(2);
+    }
+
     if cli.validate && cli.validate_markdown {
         eprintln!("参数错误：`--validate` 与 `--validate-markdown` 不能同时使用。");
         eprintln!("提示：可以先运行 `beautiful-mermaid-rs --help` 查看完整用法。");
         std::process::exit(2);
     }
 
+    if cli.bundle.is_some() && (cli.validate || cli.validate_markdown) {
+        eprintln!("参数错误：`--bundle` 只影响渲染，不能与 `--validate*` 同时使用。");
+        eprintln!("提示：可以先运行 `beautiful-mermaid-rs --help` 查看完整用法。");
+        std::process::exit(2);
+    }
+
+    // `--routing global` / `--fail-on-unroutable` 只有 native 实现，会整体绕开 JS bundle。
+    if cli.bundle.is_some()
+        && (cli.routing == Some(beautiful_mermaid_rs::AsciiRouting::Global)
+            || cli.fail_on_unroutable)
+    {
+        eprintln!(
+            "参数错误：`--routing global` / `--fail-on-unroutable` 总走 native 后端，`--bundle` 不会被用到。"
+        );
+        eprintln!("提示：去掉 `--bundle`，或改用 `--routing strict|relaxed`。");
+        std::process::exit(2);
+    }
+
     if (cli.validate || cli.validate_markdown) && (cli.ascii || cli.use_ascii) {
         eprintln!(
             "参数错误：校验模式（`--validate*`）不能与渲染模式（`--ascii/--use-ascii`）混用。"
@@ -349,18 +633,65 @@ fn main() {
         }
     }
 
+    // --------------------------------------------------------------------
+    // `--bundle`：在读 stdin 之后、渲染之前加载，握手失败（缺 API / eval 报错）直接退出。
+    // --------------------------------------------------------------------
+    if let Some(path) = &cli.bundle {
+        let config = beautiful_mermaid_rs::EngineConfig::new().bundle_path(path);
+        if let Err(err) = beautiful_mermaid_rs::set_js_engine_config(&config) {
+            eprintln!("加载 bundle 失败: {err}");
+            std::process::exit(1);
+        }
+    }
+
     let use_ascii_renderer = cli.ascii;
     let force_pure_ascii = cli.use_ascii;
 
+    #[cfg(feature = "png")]
+    if cli.png {
+        let raster = beautiful_mermaid_rs::RasterOptions {
+            scale: cli.scale,
+            dpi: cli.dpi,
+            background: cli.background.clone(),
+        };
+        let options = beautiful_mermaid_rs::RenderOptions::default();
+        match beautiful_mermaid_rs::render_mermaid_png(&input, &options, &raster) {
+            Ok(png) => write_stdout_bytes(&png),
+            Err(err) => {
+                eprintln!("渲染 PNG 失败: {err}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     if use_ascii_renderer {
         let options = beautiful_mermaid_rs::AsciiRenderOptions {
             // `--use-ascii`：输出纯 ASCII 字符集；否则输出 Unicode 线条字符
             use_ascii: Some(force_pure_ascii),
             // 让用户可显式切换路由策略,用于在“紧凑度/可读性”之间做取舍对照。
             routing: cli.routing,
+            turn_penalty: cli.turn_penalty,
+            ts_compatible: Some(cli.ts_compatible),
+            fail_on_unroutable: Some(cli.fail_on_unroutable),
             ..Default::default()
         };
 
+        #[cfg(feature = "native-backend")]
+        if cli.stats {
+            match beautiful_mermaid_rs::render_mermaid_ascii_with_stats(&input, &options) {
+                Ok(output) => {
+                    write_stdout_with_trailing_newline(&output.text);
+                    print_routing_stats(&output.stats);
+                }
+                Err(err) => {
+                    eprintln!("渲染 ASCII 失败: {err}");
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+
         match beautiful_mermaid_rs::render_mermaid_ascii(&input, &options) {
             Ok(output) => write_stdout_with_trailing_newline(&output),
             Err(err) => {
@@ -0,0 +1,351 @@
+// ============================================================================
+// 字符画布（对齐 JS bundle 的 canvas 工具函数）
+//
+// 约定：
+// - `cells[x][y]`：按列存储，与 JS 的 `canvas[x][y]` 一致；
+// - `max()` 返回最大下标（不是尺寸），对齐 JS `getCanvasSize`；
+// - 越界写入直接忽略（JS 侧越界写要么抛异常、要么写进不可见区域，不影响输出）。
+// ============================================================================
+
+use crate::native::text::{char_width, display_width};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Canvas {
+    cells: Vec<Vec<char>>,
+}
+
+impl Canvas {
+    /// 空白画布，下标范围 `[0, max_x] × [0, max_y]`。
+    pub(crate) fn new(max_x: i32, max_y: i32) -> Self {
+        let width = (max_x + 1).max(0) as usize;
+        let height = (max_y + 1).max(0) as usize;
+        Self {
+            cells: vec![vec![' '; height]; width],
+        }
+    }
+
+    /// 同尺寸的空白画布（JS `copyCanvas`）。
+    pub(crate) fn blank_like(&self) -> Self {
+        let (max_x, max_y) = self.max();
+        Self::new(max_x, max_y)
+    }
+
+    /// 最大下标 `(max_x, max_y)`。
+    pub(crate) fn max(&self) -> (i32, i32) {
+        let max_x = self.cells.len() as i32 - 1;
+        let max_y = self.cells.first().map_or(1, Vec::len) as i32 - 1;
+        (max_x, max_y)
+    }
+
+    pub(crate) fn get(&self, x: i32, y: i32) -> char {
+        if x < 0 || y < 0 {
+            return ' ';
+        }
+        self.cells
+            .get(x as usize)
+            .and_then(|column| column.get(y as usize))
+            .copied()
+            .unwrap_or(' ')
+    }
+
+    pub(crate) fn set(&mut self, x: i32, y: i32, ch: char) {
+        if x < 0 || y < 0 {
+            return;
+        }
+        if let Some(cell) = self
+            .cells
+            .get_mut(x as usize)
+            .and_then(|column| column.get_mut(y as usize))
+        {
+            *cell = ch;
+        }
+    }
+
+    /// 扩容到至少 `[0, x] × [0, y]`（JS `increaseSize`）。
+    pub(crate) fn grow(&mut self, x: i32, y: i32) {
+        let (max_x, max_y) = self.max();
+        let width = (x.max(max_x) + 1).max(0) as usize;
+        let height = (y.max(max_y) + 1).max(0) as usize;
+        for column in &mut self.cells {
+            column.resize(height, ' ');
+        }
+        self.cells.resize(width, vec![' '; height]);
+    }
+
+    /// 从 `(x, y)` 开始写一行文本，宽字符占两格（JS `drawText`）。
+    pub(crate) fn draw_text(&mut self, x: i32, y: i32, text: &str) {
+        self.grow(x + display_width(text), y);
+        let mut cursor = x;
+        for ch in text.chars() {
+            self.set(cursor, y, ch);
+            cursor += char_width(ch);
+        }
+    }
+
+    /// 上下翻转（BT 方向），同时把有方向性的字符换成镜像字符（JS `flipCanvasVertically`）。
+    pub(crate) fn flip_vertically(&mut self) {
+        for column in &mut self.cells {
+            column.reverse();
+            for cell in column.iter_mut() {
+                if let Some(flipped) = vertical_mirror(*cell) {
+                    *cell = flipped;
+                }
+            }
+        }
+    }
+
+    /// 按行拼成字符串；宽字符后面的占位格跳过（JS `canvasToString`）。
+    pub(crate) fn to_text(&self) -> String {
+        let (max_x, max_y) = self.max();
+        let mut lines = Vec::with_capacity((max_y + 1).max(0) as usize);
+        for y in 0..=max_y {
+            let mut line = String::new();
+            let mut x = 0;
+            while x <= max_x {
+                let ch = self.get(x, y);
+                line.push(ch);
+                if char_width(ch) == 2 {
+                    x += 1;
+                }
+                x += 1;
+            }
+            lines.push(line);
+        }
+        lines.join("\n")
+    }
+}
+
+fn vertical_mirror(ch: char) -> Option<char> {
+    Some(match ch {
+        '▲' => '▼',
+        '▼' => '▲',
+        '◤' => '◣',
+        '◣' => '◤',
+        '◥' => '◢',
+        '◢' => '◥',
+        '^' => 'v',
+        'v' => '^',
+        '┌' => '└',
+        '└' => '┌',
+        '┐' => '┘',
+        '┘' => '┐',
+        '┬' => '┴',
+        '┴' => '┬',
+        '╵' => '╷',
+        '╷' => '╵',
+        _ => return None,
+    })
+}
+
+fn is_box_char(ch: char) -> bool {
+    matches!(
+        ch,
+        '─' | '│'
+            | '┌'
+            | '┐'
+            | '└'
+            | '┘'
+            | '├'
+            | '┤'
+            | '┬'
+            | '┴'
+            | '┼'
+            | '╴'
+            | '╵'
+            | '╶'
+            | '╷'
+    )
+}
+
+/// 两个线条字符重叠时的合并结果（JS `mergeJunctions`）。
+fn merge_junction(existing: char, incoming: char) -> char {
+    match (existing, incoming) {
+        ('─', '│' | '├' | '┤') => '┼',
+        ('─', '┌' | '┐' | '┬') => '┬',
+        ('─', '└' | '┘' | '┴') => '┴',
+        ('│', '─' | '┬' | '┴') => '┼',
+        ('│', '┌' | '└' | '├') => '├',
+        ('│', '┐' | '┘' | '┤') => '┤',
+        ('┌', '─' | '┐' | '┬') => '┬',
+        ('┌', '│' | '└' | '├') => '├',
+        ('┌', '┘' | '┤' | '┴') => '┼',
+        ('┐', '─' | '┌' | '┬') => '┬',
+        ('┐', '│' | '┘' | '┤') => '┤',
+        ('┐', '└' | '├' | '┴') => '┼',
+        ('└', '─' | '┘' | '┴') => '┴',
+        ('└', '│' | '┌' | '├') => '├',
+        ('└', '┐' | '┤' | '┬') => '┼',
+        ('┘', '─' | '└' | '┴') => '┴',
+        ('┘', '│' | '┐' | '┤') => '┤',
+        ('┘', '┌' | '├' | '┬') => '┼',
+        ('├', '│' | '┌' | '└') => '├',
+        ('├', '─' | '┐' | '┘' | '┤' | '┬' | '┴') => '┼',
+        ('┤', '│' | '┐' | '┘') => '┤',
+        ('┤', '─' | '┌' | '└' | '├' | '┬' | '┴') => '┼',
+        ('┬', '─' | '┌' | '┐') => '┬',
+        ('┬', '│' | '└' | '┘' | '├' | '┤' | '┴') => '┼',
+        ('┴', '─' | '└' | '┘') => '┴',
+        ('┴', '│' | '┌' | '┐' | '├' | '┤' | '┬') => '┼',
+        _ => existing,
+    }
+}
+
+/// 把若干图层按偏移叠加到 `base` 上（JS `mergeCanvases`）。
+///
+/// - 图层里的空格视为透明；
+/// - Unicode 模式下线条字符相遇时合并成 junction（`┼`/`├`/...）。
+pub(crate) fn merge(
+    base: &Canvas,
+    offset_x: i32,
+    offset_y: i32,
+    use_ascii: bool,
+    layers: &[&Canvas],
+) -> Canvas {
+    let (mut max_x, mut max_y) = base.max();
+    for layer in layers {
+        let (layer_x, layer_y) = layer.max();
+        max_x = max_x.max(layer_x + offset_x);
+        max_y = max_y.max(layer_y + offset_y);
+    }
+
+    let mut merged = Canvas::new(max_x, max_y);
+    let (base_x, base_y) = base.max();
+    for x in 0..=base_x.min(max_x) {
+        for y in 0..=base_y.min(max_y) {
+            merged.set(x, y, base.get(x, y));
+        }
+    }
+
+    for layer in layers {
+        let (layer_x, layer_y) = layer.max();
+        for x in 0..=layer_x {
+            for y in 0..=layer_y {
+                let ch = layer.get(x, y);
+                if ch == ' ' {
+                    continue;
+                }
+                let (target_x, target_y) = (x + offset_x, y + offset_y);
+                let existing = merged.get(target_x, target_y);
+                if !use_ascii && is_box_char(ch) && is_box_char(existing) {
+                    merged.set(target_x, target_y, merge_junction(existing, ch));
+                } else {
+                    merged.set(target_x, target_y, ch);
+                }
+            }
+        }
+    }
+    merged
+}
+
+// ----------------------------------------------------------------------------
+// `┼` 消歧（JS `resolveCrossings`）
+// ----------------------------------------------------------------------------
+
+const JUNCTION_UP: u8 = 1;
+const JUNCTION_DOWN: u8 = 2;
+const JUNCTION_LEFT: u8 = 4;
+const JUNCTION_RIGHT: u8 = 8;
+const JUNCTION_ALL: u8 = JUNCTION_UP | JUNCTION_DOWN | JUNCTION_LEFT | JUNCTION_RIGHT;
+
+fn connects_down(ch: char) -> bool {
+    matches!(ch, '─' | '┼' | '┬' | '┴' | '┤' | '┐' | '┘')
+}
+
+fn connects_up(ch: char) -> bool {
+    matches!(ch, '─' | '┼' | '┬' | '┴' | '├' | '┌' | '└')
+}
+
+fn connects_right(ch: char) -> bool {
+    matches!(ch, '│' | '┼' | '├' | '┤' | '┴' | '└' | '┘')
+}
+
+fn connects_left(ch: char) -> bool {
+    matches!(ch, '│' | '┼' | '├' | '┤' | '┬' | '┌' | '┐')
+}
+
+fn junction_char(mask: u8) -> Option<char> {
+    const UD: u8 = JUNCTION_UP | JUNCTION_DOWN;
+    const LR: u8 = JUNCTION_LEFT | JUNCTION_RIGHT;
+    match mask {
+        JUNCTION_ALL => None,
+        UD => Some('─'),
+        LR => Some('│'),
+        m if m == JUNCTION_LEFT | JUNCTION_DOWN => Some('└'),
+        m if m == JUNCTION_LEFT | JUNCTION_UP => Some('┘'),
+        m if m == JUNCTION_RIGHT | JUNCTION_DOWN => Some('┌'),
+        m if m == JUNCTION_RIGHT | JUNCTION_UP => Some('┐'),
+        m if m == JUNCTION_LEFT | UD => Some('┴'),
+        m if m == JUNCTION_RIGHT | UD => Some('┬'),
+        m if m == LR | JUNCTION_UP => Some('┤'),
+        m if m == LR | JUNCTION_DOWN => Some('├'),
+        _ => None,
+    }
+}
+
+/// 把“其实不是十字交叉”的 `┼` 还原成直线或拐角。
+///
+/// `bends` 是边在拐点处的连接方向（key = `"x,y"`），由 `edge_bend_junctions` 产出。
+/// 注意 JS 里 up/down 实际对应的是 x 方向的邻居（沿用原实现的位定义）。
+pub(crate) fn resolve_crossings(canvas: &mut Canvas, bends: &HashMap<(i32, i32), u8>) {
+    let (max_x, max_y) = canvas.max();
+    for x in 0..=max_x {
+        for y in 0..=max_y {
+            if canvas.get(x, y) != '┼' {
+                continue;
+            }
+            let left_of = if x > 0 { canvas.get(x - 1, y) } else { ' ' };
+            let right_of = if x < max_x { canvas.get(x + 1, y) } else { ' ' };
+            let above = if y > 0 { canvas.get(x, y - 1) } else { ' ' };
+            let below = if y < max_y { canvas.get(x, y + 1) } else { ' ' };
+            let horizontal = i32::from(connects_up(left_of)) + i32::from(connects_down(right_of));
+            let vertical = i32::from(connects_left(above)) + i32::from(connects_right(below));
+
+            if let Some(&bend) = bends.get(&(x, y))
+                && bend != 0
+            {
+                let neighbours = (if connects_up(left_of) { JUNCTION_UP } else { 0 })
+                    | (if connects_down(right_of) {
+                        JUNCTION_DOWN
+                    } else {
+                        0
+                    })
+                    | (if connects_left(above) {
+                        JUNCTION_LEFT
+                    } else {
+                        0
+                    })
+                    | (if connects_right(below) {
+                        JUNCTION_RIGHT
+                    } else {
+                        0
+                    });
+                let prefer_vertical = vertical > horizontal;
+                let axis = if prefer_vertical {
+                    JUNCTION_LEFT | JUNCTION_RIGHT
+                } else {
+                    JUNCTION_UP | JUNCTION_DOWN
+                };
+                let mut mask = (neighbours & axis) | bend;
+                if mask == JUNCTION_ALL {
+                    if prefer_vertical {
+                        if bend & JUNCTION_UP == 0 {
+                            mask &= !JUNCTION_UP;
+                        } else if bend & JUNCTION_DOWN == 0 {
+                            mask &= !JUNCTION_DOWN;
+                        }
+                    } else if bend & JUNCTION_LEFT == 0 {
+                        mask &= !JUNCTION_LEFT;
+                    } else if bend & JUNCTION_RIGHT == 0 {
+                        mask &= !JUNCTION_RIGHT;
+                    }
+                }
+                if let Some(ch) = junction_char(mask) {
+                    canvas.set(x, y, ch);
+                    continue;
+                }
+            }
+            canvas.set(x, y, if vertical > horizontal { '│' } else { '─' });
+        }
+    }
+}
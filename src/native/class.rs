@@ -0,0 +1,851 @@
+// ============================================================================
+// Class diagram：解析 + ASCII 布局
+//
+// 对齐 JS bundle：
+// - parser: `parseClassDiagram`（class 块、成员、relationship）
+// - 布局:   `renderClassAscii` 里的分层（继承关系决定层级）+ 行内平铺 + 折线路由
+//
+// 说明：
+// - 这里只负责“坐标”，不负责画字符；坐标与 JS 输出的字符画逐格对齐。
+// ============================================================================
+
+#[cfg(feature = "native-backend")]
+use super::check_syntax;
+use super::text::display_width;
+use super::{significant_lines, strip_accessibility};
+#[cfg(feature = "native-backend")]
+use crate::error::Result;
+use crate::types::{
+    AsciiBox, AsciiClassMeta, AsciiClassNode, AsciiClassRelationship, AsciiDrawingCoord,
+    AsciiMemberRow, Backend,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+// ----------------------------------------------------------------------------
+// AST
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ClassMember {
+    /// `+` / `-` / `#` / `~` 或空串。
+    pub visibility: String,
+    pub name: String,
+    pub member_type: Option<String>,
+    /// 名字带 `$` 后缀（方法：整行出现 `$`）：SVG 里加下划线。
+    #[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+    pub is_static: bool,
+    /// 名字带 `*` 后缀（方法：整行出现 `*`）：SVG 里用斜体。
+    #[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+    pub is_abstract: bool,
+}
+
+impl ClassMember {
+    /// ASCII 渲染时的单行文本（例如 `+name: String`）。
+    pub(crate) fn display_text(&self) -> String {
+        match &self.member_type {
+            Some(member_type) => format!("{}{}: {member_type}", self.visibility, self.name),
+            None => format!("{}{}", self.visibility, self.name),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ClassNode {
+    pub id: String,
+    pub label: String,
+    pub annotation: Option<String>,
+    pub attributes: Vec<ClassMember>,
+    pub methods: Vec<ClassMember>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RelationshipType {
+    Inheritance,
+    Composition,
+    Aggregation,
+    Association,
+    Dependency,
+    Realization,
+}
+
+impl RelationshipType {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Inheritance => "inheritance",
+            Self::Composition => "composition",
+            Self::Aggregation => "aggregation",
+            Self::Association => "association",
+            Self::Dependency => "dependency",
+            Self::Realization => "realization",
+        }
+    }
+
+    /// 继承/实现：决定分层方向（父类在上）。
+    pub(crate) fn is_hierarchical(self) -> bool {
+        matches!(self, Self::Inheritance | Self::Realization)
+    }
+}
+
+/// 关系标记画在哪一端。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MarkerAt {
+    From,
+    To,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ClassRelationship {
+    pub from: String,
+    pub to: String,
+    pub relationship_type: RelationshipType,
+    pub marker_at: MarkerAt,
+    pub label: Option<String>,
+    /// 箭头两侧引号里的多重性（`"1" --> "*"`），只有 SVG 画。
+    #[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+    pub from_cardinality: Option<String>,
+    #[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+    pub to_cardinality: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ClassDiagram {
+    pub classes: Vec<ClassNode>,
+    pub relationships: Vec<ClassRelationship>,
+}
+
+// ----------------------------------------------------------------------------
+// Parser
+// ----------------------------------------------------------------------------
+
+static ANNOTATION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^<<([A-Za-z0-9_]+)>>$").unwrap());
+static NAMESPACE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^namespace\s+(\S+)\s*\{$").unwrap());
+static CLASS_BLOCK_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^class\s+(\S+?)(?:\s*~([A-Za-z0-9_]+)~)?\s*\{$").unwrap());
+static CLASS_DECL_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^class\s+(\S+?)(?:\s*~([A-Za-z0-9_]+)~)?\s*$").unwrap());
+static CLASS_INLINE_ANNOTATION_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^class\s+(\S+?)\s*\{\s*<<([A-Za-z0-9_]+)>>\s*\}$").unwrap());
+static MEMBER_LINE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\S+?)\s*:\s*(.+)$").unwrap());
+static RELATIONSHIP_ARROW_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"<\|--|--|\*--|o--|-->|\.\.>|\.\.\|>").unwrap());
+static METHOD_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(.+?)\(([^)]*)\)(?:\s*(.+))?$").unwrap());
+static RELATIONSHIP_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"^(\S+?)\s+(?:"([^"]*?)"\s+)?(<\|--|<\|\.\.|\*--|o--|-->|--\*|--o|--|>\s*|\.\.>|\.\.\|>|--)\s+(?:"([^"]*?)"\s+)?(\S+?)(?:\s*:\s*(.+))?$"#,
+    )
+    .unwrap()
+});
+
+fn ensure_class<'a>(
+    classes: &'a mut Vec<ClassNode>,
+    index: &mut HashMap<String, usize>,
+    id: &str,
+) -> &'a mut ClassNode {
+    let idx = *index.entry(id.to_string()).or_insert_with(|| {
+        classes.push(ClassNode {
+            id: id.to_string(),
+            label: id.to_string(),
+            annotation: None,
+            attributes: Vec::new(),
+            methods: Vec::new(),
+        });
+        classes.len() - 1
+    });
+    &mut classes[idx]
+}
+
+/// 解析单个成员（属性或方法）；返回 `(member, is_method)`。
+fn parse_member(text: &str) -> Option<(ClassMember, bool)> {
+    let trimmed = text.trim();
+    let trimmed = trimmed.strip_suffix(';').unwrap_or(trimmed);
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let mut visibility = String::new();
+    let mut rest = trimmed;
+    if let Some(first) = rest.chars().next()
+        && matches!(first, '+' | '-' | '#' | '~')
+    {
+        visibility.push(first);
+        rest = rest[first.len_utf8()..].trim();
+    }
+
+    let strip_marker =
+        |name: &str| -> String { name.strip_suffix(['$', '*']).unwrap_or(name).to_string() };
+
+    if let Some(caps) = METHOD_RE.captures(rest) {
+        let name = caps[1].trim();
+        let member_type = caps
+            .get(3)
+            .map(|m| m.as_str().trim().to_string())
+            .filter(|t| !t.is_empty());
+        return Some((
+            ClassMember {
+                visibility,
+                name: strip_marker(name),
+                member_type,
+                is_static: name.ends_with('$') || rest.contains('$'),
+                is_abstract: name.ends_with('*') || rest.contains('*'),
+            },
+            true,
+        ));
+    }
+
+    let parts: Vec<&str> = rest.split_whitespace().collect();
+    let (member_type, name) = if parts.len() >= 2 {
+        (Some(parts[0].to_string()), parts[1..].join(" "))
+    } else {
+        (None, parts.first().copied().unwrap_or(rest).to_string())
+    };
+
+    Some((
+        ClassMember {
+            visibility,
+            is_static: name.ends_with('$'),
+            is_abstract: name.ends_with('*'),
+            name: strip_marker(&name),
+            member_type,
+        },
+        false,
+    ))
+}
+
+fn parse_arrow(arrow: &str) -> Option<(RelationshipType, MarkerAt)> {
+    let parsed = match arrow {
+        "<|--" => (RelationshipType::Inheritance, MarkerAt::From),
+        "<|.." => (RelationshipType::Realization, MarkerAt::From),
+        "*--" => (RelationshipType::Composition, MarkerAt::From),
+        "--*" => (RelationshipType::Composition, MarkerAt::To),
+        "o--" => (RelationshipType::Aggregation, MarkerAt::From),
+        "--o" => (RelationshipType::Aggregation, MarkerAt::To),
+        "-->" | "--" => (RelationshipType::Association, MarkerAt::To),
+        "..>" => (RelationshipType::Dependency, MarkerAt::To),
+        "..|>" => (RelationshipType::Realization, MarkerAt::To),
+        _ => return None,
+    };
+    Some(parsed)
+}
+
+fn parse_relationship(line: &str) -> Option<ClassRelationship> {
+    let caps = RELATIONSHIP_RE.captures(line)?;
+    let (relationship_type, marker_at) = parse_arrow(caps[3].trim())?;
+    let cardinality = |index: usize| {
+        caps.get(index)
+            .map(|m| m.as_str().to_string())
+            .filter(|text| !text.is_empty())
+    };
+    Some(ClassRelationship {
+        from: caps[1].to_string(),
+        to: caps[5].to_string(),
+        relationship_type,
+        marker_at,
+        label: caps
+            .get(6)
+            .map(|m| m.as_str().trim().to_string())
+            .filter(|label| !label.is_empty()),
+        from_cardinality: cardinality(2),
+        to_cardinality: cardinality(4),
+    })
+}
+
+/// 按渲染入口预处理后解析：ASCII 只按换行分行；SVG 与 bundle 的 `renderMermaid` 一样把 `;` 也当换行。
+pub(crate) fn parse_text(text: &str, semicolons: bool) -> ClassDiagram {
+    if !semicolons {
+        return parse(&significant_lines(text));
+    }
+    let text = strip_accessibility(text);
+    let lines: Vec<&str> = text
+        .split(['\n', ';'])
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("%%"))
+        .collect();
+    parse(&lines)
+}
+
+/// native 渲染器的入口：先过 [`check_syntax`]（与 `validate_mermaid` 同一个 parser），再建 AST。
+#[cfg(feature = "native-backend")]
+pub(crate) fn parse_checked(text: &str, semicolons: bool) -> Result<ClassDiagram> {
+    check_syntax(text)?;
+    Ok(parse_text(text, semicolons))
+}
+
+/// 解析 class diagram（`lines[0]` 是 header，会被跳过）。
+fn parse(lines: &[&str]) -> ClassDiagram {
+    let mut classes: Vec<ClassNode> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut relationships: Vec<ClassRelationship> = Vec::new();
+
+    let mut in_namespace = false;
+    let mut current_class: Option<usize> = None;
+
+    for line in lines.iter().skip(1).copied() {
+        if let Some(class_idx) = current_class {
+            if line == "}" {
+                current_class = None;
+                continue;
+            }
+            if let Some(caps) = ANNOTATION_RE.captures(line) {
+                classes[class_idx].annotation = Some(caps[1].to_string());
+                continue;
+            }
+            if let Some((member, is_method)) = parse_member(line) {
+                if is_method {
+                    classes[class_idx].methods.push(member);
+                } else {
+                    classes[class_idx].attributes.push(member);
+                }
+            }
+            continue;
+        }
+
+        if NAMESPACE_RE.is_match(line) {
+            in_namespace = true;
+            continue;
+        }
+        if line == "}" && in_namespace {
+            in_namespace = false;
+            continue;
+        }
+
+        if let Some(caps) = CLASS_BLOCK_RE.captures(line) {
+            let id = caps[1].to_string();
+            let generic = caps.get(2).map(|m| m.as_str().to_string());
+            let class = ensure_class(&mut classes, &mut index, &id);
+            if let Some(generic) = generic {
+                class.label = format!("{id}<{generic}>");
+            }
+            current_class = Some(index[&id]);
+            continue;
+        }
+
+        if let Some(caps) = CLASS_DECL_RE.captures(line) {
+            let id = caps[1].to_string();
+            let generic = caps.get(2).map(|m| m.as_str().to_string());
+            let class = ensure_class(&mut classes, &mut index, &id);
+            if let Some(generic) = generic {
+                class.label = format!("{id}<{generic}>");
+            }
+            continue;
+        }
+
+        if let Some(caps) = CLASS_INLINE_ANNOTATION_RE.captures(line) {
+            ensure_class(&mut classes, &mut index, &caps[1]).annotation = Some(caps[2].to_string());
+            continue;
+        }
+
+        // `Foo : +bar()` 形式的成员（冒号右侧不能是关系箭头）
+        if let Some(caps) = MEMBER_LINE_RE.captures(line)
+            && !RELATIONSHIP_ARROW_RE.is_match(&caps[2])
+        {
+            let member = parse_member(&caps[2]);
+            let class = ensure_class(&mut classes, &mut index, &caps[1]);
+            if let Some((member, is_method)) = member {
+                if is_method {
+                    class.methods.push(member);
+                } else {
+                    class.attributes.push(member);
+                }
+            }
+            continue;
+        }
+
+        if let Some(relationship) = parse_relationship(line) {
+            ensure_class(&mut classes, &mut index, &relationship.from);
+            ensure_class(&mut classes, &mut index, &relationship.to);
+            relationships.push(relationship);
+        }
+    }
+
+    ClassDiagram {
+        classes,
+        relationships,
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Layout
+// ----------------------------------------------------------------------------
+
+/// 同层 class box 之间的最小水平间距。
+const MIN_H_GAP: i32 = 4;
+/// 层与层之间的垂直间距。
+const V_GAP: i32 = 3;
+
+/// class box 的分区：header（注解 + 名称）/ 属性 / 方法。
+///
+/// 与 JS 一致：没有成员时只有 header；只有属性时没有方法区。
+pub(crate) fn class_sections(class: &ClassNode) -> Vec<Vec<String>> {
+    let mut header = Vec::new();
+    if let Some(annotation) = &class.annotation {
+        header.push(format!("<<{annotation}>>"));
+    }
+    header.push(class.label.clone());
+
+    let attributes: Vec<String> = class
+        .attributes
+        .iter()
+        .map(ClassMember::display_text)
+        .collect();
+    let methods: Vec<String> = class
+        .methods
+        .iter()
+        .map(ClassMember::display_text)
+        .collect();
+
+    if attributes.is_empty() && methods.is_empty() {
+        vec![header]
+    } else if methods.is_empty() {
+        vec![header, attributes]
+    } else {
+        vec![header, attributes, methods]
+    }
+}
+
+/// 分区 box 的尺寸（与 JS `drawMultiBox` 一致：左右各 1 格 padding）。
+pub(crate) fn sections_size(sections: &[Vec<String>]) -> (i32, i32) {
+    let max_text = sections
+        .iter()
+        .flatten()
+        .map(|line| display_width(line))
+        .max()
+        .unwrap_or(0);
+    let rows: i32 = sections.iter().map(|s| s.len().max(1) as i32).sum();
+    (max_text + 4, rows + sections.len() as i32 - 1 + 2)
+}
+
+/// 分区 box 内每一行文本的 (分区下标, 行下标, 相对 box 顶边的行号)。
+pub(crate) fn section_rows(sections: &[Vec<String>]) -> Vec<(usize, usize, i32)> {
+    let mut out = Vec::new();
+    let mut row = 1;
+    for (section_idx, section) in sections.iter().enumerate() {
+        if section.is_empty() {
+            row += 1;
+        } else {
+            for line_idx in 0..section.len() {
+                out.push((section_idx, line_idx, row));
+                row += 1;
+            }
+        }
+        // 分区之间的分隔线
+        row += 1;
+    }
+    out
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ClassBox {
+    pub sections: Vec<Vec<String>>,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl ClassBox {
+    fn center_x(&self) -> i32 {
+        self.x + self.width / 2
+    }
+
+    fn bottom(&self) -> i32 {
+        self.y + self.height - 1
+    }
+}
+
+/// relationship 的折线几何。
+#[derive(Debug, Clone)]
+pub(crate) struct RelationshipRoute {
+    /// 有序坐标（从 from box 一侧到 to box 一侧）。
+    pub path: Vec<AsciiDrawingCoord>,
+    /// from 端与 to 端 marker 的格子与方向。
+    pub from_marker: (AsciiDrawingCoord, MarkerDirection),
+    pub to_marker: (AsciiDrawingCoord, MarkerDirection),
+    /// label 文本（含左右空格）的起点。
+    pub label_origin: AsciiDrawingCoord,
+}
+
+/// marker 的朝向（决定三角/箭头字符）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MarkerDirection {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ClassLayout {
+    /// class 下标 -> box。
+    pub boxes: Vec<ClassBox>,
+    /// relationship 下标 -> 路由（端点 class 不存在时为 None）。
+    pub routes: Vec<Option<RelationshipRoute>>,
+    /// 画布高度（行数；label 超出时 JS 不画）。
+    pub height: i32,
+}
+
+/// 计算 ASCII 布局（调用方需保证 `diagram.classes` 非空）。
+pub(crate) fn layout(diagram: &ClassDiagram) -> ClassLayout {
+    let class_index: HashMap<&str, usize> = diagram
+        .classes
+        .iter()
+        .enumerate()
+        .map(|(idx, class)| (class.id.as_str(), idx))
+        .collect();
+
+    let max_label_width = diagram
+        .relationships
+        .iter()
+        .filter_map(|rel| rel.label.as_ref())
+        .map(|label| display_width(&format!(" {label} ")))
+        .max()
+        .unwrap_or(0);
+    let h_gap = MIN_H_GAP.max((max_label_width + 1) / 2 + 1);
+
+    // ------------------------------------------------------------------
+    // 1) 分层：继承/实现关系里父类在上，其他关系 from 在上
+    // ------------------------------------------------------------------
+    let mut has_parent = vec![false; diagram.classes.len()];
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); diagram.classes.len()];
+    for rel in &diagram.relationships {
+        let (Some(&from), Some(&to)) = (
+            class_index.get(rel.from.as_str()),
+            class_index.get(rel.to.as_str()),
+        ) else {
+            continue;
+        };
+        let flipped = rel.relationship_type.is_hierarchical() && rel.marker_at == MarkerAt::To;
+        let (parent, child) = if flipped { (to, from) } else { (from, to) };
+        has_parent[child] = true;
+        if !children[parent].contains(&child) {
+            children[parent].push(child);
+        }
+    }
+
+    let mut level: Vec<Option<usize>> = vec![None; diagram.classes.len()];
+    let mut queue: Vec<usize> = (0..diagram.classes.len())
+        .filter(|&idx| !has_parent[idx])
+        .collect();
+    for &root in &queue {
+        level[root] = Some(0);
+    }
+    let max_level = diagram.classes.len() - 1;
+    let mut head = 0;
+    while head < queue.len() {
+        let current = queue[head];
+        head += 1;
+        for &child in &children[current] {
+            let next_level = level[current].unwrap_or(0) + 1;
+            if next_level > max_level {
+                continue;
+            }
+            if level[child].is_none_or(|existing| existing < next_level) {
+                level[child] = Some(next_level);
+                queue.push(child);
+            }
+        }
+    }
+    let level: Vec<usize> = level.into_iter().map(|l| l.unwrap_or(0)).collect();
+
+    // ------------------------------------------------------------------
+    // 2) 每层从左到右平铺
+    // ------------------------------------------------------------------
+    let mut boxes: Vec<ClassBox> = diagram
+        .classes
+        .iter()
+        .map(|class| {
+            let sections = class_sections(class);
+            let (width, height) = sections_size(&sections);
+            ClassBox {
+                sections,
+                x: 0,
+                y: 0,
+                width,
+                height,
+            }
+        })
+        .collect();
+
+    let top_level = level.iter().copied().max().unwrap_or(0);
+    let mut y = 0;
+    for current_level in 0..=top_level {
+        let members: Vec<usize> = (0..boxes.len())
+            .filter(|&idx| level[idx] == current_level)
+            .collect();
+        if members.is_empty() {
+            continue;
+        }
+        let mut x = 0;
+        let mut row_height = 0;
+        for idx in members {
+            boxes[idx].x = x;
+            boxes[idx].y = y;
+            x += boxes[idx].width + h_gap;
+            row_height = row_height.max(boxes[idx].height);
+        }
+        y += row_height + V_GAP;
+    }
+
+    // label 可能向左越界：整体右移
+    let mut min_label_x = 0;
+    for rel in &diagram.relationships {
+        let Some(label) = &rel.label else {
+            continue;
+        };
+        let (Some(&from), Some(&to)) = (
+            class_index.get(rel.from.as_str()),
+            class_index.get(rel.to.as_str()),
+        ) else {
+            continue;
+        };
+        let mid = (boxes[from].center_x() + boxes[to].center_x()) / 2;
+        min_label_x = min_label_x.min(mid - display_width(&format!(" {label} ")) / 2);
+    }
+    if min_label_x < 0 {
+        for class_box in &mut boxes {
+            class_box.x -= min_label_x;
+        }
+    }
+
+    let height = boxes.iter().map(|b| b.y + b.height).max().unwrap_or(0) + 2;
+
+    // ------------------------------------------------------------------
+    // 3) relationship 折线：竖 -> 横 -> 竖
+    // ------------------------------------------------------------------
+    let routes = diagram
+        .relationships
+        .iter()
+        .map(|rel| {
+            let from = *class_index.get(rel.from.as_str())?;
+            let to = *class_index.get(rel.to.as_str())?;
+            Some(route(&boxes[from], &boxes[to], rel))
+        })
+        .collect();
+
+    ClassLayout {
+        boxes,
+        routes,
+        height,
+    }
+}
+
+fn push_horizontal(path: &mut Vec<AsciiDrawingCoord>, from_x: i32, to_x: i32, y: i32) {
+    if from_x < to_x {
+        path.extend((from_x + 1..=to_x).map(|x| AsciiDrawingCoord { x, y }));
+    } else {
+        path.extend((to_x..from_x).rev().map(|x| AsciiDrawingCoord { x, y }));
+    }
+}
+
+fn route(from: &ClassBox, to: &ClassBox, rel: &ClassRelationship) -> RelationshipRoute {
+    let from_x = from.center_x();
+    let from_bottom = from.bottom();
+    let to_x = to.center_x();
+    let to_top = to.y;
+    let label_width = rel
+        .label
+        .as_ref()
+        .map(|label| display_width(&format!(" {label} ")))
+        .unwrap_or(0);
+    let label_x = ((from_x + to_x) / 2 - label_width / 2).max(0);
+
+    let mut path = Vec::new();
+    if from_bottom < to_top {
+        // to 在下方：从 from 底边向下，到中线拐弯，再下到 to 顶边
+        let elbow = from_bottom + (to_top - from_bottom) / 2;
+        path.extend((from_bottom + 1..=elbow).map(|y| AsciiDrawingCoord { x: from_x, y }));
+        push_horizontal(&mut path, from_x, to_x, elbow);
+        path.extend((elbow + 1..to_top).map(|y| AsciiDrawingCoord { x: to_x, y }));
+        RelationshipRoute {
+            path,
+            from_marker: (
+                AsciiDrawingCoord {
+                    x: from_x,
+                    y: from_bottom + 1,
+                },
+                MarkerDirection::Down,
+            ),
+            to_marker: (
+                AsciiDrawingCoord {
+                    x: to_x,
+                    y: to_top - 1,
+                },
+                MarkerDirection::Down,
+            ),
+            label_origin: AsciiDrawingCoord {
+                x: label_x,
+                y: (from_bottom + to_top) / 2,
+            },
+        }
+    } else if to.bottom() < from.y {
+        // to 在上方：从 from 顶边向上，到中线拐弯，再上到 to 底边
+        let from_top = from.y;
+        let to_bottom = to.bottom();
+        let elbow = to_bottom + (from_top - to_bottom) / 2;
+        path.extend(
+            (elbow..from_top)
+                .rev()
+                .map(|y| AsciiDrawingCoord { x: from_x, y }),
+        );
+        push_horizontal(&mut path, from_x, to_x, elbow);
+        path.extend(
+            (to_bottom + 1..elbow)
+                .rev()
+                .map(|y| AsciiDrawingCoord { x: to_x, y }),
+        );
+        let to_direction = if rel.relationship_type.is_hierarchical() {
+            MarkerDirection::Down
+        } else {
+            MarkerDirection::Up
+        };
+        RelationshipRoute {
+            path,
+            from_marker: (
+                AsciiDrawingCoord {
+                    x: from_x,
+                    y: from_top - 1,
+                },
+                MarkerDirection::Up,
+            ),
+            to_marker: (
+                AsciiDrawingCoord {
+                    x: to_x,
+                    y: to_bottom + 1,
+                },
+                to_direction,
+            ),
+            label_origin: AsciiDrawingCoord {
+                x: label_x,
+                y: (to_bottom + from_top) / 2,
+            },
+        }
+    } else {
+        // 同层：从两个 box 的下方绕过去
+        let to_bottom = to.bottom();
+        let elbow = from_bottom.max(to_bottom) + 2;
+        path.extend((from_bottom + 1..=elbow).map(|y| AsciiDrawingCoord { x: from_x, y }));
+        push_horizontal(&mut path, from_x, to_x, elbow);
+        path.extend(
+            (to_bottom + 1..elbow)
+                .rev()
+                .map(|y| AsciiDrawingCoord { x: to_x, y }),
+        );
+        RelationshipRoute {
+            path,
+            from_marker: (
+                AsciiDrawingCoord {
+                    x: from_x,
+                    y: from_bottom + 1,
+                },
+                MarkerDirection::Down,
+            ),
+            to_marker: (
+                AsciiDrawingCoord {
+                    x: to_x,
+                    y: to_bottom + 1,
+                },
+                MarkerDirection::Up,
+            ),
+            label_origin: AsciiDrawingCoord {
+                x: label_x,
+                y: elbow,
+            },
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Meta
+// ----------------------------------------------------------------------------
+
+/// 计算 class diagram 的 ASCII meta（与 JS `renderMermaidAscii` 的输出逐格对齐）。
+pub(crate) fn ascii_meta(text: &str) -> AsciiClassMeta {
+    let diagram = parse_text(text, false);
+    if diagram.classes.is_empty() {
+        return AsciiClassMeta {
+            classes: Vec::new(),
+            relationships: Vec::new(),
+            backend: Backend::Native,
+        };
+    }
+
+    let layout = layout(&diagram);
+
+    let classes = diagram
+        .classes
+        .iter()
+        .zip(&layout.boxes)
+        .map(|(class, class_box)| {
+            let mut attributes = Vec::new();
+            let mut methods = Vec::new();
+            for (section_idx, line_idx, row) in section_rows(&class_box.sections) {
+                let text = &class_box.sections[section_idx][line_idx];
+                let member_row = AsciiMemberRow {
+                    text: text.clone(),
+                    box_rect: AsciiBox {
+                        x: class_box.x + 2,
+                        y: class_box.y + row,
+                        width: display_width(text),
+                        height: 1,
+                    },
+                };
+                match section_idx {
+                    1 => attributes.push(member_row),
+                    2 => methods.push(member_row),
+                    _ => {}
+                }
+            }
+
+            AsciiClassNode {
+                id: class.id.clone(),
+                label: class.label.clone(),
+                annotation: class.annotation.clone(),
+                box_rect: AsciiBox {
+                    x: class_box.x,
+                    y: class_box.y,
+                    width: class_box.width,
+                    height: class_box.height,
+                },
+                attributes,
+                methods,
+            }
+        })
+        .collect();
+
+    let relationships = diagram
+        .relationships
+        .iter()
+        .zip(&layout.routes)
+        .filter_map(|(rel, route)| {
+            let route = route.as_ref()?;
+            let marker = match rel.marker_at {
+                MarkerAt::From => route.from_marker.0,
+                MarkerAt::To => route.to_marker.0,
+            };
+            Some(AsciiClassRelationship {
+                from: rel.from.clone(),
+                to: rel.to.clone(),
+                relationship_type: rel.relationship_type.as_str().to_string(),
+                label: rel.label.clone(),
+                label_box: rel
+                    .label
+                    .as_ref()
+                    .filter(|_| route.label_origin.y < layout.height)
+                    .map(|label| AsciiBox {
+                        x: route.label_origin.x,
+                        y: route.label_origin.y,
+                        width: display_width(&format!(" {label} ")),
+                        height: 1,
+                    }),
+                path: route.path.clone(),
+                marker: Some(marker),
+            })
+        })
+        .collect();
+
+    AsciiClassMeta {
+        classes,
+        relationships,
+        backend: Backend::Native,
+    }
+}
@@ -0,0 +1,363 @@
+// ============================================================================
+// Class diagram：纯 Rust ASCII/Unicode 渲染（`Backend::Native`）
+//
+// 对齐 JS bundle 的 `renderClassAscii`：
+// - box 坐标来自 `class::layout`（与 ASCII meta 共用同一份布局）；
+// - 这里按 JS 的顺序往画布上写字符：先画所有 class box，再逐条画 relationship
+//   （竖线 -> 横线 -> 竖线 -> 两端 marker -> label），后画的覆盖先画的；
+// - 越界裁剪沿用 JS 的判断：写入前与“初始画布尺寸”比较，而不是扩容后的尺寸。
+// ============================================================================
+
+use super::canvas::Canvas;
+use super::class::{self, ClassBox, ClassRelationship, MarkerAt, RelationshipType};
+use super::text::display_width;
+use crate::error::Result;
+use crate::types::AsciiRenderOptions;
+
+/// 渲染 class diagram 为字符画（没有 class 时返回空串，与 JS 一致）。
+pub(crate) fn render(text: &str, options: &AsciiRenderOptions) -> Result<String> {
+    let diagram = class::parse_checked(text, false)?;
+    if diagram.classes.is_empty() {
+        return Ok(String::new());
+    }
+
+    let use_ascii = options.use_ascii.unwrap_or(false);
+    let layout = class::layout(&diagram);
+    let width = layout
+        .boxes
+        .iter()
+        .map(|b| b.x + b.width)
+        .max()
+        .unwrap_or(0)
+        + 4;
+    let height = layout.height;
+    let mut canvas = Canvas::new(width - 1, height - 1);
+
+    for class_box in &layout.boxes {
+        draw_section_box(
+            &mut canvas,
+            class_box.x,
+            class_box.y,
+            &class_box.sections,
+            use_ascii,
+            (width, height),
+        );
+    }
+
+    let index: std::collections::HashMap<&str, usize> = diagram
+        .classes
+        .iter()
+        .enumerate()
+        .map(|(idx, class)| (class.id.as_str(), idx))
+        .collect();
+    for rel in &diagram.relationships {
+        let (Some(&from), Some(&to)) = (index.get(rel.from.as_str()), index.get(rel.to.as_str()))
+        else {
+            continue;
+        };
+        draw_relationship(
+            &mut canvas,
+            &layout.boxes[from],
+            &layout.boxes[to],
+            rel,
+            use_ascii,
+            (width, height),
+        );
+    }
+
+    Ok(canvas.to_text())
+}
+
+/// 多分区 box（对齐 JS `drawMultiBox`，左右各 1 格 padding）：
+/// 先画在独立的小画布上，再把非空格字符拷到 `(x, y)`，超出 `clip`（宽, 高）的部分丢弃。
+pub(crate) fn draw_section_box(
+    canvas: &mut Canvas,
+    x: i32,
+    y: i32,
+    sections: &[Vec<String>],
+    use_ascii: bool,
+    clip: (i32, i32),
+) {
+    let (horizontal, vertical) = if use_ascii {
+        ('-', '|')
+    } else {
+        ('─', '│')
+    };
+    let [
+        top_left,
+        top_right,
+        bottom_left,
+        bottom_right,
+        tee_right,
+        tee_left,
+    ] = if use_ascii {
+        ['+'; 6]
+    } else {
+        ['┌', '┐', '└', '┘', '├', '┤']
+    };
+    let (width, height) = class::sections_size(sections);
+
+    let mut local = Canvas::new(width - 1, height - 1);
+    local.set(0, 0, top_left);
+    local.set(width - 1, 0, top_right);
+    local.set(0, height - 1, bottom_left);
+    local.set(width - 1, height - 1, bottom_right);
+    for col in 1..width - 1 {
+        local.set(col, 0, horizontal);
+        local.set(col, height - 1, horizontal);
+    }
+    for row in 1..height - 1 {
+        local.set(0, row, vertical);
+        local.set(width - 1, row, vertical);
+    }
+
+    let mut row = 1;
+    for (section_idx, section) in sections.iter().enumerate() {
+        if section.is_empty() {
+            row += 1;
+        }
+        for line in section {
+            local.draw_text(2, row, line);
+            row += 1;
+        }
+        if section_idx + 1 < sections.len() {
+            local.set(0, row, tee_right);
+            for col in 1..width - 1 {
+                local.set(col, row, horizontal);
+            }
+            local.set(width - 1, row, tee_left);
+            row += 1;
+        }
+    }
+
+    let (max_x, max_y) = local.max();
+    for dx in 0..=max_x {
+        for dy in 0..=max_y {
+            let ch = local.get(dx, dy);
+            if ch != ' ' && x + dx < clip.0 && y + dy < clip.1 {
+                canvas.set(x + dx, y + dy, ch);
+            }
+        }
+    }
+}
+
+/// 关系两端的 marker 字符（对齐 JS `getMarkerChars`）。
+fn marker(relationship_type: RelationshipType, use_ascii: bool, down: bool) -> &'static str {
+    match relationship_type {
+        RelationshipType::Inheritance | RelationshipType::Realization => match (down, use_ascii) {
+            (true, true) => "^",
+            (true, false) => "△",
+            (false, true) => "v",
+            (false, false) => "▽",
+        },
+        RelationshipType::Composition => {
+            if use_ascii {
+                "*"
+            } else {
+                "◆"
+            }
+        }
+        RelationshipType::Aggregation => {
+            if use_ascii {
+                "o"
+            } else {
+                "◇"
+            }
+        }
+        RelationshipType::Association | RelationshipType::Dependency => match (down, use_ascii) {
+            (true, true) => "v",
+            (true, false) => "▼",
+            (false, true) => "^",
+            (false, false) => "▲",
+        },
+    }
+}
+
+/// marker 以 `center_x` 为中心写在第 `y` 行。
+fn draw_marker(canvas: &mut Canvas, text: &str, center_x: i32, y: i32, clip: (i32, i32)) {
+    if y < 0 || y >= clip.1 {
+        return;
+    }
+    let len = text.chars().count() as i32;
+    for (offset, ch) in text.chars().enumerate() {
+        let x = center_x - len / 2 + offset as i32;
+        if x >= 0 && x < clip.0 {
+            canvas.set(x, y, ch);
+        }
+    }
+}
+
+fn draw_relationship(
+    canvas: &mut Canvas,
+    from: &ClassBox,
+    to: &ClassBox,
+    rel: &ClassRelationship,
+    use_ascii: bool,
+    clip: (i32, i32),
+) {
+    let (width, height) = clip;
+    let dashed = matches!(
+        rel.relationship_type,
+        RelationshipType::Dependency | RelationshipType::Realization
+    );
+    let horizontal = match (dashed, use_ascii) {
+        (true, true) => '.',
+        (true, false) => '╌',
+        (false, true) => '-',
+        (false, false) => '─',
+    };
+    let vertical = match (dashed, use_ascii) {
+        (true, true) => ':',
+        (true, false) => '┊',
+        (false, true) => '|',
+        (false, false) => '│',
+    };
+    let rel_type = rel.relationship_type;
+
+    let from_x = from.x + from.width / 2;
+    let from_bottom = from.y + from.height - 1;
+    let to_x = to.x + to.width / 2;
+    let to_top = to.y;
+    let to_bottom = to.y + to.height - 1;
+    let (left, right) = (from_x.min(to_x), from_x.max(to_x));
+
+    if from_bottom < to_top {
+        // to 在下方
+        let elbow = from_bottom + (to_top - from_bottom) / 2;
+        for y in from_bottom + 1..=elbow {
+            if y < height {
+                canvas.set(from_x, y, vertical);
+            }
+        }
+        if from_x != to_x {
+            for x in left..=right {
+                if x < width && elbow < height {
+                    canvas.set(x, elbow, horizontal);
+                }
+            }
+            if !use_ascii && elbow < height {
+                let (a, b) = if from_x < to_x {
+                    ('└', '┐')
+                } else {
+                    ('┘', '┌')
+                };
+                canvas.set(from_x, elbow, a);
+                canvas.set(to_x, elbow, b);
+            }
+        }
+        for y in elbow + 1..to_top {
+            if y < height {
+                canvas.set(to_x, y, vertical);
+            }
+        }
+        match rel.marker_at {
+            MarkerAt::To => draw_marker(
+                canvas,
+                marker(rel_type, use_ascii, true),
+                to_x,
+                to_top - 1,
+                clip,
+            ),
+            MarkerAt::From => draw_marker(
+                canvas,
+                marker(rel_type, use_ascii, true),
+                from_x,
+                from_bottom + 1,
+                clip,
+            ),
+        }
+    } else if to_bottom < from.y {
+        // to 在上方
+        let from_top = from.y;
+        let elbow = to_bottom + (from_top - to_bottom) / 2;
+        for y in (elbow..from_top).rev() {
+            if y >= 0 && y < height {
+                canvas.set(from_x, y, vertical);
+            }
+        }
+        if from_x != to_x {
+            for x in left..=right {
+                if x < width && elbow >= 0 && elbow < height {
+                    canvas.set(x, elbow, horizontal);
+                }
+            }
+            if !use_ascii && elbow >= 0 && elbow < height {
+                let (a, b) = if from_x < to_x {
+                    ('┌', '┘')
+                } else {
+                    ('┐', '└')
+                };
+                canvas.set(from_x, elbow, a);
+                canvas.set(to_x, elbow, b);
+            }
+        }
+        for y in (to_bottom + 1..elbow).rev() {
+            if y >= 0 && y < height {
+                canvas.set(to_x, y, vertical);
+            }
+        }
+        match rel.marker_at {
+            MarkerAt::From => draw_marker(
+                canvas,
+                marker(rel_type, use_ascii, false),
+                from_x,
+                from_top - 1,
+                clip,
+            ),
+            MarkerAt::To => {
+                let down = rel_type.is_hierarchical();
+                draw_marker(
+                    canvas,
+                    marker(rel_type, use_ascii, down),
+                    to_x,
+                    to_bottom + 1,
+                    clip,
+                )
+            }
+        }
+    } else {
+        // 同层：从两个 box 的下方绕过去（JS 在这里会先扩容画布）
+        let elbow = from_bottom.max(to_bottom) + 2;
+        canvas.grow(width, elbow + 1);
+        for y in from_bottom + 1..=elbow {
+            canvas.set(from_x, y, vertical);
+        }
+        for x in left..=right {
+            canvas.set(x, elbow, horizontal);
+        }
+        for y in (to.y + to.height..elbow).rev() {
+            canvas.set(to_x, y, vertical);
+        }
+        match rel.marker_at {
+            MarkerAt::From => draw_marker(
+                canvas,
+                marker(rel_type, use_ascii, true),
+                from_x,
+                from_bottom + 1,
+                clip,
+            ),
+            MarkerAt::To => draw_marker(
+                canvas,
+                marker(rel_type, use_ascii, false),
+                to_x,
+                to.y + to.height,
+                clip,
+            ),
+        }
+    }
+
+    if let Some(label) = &rel.label {
+        let text = format!(" {label} ");
+        let y = if from_bottom < to_top {
+            (from_bottom + 1 + to_top - 1) / 2
+        } else if to_bottom < from.y {
+            (to_bottom + 1 + from.y - 1) / 2
+        } else {
+            from_bottom.max(to_bottom) + 2
+        };
+        let x = (from_x + to_x) / 2 - display_width(&text) / 2;
+        if y >= 0 && y < height {
+            canvas.draw_text(x.max(0), y, &text);
+        }
+    }
+}
@@ -0,0 +1,558 @@
+// ============================================================================
+// Class diagram：纯 Rust SVG 渲染（`Backend::Native`）
+//
+// 对齐 JS bundle：
+// - 尺寸: `layoutClassDiagram` 的 box 规则（标题栏 + 属性区 + 方法区，成员行按等宽字体估宽）
+// - 布局: bundle 用 dagre（TB）；这里复用 flowchart 的分层布局，box 尺寸由上面的规则给定
+// - 绘制: `renderClassSvg`（relationship -> class box -> label/多重性）
+//
+// 说明：
+// - SVG 结构、marker、元素 id、CSS 变量与 JS 后端一致；坐标与 flowchart 一样不保证与 dagre 逐像素一致；
+// - 同一对 class 之间的多条关系只画一条（对齐 dagre 非 multigraph：位置取第一条、内容取最后一条）；
+// - 布局结果直接构造成 `svg_meta` 的 `JsClass*` 结构，meta 与 JS 后端共用同一个转换。
+// ============================================================================
+
+use super::class::{self, ClassDiagram, ClassMember, ClassRelationship, MarkerAt};
+use super::flowchart::{Direction, EdgeStyle, FlowEdge, FlowchartGraph};
+use super::flowchart_svg::layout::{self, LayoutOptions};
+use super::svg::{
+    CONNECTOR_STROKE, EDGE_LABEL_FONT_SIZE, EDGE_LABEL_FONT_WEIGHT, INNER_BOX_STROKE,
+    NODE_LABEL_FONT_SIZE, NODE_LABEL_FONT_WEIGHT, OUTER_BOX_STROKE, TEXT_DY, count, edge_attrs,
+    element_group, escape_xml, js_num, list, node_attrs, style_block, svg_open_tag, text_width,
+    with_accessibility,
+};
+use crate::error::Result;
+use crate::native;
+use crate::svg_meta::{
+    self, ElementIds, JsClassLayout, JsClassNode, JsClassRelationship, polyline_midpoint, rect,
+};
+use crate::types::{RenderOptions, SvgDiagramMeta, SvgPoint, SvgRenderWithMeta};
+use std::collections::HashMap;
+
+/// 布局常量（对齐 JS `layoutClassDiagram` 用的常量）。
+const PADDING: f64 = 40.0;
+const BOX_PAD_X: f64 = 8.0;
+const HEADER_BASE_HEIGHT: f64 = 32.0;
+const ANNOTATION_HEIGHT: f64 = 16.0;
+const MEMBER_ROW_HEIGHT: f64 = 20.0;
+const SECTION_PAD_Y: f64 = 8.0;
+const EMPTY_SECTION_HEIGHT: f64 = 8.0;
+const MIN_WIDTH: f64 = 120.0;
+const MEMBER_FONT_SIZE: f64 = 11.0;
+const NODE_SPACING: f64 = 40.0;
+const LAYER_SPACING: f64 = 60.0;
+/// 成员行 / annotation 的字号字重（对齐 JS `renderClassSvg`）。
+const MEMBER_FONT_WEIGHT: u32 = 400;
+const ANNOTATION_FONT_SIZE: f64 = 10.0;
+const ANNOTATION_FONT_WEIGHT: u32 = 500;
+
+/// 布局结果 + 画布尺寸；`sources[i]` 是第 i 条画出来的关系在 AST 里的下标。
+struct Layout {
+    width: f64,
+    height: f64,
+    inner: JsClassLayout,
+    sources: Vec<usize>,
+}
+
+/// 渲染 class diagram 为 SVG。
+pub(crate) fn render(text: &str, options: &RenderOptions) -> Result<String> {
+    Ok(run(text, options)?.0)
+}
+
+/// 渲染 class diagram 为 SVG + 像素坐标 meta。
+pub(crate) fn render_with_meta(text: &str, options: &RenderOptions) -> Result<SvgRenderWithMeta> {
+    let (svg, layout, prefix) = run(text, options)?;
+    let view_box = rect(0.0, 0.0, layout.width, layout.height);
+    let meta = svg_meta::convert_class(layout.inner, &mut ElementIds::new(&prefix));
+    Ok(SvgRenderWithMeta {
+        svg,
+        view_box,
+        meta: SvgDiagramMeta::Class(meta),
+    })
+}
+
+fn run(text: &str, options: &RenderOptions) -> Result<(String, Layout, String)> {
+    // SVG 入口与 ASCII 不同：`;` 也算换行（对齐 bundle 的 `renderMermaid`）
+    let diagram = class::parse_checked(text, true)?;
+    let accessibility = native::parse_accessibility(text);
+    let layout = layout(&diagram);
+
+    let prefix = escape_xml(options.id_prefix.as_deref().unwrap_or_default());
+    let title = options.title.clone().or(accessibility.title);
+    let description = options
+        .description
+        .clone()
+        .or(accessibility.description)
+        .unwrap_or_else(|| describe(&layout.inner));
+
+    let body = render_body(&layout, &diagram, options, &prefix);
+    let svg = with_accessibility(&body, "class", title.as_deref(), &description, &prefix);
+    Ok((svg, layout, prefix))
+}
+
+// ----------------------------------------------------------------------------
+// 布局
+// ----------------------------------------------------------------------------
+
+/// class box 的尺寸：`(width, height, header, attributes, methods)`（对齐 JS `layoutClassDiagram`）。
+fn box_size(class: &class::ClassNode) -> (f64, f64, f64, f64, f64) {
+    let header = if class.annotation.is_some() {
+        HEADER_BASE_HEIGHT + ANNOTATION_HEIGHT
+    } else {
+        HEADER_BASE_HEIGHT
+    };
+    let section = |members: &[ClassMember]| {
+        if members.is_empty() {
+            EMPTY_SECTION_HEIGHT
+        } else {
+            members.len() as f64 * MEMBER_ROW_HEIGHT + SECTION_PAD_Y
+        }
+    };
+    let (attributes, methods) = (section(&class.attributes), section(&class.methods));
+    let width = MIN_WIDTH
+        .max(
+            text_width(&class.label, NODE_LABEL_FONT_SIZE, NODE_LABEL_FONT_WEIGHT)
+                + BOX_PAD_X * 2.0,
+        )
+        .max(members_width(&class.attributes) + BOX_PAD_X * 2.0)
+        .max(members_width(&class.methods) + BOX_PAD_X * 2.0);
+    (
+        width,
+        header + attributes + methods,
+        header,
+        attributes,
+        methods,
+    )
+}
+
+/// 成员行里最宽的一行（等宽字体：UTF-16 长度 × 字号 × 0.6）。
+fn members_width(members: &[ClassMember]) -> f64 {
+    members
+        .iter()
+        .map(|member| member_text(member).encode_utf16().count() as f64 * MEMBER_FONT_SIZE * 0.6)
+        .fold(0.0, f64::max)
+}
+
+/// SVG 成员行的文本（可见性后面带空格，与 ASCII 的 `display_text` 不同）。
+fn member_text(member: &ClassMember) -> String {
+    let visibility = if member.visibility.is_empty() {
+        String::new()
+    } else {
+        format!("{} ", member.visibility)
+    };
+    let member_type = member
+        .member_type
+        .as_deref()
+        .map(|member_type| format!(": {member_type}"))
+        .unwrap_or_default();
+    format!("{visibility}{}{member_type}", member.name)
+}
+
+fn layout(diagram: &ClassDiagram) -> Layout {
+    if diagram.classes.is_empty() {
+        return Layout {
+            width: 0.0,
+            height: 0.0,
+            inner: JsClassLayout {
+                classes: Vec::new(),
+                relationships: Vec::new(),
+            },
+            sources: Vec::new(),
+        };
+    }
+
+    let sizes: Vec<_> = diagram.classes.iter().map(box_size).collect();
+    let graph = FlowchartGraph::boxes(
+        Direction::Tb,
+        diagram.classes.iter().map(|class| class.id.as_str()),
+        diagram
+            .relationships
+            .iter()
+            .map(|rel| FlowEdge {
+                source: rel.from.clone(),
+                target: rel.to.clone(),
+                label: rel.label.clone(),
+                style: EdgeStyle::Solid,
+                has_arrow_start: false,
+                has_arrow_end: false,
+            })
+            .collect(),
+    );
+    let placed = layout::layout(
+        &graph,
+        &LayoutOptions {
+            padding: PADDING,
+            node_spacing: NODE_SPACING,
+            layer_spacing: LAYER_SPACING,
+            node_sizes: diagram
+                .classes
+                .iter()
+                .zip(&sizes)
+                .map(|(class, size)| (class.id.clone(), (size.0, size.1)))
+                .collect(),
+        },
+    );
+
+    let placed_box: HashMap<&str, (f64, f64, f64, f64)> = placed
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), (node.x, node.y, node.width, node.height)))
+        .collect();
+    let classes = diagram
+        .classes
+        .iter()
+        .zip(&sizes)
+        .map(|(class, &(width, height, header, attributes, methods))| {
+            let (x, y, width, height) = placed_box
+                .get(class.id.as_str())
+                .copied()
+                .unwrap_or((0.0, 0.0, width, height));
+            JsClassNode {
+                id: class.id.clone(),
+                label: class.label.clone(),
+                annotation: class.annotation.clone(),
+                x,
+                y,
+                width,
+                height,
+                header_height: header,
+                attr_height: attributes,
+                method_height: methods,
+            }
+        })
+        .collect();
+
+    // 布局按端点去重：每条画出来的边对应同一对端点的最后一条关系
+    let mut sources = Vec::with_capacity(placed.edges.len());
+    let relationships = placed
+        .edges
+        .into_iter()
+        .filter_map(|edge| {
+            let index = diagram
+                .relationships
+                .iter()
+                .rposition(|rel| rel.from == edge.source && rel.to == edge.target)?;
+            sources.push(index);
+            let rel = &diagram.relationships[index];
+            Some(JsClassRelationship {
+                from: rel.from.clone(),
+                to: rel.to.clone(),
+                relationship_type: rel.relationship_type.as_str().to_string(),
+                marker_at: marker_at(rel).to_string(),
+                label: rel.label.clone(),
+                points: edge.points,
+                label_position: rel.label.as_ref().and(edge.label_position),
+            })
+        })
+        .collect();
+
+    Layout {
+        width: placed.width,
+        height: placed.height,
+        inner: JsClassLayout {
+            classes,
+            relationships,
+        },
+        sources,
+    }
+}
+
+fn marker_at(rel: &ClassRelationship) -> &'static str {
+    match rel.marker_at {
+        MarkerAt::From => "from",
+        MarkerAt::To => "to",
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 绘制
+// ----------------------------------------------------------------------------
+
+fn render_body(
+    layout: &Layout,
+    diagram: &ClassDiagram,
+    options: &RenderOptions,
+    prefix: &str,
+) -> String {
+    let inner = &layout.inner;
+    let mut parts = vec![
+        svg_open_tag(layout.width, layout.height, options),
+        style_block(options, true),
+        "<defs>".to_string(),
+        markers(prefix),
+        "</defs>".to_string(),
+    ];
+    let mut ids = ElementIds::new(prefix);
+    let edge_ids: Vec<String> = inner
+        .relationships
+        .iter()
+        .map(|rel| ids.edge(&rel.from, &rel.to))
+        .collect();
+    for (rel, id) in inner.relationships.iter().zip(&edge_ids) {
+        parts.push(element_group(
+            &edge_attrs(id, &rel.from, &rel.to, "edge"),
+            &[relationship(rel, prefix)],
+        ));
+    }
+    for (node, class) in inner.classes.iter().zip(&diagram.classes) {
+        parts.push(element_group(
+            &node_attrs(&ids.node(&node.id), &node.id),
+            &[class_box(node, class)],
+        ));
+    }
+    for ((rel, id), &source) in inner
+        .relationships
+        .iter()
+        .zip(&edge_ids)
+        .zip(&layout.sources)
+    {
+        parts.push(element_group(
+            &edge_attrs(&format!("{id}-label"), &rel.from, &rel.to, "edge-label"),
+            &[relationship_labels(rel, &diagram.relationships[source])],
+        ));
+    }
+    parts.push("</svg>".to_string());
+    parts.join("\n")
+}
+
+/// 继承（空心三角）、组合（实心菱形）、聚合（空心菱形）、关联（开口箭头）。
+fn markers(prefix: &str) -> String {
+    format!(
+        "  <marker id=\"{prefix}cls-inherit\" markerWidth=\"12\" markerHeight=\"10\" refX=\"12\" refY=\"5\" orient=\"auto-start-reverse\">\n    \
+         <polygon points=\"0 0, 12 5, 0 10\" fill=\"var(--bg)\" stroke=\"var(--_arrow)\" stroke-width=\"1.5\" />\n  \
+         </marker>\n  \
+         <marker id=\"{prefix}cls-composition\" markerWidth=\"12\" markerHeight=\"10\" refX=\"0\" refY=\"5\" orient=\"auto-start-reverse\">\n    \
+         <polygon points=\"6 0, 12 5, 6 10, 0 5\" fill=\"var(--_arrow)\" stroke=\"var(--_arrow)\" stroke-width=\"1\" />\n  \
+         </marker>\n  \
+         <marker id=\"{prefix}cls-aggregation\" markerWidth=\"12\" markerHeight=\"10\" refX=\"0\" refY=\"5\" orient=\"auto-start-reverse\">\n    \
+         <polygon points=\"6 0, 12 5, 6 10, 0 5\" fill=\"var(--bg)\" stroke=\"var(--_arrow)\" stroke-width=\"1.5\" />\n  \
+         </marker>\n  \
+         <marker id=\"{prefix}cls-arrow\" markerWidth=\"8\" markerHeight=\"6\" refX=\"8\" refY=\"3\" orient=\"auto-start-reverse\">\n    \
+         <polyline points=\"0 0, 8 3, 0 6\" fill=\"none\" stroke=\"var(--_arrow)\" stroke-width=\"1.5\" />\n  \
+         </marker>"
+    )
+}
+
+/// class box：外框 + 标题栏（可选 annotation）+ 属性区 + 方法区。
+fn class_box(node: &JsClassNode, class: &class::ClassNode) -> String {
+    let JsClassNode {
+        x,
+        y,
+        width,
+        height,
+        header_height,
+        attr_height,
+        ..
+    } = *node;
+    let outer = js_num(OUTER_BOX_STROKE);
+    let inner = js_num(INNER_BOX_STROKE);
+    let center = js_num(x + width / 2.0);
+    let mut parts = vec![
+        format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"0\" ry=\"0\" fill=\"var(--_node-fill)\" stroke=\"var(--_node-stroke)\" stroke-width=\"{outer}\" />",
+            js_num(x),
+            js_num(y),
+            js_num(width),
+            js_num(height),
+        ),
+        format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"0\" ry=\"0\" fill=\"var(--_group-hdr)\" stroke=\"var(--_node-stroke)\" stroke-width=\"{outer}\" />",
+            js_num(x),
+            js_num(y),
+            js_num(width),
+            js_num(header_height),
+        ),
+    ];
+    let mut label_y = y + header_height / 2.0;
+    if let Some(annotation) = &node.annotation {
+        parts.push(format!(
+            "<text x=\"{center}\" y=\"{}\" text-anchor=\"middle\" dy=\"{TEXT_DY}\" font-size=\"{ANNOTATION_FONT_SIZE}\" font-weight=\"{ANNOTATION_FONT_WEIGHT}\" font-style=\"italic\" fill=\"var(--_text-muted)\">&lt;&lt;{}&gt;&gt;</text>",
+            js_num(y + 12.0),
+            escape_xml(annotation),
+        ));
+        label_y = y + header_height / 2.0 + 6.0;
+    }
+    parts.push(format!(
+        "<text x=\"{center}\" y=\"{}\" text-anchor=\"middle\" dy=\"{TEXT_DY}\" font-size=\"{NODE_LABEL_FONT_SIZE}\" font-weight=\"700\" fill=\"var(--_text)\">{}</text>",
+        js_num(label_y),
+        escape_xml(&node.label),
+    ));
+
+    let divider = |line_y: f64| {
+        format!(
+            "<line x1=\"{}\" y1=\"{ly}\" x2=\"{}\" y2=\"{ly}\" stroke=\"var(--_node-stroke)\" stroke-width=\"{inner}\" />",
+            js_num(x),
+            js_num(x + width),
+            ly = js_num(line_y),
+        )
+    };
+    let attributes_top = y + header_height;
+    parts.push(divider(attributes_top));
+    for (index, member) in class.attributes.iter().enumerate() {
+        let row_y =
+            attributes_top + 4.0 + index as f64 * MEMBER_ROW_HEIGHT + MEMBER_ROW_HEIGHT / 2.0;
+        parts.push(member_row(member, x + BOX_PAD_X, row_y));
+    }
+    let methods_top = attributes_top + attr_height;
+    parts.push(divider(methods_top));
+    for (index, member) in class.methods.iter().enumerate() {
+        let row_y = methods_top + 4.0 + index as f64 * MEMBER_ROW_HEIGHT + MEMBER_ROW_HEIGHT / 2.0;
+        parts.push(member_row(member, x + BOX_PAD_X, row_y));
+    }
+    parts.join("\n")
+}
+
+/// 成员行：可见性 / 名字 / 类型分段着色；abstract 斜体，static 下划线。
+fn member_row(member: &ClassMember, x: f64, y: f64) -> String {
+    let italic = if member.is_abstract {
+        " font-style=\"italic\""
+    } else {
+        ""
+    };
+    let underline = if member.is_static {
+        " text-decoration=\"underline\""
+    } else {
+        ""
+    };
+    let mut spans = Vec::new();
+    if !member.visibility.is_empty() {
+        spans.push(format!(
+            "<tspan fill=\"var(--_text-faint)\">{} </tspan>",
+            escape_xml(&member.visibility)
+        ));
+    }
+    spans.push(format!(
+        "<tspan fill=\"var(--_text-sec)\">{}</tspan>",
+        escape_xml(&member.name)
+    ));
+    if let Some(member_type) = &member.member_type {
+        spans.push("<tspan fill=\"var(--_text-faint)\">: </tspan>".to_string());
+        spans.push(format!(
+            "<tspan fill=\"var(--_text-muted)\">{}</tspan>",
+            escape_xml(member_type)
+        ));
+    }
+    format!(
+        "<text x=\"{}\" y=\"{}\" class=\"mono\" dy=\"{TEXT_DY}\" font-size=\"{MEMBER_FONT_SIZE}\" font-weight=\"{MEMBER_FONT_WEIGHT}\"{italic}{underline}>{}</text>",
+        js_num(x),
+        js_num(y),
+        spans.concat(),
+    )
+}
+
+/// 关系连线：dependency / realization 用虚线；marker 画在 `marker_at` 那一端。
+fn relationship(rel: &JsClassRelationship, prefix: &str) -> String {
+    if rel.points.len() < 2 {
+        return String::new();
+    }
+    let points: Vec<String> = rel
+        .points
+        .iter()
+        .map(|point| format!("{},{}", js_num(point.x), js_num(point.y)))
+        .collect();
+    let dash = if matches!(rel.relationship_type.as_str(), "dependency" | "realization") {
+        " stroke-dasharray=\"6 4\""
+    } else {
+        ""
+    };
+    let marker = match rel.relationship_type.as_str() {
+        "inheritance" | "realization" => "cls-inherit",
+        "composition" => "cls-composition",
+        "aggregation" => "cls-aggregation",
+        _ => "cls-arrow",
+    };
+    let end = if rel.marker_at == "from" {
+        "marker-start"
+    } else {
+        "marker-end"
+    };
+    format!(
+        "<polyline points=\"{}\" fill=\"none\" stroke=\"var(--_line)\" stroke-width=\"{}\"{dash} {end}=\"url(#{prefix}{marker})\" />",
+        points.join(" "),
+        js_num(CONNECTOR_STROKE),
+    )
+}
+
+/// 关系 label（线的上方）+ 两端的多重性。
+fn relationship_labels(rel: &JsClassRelationship, source: &ClassRelationship) -> String {
+    let points = &rel.points;
+    if points.len() < 2 {
+        return String::new();
+    }
+    let text = |x: f64, y: f64, content: &str| {
+        format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"{EDGE_LABEL_FONT_SIZE}\" font-weight=\"{EDGE_LABEL_FONT_WEIGHT}\" fill=\"var(--_text-muted)\">{}</text>",
+            js_num(x),
+            js_num(y),
+            escape_xml(content),
+        )
+    };
+    let mut parts = Vec::new();
+    if let Some(label) = &rel.label {
+        let position = rel
+            .label_position
+            .unwrap_or_else(|| polyline_midpoint(points));
+        parts.push(text(position.x, position.y - 8.0, label));
+    }
+    let ends = [
+        (&source.from_cardinality, points[0], points[1]),
+        (
+            &source.to_cardinality,
+            points[points.len() - 1],
+            points[points.len() - 2],
+        ),
+    ];
+    for (cardinality, end, next) in ends {
+        if let Some(cardinality) = cardinality {
+            let (dx, dy) = cardinality_offset(end, next);
+            parts.push(text(end.x + dx, end.y + dy, cardinality));
+        }
+    }
+    parts.join("\n")
+}
+
+/// 多重性文字相对端点的偏移：沿线方向让开，放在线的一侧。
+fn cardinality_offset(end: SvgPoint, next: SvgPoint) -> (f64, f64) {
+    let (dx, dy) = (next.x - end.x, next.y - end.y);
+    if dx.abs() > dy.abs() {
+        (if dx > 0.0 { 14.0 } else { -14.0 }, -10.0)
+    } else {
+        (-14.0, if dy > 0.0 { 14.0 } else { -14.0 })
+    }
+}
+
+/// 自动生成的 `<desc>`（对齐 JS `bmDescribe` 的 class 分支）。
+fn describe(layout: &JsClassLayout) -> String {
+    let label_of = |id: &str| -> String {
+        layout
+            .classes
+            .iter()
+            .find(|class| class.id == id)
+            .map_or_else(|| id.to_string(), |class| class.label.clone())
+    };
+    let classes: Vec<String> = layout.classes.iter().map(|c| c.label.clone()).collect();
+    let relationships: Vec<String> = layout
+        .relationships
+        .iter()
+        .map(|rel| {
+            let label = rel
+                .label
+                .as_deref()
+                .map(|label| format!(": {label}"))
+                .unwrap_or_default();
+            format!(
+                "{} → {} ({}{label})",
+                label_of(&rel.from),
+                label_of(&rel.to),
+                rel.relationship_type
+            )
+        })
+        .collect();
+    format!(
+        "Class diagram with {} and {}.{}{}",
+        count(layout.classes.len(), "class", "classes"),
+        count(layout.relationships.len(), "relationship", "relationships"),
+        list(&classes, "Classes"),
+        list(&relationships, "Relationships")
+    )
+}
@@ -0,0 +1,586 @@
+// ============================================================================
+// ER diagram：解析 + ASCII 布局
+//
+// 对齐 JS bundle：
+// - parser: `parseErDiagram`（entity 块、属性、relationship + cardinality）
+// - 布局:   `renderErAscii` 里的网格平铺 + 水平/垂直连线
+//
+// 说明：
+// - 这里只负责“坐标”，不负责画字符；坐标与 JS 输出的字符画逐格对齐。
+// ============================================================================
+
+#[cfg(feature = "native-backend")]
+use super::check_syntax;
+use super::class::{section_rows, sections_size};
+use super::text::{display_width, truncate_to_width};
+use super::{significant_lines, strip_accessibility};
+#[cfg(feature = "native-backend")]
+use crate::error::Result;
+use crate::types::{
+    AsciiBox, AsciiCardinalityMarker, AsciiDrawingCoord, AsciiErEntity, AsciiErMeta,
+    AsciiErRelationship, AsciiMemberRow, Backend,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+// ----------------------------------------------------------------------------
+// AST
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub(crate) struct ErAttribute {
+    pub attr_type: String,
+    pub name: String,
+    /// `PK` / `FK` / `UK`（大写，保持声明顺序）。
+    pub keys: Vec<String>,
+}
+
+impl ErAttribute {
+    /// ASCII 渲染时的单行文本（例如 `PK int id`；无 key 时前缀 3 个空格）。
+    pub(crate) fn display_text(&self) -> String {
+        let keys = if self.keys.is_empty() {
+            "   ".to_string()
+        } else {
+            format!("{} ", self.keys.join(","))
+        };
+        format!("{keys}{} {}", self.attr_type, self.name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ErEntity {
+    pub id: String,
+    pub label: String,
+    pub attributes: Vec<ErAttribute>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Cardinality {
+    One,
+    ZeroOne,
+    Many,
+    ZeroMany,
+}
+
+impl Cardinality {
+    /// 对齐 JS：把两个字符排序后再匹配（`|{` 与 `{|` 等价）。
+    fn parse(text: &str) -> Option<Self> {
+        let mut chars: Vec<char> = text.chars().collect();
+        chars.sort_unstable();
+        let sorted: String = chars.into_iter().collect();
+        match sorted.as_str() {
+            "||" => Some(Self::One),
+            "o|" => Some(Self::ZeroOne),
+            "|}" | "{|" => Some(Self::Many),
+            "o{" => Some(Self::ZeroMany),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::One => "one",
+            Self::ZeroOne => "zero-one",
+            Self::Many => "many",
+            Self::ZeroMany => "zero-many",
+        }
+    }
+
+    /// 线上画的 crow's foot 标记。
+    pub(crate) fn marker(self, use_ascii: bool) -> &'static str {
+        match (self, use_ascii) {
+            (Self::One, true) => "||",
+            (Self::ZeroOne, true) => "o|",
+            (Self::Many, true) => "}|",
+            (Self::ZeroMany, true) => "o{",
+            (Self::One, false) => "\u{2551}",
+            (Self::ZeroOne, false) => "o\u{2551}",
+            (Self::Many, false) => "\u{255F}",
+            (Self::ZeroMany, false) => "o\u{255F}",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ErRelationship {
+    pub entity1: String,
+    pub entity2: String,
+    pub cardinality1: Cardinality,
+    pub cardinality2: Cardinality,
+    pub label: String,
+    pub identifying: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ErDiagram {
+    pub entities: Vec<ErEntity>,
+    pub relationships: Vec<ErRelationship>,
+}
+
+// ----------------------------------------------------------------------------
+// Parser
+// ----------------------------------------------------------------------------
+
+static ENTITY_BLOCK_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\S+)\s*\{$").unwrap());
+static ATTRIBUTE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\S+)\s+(\S+)(?:\s+(.+))?$").unwrap());
+static ATTRIBUTE_COMMENT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#""[^"]*""#).unwrap());
+static RELATIONSHIP_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\S+)\s+([|o}{]+(?:--|\.\.)[|o}{]+)\s+(\S+)\s*:\s*(.+)$").unwrap());
+static CARDINALITY_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([|o}{]+)(--|\.\.?)([|o}{]+)$").unwrap());
+
+fn ensure_entity<'a>(
+    entities: &'a mut Vec<ErEntity>,
+    index: &mut HashMap<String, usize>,
+    id: &str,
+) -> &'a mut ErEntity {
+    let idx = *index.entry(id.to_string()).or_insert_with(|| {
+        entities.push(ErEntity {
+            id: id.to_string(),
+            label: id.to_string(),
+            attributes: Vec::new(),
+        });
+        entities.len() - 1
+    });
+    &mut entities[idx]
+}
+
+fn parse_attribute(line: &str) -> Option<ErAttribute> {
+    let caps = ATTRIBUTE_RE.captures(line)?;
+    let rest = caps.get(3).map(|m| m.as_str().trim()).unwrap_or_default();
+    // 注释（`"..."`）不参与 key 识别
+    let without_comment = ATTRIBUTE_COMMENT_RE.replace(rest, "");
+    let keys = without_comment
+        .split_whitespace()
+        .map(str::to_uppercase)
+        .filter(|key| matches!(key.as_str(), "PK" | "FK" | "UK"))
+        .collect();
+    Some(ErAttribute {
+        attr_type: caps[1].to_string(),
+        name: caps[2].to_string(),
+        keys,
+    })
+}
+
+fn parse_relationship(line: &str) -> Option<ErRelationship> {
+    let caps = RELATIONSHIP_RE.captures(line)?;
+    let arrow = CARDINALITY_RE.captures(&caps[2])?;
+    Some(ErRelationship {
+        entity1: caps[1].to_string(),
+        entity2: caps[3].to_string(),
+        cardinality1: Cardinality::parse(&arrow[1])?,
+        cardinality2: Cardinality::parse(&arrow[3])?,
+        label: caps[4].trim().to_string(),
+        identifying: &arrow[2] == "--",
+    })
+}
+
+/// 按渲染入口预处理后解析：ASCII 只按换行分行；SVG 与 bundle 的 `renderMermaid` 一样把 `;` 也当换行。
+pub(crate) fn parse_text(text: &str, semicolons: bool) -> ErDiagram {
+    if !semicolons {
+        return parse(&significant_lines(text));
+    }
+    let text = strip_accessibility(text);
+    let lines: Vec<&str> = text
+        .split(['\n', ';'])
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("%%"))
+        .collect();
+    parse(&lines)
+}
+
+/// native 渲染器的入口：先过 [`check_syntax`]（与 `validate_mermaid` 同一个 parser），再建 AST。
+#[cfg(feature = "native-backend")]
+pub(crate) fn parse_checked(text: &str, semicolons: bool) -> Result<ErDiagram> {
+    check_syntax(text)?;
+    Ok(parse_text(text, semicolons))
+}
+
+/// 解析 ER diagram（`lines[0]` 是 header，会被跳过）。
+fn parse(lines: &[&str]) -> ErDiagram {
+    let mut entities: Vec<ErEntity> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut relationships = Vec::new();
+    let mut current_entity: Option<usize> = None;
+
+    for line in lines.iter().skip(1).copied() {
+        if let Some(entity_idx) = current_entity {
+            if line == "}" {
+                current_entity = None;
+            } else if let Some(attribute) = parse_attribute(line) {
+                entities[entity_idx].attributes.push(attribute);
+            }
+            continue;
+        }
+
+        if let Some(caps) = ENTITY_BLOCK_RE.captures(line) {
+            ensure_entity(&mut entities, &mut index, &caps[1]);
+            current_entity = Some(index[&caps[1]]);
+            continue;
+        }
+
+        if let Some(relationship) = parse_relationship(line) {
+            ensure_entity(&mut entities, &mut index, &relationship.entity1);
+            ensure_entity(&mut entities, &mut index, &relationship.entity2);
+            relationships.push(relationship);
+        }
+    }
+
+    ErDiagram {
+        entities,
+        relationships,
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Layout
+// ----------------------------------------------------------------------------
+
+/// entity 之间的最小水平间距（会被最长 label 撑大）。
+const MIN_H_GAP: i32 = 6;
+/// 网格行之间的垂直间距。
+const V_GAP: i32 = 4;
+
+/// entity box 的分区：header / 属性（无属性时只有 header）。
+pub(crate) fn entity_sections(entity: &ErEntity) -> Vec<Vec<String>> {
+    let header = vec![entity.label.clone()];
+    let attributes: Vec<String> = entity
+        .attributes
+        .iter()
+        .map(ErAttribute::display_text)
+        .collect();
+    if attributes.is_empty() {
+        vec![header]
+    } else {
+        vec![header, attributes]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct EntityBox {
+    pub sections: Vec<Vec<String>>,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl EntityBox {
+    fn center_x(&self) -> i32 {
+        self.x + self.width / 2
+    }
+
+    fn center_y(&self) -> i32 {
+        self.y + self.height / 2
+    }
+}
+
+/// relationship 的几何（统一以 entity1 -> entity2 的方向给出）。
+#[derive(Debug, Clone)]
+pub(crate) struct ErRoute {
+    pub path: Vec<AsciiDrawingCoord>,
+    /// entity1 / entity2 一侧 cardinality 标记的起点（标记从这里向右绘制）。
+    pub marker1: AsciiDrawingCoord,
+    pub marker2: AsciiDrawingCoord,
+    /// label 实际绘制的起点与文本（可能被截断）；落在画布外时为 None。
+    pub label: Option<(AsciiDrawingCoord, String)>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ErLayout {
+    /// entity 下标 -> box。
+    pub boxes: Vec<EntityBox>,
+    /// relationship 下标 -> 路由（端点 entity 不存在时为 None）。
+    pub routes: Vec<Option<ErRoute>>,
+}
+
+/// 计算 ASCII 布局（调用方需保证 `diagram.entities` 非空）。
+pub(crate) fn layout(diagram: &ErDiagram, use_ascii: bool) -> ErLayout {
+    let entity_index: HashMap<&str, usize> = diagram
+        .entities
+        .iter()
+        .enumerate()
+        .map(|(idx, entity)| (entity.id.as_str(), idx))
+        .collect();
+
+    let max_label_width = diagram
+        .relationships
+        .iter()
+        .map(|rel| display_width(&rel.label))
+        .max()
+        .unwrap_or(0);
+    let h_gap = MIN_H_GAP.max(max_label_width);
+
+    // 近似正方形的网格：每行 max(2, ceil(sqrt(n))) 个 entity
+    let per_row = 2.max((diagram.entities.len() as f64).sqrt().ceil() as usize);
+    let mut boxes = Vec::with_capacity(diagram.entities.len());
+    let (mut x, mut y, mut row_height, mut in_row) = (0, 0, 0, 0);
+    for entity in &diagram.entities {
+        let sections = entity_sections(entity);
+        let (width, height) = sections_size(&sections);
+        if in_row >= per_row {
+            y += row_height + V_GAP;
+            x = 0;
+            row_height = 0;
+            in_row = 0;
+        }
+        boxes.push(EntityBox {
+            sections,
+            x,
+            y,
+            width,
+            height,
+        });
+        x += width + h_gap;
+        row_height = row_height.max(height);
+        in_row += 1;
+    }
+
+    let height = boxes.iter().map(|b| b.y + b.height).max().unwrap_or(0) + 2;
+
+    let routes = diagram
+        .relationships
+        .iter()
+        .map(|rel| {
+            let first = *entity_index.get(rel.entity1.as_str())?;
+            let second = *entity_index.get(rel.entity2.as_str())?;
+            Some(route(&boxes[first], &boxes[second], rel, use_ascii, height))
+        })
+        .collect();
+
+    ErLayout { boxes, routes }
+}
+
+fn route(
+    first: &EntityBox,
+    second: &EntityBox,
+    rel: &ErRelationship,
+    use_ascii: bool,
+    canvas_height: i32,
+) -> ErRoute {
+    let horizontal = (first.center_y() - second.center_y()).abs() < first.height.max(second.height);
+
+    if horizontal {
+        // 左右相邻：从左侧 box 的右边缘连到右侧 box 的左边缘
+        let first_is_left = first.center_x() < second.center_x();
+        let (left, right) = if first_is_left {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let right_card = if first_is_left {
+            rel.cardinality2
+        } else {
+            rel.cardinality1
+        };
+        let start = left.x + left.width;
+        let end = right.x - 1;
+        let row = left.center_y();
+
+        let mut path: Vec<AsciiDrawingCoord> = (start..=end)
+            .map(|x| AsciiDrawingCoord { x, y: row })
+            .collect();
+        let left_marker = AsciiDrawingCoord { x: start, y: row };
+        let right_len = right_card.marker(use_ascii).chars().count() as i32;
+        let right_marker = AsciiDrawingCoord {
+            x: end - right_len + 1,
+            y: row,
+        };
+
+        let label = (!rel.label.is_empty() && row > 0).then(|| {
+            let mid = (start + end) / 2;
+            let x = start.max(mid - display_width(&rel.label) / 2);
+            let text = truncate_to_width(&rel.label, (end - x + 1).max(0));
+            (AsciiDrawingCoord { x, y: row - 1 }, text)
+        });
+
+        if !first_is_left {
+            path.reverse();
+        }
+        let (marker1, marker2) = if first_is_left {
+            (left_marker, right_marker)
+        } else {
+            (right_marker, left_marker)
+        };
+        ErRoute {
+            path,
+            marker1,
+            marker2,
+            label,
+        }
+    } else {
+        // 上下相邻：从上方 box 的底边向下，必要时在中线拐到下方 box 的中心列
+        let first_is_top = first.center_y() < second.center_y();
+        let (top, bottom) = if first_is_top {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let (top_card, bottom_card) = if first_is_top {
+            (rel.cardinality1, rel.cardinality2)
+        } else {
+            (rel.cardinality2, rel.cardinality1)
+        };
+        let start = top.y + top.height;
+        let end = bottom.y - 1;
+        let top_x = top.center_x();
+        let bottom_x = bottom.center_x();
+        let mid = (start + end) / 2;
+
+        let mut path = Vec::new();
+        if top_x == bottom_x {
+            path.extend((start..=end).map(|y| AsciiDrawingCoord { x: top_x, y }));
+        } else {
+            path.extend((start..=mid).map(|y| AsciiDrawingCoord { x: top_x, y }));
+            if top_x < bottom_x {
+                path.extend((top_x + 1..=bottom_x).map(|x| AsciiDrawingCoord { x, y: mid }));
+            } else {
+                path.extend(
+                    (bottom_x..top_x)
+                        .rev()
+                        .map(|x| AsciiDrawingCoord { x, y: mid }),
+                );
+            }
+            path.extend((mid + 1..=end).map(|y| AsciiDrawingCoord { x: bottom_x, y }));
+        }
+
+        let top_len = top_card.marker(use_ascii).chars().count() as i32;
+        let bottom_len = bottom_card.marker(use_ascii).chars().count() as i32;
+        let top_marker = AsciiDrawingCoord {
+            x: top_x - top_len / 2,
+            y: start,
+        };
+        let bottom_marker = AsciiDrawingCoord {
+            x: bottom_x - bottom_len / 2,
+            y: end,
+        };
+
+        let label = (!rel.label.is_empty() && mid >= 0 && mid < canvas_height).then(|| {
+            (
+                AsciiDrawingCoord {
+                    x: top_x + 2,
+                    y: mid,
+                },
+                rel.label.clone(),
+            )
+        });
+
+        if !first_is_top {
+            path.reverse();
+        }
+        let (marker1, marker2) = if first_is_top {
+            (top_marker, bottom_marker)
+        } else {
+            (bottom_marker, top_marker)
+        };
+        ErRoute {
+            path,
+            marker1,
+            marker2,
+            label,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Meta
+// ----------------------------------------------------------------------------
+
+fn cardinality_marker(
+    cardinality: Cardinality,
+    origin: AsciiDrawingCoord,
+    use_ascii: bool,
+) -> AsciiCardinalityMarker {
+    let text = cardinality.marker(use_ascii);
+    AsciiCardinalityMarker {
+        cardinality: cardinality.as_str().to_string(),
+        text: text.to_string(),
+        box_rect: AsciiBox {
+            x: origin.x,
+            y: origin.y,
+            width: display_width(text),
+            height: 1,
+        },
+    }
+}
+
+/// 计算 ER diagram 的 ASCII meta（与 JS `renderMermaidAscii` 的输出逐格对齐）。
+pub(crate) fn ascii_meta(text: &str, use_ascii: bool) -> AsciiErMeta {
+    let diagram = parse_text(text, false);
+    if diagram.entities.is_empty() {
+        return AsciiErMeta {
+            entities: Vec::new(),
+            relationships: Vec::new(),
+            backend: Backend::Native,
+        };
+    }
+
+    let layout = layout(&diagram, use_ascii);
+
+    let entities = diagram
+        .entities
+        .iter()
+        .zip(&layout.boxes)
+        .map(|(entity, entity_box)| {
+            let attributes = section_rows(&entity_box.sections)
+                .into_iter()
+                .filter(|&(section_idx, _, _)| section_idx == 1)
+                .map(|(section_idx, line_idx, row)| {
+                    let text = &entity_box.sections[section_idx][line_idx];
+                    AsciiMemberRow {
+                        text: text.clone(),
+                        box_rect: AsciiBox {
+                            x: entity_box.x + 2,
+                            y: entity_box.y + row,
+                            width: display_width(text),
+                            height: 1,
+                        },
+                    }
+                })
+                .collect();
+            AsciiErEntity {
+                id: entity.id.clone(),
+                label: entity.label.clone(),
+                box_rect: AsciiBox {
+                    x: entity_box.x,
+                    y: entity_box.y,
+                    width: entity_box.width,
+                    height: entity_box.height,
+                },
+                attributes,
+            }
+        })
+        .collect();
+
+    let relationships = diagram
+        .relationships
+        .iter()
+        .zip(&layout.routes)
+        .filter_map(|(rel, route)| {
+            let route = route.as_ref()?;
+            Some(AsciiErRelationship {
+                from: rel.entity1.clone(),
+                to: rel.entity2.clone(),
+                label: rel.label.clone(),
+                identifying: rel.identifying,
+                label_box: route.label.as_ref().map(|(origin, text)| AsciiBox {
+                    x: origin.x,
+                    y: origin.y,
+                    width: display_width(text),
+                    height: 1,
+                }),
+                path: route.path.clone(),
+                from_cardinality: cardinality_marker(rel.cardinality1, route.marker1, use_ascii),
+                to_cardinality: cardinality_marker(rel.cardinality2, route.marker2, use_ascii),
+            })
+        })
+        .collect();
+
+    AsciiErMeta {
+        entities,
+        relationships,
+        backend: Backend::Native,
+    }
+}
@@ -0,0 +1,204 @@
+// ============================================================================
+// ER diagram：纯 Rust ASCII/Unicode 渲染（`Backend::Native`）
+//
+// 对齐 JS bundle 的 `renderErAscii`：
+// - box 坐标来自 `er::layout`（与 ASCII meta 共用同一份布局）；
+// - 先画所有 entity box，再逐条画 relationship（线 -> 两端 cardinality -> label）；
+// - 越界裁剪沿用 JS 的判断（与初始画布尺寸比较）。
+// ============================================================================
+
+use super::canvas::Canvas;
+use super::class_ascii::draw_section_box;
+use super::er::{self, EntityBox, ErRelationship};
+use super::text::{display_width, truncate_to_width};
+use crate::error::Result;
+use crate::types::AsciiRenderOptions;
+use std::collections::HashMap;
+
+/// 渲染 ER diagram 为字符画（没有 entity 时返回空串，与 JS 一致）。
+pub(crate) fn render(text: &str, options: &AsciiRenderOptions) -> Result<String> {
+    let diagram = er::parse_checked(text, false)?;
+    if diagram.entities.is_empty() {
+        return Ok(String::new());
+    }
+
+    let use_ascii = options.use_ascii.unwrap_or(false);
+    let layout = er::layout(&diagram, use_ascii);
+    let width = layout
+        .boxes
+        .iter()
+        .map(|b| b.x + b.width)
+        .max()
+        .unwrap_or(0)
+        + 4;
+    let height = layout
+        .boxes
+        .iter()
+        .map(|b| b.y + b.height)
+        .max()
+        .unwrap_or(0)
+        + 2;
+    let mut canvas = Canvas::new(width - 1, height - 1);
+
+    for entity_box in &layout.boxes {
+        draw_section_box(
+            &mut canvas,
+            entity_box.x,
+            entity_box.y,
+            &entity_box.sections,
+            use_ascii,
+            (width, height),
+        );
+    }
+
+    let index: HashMap<&str, usize> = diagram
+        .entities
+        .iter()
+        .enumerate()
+        .map(|(idx, entity)| (entity.id.as_str(), idx))
+        .collect();
+    for rel in &diagram.relationships {
+        let (Some(&first), Some(&second)) = (
+            index.get(rel.entity1.as_str()),
+            index.get(rel.entity2.as_str()),
+        ) else {
+            continue;
+        };
+        draw_relationship(
+            &mut canvas,
+            &layout.boxes[first],
+            &layout.boxes[second],
+            rel,
+            use_ascii,
+            (width, height),
+        );
+    }
+
+    Ok(canvas.to_text())
+}
+
+fn draw_relationship(
+    canvas: &mut Canvas,
+    first: &EntityBox,
+    second: &EntityBox,
+    rel: &ErRelationship,
+    use_ascii: bool,
+    clip: (i32, i32),
+) {
+    let (width, height) = clip;
+    let horizontal = match (rel.identifying, use_ascii) {
+        (true, true) => '-',
+        (true, false) => '─',
+        (false, true) => '.',
+        (false, false) => '╌',
+    };
+    let vertical = match (rel.identifying, use_ascii) {
+        (true, true) => '|',
+        (true, false) => '│',
+        (false, true) => ':',
+        (false, false) => '┊',
+    };
+    let center = |b: &EntityBox| (b.x + b.width / 2, b.y + b.height / 2);
+    let (first_x, first_y) = center(first);
+    let (second_x, second_y) = center(second);
+
+    if (first_y - second_y).abs() < first.height.max(second.height) {
+        // 左右相邻
+        let first_is_left = first_x < second_x;
+        let (left, right) = if first_is_left {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let (left_card, right_card) = if first_is_left {
+            (rel.cardinality1, rel.cardinality2)
+        } else {
+            (rel.cardinality2, rel.cardinality1)
+        };
+        let start = left.x + left.width;
+        let end = right.x - 1;
+        let row = left.y + left.height / 2;
+        for x in start..=end {
+            if x < width {
+                canvas.set(x, row, horizontal);
+            }
+        }
+        for (offset, ch) in left_card.marker(use_ascii).chars().enumerate() {
+            let x = start + offset as i32;
+            if x < width {
+                canvas.set(x, row, ch);
+            }
+        }
+        let right_marker = right_card.marker(use_ascii);
+        let len = right_marker.chars().count() as i32;
+        for (offset, ch) in right_marker.chars().enumerate() {
+            let x = end - len + 1 + offset as i32;
+            if x >= 0 && x < width {
+                canvas.set(x, row, ch);
+            }
+        }
+        if !rel.label.is_empty() && row > 0 {
+            let mid = (start + end) / 2;
+            let x = start.max(mid - display_width(&rel.label) / 2);
+            let text = truncate_to_width(&rel.label, (end - x + 1).max(0));
+            canvas.draw_text(x, row - 1, &text);
+        }
+    } else {
+        // 上下相邻
+        let first_is_top = first_y < second_y;
+        let (top, bottom) = if first_is_top {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let (top_card, bottom_card) = if first_is_top {
+            (rel.cardinality1, rel.cardinality2)
+        } else {
+            (rel.cardinality2, rel.cardinality1)
+        };
+        let start = top.y + top.height;
+        let end = bottom.y - 1;
+        let top_x = top.x + top.width / 2;
+        let bottom_x = bottom.x + bottom.width / 2;
+        for y in start..=end {
+            if y < height {
+                canvas.set(top_x, y, vertical);
+            }
+        }
+        if top_x != bottom_x {
+            let mid = (start + end) / 2;
+            for x in top_x.min(bottom_x)..=top_x.max(bottom_x) {
+                if x < width && mid < height {
+                    canvas.set(x, mid, horizontal);
+                }
+            }
+            for y in mid + 1..=end {
+                if y < height {
+                    canvas.set(bottom_x, y, vertical);
+                }
+            }
+        }
+        draw_centered(canvas, top_card.marker(use_ascii), top_x, start, clip);
+        draw_centered(canvas, bottom_card.marker(use_ascii), bottom_x, end, clip);
+        if !rel.label.is_empty() {
+            let mid = (start + end) / 2;
+            if mid >= 0 {
+                canvas.draw_text(top_x + 2, mid, &rel.label);
+            }
+        }
+    }
+}
+
+/// cardinality 标记以 `center_x` 为中心写在第 `y` 行。
+fn draw_centered(canvas: &mut Canvas, text: &str, center_x: i32, y: i32, clip: (i32, i32)) {
+    if y < 0 || y >= clip.1 {
+        return;
+    }
+    let len = text.chars().count() as i32;
+    for (offset, ch) in text.chars().enumerate() {
+        let x = center_x - len / 2 + offset as i32;
+        if x >= 0 && x < clip.0 {
+            canvas.set(x, y, ch);
+        }
+    }
+}
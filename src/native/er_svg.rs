@@ -0,0 +1,531 @@
+// ============================================================================
+// ER diagram：纯 Rust SVG 渲染（`Backend::Native`）
+//
+// 对齐 JS bundle：
+// - 尺寸: `layoutErDiagram` 的 entity 规则（标题栏 + 每个属性一行，属性行按等宽字体估宽）
+// - 布局: bundle 用 dagre（LR）；这里复用 flowchart 的分层布局，box 尺寸由上面的规则给定
+// - 绘制: `renderErSvg`（relationship -> entity -> crow's foot -> label）
+//
+// 说明：
+// - SVG 结构、元素 id、CSS 变量与 JS 后端一致；坐标与 flowchart 一样不保证与 dagre 逐像素一致；
+// - 同一对 entity 之间的多条关系只画一条（对齐 dagre 非 multigraph：位置取第一条、内容取最后一条）；
+// - 布局结果直接构造成 `svg_meta` 的 `JsEr*` 结构，meta 与 JS 后端共用同一个转换。
+// ============================================================================
+
+use super::er::{self, ErAttribute, ErDiagram};
+use super::flowchart::{Direction, EdgeStyle, FlowEdge, FlowchartGraph};
+use super::flowchart_svg::layout::{self, LayoutOptions};
+use super::svg::{
+    CONNECTOR_STROKE, EDGE_LABEL_FONT_SIZE, EDGE_LABEL_FONT_WEIGHT, INNER_BOX_STROKE,
+    NODE_LABEL_FONT_SIZE, NODE_LABEL_FONT_WEIGHT, OUTER_BOX_STROKE, TEXT_DY, count, edge_attrs,
+    element_group, escape_xml, js_num, list, node_attrs, style_block, svg_open_tag, text_width,
+    with_accessibility,
+};
+use crate::error::Result;
+use crate::native;
+use crate::svg_meta::{
+    self, ElementIds, JsErEntity, JsErLayout, JsErRelationship, polyline_midpoint, rect,
+};
+use crate::types::{RenderOptions, SvgDiagramMeta, SvgPoint, SvgRenderWithMeta};
+use std::collections::HashMap;
+
+/// 布局常量（对齐 JS `layoutErDiagram` 用的常量）。
+const PADDING: f64 = 40.0;
+const BOX_PAD_X: f64 = 12.0;
+const HEADER_HEIGHT: f64 = 32.0;
+const ROW_HEIGHT: f64 = 22.0;
+const MIN_WIDTH: f64 = 140.0;
+const ATTR_FONT_SIZE: f64 = 11.0;
+const NODE_SPACING: f64 = 50.0;
+const LAYER_SPACING: f64 = 70.0;
+/// 属性行 / key 标记的字号字重（对齐 JS `renderErSvg`）。
+const ATTR_FONT_WEIGHT: u32 = 400;
+const KEY_FONT_SIZE: f64 = 9.0;
+const KEY_FONT_WEIGHT: u32 = 600;
+
+/// 布局结果 + 画布尺寸。
+struct Layout {
+    width: f64,
+    height: f64,
+    inner: JsErLayout,
+}
+
+/// 渲染 ER diagram 为 SVG。
+pub(crate) fn render(text: &str, options: &RenderOptions) -> Result<String> {
+    Ok(run(text, options)?.0)
+}
+
+/// 渲染 ER diagram 为 SVG + 像素坐标 meta。
+pub(crate) fn render_with_meta(text: &str, options: &RenderOptions) -> Result<SvgRenderWithMeta> {
+    let (svg, layout, prefix) = run(text, options)?;
+    let view_box = rect(0.0, 0.0, layout.width, layout.height);
+    let meta = svg_meta::convert_er(layout.inner, &mut ElementIds::new(&prefix));
+    Ok(SvgRenderWithMeta {
+        svg,
+        view_box,
+        meta: SvgDiagramMeta::Er(meta),
+    })
+}
+
+fn run(text: &str, options: &RenderOptions) -> Result<(String, Layout, String)> {
+    // SVG 入口与 ASCII 不同：`;` 也算换行（对齐 bundle 的 `renderMermaid`）
+    let diagram = er::parse_checked(text, true)?;
+    let accessibility = native::parse_accessibility(text);
+    let layout = layout(&diagram);
+
+    let prefix = escape_xml(options.id_prefix.as_deref().unwrap_or_default());
+    let title = options.title.clone().or(accessibility.title);
+    let description = options
+        .description
+        .clone()
+        .or(accessibility.description)
+        .unwrap_or_else(|| describe(&layout.inner));
+
+    let body = render_body(&layout, &diagram, options, &prefix);
+    let svg = with_accessibility(
+        &body,
+        "entity relationship",
+        title.as_deref(),
+        &description,
+        &prefix,
+    );
+    Ok((svg, layout, prefix))
+}
+
+// ----------------------------------------------------------------------------
+// 布局
+// ----------------------------------------------------------------------------
+
+/// entity box 的尺寸（对齐 JS `layoutErDiagram`：属性行按等宽字体估宽，没有属性也留一行）。
+fn box_size(entity: &er::ErEntity) -> (f64, f64) {
+    let label = text_width(&entity.label, NODE_LABEL_FONT_SIZE, NODE_LABEL_FONT_WEIGHT);
+    let attributes = entity
+        .attributes
+        .iter()
+        .map(|attribute| {
+            let keys = if attribute.keys.is_empty() {
+                String::new()
+            } else {
+                format!("  {}", attribute.keys.join(","))
+            };
+            let text = format!("{}  {}{keys}", attribute.attr_type, attribute.name);
+            text.encode_utf16().count() as f64 * ATTR_FONT_SIZE * 0.6
+        })
+        .fold(0.0, f64::max);
+    let width = MIN_WIDTH
+        .max(label + BOX_PAD_X * 2.0)
+        .max(attributes + BOX_PAD_X * 2.0);
+    let rows = entity.attributes.len().max(1) as f64;
+    (width, HEADER_HEIGHT + rows * ROW_HEIGHT)
+}
+
+fn layout(diagram: &ErDiagram) -> Layout {
+    if diagram.entities.is_empty() {
+        return Layout {
+            width: 0.0,
+            height: 0.0,
+            inner: JsErLayout {
+                entities: Vec::new(),
+                relationships: Vec::new(),
+            },
+        };
+    }
+
+    let graph = FlowchartGraph::boxes(
+        Direction::Lr,
+        diagram.entities.iter().map(|entity| entity.id.as_str()),
+        diagram
+            .relationships
+            .iter()
+            .map(|rel| FlowEdge {
+                source: rel.entity1.clone(),
+                target: rel.entity2.clone(),
+                label: Some(rel.label.clone()),
+                style: EdgeStyle::Solid,
+                has_arrow_start: false,
+                has_arrow_end: false,
+            })
+            .collect(),
+    );
+    let placed = layout::layout(
+        &graph,
+        &LayoutOptions {
+            padding: PADDING,
+            node_spacing: NODE_SPACING,
+            layer_spacing: LAYER_SPACING,
+            node_sizes: diagram
+                .entities
+                .iter()
+                .map(|entity| (entity.id.clone(), box_size(entity)))
+                .collect(),
+        },
+    );
+
+    let placed_box: HashMap<&str, (f64, f64, f64, f64)> = placed
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), (node.x, node.y, node.width, node.height)))
+        .collect();
+    let entities = diagram
+        .entities
+        .iter()
+        .map(|entity| {
+            let (x, y, width, height) =
+                placed_box
+                    .get(entity.id.as_str())
+                    .copied()
+                    .unwrap_or_else(|| {
+                        let (width, height) = box_size(entity);
+                        (0.0, 0.0, width, height)
+                    });
+            JsErEntity {
+                id: entity.id.clone(),
+                label: entity.label.clone(),
+                x,
+                y,
+                width,
+                height,
+                header_height: HEADER_HEIGHT,
+                row_height: ROW_HEIGHT,
+            }
+        })
+        .collect();
+
+    // 布局按端点去重：每条画出来的边对应同一对端点的最后一条关系
+    let relationships = placed
+        .edges
+        .into_iter()
+        .filter_map(|edge| {
+            let rel = diagram
+                .relationships
+                .iter()
+                .rev()
+                .find(|rel| rel.entity1 == edge.source && rel.entity2 == edge.target)?;
+            Some(JsErRelationship {
+                entity1: rel.entity1.clone(),
+                entity2: rel.entity2.clone(),
+                cardinality1: rel.cardinality1.as_str().to_string(),
+                cardinality2: rel.cardinality2.as_str().to_string(),
+                label: rel.label.clone(),
+                identifying: rel.identifying,
+                points: edge.points,
+            })
+        })
+        .collect();
+
+    Layout {
+        width: placed.width,
+        height: placed.height,
+        inner: JsErLayout {
+            entities,
+            relationships,
+        },
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 绘制
+// ----------------------------------------------------------------------------
+
+fn render_body(
+    layout: &Layout,
+    diagram: &ErDiagram,
+    options: &RenderOptions,
+    prefix: &str,
+) -> String {
+    let inner = &layout.inner;
+    let mut parts = vec![
+        svg_open_tag(layout.width, layout.height, options),
+        style_block(options, true),
+        "<defs>".to_string(),
+        "</defs>".to_string(),
+    ];
+    let mut ids = ElementIds::new(prefix);
+    let edge_ids: Vec<String> = inner
+        .relationships
+        .iter()
+        .map(|rel| ids.edge(&rel.entity1, &rel.entity2))
+        .collect();
+    for (rel, id) in inner.relationships.iter().zip(&edge_ids) {
+        parts.push(element_group(
+            &edge_attrs(id, &rel.entity1, &rel.entity2, "edge"),
+            &[relationship(rel)],
+        ));
+    }
+    for (node, entity) in inner.entities.iter().zip(&diagram.entities) {
+        parts.push(element_group(
+            &node_attrs(&ids.node(&node.id), &node.id),
+            &[entity_box(node, &entity.attributes)],
+        ));
+    }
+    for (rel, id) in inner.relationships.iter().zip(&edge_ids) {
+        parts.push(element_group(
+            &edge_attrs(
+                &format!("{id}-cardinality"),
+                &rel.entity1,
+                &rel.entity2,
+                "edge-cardinality",
+            ),
+            &[cardinalities(rel)],
+        ));
+    }
+    for (rel, id) in inner.relationships.iter().zip(&edge_ids) {
+        parts.push(element_group(
+            &edge_attrs(
+                &format!("{id}-label"),
+                &rel.entity1,
+                &rel.entity2,
+                "edge-label",
+            ),
+            &[relationship_label(rel)],
+        ));
+    }
+    parts.push("</svg>".to_string());
+    parts.join("\n")
+}
+
+/// entity box：外框 + 标题栏 + 属性行（没有属性时画 `(no attributes)`）。
+fn entity_box(node: &JsErEntity, attributes: &[ErAttribute]) -> String {
+    let JsErEntity {
+        x,
+        y,
+        width,
+        height,
+        header_height,
+        row_height,
+        ..
+    } = *node;
+    let outer = js_num(OUTER_BOX_STROKE);
+    let center = js_num(x + width / 2.0);
+    let mut parts = vec![
+        format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"0\" ry=\"0\" fill=\"var(--_node-fill)\" stroke=\"var(--_node-stroke)\" stroke-width=\"{outer}\" />",
+            js_num(x),
+            js_num(y),
+            js_num(width),
+            js_num(height),
+        ),
+        format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"0\" ry=\"0\" fill=\"var(--_group-hdr)\" stroke=\"var(--_node-stroke)\" stroke-width=\"{outer}\" />",
+            js_num(x),
+            js_num(y),
+            js_num(width),
+            js_num(header_height),
+        ),
+        format!(
+            "<text x=\"{center}\" y=\"{}\" text-anchor=\"middle\" dy=\"{TEXT_DY}\" font-size=\"{NODE_LABEL_FONT_SIZE}\" font-weight=\"700\" fill=\"var(--_text)\">{}</text>",
+            js_num(y + header_height / 2.0),
+            escape_xml(&node.label),
+        ),
+    ];
+    let top = y + header_height;
+    parts.push(format!(
+        "<line x1=\"{}\" y1=\"{t}\" x2=\"{}\" y2=\"{t}\" stroke=\"var(--_node-stroke)\" stroke-width=\"{}\" />",
+        js_num(x),
+        js_num(x + width),
+        js_num(INNER_BOX_STROKE),
+        t = js_num(top),
+    ));
+    for (index, attribute) in attributes.iter().enumerate() {
+        let row_y = top + index as f64 * row_height + row_height / 2.0;
+        parts.push(attribute_row(attribute, x, row_y, width));
+    }
+    if attributes.is_empty() {
+        parts.push(format!(
+            "<text x=\"{center}\" y=\"{}\" text-anchor=\"middle\" dy=\"{TEXT_DY}\" font-size=\"{ATTR_FONT_SIZE}\" fill=\"var(--_text-faint)\" font-style=\"italic\">(no attributes)</text>",
+            js_num(top + row_height / 2.0),
+        ));
+    }
+    parts.join("\n")
+}
+
+/// 属性行：左侧 key 标记 + 类型，右对齐的属性名。
+fn attribute_row(attribute: &ErAttribute, x: f64, y: f64, width: f64) -> String {
+    let mut parts = Vec::new();
+    let mut badge_width = 0.0;
+    if !attribute.keys.is_empty() {
+        let keys = attribute.keys.join(",");
+        badge_width = text_width(&keys, KEY_FONT_SIZE, KEY_FONT_WEIGHT) + 8.0;
+        parts.push(format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"14\" rx=\"2\" ry=\"2\" fill=\"var(--_key-badge)\" />",
+            js_num(x + 6.0),
+            js_num(y - 7.0),
+            js_num(badge_width),
+        ));
+        parts.push(format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dy=\"{TEXT_DY}\" font-size=\"{KEY_FONT_SIZE}\" font-weight=\"{KEY_FONT_WEIGHT}\" fill=\"var(--_text-sec)\">{}</text>",
+            js_num(x + 6.0 + badge_width / 2.0),
+            js_num(y),
+            escape_xml(&keys),
+        ));
+    }
+    let type_x = x
+        + 8.0
+        + if badge_width > 0.0 {
+            badge_width + 6.0
+        } else {
+            0.0
+        };
+    parts.push(format!(
+        "<text x=\"{}\" y=\"{}\" class=\"mono\" dy=\"{TEXT_DY}\" font-size=\"{ATTR_FONT_SIZE}\" font-weight=\"{ATTR_FONT_WEIGHT}\"><tspan fill=\"var(--_text-muted)\">{}</tspan></text>",
+        js_num(type_x),
+        js_num(y),
+        escape_xml(&attribute.attr_type),
+    ));
+    parts.push(format!(
+        "<text x=\"{}\" y=\"{}\" class=\"mono\" text-anchor=\"end\" dy=\"{TEXT_DY}\" font-size=\"{ATTR_FONT_SIZE}\" font-weight=\"{ATTR_FONT_WEIGHT}\"><tspan fill=\"var(--_text-sec)\">{}</tspan></text>",
+        js_num(x + width - 8.0),
+        js_num(y),
+        escape_xml(&attribute.name),
+    ));
+    parts.join("\n")
+}
+
+/// 关系连线：identifying 实线，其余虚线。
+fn relationship(rel: &JsErRelationship) -> String {
+    if rel.points.len() < 2 {
+        return String::new();
+    }
+    let points: Vec<String> = rel
+        .points
+        .iter()
+        .map(|point| format!("{},{}", js_num(point.x), js_num(point.y)))
+        .collect();
+    let dash = if rel.identifying {
+        ""
+    } else {
+        " stroke-dasharray=\"6 4\""
+    };
+    format!(
+        "<polyline points=\"{}\" fill=\"none\" stroke=\"var(--_line)\" stroke-width=\"{}\"{dash} />",
+        points.join(" "),
+        js_num(CONNECTOR_STROKE),
+    )
+}
+
+/// 关系 label：折线长度中点上的底框 + 文字。
+fn relationship_label(rel: &JsErRelationship) -> String {
+    if rel.label.is_empty() || rel.points.len() < 2 {
+        return String::new();
+    }
+    let position = polyline_midpoint(&rel.points);
+    let width = text_width(&rel.label, EDGE_LABEL_FONT_SIZE, EDGE_LABEL_FONT_WEIGHT) + 8.0;
+    let height = EDGE_LABEL_FONT_SIZE + 6.0;
+    format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"2\" ry=\"2\" fill=\"var(--bg)\" stroke=\"var(--_inner-stroke)\" stroke-width=\"0.5\" />\n\
+         <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dy=\"{TEXT_DY}\" font-size=\"{EDGE_LABEL_FONT_SIZE}\" font-weight=\"{EDGE_LABEL_FONT_WEIGHT}\" fill=\"var(--_text-muted)\">{}</text>",
+        js_num(position.x - width / 2.0),
+        js_num(position.y - height / 2.0),
+        js_num(width),
+        js_num(height),
+        js_num(position.x),
+        js_num(position.y),
+        escape_xml(&rel.label),
+    )
+}
+
+/// 两端的 crow's foot 标记。
+fn cardinalities(rel: &JsErRelationship) -> String {
+    let points = &rel.points;
+    if points.len() < 2 {
+        return String::new();
+    }
+    [
+        crows_foot(points[0], points[1], &rel.cardinality1),
+        crows_foot(
+            points[points.len() - 1],
+            points[points.len() - 2],
+            &rel.cardinality2,
+        ),
+    ]
+    .join("\n")
+}
+
+/// 单端标记（对齐 JS 的 crow's foot 画法）：one 两道竖线，many 三叉，zero 再加一个空心圆。
+fn crows_foot(end: SvgPoint, next: SvgPoint, cardinality: &str) -> String {
+    let stroke = js_num(CONNECTOR_STROKE + 0.25);
+    let (dx, dy) = (end.x - next.x, end.y - next.y);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length == 0.0 {
+        return String::new();
+    }
+    let (ux, uy) = (dx / length, dy / length);
+    let (px, py) = (-uy, ux);
+    let (bar_x, bar_y) = (end.x - ux * 4.0, end.y - uy * 4.0);
+    let (tip_x, tip_y) = (end.x - ux * 16.0, end.y - uy * 16.0);
+    let line = |x1: f64, y1: f64, x2: f64, y2: f64| {
+        format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"var(--_line)\" stroke-width=\"{stroke}\" />",
+            js_num(x1),
+            js_num(y1),
+            js_num(x2),
+            js_num(y2),
+        )
+    };
+    let one = matches!(cardinality, "one" | "zero-one");
+    let many = matches!(cardinality, "many" | "zero-many");
+    let zero = matches!(cardinality, "zero-one" | "zero-many");
+    let mut parts = Vec::new();
+    if one {
+        parts.push(line(
+            bar_x + px * 6.0,
+            bar_y + py * 6.0,
+            bar_x - px * 6.0,
+            bar_y - py * 6.0,
+        ));
+        let (second_x, second_y) = (bar_x - ux * 4.0, bar_y - uy * 4.0);
+        parts.push(line(
+            second_x + px * 6.0,
+            second_y + py * 6.0,
+            second_x - px * 6.0,
+            second_y - py * 6.0,
+        ));
+    }
+    if many {
+        parts.push(line(bar_x + px * 7.0, bar_y + py * 7.0, tip_x, tip_y));
+        parts.push(line(bar_x, bar_y, tip_x, tip_y));
+        parts.push(line(bar_x - px * 7.0, bar_y - py * 7.0, tip_x, tip_y));
+    }
+    if zero {
+        let offset = if many { 20.0 } else { 12.0 };
+        parts.push(format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"4\" fill=\"var(--bg)\" stroke=\"var(--_line)\" stroke-width=\"{stroke}\" />",
+            js_num(end.x - ux * offset),
+            js_num(end.y - uy * offset),
+        ));
+    }
+    parts.join("\n")
+}
+
+/// 自动生成的 `<desc>`（对齐 JS `bmDescribe` 的 er 分支）。
+fn describe(layout: &JsErLayout) -> String {
+    let label_of = |id: &str| -> String {
+        layout
+            .entities
+            .iter()
+            .find(|entity| entity.id == id)
+            .map_or_else(|| id.to_string(), |entity| entity.label.clone())
+    };
+    let entities: Vec<String> = layout.entities.iter().map(|e| e.label.clone()).collect();
+    let relationships: Vec<String> = layout
+        .relationships
+        .iter()
+        .map(|rel| {
+            format!(
+                "{} → {} ({}, {} to {})",
+                label_of(&rel.entity1),
+                label_of(&rel.entity2),
+                rel.label,
+                rel.cardinality1,
+                rel.cardinality2
+            )
+        })
+        .collect();
+    format!(
+        "Entity relationship diagram with {} and {}.{}{}",
+        count(layout.entities.len(), "entity", "entities"),
+        count(layout.relationships.len(), "relationship", "relationships"),
+        list(&entities, "Entities"),
+        list(&relationships, "Relationships")
+    )
+}
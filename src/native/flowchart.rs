@@ -0,0 +1,757 @@
+// ============================================================================
+// Flowchart / State diagram：解析
+//
+// 对齐 JS bundle：
+// - `parseMermaid`：按 `\n` / `;` 切分，header 决定走 flowchart 还是 stateDiagram 分支
+// - flowchart：节点形状、`&` 并列、链式边、`:::class`、classDef/class/style、subgraph（可嵌套）
+// - stateDiagram：`[*]` 起止点、`state "label" as id`、复合状态 `state X { ... }`
+//
+// 说明：
+// - 这里只产出 AST（与 TS 的 `MermaidGraph` 同构），布局/绘制在 `flowchart_ascii`；
+// - 插入顺序有语义（节点 index 决定布局与路由的 tie-break），所以节点用 Vec + 索引表保存。
+// ============================================================================
+
+use super::{check_syntax, strip_accessibility};
+use crate::error::{BeautifulMermaidError, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+// ----------------------------------------------------------------------------
+// AST
+// ----------------------------------------------------------------------------
+
+/// 图方向（`graph TD` / `flowchart LR` / `direction BT` ...）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Direction {
+    Td,
+    Tb,
+    Lr,
+    Bt,
+    Rl,
+}
+
+impl Direction {
+    fn parse(text: &str) -> Option<Self> {
+        match text.to_ascii_uppercase().as_str() {
+            "TD" => Some(Self::Td),
+            "TB" => Some(Self::Tb),
+            "LR" => Some(Self::Lr),
+            "BT" => Some(Self::Bt),
+            "RL" => Some(Self::Rl),
+            _ => None,
+        }
+    }
+
+    /// 水平方向（LR/RL）。
+    pub(crate) fn is_horizontal(self) -> bool {
+        matches!(self, Self::Lr | Self::Rl)
+    }
+}
+
+/// 节点形状（对齐 TS: `NodeShape`）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NodeShape {
+    Rectangle,
+    Rounded,
+    Diamond,
+    Stadium,
+    Circle,
+    Subroutine,
+    DoubleCircle,
+    Hexagon,
+    Cylinder,
+    Asymmetric,
+    Trapezoid,
+    TrapezoidAlt,
+    StateStart,
+    StateEnd,
+}
+
+impl NodeShape {
+    /// bundle 里的形状名（SVG meta 的 `shape` 字段）。
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Rectangle => "rectangle",
+            Self::Rounded => "rounded",
+            Self::Diamond => "diamond",
+            Self::Stadium => "stadium",
+            Self::Circle => "circle",
+            Self::Subroutine => "subroutine",
+            Self::DoubleCircle => "doublecircle",
+            Self::Hexagon => "hexagon",
+            Self::Cylinder => "cylinder",
+            Self::Asymmetric => "asymmetric",
+            Self::Trapezoid => "trapezoid",
+            Self::TrapezoidAlt => "trapezoid-alt",
+            Self::StateStart => "state-start",
+            Self::StateEnd => "state-end",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct FlowNode {
+    pub id: String,
+    pub label: String,
+    pub shape: NodeShape,
+}
+
+/// 边线型（`-->` / `-.->` / `==>`）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EdgeStyle {
+    Solid,
+    Dotted,
+    Thick,
+}
+
+impl EdgeStyle {
+    /// bundle 里的线型名（SVG meta 的 `style` 字段）。
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Solid => "solid",
+            Self::Dotted => "dotted",
+            Self::Thick => "thick",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct FlowEdge {
+    pub source: String,
+    pub target: String,
+    pub label: Option<String>,
+    pub style: EdgeStyle,
+    pub has_arrow_start: bool,
+    pub has_arrow_end: bool,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub(crate) struct FlowSubgraph {
+    pub id: String,
+    pub label: String,
+    pub node_ids: Vec<String>,
+    pub children: Vec<FlowSubgraph>,
+    // 子图内的 `direction`：与 bundle 一样只解析、不参与布局。
+    #[allow(dead_code)]
+    pub direction: Option<Direction>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct FlowchartGraph {
+    pub direction: Direction,
+    /// 按首次出现顺序排列。
+    pub nodes: Vec<FlowNode>,
+    node_index: HashMap<String, usize>,
+    pub edges: Vec<FlowEdge>,
+    pub subgraphs: Vec<FlowSubgraph>,
+    pub class_defs: HashMap<String, Vec<(String, String)>>,
+    /// 节点 id -> class 名（按首次赋值顺序；重复赋值覆盖值但保留位置）。
+    pub class_assignments: Vec<(String, String)>,
+    pub node_styles: HashMap<String, Vec<(String, String)>>,
+}
+
+impl FlowchartGraph {
+    fn new(direction: Direction) -> Self {
+        Self {
+            direction,
+            nodes: Vec::new(),
+            node_index: HashMap::new(),
+            edges: Vec::new(),
+            subgraphs: Vec::new(),
+            class_defs: HashMap::new(),
+            class_assignments: Vec::new(),
+            node_styles: HashMap::new(),
+        }
+    }
+
+    /// 只有矩形节点与普通边的图：class / ER 的 SVG 借用这里的分层布局（节点尺寸由布局参数给出）。
+    pub(crate) fn boxes<'a>(
+        direction: Direction,
+        ids: impl IntoIterator<Item = &'a str>,
+        edges: Vec<FlowEdge>,
+    ) -> Self {
+        let mut graph = Self::new(direction);
+        for id in ids {
+            graph.insert_node(FlowNode {
+                id: id.to_string(),
+                label: id.to_string(),
+                shape: NodeShape::Rectangle,
+            });
+        }
+        graph.edges = edges;
+        graph
+    }
+
+    pub(crate) fn node_position(&self, id: &str) -> Option<usize> {
+        self.node_index.get(id).copied()
+    }
+
+    fn has_node(&self, id: &str) -> bool {
+        self.node_index.contains_key(id)
+    }
+
+    /// 首次出现时登记节点（之后再出现的定义不覆盖，对齐 JS `nodes.has(id) || nodes.set(...)`）。
+    fn insert_node(&mut self, node: FlowNode) {
+        if !self.has_node(&node.id) {
+            self.node_index.insert(node.id.clone(), self.nodes.len());
+            self.nodes.push(node);
+        }
+    }
+
+    fn assign_class(&mut self, node_id: &str, class_name: &str) {
+        match self
+            .class_assignments
+            .iter_mut()
+            .find(|(id, _)| id == node_id)
+        {
+            Some(entry) => entry.1 = class_name.to_string(),
+            None => self
+                .class_assignments
+                .push((node_id.to_string(), class_name.to_string())),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 入口
+// ----------------------------------------------------------------------------
+
+fn parse_error(message: impl Into<String>) -> BeautifulMermaidError {
+    BeautifulMermaidError::Parse {
+        message: message.into(),
+    }
+}
+
+static STATE_HEADER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^stateDiagram(-v2)?\s*$").unwrap());
+
+/// 解析 flowchart / stateDiagram：先过 [`check_syntax`]，再建 AST（native 渲染器都从这里进）。
+pub(crate) fn parse(text: &str) -> Result<FlowchartGraph> {
+    check_syntax(text)?;
+    parse_graph(text)
+}
+
+/// 只跑 native parser（对齐 JS `parseMermaid`）；`validate_mermaid` 已经跑过 selkie，直接用这个。
+pub(crate) fn parse_graph(text: &str) -> Result<FlowchartGraph> {
+    let text = strip_accessibility(text);
+    let lines: Vec<&str> = text
+        .split(['\n', ';'])
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("%%"))
+        .collect();
+
+    let Some(header) = lines.first() else {
+        return Err(parse_error("Empty mermaid diagram"));
+    };
+
+    if STATE_HEADER.is_match(header) {
+        Ok(parse_state_diagram(&lines))
+    } else {
+        parse_flowchart(&lines)
+    }
+}
+
+// ----------------------------------------------------------------------------
+// flowchart
+// ----------------------------------------------------------------------------
+
+static FLOWCHART_HEADER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^(?:graph|flowchart)\s+(TD|TB|LR|BT|RL)\s*$").unwrap());
+static CLASS_DEF: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^classDef\s+([A-Za-z0-9_]+)\s+(.+)$").unwrap());
+static CLASS_ASSIGN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^class\s+(\S+)\s+([A-Za-z0-9_]+)$").unwrap());
+static STYLE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^style\s+(\S+)\s+(.+)$").unwrap());
+static DIRECTION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^direction\s+(TD|TB|LR|BT|RL)\s*$").unwrap());
+static SUBGRAPH: Lazy<Regex> = Lazy::new(|| Regex::new(r"^subgraph\s+(.+)$").unwrap());
+static SUBGRAPH_ID_LABEL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([\p{L}\p{N}_-]+)\s*\[(.+)\]$").unwrap());
+static WHITESPACE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s+").unwrap());
+static NON_ID_CHAR: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^\p{L}\p{N}_]").unwrap());
+
+fn parse_flowchart(lines: &[&str]) -> Result<FlowchartGraph> {
+    let header = lines[0];
+    let Some(caps) = FLOWCHART_HEADER.captures(header) else {
+        return Err(parse_error(format!(
+            "Invalid mermaid header: \"{header}\". Expected \"graph TD\", \"flowchart LR\", \"stateDiagram-v2\", etc."
+        )));
+    };
+    let direction = Direction::parse(&caps[1]).expect("header regex 只匹配合法方向");
+    let mut graph = FlowchartGraph::new(direction);
+    let mut stack: Vec<FlowSubgraph> = Vec::new();
+
+    for &line in &lines[1..] {
+        if let Some(caps) = CLASS_DEF.captures(line) {
+            graph
+                .class_defs
+                .insert(caps[1].to_string(), parse_style_props(&caps[2]));
+            continue;
+        }
+        if let Some(caps) = CLASS_ASSIGN.captures(line) {
+            for id in caps[1].split(',').map(str::trim) {
+                graph.assign_class(id, &caps[2]);
+            }
+            continue;
+        }
+        if let Some(caps) = STYLE.captures(line) {
+            let props = parse_style_props(&caps[2]);
+            for id in caps[1].split(',').map(str::trim) {
+                let entry = graph.node_styles.entry(id.to_string()).or_default();
+                for (key, value) in &props {
+                    match entry.iter_mut().find(|(k, _)| k == key) {
+                        Some(existing) => existing.1 = value.clone(),
+                        None => entry.push((key.clone(), value.clone())),
+                    }
+                }
+            }
+            continue;
+        }
+        if let Some(caps) = DIRECTION.captures(line)
+            && let Some(current) = stack.last_mut()
+        {
+            current.direction = Direction::parse(&caps[1]);
+            continue;
+        }
+        if let Some(caps) = SUBGRAPH.captures(line) {
+            let rest = caps[1].trim();
+            let (id, label) = match SUBGRAPH_ID_LABEL.captures(rest) {
+                Some(parts) => (parts[1].to_string(), parts[2].to_string()),
+                None => {
+                    let id = WHITESPACE.replace_all(rest, "_");
+                    (
+                        NON_ID_CHAR.replace_all(&id, "").into_owned(),
+                        rest.to_string(),
+                    )
+                }
+            };
+            stack.push(FlowSubgraph {
+                id,
+                label,
+                node_ids: Vec::new(),
+                children: Vec::new(),
+                direction: None,
+            });
+            continue;
+        }
+        if line == "end" {
+            if let Some(done) = stack.pop() {
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(done),
+                    None => graph.subgraphs.push(done),
+                }
+            }
+            continue;
+        }
+        parse_edge_line(line, &mut graph, &mut stack);
+    }
+
+    Ok(graph)
+}
+
+/// `fill:#f9f,stroke:#333` -> [(fill, #f9f), (stroke, #333)]
+fn parse_style_props(text: &str) -> Vec<(String, String)> {
+    let mut props: Vec<(String, String)> = Vec::new();
+    for part in text.split(',') {
+        if let Some(colon) = part.find(':')
+            && colon > 0
+        {
+            let key = part[..colon].trim();
+            let value = part[colon + 1..].trim();
+            if !key.is_empty() && !value.is_empty() {
+                match props.iter_mut().find(|(k, _)| k == key) {
+                    Some(existing) => existing.1 = value.to_string(),
+                    None => props.push((key.to_string(), value.to_string())),
+                }
+            }
+        }
+    }
+    props
+}
+
+/// 边运算符；注意 `-.->` 里的 `.` 在 JS 正则里没有转义（匹配任意字符），这里保持一致。
+static ARROW: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(<)?(-->|-.->|==>|---|-\.-|===)(?:\|([^|]*)\|)?").unwrap());
+static BARE_ID: Lazy<Regex> = Lazy::new(|| Regex::new(r"^([\p{L}\p{N}_-]+)").unwrap());
+static CLASS_SHORTHAND: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^:::([A-Za-z0-9_][A-Za-z0-9_-]*)").unwrap());
+
+/// 形状语法，按优先级排列（多字符定界符在前）。
+static SHAPES: Lazy<Vec<(Regex, NodeShape)>> = Lazy::new(|| {
+    [
+        (
+            r"^([\p{L}\p{N}_-]+)\(\(\((.+?)\)\)\)",
+            NodeShape::DoubleCircle,
+        ),
+        (r"^([\p{L}\p{N}_-]+)\(\[(.+?)\]\)", NodeShape::Stadium),
+        (r"^([\p{L}\p{N}_-]+)\(\((.+?)\)\)", NodeShape::Circle),
+        (r"^([\p{L}\p{N}_-]+)\[\[(.+?)\]\]", NodeShape::Subroutine),
+        (r"^([\p{L}\p{N}_-]+)\[\((.+?)\)\]", NodeShape::Cylinder),
+        (r"^([\p{L}\p{N}_-]+)\[/(.+?)\\\]", NodeShape::Trapezoid),
+        (r"^([\p{L}\p{N}_-]+)\[\\(.+?)/\]", NodeShape::TrapezoidAlt),
+        (r"^([\p{L}\p{N}_-]+)>(.+?)\]", NodeShape::Asymmetric),
+        (r"^([\p{L}\p{N}_-]+)\{\{(.+?)\}\}", NodeShape::Hexagon),
+        (r"^([\p{L}\p{N}_-]+)\[(.+?)\]", NodeShape::Rectangle),
+        (r"^([\p{L}\p{N}_-]+)\((.+?)\)", NodeShape::Rounded),
+        (r"^([\p{L}\p{N}_-]+)\{(.+?)\}", NodeShape::Diamond),
+    ]
+    .into_iter()
+    .map(|(pattern, shape)| (Regex::new(pattern).unwrap(), shape))
+    .collect()
+});
+
+/// 节点 label 两侧的双引号：`A["x \"y\""]` -> `x "y"`。
+fn unquote_label(label: &str) -> String {
+    let trimmed = label.trim();
+    if trimmed.len() >= 2 && trimmed.starts_with('"') && trimmed.ends_with('"') {
+        trimmed[1..trimmed.len() - 1].replace("\\\"", "\"")
+    } else {
+        label.to_string()
+    }
+}
+
+fn edge_style(arrow: &str) -> EdgeStyle {
+    match arrow {
+        "-.->" | "-.-" => EdgeStyle::Dotted,
+        "==>" | "===" => EdgeStyle::Thick,
+        _ => EdgeStyle::Solid,
+    }
+}
+
+/// `A --> B & C -->|x| D`：解析一整行的节点组与边链。
+fn parse_edge_line(line: &str, graph: &mut FlowchartGraph, stack: &mut [FlowSubgraph]) {
+    let Some(first) = parse_node_group(line.trim(), graph, stack) else {
+        return;
+    };
+    let mut rest = first.remaining.trim().to_string();
+    let mut sources = first.ids;
+
+    while !rest.is_empty() {
+        let Some(caps) = ARROW.captures(&rest) else {
+            break;
+        };
+        let has_arrow_start = caps.get(1).is_some();
+        let arrow = caps[2].to_string();
+        let label = caps
+            .get(3)
+            .map(|m| m.as_str().trim().to_string())
+            .filter(|label| !label.is_empty());
+        let consumed = caps[0].len();
+        rest = rest[consumed..].trim().to_string();
+
+        let style = edge_style(&arrow);
+        let has_arrow_end = arrow.ends_with('>');
+
+        let Some(targets) = parse_node_group(&rest, graph, stack) else {
+            break;
+        };
+        rest = targets.remaining.trim().to_string();
+
+        for source in &sources {
+            for target in &targets.ids {
+                graph.edges.push(FlowEdge {
+                    source: source.clone(),
+                    target: target.clone(),
+                    label: label.clone(),
+                    style,
+                    has_arrow_start,
+                    has_arrow_end,
+                });
+            }
+        }
+        sources = targets.ids;
+    }
+}
+
+struct NodeGroup {
+    ids: Vec<String>,
+    remaining: String,
+}
+
+/// `A & B & C`
+fn parse_node_group(
+    text: &str,
+    graph: &mut FlowchartGraph,
+    stack: &mut [FlowSubgraph],
+) -> Option<NodeGroup> {
+    let first = parse_node_ref(text, graph, stack)?;
+    let mut ids = vec![first.0];
+    let mut rest = first.1.trim().to_string();
+
+    while let Some(after) = rest.strip_prefix('&') {
+        let after = after.trim().to_string();
+        let Some((id, remaining)) = parse_node_ref(&after, graph, stack) else {
+            rest = after;
+            break;
+        };
+        ids.push(id);
+        rest = remaining.trim().to_string();
+    }
+
+    Some(NodeGroup {
+        ids,
+        remaining: rest,
+    })
+}
+
+/// 单个节点引用（带形状或裸 id），以及可选的 `:::class`。
+fn parse_node_ref(
+    text: &str,
+    graph: &mut FlowchartGraph,
+    stack: &mut [FlowSubgraph],
+) -> Option<(String, String)> {
+    let mut id: Option<String> = None;
+    let mut remaining = text;
+
+    for (regex, shape) in SHAPES.iter() {
+        if let Some(caps) = regex.captures(text) {
+            let node_id = caps[1].to_string();
+            register_node(
+                graph,
+                stack,
+                FlowNode {
+                    id: node_id.clone(),
+                    label: unquote_label(&caps[2]),
+                    shape: *shape,
+                },
+            );
+            remaining = &text[caps[0].len()..];
+            id = Some(node_id);
+            break;
+        }
+    }
+
+    if id.is_none()
+        && let Some(caps) = BARE_ID.captures(text)
+    {
+        let end = bare_id_end(text, caps[1].len());
+        let node_id = text[..end].to_string();
+        if graph.has_node(&node_id) {
+            add_to_current_subgraph(stack, &node_id);
+        } else {
+            register_node(
+                graph,
+                stack,
+                FlowNode {
+                    id: node_id.clone(),
+                    label: node_id.clone(),
+                    shape: NodeShape::Rectangle,
+                },
+            );
+        }
+        remaining = &text[end..];
+        id = Some(node_id);
+    }
+
+    let id = id?;
+    let mut remaining = remaining.to_string();
+    if let Some(caps) = CLASS_SHORTHAND.captures(&remaining) {
+        graph.assign_class(&id, &caps[1]);
+        remaining = remaining[caps[0].len()..].to_string();
+    }
+    Some((id, remaining))
+}
+
+/// 裸 id 的结束位置：`A-->B` 里 `-` 同时是 id 字符和箭头开头，需要回退到箭头前。
+fn bare_id_end(text: &str, matched_len: usize) -> usize {
+    let boundaries: Vec<usize> = (1..=matched_len)
+        .rev()
+        .filter(|&idx| text.is_char_boundary(idx))
+        .collect();
+
+    for &end in &boundaries {
+        let rest = &text[end..];
+        let Some(arrow) = ARROW.find(rest) else {
+            continue;
+        };
+        let after = rest[arrow.end()..].trim_start();
+        if !after.is_empty() && BARE_ID.is_match(after) {
+            return end;
+        }
+    }
+
+    for &end in &boundaries {
+        let rest = &text[end..];
+        let Some(first) = rest.chars().next() else {
+            return end;
+        };
+        if first.is_whitespace() || first == '&' || rest.starts_with(":::") {
+            return end;
+        }
+    }
+
+    matched_len.max(1)
+}
+
+fn register_node(graph: &mut FlowchartGraph, stack: &mut [FlowSubgraph], node: FlowNode) {
+    let id = node.id.clone();
+    graph.insert_node(node);
+    add_to_current_subgraph(stack, &id);
+}
+
+fn add_to_current_subgraph(stack: &mut [FlowSubgraph], id: &str) {
+    if let Some(current) = stack.last_mut()
+        && !current.node_ids.iter().any(|existing| existing == id)
+    {
+        current.node_ids.push(id.to_string());
+    }
+}
+
+// ----------------------------------------------------------------------------
+// stateDiagram
+// ----------------------------------------------------------------------------
+
+static STATE_BLOCK: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^state\s+(?:"([^"]+)"\s+as\s+)?([\p{L}\p{N}_-]+)\s*\{$"#).unwrap());
+static STATE_ALIAS: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^state\s+"([^"]+)"\s+as\s+([\p{L}\p{N}_-]+)\s*$"#).unwrap());
+static STATE_TRANSITION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\[\*\]|[\p{L}\p{N}_-]+)\s*(-->)\s*(\[\*\]|[\p{L}\p{N}_-]+)(?:\s*:\s*(.+))?$")
+        .unwrap()
+});
+static STATE_DESCRIPTION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([\p{L}\p{N}_-]+)\s*:\s*(.+)$").unwrap());
+
+fn parse_state_diagram(lines: &[&str]) -> FlowchartGraph {
+    let mut graph = FlowchartGraph::new(Direction::Td);
+    let mut stack: Vec<FlowSubgraph> = Vec::new();
+    let mut start_count = 0;
+    let mut end_count = 0;
+
+    for &line in &lines[1..] {
+        if let Some(caps) = DIRECTION.captures(line) {
+            let direction = Direction::parse(&caps[1]);
+            match stack.last_mut() {
+                Some(current) => current.direction = direction,
+                None => graph.direction = direction.expect("direction regex 只匹配合法方向"),
+            }
+            continue;
+        }
+        if let Some(caps) = STATE_BLOCK.captures(line) {
+            let id = caps[2].to_string();
+            let label = caps
+                .get(1)
+                .map_or_else(|| id.clone(), |m| m.as_str().to_string());
+            stack.push(FlowSubgraph {
+                id,
+                label,
+                node_ids: Vec::new(),
+                children: Vec::new(),
+                direction: None,
+            });
+            continue;
+        }
+        if line == "}" {
+            if let Some(done) = stack.pop() {
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(done),
+                    None => graph.subgraphs.push(done),
+                }
+            }
+            continue;
+        }
+        if let Some(caps) = STATE_ALIAS.captures(line) {
+            register_node(
+                &mut graph,
+                &mut stack,
+                FlowNode {
+                    id: caps[2].to_string(),
+                    label: caps[1].to_string(),
+                    shape: NodeShape::Rounded,
+                },
+            );
+            continue;
+        }
+        if let Some(caps) = STATE_TRANSITION.captures(line) {
+            let mut source = caps[1].to_string();
+            let mut target = caps[3].to_string();
+            let label = caps
+                .get(4)
+                .map(|m| m.as_str().trim().to_string())
+                .filter(|label| !label.is_empty());
+
+            if source == "[*]" {
+                start_count += 1;
+                source = pseudo_state_id("_start", start_count);
+                register_pseudo_state(&mut graph, &mut stack, &source, NodeShape::StateStart);
+            } else {
+                ensure_state(&mut graph, &mut stack, &source);
+            }
+            if target == "[*]" {
+                end_count += 1;
+                target = pseudo_state_id("_end", end_count);
+                register_pseudo_state(&mut graph, &mut stack, &target, NodeShape::StateEnd);
+            } else {
+                ensure_state(&mut graph, &mut stack, &target);
+            }
+
+            graph.edges.push(FlowEdge {
+                source,
+                target,
+                label,
+                style: EdgeStyle::Solid,
+                has_arrow_start: false,
+                has_arrow_end: true,
+            });
+            continue;
+        }
+        if let Some(caps) = STATE_DESCRIPTION.captures(line) {
+            register_node(
+                &mut graph,
+                &mut stack,
+                FlowNode {
+                    id: caps[1].to_string(),
+                    label: caps[2].trim().to_string(),
+                    shape: NodeShape::Rounded,
+                },
+            );
+        }
+    }
+
+    graph
+}
+
+/// `_start` / `_start2` / `_start3` ...
+fn pseudo_state_id(prefix: &str, count: usize) -> String {
+    if count > 1 {
+        format!("{prefix}{count}")
+    } else {
+        prefix.to_string()
+    }
+}
+
+fn register_pseudo_state(
+    graph: &mut FlowchartGraph,
+    stack: &mut [FlowSubgraph],
+    id: &str,
+    shape: NodeShape,
+) {
+    register_node(
+        graph,
+        stack,
+        FlowNode {
+            id: id.to_string(),
+            label: String::new(),
+            shape,
+        },
+    );
+}
+
+/// 转移里引用到的状态：首次出现时按 id 作 label 登记，否则只加进当前复合状态。
+fn ensure_state(graph: &mut FlowchartGraph, stack: &mut [FlowSubgraph], id: &str) {
+    if graph.has_node(id) {
+        add_to_current_subgraph(stack, id);
+    } else {
+        register_node(
+            graph,
+            stack,
+            FlowNode {
+                id: id.to_string(),
+                label: id.to_string(),
+                shape: NodeShape::Rounded,
+            },
+        );
+    }
+}
@@ -0,0 +1,1184 @@
+// ============================================================================
+// 绘制：node box / 边（线段、拐角、箭头、起点 junction）/ label / subgraph
+//
+// 对齐 JS bundle 的 `drawGraph`：
+// - 每类元素先画到独立图层，再按固定顺序 merge（线段 -> 拐角 -> 箭头 -> 起点 junction）；
+// - label 在所有线条之后放置，并避开已有线条/箭头（relaxed + Unicode 下同向平行边的 label 会竖向堆叠）。
+// ============================================================================
+
+use super::{
+    DOWN, Edge, Graph, LEFT, LOWER_LEFT, LOWER_RIGHT, MIDDLE, Point, RIGHT, UP, UPPER_LEFT,
+    UPPER_RIGHT, direction_between,
+};
+use crate::native::canvas::{self, Canvas};
+use crate::native::text::display_width;
+use std::collections::HashMap;
+
+// ----------------------------------------------------------------------------
+// 坐标换算
+// ----------------------------------------------------------------------------
+
+/// 画布坐标下的矩形（闭区间）。
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Bounds {
+    pub(super) min_x: i32,
+    pub(super) min_y: i32,
+    pub(super) max_x: i32,
+    pub(super) max_y: i32,
+}
+
+/// grid 坐标的左上角（始终按列宽/行高现算，走线阶段还没有前缀表）。
+fn grid_origin(graph: &Graph, coord: Point) -> Point {
+    let x: i32 = (0..coord.x).map(|x| graph.column_width_at(x)).sum();
+    let y: i32 = (0..coord.y).map(|y| graph.row_height_at(y)).sum();
+    Point::new(graph.offset_x + x, graph.offset_y + y)
+}
+
+/// 节点 box 占用的画布范围（节点占 2 列 2 行的宽高）。
+pub(super) fn node_bounds(graph: &Graph, node: usize) -> Option<Bounds> {
+    let coord = graph.nodes[node].grid_coord?;
+    let width = graph.column_width_at(coord.x) + graph.column_width_at(coord.x + 1);
+    let height = graph.row_height_at(coord.y) + graph.row_height_at(coord.y + 1);
+    let origin = grid_origin(graph, coord);
+    Some(Bounds {
+        min_x: origin.x,
+        min_y: origin.y,
+        max_x: origin.x + width,
+        max_y: origin.y + height,
+    })
+}
+
+/// 边上某个 grid 点对应的画布坐标：cell 中心，首尾段按端口偏移对齐（JS `gridToDrawingCoordForEdge`）。
+pub(super) fn edge_point(graph: &Graph, edge: &Edge, coord: Point) -> Point {
+    let start = super::layout::line_start(graph, coord);
+    let width = graph.column_width_at(coord.x);
+    let height = graph.row_height_at(coord.y);
+    let mut dx = width / 2;
+    let mut dy = height / 2;
+    if edge.path.len() >= 2 {
+        let first = edge.path[0];
+        let second = edge.path[1];
+        let last = edge.path[edge.path.len() - 1];
+        let before_last = edge.path[edge.path.len() - 2];
+        if coord == first || coord == second {
+            if first.x == second.x
+                && let Some(offset) = edge.start_port_offset_x
+            {
+                dx = offset;
+            }
+            if first.y == second.y
+                && let Some(offset) = edge.start_port_offset_y
+            {
+                dy = offset;
+            }
+        }
+        if coord == last || coord == before_last {
+            if last.x == before_last.x
+                && let Some(offset) = edge.end_port_offset_x
+            {
+                dx = offset;
+            }
+            if last.y == before_last.y
+                && let Some(offset) = edge.end_port_offset_y
+            {
+                dy = offset;
+            }
+        }
+    }
+    dx = dx.max(0);
+    dy = dy.max(0);
+    if width > 0 {
+        dx = dx.min(width - 1);
+    }
+    if height > 0 {
+        dy = dy.min(height - 1);
+    }
+    Point::new(start.x + dx + graph.offset_x, start.y + dy + graph.offset_y)
+}
+
+fn edge_points(graph: &Graph, edge: &Edge, coords: &[Point]) -> Vec<Point> {
+    coords
+        .iter()
+        .map(|&coord| edge_point(graph, edge, coord))
+        .collect()
+}
+
+fn box_of(graph: &Graph, node: usize) -> Option<(Point, i32, i32)> {
+    let node = &graph.nodes[node];
+    let origin = node.drawing_coord?;
+    let (width, height) = node.drawing.as_ref()?.max();
+    Some((origin, width, height))
+}
+
+/// 起点贴到源节点 box 的边上（JS `clampToSourceBox`）。
+pub(super) fn source_port(graph: &Graph, edge: &Edge, dir: Point, point: Point) -> Point {
+    let Some((origin, width, height)) = box_of(graph, edge.from) else {
+        return point;
+    };
+    let (left, right, top, bottom) = (origin.x, origin.x + width, origin.y, origin.y + height);
+    match dir {
+        LEFT => Point::new(left, point.y.clamp(top, bottom)),
+        RIGHT => Point::new(right, point.y.clamp(top, bottom)),
+        UP => Point::new(point.x.clamp(left, right), top),
+        DOWN => Point::new(point.x.clamp(left, right), bottom),
+        _ => point,
+    }
+}
+
+/// 终点停在目标节点 box 外一格（箭头所在位置，JS `clampToTargetBox`）。
+pub(super) fn target_port(graph: &Graph, edge: &Edge, dir: Point, point: Point) -> Point {
+    let Some((origin, width, height)) = box_of(graph, edge.to) else {
+        return point;
+    };
+    let (left, right, top, bottom) = (origin.x, origin.x + width, origin.y, origin.y + height);
+    match dir {
+        LEFT => Point::new(right + 1, point.y.clamp(top, bottom)),
+        RIGHT => Point::new(left - 1, point.y.clamp(top, bottom)),
+        UP => Point::new(point.x.clamp(left, right), bottom + 1),
+        DOWN => Point::new(point.x.clamp(left, right), top - 1),
+        _ => point,
+    }
+}
+
+// ----------------------------------------------------------------------------
+// label 所在线段（布局阶段：决定列宽）
+// ----------------------------------------------------------------------------
+
+/// 线段覆盖的列宽之和。
+fn span_width(graph: &Graph, line: [Point; 2]) -> i32 {
+    let (min_x, max_x) = (line[0].x.min(line[1].x), line[0].x.max(line[1].x));
+    (min_x..=max_x).map(|x| graph.column_width_at(x)).sum()
+}
+
+/// label 在画布上占的一行区间。
+#[derive(Debug, Clone, Copy)]
+struct LabelSpan {
+    y: i32,
+    start_x: i32,
+    end_x: i32,
+}
+
+impl LabelSpan {
+    fn overlaps(&self, other: &LabelSpan) -> bool {
+        self.y == other.y && !(self.end_x < other.start_x || other.end_x < self.start_x)
+    }
+
+    fn hits(&self, bounds: &Bounds) -> bool {
+        if self.y < bounds.min_y || self.y > bounds.max_y {
+            return false;
+        }
+        !(self.end_x < bounds.min_x || bounds.max_x < self.start_x)
+    }
+}
+
+fn label_span(graph: &Graph, edge: &Edge, line: [Point; 2], text: &str) -> Option<LabelSpan> {
+    let width = display_width(text);
+    if width <= 0 {
+        return None;
+    }
+    let a = edge_point(graph, edge, line[0]);
+    let b = edge_point(graph, edge, line[1]);
+    let (min_x, max_x) = (a.x.min(b.x), a.x.max(b.x));
+    let (min_y, max_y) = (a.y.min(b.y), a.y.max(b.y));
+    let mid_x = min_x + (max_x - min_x) / 2;
+    let mid_y = min_y + (max_y - min_y) / 2;
+    let start_x = mid_x - width / 2;
+    Some(LabelSpan {
+        y: mid_y,
+        start_x,
+        end_x: start_x + width - 1,
+    })
+}
+
+/// 选出 label 所在的线段，并把所在列撑宽到能放下 label（JS `calculateLineLabel`）。
+pub(super) fn assign_label_line(graph: &mut Graph, edge_index: usize) {
+    let edge = &graph.edges[edge_index];
+    if edge.text.is_empty() || edge.path.len() < 2 {
+        return;
+    }
+    let label_width = display_width(&edge.text);
+    let other_labels: Vec<LabelSpan> = graph
+        .edges
+        .iter()
+        .filter(|other| !other.text.is_empty() && other.label_line.len() >= 2)
+        .filter_map(|other| {
+            label_span(
+                graph,
+                other,
+                [other.label_line[0], other.label_line[1]],
+                &other.text,
+            )
+        })
+        .collect();
+    let node_boxes: Vec<Bounds> = (0..graph.nodes.len())
+        .filter_map(|node| node_bounds(graph, node))
+        .collect();
+
+    let mut previous = edge.path[0];
+    let mut chosen = None;
+    // 退路 1：第一条够宽的线段，否则最宽的线段
+    let mut widest = [previous, edge.path[1]];
+    let mut widest_width = 0;
+    let mut found_wide_enough = false;
+    // 退路 2：不和其它 label / 节点冲突的最宽线段
+    let mut clear_widest: Option<[Point; 2]> = None;
+    let mut clear_widest_width = -1;
+    for &point in &edge.path[1..] {
+        let line = [previous, point];
+        let width = span_width(graph, line);
+        if !found_wide_enough {
+            if width >= label_width {
+                widest = line;
+                found_wide_enough = true;
+            } else if width > widest_width {
+                widest_width = width;
+                widest = line;
+            }
+        }
+        let span = label_span(graph, edge, line, &edge.text);
+        let hits_label = span.is_some_and(|span| other_labels.iter().any(|o| o.overlaps(&span)));
+        let hits_node = span.is_some_and(|span| node_boxes.iter().any(|b| span.hits(b)));
+        if !hits_label && !hits_node {
+            if width > clear_widest_width {
+                clear_widest_width = width;
+                clear_widest = Some(line);
+            }
+            if width >= label_width {
+                chosen = Some(line);
+                break;
+            }
+        }
+        previous = point;
+    }
+    let line = chosen.or(clear_widest).unwrap_or(widest);
+
+    let (min_x, max_x) = (line[0].x.min(line[1].x), line[0].x.max(line[1].x));
+    let mut column = min_x + (max_x - min_x) / 2;
+    if graph.config.relaxed_unicode() {
+        // 尽量把 label 撑宽的列放在节点列之外，避免把节点 box 一起撑大
+        let node_columns: Vec<i32> = graph
+            .nodes
+            .iter()
+            .filter_map(|node| node.grid_coord)
+            .flat_map(|coord| [coord.x, coord.x + 1, coord.x + 2])
+            .collect();
+        if node_columns.contains(&column) {
+            for step in 1..=max_x - min_x {
+                let left = column - step;
+                if left >= min_x && left <= max_x && !node_columns.contains(&left) {
+                    column = left;
+                    break;
+                }
+                let right = column + step;
+                if right >= min_x && right <= max_x && !node_columns.contains(&right) {
+                    column = right;
+                    break;
+                }
+            }
+        }
+    }
+    let current = graph.column_width_at(column);
+    let needed = label_width + 2;
+    let available = span_width(graph, line);
+    if available < needed {
+        graph
+            .column_width
+            .insert(column, current + needed - available);
+    }
+    graph.edges[edge_index].label_line = line.to_vec();
+}
+
+// ----------------------------------------------------------------------------
+// node box / subgraph 框
+// ----------------------------------------------------------------------------
+
+fn draw_rect(canvas: &mut Canvas, width: i32, height: i32, use_ascii: bool) {
+    let (horizontal, vertical, corners) = if use_ascii {
+        ('-', '|', ['+', '+', '+', '+'])
+    } else {
+        ('─', '│', ['┌', '┐', '└', '┘'])
+    };
+    for x in 1..width {
+        canvas.set(x, 0, horizontal);
+        canvas.set(x, height, horizontal);
+    }
+    for y in 1..height {
+        canvas.set(0, y, vertical);
+        canvas.set(width, y, vertical);
+    }
+    canvas.set(0, 0, corners[0]);
+    canvas.set(width, 0, corners[1]);
+    canvas.set(0, height, corners[2]);
+    canvas.set(width, height, corners[3]);
+}
+
+/// 节点 box：2 列 2 行大小，label 居中（JS `drawBox`）。
+pub(super) fn node_box(graph: &Graph, node: usize) -> Canvas {
+    let node = &graph.nodes[node];
+    let coord = node.grid_coord.unwrap_or_default();
+    let width = graph.column_width_at(coord.x) + graph.column_width_at(coord.x + 1);
+    let height = graph.row_height_at(coord.y) + graph.row_height_at(coord.y + 1);
+    let mut canvas = Canvas::new(width.max(0), height.max(0));
+    draw_rect(&mut canvas, width, height, graph.config.use_ascii);
+
+    let label_width = display_width(&node.display_label);
+    let x = width / 2 - (label_width + 1) / 2 + 1;
+    canvas.draw_text(x, height / 2, &node.display_label);
+    canvas
+}
+
+fn subgraph_frame(graph: &Graph, subgraph: usize) -> Canvas {
+    let sg = &graph.subgraphs[subgraph];
+    let width = sg.max_x - sg.min_x;
+    let height = sg.max_y - sg.min_y;
+    if width <= 0 || height <= 0 {
+        return Canvas::new(0, 0);
+    }
+    let mut canvas = Canvas::new(width, height);
+    draw_rect(&mut canvas, width, height, graph.config.use_ascii);
+    canvas
+}
+
+fn subgraph_label(graph: &Graph, subgraph: usize) -> (Canvas, Point) {
+    let sg = &graph.subgraphs[subgraph];
+    let width = sg.max_x - sg.min_x;
+    let height = sg.max_y - sg.min_y;
+    if width <= 0 || height <= 0 {
+        return (Canvas::new(0, 0), Point::default());
+    }
+    let mut canvas = Canvas::new(width, height);
+    let x = (width / 2 - display_width(&sg.name) / 2).max(1);
+    canvas.draw_text(x, 1, &sg.name);
+    (canvas, Point::new(sg.min_x, sg.min_y))
+}
+
+fn subgraph_depth(graph: &Graph, subgraph: usize) -> usize {
+    let mut depth = 0;
+    let mut current = graph.subgraphs[subgraph].parent;
+    while let Some(parent) = current {
+        depth += 1;
+        current = graph.subgraphs[parent].parent;
+    }
+    depth
+}
+
+// ----------------------------------------------------------------------------
+// 边：线段 / 起点 junction / 箭头 / 拐角
+// ----------------------------------------------------------------------------
+
+/// 从 `from` 画直线或 45° 斜线到 `to`；`skip_start` / `extend_end` 控制两端收缩（JS `drawLine`）。
+fn draw_line(
+    canvas: &mut Canvas,
+    from: Point,
+    to: Point,
+    skip_start: i32,
+    extend_end: i32,
+    use_ascii: bool,
+) -> Vec<Point> {
+    let (max_x, max_y) = canvas.max();
+    let (right, bottom) = (from.x.max(to.x), from.y.max(to.y));
+    if right > max_x || bottom > max_y {
+        canvas.grow(right, bottom);
+    }
+    let (horizontal, vertical, backslash, slash) = if use_ascii {
+        ('-', '|', '\\', '/')
+    } else {
+        ('─', '│', '╲', '╱')
+    };
+    let (n, o) = (skip_start, extend_end);
+    let mut points = Vec::new();
+    let mut put = |x: i32, y: i32, ch: char| {
+        points.push(Point::new(x, y));
+        canvas.set(x, y, ch);
+    };
+    match direction_between(from, to) {
+        UP => {
+            let mut y = from.y - n;
+            while y >= to.y - o {
+                put(from.x, y, vertical);
+                y -= 1;
+            }
+        }
+        DOWN => {
+            let mut y = from.y + n;
+            while y <= to.y + o {
+                put(from.x, y, vertical);
+                y += 1;
+            }
+        }
+        LEFT => {
+            let mut x = from.x - n;
+            while x >= to.x - o {
+                put(x, from.y, horizontal);
+                x -= 1;
+            }
+        }
+        RIGHT => {
+            let mut x = from.x + n;
+            while x <= to.x + o {
+                put(x, from.y, horizontal);
+                x += 1;
+            }
+        }
+        UPPER_LEFT => {
+            let (mut x, mut y) = (from.x, from.y - n);
+            while x >= to.x - o && y >= to.y - o {
+                put(x, y, backslash);
+                x -= 1;
+                y -= 1;
+            }
+        }
+        UPPER_RIGHT => {
+            let (mut x, mut y) = (from.x, from.y - n);
+            while x <= to.x + o && y >= to.y - o {
+                put(x, y, slash);
+                x += 1;
+                y -= 1;
+            }
+        }
+        LOWER_LEFT => {
+            let (mut x, mut y) = (from.x, from.y + n);
+            while x >= to.x - o && y <= to.y + o {
+                put(x, y, slash);
+                x -= 1;
+                y += 1;
+            }
+        }
+        LOWER_RIGHT => {
+            let (mut x, mut y) = (from.x, from.y + n);
+            while x <= to.x + o && y <= to.y + o {
+                put(x, y, backslash);
+                x += 1;
+                y += 1;
+            }
+        }
+        _ => {}
+    }
+    points
+}
+
+/// 线段图层 + 每段画到的点 + 每段的 grid 方向（JS `drawPath`）。
+fn draw_path(graph: &Graph, edge: &Edge) -> (Canvas, Vec<Vec<Point>>, Vec<Point>) {
+    let mut canvas = graph.canvas.blank_like();
+    let mut lines = Vec::new();
+    let mut dirs = Vec::new();
+    let mut previous = edge.path[0];
+    for &next in &edge.path[1..] {
+        let from = edge_point(graph, edge, previous);
+        let to = edge_point(graph, edge, next);
+        if from == to {
+            previous = next;
+            continue;
+        }
+        let dir = direction_between(previous, next);
+        let mut line = draw_line(&mut canvas, from, to, 1, -1, graph.config.use_ascii);
+        if line.is_empty() {
+            line.push(from);
+        }
+        lines.push(line);
+        dirs.push(dir);
+        previous = next;
+    }
+    (canvas, lines, dirs)
+}
+
+#[derive(Clone, Copy)]
+enum Horizontal {
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy)]
+enum Vertical {
+    Up,
+    Down,
+}
+
+fn elbow(horizontal: Horizontal, vertical: Vertical, use_ascii: bool) -> char {
+    if use_ascii {
+        return '+';
+    }
+    match (horizontal, vertical) {
+        (Horizontal::Left, Vertical::Down) => '┐',
+        (Horizontal::Left, Vertical::Up) => '┘',
+        (Horizontal::Right, Vertical::Down) => '┌',
+        (Horizontal::Right, Vertical::Up) => '└',
+    }
+}
+
+/// 端点与 box 边之间的连接线（必要时带一个或两个拐角，JS `drawConnector`）。
+fn draw_connector(canvas: &mut Canvas, from: Point, to: Point, dir: Point, use_ascii: bool) {
+    let (right, bottom) = (from.x.max(to.x), from.y.max(to.y));
+    if right >= 0 && bottom >= 0 {
+        canvas.grow(right, bottom);
+    }
+    let (horizontal, vertical) = if use_ascii {
+        ('-', '|')
+    } else {
+        ('─', '│')
+    };
+    let hline = |canvas: &mut Canvas, y: i32, a: i32, b: i32| {
+        for x in a.min(b)..=a.max(b) {
+            canvas.set(x, y, horizontal);
+        }
+    };
+    let vline = |canvas: &mut Canvas, x: i32, a: i32, b: i32| {
+        for y in a.min(b)..=a.max(b) {
+            canvas.set(x, y, vertical);
+        }
+    };
+    let side_x = |x: i32, reference: i32| {
+        if x < reference {
+            Horizontal::Left
+        } else {
+            Horizontal::Right
+        }
+    };
+    let side_y = |y: i32, reference: i32| {
+        if y < reference {
+            Vertical::Up
+        } else {
+            Vertical::Down
+        }
+    };
+
+    if dir == LEFT || dir == RIGHT {
+        if from.y == to.y {
+            hline(canvas, from.y, from.x, to.x);
+            return;
+        }
+        let corner = Point::new(from.x, to.y);
+        vline(canvas, from.x, from.y, corner.y);
+        hline(canvas, corner.y, corner.x, to.x);
+        let ch = elbow(side_x(to.x, corner.x), side_y(from.y, corner.y), use_ascii);
+        canvas.set(corner.x, corner.y, ch);
+        return;
+    }
+    if dir == UP || dir == DOWN {
+        if from.x == to.x {
+            vline(canvas, from.x, from.y, to.y);
+            return;
+        }
+        if from.y == to.y {
+            let y = if dir == DOWN { to.y - 1 } else { to.y + 1 };
+            let first = Point::new(from.x, y);
+            let second = Point::new(to.x, y);
+            vline(canvas, from.x, from.y, first.y);
+            hline(canvas, y, from.x, to.x);
+            vline(canvas, to.x, second.y, to.y);
+            let ch = elbow(side_x(to.x, first.x), side_y(from.y, first.y), use_ascii);
+            canvas.set(first.x, first.y, ch);
+            let ch = elbow(side_x(from.x, second.x), side_y(to.y, second.y), use_ascii);
+            canvas.set(second.x, second.y, ch);
+            return;
+        }
+        let corner = Point::new(to.x, from.y);
+        hline(canvas, from.y, from.x, corner.x);
+        vline(canvas, corner.x, corner.y, to.y);
+        let ch = elbow(side_x(from.x, corner.x), side_y(to.y, corner.y), use_ascii);
+        canvas.set(corner.x, corner.y, ch);
+    }
+}
+
+/// 起点处的 box junction（`┴┬┤├`，仅 Unicode，JS `drawBoxStart`）。
+fn draw_box_start(graph: &Graph, edge: &Edge) -> Canvas {
+    let mut canvas = graph.canvas.blank_like();
+    if graph.config.use_ascii || edge.path.len() < 2 {
+        return canvas;
+    }
+    let dir = direction_between(edge.path[0], edge.path[1]);
+    let start = edge_point(graph, edge, edge.path[0]);
+    let port = source_port(graph, edge, dir, start);
+    draw_connector(&mut canvas, start, port, dir, false);
+    let junction = match dir {
+        UP => Some('┴'),
+        DOWN => Some('┬'),
+        LEFT => Some('┤'),
+        RIGHT => Some('├'),
+        _ => None,
+    };
+    if let Some(ch) = junction {
+        canvas.set(port.x, port.y, ch);
+    }
+    canvas
+}
+
+fn arrow_char(dir: Point, use_ascii: bool) -> Option<char> {
+    let ch = if use_ascii {
+        match dir {
+            UP => '^',
+            DOWN => 'v',
+            LEFT => '<',
+            RIGHT => '>',
+            _ => return None,
+        }
+    } else {
+        match dir {
+            UP => '▲',
+            DOWN => '▼',
+            LEFT => '◄',
+            RIGHT => '►',
+            UPPER_RIGHT => '◥',
+            UPPER_LEFT => '◤',
+            LOWER_RIGHT => '◢',
+            LOWER_LEFT => '◣',
+            _ => return None,
+        }
+    };
+    Some(ch)
+}
+
+/// 箭头：最后一段的方向决定字符，位置在目标 box 外一格（JS `drawArrowHead`）。
+fn draw_arrow_head(graph: &Graph, edge: &Edge, last_line: &[Point], last_dir: Point) -> Canvas {
+    let mut canvas = graph.canvas.blank_like();
+    let (Some(&first), Some(&last)) = (last_line.first(), last_line.last()) else {
+        return canvas;
+    };
+    let use_ascii = graph.config.use_ascii;
+    let mut dir = direction_between(first, last);
+    if last_line.len() == 1 || dir == MIDDLE {
+        dir = last_dir;
+    }
+    let ch = arrow_char(dir, use_ascii)
+        .or_else(|| arrow_char(last_dir, use_ascii))
+        .unwrap_or(if use_ascii { '*' } else { '●' });
+    let tip = target_port(graph, edge, dir, last);
+    draw_connector(&mut canvas, last, tip, dir, use_ascii);
+    canvas.set(tip.x, tip.y, ch);
+    canvas
+}
+
+/// 路径拐点上的拐角字符（JS `drawCorners`）。
+fn draw_corners(graph: &Graph, edge: &Edge) -> Canvas {
+    let mut canvas = graph.canvas.blank_like();
+    let path = &edge.path;
+    for i in 1..path.len().saturating_sub(1) {
+        let point = edge_point(graph, edge, path[i]);
+        let incoming = direction_between(path[i - 1], path[i]);
+        let outgoing = direction_between(path[i], path[i + 1]);
+        let ch = if graph.config.use_ascii {
+            '+'
+        } else {
+            match (incoming, outgoing) {
+                (RIGHT, DOWN) | (UP, LEFT) => '┐',
+                (RIGHT, UP) | (DOWN, LEFT) => '┘',
+                (LEFT, DOWN) | (UP, RIGHT) => '┌',
+                (LEFT, UP) | (DOWN, RIGHT) => '└',
+                _ => '+',
+            }
+        };
+        let (max_x, max_y) = canvas.max();
+        if point.x > max_x || point.y > max_y {
+            canvas.grow(point.x.max(max_x), point.y.max(max_y));
+        }
+        canvas.set(point.x, point.y, ch);
+    }
+    canvas
+}
+
+/// 拐点处的方向位（供 `canvas::resolve_crossings` 判断 `┼` 实际连了哪几个方向）。
+pub(super) fn bend_junctions(graph: &Graph, edge_index: usize) -> HashMap<(i32, i32), u8> {
+    let edge = &graph.edges[edge_index];
+    let mut bends = HashMap::new();
+    if edge.path.len() < 3 {
+        return bends;
+    }
+    let incoming_bit = |dir: Point| match dir {
+        UP => 8,
+        DOWN => 4,
+        LEFT => 2,
+        RIGHT => 1,
+        _ => 0,
+    };
+    let outgoing_bit = |dir: Point| match dir {
+        UP => 4,
+        DOWN => 8,
+        LEFT => 1,
+        RIGHT => 2,
+        _ => 0,
+    };
+    for i in 1..edge.path.len() - 1 {
+        let (previous, point, next) = (edge.path[i - 1], edge.path[i], edge.path[i + 1]);
+        let incoming = direction_between(previous, point);
+        let outgoing = direction_between(point, next);
+        if incoming == outgoing || incoming == MIDDLE || outgoing == MIDDLE {
+            continue;
+        }
+        let at = edge_point(graph, edge, point);
+        let mask = incoming_bit(incoming) | outgoing_bit(outgoing);
+        if mask != 0 {
+            *bends.entry((at.x, at.y)).or_insert(0) |= mask;
+        }
+    }
+    bends
+}
+
+// ----------------------------------------------------------------------------
+// label 放置
+// ----------------------------------------------------------------------------
+
+fn is_unicode_arrow(ch: char) -> bool {
+    matches!(ch, '▲' | '▼' | '◄' | '►' | '◥' | '◤' | '◢' | '◣' | '●')
+}
+
+fn is_ascii_arrow(ch: char) -> bool {
+    matches!(ch, '^' | 'v' | '<' | '>' | '*')
+}
+
+fn is_unicode_junction(ch: char) -> bool {
+    matches!(
+        ch,
+        '┼' | '┬' | '┴' | '├' | '┤' | '┌' | '┐' | '└' | '┘' | '╴' | '╵' | '╶' | '╷'
+    )
+}
+
+fn is_vertical_line(ch: char, use_ascii: bool) -> bool {
+    if use_ascii {
+        matches!(ch, '|' | '+')
+    } else {
+        matches!(
+            ch,
+            '│' | '┼' | '┬' | '┴' | '├' | '┤' | '┌' | '┐' | '└' | '┘' | '╷' | '╵'
+        )
+    }
+}
+
+fn is_horizontal_line(ch: char, use_ascii: bool) -> bool {
+    if use_ascii {
+        matches!(ch, '-' | '+')
+    } else {
+        matches!(
+            ch,
+            '─' | '┼' | '┬' | '┴' | '├' | '┤' | '┌' | '┐' | '└' | '┘' | '╴' | '╶'
+        )
+    }
+}
+
+/// 在 `(x, y)` 写字会不会切断一条穿过的线。
+fn cuts_through_line(canvas: &Canvas, x: i32, y: i32, use_ascii: bool) -> bool {
+    let (max_x, max_y) = canvas.max();
+    if x < 0 || y < 0 || x > max_x || y > max_y {
+        return false;
+    }
+    let ch = canvas.get(x, y);
+    let left = if x > 0 { canvas.get(x - 1, y) } else { ' ' };
+    let right = if x < max_x { canvas.get(x + 1, y) } else { ' ' };
+    let up = if y > 0 { canvas.get(x, y - 1) } else { ' ' };
+    let down = if y < max_y { canvas.get(x, y + 1) } else { ' ' };
+    let vertical_through = is_vertical_line(up, use_ascii) && is_vertical_line(down, use_ascii);
+    let horizontal_through =
+        is_horizontal_line(left, use_ascii) && is_horizontal_line(right, use_ascii);
+    ((ch == ' ' || is_horizontal_line(ch, use_ascii)) && vertical_through)
+        || ((ch == ' ' || is_vertical_line(ch, use_ascii)) && horizontal_through)
+}
+
+/// label 的某一格是否不能写（箭头、junction、穿过的线、紧贴其它文字）。
+fn label_cell_blocked(canvas: &Canvas, x: i32, y: i32, use_ascii: bool) -> bool {
+    let (max_x, max_y) = canvas.max();
+    if x < 0 || y < 0 || x > max_x || y > max_y {
+        return false;
+    }
+    let ch = canvas.get(x, y);
+    if use_ascii {
+        if is_ascii_arrow(ch) || ch == '+' {
+            return true;
+        }
+    } else if is_unicode_arrow(ch) || is_unicode_junction(ch) {
+        return true;
+    }
+    if cuts_through_line(canvas, x, y, use_ascii)
+        || (!use_ascii
+            && ch != ' '
+            && !is_vertical_line(ch, use_ascii)
+            && !is_horizontal_line(ch, use_ascii))
+    {
+        return true;
+    }
+    if !use_ascii {
+        let left = if x > 0 { canvas.get(x - 1, y) } else { ' ' };
+        let right = if x < max_x { canvas.get(x + 1, y) } else { ' ' };
+        let is_text = |c: char| {
+            c != ' '
+                && !is_unicode_arrow(c)
+                && !is_unicode_junction(c)
+                && !is_vertical_line(c, use_ascii)
+                && !is_horizontal_line(c, use_ascii)
+        };
+        if (ch == ' ' || is_vertical_line(ch, use_ascii) || is_horizontal_line(ch, use_ascii))
+            && (is_text(left) || is_text(right))
+        {
+            return true;
+        }
+    }
+    false
+}
+
+fn hits_obstacle(y: i32, start_x: i32, end_x: i32, obstacles: &[Point]) -> bool {
+    obstacles
+        .iter()
+        .any(|p| p.y == y && p.x >= start_x && p.x <= end_x)
+}
+
+fn row_blocked(canvas: &Canvas, y: i32, start_x: i32, end_x: i32, use_ascii: bool) -> bool {
+    (start_x..=end_x).any(|x| label_cell_blocked(canvas, x, y, use_ascii))
+}
+
+/// 从 `desired` 开始左右交替找第一个合法位置；都不合法时返回 `desired`。
+fn search_start_x(desired: i32, min: i32, max: i32, is_valid: impl Fn(i32) -> bool) -> i32 {
+    if is_valid(desired) {
+        return desired;
+    }
+    for step in 1..=(max - min).max(0) {
+        let left = desired - step;
+        if left >= min && is_valid(left) {
+            return left;
+        }
+        let right = desired + step;
+        if right <= max && is_valid(right) {
+            return right;
+        }
+    }
+    desired
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LabelPlacement {
+    start_x: i32,
+    y: i32,
+    width: i32,
+}
+
+/// 在 `line`（画布坐标）附近给 label 找位置；`collision` 是用来检测冲突的画布（JS `drawTextOnLine`）。
+fn place_label(
+    line: &[Point],
+    text: &str,
+    obstacles: &[Point],
+    collision: &Canvas,
+    use_ascii: bool,
+    vertical_only_stack: bool,
+) -> Option<LabelPlacement> {
+    if line.len() < 2 {
+        return None;
+    }
+    let (min_x, max_x) = (line[0].x.min(line[1].x), line[0].x.max(line[1].x));
+    let (min_y, max_y) = (line[0].y.min(line[1].y), line[0].y.max(line[1].y));
+    let mid_x = min_x + (max_x - min_x) / 2;
+    let y = min_y + (max_y - min_y) / 2;
+    let width = display_width(text);
+    let mut start_x = mid_x - width / 2;
+
+    let (canvas_max_x, canvas_max_y) = collision.max();
+    let lower = 0;
+    let upper = lower.max(canvas_max_x - width + 1);
+    start_x = start_x.max(lower).min(upper);
+    let placed = |start_x: i32, y: i32| LabelPlacement { start_x, y, width };
+
+    if vertical_only_stack {
+        let end_x = start_x + width - 1;
+        let free = |row: i32| {
+            !(hits_obstacle(row, start_x, end_x, obstacles)
+                || row_blocked(collision, row, start_x, end_x, use_ascii))
+        };
+        if free(y) {
+            return Some(placed(start_x, y));
+        }
+        for step in 1..=y.max(canvas_max_y - y) {
+            let above = y - step;
+            if above >= 0 && free(above) {
+                return Some(placed(start_x, above));
+            }
+            let below = y + step;
+            if below <= canvas_max_y && free(below) {
+                return Some(placed(start_x, below));
+            }
+        }
+        return use_ascii.then(|| placed(start_x, y));
+    }
+
+    let horizontal = line[0].y == line[1].y;
+    let fits_on_line = horizontal && max_x - width + 1 >= min_x;
+    let (min_start, max_start) = if fits_on_line {
+        (lower.max(min_x), upper.min(max_x - width + 1))
+    } else {
+        (lower, upper)
+    };
+    if max_start >= min_start {
+        start_x = start_x.clamp(min_start, max_start);
+        let free = |start_x: i32| {
+            let end_x = start_x + width - 1;
+            !(hits_obstacle(y, start_x, end_x, obstacles)
+                || row_blocked(collision, y, start_x, end_x, use_ascii))
+        };
+        let desired = start_x;
+        start_x = search_start_x(desired, min_start, max_start, free);
+        if !use_ascii {
+            if !free(start_x) && fits_on_line {
+                let widest = 0.max(canvas_max_x - width + 1);
+                let from = desired.max(0).min(widest);
+                let candidate = search_start_x(from, 0, widest, free);
+                if free(candidate) {
+                    start_x = candidate;
+                }
+            }
+            if !free(start_x) {
+                return None;
+            }
+        }
+    }
+    Some(placed(start_x, y))
+}
+
+/// 堆叠的 label 与原线段不在同一行时，补一格竖线把 label 和线连起来（JS `drawStackConnector`）。
+fn draw_stack_connector(
+    canvas: &mut Canvas,
+    placement: LabelPlacement,
+    line_y: i32,
+    use_ascii: bool,
+) {
+    let (_, max_y) = canvas.max();
+    let x = placement.start_x + (placement.width - 1) / 2;
+    let delta = line_y - placement.y;
+    if delta == 0 {
+        return;
+    }
+    let y = if delta > 0 {
+        placement.y + 1
+    } else {
+        placement.y - 1
+    };
+    if y < 0 || y > max_y {
+        return;
+    }
+    if canvas.get(x, y) == ' ' {
+        canvas.set(x, y, if use_ascii { '|' } else { '│' });
+    }
+}
+
+/// label 需要避开的两个点：箭头前一格与起点 junction。
+fn label_obstacles(graph: &Graph, edge: &Edge) -> Vec<Point> {
+    if edge.path.len() < 2 {
+        return Vec::new();
+    }
+    let last = edge.path[edge.path.len() - 1];
+    let before_last = edge.path[edge.path.len() - 2];
+    let dir = direction_between(before_last, last);
+    let end = edge_point(graph, edge, last);
+    let near_end = match dir {
+        UP => Point::new(end.x, end.y + 1),
+        DOWN => Point::new(end.x, end.y - 1),
+        LEFT => Point::new(end.x + 1, end.y),
+        RIGHT => Point::new(end.x - 1, end.y),
+        _ => end,
+    };
+    let arrow = target_port(graph, edge, dir, near_end);
+
+    let start_dir = direction_between(edge.path[0], edge.path[1]);
+    let start = edge_point(graph, edge, edge.path[0]);
+    let junction = source_port(graph, edge, start_dir, start);
+    vec![arrow, junction]
+}
+
+/// JS `Math.round`（.5 向 +∞ 取整）。
+fn round_half_up(value: f64) -> i32 {
+    (value + 0.5).floor() as i32
+}
+
+/// relaxed + Unicode：同一对节点之间的多条带 label 边，label 在中线附近竖向堆叠。
+fn stacked_label_lines(graph: &Graph) -> (HashMap<usize, Vec<Point>>, Vec<bool>) {
+    let mut groups: Vec<((usize, usize), Vec<usize>)> = Vec::new();
+    for (index, edge) in graph.edges.iter().enumerate() {
+        if edge.text.is_empty() {
+            continue;
+        }
+        let key = (edge.from, edge.to);
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, members)) => members.push(index),
+            None => groups.push((key, vec![index])),
+        }
+    }
+    groups.retain(|(_, members)| members.len() > 1);
+
+    let mut stacked = vec![false; graph.edges.len()];
+    let mut lines = HashMap::new();
+    let (canvas_max_x, canvas_max_y) = graph.canvas.max();
+    for (_, members) in &groups {
+        let size = members.len() as i32;
+        let base_lines: Vec<Vec<Point>> = members
+            .iter()
+            .map(|&index| {
+                let edge = &graph.edges[index];
+                edge_points(graph, edge, &edge.label_line)
+            })
+            .collect();
+        for &index in members {
+            stacked[index] = true;
+        }
+
+        let mut sum_y = 0;
+        let mut mid_xs = Vec::new();
+        for line in base_lines.iter().filter(|line| line.len() >= 2) {
+            let (min_y, max_y) = (line[0].y.min(line[1].y), line[0].y.max(line[1].y));
+            sum_y += min_y + (max_y - min_y) / 2;
+            let (min_x, max_x) = (line[0].x.min(line[1].x), line[0].x.max(line[1].x));
+            mid_xs.push(min_x + (max_x - min_x) / 2);
+        }
+        let center_y = round_half_up(f64::from(sum_y) / f64::from(size));
+        mid_xs.sort_unstable();
+        let center_x = mid_xs.get(mid_xs.len() / 2).copied().unwrap_or(0).max(0);
+        let widest = members
+            .iter()
+            .map(|&index| display_width(&graph.edges[index].text))
+            .fold(1, i32::max);
+        let max_start = 0.max(canvas_max_x - widest + 1);
+        let start_x = (center_x - widest / 2).max(0).min(max_start);
+
+        for (rank, (&index, base_line)) in members.iter().zip(&base_lines).enumerate() {
+            if base_line.len() < 2 {
+                lines.insert(index, base_line.clone());
+                continue;
+            }
+            let offset = 2 * rank as i32 - (size - 1);
+            let y = (center_y + offset).max(0).min(canvas_max_y);
+            let width = display_width(&graph.edges[index].text);
+            let x = (start_x + width / 2).max(0).min(canvas_max_x);
+            lines.insert(index, vec![Point::new(x, y), Point::new(x, y)]);
+        }
+    }
+    (lines, stacked)
+}
+
+fn draw_labels(graph: &mut Graph) {
+    let use_ascii = graph.config.use_ascii;
+    if graph.config.relaxed_unicode() {
+        let (stacked_lines, stacked) = stacked_label_lines(graph);
+        for (index, &is_stacked) in stacked.iter().enumerate() {
+            let edge = &graph.edges[index];
+            if edge.text.is_empty() {
+                continue;
+            }
+            let base_line = edge_points(graph, edge, &edge.label_line);
+            let line = stacked_lines.get(&index).unwrap_or(&base_line);
+            let obstacles = label_obstacles(graph, edge);
+            let placement = place_label(
+                line,
+                &edge.text,
+                &obstacles,
+                &graph.canvas,
+                use_ascii,
+                is_stacked,
+            );
+            let Some(placement) = placement else {
+                continue;
+            };
+            let text = edge.text.clone();
+            graph
+                .canvas
+                .draw_text(placement.start_x, placement.y, &text);
+            if stacked[index] && base_line.len() >= 2 {
+                let (min_y, max_y) = (
+                    base_line[0].y.min(base_line[1].y),
+                    base_line[0].y.max(base_line[1].y),
+                );
+                let line_y = min_y + (max_y - min_y) / 2;
+                draw_stack_connector(&mut graph.canvas, placement, line_y, use_ascii);
+            }
+        }
+        return;
+    }
+
+    let layers: Vec<Canvas> = graph
+        .edges
+        .iter()
+        .map(|edge| {
+            let mut layer = graph.canvas.blank_like();
+            if edge.text.is_empty() {
+                return layer;
+            }
+            let line = edge_points(graph, edge, &edge.label_line);
+            let obstacles = label_obstacles(graph, edge);
+            if let Some(placement) = place_label(
+                &line,
+                &edge.text,
+                &obstacles,
+                &graph.canvas,
+                use_ascii,
+                false,
+            ) {
+                layer.draw_text(placement.start_x, placement.y, &edge.text);
+            }
+            layer
+        })
+        .collect();
+    let refs: Vec<&Canvas> = layers.iter().collect();
+    graph.canvas = canvas::merge(&graph.canvas, 0, 0, use_ascii, &refs);
+}
+
+// ----------------------------------------------------------------------------
+// 入口
+// ----------------------------------------------------------------------------
+
+/// 按 JS 的顺序把所有元素画到 `graph.canvas`。
+pub(super) fn draw(graph: &mut Graph) {
+    let use_ascii = graph.config.use_ascii;
+
+    // 1) subgraph 框：浅的先画，深的覆盖在上面
+    let mut order: Vec<usize> = (0..graph.subgraphs.len()).collect();
+    order.sort_by_key(|&index| subgraph_depth(graph, index));
+    for index in order {
+        let frame = subgraph_frame(graph, index);
+        let sg = &graph.subgraphs[index];
+        graph.canvas = canvas::merge(&graph.canvas, sg.min_x, sg.min_y, use_ascii, &[&frame]);
+    }
+
+    // 2) node box
+    for index in 0..graph.nodes.len() {
+        let node = &graph.nodes[index];
+        if node.drawn {
+            continue;
+        }
+        let (Some(at), Some(drawing)) = (node.drawing_coord, node.drawing.as_ref()) else {
+            continue;
+        };
+        graph.canvas = canvas::merge(&graph.canvas, at.x, at.y, use_ascii, &[drawing]);
+        graph.nodes[index].drawn = true;
+    }
+
+    // 3) 边：所有线段 -> 拐角 -> 箭头 -> 起点 junction
+    let mut paths = Vec::new();
+    let mut corners = Vec::new();
+    let mut arrows = Vec::new();
+    let mut box_starts = Vec::new();
+    for edge in &graph.edges {
+        if edge.path.len() < 2 {
+            let blank = graph.canvas.blank_like();
+            paths.push(blank.clone());
+            corners.push(blank.clone());
+            arrows.push(blank.clone());
+            box_starts.push(blank);
+            continue;
+        }
+        let (path_layer, lines, dirs) = draw_path(graph, edge);
+        paths.push(path_layer);
+        box_starts.push(draw_box_start(graph, edge));
+        let last_line = lines.last().map(Vec::as_slice).unwrap_or(&[]);
+        let last_dir = dirs.last().copied().unwrap_or_default();
+        arrows.push(draw_arrow_head(graph, edge, last_line, last_dir));
+        corners.push(draw_corners(graph, edge));
+    }
+    for layers in [&paths, &corners, &arrows, &box_starts] {
+        let refs: Vec<&Canvas> = layers.iter().collect();
+        graph.canvas = canvas::merge(&graph.canvas, 0, 0, use_ascii, &refs);
+    }
+
+    // 4) label
+    draw_labels(graph);
+
+    // 5) subgraph 标题
+    for index in 0..graph.subgraphs.len() {
+        if graph.subgraphs[index].nodes.is_empty() {
+            continue;
+        }
+        let (label, at) = subgraph_label(graph, index);
+        graph.canvas = canvas::merge(&graph.canvas, at.x, at.y, use_ascii, &[&label]);
+    }
+}
@@ -0,0 +1,944 @@
+// ============================================================================
+// 布局：grid 放置 -> 走线 -> 列宽/行高 -> 画布坐标 -> subgraph 边界
+//
+// 对齐 JS bundle 的 `createMapping`：
+// - 节点占 3×3 的 grid cell，按 BFS 从根节点逐层放置（TD 按行、LR 按列）；
+// - 走线失败时整体重试（起始偏移 0..=4），最后一次允许无约束兜底；
+// - relaxed + Unicode 下会额外把同一侧的多条边分散到不同端口。
+// ============================================================================
+
+use super::routing::Router;
+use super::{Graph, Point, RouteInfo, draw, parallel, reroute};
+use crate::native::canvas::Canvas;
+use crate::native::text::display_width;
+use crate::routing::{GridPoint, RoutingGrid};
+use std::collections::{HashMap, HashSet};
+
+/// 最后一档搜索半径（grid 会在节点范围外预留这么多格给绕行）。
+pub(super) const MAX_ROUTE_MARGIN: i32 = 384;
+
+/// 布局入口：依次尝试起始偏移 0..=4，直到所有边都能走通。
+pub(super) fn layout(graph: &mut Graph) {
+    for attempt in 0..=4 {
+        reset(graph);
+        graph.layout_attempts = attempt as u32 + 1;
+        if try_layout(graph, attempt) {
+            return;
+        }
+    }
+}
+
+fn reset(graph: &mut Graph) {
+    graph.grid.clear();
+    graph.column_width.clear();
+    graph.row_height.clear();
+    graph.canvas = Canvas::new(0, 0);
+    graph.offset_x = 0;
+    graph.offset_y = 0;
+    graph.column_start_x = None;
+    graph.row_start_y = None;
+    graph.port_usage = graph
+        .config
+        .relaxed()
+        .then(|| vec![0; graph.nodes.len() * 9]);
+    for node in &mut graph.nodes {
+        node.grid_coord = None;
+        node.drawing_coord = None;
+        node.drawing = None;
+        node.drawn = false;
+    }
+    for edge in &mut graph.edges {
+        edge.reset();
+    }
+    for subgraph in &mut graph.subgraphs {
+        subgraph.min_x = 0;
+        subgraph.min_y = 0;
+        subgraph.max_x = 0;
+        subgraph.max_y = 0;
+    }
+}
+
+/// 节点之间的 grid 步长：relaxed 下平行边/扇出越多，留的走线通道越宽。
+fn level_step(graph: &Graph) -> i32 {
+    if !graph.config.relaxed() || graph.nodes.is_empty() {
+        return 4;
+    }
+    let node_count = graph.nodes.len();
+    let mut max_parallel = 1;
+    let mut parallel: HashMap<usize, i32> = HashMap::new();
+    for edge in &graph.edges {
+        let count = parallel
+            .entry(edge.from * node_count + edge.to)
+            .or_insert(0);
+        *count += 1;
+        max_parallel = max_parallel.max(*count);
+    }
+    let mut max_fan_out = 0;
+    let mut fan_out = vec![0; node_count];
+    for edge in &graph.edges {
+        fan_out[edge.from] += 1;
+        max_fan_out = max_fan_out.max(fan_out[edge.from]);
+    }
+
+    if max_parallel >= 5 || max_fan_out >= 8 {
+        8
+    } else if max_parallel >= 3 || max_fan_out >= 4 {
+        6
+    } else {
+        4
+    }
+}
+
+/// 每一层（TD 的行 / LR 的列）已经放到哪里了；JS 里是一个会自动扩容的数组。
+struct LevelCursor(Vec<i32>);
+
+impl LevelCursor {
+    fn get(&self, level: i32) -> i32 {
+        usize::try_from(level)
+            .ok()
+            .and_then(|idx| self.0.get(idx))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn set(&mut self, level: i32, value: i32) {
+        let Ok(idx) = usize::try_from(level) else {
+            return;
+        };
+        if idx >= self.0.len() {
+            self.0.resize(idx + 1, 0);
+        }
+        self.0[idx] = value;
+    }
+}
+
+/// 把节点放到 `coord`；被占用时沿着次轴继续找（JS `reserveSpotInGrid`）。
+fn reserve_spot(graph: &mut Graph, node: usize, coord: Point, step: i32) -> Point {
+    let mut coord = coord;
+    while graph.grid.contains_key(&coord) {
+        coord = if graph.config.horizontal || graph.config.relaxed_unicode() {
+            Point::new(coord.x, coord.y + step)
+        } else {
+            Point::new(coord.x + step, coord.y)
+        };
+    }
+    for dx in 0..3 {
+        for dy in 0..3 {
+            graph
+                .grid
+                .insert(Point::new(coord.x + dx, coord.y + dy), node);
+        }
+    }
+    graph.nodes[node].grid_coord = Some(coord);
+    coord
+}
+
+fn place(graph: &Graph, level: i32, along: i32) -> Point {
+    if graph.config.horizontal {
+        Point::new(level, along)
+    } else {
+        Point::new(along, level)
+    }
+}
+
+fn try_layout(graph: &mut Graph, attempt: i32) -> bool {
+    let step = level_step(graph);
+    let horizontal = graph.config.horizontal;
+    let mut levels = LevelCursor(vec![
+        0;
+        100.max((graph.nodes.len() as i32 + 2) * step + 16)
+            as usize
+    ]);
+
+    // ------------------------------------------------------------------
+    // 1) 根节点
+    // ------------------------------------------------------------------
+    let mut roots: Vec<usize> = Vec::new();
+    if graph.config.relaxed() {
+        let targets: HashSet<usize> = graph.edges.iter().map(|edge| edge.to).collect();
+        roots = (0..graph.nodes.len())
+            .filter(|node| !targets.contains(node))
+            .collect();
+        if roots.is_empty() && !graph.nodes.is_empty() {
+            roots.push(0);
+        }
+    } else {
+        let mut seen = HashSet::new();
+        for node in 0..graph.nodes.len() {
+            if !seen.contains(&node) {
+                roots.push(node);
+            }
+            seen.insert(node);
+            seen.extend(graph.children_of(node));
+        }
+    }
+
+    let mut has_external_root = false;
+    let mut has_subgraph_root_with_children = false;
+    for &root in &roots {
+        if graph.is_in_subgraph(root) {
+            if !graph.children_of(root).is_empty() {
+                has_subgraph_root_with_children = true;
+            }
+        } else {
+            has_external_root = true;
+        }
+    }
+    // LR 下 subgraph 里的根单独放在第二列，避免与外部根挤在同一列
+    let split_roots = horizontal && has_external_root && has_subgraph_root_with_children;
+    let (first_roots, subgraph_roots): (Vec<usize>, Vec<usize>) = if split_roots {
+        roots.iter().partition(|&&root| !graph.is_in_subgraph(root))
+    } else {
+        (roots, Vec::new())
+    };
+
+    for &root in &first_roots {
+        let coord = place(graph, attempt, levels.get(0) + attempt);
+        reserve_spot(graph, root, coord, step);
+        levels.set(0, levels.get(0) + step);
+    }
+    if split_roots && !subgraph_roots.is_empty() {
+        let level = step;
+        for &root in &subgraph_roots {
+            let coord = place(graph, level + attempt, levels.get(level) + attempt);
+            reserve_spot(graph, root, coord, step);
+            levels.set(level, levels.get(level) + step);
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // 2) BFS 放置子节点；孤立的连通分量从第 0 层（或 subgraph 层）继续
+    // ------------------------------------------------------------------
+    let mut placed_any = true;
+    while placed_any {
+        placed_any = false;
+        for node in 0..graph.nodes.len() {
+            let Some(coord) = graph.nodes[node].grid_coord else {
+                continue;
+            };
+            let level = if horizontal { coord.x } else { coord.y } - attempt + step;
+            let mut along = levels.get(level);
+            for child in graph.children_of(node) {
+                if graph.nodes[child].grid_coord.is_some() {
+                    continue;
+                }
+                // relaxed + Unicode（TD）：回边的源节点放到下一层，避免回边穿过父节点
+                if !horizontal
+                    && graph.config.relaxed_unicode()
+                    && graph
+                        .edges
+                        .iter()
+                        .any(|edge| edge.from == child && edge.to == node)
+                {
+                    let next_level = level + step;
+                    let target = Point::new(coord.x, next_level + attempt);
+                    reserve_spot(graph, child, target, step);
+                    let column = coord.x - attempt;
+                    let current = levels.get(next_level);
+                    levels.set(next_level, current.max(column + step));
+                    placed_any = true;
+                    continue;
+                }
+                let target = place(graph, level + attempt, along + attempt);
+                reserve_spot(graph, child, target, step);
+                levels.set(level, along + step);
+                along = levels.get(level);
+                placed_any = true;
+            }
+        }
+        if placed_any {
+            continue;
+        }
+        let Some(orphan) = graph.nodes.iter().position(|n| n.grid_coord.is_none()) else {
+            break;
+        };
+        let level = if split_roots && graph.is_in_subgraph(orphan) {
+            step
+        } else {
+            0
+        };
+        let coord = place(graph, level + attempt, levels.get(level) + attempt);
+        reserve_spot(graph, orphan, coord, step);
+        levels.set(level, levels.get(level) + step);
+        placed_any = true;
+    }
+    if graph.nodes.iter().any(|node| node.grid_coord.is_none()) {
+        return false;
+    }
+
+    // ------------------------------------------------------------------
+    // 3) 走线网格：节点范围 + 最大搜索半径
+    // ------------------------------------------------------------------
+    let (mut extent_x, mut extent_y) = (0, 0);
+    for node in &graph.nodes {
+        if let Some(coord) = node.grid_coord {
+            extent_x = extent_x.max(coord.x + 2);
+            extent_y = extent_y.max(coord.y + 2);
+        }
+    }
+    let mut grid = RoutingGrid::new(
+        (extent_x + MAX_ROUTE_MARGIN + 1) as u32,
+        (extent_y + MAX_ROUTE_MARGIN + 1) as u32,
+    );
+    grid.set_enclosure_probe(graph.config.enclosure_probe);
+    for node in &graph.nodes {
+        if let Some(coord) = node.grid_coord {
+            grid.block_rect(GridPoint::new(coord.x as u32, coord.y as u32), 3, 3);
+        }
+    }
+    let mut router = Router {
+        grid,
+        max_x: extent_x,
+        max_y: extent_y,
+        last_attempt: attempt >= 4,
+        turn_penalty: graph.config.turn_penalty,
+        assume_routes: false,
+        stats: graph
+            .config
+            .collect_stats
+            .then(|| vec![Default::default(); graph.edges.len()]),
+        frames: graph.config.collect_frames.then(Vec::new),
+        work: 0,
+        searches: HashMap::new(),
+    };
+
+    for node in 0..graph.nodes.len() {
+        reserve_node_cells(graph, node);
+    }
+
+    // ------------------------------------------------------------------
+    // 4) 走线（relaxed + Unicode：BFS 树边优先；同向平行边复用第一条的路径；
+    //    global：全部走通后再 rip-up-and-reroute；互不相连的区域可先并行预演，见 `parallel`）
+    // ------------------------------------------------------------------
+    let order: Vec<usize> = if graph.config.relaxed_unicode() {
+        tree_edges_first(graph)
+    } else {
+        (0..graph.edges.len()).collect()
+    };
+    let global = graph.config.global();
+    let mut pass = RoutePass::new(graph, &order);
+    let mut plan = parallel::plan(graph, &router, &order);
+    graph.parallel_regions = plan.as_ref().map_or(0, parallel::Plan::regions);
+    for &edge in &order {
+        pass.step(graph, edge, |graph| {
+            parallel::route_edge(graph, edge, &mut router, &mut plan)
+        });
+    }
+    if graph.edges.iter().any(|edge| edge.path.len() < 2) {
+        graph.route_stats = router.stats.take();
+        graph.route_frames = router.frames.take();
+        return false;
+    }
+    if global {
+        reroute::improve(graph, &mut router, &pass.owned, &pass.followers);
+        for &edge in &order {
+            let path = graph.edges[edge].path.clone();
+            ensure_path_cells(graph, &path);
+        }
+    }
+    graph.route_stats = router.stats.take();
+    graph.route_frames = router.frames.take();
+
+    if graph.config.relaxed_unicode() {
+        spread_ports(graph);
+    }
+
+    // ------------------------------------------------------------------
+    // 5) label 所在线段 -> 列宽；画布坐标；box；subgraph 边界
+    // ------------------------------------------------------------------
+    for edge in 0..graph.edges.len() {
+        draw::assign_label_line(graph, edge);
+    }
+    compute_line_starts(graph);
+    for node in 0..graph.nodes.len() {
+        let coord = graph.nodes[node].grid_coord.expect("所有节点都已放置");
+        graph.nodes[node].drawing_coord = Some(grid_to_drawing(graph, coord));
+        graph.nodes[node].drawing = Some(draw::node_box(graph, node));
+    }
+
+    let total_width: i32 = graph.column_width.values().sum();
+    let total_height: i32 = graph.row_height.values().sum();
+    graph.canvas.grow(total_width - 1, total_height - 1);
+    compute_subgraph_bounds(graph);
+    apply_negative_offsets(graph);
+    true
+}
+
+/// 节点所在的 3 列/3 行设定最小宽高；与前一列/行之间留出 padding（JS `setColumnWidth`）。
+fn reserve_node_cells(graph: &mut Graph, node: usize) {
+    let coord = graph.nodes[node].grid_coord.expect("所有节点都已放置");
+    let padding = graph.config.box_border_padding;
+    let widths = [
+        1,
+        2 * padding + display_width(&graph.nodes[node].display_label),
+        1,
+    ];
+    let heights = [1, 1 + 2 * padding, 1];
+    for (offset, width) in widths.into_iter().enumerate() {
+        let column = coord.x + offset as i32;
+        let current = graph.column_width_at(column);
+        graph.column_width.insert(column, current.max(width));
+    }
+    for (offset, height) in heights.into_iter().enumerate() {
+        let row = coord.y + offset as i32;
+        let current = graph.row_height_at(row);
+        graph.row_height.insert(row, current.max(height));
+    }
+    if coord.x > 0 {
+        let current = graph.column_width_at(coord.x - 1);
+        graph
+            .column_width
+            .insert(coord.x - 1, current.max(graph.config.padding_x));
+    }
+    if coord.y > 0 {
+        let mut padding_y = graph.config.padding_y;
+        if needs_subgraph_header_room(graph, node) {
+            padding_y += 4;
+        }
+        let current = graph.row_height_at(coord.y - 1);
+        graph.row_height.insert(coord.y - 1, current.max(padding_y));
+    }
+}
+
+/// subgraph 里最靠上的“入口节点”（有来自 subgraph 外的边）上方要给标题留空间。
+fn needs_subgraph_header_room(graph: &Graph, node: usize) -> bool {
+    let Some(subgraph) = graph.subgraph_of(node) else {
+        return false;
+    };
+    let has_external_incoming = |target: usize| {
+        graph
+            .edges
+            .iter()
+            .any(|edge| edge.to == target && graph.subgraph_of(edge.from) != Some(subgraph))
+    };
+    if !has_external_incoming(node) {
+        return false;
+    }
+    let node_y = graph.nodes[node].grid_coord.map_or(0, |coord| coord.y);
+    for &other in &graph.subgraphs[subgraph].nodes {
+        if other == node {
+            continue;
+        }
+        let Some(coord) = graph.nodes[other].grid_coord else {
+            continue;
+        };
+        if has_external_incoming(other) && coord.y < node_y {
+            return false;
+        }
+    }
+    true
+}
+
+/// 按走线顺序逐条处理边（主流程与 `parallel` 的预演共用）。
+///
+/// relaxed + Unicode 下同向平行边复用第一条（leader）的路径；非 global 时每条边走完立即补列宽/行高，
+/// 后面的边算包围盒时能看到。
+pub(super) struct RoutePass {
+    reuse_parallel: bool,
+    global: bool,
+    parallel_count: HashMap<(usize, usize), usize>,
+    first_parallel: HashMap<(usize, usize), usize>,
+    /// 真正占用网格的边（按走线顺序）。
+    pub(super) owned: Vec<usize>,
+    /// `followers[edge]`：复用 `edge` 路径的平行边（global 时随 leader 一起重走）。
+    pub(super) followers: Vec<Vec<usize>>,
+}
+
+impl RoutePass {
+    pub(super) fn new(graph: &Graph, order: &[usize]) -> Self {
+        let reuse_parallel = graph.config.relaxed_unicode();
+        let mut parallel_count: HashMap<(usize, usize), usize> = HashMap::new();
+        if reuse_parallel {
+            for &edge in order {
+                let key = (graph.edges[edge].from, graph.edges[edge].to);
+                *parallel_count.entry(key).or_insert(0) += 1;
+            }
+        }
+        Self {
+            reuse_parallel,
+            global: graph.config.global(),
+            parallel_count,
+            first_parallel: HashMap::new(),
+            owned: Vec::new(),
+            followers: vec![Vec::new(); graph.edges.len()],
+        }
+    }
+
+    /// 处理一条边：跟随 leader，或者调用 `route` 真正走线。
+    pub(super) fn step(&mut self, graph: &mut Graph, edge: usize, route: impl FnOnce(&mut Graph)) {
+        let key = (graph.edges[edge].from, graph.edges[edge].to);
+        let parallel =
+            self.reuse_parallel && self.parallel_count.get(&key).copied().unwrap_or(0) >= 2;
+        if let Some(&first) = self.first_parallel.get(&key).filter(|_| parallel) {
+            self.followers[first].push(edge);
+            let (path, start_dir, end_dir) = {
+                let first = &graph.edges[first];
+                (first.path.clone(), first.start_dir, first.end_dir)
+            };
+            let edge_ref = &mut graph.edges[edge];
+            edge_ref.path = path;
+            edge_ref.start_dir = start_dir;
+            edge_ref.end_dir = end_dir;
+            edge_ref.route = RouteInfo {
+                shared_path: true,
+                ..RouteInfo::default()
+            };
+            edge_ref.clear_port_offsets();
+        } else {
+            route(graph);
+            self.owned.push(edge);
+            if parallel && graph.edges[edge].path.len() >= 2 {
+                self.first_parallel.insert(key, edge);
+            }
+        }
+        if !self.global {
+            let path = graph.edges[edge].path.clone();
+            ensure_path_cells(graph, &path);
+        }
+    }
+}
+
+/// 路径经过的列/行至少要有 padding/2 的宽高（JS `increaseGridSizeForPath`）。
+fn ensure_path_cells(graph: &mut Graph, path: &[Point]) {
+    let Some(&first) = path.first() else {
+        return;
+    };
+    let half_x = graph.config.padding_x / 2;
+    let half_y = graph.config.padding_y / 2;
+    let ensure = |graph: &mut Graph, point: Point| {
+        graph.column_width.entry(point.x).or_insert(half_x);
+        graph.row_height.entry(point.y).or_insert(half_y);
+    };
+
+    ensure(graph, first);
+    let mut previous = first;
+    for &point in &path[1..] {
+        if previous.x == point.x {
+            let step = if point.y > previous.y { 1 } else { -1 };
+            let mut y = previous.y;
+            while y != point.y {
+                ensure(graph, Point::new(previous.x, y));
+                ensure(graph, Point::new(previous.x, y + step));
+                y += step;
+            }
+        } else if previous.y == point.y {
+            let step = if point.x > previous.x { 1 } else { -1 };
+            let mut x = previous.x;
+            while x != point.x {
+                ensure(graph, Point::new(x, previous.y));
+                ensure(graph, Point::new(x + step, previous.y));
+                x += step;
+            }
+        } else {
+            ensure(graph, point);
+        }
+        previous = point;
+    }
+}
+
+/// relaxed + Unicode 的走线顺序：先走 BFS 生成树上的边，再走其余的边。
+fn tree_edges_first(graph: &Graph) -> Vec<usize> {
+    let mut in_degree = vec![0; graph.nodes.len()];
+    for edge in &graph.edges {
+        in_degree[edge.to] += 1;
+    }
+    let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); graph.nodes.len()];
+    for (index, edge) in graph.edges.iter().enumerate() {
+        outgoing[edge.from].push(index);
+    }
+
+    let mut starts: Vec<usize> = (0..graph.nodes.len())
+        .filter(|&node| in_degree[node] == 0)
+        .collect();
+    if starts.is_empty() && !graph.nodes.is_empty() {
+        starts.push(0);
+    }
+
+    let mut visited = vec![false; graph.nodes.len()];
+    let mut tree_edges = HashSet::new();
+    let mut queue = Vec::new();
+    let visit = |node: usize, queue: &mut Vec<usize>, visited: &mut Vec<bool>| {
+        if !visited[node] {
+            visited[node] = true;
+            queue.push(node);
+        }
+    };
+    for start in starts {
+        visit(start, &mut queue, &mut visited);
+    }
+    let mut head = 0;
+    loop {
+        while head < queue.len() {
+            let node = queue[head];
+            head += 1;
+            for &edge in &outgoing[node] {
+                let target = graph.edges[edge].to;
+                if !visited[target] {
+                    tree_edges.insert(edge);
+                    visit(target, &mut queue, &mut visited);
+                }
+            }
+        }
+        if queue.len() >= graph.nodes.len() {
+            break;
+        }
+        let Some(next) = visited.iter().position(|&seen| !seen) else {
+            break;
+        };
+        visit(next, &mut queue, &mut visited);
+    }
+
+    let (mut order, rest): (Vec<usize>, Vec<usize>) =
+        (0..graph.edges.len()).partition(|edge| tree_edges.contains(edge));
+    order.extend(rest);
+    order
+}
+
+// ----------------------------------------------------------------------------
+// 端口分散（relaxed + Unicode）
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Side {
+    fn is_horizontal(self) -> bool {
+        matches!(self, Self::Left | Self::Right)
+    }
+}
+
+/// 端口方向 -> 节点的哪一侧；对角方向按两端相对位置归到主轴。
+fn port_side(dir: Point, from: Option<Point>, to: Option<Point>) -> Option<Side> {
+    match (dir.x, dir.y) {
+        (1, 0) => return Some(Side::Up),
+        (1, 2) => return Some(Side::Down),
+        (0, 1) => return Some(Side::Left),
+        (2, 1) => return Some(Side::Right),
+        _ => {}
+    }
+    let (from, to) = (from?, to?);
+    let dx = to.x - from.x;
+    let dy = to.y - from.y;
+    let wide = dx.abs() >= dy.abs();
+    match (dir.x, dir.y) {
+        (2, 0) => Some(if wide { Side::Right } else { Side::Up }),
+        (0, 0) => Some(if wide { Side::Left } else { Side::Up }),
+        (2, 2) => Some(if wide { Side::Right } else { Side::Down }),
+        (0, 2) => Some(if wide { Side::Left } else { Side::Down }),
+        _ => None,
+    }
+}
+
+/// `count` 个端口均匀分布在 `[0, span)` 上。
+fn spread_offsets(count: usize, span: i32) -> Vec<i32> {
+    if count == 0 || span <= 0 {
+        return Vec::new();
+    }
+    if count == 1 {
+        return vec![span / 2];
+    }
+    (0..count as i32)
+        .map(|i| i * (span - 1) / (count as i32 - 1))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PortEntry {
+    edge: usize,
+    is_start: bool,
+    other_sort: i32,
+    edge_order: usize,
+}
+
+#[derive(Default)]
+struct PortBuckets {
+    up: Vec<PortEntry>,
+    down: Vec<PortEntry>,
+    left: Vec<PortEntry>,
+    right: Vec<PortEntry>,
+}
+
+impl PortBuckets {
+    fn side_mut(&mut self, side: Side) -> &mut Vec<PortEntry> {
+        match side {
+            Side::Up => &mut self.up,
+            Side::Down => &mut self.down,
+            Side::Left => &mut self.left,
+            Side::Right => &mut self.right,
+        }
+    }
+}
+
+/// 同一侧有多条边时，把节点中间列/行撑宽，并给每条边分配不同的端口偏移。
+fn spread_ports(graph: &mut Graph) {
+    let mut buckets: Vec<PortBuckets> =
+        graph.nodes.iter().map(|_| PortBuckets::default()).collect();
+    for (order, edge) in graph.edges.iter().enumerate() {
+        if edge.path.len() < 2 {
+            continue;
+        }
+        let from_coord = graph.nodes[edge.from].grid_coord;
+        let to_coord = graph.nodes[edge.to].grid_coord;
+        if let Some(side) = port_side(edge.start_dir, from_coord, to_coord) {
+            let other = to_coord.unwrap_or_default();
+            buckets[edge.from].side_mut(side).push(PortEntry {
+                edge: order,
+                is_start: true,
+                other_sort: if side.is_horizontal() {
+                    other.y
+                } else {
+                    other.x
+                },
+                edge_order: order,
+            });
+        }
+        if let Some(side) = port_side(edge.end_dir, to_coord, from_coord) {
+            let other = from_coord.unwrap_or_default();
+            buckets[edge.to].side_mut(side).push(PortEntry {
+                edge: order,
+                is_start: false,
+                other_sort: if side.is_horizontal() {
+                    other.y
+                } else {
+                    other.x
+                },
+                edge_order: order,
+            });
+        }
+    }
+
+    let extra_room = |count: usize| -> i32 {
+        if count <= 3 {
+            0
+        } else {
+            ((count as i32 - 3 + 1) / 2).min(4)
+        }
+    };
+    for (node, bucket) in buckets.iter().enumerate() {
+        let Some(coord) = graph.nodes[node].grid_coord else {
+            continue;
+        };
+        let padding = graph.config.box_border_padding;
+        let label_width = 2 * padding + display_width(&graph.nodes[node].display_label);
+        let label_height = 1 + 2 * padding;
+        let vertical_ports = bucket.up.len().max(bucket.down.len());
+        let horizontal_ports = bucket.left.len().max(bucket.right.len());
+        let width = label_width.max(vertical_ports as i32 + extra_room(vertical_ports));
+        let height = label_height.max(horizontal_ports as i32 + extra_room(horizontal_ports));
+        let (column, row) = (coord.x + 1, coord.y + 1);
+        if width > graph.column_width_at(column) {
+            graph.column_width.insert(column, width);
+        }
+        if height > graph.row_height_at(row) {
+            graph.row_height.insert(row, height);
+        }
+    }
+
+    for (node, bucket) in buckets.iter_mut().enumerate() {
+        let Some(coord) = graph.nodes[node].grid_coord else {
+            continue;
+        };
+        let column_width = graph.column_width_at(coord.x + 1);
+        let row_height = graph.row_height_at(coord.y + 1);
+        let bucket = std::mem::take(bucket);
+        for (mut entries, side) in [
+            (bucket.left, Side::Left),
+            (bucket.right, Side::Right),
+            (bucket.up, Side::Up),
+            (bucket.down, Side::Down),
+        ] {
+            if entries.is_empty() {
+                continue;
+            }
+            entries.sort_by(|a, b| {
+                a.other_sort
+                    .cmp(&b.other_sort)
+                    .then(a.edge_order.cmp(&b.edge_order))
+            });
+            let span = if side.is_horizontal() {
+                row_height
+            } else {
+                column_width
+            };
+            let offsets = spread_offsets(entries.len(), span);
+            for (index, entry) in entries.iter().enumerate() {
+                let Some(&base) = offsets.get(index) else {
+                    continue;
+                };
+                let mut offset = base;
+                // 单条边时稍微偏离中心，让进出同一侧的边错开
+                if entries.len() == 1 && span >= 2 {
+                    let mut nudge = if matches!(side, Side::Left | Side::Up) {
+                        -1
+                    } else {
+                        1
+                    };
+                    if entry.is_start {
+                        nudge = -nudge;
+                    }
+                    offset = (offset + nudge).clamp(0, span - 1);
+                }
+                let edge = &mut graph.edges[entry.edge];
+                match (side.is_horizontal(), entry.is_start) {
+                    (true, true) => edge.start_port_offset_y = Some(offset),
+                    (true, false) => edge.end_port_offset_y = Some(offset),
+                    (false, true) => edge.start_port_offset_x = Some(offset),
+                    (false, false) => edge.end_port_offset_x = Some(offset),
+                }
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// grid -> 画布坐标
+// ----------------------------------------------------------------------------
+
+/// 每列/行的起始画布坐标（前缀和），供 `grid_to_drawing` 快速查表。
+fn compute_line_starts(graph: &mut Graph) {
+    let max_column = graph.column_width.keys().copied().fold(0, i32::max);
+    let max_row = graph.row_height.keys().copied().fold(0, i32::max);
+    let prefix = |sizes: &HashMap<i32, i32>, count: i32| {
+        let mut starts = Vec::with_capacity(count as usize);
+        let mut acc = 0;
+        for i in 0..count {
+            starts.push(acc);
+            acc += sizes.get(&i).copied().unwrap_or(0);
+        }
+        starts
+    };
+    graph.column_start_x = Some(prefix(&graph.column_width, max_column + 2));
+    graph.row_start_y = Some(prefix(&graph.row_height, max_row + 2));
+}
+
+/// grid 列/行的起点（有前缀表就查表，否则现算）。
+pub(super) fn line_start(graph: &Graph, coord: Point) -> Point {
+    let x = match &graph.column_start_x {
+        Some(starts) if coord.x >= 0 && (coord.x as usize) < starts.len() => {
+            starts[coord.x as usize]
+        }
+        _ => (0..coord.x).map(|x| graph.column_width_at(x)).sum(),
+    };
+    let y = match &graph.row_start_y {
+        Some(starts) if coord.y >= 0 && (coord.y as usize) < starts.len() => {
+            starts[coord.y as usize]
+        }
+        _ => (0..coord.y).map(|y| graph.row_height_at(y)).sum(),
+    };
+    Point::new(x, y)
+}
+
+/// grid cell 的中心点在画布上的坐标（不考虑端口偏移，用于节点 box 定位）。
+fn grid_to_drawing(graph: &Graph, coord: Point) -> Point {
+    let start = line_start(graph, coord);
+    Point::new(
+        start.x + graph.column_width_at(coord.x) / 2 + graph.offset_x,
+        start.y + graph.row_height_at(coord.y) / 2 + graph.offset_y,
+    )
+}
+
+// ----------------------------------------------------------------------------
+// subgraph 边界
+// ----------------------------------------------------------------------------
+
+fn compute_subgraph_bounds(graph: &mut Graph) {
+    for index in 0..graph.subgraphs.len() {
+        subgraph_bounds(graph, index);
+    }
+    resolve_subgraph_overlaps(graph);
+}
+
+fn subgraph_bounds(graph: &mut Graph, index: usize) {
+    if graph.subgraphs[index].nodes.is_empty() {
+        return;
+    }
+    let (mut min_x, mut min_y, mut max_x, mut max_y) =
+        (1_000_000, 1_000_000, -1_000_000, -1_000_000);
+    for child in graph.subgraphs[index].children.clone() {
+        subgraph_bounds(graph, child);
+        let child = &graph.subgraphs[child];
+        if !child.nodes.is_empty() {
+            min_x = min_x.min(child.min_x);
+            min_y = min_y.min(child.min_y);
+            max_x = max_x.max(child.max_x);
+            max_y = max_y.max(child.max_y);
+        }
+    }
+    for &node in &graph.subgraphs[index].nodes {
+        let node = &graph.nodes[node];
+        let (Some(coord), Some(drawing)) = (node.drawing_coord, node.drawing.as_ref()) else {
+            continue;
+        };
+        let (width, height) = drawing.max();
+        min_x = min_x.min(coord.x);
+        min_y = min_y.min(coord.y);
+        max_x = max_x.max(coord.x + width);
+        max_y = max_y.max(coord.y + height);
+    }
+
+    // 四周留 2 格，顶部再多留 2 格给标题
+    let subgraph = &mut graph.subgraphs[index];
+    subgraph.min_x = min_x - 2;
+    subgraph.min_y = min_y - 2 - 2;
+    subgraph.max_x = max_x + 2;
+    subgraph.max_y = max_y + 2;
+}
+
+/// 顶层 subgraph 两两之间不能重叠（至少隔一格）。
+fn resolve_subgraph_overlaps(graph: &mut Graph) {
+    let top_level: Vec<usize> = (0..graph.subgraphs.len())
+        .filter(|&i| graph.subgraphs[i].parent.is_none() && !graph.subgraphs[i].nodes.is_empty())
+        .collect();
+    for (pos, &a) in top_level.iter().enumerate() {
+        for &b in &top_level[pos + 1..] {
+            let (first, second) = (graph.subgraphs[a].clone(), graph.subgraphs[b].clone());
+            if first.min_x < second.max_x && first.max_x > second.min_x {
+                if first.max_y >= second.min_y - 1 && first.min_y < second.min_y {
+                    graph.subgraphs[b].min_y = first.max_y + 2;
+                } else if second.max_y >= first.min_y - 1 && second.min_y < first.min_y {
+                    graph.subgraphs[a].min_y = second.max_y + 2;
+                }
+            }
+            let (first, second) = (graph.subgraphs[a].clone(), graph.subgraphs[b].clone());
+            if first.min_y < second.max_y && first.max_y > second.min_y {
+                if first.max_x >= second.min_x - 1 && first.min_x < second.min_x {
+                    graph.subgraphs[b].min_x = first.max_x + 2;
+                } else if second.max_x >= first.min_x - 1 && second.min_x < first.min_x {
+                    graph.subgraphs[a].min_x = second.max_x + 2;
+                }
+            }
+        }
+    }
+}
+
+/// subgraph 框可能伸到负坐标；整体平移让所有内容落在画布内。
+fn apply_negative_offsets(graph: &mut Graph) {
+    if graph.subgraphs.is_empty() {
+        return;
+    }
+    let min_x = graph.subgraphs.iter().map(|sg| sg.min_x).fold(0, i32::min);
+    let min_y = graph.subgraphs.iter().map(|sg| sg.min_y).fold(0, i32::min);
+    let (dx, dy) = (-min_x, -min_y);
+    if dx == 0 && dy == 0 {
+        return;
+    }
+    graph.offset_x = dx;
+    graph.offset_y = dy;
+    for subgraph in &mut graph.subgraphs {
+        subgraph.min_x += dx;
+        subgraph.min_y += dy;
+        subgraph.max_x += dx;
+        subgraph.max_y += dy;
+    }
+    for node in &mut graph.nodes {
+        if let Some(coord) = &mut node.drawing_coord {
+            coord.x += dx;
+            coord.y += dy;
+        }
+    }
+}
@@ -0,0 +1,262 @@
+// ============================================================================
+// flowchart meta：node box + edge stroke 的有序坐标（对齐 JS `buildAsciiMeta`）
+// ============================================================================
+
+use super::draw::{edge_point, source_port, target_port};
+use super::{
+    DOWN, Edge, Graph, LEFT, LOWER_LEFT, LOWER_RIGHT, MIDDLE, Point, RIGHT, UP, UPPER_LEFT,
+    UPPER_RIGHT, direction_between,
+};
+use crate::types::{
+    AsciiBox, AsciiDrawingCoord, AsciiRenderMeta, AsciiRenderMetaEdge, AsciiRenderMetaNode, Backend,
+};
+use std::collections::HashSet;
+
+pub(super) fn build(graph: &Graph) -> AsciiRenderMeta {
+    let nodes = graph
+        .nodes
+        .iter()
+        .filter_map(|node| {
+            let at = node.drawing_coord?;
+            let (max_x, max_y) = node.drawing.as_ref()?.max();
+            Some(AsciiRenderMetaNode {
+                id: node.name.clone(),
+                label: node.display_label.clone(),
+                box_rect: AsciiBox {
+                    x: at.x,
+                    y: at.y,
+                    width: max_x + 1,
+                    height: max_y + 1,
+                },
+            })
+        })
+        .collect();
+    let edges = graph
+        .edges
+        .iter()
+        .map(|edge| {
+            let (outcome, reason) = edge.outcome();
+            AsciiRenderMetaEdge {
+                from: graph.nodes[edge.from].name.clone(),
+                to: graph.nodes[edge.to].name.clone(),
+                label: edge.text.clone(),
+                path: stroke_path(graph, edge)
+                    .into_iter()
+                    .map(|p| AsciiDrawingCoord { x: p.x, y: p.y })
+                    .collect(),
+                outcome,
+                reason,
+            }
+        })
+        .collect();
+    AsciiRenderMeta {
+        nodes,
+        edges,
+        backend: Backend::Native,
+    }
+}
+
+/// BT 方向整体翻转后，meta 坐标跟着翻转（`max_y` 为翻转后画布的最大行下标）。
+pub(super) fn flip_vertically(meta: &mut AsciiRenderMeta, max_y: i32) {
+    for node in &mut meta.nodes {
+        let rect = &mut node.box_rect;
+        rect.y = max_y - (rect.y + rect.height - 1);
+    }
+    for edge in &mut meta.edges {
+        for point in &mut edge.path {
+            point.y = max_y - point.y;
+        }
+    }
+}
+
+/// 有序、去重的 stroke 坐标。
+struct Stroke {
+    points: Vec<Point>,
+    seen: HashSet<Point>,
+}
+
+impl Stroke {
+    fn push(&mut self, point: Point) {
+        if self.seen.insert(point) {
+            self.points.push(point);
+        }
+    }
+
+    /// 已存在时挪到末尾（箭头必须是最后一个点）。
+    fn push_last(&mut self, point: Point) {
+        if self.seen.insert(point) {
+            self.points.push(point);
+            return;
+        }
+        if let Some(index) = self.points.iter().position(|&p| p == point) {
+            self.points.remove(index);
+        }
+        self.points.push(point);
+    }
+}
+
+/// 与 `draw::draw_line(.., 1, -1, ..)` 画出的格子一致：起点不含、终点不含。
+fn segment_bounds(from: Point, to: Point, dir: Point) -> Option<(Point, Point)> {
+    const SKIP: i32 = 1;
+    const EXTEND: i32 = -1;
+    let (first, last, ok) = match dir {
+        UP => (
+            Point::new(from.x, from.y - SKIP),
+            Point::new(from.x, to.y - EXTEND),
+            from.y - SKIP >= to.y - EXTEND,
+        ),
+        DOWN => (
+            Point::new(from.x, from.y + SKIP),
+            Point::new(from.x, to.y + EXTEND),
+            from.y + SKIP <= to.y + EXTEND,
+        ),
+        LEFT => (
+            Point::new(from.x - SKIP, from.y),
+            Point::new(to.x - EXTEND, from.y),
+            from.x - SKIP >= to.x - EXTEND,
+        ),
+        RIGHT => (
+            Point::new(from.x + SKIP, from.y),
+            Point::new(to.x + EXTEND, from.y),
+            from.x + SKIP <= to.x + EXTEND,
+        ),
+        UPPER_LEFT => (
+            Point::new(from.x, from.y - SKIP),
+            Point::new(to.x - EXTEND, to.y - EXTEND),
+            from.x >= to.x - EXTEND && from.y - SKIP >= to.y - EXTEND,
+        ),
+        UPPER_RIGHT => (
+            Point::new(from.x, from.y - SKIP),
+            Point::new(to.x + EXTEND, to.y - EXTEND),
+            from.x <= to.x + EXTEND && from.y - SKIP >= to.y - EXTEND,
+        ),
+        LOWER_LEFT => (
+            Point::new(from.x, from.y + SKIP),
+            Point::new(to.x - EXTEND, to.y + EXTEND),
+            from.x >= to.x - EXTEND && from.y + SKIP <= to.y + EXTEND,
+        ),
+        LOWER_RIGHT => (
+            Point::new(from.x, from.y + SKIP),
+            Point::new(to.x + EXTEND, to.y + EXTEND),
+            from.x <= to.x + EXTEND && from.y + SKIP <= to.y + EXTEND,
+        ),
+        _ => return None,
+    };
+    ok.then_some((first, last))
+}
+
+/// 逐格走完一段（与绘制时的循环同构）。
+fn walk_segment(stroke: &mut Stroke, from: Point, to: Point, dir: Point) {
+    const SKIP: i32 = 1;
+    const EXTEND: i32 = -1;
+    let (step_x, step_y) = match dir {
+        UP => (0, -1),
+        DOWN => (0, 1),
+        LEFT => (-1, 0),
+        RIGHT => (1, 0),
+        UPPER_LEFT => (-1, -1),
+        UPPER_RIGHT => (1, -1),
+        LOWER_LEFT => (-1, 1),
+        LOWER_RIGHT => (1, 1),
+        _ => return,
+    };
+    let mut x = if step_y != 0 && step_x != 0 {
+        from.x
+    } else {
+        from.x + step_x * SKIP
+    };
+    let mut y = from.y + step_y * SKIP;
+    let in_range = |x: i32, y: i32| {
+        let x_ok = match step_x {
+            -1 => x >= to.x - EXTEND,
+            1 => x <= to.x + EXTEND,
+            _ => true,
+        };
+        let y_ok = match step_y {
+            -1 => y >= to.y - EXTEND,
+            1 => y <= to.y + EXTEND,
+            _ => true,
+        };
+        x_ok && y_ok
+    };
+    while in_range(x, y) {
+        stroke.push(Point::new(x, y));
+        x += step_x;
+        y += step_y;
+    }
+}
+
+fn stroke_path(graph: &Graph, edge: &Edge) -> Vec<Point> {
+    let mut stroke = Stroke {
+        points: Vec::new(),
+        seen: HashSet::new(),
+    };
+    let path = &edge.path;
+    if path.len() < 2 {
+        return stroke.points;
+    }
+
+    // Unicode：起点 junction 与 box 边之间的连接格
+    if !graph.config.use_ascii {
+        let start = edge_point(graph, edge, path[0]);
+        let dir = direction_between(path[0], path[1]);
+        let port = source_port(graph, edge, dir, start);
+        if (dir == LEFT || dir == RIGHT) && port.y == start.y {
+            for x in port.x.min(start.x)..=port.x.max(start.x) {
+                stroke.push(Point::new(x, start.y));
+            }
+        } else if (dir == UP || dir == DOWN) && port.x == start.x {
+            for y in port.y.min(start.y)..=port.y.max(start.y) {
+                stroke.push(Point::new(start.x, y));
+            }
+        }
+        stroke.push(port);
+    }
+
+    let mut last_first: Option<Point> = None;
+    let mut last_end: Option<Point> = None;
+    let mut last_dir: Option<Point> = None;
+    for i in 1..path.len() {
+        let (previous, current) = (path[i - 1], path[i]);
+        let from = edge_point(graph, edge, previous);
+        let to = edge_point(graph, edge, current);
+        if from == to {
+            continue;
+        }
+        let dir = direction_between(previous, current);
+        let (first, end) = segment_bounds(from, to, dir).unwrap_or((from, from));
+        last_first = Some(first);
+        last_end = Some(end);
+        last_dir = Some(dir);
+        walk_segment(&mut stroke, from, to, dir);
+        if i < path.len() - 1 {
+            let next_dir = direction_between(current, path[i + 1]);
+            if dir != next_dir && dir != MIDDLE && next_dir != MIDDLE {
+                stroke.push(to);
+            }
+        }
+    }
+
+    // 箭头：最后一段的方向，位置在目标 box 外一格
+    let last = path[path.len() - 1];
+    let before_last = path[path.len() - 2];
+    let fallback_dir = last_dir.unwrap_or_else(|| direction_between(before_last, last));
+    let end = last_end.unwrap_or_else(|| edge_point(graph, edge, last));
+    let first = last_first.unwrap_or(end);
+    let mut dir = direction_between(first, end);
+    if first == end || dir == MIDDLE {
+        dir = fallback_dir;
+    }
+    let tip = target_port(graph, edge, dir, end);
+    if (dir == LEFT || dir == RIGHT) && tip.y == end.y {
+        for x in tip.x.min(end.x) + 1..tip.x.max(end.x) {
+            stroke.push(Point::new(x, end.y));
+        }
+    } else if (dir == UP || dir == DOWN) && tip.x == end.x {
+        for y in tip.y.min(end.y) + 1..tip.y.max(end.y) {
+            stroke.push(Point::new(end.x, y));
+        }
+    }
+    stroke.push_last(tip);
+    stroke.points
+}
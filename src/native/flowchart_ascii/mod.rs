@@ -0,0 +1,599 @@
+// ============================================================================
+// Flowchart / State diagram：纯 Rust ASCII/Unicode 渲染（`Backend::Native`）
+//
+// 流水线（逐步对齐 JS bundle 的 `renderMermaidAscii` flowchart 分支）：
+// 1) 解析：`native::flowchart::parse`（与 bundle 的 `parseMermaid` 同构）
+// 2) 建图：AST -> 布局用的 node/edge/subgraph（保留插入顺序，index 参与 tie-break）
+// 3) 布局：grid 放置 + A* 走线（`layout` / `routing`，走线直接调用 `NativeAStar`）
+// 4) 绘制：box / 线段 / 拐角 / 箭头 / label / subgraph 框（`draw`）
+// 5) 收尾：BT 方向整体翻转；Unicode 模式下消解多余的 `┼`
+//
+// 说明：
+// - 输出必须与 JS 后端逐字符一致（`tests/ascii_native_backend.rs` 用 golden 文件把关）；
+// - 所以这里刻意保留了 JS 的计算顺序与 tie-break 规则，读代码时可以和 bundle 对照。
+// ============================================================================
+
+mod draw;
+mod layout;
+mod meta;
+mod parallel;
+mod reroute;
+mod routing;
+
+use crate::error::BeautifulMermaidError;
+use crate::error::Result;
+use crate::native::canvas::{self, Canvas};
+use crate::native::flowchart::{self, Direction, FlowSubgraph};
+use crate::routing::debug::DebugFrame;
+use crate::types::{
+    AsciiEdgeRoutingStats, AsciiRenderMeta, AsciiRenderOptions, AsciiRouteOutcome,
+    AsciiRouteReason, AsciiRouting, AsciiRoutingStats,
+};
+use std::collections::HashMap;
+
+/// 渲染 flowchart/state 为字符画。
+pub(crate) fn render(text: &str, options: &AsciiRenderOptions) -> Result<String> {
+    let rendered = run(text, options, Collect::Nothing)?;
+    Ok(rendered.graph.canvas.to_text())
+}
+
+/// 渲染 flowchart/state 为字符画 + meta（坐标与 JS `renderMermaidAsciiWithMeta` 一致）。
+pub(crate) fn render_with_meta(
+    text: &str,
+    options: &AsciiRenderOptions,
+) -> Result<(String, AsciiRenderMeta)> {
+    let rendered = run(text, options, Collect::Nothing)?;
+    let mut meta = meta::build(&rendered.graph);
+    if rendered.flipped_vertically {
+        let (_, max_y) = rendered.graph.canvas.max();
+        meta::flip_vertically(&mut meta, max_y);
+    }
+    Ok((rendered.graph.canvas.to_text(), meta))
+}
+
+/// 渲染 flowchart/state 为字符画 + 走线统计（输出与 `render` 相同；只有显式打开 `parallel_routing` 才并行预演）。
+pub(crate) fn render_with_stats(
+    text: &str,
+    options: &AsciiRenderOptions,
+) -> Result<(String, AsciiRoutingStats)> {
+    let rendered = run(text, options, Collect::Stats)?;
+    let graph = &rendered.graph;
+    let mut edges = graph.route_stats.clone().unwrap_or_default();
+    edges.resize_with(graph.edges.len(), AsciiEdgeRoutingStats::default);
+    for (stats, edge) in edges.iter_mut().zip(&graph.edges) {
+        stats.from = graph.nodes[edge.from].name.clone();
+        stats.to = graph.nodes[edge.to].name.clone();
+        stats.label = edge.text.clone();
+        stats.path_cost = edge.route.cost;
+        stats.end_segment_reuse = edge.route.end_segment_reuse;
+        stats.unconstrained = edge.route.unconstrained;
+        stats.shared_path = edge.route.shared_path;
+        stats.routed = edge.path.len() >= 2;
+    }
+    let stats = AsciiRoutingStats {
+        layout_attempts: graph.layout_attempts,
+        parallel_regions: graph.parallel_regions,
+        edges,
+    };
+    Ok((graph.canvas.to_text(), stats))
+}
+
+/// 渲染 flowchart/state 为字符画 + 逐条边的走线调试帧（输出与 `render` 相同；走线不并行预演）。
+pub(crate) fn render_with_debug(
+    text: &str,
+    options: &AsciiRenderOptions,
+) -> Result<(String, Vec<DebugFrame>)> {
+    let mut rendered = run(text, options, Collect::Frames)?;
+    let frames = rendered.graph.route_frames.take().unwrap_or_default();
+    Ok((rendered.graph.canvas.to_text(), frames))
+}
+
+struct Rendered {
+    graph: Graph,
+    flipped_vertically: bool,
+}
+
+/// `run` 在走线时额外记录什么（都是旁路记录，不影响输出）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Collect {
+    Nothing,
+    Stats,
+    Frames,
+}
+
+fn run(text: &str, options: &AsciiRenderOptions, collect: Collect) -> Result<Rendered> {
+    let ast = flowchart::parse(text)?;
+    let mut config = Config::from_options(options);
+    config.horizontal = ast.direction.is_horizontal();
+    config.collect_stats = collect == Collect::Stats;
+    config.collect_frames = collect == Collect::Frames;
+
+    let mut graph = Graph::build(&ast, config);
+    layout::layout(&mut graph);
+    if options.fail_on_unroutable == Some(true) {
+        check_routed(&graph)?;
+    }
+    draw::draw(&mut graph);
+
+    let flipped_vertically = ast.direction == Direction::Bt;
+    if flipped_vertically {
+        graph.canvas.flip_vertically();
+    }
+    if !graph.config.use_ascii {
+        let mut bends = HashMap::new();
+        for edge in 0..graph.edges.len() {
+            for (key, mask) in draw::bend_junctions(&graph, edge) {
+                bends.insert(key, mask);
+            }
+        }
+        canvas::resolve_crossings(&mut graph.canvas, &bends);
+    }
+
+    Ok(Rendered {
+        graph,
+        flipped_vertically,
+    })
+}
+
+/// `fail_on_unroutable`：有边走不通或靠兜底走通时报错，消息里逐条列出。
+fn check_routed(graph: &Graph) -> Result<()> {
+    let problems: Vec<String> = graph
+        .edges
+        .iter()
+        .filter_map(|edge| {
+            let (outcome, reason) = edge.outcome();
+            let what = match outcome {
+                AsciiRouteOutcome::Routed => return None,
+                AsciiRouteOutcome::RoutedWithFallback => "放开约束才走通",
+                AsciiRouteOutcome::Unroutable => "走不通",
+            };
+            let reason = reason.map_or("节点没有放下", AsciiRouteReason::describe);
+            Some(format!(
+                "{} -> {}: {what}（{reason}）",
+                graph.nodes[edge.from].name, graph.nodes[edge.to].name
+            ))
+        })
+        .collect();
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(BeautifulMermaidError::Unroutable {
+        message: problems.join("; "),
+    })
+}
+
+// ----------------------------------------------------------------------------
+// 配置
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy)]
+struct Config {
+    use_ascii: bool,
+    padding_x: i32,
+    padding_y: i32,
+    box_border_padding: i32,
+    /// LR/RL 统一按 LR 布局，其余按 TD（BT 在最后翻转）。
+    horizontal: bool,
+    routing: AsciiRouting,
+    /// A* 每次转弯的额外代价（0 = 与 JS 一致）。
+    turn_penalty: u32,
+    /// A* 之前是否先做封闭探测（`ts_compatible` 时关掉；输出不变）。
+    enclosure_probe: bool,
+    /// 互不相连的分量并行预演走线（`None` = 自动；输出不变，见 `parallel`）。
+    parallel_routing: Option<bool>,
+    /// 记录每条边的 A* 统计（`render_with_stats`；开启时只有 `parallel_routing = Some(true)` 才并行预演）。
+    collect_stats: bool,
+    /// 记录每条边的走线调试帧（`render_with_debug`；开启时不并行预演）。
+    collect_frames: bool,
+}
+
+impl Config {
+    /// 默认值对齐 JS：padding 5/5、boxBorderPadding 1；routing 在 ASCII 下默认 strict。
+    fn from_options(options: &AsciiRenderOptions) -> Self {
+        let use_ascii = options.use_ascii.unwrap_or(false);
+        Self {
+            use_ascii,
+            padding_x: options.padding_x.unwrap_or(5),
+            padding_y: options.padding_y.unwrap_or(5),
+            box_border_padding: options.box_border_padding.unwrap_or(1),
+            horizontal: false,
+            routing: options.routing.unwrap_or(if use_ascii {
+                AsciiRouting::Strict
+            } else {
+                AsciiRouting::Relaxed
+            }),
+            turn_penalty: options.effective_turn_penalty(),
+            enclosure_probe: options.enclosure_probe(),
+            parallel_routing: options.parallel_routing,
+            collect_stats: false,
+            collect_frames: false,
+        }
+    }
+
+    /// global 的逐条走线阶段就是 relaxed，规则完全相同。
+    fn relaxed(&self) -> bool {
+        matches!(self.routing, AsciiRouting::Relaxed | AsciiRouting::Global)
+    }
+
+    /// relaxed 全部走通后再做一轮 rip-up-and-reroute（见 `reroute`）。
+    fn global(&self) -> bool {
+        self.routing == AsciiRouting::Global
+    }
+
+    /// relaxed + Unicode：bundle 里有一整套额外规则（端口分散、label 堆叠等）只在这个组合下生效。
+    fn relaxed_unicode(&self) -> bool {
+        self.relaxed() && !self.use_ascii
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 坐标与方向
+// ----------------------------------------------------------------------------
+
+/// grid 坐标或画布坐标（取决于上下文）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+impl Point {
+    const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    fn offset(self, by: Point) -> Self {
+        Self::new(self.x + by.x, self.y + by.y)
+    }
+}
+
+// 方向用节点 3×3 占位里的相对坐标表示（对齐 JS 的 Up/Down/... 常量）。
+const UP: Point = Point::new(1, 0);
+const DOWN: Point = Point::new(1, 2);
+const LEFT: Point = Point::new(0, 1);
+const RIGHT: Point = Point::new(2, 1);
+const UPPER_RIGHT: Point = Point::new(2, 0);
+const UPPER_LEFT: Point = Point::new(0, 0);
+const LOWER_RIGHT: Point = Point::new(2, 2);
+const LOWER_LEFT: Point = Point::new(0, 2);
+const MIDDLE: Point = Point::new(1, 1);
+
+fn is_cardinal(dir: Point) -> bool {
+    matches!(dir, UP | DOWN | LEFT | RIGHT)
+}
+
+fn opposite(dir: Point) -> Point {
+    match dir {
+        UP => DOWN,
+        DOWN => UP,
+        LEFT => RIGHT,
+        RIGHT => LEFT,
+        UPPER_RIGHT => LOWER_LEFT,
+        UPPER_LEFT => LOWER_RIGHT,
+        LOWER_RIGHT => UPPER_LEFT,
+        LOWER_LEFT => UPPER_RIGHT,
+        _ => MIDDLE,
+    }
+}
+
+/// `from` 指向 `to` 的方向（同一点时返回 `UPPER_LEFT`，与 JS 一致）。
+fn direction_between(from: Point, to: Point) -> Point {
+    if from.x == to.x {
+        if from.y < to.y { DOWN } else { UP }
+    } else if from.y == to.y {
+        if from.x < to.x { RIGHT } else { LEFT }
+    } else if from.x < to.x {
+        if from.y < to.y {
+            LOWER_RIGHT
+        } else {
+            UPPER_RIGHT
+        }
+    } else if from.y < to.y {
+        LOWER_LEFT
+    } else {
+        UPPER_LEFT
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 布局用的图结构
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+struct Node {
+    name: String,
+    display_label: String,
+    grid_coord: Option<Point>,
+    drawing_coord: Option<Point>,
+    drawing: Option<Canvas>,
+    drawn: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Edge {
+    from: usize,
+    to: usize,
+    text: String,
+    path: Vec<Point>,
+    label_line: Vec<Point>,
+    start_dir: Point,
+    end_dir: Point,
+    start_port_offset_x: Option<i32>,
+    start_port_offset_y: Option<i32>,
+    end_port_offset_x: Option<i32>,
+    end_port_offset_y: Option<i32>,
+    /// 这条边的路径是怎么找到的（统计用，不影响绘制）。
+    route: RouteInfo,
+}
+
+/// 一条边最终采用的路径来自哪次搜索。
+#[derive(Debug, Clone, Copy, Default)]
+struct RouteInfo {
+    /// 该次 A* 的路径代价；固定形状的自环、复用平行边路径时为 `None`。
+    cost: Option<u32>,
+    /// relaxed：来自允许终点线段复用的那一轮搜索。
+    end_segment_reuse: bool,
+    /// 最后一次布局尝试里的无约束兜底搜索。
+    unconstrained: bool,
+    /// 复用同向平行边（leader）的路径，自己没有搜索。
+    shared_path: bool,
+    /// 没按规则走通的原因：有路径时是兜底前失败的规则，没有路径时是走不通的原因。
+    fallback: Option<AsciiRouteReason>,
+}
+
+impl Edge {
+    fn is_self_loop(&self) -> bool {
+        self.from == self.to
+    }
+
+    /// 走线结果与原因（meta / `fail_on_unroutable` 用）。
+    fn outcome(&self) -> (AsciiRouteOutcome, Option<AsciiRouteReason>) {
+        let outcome = if self.path.len() < 2 {
+            AsciiRouteOutcome::Unroutable
+        } else if self.route.fallback.is_some() {
+            AsciiRouteOutcome::RoutedWithFallback
+        } else {
+            AsciiRouteOutcome::Routed
+        };
+        (outcome, self.route.fallback)
+    }
+
+    fn reset(&mut self) {
+        self.path.clear();
+        self.label_line.clear();
+        self.start_dir = Point::default();
+        self.end_dir = Point::default();
+        self.route = RouteInfo::default();
+        self.clear_port_offsets();
+    }
+
+    fn clear_port_offsets(&mut self) {
+        self.start_port_offset_x = None;
+        self.start_port_offset_y = None;
+        self.end_port_offset_x = None;
+        self.end_port_offset_y = None;
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Subgraph {
+    name: String,
+    nodes: Vec<usize>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    min_x: i32,
+    min_y: i32,
+    max_x: i32,
+    max_y: i32,
+}
+
+#[derive(Debug, Clone)]
+struct Graph {
+    config: Config,
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    /// 按 AST 先序排列（父在子前）。
+    subgraphs: Vec<Subgraph>,
+    canvas: Canvas,
+    grid: HashMap<Point, usize>,
+    column_width: HashMap<i32, i32>,
+    row_height: HashMap<i32, i32>,
+    column_start_x: Option<Vec<i32>>,
+    row_start_y: Option<Vec<i32>>,
+    offset_x: i32,
+    offset_y: i32,
+    /// relaxed：每个节点 9 个端口的使用次数（index * 9 + dir.x + dir.y * 3）。
+    port_usage: Option<Vec<u16>>,
+    /// 用了第几次布局尝试（从 1 开始）。
+    layout_attempts: u32,
+    /// 最后一次布局尝试里并行预演的走线区域数（0 = 逐条走线）。
+    parallel_regions: u32,
+    /// `config.collect_stats` 时：最后一次布局尝试里每条边的搜索统计（按边下标）。
+    route_stats: Option<Vec<AsciiEdgeRoutingStats>>,
+    /// `config.collect_frames` 时：最后一次布局尝试里按走线顺序的调试帧（网格坐标，BT 不翻转）。
+    route_frames: Option<Vec<DebugFrame>>,
+}
+
+impl Graph {
+    fn build(ast: &flowchart::FlowchartGraph, config: Config) -> Self {
+        let nodes: Vec<Node> = ast
+            .nodes
+            .iter()
+            .map(|node| Node {
+                name: node.id.clone(),
+                display_label: node.label.clone(),
+                grid_coord: None,
+                drawing_coord: None,
+                drawing: None,
+                drawn: false,
+            })
+            .collect();
+
+        let edges = ast
+            .edges
+            .iter()
+            .filter_map(|edge| {
+                let from = ast.node_position(&edge.source)?;
+                let to = ast.node_position(&edge.target)?;
+                Some(Edge {
+                    from,
+                    to,
+                    text: edge.label.clone().unwrap_or_default(),
+                    path: Vec::new(),
+                    label_line: Vec::new(),
+                    start_dir: Point::default(),
+                    end_dir: Point::default(),
+                    start_port_offset_x: None,
+                    start_port_offset_y: None,
+                    end_port_offset_x: None,
+                    end_port_offset_y: None,
+                    route: RouteInfo::default(),
+                })
+            })
+            .collect();
+
+        let mut subgraphs = Vec::new();
+        for subgraph in &ast.subgraphs {
+            build_subgraph(subgraph, None, ast, &mut subgraphs);
+        }
+        restrict_subgraph_nodes(ast, &mut subgraphs);
+
+        Self {
+            config,
+            nodes,
+            edges,
+            subgraphs,
+            canvas: Canvas::new(0, 0),
+            grid: HashMap::new(),
+            column_width: HashMap::new(),
+            row_height: HashMap::new(),
+            column_start_x: None,
+            row_start_y: None,
+            offset_x: 0,
+            offset_y: 0,
+            port_usage: None,
+            layout_attempts: 0,
+            parallel_regions: 0,
+            route_stats: None,
+            route_frames: None,
+        }
+    }
+
+    fn column_width_at(&self, x: i32) -> i32 {
+        self.column_width.get(&x).copied().unwrap_or(0)
+    }
+
+    fn row_height_at(&self, y: i32) -> i32 {
+        self.row_height.get(&y).copied().unwrap_or(0)
+    }
+
+    /// 节点 `targets`（按边的顺序，含重复）。
+    fn children_of(&self, node: usize) -> Vec<usize> {
+        self.edges
+            .iter()
+            .filter(|edge| edge.from == node)
+            .map(|edge| edge.to)
+            .collect()
+    }
+
+    fn is_in_subgraph(&self, node: usize) -> bool {
+        self.subgraphs.iter().any(|sg| sg.nodes.contains(&node))
+    }
+
+    /// 第一个包含该节点的 subgraph（先序）。
+    fn subgraph_of(&self, node: usize) -> Option<usize> {
+        self.subgraphs
+            .iter()
+            .position(|sg| sg.nodes.contains(&node))
+    }
+}
+
+/// 先序展开 subgraph；父 subgraph 的节点集合包含所有子孙的节点。
+fn build_subgraph(
+    ast: &FlowSubgraph,
+    parent: Option<usize>,
+    graph: &flowchart::FlowchartGraph,
+    out: &mut Vec<Subgraph>,
+) -> usize {
+    let index = out.len();
+    out.push(Subgraph {
+        name: ast.label.clone(),
+        nodes: ast
+            .node_ids
+            .iter()
+            .filter_map(|id| graph.node_position(id))
+            .collect(),
+        parent,
+        children: Vec::new(),
+        min_x: 0,
+        min_y: 0,
+        max_x: 0,
+        max_y: 0,
+    });
+
+    for child in &ast.children {
+        let child_index = build_subgraph(child, Some(index), graph, out);
+        out[index].children.push(child_index);
+        let child_nodes = out[child_index].nodes.clone();
+        for node in child_nodes {
+            if !out[index].nodes.contains(&node) {
+                out[index].nodes.push(node);
+            }
+        }
+    }
+    index
+}
+
+/// 一个节点在多个 subgraph 里被引用时，只保留“最深的直接声明者”及其祖先。
+fn restrict_subgraph_nodes(ast: &flowchart::FlowchartGraph, subgraphs: &mut [Subgraph]) {
+    fn collect_preorder<'a>(list: &'a [FlowSubgraph], out: &mut Vec<&'a FlowSubgraph>) {
+        for subgraph in list {
+            out.push(subgraph);
+            collect_preorder(&subgraph.children, out);
+        }
+    }
+
+    fn assign_owners(
+        subgraph: &FlowSubgraph,
+        preorder: &[&FlowSubgraph],
+        owners: &mut HashMap<String, usize>,
+    ) {
+        let Some(index) = preorder.iter().position(|s| std::ptr::eq(*s, subgraph)) else {
+            return;
+        };
+        for child in &subgraph.children {
+            assign_owners(child, preorder, owners);
+        }
+        for id in &subgraph.node_ids {
+            owners.entry(id.clone()).or_insert(index);
+        }
+    }
+
+    let mut preorder = Vec::new();
+    collect_preorder(&ast.subgraphs, &mut preorder);
+    let mut owners = HashMap::new();
+    for subgraph in &ast.subgraphs {
+        assign_owners(subgraph, &preorder, &mut owners);
+    }
+
+    let parents: Vec<Option<usize>> = subgraphs.iter().map(|sg| sg.parent).collect();
+    let is_self_or_ancestor = |candidate: usize, of: usize| {
+        let mut current = Some(of);
+        while let Some(index) = current {
+            if index == candidate {
+                return true;
+            }
+            current = parents[index];
+        }
+        false
+    };
+
+    for (index, subgraph) in subgraphs.iter_mut().enumerate() {
+        subgraph.nodes.retain(|&node| {
+            let name = &ast.nodes[node].id;
+            owners
+                .get(name)
+                .is_none_or(|&owner| is_self_or_ancestor(index, owner))
+        });
+    }
+}
@@ -0,0 +1,426 @@
+// ============================================================================
+// 并行走线：互不重叠的走线区域先在各自的线程上预演，再按原顺序逐条校验、落定
+//
+// 说明：
+// - 区域：连通分量按节点占的 grid 外接矩形归组，矩形重叠的分量合成一个区域
+//   （grid 放置按 BFS 层把分量交错排开，交错的分量之间路径多半贴在一起，分开预演也是白算）；
+// - 不同区域的边没有公共端点，但逐条走线时仍可能互相影响：
+//   - 占用表：先走的路径会挡住 / 加罚后走的搜索（绕远的路径可能穿过别的区域）；
+//   - 列宽 / 行高：先走的路径撑开的行列会改变后面的边算出的包围盒方向（`facing_cost`）；
+//   - strict 下“占用表是否为空”决定先走 free 还是 strict 搜索；
+// - 预演：每个区域在空占用表上按原顺序走一遍，记下每条边的结果、上面两个外部输入，
+//   以及它在占用表上读过的格子（走通的 strict/relaxed 搜索展开过的格子 + 四邻域 + 自己的路径）；
+//   走不通的搜索不用记：占用只增不减，别的区域的路径只会让它继续走不通；
+// - 落定：主流程照常按顺序处理每条边。外部输入与预演时一致、读过的格子上也没有别的区域的路径，
+//   就直接写入预演结果（合并占用表）；否则这个区域从这条边起改为逐条走线（它后面的预演结果都作废），
+//   其他区域不受影响。所以输出与逐条走线逐字节相同，只是更快；
+// - 统计：显式 `parallel_routing = Some(true)` 时统计也走预演，落定的边记预演时的搜索
+//   （`AsciiEdgeRoutingStats.rehearsed`），测试靠它确认多个区域确实是并行预演、整段落定的。
+// ============================================================================
+
+use super::layout::RoutePass;
+use super::routing::{self, Router};
+use super::{Graph, Point, RouteInfo};
+use crate::types::AsciiEdgeRoutingStats;
+use std::num::NonZeroUsize;
+
+/// 自动模式下边数少于这个值时不开线程：克隆图与网格的开销比走线本身还大。
+const MIN_PARALLEL_EDGES: usize = 32;
+
+/// `Plan::owners` 里“被多个区域的路径占用”的标记。
+const SHARED: u32 = u32::MAX;
+
+/// 预演时一条边（真正走线的那条，不含跟随 leader 的平行边）的结果。
+struct Outcome {
+    /// 包围盒方向（`bbox_delta` 的符号，`facing_cost` 只看它）。
+    facing: Option<(i32, i32)>,
+    /// 走线前占用表是否非空（见 `Router::has_routes`）。
+    has_routes: bool,
+    path: Vec<Point>,
+    start_dir: Point,
+    end_dir: Point,
+    route: RouteInfo,
+    /// 写进占用表的逐格路径（走不通时为空）。
+    cells: Vec<u32>,
+    /// 走线时读过的格子（去重）。
+    reads: Vec<u32>,
+    /// 预演时这条边的搜索统计（不统计时为 `None`）。
+    stats: Option<AsciiEdgeRoutingStats>,
+}
+
+/// 预演结果，由主流程按走线顺序逐条取用。
+pub(super) struct Plan {
+    /// 按边下标索引；取用后置空。
+    outcomes: Vec<Option<Outcome>>,
+    /// 每条边所在的区域。
+    region_of: Vec<usize>,
+    /// 区域是否已改为逐条走线。
+    diverged: Vec<bool>,
+    /// 每个格子被哪个区域的路径占用：0 = 空，`k + 1` = 区域 k，`SHARED` = 多个区域。
+    owners: Vec<u32>,
+}
+
+impl Plan {
+    /// 预演的区域数。
+    pub(super) fn regions(&self) -> u32 {
+        self.diverged.len() as u32
+    }
+
+    fn route_edge(&mut self, graph: &mut Graph, edge: usize, router: &mut Router) {
+        let region = self.region_of[edge];
+        let outcome = self.outcomes[edge].take();
+        let cells = match outcome {
+            Some(outcome)
+                if !self.diverged[region] && self.still_valid(graph, edge, router, &outcome) =>
+            {
+                let (from, to) = (graph.edges[edge].from, graph.edges[edge].to);
+                let edge_ref = &mut graph.edges[edge];
+                edge_ref.start_dir = outcome.start_dir;
+                edge_ref.end_dir = outcome.end_dir;
+                edge_ref.route = outcome.route;
+                edge_ref.path = outcome.path;
+                if let (Some(stats), Some(rehearsed)) = (&mut router.stats, outcome.stats) {
+                    stats[edge] = AsciiEdgeRoutingStats {
+                        rehearsed: true,
+                        ..rehearsed
+                    };
+                }
+                if !outcome.cells.is_empty() {
+                    router
+                        .grid
+                        .record(from as u32 + 1, to as u32 + 1, &outcome.cells);
+                    routing::claim_ports(graph, edge);
+                }
+                outcome.cells
+            }
+            _ => {
+                self.diverged[region] = true;
+                routing::route_edge(graph, edge, router);
+                routing::expand(router, &graph.edges[edge].path)
+            }
+        };
+        let owner = region as u32 + 1;
+        for cell in cells {
+            let slot = &mut self.owners[cell as usize];
+            if *slot == 0 {
+                *slot = owner;
+            } else if *slot != owner {
+                *slot = SHARED;
+            }
+        }
+    }
+
+    /// 预演结果在当前状态下是否仍然成立（外部输入相同，读过的格子上没有别的区域的路径）。
+    fn still_valid(&self, graph: &Graph, edge: usize, router: &Router, outcome: &Outcome) -> bool {
+        let owner = self.region_of[edge] as u32 + 1;
+        facing(graph, edge) == outcome.facing
+            && router.has_routes() == outcome.has_routes
+            && outcome.reads.iter().all(|&cell| {
+                let slot = self.owners[cell as usize];
+                slot == 0 || slot == owner
+            })
+    }
+}
+
+/// 走一条边：有预演结果就先校验再用，否则逐条走线。
+pub(super) fn route_edge(
+    graph: &mut Graph,
+    edge: usize,
+    router: &mut Router,
+    plan: &mut Option<Plan>,
+) {
+    match plan {
+        Some(plan) => plan.route_edge(graph, edge, router),
+        None => routing::route_edge(graph, edge, router),
+    }
+}
+
+/// 按走线区域并行预演；不值得并行时返回 `None`。
+///
+/// `router` 必须还没有走过任何边（占用表为空）。记录调试帧时不预演：帧要按逐条走线时的搜索来记；
+/// 统计 A* 时只有显式打开才预演（预演里走不通的搜索与逐条走线时的不一定相同）。
+pub(super) fn plan(graph: &Graph, router: &Router, order: &[usize]) -> Option<Plan> {
+    let forced = match graph.config.parallel_routing {
+        Some(false) => return None,
+        Some(true) => true,
+        None => false,
+    };
+    if router.frames.is_some() || (router.stats.is_some() && !forced) {
+        return None;
+    }
+    if cfg!(target_arch = "wasm32") || (!forced && order.len() < MIN_PARALLEL_EDGES) {
+        return None;
+    }
+    let regions = regions(graph, order);
+    let cores = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let threads = if forced { cores.max(2) } else { cores }.min(regions.len());
+    if threads < 2 {
+        return None;
+    }
+
+    // 边多的区域先分，每次分给当前负担最轻的线程
+    let mut by_size: Vec<usize> = (0..regions.len()).collect();
+    by_size.sort_by_key(|&region| std::cmp::Reverse(regions[region].len()));
+    let mut buckets: Vec<(usize, Vec<usize>)> = vec![(0, Vec::new()); threads];
+    for region in by_size {
+        let bucket = buckets
+            .iter_mut()
+            .min_by_key(|(load, _)| *load)
+            .expect("至少两个线程");
+        bucket.0 += regions[region].len();
+        bucket.1.push(region);
+    }
+
+    let mut outcomes: Vec<Option<Outcome>> = (0..graph.edges.len()).map(|_| None).collect();
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = buckets
+            .into_iter()
+            .map(|(_, bucket)| {
+                let regions = &regions;
+                scope.spawn(move || {
+                    let mut worker = Worker::new(graph, router);
+                    let mut outcomes = Vec::new();
+                    for region in bucket {
+                        // 第 0 个区域里有整张图第一条走线的边，其余区域走线时占用表已经非空
+                        worker.rehearse(graph, &regions[region], region != 0, &mut outcomes);
+                    }
+                    outcomes
+                })
+            })
+            .collect();
+        for worker in workers {
+            for (edge, outcome) in worker.join().expect("预演线程 panic") {
+                outcomes[edge] = Some(outcome);
+            }
+        }
+    });
+
+    let mut region_of = vec![0; graph.edges.len()];
+    for (region, edges) in regions.iter().enumerate() {
+        for &edge in edges {
+            region_of[edge] = region;
+        }
+    }
+    Some(Plan {
+        outcomes,
+        region_of,
+        diverged: vec![false; regions.len()],
+        owners: vec![0; (router.grid.width() * router.grid.height()) as usize],
+    })
+}
+
+/// 预演线程的草稿：图与网格各克隆一份，区域之间复用。
+struct Worker {
+    scratch: Graph,
+    router: Router,
+    /// 去重用：`seen[cell] == stamp` 表示当前这条边已经记过这个格子。
+    seen: Vec<u32>,
+    stamp: u32,
+}
+
+impl Worker {
+    fn new(graph: &Graph, router: &Router) -> Self {
+        // 走线之前克隆，占用表是空的
+        debug_assert_eq!(router.grid.used_segments(), 0);
+        let cells = (router.grid.width() * router.grid.height()) as usize;
+        Self {
+            scratch: graph.clone(),
+            router: router.clone(),
+            seen: vec![0; cells],
+            stamp: 0,
+        }
+    }
+
+    /// 在空占用表上按顺序预演一个区域的边，结果追加到 `outcomes`；走完把占用表还原成空的。
+    fn rehearse(
+        &mut self,
+        base: &Graph,
+        edges: &[usize],
+        assume_routes: bool,
+        outcomes: &mut Vec<(usize, Outcome)>,
+    ) {
+        let Self {
+            scratch,
+            router,
+            seen,
+            stamp,
+        } = self;
+        // 同一线程上一个区域撑开的行列不能带进来（端口占用按节点分槽，区域之间互不相干）
+        scratch.column_width.clone_from(&base.column_width);
+        scratch.row_height.clone_from(&base.row_height);
+        router.assume_routes = assume_routes;
+
+        let (width, height) = (router.grid.width() as usize, router.grid.height() as usize);
+        let first = outcomes.len();
+        let mut pass = RoutePass::new(scratch, edges);
+        for &edge in edges {
+            pass.step(scratch, edge, |scratch| {
+                let facing = facing(scratch, edge);
+                let has_routes = router.has_routes();
+                router.grid.start_trace();
+                routing::route_edge(scratch, edge, router);
+                let stats = router
+                    .stats
+                    .as_mut()
+                    .map(|stats| std::mem::take(&mut stats[edge]));
+                let edge_ref = &scratch.edges[edge];
+                let cells = routing::expand(router, &edge_ref.path);
+
+                *stamp += 1;
+                let mut reads = Vec::new();
+                let mut note = |cell: usize| {
+                    if seen[cell] != *stamp {
+                        seen[cell] = *stamp;
+                        reads.push(cell as u32);
+                    }
+                };
+                for cell in router
+                    .grid
+                    .take_trace()
+                    .into_iter()
+                    .chain(cells.iter().copied())
+                {
+                    let cell = cell as usize;
+                    let (x, y) = (cell % width, cell / width);
+                    note(cell);
+                    if x > 0 {
+                        note(cell - 1);
+                    }
+                    if x + 1 < width {
+                        note(cell + 1);
+                    }
+                    if y > 0 {
+                        note(cell - width);
+                    }
+                    if y + 1 < height {
+                        note(cell + width);
+                    }
+                }
+                outcomes.push((
+                    edge,
+                    Outcome {
+                        facing,
+                        has_routes,
+                        path: edge_ref.path.clone(),
+                        start_dir: edge_ref.start_dir,
+                        end_dir: edge_ref.end_dir,
+                        route: edge_ref.route,
+                        cells,
+                        reads,
+                        stats,
+                    },
+                ));
+            });
+        }
+        // 占用表上只有这个区域的路径：逐条撤销比整张表清零便宜得多
+        router.grid.forget(
+            outcomes[first..]
+                .iter()
+                .map(|(_, outcome)| outcome.cells.as_slice()),
+        );
+    }
+}
+
+fn facing(graph: &Graph, edge: usize) -> Option<(i32, i32)> {
+    routing::bbox_delta(graph, edge).map(|(dx, dy)| (dx.signum(), dy.signum()))
+}
+
+/// 节点占用的 grid 范围（含两端）。
+#[derive(Clone, Copy)]
+struct Hull {
+    min: Point,
+    max: Point,
+}
+
+impl Hull {
+    fn overlaps(&self, other: &Hull) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    fn union(&self, other: &Hull) -> Hull {
+        Hull {
+            min: Point::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Point::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+}
+
+/// 互不重叠的走线区域：连通分量按节点占的 grid 范围（外接矩形）归组，矩形有重叠的分量合成一个区域。
+///
+/// grid 放置按 BFS 层把所有分量交错排开，交错的分量之间路径多半会贴在一起，分开预演也是白算。
+/// 返回每个区域的边（保持 `order` 中的先后）；第 0 个区域含 `order[0]`。
+fn regions(graph: &Graph, order: &[usize]) -> Vec<Vec<usize>> {
+    fn find(parent: &mut [usize], mut node: usize) -> usize {
+        while parent[node] != node {
+            parent[node] = parent[parent[node]];
+            node = parent[node];
+        }
+        node
+    }
+
+    let mut parent: Vec<usize> = (0..graph.nodes.len()).collect();
+    for &edge in order {
+        let a = find(&mut parent, graph.edges[edge].from);
+        let b = find(&mut parent, graph.edges[edge].to);
+        parent[a] = b;
+    }
+
+    // 连通分量：外接矩形 + 边在 `order` 里的位置
+    let mut group_of: Vec<Option<usize>> = vec![None; graph.nodes.len()];
+    let mut groups: Vec<(Option<Hull>, Vec<usize>)> = Vec::new();
+    for (position, &edge) in order.iter().enumerate() {
+        let root = find(&mut parent, graph.edges[edge].from);
+        let group = *group_of[root].get_or_insert_with(|| {
+            groups.push((None, Vec::new()));
+            groups.len() - 1
+        });
+        groups[group].1.push(position);
+    }
+    for node in 0..graph.nodes.len() {
+        let root = find(&mut parent, node);
+        if let (Some(group), Some(coord)) = (group_of[root], graph.nodes[node].grid_coord) {
+            let hull = Hull {
+                min: coord,
+                max: Point::new(coord.x + 2, coord.y + 2),
+            };
+            let slot = &mut groups[group].0;
+            *slot = Some(slot.map_or(hull, |current| current.union(&hull)));
+        }
+    }
+
+    // 矩形重叠就合并（合并后的矩形变大，可能又与别的重叠），直到两两不重叠
+    'merge: loop {
+        for i in 0..groups.len() {
+            for j in i + 1..groups.len() {
+                let overlapping = match (groups[i].0, groups[j].0) {
+                    (Some(a), Some(b)) => a.overlaps(&b),
+                    _ => true,
+                };
+                if overlapping {
+                    let (hull, positions) = groups.remove(j);
+                    groups[i].0 = match (groups[i].0, hull) {
+                        (Some(a), Some(b)) => Some(a.union(&b)),
+                        (a, b) => a.or(b),
+                    };
+                    groups[i].1.extend(positions);
+                    continue 'merge;
+                }
+            }
+        }
+        break;
+    }
+
+    groups
+        .into_iter()
+        .map(|(_, mut positions)| {
+            positions.sort_unstable();
+            positions
+                .into_iter()
+                .map(|position| order[position])
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,248 @@
+// ============================================================================
+// 全局走线（`AsciiRouting::Global`）：逐条走线之后的 rip-up-and-reroute
+//
+// 说明：
+// - 逐条走线时先走的边会占掉好通道，后走的边只能绕“大矩形”
+//   （见 `docs/terminal-readable-diagrams.md`）；
+// - 这里在 relaxed 规则全部走通之后给整张图打分（交叉 / 汇合 / 拐点 / 长度），
+//   按“最差”的顺序把边拆掉，在其余边都已落定的占用表上重走，总分严格下降才接受；
+// - 只拆一条边重走时其余边不变，A* 多半给出原样的路径，所以连同与它交叉/汇合的边一起拆；
+// - 只改走线，不动节点位置；一轮没有任何改进、跑满轮数或用完工作量预算就停。
+// ============================================================================
+
+use super::routing::{self, Router};
+use super::{Graph, is_cardinal};
+use crate::routing::{CONNECT_DOWN, CONNECT_LEFT, CONNECT_RIGHT, CONNECT_UP};
+
+/// 一个 `┼`（四个方向都有线）的代价。
+const CROSSING_COST: i64 = 16;
+/// 三岔（`├┤┬┴`）的代价：同源共享也会产生三岔，所以比交叉轻。
+const JUNCTION_COST: i64 = 4;
+/// Unicode 下从节点角上出线（对角端口）的代价：角上的 box 字符会被线盖掉，比交叉还难读。
+const DIAGONAL_PORT_COST: i64 = 32;
+/// 每个拐点的代价（长度按格子数计 1）。
+const BEND_COST: i64 = 2;
+/// 最多重走几轮（每轮把所有可重走的边按分数从差到好各试一次）。
+const MAX_ROUNDS: usize = 4;
+/// 工作量预算（`Router.work`：A* 展开 + 封闭探测看过的格子）：只给大图兜底。
+///
+/// testdata 里最费的图约 14 万，随机 9 节点 14 边的小图最多两千多万（release 约 3 秒）；
+/// 按工作量而不是时间计，停在哪一步与机器快慢、debug / release、wasm 都无关。
+const WORK_BUDGET: u64 = 4_000_000;
+
+/// 对已经全部走通的边做 rip-up-and-reroute。
+///
+/// - `owned`：按走线顺序排列、真正写进占用表的边；
+/// - `followers[edge]`：复用 `edge` 路径的平行边（relaxed + Unicode），随 leader 一起更新。
+pub(super) fn improve(
+    graph: &mut Graph,
+    router: &mut Router,
+    owned: &[usize],
+    followers: &[Vec<usize>],
+) {
+    let budget = router.work + WORK_BUDGET;
+    let mut cells: Vec<Vec<u32>> = vec![Vec::new(); graph.edges.len()];
+    for &edge in owned {
+        cells[edge] = routing::expand(router, &graph.edges[edge].path);
+    }
+    let mut best = total_score(graph, router, owned, &cells);
+
+    for _ in 0..MAX_ROUNDS {
+        let mut improved = false;
+        for edge in ranking(graph, router, owned, &cells) {
+            if router.work >= budget {
+                return;
+            }
+            // “最差”的边先走，与它冲突的边按原顺序跟上
+            let mut group = vec![edge];
+            group.extend(conflicting(graph, router, owned, &cells, edge));
+            let saved: Vec<_> = group
+                .iter()
+                .map(|&member| {
+                    let edge_ref = &graph.edges[member];
+                    (
+                        edge_ref.path.clone(),
+                        edge_ref.start_dir,
+                        edge_ref.end_dir,
+                        edge_ref.route,
+                    )
+                })
+                .collect();
+            let saved_cells: Vec<Vec<u32>> = group
+                .iter()
+                .map(|&member| std::mem::take(&mut cells[member]))
+                .collect();
+
+            for &member in &group {
+                routing::release_ports(graph, member);
+            }
+            rebuild(router, graph, owned, &cells);
+            for &member in &group {
+                routing::route_edge(graph, member, router);
+                if graph.edges[member].path.len() >= 2 {
+                    cells[member] = routing::expand(router, &graph.edges[member].path);
+                }
+            }
+
+            let routed = group
+                .iter()
+                .all(|&member| graph.edges[member].path.len() >= 2);
+            if routed {
+                let score = total_score(graph, router, owned, &cells);
+                if score < best {
+                    best = score;
+                    improved = true;
+                    for &member in &group {
+                        copy_to_followers(graph, member, &followers[member]);
+                    }
+                    continue;
+                }
+            }
+
+            // 没变好：恢复原路径与端口占用
+            for (&member, (path, start_dir, end_dir, route)) in group.iter().zip(saved) {
+                if graph.edges[member].path.len() >= 2 {
+                    routing::release_ports(graph, member);
+                }
+                let edge_ref = &mut graph.edges[member];
+                edge_ref.path = path;
+                edge_ref.start_dir = start_dir;
+                edge_ref.end_dir = end_dir;
+                edge_ref.route = route;
+                routing::claim_ports(graph, member);
+            }
+            for (&member, member_cells) in group.iter().zip(saved_cells) {
+                cells[member] = member_cells;
+            }
+            rebuild(router, graph, owned, &cells);
+        }
+        if !improved {
+            break;
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 打分
+// ----------------------------------------------------------------------------
+
+/// 单元格的连线数：4 = 交叉，3 = 三岔。
+fn arms(router: &Router, index: u32) -> u32 {
+    let bits = router.grid.connections_index(index);
+    [CONNECT_RIGHT, CONNECT_LEFT, CONNECT_DOWN, CONNECT_UP]
+        .iter()
+        .filter(|&&bit| bits & bit != 0)
+        .count() as u32
+}
+
+fn cell_cost(router: &Router, index: u32) -> i64 {
+    match arms(router, index) {
+        4 => CROSSING_COST,
+        3 => JUNCTION_COST,
+        _ => 0,
+    }
+}
+
+/// 单条边自身的代价：长度 + 拐点 + 对角端口（交叉/三岔按格子算在 `total_score` 里）。
+fn edge_cost(graph: &Graph, edge: usize, cells: &[u32]) -> i64 {
+    let edge_ref = &graph.edges[edge];
+    let bends = edge_ref.path.len().saturating_sub(2) as i64;
+    let diagonal_ports = if graph.config.use_ascii {
+        0
+    } else {
+        [edge_ref.start_dir, edge_ref.end_dir]
+            .into_iter()
+            .filter(|&dir| !is_cardinal(dir))
+            .count() as i64
+    };
+    cells.len().saturating_sub(1) as i64 + bends * BEND_COST + diagonal_ports * DIAGONAL_PORT_COST
+}
+
+fn total_score(graph: &Graph, router: &Router, owned: &[usize], cells: &[Vec<u32>]) -> i64 {
+    let mut used: Vec<u32> = owned
+        .iter()
+        .flat_map(|&edge| cells[edge].iter().copied())
+        .collect();
+    used.sort_unstable();
+    used.dedup();
+    let shared: i64 = used.iter().map(|&index| cell_cost(router, index)).sum();
+    let own: i64 = owned
+        .iter()
+        .map(|&edge| edge_cost(graph, edge, &cells[edge]))
+        .sum();
+    shared + own
+}
+
+/// 可重走的边（自环保持固定形状），按分数从差到好排序；同分保持走线顺序。
+fn ranking(graph: &Graph, router: &Router, owned: &[usize], cells: &[Vec<u32>]) -> Vec<usize> {
+    let mut scored: Vec<(i64, usize)> = owned
+        .iter()
+        .filter(|&&edge| !graph.edges[edge].is_self_loop())
+        .map(|&edge| {
+            let shared: i64 = cells[edge]
+                .iter()
+                .map(|&index| cell_cost(router, index))
+                .sum();
+            (shared + edge_cost(graph, edge, &cells[edge]), edge)
+        })
+        .collect();
+    scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+    scored.into_iter().map(|(_, edge)| edge).collect()
+}
+
+/// 与 `edge` 在交叉/三岔格子上相遇的其它可重走边（按走线顺序）。
+fn conflicting(
+    graph: &Graph,
+    router: &Router,
+    owned: &[usize],
+    cells: &[Vec<u32>],
+    edge: usize,
+) -> Vec<usize> {
+    let mut hot: Vec<u32> = cells[edge]
+        .iter()
+        .copied()
+        .filter(|&index| cell_cost(router, index) > 0)
+        .collect();
+    hot.sort_unstable();
+    owned
+        .iter()
+        .copied()
+        .filter(|&other| other != edge && !graph.edges[other].is_self_loop())
+        .filter(|&other| {
+            cells[other]
+                .iter()
+                .any(|index| hot.binary_search(index).is_ok())
+        })
+        .collect()
+}
+
+// ----------------------------------------------------------------------------
+// 占用表
+// ----------------------------------------------------------------------------
+
+/// 清空占用表，按走线顺序重新写入 `cells` 里非空的路径。
+fn rebuild(router: &mut Router, graph: &Graph, owned: &[usize], cells: &[Vec<u32>]) {
+    router.grid.clear_routes();
+    for &edge in owned {
+        if cells[edge].is_empty() {
+            continue;
+        }
+        let (from, to) = (graph.edges[edge].from, graph.edges[edge].to);
+        router
+            .grid
+            .record(from as u32 + 1, to as u32 + 1, &cells[edge]);
+    }
+}
+
+fn copy_to_followers(graph: &mut Graph, leader: usize, followers: &[usize]) {
+    let (path, start_dir, end_dir) = {
+        let leader = &graph.edges[leader];
+        (leader.path.clone(), leader.start_dir, leader.end_dir)
+    };
+    for &edge in followers {
+        let edge_ref = &mut graph.edges[edge];
+        edge_ref.path = path.clone();
+        edge_ref.start_dir = start_dir;
+        edge_ref.end_dir = end_dir;
+    }
+}
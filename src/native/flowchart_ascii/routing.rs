@@ -0,0 +1,1179 @@
+// ============================================================================
+// 边走线：候选端口 × 搜索半径 × 代价函数（对齐 JS bundle 的 `determinePath`）
+//
+// 说明：
+// - A* 与占用表复用 `crate::routing::RoutingGrid`（底层与 QuickJS 里注入的 `__bm_getPath*` 是同一份实现）；
+// - 这里负责的是 A* 外面那一层：枚举起止端口、按 tier 扩大搜索范围、按代价挑最优候选，
+//   以及走通之后更新 segment/point 占用表。
+// ============================================================================
+
+use super::draw::node_bounds;
+use super::{
+    DOWN, Graph, LEFT, LOWER_LEFT, LOWER_RIGHT, Point, RIGHT, RouteInfo, UP, UPPER_LEFT,
+    UPPER_RIGHT, direction_between, is_cardinal, opposite,
+};
+use crate::routing::debug::DebugFrame;
+use crate::routing::{GridPoint, RouteConstraints, RouteRules, RoutingGrid};
+use crate::types::{AsciiEdgeRoutingStats, AsciiRouteOutcome, AsciiRouteReason};
+use std::collections::HashMap;
+
+/// 前几档搜索半径（大多数边在这里就能走通）。
+const NEAR_TIERS: [i32; 3] = [12, 24, 48];
+/// 全部搜索半径。
+const ALL_TIERS: [i32; 6] = [12, 24, 48, 96, 192, 384];
+const WIDEST_TIER: [i32; 1] = [384];
+
+// ----------------------------------------------------------------------------
+// Router：一次布局尝试内共享的走线状态
+// ----------------------------------------------------------------------------
+
+#[derive(Clone)]
+pub(super) struct Router {
+    /// blocked 格子 + segment/point 占用表 + A* 缓冲区。
+    pub(super) grid: RoutingGrid,
+    /// 节点占用范围的右下角（搜索半径从这里往外扩）。
+    pub(super) max_x: i32,
+    pub(super) max_y: i32,
+    /// 最后一次布局尝试：relaxed 下所有约束都失败时允许无约束兜底；strict 下走不通时细分原因。
+    pub(super) last_attempt: bool,
+    /// 每次转弯的额外代价（`AsciiRenderOptions.turn_penalty`）。
+    pub(super) turn_penalty: u32,
+    /// 并行预演（`parallel`）：假定网格上已有其他连通分量的路径，即使占用表还是空的。
+    pub(super) assume_routes: bool,
+    /// 按边下标累计的 A* 统计；`None` = 不统计。
+    pub(super) stats: Option<Vec<AsciiEdgeRoutingStats>>,
+    /// 按走线顺序记录的调试帧（每次 `route_edge` 一帧）；`None` = 不记录。
+    pub(super) frames: Option<Vec<DebugFrame>>,
+    /// 所有搜索累计的工作量（A* 展开 + 封闭探测看过的格子；命中缓存的不算）。
+    ///
+    /// 与机器快慢无关，全局走线按它限定 rip-up-and-reroute 的预算（见 `reroute`）。
+    pub(super) work: u64,
+    /// 当前这条边已跑过的搜索（见 `search`；`find_route` 开头清空）。
+    pub(super) searches: HashMap<SearchKey, Option<(Vec<u32>, u32)>>,
+}
+
+/// 一次搜索的全部输入：（起点, 终点, 约束, 两端 id）。
+type SearchKey = (u32, u32, RouteConstraints, (u32, u32));
+
+impl Router {
+    fn stride(&self) -> i32 {
+        self.grid.width() as i32
+    }
+
+    fn height(&self) -> i32 {
+        self.grid.height() as i32
+    }
+
+    pub(super) fn index(&self, point: Point) -> u32 {
+        (point.x + point.y * self.stride()) as u32
+    }
+
+    fn point(&self, index: u32) -> Point {
+        let point = self.grid.point(index);
+        Point::new(point.x as i32, point.y as i32)
+    }
+
+    /// 占用表里是否已有路径（strict 下决定走 free 还是 strict 搜索）。
+    pub(super) fn has_routes(&self) -> bool {
+        self.assume_routes || self.grid.used_segments() > 0
+    }
+
+    fn constraints(&self, rules: RouteRules, margin: i32) -> RouteConstraints {
+        RouteConstraints {
+            rules,
+            bounds: Some(GridPoint::new(
+                (self.stride() - 1).min(self.max_x + margin) as u32,
+                (self.height() - 1).min(self.max_y + margin) as u32,
+            )),
+            turn_penalty: self.turn_penalty,
+            ..Default::default()
+        }
+    }
+
+    fn search_free(
+        &mut self,
+        edge: usize,
+        candidate: &Candidate,
+        margin: i32,
+    ) -> Option<(Vec<u32>, u32)> {
+        let constraints = self.constraints(RouteRules::Free, margin);
+        self.search(edge, candidate, constraints, (0, 0))
+    }
+
+    fn search_strict(
+        &mut self,
+        edge: usize,
+        candidate: &Candidate,
+        margin: i32,
+        ids: (u32, u32),
+    ) -> Option<(Vec<u32>, u32)> {
+        let constraints = self.constraints(RouteRules::Strict, margin);
+        self.search(edge, candidate, constraints, ids)
+    }
+
+    fn search_relaxed(
+        &mut self,
+        edge: usize,
+        candidate: &Candidate,
+        margin: i32,
+        ids: (u32, u32),
+        allow_end_segment_reuse: bool,
+    ) -> Option<(Vec<u32>, u32)> {
+        let constraints = RouteConstraints {
+            allow_end_segment_reuse,
+            ..self.constraints(RouteRules::Relaxed, margin)
+        };
+        self.search(edge, candidate, constraints, ids)
+    }
+
+    /// 跑一次 A*；同一条边上同样的搜索（端点、约束、id 都相同）直接取上次的结果。
+    ///
+    /// 候选集一层套一层（首选 ⊂ 任意起点 ⊂ 加对角），tier 放大到整张网格后窗口也不再变，
+    /// 重试循环会把同一次搜索跑很多遍；`find_route` 落定之前网格不变，结果也就不变。
+    /// 命中缓存的搜索不计入统计，也不再记调试探测。
+    fn search(
+        &mut self,
+        edge: usize,
+        candidate: &Candidate,
+        constraints: RouteConstraints,
+        ids: (u32, u32),
+    ) -> Option<(Vec<u32>, u32)> {
+        let key = (
+            candidate.route_from_idx,
+            candidate.route_to_idx,
+            constraints,
+            ids,
+        );
+        if let Some(found) = self.searches.get(&key) {
+            return found.clone();
+        }
+        let found = self.grid.route_indices(
+            candidate.route_from_idx,
+            candidate.route_to_idx,
+            &constraints,
+            ids,
+        );
+        let stats = self.grid.last_stats();
+        self.work += stats.expansions + stats.probe_cells;
+        self.note_search(edge);
+        self.searches.insert(key, found.clone());
+        found
+    }
+
+    /// 把刚结束的那次 A* 的统计记到 `edge` 名下（不统计时什么也不做）。
+    fn note_search(&mut self, edge: usize) {
+        let Some(stats) = &mut self.stats else {
+            return;
+        };
+        let search = self.grid.last_stats();
+        let stats = &mut stats[edge];
+        stats.searches += 1;
+        stats.unreachable += u32::from(search.cost.is_none());
+        stats.budget_exhausted += u32::from(search.budget_exhausted);
+        stats.expansions += search.expansions;
+        stats.probe_cells += search.probe_cells;
+        stats.heap_peak = stats.heap_peak.max(search.heap_peak);
+        stats.rejections.blocked += search.rejections.blocked;
+        stats.rejections.crossing += search.rejections.crossing;
+        stats.rejections.segment += search.rejections.segment;
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 路径工具
+// ----------------------------------------------------------------------------
+
+/// 路径的“段数”（拐弯次数 + 2；长度 <= 2 时就是长度）。
+fn segment_count(path: &[u32]) -> i32 {
+    if path.len() <= 2 {
+        return path.len() as i32;
+    }
+    let mut turns = 0;
+    let mut step = i64::from(path[1]) - i64::from(path[0]);
+    for pair in path[1..].windows(2) {
+        let next = i64::from(pair[1]) - i64::from(pair[0]);
+        if next != step {
+            turns += 1;
+            step = next;
+        }
+    }
+    2 + turns
+}
+
+/// 只保留端点与拐点。
+fn compress(path: &[u32]) -> Vec<u32> {
+    if path.len() <= 2 {
+        return path.to_vec();
+    }
+    let mut out = vec![path[0]];
+    let mut step = i64::from(path[1]) - i64::from(path[0]);
+    for i in 2..path.len() {
+        let next = i64::from(path[i]) - i64::from(path[i - 1]);
+        if next != step {
+            out.push(path[i - 1]);
+            step = next;
+        }
+    }
+    out.push(path[path.len() - 1]);
+    out
+}
+
+/// 拐点序列 -> 逐格 index（`compress` 的逆操作，与 `commit` 写进占用表的路径一致）。
+pub(super) fn expand(router: &Router, path: &[Point]) -> Vec<u32> {
+    let Some(&first) = path.first() else {
+        return Vec::new();
+    };
+    let mut cells = vec![router.index(first)];
+    let mut current = first;
+    for &point in &path[1..] {
+        let step = Point::new(
+            (point.x - current.x).signum(),
+            (point.y - current.y).signum(),
+        );
+        while current != point {
+            current = Point::new(current.x + step.x, current.y + step.y);
+            cells.push(router.index(current));
+        }
+    }
+    cells
+}
+
+/// 去掉共线的中间点（JS `mergePath`）。
+fn merge_collinear(path: &[Point]) -> Vec<Point> {
+    if path.len() <= 2 {
+        return path.to_vec();
+    }
+    let mut drop = vec![false; path.len()];
+    for i in 2..path.len() {
+        let (a, b, c) = (path[i - 2], path[i - 1], path[i]);
+        if b.x - a.x == c.x - b.x && b.y - a.y == c.y - b.y {
+            drop[i - 1] = true;
+        }
+    }
+    path.iter()
+        .zip(drop)
+        .filter(|(_, dropped)| !dropped)
+        .map(|(point, _)| *point)
+        .collect()
+}
+
+fn dedup_dirs(dirs: &[Point]) -> Vec<Point> {
+    let mut out: Vec<Point> = Vec::new();
+    for &dir in dirs {
+        if !out.contains(&dir) {
+            out.push(dir);
+        }
+    }
+    out
+}
+
+// ----------------------------------------------------------------------------
+// 端口方向
+// ----------------------------------------------------------------------------
+
+/// 首选/备选的起止端口 `[start, end, alt_start, alt_end]`（JS `determineStartAndEndDir`）。
+fn preferred_dirs(graph: &Graph, edge: usize) -> [Point; 4] {
+    let edge_ref = &graph.edges[edge];
+    let horizontal = graph.config.horizontal;
+    if edge_ref.is_self_loop() {
+        return if horizontal {
+            [RIGHT, DOWN, DOWN, RIGHT]
+        } else {
+            [DOWN, RIGHT, RIGHT, DOWN]
+        };
+    }
+    let from = graph.nodes[edge_ref.from].grid_coord.unwrap_or_default();
+    let to = graph.nodes[edge_ref.to].grid_coord.unwrap_or_default();
+    let dir = direction_between(from, to);
+    let backwards = if horizontal {
+        matches!(dir, LEFT | UPPER_LEFT | LOWER_LEFT)
+    } else {
+        matches!(dir, UP | UPPER_LEFT | UPPER_RIGHT)
+    };
+
+    let straight = [dir, opposite(dir), dir, opposite(dir)];
+    let [mut start, mut end, mut alt_start, mut alt_end] = match dir {
+        LOWER_RIGHT if horizontal => [DOWN, LEFT, RIGHT, UP],
+        LOWER_RIGHT => [RIGHT, UP, DOWN, LEFT],
+        UPPER_RIGHT if horizontal => [UP, LEFT, RIGHT, DOWN],
+        UPPER_RIGHT => [RIGHT, DOWN, UP, LEFT],
+        LOWER_LEFT if horizontal => [DOWN, DOWN, LEFT, UP],
+        LOWER_LEFT => [LEFT, UP, DOWN, RIGHT],
+        UPPER_LEFT if horizontal => [DOWN, DOWN, LEFT, DOWN],
+        UPPER_LEFT => [RIGHT, RIGHT, UP, RIGHT],
+        LEFT if backwards && horizontal => [DOWN, DOWN, LEFT, RIGHT],
+        UP if backwards && !horizontal => [RIGHT, RIGHT, UP, DOWN],
+        _ => straight,
+    };
+
+    // relaxed：端口如果背离对方节点，翻到朝向对方的一侧
+    if graph.config.relaxed() {
+        let dx = to.x - from.x;
+        let dy = to.y - from.y;
+        let mirror_x = |d: Point| match d {
+            LEFT => RIGHT,
+            RIGHT => LEFT,
+            UPPER_LEFT => UPPER_RIGHT,
+            UPPER_RIGHT => UPPER_LEFT,
+            LOWER_LEFT => LOWER_RIGHT,
+            LOWER_RIGHT => LOWER_LEFT,
+            other => other,
+        };
+        let mirror_y = |d: Point| match d {
+            UP => DOWN,
+            DOWN => UP,
+            UPPER_LEFT => LOWER_LEFT,
+            UPPER_RIGHT => LOWER_RIGHT,
+            LOWER_LEFT => UPPER_LEFT,
+            LOWER_RIGHT => UPPER_RIGHT,
+            other => other,
+        };
+        let face_target = |d: Point| {
+            let mut d = d;
+            let (sx, sy) = ((d.x - 1).signum(), (d.y - 1).signum());
+            if (dx < 0 && sx == 1) || (dx > 0 && sx == -1) {
+                d = mirror_x(d);
+            }
+            if (dy < 0 && sy == 1) || (dy > 0 && sy == -1) {
+                d = mirror_y(d);
+            }
+            d
+        };
+        let face_source = |d: Point| {
+            let mut d = d;
+            let (sx, sy) = ((d.x - 1).signum(), (d.y - 1).signum());
+            if (dx < 0 && sx == -1) || (dx > 0 && sx == 1) {
+                d = mirror_x(d);
+            }
+            if (dy < 0 && sy == -1) || (dy > 0 && sy == 1) {
+                d = mirror_y(d);
+            }
+            d
+        };
+        start = face_target(start);
+        alt_start = face_target(alt_start);
+        end = face_source(end);
+        alt_end = face_source(alt_end);
+    }
+    [start, end, alt_start, alt_end]
+}
+
+// ----------------------------------------------------------------------------
+// 候选与评估
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    start_dir: Point,
+    end_dir: Point,
+    route_from: Point,
+    route_to: Point,
+    route_from_idx: u32,
+    route_to_idx: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Choice {
+    candidate: Candidate,
+    path: Vec<u32>,
+    cost: i64,
+    info: RouteInfo,
+}
+
+/// 两个结果里挑更好的：代价低优先，平手时段数少优先，再平手保留先到的。
+fn better(current: Option<Choice>, challenger: Option<Choice>) -> Option<Choice> {
+    match (current, challenger) {
+        (None, other) | (other, None) => other,
+        (Some(current), Some(challenger)) => {
+            if challenger.cost < current.cost {
+                Some(challenger)
+            } else if challenger.cost > current.cost {
+                Some(current)
+            } else if segment_count(&challenger.path) < segment_count(&current.path) {
+                Some(challenger)
+            } else {
+                Some(current)
+            }
+        }
+    }
+}
+
+/// 走线上下文：一条边的所有候选与代价函数。
+struct EdgeRoute<'a> {
+    graph: &'a Graph,
+    edge: usize,
+    ids: (u32, u32),
+    self_loop: bool,
+    relaxed: bool,
+    /// 两端节点包围盒的相对位置（用于“端口方向背离对方”惩罚）。
+    bbox_delta: Option<(i32, i32)>,
+}
+
+impl EdgeRoute<'_> {
+    fn candidates(&self, router: &Router, starts: &[Point], ends: &[Point]) -> Vec<Candidate> {
+        let edge = &self.graph.edges[self.edge];
+        let from = self.graph.nodes[edge.from].grid_coord.unwrap_or_default();
+        let to = self.graph.nodes[edge.to].grid_coord.unwrap_or_default();
+        let mut out = Vec::new();
+        for &start_dir in starts {
+            for &end_dir in ends {
+                let route_from = from.offset(start_dir);
+                let route_to = to.offset(end_dir);
+                if route_from == route_to {
+                    continue;
+                }
+                out.push(Candidate {
+                    start_dir,
+                    end_dir,
+                    route_from,
+                    route_to,
+                    route_from_idx: router.index(route_from),
+                    route_to_idx: router.index(route_to),
+                });
+            }
+        }
+        out
+    }
+
+    fn diagonal_cost(&self, dir: Point) -> i64 {
+        if is_cardinal(dir) {
+            0
+        } else if self.relaxed {
+            10
+        } else {
+            100
+        }
+    }
+
+    /// 贴着 grid 边缘（x=0 或 y=0）出发/到达的端口很难看，加重惩罚。
+    fn border_cost(point: Point) -> i64 {
+        if point.x == 0 || point.y == 0 { 200 } else { 0 }
+    }
+
+    /// relaxed + ASCII：同一端口复用越多越贵（分散扇出）。
+    fn port_usage_cost(&self, node: usize, dir: Point) -> i64 {
+        if !self.relaxed || !self.graph.config.use_ascii {
+            return 0;
+        }
+        let Some(usage) = &self.graph.port_usage else {
+            return 0;
+        };
+        let slot = node * 9 + (dir.x + dir.y * 3) as usize;
+        i64::from(usage.get(slot).copied().unwrap_or(0)) * 6
+    }
+
+    fn basic_cost(&self, candidate: &Candidate, path: &[u32]) -> i64 {
+        i64::from(segment_count(path))
+            + self.diagonal_cost(candidate.start_dir)
+            + self.diagonal_cost(candidate.end_dir)
+            + Self::border_cost(candidate.route_from)
+            + Self::border_cost(candidate.route_to)
+    }
+
+    /// 路径绕出两端节点包围盒太远时的惩罚。
+    fn detour_cost(&self, router: &Router, path: &[u32]) -> i64 {
+        let edge = &self.graph.edges[self.edge];
+        let (Some(from), Some(to)) = (
+            self.graph.nodes[edge.from].grid_coord,
+            self.graph.nodes[edge.to].grid_coord,
+        ) else {
+            return 0;
+        };
+        if path.len() < 8 {
+            return 0;
+        }
+        let stride = router.stride() as u32;
+        let (mut min_x, mut max_x, mut min_y, mut max_y) = (i64::MAX, i64::MIN, i64::MAX, i64::MIN);
+        for &idx in path {
+            let x = i64::from(idx % stride);
+            let y = i64::from(idx / stride);
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+            min_y = min_y.min(y);
+            max_y = max_y.max(y);
+        }
+        let box_min_x = i64::from(from.x.min(to.x));
+        let box_max_x = i64::from(from.x.max(to.x)) + 2;
+        let box_min_y = i64::from(from.y.min(to.y));
+        let box_max_y = i64::from(from.y.max(to.y)) + 2;
+        let slack = 2;
+        let detour = (box_min_x - min_x - slack).max(0)
+            + (max_x - box_max_x - slack).max(0)
+            + (box_min_y - min_y - slack).max(0)
+            + (max_y - box_max_y - slack).max(0);
+        let span_x = i64::from((to.x - from.x).abs());
+        let tall = i64::from((to.y - from.y).abs()) >= span_x + 2;
+        let (allowance, weight) = if tall { (8, 10) } else { (12, 4) };
+        if detour <= allowance {
+            0
+        } else {
+            (detour - allowance) * weight
+        }
+    }
+
+    /// 端口方向背离对方节点时的惩罚。
+    fn facing_cost(&self, candidate: &Candidate) -> i64 {
+        let Some((dx, dy)) = self.bbox_delta else {
+            return 0;
+        };
+        if dx == 0 && dy == 0 {
+            return 0;
+        }
+        let (sx, sy) = (dx.signum(), dy.signum());
+        let start_x = (candidate.start_dir.x - 1).signum();
+        let start_y = (candidate.start_dir.y - 1).signum();
+        let end_x = (candidate.end_dir.x - 1).signum();
+        let end_y = (candidate.end_dir.y - 1).signum();
+        let penalty = 180;
+        let mut cost = 0;
+        if sx != 0 && start_x != 0 && start_x == -sx {
+            cost += penalty;
+        }
+        if sy != 0 && start_y != 0 && start_y == -sy {
+            cost += penalty;
+        }
+        if sx != 0 && end_x != 0 && end_x == sx {
+            cost += penalty;
+        }
+        if sy != 0 && end_y != 0 && end_y == sy {
+            cost += penalty;
+        }
+        cost
+    }
+
+    fn relaxed_cost(
+        &self,
+        router: &Router,
+        candidate: &Candidate,
+        path: &[u32],
+        search_cost: u32,
+    ) -> i64 {
+        let edge = &self.graph.edges[self.edge];
+        i64::from(search_cost)
+            + i64::from(segment_count(path))
+            + self.detour_cost(router, path)
+            + self.facing_cost(candidate)
+            + self.diagonal_cost(candidate.start_dir)
+            + self.diagonal_cost(candidate.end_dir)
+            + Self::border_cost(candidate.route_from)
+            + Self::border_cost(candidate.route_to)
+            + self.port_usage_cost(edge.from, candidate.start_dir)
+            + self.port_usage_cost(edge.to, candidate.end_dir)
+    }
+
+    fn too_short_for_self_loop(&self, path: &[u32]) -> bool {
+        self.self_loop && segment_count(path) < 4
+    }
+
+    /// 无约束 A*（只避开节点）。
+    fn search_free(
+        &self,
+        router: &mut Router,
+        candidates: &[Candidate],
+        tiers: &[i32],
+    ) -> Option<Choice> {
+        for &margin in tiers {
+            let mut best: Option<Choice> = None;
+            for candidate in candidates {
+                let found = router.search_free(self.edge, candidate, margin);
+                let Some((path, search_cost)) = found else {
+                    continue;
+                };
+                if self.too_short_for_self_loop(&path) {
+                    continue;
+                }
+                let cost = self.basic_cost(candidate, &path);
+                if best.as_ref().is_none_or(|b| cost < b.cost) {
+                    best = Some(Choice {
+                        candidate: *candidate,
+                        path,
+                        cost,
+                        info: RouteInfo {
+                            cost: Some(search_cost),
+                            ..RouteInfo::default()
+                        },
+                    });
+                }
+            }
+            if best.is_some() {
+                return best;
+            }
+        }
+        None
+    }
+
+    /// strict A*（禁止 `┼` 与非法共线）；还没有任何已走线段时不需要它。
+    fn search_strict(
+        &self,
+        router: &mut Router,
+        candidates: &[Candidate],
+        tiers: &[i32],
+    ) -> Option<Choice> {
+        if !router.has_routes() {
+            return None;
+        }
+        for &margin in tiers {
+            let mut best: Option<Choice> = None;
+            for candidate in candidates {
+                let found = router.search_strict(self.edge, candidate, margin, self.ids);
+                let Some((path, search_cost)) = found else {
+                    continue;
+                };
+                if self.too_short_for_self_loop(&path) {
+                    continue;
+                }
+                let cost = self.basic_cost(candidate, &path);
+                if best.as_ref().is_none_or(|b| cost < b.cost) {
+                    best = Some(Choice {
+                        candidate: *candidate,
+                        path,
+                        cost,
+                        info: RouteInfo {
+                            cost: Some(search_cost),
+                            ..RouteInfo::default()
+                        },
+                    });
+                }
+            }
+            if best.is_some() {
+                return best;
+            }
+        }
+        None
+    }
+
+    /// relaxed A*（crossing 加罚，点重叠禁止）。
+    fn search_relaxed(
+        &self,
+        router: &mut Router,
+        candidates: &[Candidate],
+        tiers: &[i32],
+        allow_end_segment_reuse: bool,
+    ) -> Option<Choice> {
+        for &margin in tiers {
+            let mut best: Option<Choice> = None;
+            for candidate in candidates {
+                let found = router.search_relaxed(
+                    self.edge,
+                    candidate,
+                    margin,
+                    self.ids,
+                    allow_end_segment_reuse,
+                );
+                let Some((path, search_cost)) = found else {
+                    continue;
+                };
+                if self.too_short_for_self_loop(&path) {
+                    continue;
+                }
+                let cost = self.relaxed_cost(router, candidate, &path, search_cost);
+                if best.as_ref().is_none_or(|b| cost < b.cost) {
+                    best = Some(Choice {
+                        candidate: *candidate,
+                        path,
+                        cost,
+                        info: RouteInfo {
+                            cost: Some(search_cost),
+                            end_segment_reuse: allow_end_segment_reuse,
+                            ..RouteInfo::default()
+                        },
+                    });
+                }
+            }
+            if best.is_some() {
+                return best;
+            }
+        }
+        None
+    }
+
+    /// relaxed 结果是否“绕得太远”，值得在更大的候选集里再找一次（仅 Unicode）。
+    fn looks_detoured(&self, router: &Router, choice: &Choice) -> bool {
+        if self.graph.config.use_ascii {
+            return false;
+        }
+        let from = router.point(choice.candidate.route_from_idx);
+        let to = router.point(choice.candidate.route_to_idx);
+        let manhattan = (from.x - to.x).abs() + (from.y - to.y).abs() + 1;
+        let extra = choice.path.len() as i32 - manhattan;
+        choice.path.len() >= 28 || segment_count(&choice.path) >= 8 || extra >= 12
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 自环
+// ----------------------------------------------------------------------------
+
+fn unit_vector(dir: Point) -> Option<Point> {
+    match dir {
+        UP => Some(Point::new(0, -1)),
+        DOWN => Some(Point::new(0, 1)),
+        LEFT => Some(Point::new(-1, 0)),
+        RIGHT => Some(Point::new(1, 0)),
+        _ => None,
+    }
+}
+
+fn append_straight(path: &mut Vec<Point>, to: Point) {
+    let last = *path.last().expect("路径至少包含起点");
+    if last.x == to.x {
+        let step = if to.y > last.y { 1 } else { -1 };
+        let mut y = last.y + step;
+        while y != to.y + step {
+            path.push(Point::new(last.x, y));
+            y += step;
+        }
+    } else if last.y == to.y {
+        let step = if to.x > last.x { 1 } else { -1 };
+        let mut x = last.x + step;
+        while x != to.x + step {
+            path.push(Point::new(x, last.y));
+            x += step;
+        }
+    }
+}
+
+/// 自环：从起点端口往外走 `reach` 格，绕一个矩形回到终点端口。
+fn self_loop_path(
+    route: &EdgeRoute<'_>,
+    router: &Router,
+    candidate: &Candidate,
+    reach: i32,
+) -> Option<Vec<Point>> {
+    let start_step = unit_vector(candidate.start_dir)?;
+    let end_step = unit_vector(candidate.end_dir)?;
+    if reach < 1 {
+        return None;
+    }
+    let out = Point::new(
+        candidate.route_from.x + start_step.x * reach,
+        candidate.route_from.y + start_step.y * reach,
+    );
+    let back = Point::new(
+        candidate.route_to.x + end_step.x * reach,
+        candidate.route_to.y + end_step.y * reach,
+    );
+    for corner in [Point::new(out.x, back.y), Point::new(back.x, out.y)] {
+        let mut path = vec![candidate.route_from];
+        append_straight(&mut path, out);
+        append_straight(&mut path, corner);
+        append_straight(&mut path, back);
+        append_straight(&mut path, candidate.route_to);
+        path.dedup();
+        if self_loop_is_clear(route, router, candidate, &path) && merge_collinear(&path).len() >= 4
+        {
+            return Some(path);
+        }
+    }
+    None
+}
+
+fn self_loop_is_clear(
+    route: &EdgeRoute<'_>,
+    router: &Router,
+    candidate: &Candidate,
+    path: &[Point],
+) -> bool {
+    if path.len() < 4 {
+        return false;
+    }
+    let mut indices = Vec::with_capacity(path.len());
+    for &point in path {
+        if point.x < 0 || point.y < 0 || point.x >= router.stride() || point.y >= router.height() {
+            return false;
+        }
+        let index = router.index(point);
+        indices.push(index);
+        let is_endpoint = point == candidate.route_from || point == candidate.route_to;
+        if !is_endpoint && router.grid.is_blocked_index(index) {
+            return false;
+        }
+    }
+    for pair in indices.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        if !router.grid.step_allowed_strict(
+            a,
+            b,
+            route.ids,
+            a == candidate.route_from_idx,
+            b == candidate.route_to_idx,
+        ) {
+            return false;
+        }
+    }
+    true
+}
+
+// ----------------------------------------------------------------------------
+// 入口
+// ----------------------------------------------------------------------------
+
+/// 给一条边找路径；走不通时 `path` 留空（由布局决定是否整体重试）。
+pub(super) fn route_edge(graph: &mut Graph, edge: usize, router: &mut Router) {
+    if router.frames.is_none() {
+        find_route(graph, edge, router);
+        return;
+    }
+    let grid = router.grid.snapshot();
+    router.grid.start_probe();
+    find_route(graph, edge, router);
+    let probe = router.grid.take_probe();
+    let path = expand(router, &graph.edges[edge].path)
+        .into_iter()
+        .map(|index| router.grid.point(index))
+        .collect();
+    let frame = DebugFrame {
+        title: frame_title(graph, edge),
+        grid,
+        probe,
+        path,
+    };
+    if let Some(frames) = &mut router.frames {
+        frames.push(frame);
+    }
+}
+
+/// 调试帧标题：`A -> B [label]：走线结果`。
+fn frame_title(graph: &Graph, edge: usize) -> String {
+    let edge_ref = &graph.edges[edge];
+    let mut title = format!(
+        "{} -> {}",
+        graph.nodes[edge_ref.from].name, graph.nodes[edge_ref.to].name
+    );
+    if !edge_ref.text.is_empty() {
+        title.push_str(&format!(" [{}]", edge_ref.text));
+    }
+    let (outcome, reason) = edge_ref.outcome();
+    let outcome = match outcome {
+        AsciiRouteOutcome::Routed => "走通",
+        AsciiRouteOutcome::RoutedWithFallback => "放开约束才走通",
+        AsciiRouteOutcome::Unroutable => "走不通",
+    };
+    title.push('：');
+    title.push_str(outcome);
+    if let Some(reason) = reason {
+        title.push_str(&format!("（{}）", reason.describe()));
+    }
+    title
+}
+
+fn find_route(graph: &mut Graph, edge: usize, router: &mut Router) {
+    router.searches.clear();
+    let [start, end, alt_start, alt_end] = preferred_dirs(graph, edge);
+    let relaxed = graph.config.relaxed();
+    let (from, to) = (graph.edges[edge].from, graph.edges[edge].to);
+
+    let bbox_delta = bbox_delta(graph, edge);
+
+    let route = EdgeRoute {
+        graph,
+        edge,
+        ids: (from as u32 + 1, to as u32 + 1),
+        self_loop: from == to,
+        relaxed,
+        bbox_delta,
+    };
+
+    let preferred_starts = dedup_dirs(&[start, alt_start]);
+    let preferred_ends = dedup_dirs(&[end, alt_end]);
+    let mut preferred: Vec<Candidate> = Vec::new();
+    let mut add_preferred = |start_dir: Point, end_dir: Point| {
+        if preferred
+            .iter()
+            .any(|c| c.start_dir == start_dir && c.end_dir == end_dir)
+        {
+            return;
+        }
+        preferred.extend(route.candidates(router, &[start_dir], &[end_dir]));
+    };
+    add_preferred(start, end);
+    add_preferred(alt_start, alt_end);
+    if relaxed && preferred_starts.len() > 1 && preferred_ends.len() > 1 {
+        for &s in &preferred_starts {
+            for &e in &preferred_ends {
+                add_preferred(s, e);
+            }
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // 候选集：首选 -> 全部正交端口 -> 再加对角端口
+    // ------------------------------------------------------------------
+    let diagonals = [UPPER_RIGHT, UPPER_LEFT, LOWER_RIGHT, LOWER_LEFT];
+    let ascii_diagonals = relaxed && graph.config.use_ascii;
+    let mut all_starts = vec![start, alt_start, RIGHT, LEFT, DOWN, UP];
+    let mut all_ends = vec![end, alt_end, RIGHT, LEFT, DOWN, UP];
+    if ascii_diagonals {
+        all_starts.extend(diagonals);
+        all_ends.extend(diagonals);
+    }
+    let all_starts = dedup_dirs(&all_starts);
+    let all_ends = dedup_dirs(&all_ends);
+    let with_diagonal_starts = dedup_dirs(&[all_starts.as_slice(), &diagonals].concat());
+    let with_diagonal_ends = dedup_dirs(&[all_ends.as_slice(), &diagonals].concat());
+
+    let any_start_preferred_end = route.candidates(router, &all_starts, &preferred_ends);
+    let any_start_any_end = route.candidates(router, &all_starts, &all_ends);
+    let diagonal_ends = route.candidates(router, &all_starts, &with_diagonal_ends);
+    let diagonal_both = route.candidates(router, &with_diagonal_starts, &with_diagonal_ends);
+
+    // ------------------------------------------------------------------
+    // 自环：优先尝试固定形状的矩形环
+    // ------------------------------------------------------------------
+    if route.self_loop {
+        let loop_candidates = if preferred.is_empty() {
+            &any_start_preferred_end
+        } else {
+            &preferred
+        };
+        for candidate in loop_candidates {
+            for reach in 1..=12 {
+                let Some(path) = self_loop_path(&route, router, candidate, reach) else {
+                    continue;
+                };
+                let indices: Vec<u32> = path.iter().map(|&p| router.index(p)).collect();
+                let merged = merge_collinear(&path);
+                let candidate = *candidate;
+                let info = RouteInfo::default();
+                commit(graph, edge, router, &candidate, merged, &indices, info);
+                return;
+            }
+        }
+    }
+
+    let choice = if relaxed {
+        let attempt = |router: &mut Router, allow_end_reuse: bool| -> Option<Choice> {
+            if let Some(first) =
+                route.search_relaxed(router, &preferred, &NEAR_TIERS, allow_end_reuse)
+            {
+                let mut best = first;
+                if segment_count(&best.path) - 2 > 4 {
+                    let same_end = route.candidates(router, &all_starts, &[best.candidate.end_dir]);
+                    let retry =
+                        route.search_relaxed(router, &same_end, &NEAR_TIERS, allow_end_reuse);
+                    best = better(Some(best), retry).expect("至少有一个结果");
+                }
+                if best.path.len() >= 4 {
+                    let crowded = best.path[1..best.path.len() - 1].iter().any(|&idx| {
+                        let bits = router.grid.connections_index(idx);
+                        bits != 0 && bits & (bits - 1) != 0
+                    });
+                    if crowded {
+                        let retry =
+                            route.search_relaxed(router, &preferred, &WIDEST_TIER, allow_end_reuse);
+                        best = better(Some(best), retry).expect("至少有一个结果");
+                    }
+                }
+                if route.looks_detoured(router, &best) {
+                    let near = route.search_relaxed(
+                        router,
+                        &any_start_any_end,
+                        &NEAR_TIERS,
+                        allow_end_reuse,
+                    );
+                    best = better(Some(best), near).expect("至少有一个结果");
+                    let far = route.search_relaxed(
+                        router,
+                        &any_start_any_end,
+                        &ALL_TIERS,
+                        allow_end_reuse,
+                    );
+                    best = better(Some(best), far).expect("至少有一个结果");
+                }
+                return Some(best);
+            }
+
+            let near = route.search_relaxed(
+                router,
+                &any_start_preferred_end,
+                &NEAR_TIERS,
+                allow_end_reuse,
+            );
+            let wider = match &near {
+                Some(found) if !route.looks_detoured(router, found) => None,
+                _ => route.search_relaxed(router, &any_start_any_end, &NEAR_TIERS, allow_end_reuse),
+            };
+            let mut best = better(near, wider);
+            if let Some(found) = &best
+                && route.looks_detoured(router, found)
+            {
+                let far =
+                    route.search_relaxed(router, &any_start_any_end, &ALL_TIERS, allow_end_reuse);
+                best = better(best, far);
+            }
+            best.or_else(|| route.search_relaxed(router, &preferred, &ALL_TIERS, allow_end_reuse))
+                .or_else(|| {
+                    route.search_relaxed(
+                        router,
+                        &any_start_preferred_end,
+                        &ALL_TIERS,
+                        allow_end_reuse,
+                    )
+                })
+                .or_else(|| {
+                    route.search_relaxed(router, &any_start_any_end, &ALL_TIERS, allow_end_reuse)
+                })
+        };
+
+        let strict_ends = attempt(router, false);
+        let reuse_ends = attempt(router, true);
+        better(strict_ends, reuse_ends).or_else(|| {
+            let diagonal = |router: &mut Router, allow_end_reuse: bool| {
+                route
+                    .search_relaxed(router, &diagonal_ends, &NEAR_TIERS, allow_end_reuse)
+                    .or_else(|| {
+                        route.search_relaxed(router, &diagonal_both, &NEAR_TIERS, allow_end_reuse)
+                    })
+                    .or_else(|| {
+                        route.search_relaxed(router, &diagonal_ends, &ALL_TIERS, allow_end_reuse)
+                    })
+                    .or_else(|| {
+                        route.search_relaxed(router, &diagonal_both, &ALL_TIERS, allow_end_reuse)
+                    })
+            };
+            diagonal(router, false).or_else(|| diagonal(router, true))
+        })
+    } else if !router.has_routes() {
+        route.search_free(router, &preferred, &NEAR_TIERS)
+    } else {
+        route
+            .search_strict(router, &preferred, &NEAR_TIERS)
+            .or_else(|| route.search_strict(router, &any_start_preferred_end, &NEAR_TIERS))
+            .or_else(|| route.search_strict(router, &any_start_any_end, &NEAR_TIERS))
+            .or_else(|| route.search_strict(router, &preferred, &ALL_TIERS))
+            .or_else(|| route.search_strict(router, &any_start_preferred_end, &ALL_TIERS))
+            .or_else(|| route.search_strict(router, &any_start_any_end, &ALL_TIERS))
+    };
+
+    // 最后一次布局尝试：relaxed 下放开所有约束
+    let choice = choice.or_else(|| {
+        if !(relaxed && router.last_attempt) {
+            return None;
+        }
+        route
+            .search_free(router, &preferred, &NEAR_TIERS)
+            .or_else(|| route.search_free(router, &any_start_preferred_end, &NEAR_TIERS))
+            .or_else(|| route.search_free(router, &any_start_any_end, &NEAR_TIERS))
+            .or_else(|| route.search_free(router, &diagonal_ends, &NEAR_TIERS))
+            .or_else(|| route.search_free(router, &diagonal_both, &ALL_TIERS))
+            .map(|choice| Choice {
+                info: RouteInfo {
+                    unconstrained: true,
+                    fallback: Some(AsciiRouteReason::RelaxedRules),
+                    ..choice.info
+                },
+                ..choice
+            })
+    });
+
+    let Some(choice) = choice else {
+        // 只避开节点时总有路：节点按步长 >= 4 的格点放置，3×3 占位之间至少隔一格，
+        // 每个端口都通向这些连成一片的空行 / 空列（贴着 grid 边缘的端口出不去，但那只发生在
+        // 偏移为 0 的前几次尝试里，会整体重试）。所以最后一次尝试里走不通只可能是规则挡住了；
+        // relaxed 的最后一次尝试还有无约束兜底，实际只有 strict 会留下走不通的边。
+        let reason = if relaxed {
+            AsciiRouteReason::RelaxedRules
+        } else {
+            AsciiRouteReason::StrictRules
+        };
+        let edge_ref = &mut graph.edges[edge];
+        edge_ref.start_dir = start;
+        edge_ref.end_dir = end;
+        edge_ref.path.clear();
+        edge_ref.route = RouteInfo {
+            fallback: Some(reason),
+            ..RouteInfo::default()
+        };
+        return;
+    };
+    let path: Vec<Point> = compress(&choice.path)
+        .into_iter()
+        .map(|idx| router.point(idx))
+        .collect();
+    commit(
+        graph,
+        edge,
+        router,
+        &choice.candidate,
+        path,
+        &choice.path,
+        choice.info,
+    );
+}
+
+/// 两端节点包围盒的相对位置（b - a；重叠时退回到中心点之差）。
+pub(super) fn bbox_delta(graph: &Graph, edge: usize) -> Option<(i32, i32)> {
+    let (from, to) = (graph.edges[edge].from, graph.edges[edge].to);
+    match (node_bounds(graph, from), node_bounds(graph, to)) {
+        (Some(a), Some(b)) => {
+            let dx = if b.min_x > a.max_x {
+                b.min_x - a.max_x
+            } else if b.max_x < a.min_x {
+                b.max_x - a.min_x
+            } else {
+                0
+            };
+            let dy = if b.min_y > a.max_y {
+                b.min_y - a.max_y
+            } else if b.max_y < a.min_y {
+                b.max_y - a.min_y
+            } else {
+                0
+            };
+            if dx == 0 && dy == 0 {
+                // 包围盒重叠：退回到中心点之差
+                let center = |min: i32, max: i32| (min + max).div_euclid(2);
+                Some((
+                    center(b.min_x, b.max_x) - center(a.min_x, a.max_x),
+                    center(b.min_y, b.max_y) - center(a.min_y, a.max_y),
+                ))
+            } else {
+                Some((dx, dy))
+            }
+        }
+        _ => None,
+    }
+}
+
+/// 落定一条路径：写回 edge，并更新 segment/point/端口占用。
+fn commit(
+    graph: &mut Graph,
+    edge: usize,
+    router: &mut Router,
+    candidate: &Candidate,
+    path: Vec<Point>,
+    indices: &[u32],
+    info: RouteInfo,
+) {
+    let (from, to) = (graph.edges[edge].from, graph.edges[edge].to);
+    let edge_ref = &mut graph.edges[edge];
+    edge_ref.start_dir = candidate.start_dir;
+    edge_ref.end_dir = candidate.end_dir;
+    edge_ref.path = path;
+    edge_ref.route = info;
+
+    router.grid.record(from as u32 + 1, to as u32 + 1, indices);
+    claim_ports(graph, edge);
+}
+
+/// 按 edge 当前的起止方向给端口占用计数 +1（只在 relaxed 下统计）。
+pub(super) fn claim_ports(graph: &mut Graph, edge: usize) {
+    update_port_usage(graph, edge, u16::wrapping_add);
+}
+
+/// `claim_ports` 的逆操作：rip-up 时归还端口（占用表由调用方整体重建）。
+pub(super) fn release_ports(graph: &mut Graph, edge: usize) {
+    update_port_usage(graph, edge, u16::wrapping_sub);
+}
+
+fn update_port_usage(graph: &mut Graph, edge: usize, op: fn(u16, u16) -> u16) {
+    let edge_ref = &graph.edges[edge];
+    let start_slot = edge_ref.from * 9 + (edge_ref.start_dir.x + edge_ref.start_dir.y * 3) as usize;
+    let end_slot = edge_ref.to * 9 + (edge_ref.end_dir.x + edge_ref.end_dir.y * 3) as usize;
+    if graph.config.relaxed()
+        && let Some(usage) = &mut graph.port_usage
+    {
+        usage[start_slot] = op(usage[start_slot], 1);
+        usage[end_slot] = op(usage[end_slot], 1);
+    }
+}
@@ -0,0 +1,289 @@
+// ============================================================================
+// flowchart SVG：元素绘制（逐个对齐 bundle 的 `renderNodeShape` / `renderEdge` 等）
+//
+// 说明：
+// - 属性顺序、默认颜色变量、描边宽度都与 JS 输出保持一致，
+//   这样同一份主题 CSS（`--bg` / `--fg` / `--_line` ...）对两个后端都生效；
+// - 数值统一经过 `num` 格式化（最多三位小数，整数不带小数点）。
+// ============================================================================
+
+use super::layout::{PlacedEdge, PlacedGroup, PlacedNode, edge_label_box};
+use crate::native::flowchart::{EdgeStyle, NodeShape};
+use crate::native::svg::{
+    ARROW_HEIGHT, ARROW_WIDTH, CONNECTOR_STROKE, EDGE_LABEL_FONT_SIZE, EDGE_LABEL_FONT_WEIGHT,
+    GROUP_HEADER_FONT_SIZE, GROUP_HEADER_FONT_WEIGHT, INNER_BOX_STROKE, NODE_LABEL_FONT_SIZE,
+    NODE_LABEL_FONT_WEIGHT, OUTER_BOX_STROKE, TEXT_DY, escape_xml, num,
+};
+use crate::types::SvgPoint;
+
+/// `<defs>` 里的两个箭头 marker（终点 / 起点）。
+pub(super) fn arrow_markers(prefix: &str) -> String {
+    let (w, h) = (num(ARROW_WIDTH), num(ARROW_HEIGHT));
+    let half = num(ARROW_HEIGHT / 2.0);
+    format!(
+        "  <marker id=\"{prefix}arrowhead\" markerWidth=\"{w}\" markerHeight=\"{h}\" refX=\"{w}\" refY=\"{half}\" orient=\"auto\">\n    \
+         <polygon points=\"0 0, {w} {half}, 0 {h}\" fill=\"var(--_arrow)\" />\n  \
+         </marker>\n  \
+         <marker id=\"{prefix}arrowhead-start\" markerWidth=\"{w}\" markerHeight=\"{h}\" refX=\"0\" refY=\"{half}\" orient=\"auto-start-reverse\">\n    \
+         <polygon points=\"{w} 0, 0 {half}, {w} {h}\" fill=\"var(--_arrow)\" />\n  \
+         </marker>"
+    )
+}
+
+/// subgraph：外框 + 标题栏 + 标题，子 subgraph 递归画在后面（对齐 JS `renderGroup`）。
+pub(super) fn group(group: &PlacedGroup) -> String {
+    let header = super::layout::GROUP_HEADER_HEIGHT;
+    let (x, y, w, h) = (
+        num(group.x),
+        num(group.y),
+        num(group.width),
+        num(group.height),
+    );
+    let mut parts = vec![
+        format!(
+            "<rect x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\" rx=\"0\" ry=\"0\" fill=\"var(--_group-fill)\" stroke=\"var(--_node-stroke)\" stroke-width=\"{}\" />",
+            num(OUTER_BOX_STROKE)
+        ),
+        format!(
+            "<rect x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{}\" rx=\"0\" ry=\"0\" fill=\"var(--_group-hdr)\" stroke=\"var(--_node-stroke)\" stroke-width=\"{}\" />",
+            num(header),
+            num(OUTER_BOX_STROKE)
+        ),
+        format!(
+            "<text x=\"{}\" y=\"{}\" dy=\"{TEXT_DY}\" font-size=\"{}\" font-weight=\"{GROUP_HEADER_FONT_WEIGHT}\" fill=\"var(--_text-sec)\">{}</text>",
+            num(group.x + 12.0),
+            num(group.y + header / 2.0),
+            num(GROUP_HEADER_FONT_SIZE),
+            escape_xml(&group.label)
+        ),
+    ];
+    parts.extend(group.children.iter().map(self::group));
+    parts.join("\n")
+}
+
+/// 边的折线（少于两个点时 JS 输出空串，外层 `<g>` 也随之省略）。
+pub(super) fn edge(edge: &PlacedEdge, prefix: &str) -> String {
+    if edge.points.len() < 2 {
+        return String::new();
+    }
+    let points = edge
+        .points
+        .iter()
+        .map(|point| format!("{},{}", num(point.x), num(point.y)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let dash = if edge.style == EdgeStyle::Dotted {
+        " stroke-dasharray=\"4 4\""
+    } else {
+        ""
+    };
+    let width = if edge.style == EdgeStyle::Thick {
+        CONNECTOR_STROKE * 2.0
+    } else {
+        CONNECTOR_STROKE
+    };
+    let mut markers = String::new();
+    if edge.has_arrow_end {
+        markers.push_str(&format!(" marker-end=\"url(#{prefix}arrowhead)\""));
+    }
+    if edge.has_arrow_start {
+        markers.push_str(&format!(" marker-start=\"url(#{prefix}arrowhead-start)\""));
+    }
+    format!(
+        "<polyline points=\"{points}\" fill=\"none\" stroke=\"var(--_line)\" stroke-width=\"{}\"{dash}{markers} />",
+        num(width)
+    )
+}
+
+/// 边 label：圆角底框 + 居中文字（对齐 JS `renderEdgeLabel`）。
+pub(super) fn edge_label(label: &str, position: SvgPoint) -> String {
+    let (width, height) = edge_label_box(label);
+    format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"4\" ry=\"4\" fill=\"var(--bg)\" stroke=\"var(--_inner-stroke)\" stroke-width=\"0.5\" />\n\
+         <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dy=\"{TEXT_DY}\" font-size=\"{}\" font-weight=\"{EDGE_LABEL_FONT_WEIGHT}\" fill=\"var(--_text-muted)\">{}</text>",
+        num(position.x - width / 2.0),
+        num(position.y - height / 2.0),
+        num(width),
+        num(height),
+        num(position.x),
+        num(position.y),
+        num(EDGE_LABEL_FONT_SIZE),
+        escape_xml(label)
+    )
+}
+
+fn style_value<'s>(node: &'s PlacedNode, key: &str) -> Option<&'s str> {
+    node.style
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.as_str())
+}
+
+/// 多边形顶点串：`x,y x,y ...`。
+fn polygon_points(points: &[(f64, f64)]) -> String {
+    points
+        .iter()
+        .map(|&(x, y)| format!("{},{}", num(x), num(y)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 节点形状（对齐 JS `renderNodeShape`；内联样式覆盖默认的填充/描边）。
+pub(super) fn node_shape(node: &PlacedNode) -> String {
+    let fill = escape_xml(style_value(node, "fill").unwrap_or("var(--_node-fill)"));
+    let stroke = escape_xml(style_value(node, "stroke").unwrap_or("var(--_node-stroke)"));
+    let stroke_width = escape_xml(
+        &style_value(node, "stroke-width").map_or_else(|| num(INNER_BOX_STROKE), str::to_string),
+    );
+    let paint = format!("fill=\"{fill}\" stroke=\"{stroke}\" stroke-width=\"{stroke_width}\"");
+    let line_paint = format!("stroke=\"{stroke}\" stroke-width=\"{stroke_width}\"");
+
+    let (x, y, w, h) = (node.x, node.y, node.width, node.height);
+    let (cx, cy) = (x + w / 2.0, y + h / 2.0);
+    let rect = |rx: f64| {
+        format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{r}\" ry=\"{r}\" {paint} />",
+            num(x),
+            num(y),
+            num(w),
+            num(h),
+            r = num(rx)
+        )
+    };
+    let circle = |r: f64, paint: &str| {
+        format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" {paint} />",
+            num(cx),
+            num(cy),
+            num(r)
+        )
+    };
+    let polygon = |points: &[(f64, f64)]| {
+        format!("<polygon points=\"{}\" {paint} />", polygon_points(points))
+    };
+    let vertical_line = |line_x: f64, top: f64, bottom: f64| {
+        format!(
+            "<line x1=\"{lx}\" y1=\"{}\" x2=\"{lx}\" y2=\"{}\" {line_paint} />",
+            num(top),
+            num(bottom),
+            lx = num(line_x)
+        )
+    };
+
+    match node.shape {
+        NodeShape::Rounded => rect(6.0),
+        NodeShape::Stadium => rect(h / 2.0),
+        NodeShape::Circle => circle(w.min(h) / 2.0, &paint),
+        NodeShape::Diamond => polygon(&[
+            (cx, cy - h / 2.0),
+            (cx + w / 2.0, cy),
+            (cx, cy + h / 2.0),
+            (cx - w / 2.0, cy),
+        ]),
+        NodeShape::Subroutine => [
+            rect(0.0),
+            vertical_line(x + 8.0, y, y + h),
+            vertical_line(x + w - 8.0, y, y + h),
+        ]
+        .join("\n"),
+        NodeShape::DoubleCircle => {
+            let r = w.min(h) / 2.0;
+            [circle(r, &paint), circle(r - 5.0, &paint)].join("\n")
+        }
+        NodeShape::Hexagon => {
+            let inset = h / 4.0;
+            polygon(&[
+                (x + inset, y),
+                (x + w - inset, y),
+                (x + w, y + h / 2.0),
+                (x + w - inset, y + h),
+                (x + inset, y + h),
+                (x, y + h / 2.0),
+            ])
+        }
+        NodeShape::Cylinder => {
+            let (top, body) = (y + 7.0, h - 14.0);
+            let ellipse = |ellipse_y: f64| {
+                format!(
+                    "<ellipse cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"7\" {paint} />",
+                    num(cx),
+                    num(ellipse_y),
+                    num(w / 2.0)
+                )
+            };
+            [
+                format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{fill}\" stroke=\"none\" />",
+                    num(x),
+                    num(top),
+                    num(w),
+                    num(body)
+                ),
+                vertical_line(x, top, top + body),
+                vertical_line(x + w, top, top + body),
+                ellipse(y + h - 7.0),
+                ellipse(top),
+            ]
+            .join("\n")
+        }
+        NodeShape::Asymmetric => polygon(&[
+            (x + 12.0, y),
+            (x + w, y),
+            (x + w, y + h),
+            (x + 12.0, y + h),
+            (x, y + h / 2.0),
+        ]),
+        NodeShape::Trapezoid => {
+            let inset = w * 0.15;
+            polygon(&[
+                (x + inset, y),
+                (x + w - inset, y),
+                (x + w, y + h),
+                (x, y + h),
+            ])
+        }
+        NodeShape::TrapezoidAlt => {
+            let inset = w * 0.15;
+            polygon(&[
+                (x, y),
+                (x + w, y),
+                (x + w - inset, y + h),
+                (x + inset, y + h),
+            ])
+        }
+        NodeShape::StateStart => circle(
+            w.min(h) / 2.0 - 2.0,
+            "fill=\"var(--_text)\" stroke=\"none\"",
+        ),
+        NodeShape::StateEnd => {
+            let r = w.min(h) / 2.0 - 2.0;
+            [
+                circle(
+                    r,
+                    &format!(
+                        "fill=\"none\" stroke=\"var(--_text)\" stroke-width=\"{}\"",
+                        num(INNER_BOX_STROKE * 2.0)
+                    ),
+                ),
+                circle(r - 4.0, "fill=\"var(--_text)\" stroke=\"none\""),
+            ]
+            .join("\n")
+        }
+        NodeShape::Rectangle => rect(0.0),
+    }
+}
+
+/// 节点文字（起止伪状态没有 label 时不画）。
+pub(super) fn node_label(node: &PlacedNode) -> String {
+    if matches!(node.shape, NodeShape::StateStart | NodeShape::StateEnd) && node.label.is_empty() {
+        return String::new();
+    }
+    let color = escape_xml(style_value(node, "color").unwrap_or("var(--_text)"));
+    format!(
+        "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dy=\"{TEXT_DY}\" font-size=\"{}\" font-weight=\"{NODE_LABEL_FONT_WEIGHT}\" fill=\"{color}\">{}</text>",
+        num(node.x + node.width / 2.0),
+        num(node.y + node.height / 2.0),
+        num(NODE_LABEL_FONT_SIZE),
+        escape_xml(&node.label)
+    )
+}
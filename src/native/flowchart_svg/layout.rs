@@ -0,0 +1,1901 @@
+// ============================================================================
+// flowchart SVG：分层布局（简化版 dagre）
+//
+// bundle 用 dagre（network simplex 定层 + Brandes-Köpf 定坐标），这里是更小的实现：
+// 1) 连通分量各自布局，最后沿“横向”拼接（对齐 JS `splitComponents` / `mergeComponents`）
+// 2) 去环：DFS 反向边；定层：最长路径 + 源点下沉
+// 3) rank 翻倍：边 label 作为中间 rank 上的占位节点，长边拆成 dummy 链
+// 4) subgraph：每个 rank 放左右边界节点（与 dagre 的 border node 同思路），排序时保持簇连续
+// 5) 排序：重心法上下扫描，保留交叉最少的一轮
+// 6) 横向坐标：按邻居加权平均求期望位置，用保序回归满足最小间距
+// 7) 走线：正交折线，拐点放在两层之间的空隙中线上
+//
+// 说明：坐标不追求与 JS 逐像素一致，只保证同一套节点尺寸规则与 SVG 结构。
+// ============================================================================
+
+use crate::native::flowchart::{Direction, EdgeStyle, FlowSubgraph, FlowchartGraph, NodeShape};
+use crate::native::svg::{
+    EDGE_LABEL_FONT_SIZE, EDGE_LABEL_FONT_WEIGHT, GROUP_HEADER_FONT_SIZE, GROUP_HEADER_FONT_WEIGHT,
+    NODE_LABEL_FONT_SIZE, NODE_LABEL_FONT_WEIGHT, text_width,
+};
+use crate::types::SvgPoint;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+/// 节点 label 的左右/上下留白与菱形额外尺寸（对齐 JS `NODE_PADDING`）。
+const NODE_PADDING_H: f64 = 16.0;
+const NODE_PADDING_V: f64 = 10.0;
+const DIAMOND_EXTRA: f64 = 24.0;
+/// dagre 默认的 edgesep：dummy/边界节点两侧的间距。
+const EDGE_SEP: f64 = 20.0;
+/// subgraph 框在 rank 方向上的内边距。
+const GROUP_PADDING: f64 = 16.0;
+/// subgraph 标题栏高度 + 与内容的间隔（对齐 JS `groupHeader + 16` 与 `GROUP_HEADER_GAP`）。
+pub(crate) const GROUP_HEADER_HEIGHT: f64 = GROUP_HEADER_FONT_SIZE + 16.0;
+const GROUP_HEADER_GAP: f64 = 8.0;
+/// 自环伸出节点的距离。
+const SELF_LOOP_REACH: f64 = 20.0;
+const SELF_LOOP_LABEL_GAP: f64 = 4.0;
+/// 同一侧有多条边时的端口间距（只对矩形类节点生效，菱形/圆形的边缘不是直线）。
+const PORT_SPACING: f64 = 12.0;
+/// 小于这个距离的横向错位直接拉直，不画拐弯。
+const MIN_JOG: f64 = 4.0;
+/// 同一空隙里多条拐弯段之间的间距上限。
+const JOG_TRACK_SPACING: f64 = 6.0;
+/// 排序阶段的上下扫描轮数 / 坐标阶段的迭代轮数。
+const ORDER_SWEEPS: usize = 12;
+const POSITION_ROUNDS: usize = 16;
+
+pub(crate) struct LayoutOptions {
+    pub(crate) padding: f64,
+    pub(crate) node_spacing: f64,
+    pub(crate) layer_spacing: f64,
+    /// 节点 id -> 固定尺寸（class / ER 的 box 由各自的规则算尺寸）；不在表里的按 `node_size`。
+    pub(crate) node_sizes: HashMap<String, (f64, f64)>,
+}
+
+pub(crate) struct PlacedNode {
+    pub(crate) id: String,
+    pub(crate) label: String,
+    pub(crate) shape: NodeShape,
+    /// classDef + style 合并后的内联样式（`fill` / `stroke` / `stroke-width` / `color`）。
+    pub(crate) style: Vec<(String, String)>,
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+}
+
+pub(crate) struct PlacedEdge {
+    pub(crate) source: String,
+    pub(crate) target: String,
+    pub(crate) label: Option<String>,
+    pub(crate) style: EdgeStyle,
+    pub(crate) has_arrow_start: bool,
+    pub(crate) has_arrow_end: bool,
+    pub(crate) points: Vec<SvgPoint>,
+    pub(crate) label_position: Option<SvgPoint>,
+}
+
+pub(crate) struct PlacedGroup {
+    pub(crate) id: String,
+    pub(crate) label: String,
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+    pub(crate) children: Vec<PlacedGroup>,
+}
+
+pub(crate) struct Layout {
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+    pub(crate) nodes: Vec<PlacedNode>,
+    pub(crate) edges: Vec<PlacedEdge>,
+    pub(crate) groups: Vec<PlacedGroup>,
+}
+
+/// 节点尺寸（对齐 JS `estimateNodeSize`）。
+fn node_size(label: &str, shape: NodeShape) -> (f64, f64) {
+    let mut width =
+        text_width(label, NODE_LABEL_FONT_SIZE, NODE_LABEL_FONT_WEIGHT) + NODE_PADDING_H * 2.0;
+    let mut height = NODE_LABEL_FONT_SIZE + NODE_PADDING_V * 2.0;
+    match shape {
+        NodeShape::Diamond => {
+            let side = width.max(height) + DIAMOND_EXTRA;
+            width = side;
+            height = side;
+        }
+        NodeShape::Circle | NodeShape::DoubleCircle => {
+            let diameter = (width * width + height * height).sqrt().ceil() + 8.0;
+            width = if shape == NodeShape::DoubleCircle {
+                diameter + 12.0
+            } else {
+                diameter
+            };
+            height = width;
+        }
+        NodeShape::Hexagon | NodeShape::Trapezoid | NodeShape::TrapezoidAlt => {
+            width += NODE_PADDING_H;
+        }
+        NodeShape::Asymmetric => width += 12.0,
+        NodeShape::Cylinder => height += 14.0,
+        NodeShape::StateStart | NodeShape::StateEnd => {
+            width = 28.0;
+            height = 28.0;
+        }
+        _ => {}
+    }
+    (width.max(60.0), height.max(36.0))
+}
+
+/// 边 label 占位尺寸（对齐 JS 传给 dagre 的 label width/height）。
+fn edge_label_size(label: &str) -> (f64, f64) {
+    (
+        text_width(label, EDGE_LABEL_FONT_SIZE, EDGE_LABEL_FONT_WEIGHT) + 8.0,
+        EDGE_LABEL_FONT_SIZE + 6.0,
+    )
+}
+
+/// SVG 里实际画出来的 label 底框尺寸（对齐 JS `renderEdgeLabel`）。
+pub(crate) fn edge_label_box(label: &str) -> (f64, f64) {
+    (
+        text_width(label, EDGE_LABEL_FONT_SIZE, EDGE_LABEL_FONT_WEIGHT) + 16.0,
+        EDGE_LABEL_FONT_SIZE + 16.0,
+    )
+}
+
+// ----------------------------------------------------------------------------
+// AST -> 布局单元
+// ----------------------------------------------------------------------------
+
+struct Cluster {
+    id: String,
+    label: String,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    /// `[...nodeIds, ...children]`：决定指向 subgraph 的边落到哪个节点上。
+    members: Vec<Member>,
+}
+
+#[derive(Clone, Copy)]
+enum Member {
+    Node(usize),
+    Cluster(usize),
+}
+
+#[derive(Clone, Copy)]
+enum UnitKind {
+    /// AST 节点。
+    Node(usize),
+    /// 空 subgraph 的占位（只有框，没有节点）。
+    Placeholder,
+}
+
+struct Unit {
+    kind: UnitKind,
+    cluster: Option<usize>,
+    width: f64,
+    height: f64,
+}
+
+/// 参与布局的边：端点已换算成 unit（指向 subgraph 的边落到其首/尾成员上）。
+struct Link {
+    edge: usize,
+    from: usize,
+    to: usize,
+}
+
+struct Model<'a> {
+    ast: &'a FlowchartGraph,
+    node_sizes: &'a HashMap<String, (f64, f64)>,
+    clusters: Vec<Cluster>,
+    units: Vec<Unit>,
+    links: Vec<Link>,
+}
+
+impl<'a> Model<'a> {
+    fn build(ast: &'a FlowchartGraph, node_sizes: &'a HashMap<String, (f64, f64)>) -> Self {
+        let mut model = Model {
+            ast,
+            node_sizes,
+            clusters: Vec::new(),
+            units: Vec::new(),
+            links: Vec::new(),
+        };
+        for subgraph in &ast.subgraphs {
+            model.add_cluster(subgraph, None);
+        }
+        let cluster_by_id: HashMap<String, usize> = model
+            .clusters
+            .iter()
+            .enumerate()
+            .map(|(index, cluster)| (cluster.id.clone(), index))
+            .collect();
+
+        // 节点归属：嵌套时取最深的那层（后遍历的子 subgraph 覆盖父级）
+        let mut owner: HashMap<usize, usize> = HashMap::new();
+        for (index, cluster) in model.clusters.iter().enumerate() {
+            for member in &cluster.members {
+                if let Member::Node(node) = member {
+                    owner.insert(*node, index);
+                }
+            }
+        }
+
+        // unit 顺序对齐 dagre 的插入顺序：先是不属于任何 subgraph 的节点，再按 subgraph 先序遍历
+        let mut unit_of_node: HashMap<usize, usize> = HashMap::new();
+        for (index, node) in ast.nodes.iter().enumerate() {
+            if owner.contains_key(&index) || cluster_by_id.contains_key(node.id.as_str()) {
+                continue;
+            }
+            model.push_node_unit(index, None, &mut unit_of_node);
+        }
+        let mut placeholder_of: HashMap<usize, usize> = HashMap::new();
+        for cluster in 0..model.clusters.len() {
+            if model.clusters[cluster].members.is_empty() {
+                let (width, height) = placeholder_size(&model.clusters[cluster].label);
+                placeholder_of.insert(cluster, model.units.len());
+                model.units.push(Unit {
+                    kind: UnitKind::Placeholder,
+                    cluster: Some(cluster),
+                    width,
+                    height,
+                });
+                continue;
+            }
+            let nodes: Vec<usize> = model.clusters[cluster]
+                .members
+                .iter()
+                .filter_map(|member| match member {
+                    Member::Node(node) => Some(*node),
+                    Member::Cluster(_) => None,
+                })
+                .collect();
+            for node in nodes {
+                if !unit_of_node.contains_key(&node) {
+                    let owner = owner.get(&node).copied();
+                    model.push_node_unit(node, owner, &mut unit_of_node);
+                }
+            }
+        }
+
+        let resolve = |id: &str, as_target: bool| -> Option<usize> {
+            match cluster_by_id.get(id) {
+                Some(&cluster) => {
+                    model.cluster_endpoint(cluster, as_target, &unit_of_node, &placeholder_of)
+                }
+                None => unit_of_node.get(&ast.node_position(id)?).copied(),
+            }
+        };
+        // dagre 的图不是 multigraph：换算后端点相同的边只留一条，
+        // 位置是第一次出现的位置、内容是最后一条（对齐 JS `setEdge` 覆盖 label）
+        let mut links: Vec<Link> = Vec::new();
+        let mut slot_of: HashMap<(usize, usize), usize> = HashMap::new();
+        for (index, edge) in ast.edges.iter().enumerate() {
+            let (Some(from), Some(to)) =
+                (resolve(&edge.source, false), resolve(&edge.target, true))
+            else {
+                continue;
+            };
+            match slot_of.entry((from, to)) {
+                Entry::Occupied(slot) => links[*slot.get()].edge = index,
+                Entry::Vacant(slot) => {
+                    slot.insert(links.len());
+                    links.push(Link {
+                        edge: index,
+                        from,
+                        to,
+                    });
+                }
+            }
+        }
+        model.links = links;
+        model
+    }
+
+    fn add_cluster(&mut self, subgraph: &FlowSubgraph, parent: Option<usize>) -> usize {
+        let index = self.clusters.len();
+        let mut members: Vec<Member> = subgraph
+            .node_ids
+            .iter()
+            .filter_map(|id| self.ast.node_position(id).map(Member::Node))
+            .collect();
+        self.clusters.push(Cluster {
+            id: subgraph.id.clone(),
+            label: subgraph.label.clone(),
+            parent,
+            children: Vec::new(),
+            members: Vec::new(),
+        });
+        for child in &subgraph.children {
+            let child_index = self.add_cluster(child, Some(index));
+            self.clusters[index].children.push(child_index);
+            members.push(Member::Cluster(child_index));
+        }
+        self.clusters[index].members = members;
+        index
+    }
+
+    fn push_node_unit(
+        &mut self,
+        node: usize,
+        cluster: Option<usize>,
+        unit_of_node: &mut HashMap<usize, usize>,
+    ) {
+        let ast_node = &self.ast.nodes[node];
+        let (width, height) = self
+            .node_sizes
+            .get(&ast_node.id)
+            .copied()
+            .unwrap_or_else(|| node_size(&ast_node.label, ast_node.shape));
+        unit_of_node.insert(node, self.units.len());
+        self.units.push(Unit {
+            kind: UnitKind::Node(node),
+            cluster,
+            width,
+            height,
+        });
+    }
+
+    /// 指向 subgraph 的边：target 落到第一个成员，source 落到最后一个成员（对齐 JS）。
+    fn cluster_endpoint(
+        &self,
+        cluster: usize,
+        first: bool,
+        unit_of_node: &HashMap<usize, usize>,
+        placeholder_of: &HashMap<usize, usize>,
+    ) -> Option<usize> {
+        let members = &self.clusters[cluster].members;
+        let member = if first {
+            members.first()
+        } else {
+            members.last()
+        };
+        match member {
+            None => placeholder_of.get(&cluster).copied(),
+            Some(Member::Node(node)) => unit_of_node.get(node).copied(),
+            Some(Member::Cluster(child)) => {
+                self.cluster_endpoint(*child, first, unit_of_node, placeholder_of)
+            }
+        }
+    }
+
+    /// 连通分量（subgraph 内的节点视为连通），按首个 unit 的顺序输出。
+    fn components(&self) -> Vec<Vec<usize>> {
+        let mut parent: Vec<usize> = (0..self.units.len()).collect();
+        fn find(parent: &mut [usize], x: usize) -> usize {
+            let mut root = x;
+            while parent[root] != root {
+                root = parent[root];
+            }
+            let mut current = x;
+            while parent[current] != root {
+                let next = parent[current];
+                parent[current] = root;
+                current = next;
+            }
+            root
+        }
+        let union = |parent: &mut Vec<usize>, a: usize, b: usize| {
+            let (ra, rb) = (find(parent, a), find(parent, b));
+            if ra != rb {
+                parent[ra.max(rb)] = ra.min(rb);
+            }
+        };
+        for link in &self.links {
+            union(&mut parent, link.from, link.to);
+        }
+        let mut first_in_cluster: HashMap<usize, usize> = HashMap::new();
+        for (unit, data) in self.units.iter().enumerate() {
+            let mut cluster = data.cluster;
+            while let Some(current) = cluster {
+                match first_in_cluster.get(&current) {
+                    Some(&first) => union(&mut parent, first, unit),
+                    None => {
+                        first_in_cluster.insert(current, unit);
+                    }
+                }
+                cluster = self.clusters[current].parent;
+            }
+        }
+
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut group_of_root: HashMap<usize, usize> = HashMap::new();
+        for unit in 0..self.units.len() {
+            let root = find(&mut parent, unit);
+            let group = *group_of_root.entry(root).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(unit);
+        }
+        groups
+    }
+
+    fn cluster_depth(&self, cluster: usize) -> usize {
+        let mut depth = 0;
+        let mut current = self.clusters[cluster].parent;
+        while let Some(parent) = current {
+            depth += 1;
+            current = self.clusters[parent].parent;
+        }
+        depth
+    }
+
+    /// 两个簇的最近公共祖先（None = 顶层）。
+    fn common_cluster(&self, a: Option<usize>, b: Option<usize>) -> Option<usize> {
+        let (mut a, mut b) = (a?, b?);
+        let (mut depth_a, mut depth_b) = (self.cluster_depth(a), self.cluster_depth(b));
+        while depth_a > depth_b {
+            a = self.clusters[a].parent?;
+            depth_a -= 1;
+        }
+        while depth_b > depth_a {
+            b = self.clusters[b].parent?;
+            depth_b -= 1;
+        }
+        while a != b {
+            a = self.clusters[a].parent?;
+            b = self.clusters[b].parent?;
+        }
+        Some(a)
+    }
+
+    /// `cluster` 是否等于 `ancestor` 或在其内部。
+    fn is_within(&self, cluster: Option<usize>, ancestor: usize) -> bool {
+        let mut current = cluster;
+        while let Some(c) = current {
+            if c == ancestor {
+                return true;
+            }
+            current = self.clusters[c].parent;
+        }
+        false
+    }
+
+    /// `cluster` 在 `parent` 下的那一级祖先（`cluster` 本身就在 `parent` 里直接返回自己）。
+    fn child_under(&self, cluster: Option<usize>, parent: Option<usize>) -> Option<usize> {
+        let mut current = cluster?;
+        loop {
+            let up = self.clusters[current].parent;
+            if up == parent {
+                return Some(current);
+            }
+            current = up?;
+        }
+    }
+}
+
+fn placeholder_size(label: &str) -> (f64, f64) {
+    (
+        (text_width(label, GROUP_HEADER_FONT_SIZE, GROUP_HEADER_FONT_WEIGHT) + 24.0).max(60.0),
+        36.0,
+    )
+}
+
+// ----------------------------------------------------------------------------
+// 单个连通分量的分层布局
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Copy, PartialEq)]
+enum ItemKind {
+    Unit(usize),
+    Dummy,
+    Label,
+    LeftBorder(usize),
+    RightBorder(usize),
+}
+
+struct Item {
+    kind: ItemKind,
+    rank: usize,
+    cluster: Option<usize>,
+    /// 横向（排序方向）尺寸 / rank 方向尺寸。
+    breadth: f64,
+    depth: f64,
+    /// 自环在右侧额外占用的宽度。
+    extra_right: f64,
+}
+
+impl Item {
+    fn is_unit(&self) -> bool {
+        matches!(self.kind, ItemKind::Unit(_))
+    }
+}
+
+/// 相邻 rank 之间的一段（上 -> 下）与权重（dummy 链权重更高，长边更直）。
+struct Segment {
+    upper: usize,
+    lower: usize,
+    weight: f64,
+}
+
+struct Chain {
+    link: usize,
+    items: Vec<usize>,
+    label: Option<usize>,
+    reversed: bool,
+}
+
+struct ComponentLayout<'m, 'a> {
+    model: &'m Model<'a>,
+    horizontal: bool,
+    header_after: bool,
+    options: &'m LayoutOptions,
+    items: Vec<Item>,
+    segments: Vec<Segment>,
+    chains: Vec<Chain>,
+    self_loops: Vec<(usize, usize)>,
+    layers: Vec<Vec<usize>>,
+    /// 分量内出现的簇 -> rank 范围。
+    cluster_span: HashMap<usize, (usize, usize)>,
+    pos: Vec<f64>,
+    rank_center: Vec<f64>,
+    /// rank k-1 与 rank k 之间空隙（不含 subgraph 内边距/标题栏）的中线：边在这里拐弯。
+    band_middle: Vec<f64>,
+    /// 端口偏移（见 `port_offsets`）。
+    ports: HashMap<(usize, bool), f64>,
+}
+
+/// 一条边的折线点 + 拐弯段 `(起点下标, 所在 rank)`。
+type Route = (Vec<(f64, f64)>, Vec<(usize, usize)>);
+
+pub(crate) fn layout(ast: &FlowchartGraph, options: &LayoutOptions) -> Layout {
+    let model = Model::build(ast, &options.node_sizes);
+    let components = model.components();
+    if components.is_empty() {
+        return Layout {
+            width: options.padding * 2.0,
+            height: options.padding * 2.0,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            groups: Vec::new(),
+        };
+    }
+
+    let parts: Vec<Layout> = components
+        .iter()
+        .map(|units| ComponentLayout::run(&model, units, ast.direction, options))
+        .collect();
+    let mut layout = merge_components(parts, ast.direction.is_horizontal(), options);
+    round_coordinates(&mut layout);
+    layout
+}
+
+/// 坐标统一保留 3 位小数：SVG 输出与 meta 用同一组数值（meta 能在 SVG 里逐字找到）。
+fn round_coordinates(layout: &mut Layout) {
+    fn round(value: &mut f64) {
+        *value = (*value * 1000.0).round() / 1000.0 + 0.0;
+    }
+    fn round_group(group: &mut PlacedGroup) {
+        for value in [
+            &mut group.x,
+            &mut group.y,
+            &mut group.width,
+            &mut group.height,
+        ] {
+            round(value);
+        }
+        group.children.iter_mut().for_each(round_group);
+    }
+    round(&mut layout.width);
+    round(&mut layout.height);
+    for node in &mut layout.nodes {
+        for value in [&mut node.x, &mut node.y, &mut node.width, &mut node.height] {
+            round(value);
+        }
+    }
+    for edge in &mut layout.edges {
+        for point in edge.points.iter_mut().chain(edge.label_position.as_mut()) {
+            round(&mut point.x);
+            round(&mut point.y);
+        }
+    }
+    layout.groups.iter_mut().for_each(round_group);
+}
+
+/// 多个分量沿横向拼接（对齐 JS `mergeComponents`：间距 = nodeSpacing - 2 * padding）。
+fn merge_components(parts: Vec<Layout>, horizontal: bool, options: &LayoutOptions) -> Layout {
+    let gap = options.node_spacing - 2.0 * options.padding;
+    let mut merged = Layout {
+        width: 0.0,
+        height: 0.0,
+        nodes: Vec::new(),
+        edges: Vec::new(),
+        groups: Vec::new(),
+    };
+    let mut offset = 0.0;
+    for mut part in parts {
+        let (dx, dy) = if horizontal {
+            (0.0, offset)
+        } else {
+            (offset, 0.0)
+        };
+        translate(&mut part, dx, dy);
+        if horizontal {
+            merged.width = merged.width.max(part.width);
+            merged.height = offset + part.height;
+            offset += part.height + gap;
+        } else {
+            merged.width = offset + part.width;
+            merged.height = merged.height.max(part.height);
+            offset += part.width + gap;
+        }
+        merged.nodes.extend(part.nodes);
+        merged.edges.extend(part.edges);
+        merged.groups.extend(part.groups);
+    }
+    merged
+}
+
+fn translate(layout: &mut Layout, dx: f64, dy: f64) {
+    fn shift_group(group: &mut PlacedGroup, dx: f64, dy: f64) {
+        group.x += dx;
+        group.y += dy;
+        for child in &mut group.children {
+            shift_group(child, dx, dy);
+        }
+    }
+    for node in &mut layout.nodes {
+        node.x += dx;
+        node.y += dy;
+    }
+    for edge in &mut layout.edges {
+        for point in &mut edge.points {
+            point.x += dx;
+            point.y += dy;
+        }
+        if let Some(point) = &mut edge.label_position {
+            point.x += dx;
+            point.y += dy;
+        }
+    }
+    for group in &mut layout.groups {
+        shift_group(group, dx, dy);
+    }
+}
+
+impl<'m, 'a> ComponentLayout<'m, 'a> {
+    fn run(
+        model: &'m Model<'a>,
+        units: &[usize],
+        direction: Direction,
+        options: &'m LayoutOptions,
+    ) -> Layout {
+        let mut this = ComponentLayout {
+            model,
+            horizontal: direction.is_horizontal(),
+            header_after: direction == Direction::Bt,
+            options,
+            items: Vec::new(),
+            segments: Vec::new(),
+            chains: Vec::new(),
+            self_loops: Vec::new(),
+            layers: Vec::new(),
+            cluster_span: HashMap::new(),
+            pos: Vec::new(),
+            rank_center: Vec::new(),
+            band_middle: Vec::new(),
+            ports: HashMap::new(),
+        };
+        let ranks = this.assign_ranks(units);
+        this.build_items(units, &ranks);
+        this.order();
+        this.position();
+        this.align_borders();
+        this.ports = this.port_offsets();
+        this.straighten();
+        this.rank_positions();
+        this.finish(direction)
+    }
+
+    // ---------------------------- 定层 ----------------------------
+
+    /// 返回 unit -> rank（已翻倍），并记录每条边是否被反向。
+    fn assign_ranks(&mut self, units: &[usize]) -> HashMap<usize, usize> {
+        let local: HashMap<usize, usize> = units
+            .iter()
+            .enumerate()
+            .map(|(index, &unit)| (unit, index))
+            .collect();
+        let links: Vec<usize> = (0..self.model.links.len())
+            .filter(|&link| local.contains_key(&self.model.links[link].from))
+            .collect();
+
+        let n = units.len();
+        let mut out: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+        for &link in &links {
+            let data = &self.model.links[link];
+            if data.from == data.to {
+                self.self_loops.push((data.from, link));
+                continue;
+            }
+            out[local[&data.from]].push((local[&data.to], link));
+        }
+
+        // DFS 去环：指向栈上节点的边反向
+        let mut reversed: HashMap<usize, bool> = HashMap::new();
+        let mut state = vec![0u8; n];
+        for start in 0..n {
+            if state[start] != 0 {
+                continue;
+            }
+            let mut stack: Vec<(usize, usize)> = vec![(start, 0)];
+            state[start] = 1;
+            while let Some(&mut (node, ref mut next)) = stack.last_mut() {
+                if *next < out[node].len() {
+                    let (target, link) = out[node][*next];
+                    *next += 1;
+                    match state[target] {
+                        0 => {
+                            state[target] = 1;
+                            stack.push((target, 0));
+                            reversed.insert(link, false);
+                        }
+                        1 => {
+                            reversed.insert(link, true);
+                        }
+                        _ => {
+                            reversed.insert(link, false);
+                        }
+                    }
+                } else {
+                    state[node] = 2;
+                    stack.pop();
+                }
+            }
+        }
+
+        // 最长路径定层
+        let mut succ: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut indegree = vec![0usize; n];
+        for (&link, &is_reversed) in &reversed {
+            let data = &self.model.links[link];
+            let (from, to) = (local[&data.from], local[&data.to]);
+            let (from, to) = if is_reversed { (to, from) } else { (from, to) };
+            succ[from].push(to);
+            indegree[to] += 1;
+        }
+        let mut rank = vec![0usize; n];
+        let mut queue: Vec<usize> = (0..n).filter(|&node| indegree[node] == 0).collect();
+        let mut topo = Vec::with_capacity(n);
+        let mut remaining = indegree.clone();
+        while let Some(node) = queue.pop() {
+            topo.push(node);
+            for &next in &succ[node] {
+                rank[next] = rank[next].max(rank[node] + 1);
+                remaining[next] -= 1;
+                if remaining[next] == 0 {
+                    queue.push(next);
+                }
+            }
+        }
+        // 源点下沉到紧挨着后继的位置，避免被拉得很长的首段
+        for &node in topo.iter().rev() {
+            if indegree[node] == 0
+                && let Some(min_next) = succ[node].iter().map(|&next| rank[next]).min()
+            {
+                rank[node] = min_next.saturating_sub(1);
+            }
+        }
+
+        self.chains = links
+            .iter()
+            .filter(|link| reversed.contains_key(link))
+            .map(|&link| Chain {
+                link,
+                items: Vec::new(),
+                label: None,
+                reversed: reversed[&link],
+            })
+            .collect();
+        units
+            .iter()
+            .enumerate()
+            .map(|(index, &unit)| (unit, rank[index] * 2))
+            .collect()
+    }
+
+    // ---------------------------- 建 item ----------------------------
+
+    fn unit_dims(&self, unit: usize) -> (f64, f64) {
+        let data = &self.model.units[unit];
+        if self.horizontal {
+            (data.height, data.width)
+        } else {
+            (data.width, data.height)
+        }
+    }
+
+    fn push_item(&mut self, kind: ItemKind, rank: usize, cluster: Option<usize>) -> usize {
+        self.items.push(Item {
+            kind,
+            rank,
+            cluster,
+            breadth: 0.0,
+            depth: 0.0,
+            extra_right: 0.0,
+        });
+        self.items.len() - 1
+    }
+
+    fn build_items(&mut self, units: &[usize], ranks: &HashMap<usize, usize>) {
+        let mut item_of_unit: HashMap<usize, usize> = HashMap::new();
+        for &unit in units {
+            let item = self.push_item(
+                ItemKind::Unit(unit),
+                ranks[&unit],
+                self.model.units[unit].cluster,
+            );
+            let (breadth, depth) = self.unit_dims(unit);
+            self.items[item].breadth = breadth;
+            self.items[item].depth = depth;
+            item_of_unit.insert(unit, item);
+        }
+
+        // 自环：在节点右侧（横向正方向）留出回环与 label 的空间
+        let mut loops_per_unit: HashMap<usize, usize> = HashMap::new();
+        for &(unit, link) in &self.self_loops {
+            let count = loops_per_unit.entry(unit).or_insert(0);
+            *count += 1;
+            let label_breadth = self.link_label(link).map_or(0.0, |label| {
+                let (width, height) = edge_label_box(label);
+                if self.horizontal { height } else { width }
+            });
+            let reach = SELF_LOOP_REACH * *count as f64 + label_breadth + SELF_LOOP_LABEL_GAP;
+            let item = item_of_unit[&unit];
+            self.items[item].extra_right = self.items[item].extra_right.max(reach);
+        }
+
+        // 边拆成 dummy 链；label 占位放在中间 rank
+        for chain in 0..self.chains.len() {
+            let link = &self.model.links[self.chains[chain].link];
+            let (mut upper, mut lower) = (link.from, link.to);
+            if self.chains[chain].reversed {
+                std::mem::swap(&mut upper, &mut lower);
+            }
+            let (upper_rank, lower_rank) = (ranks[&upper], ranks[&lower]);
+            let cluster = self.model.common_cluster(
+                self.model.units[upper].cluster,
+                self.model.units[lower].cluster,
+            );
+            let label_size = self
+                .link_label(self.chains[chain].link)
+                .map(edge_label_size);
+            let label_rank = upper_rank + (lower_rank - upper_rank) / 2;
+
+            let mut items = vec![item_of_unit[&upper]];
+            for rank in upper_rank + 1..lower_rank {
+                let is_label = label_size.is_some() && rank == label_rank;
+                let kind = if is_label {
+                    ItemKind::Label
+                } else {
+                    ItemKind::Dummy
+                };
+                let item = self.push_item(kind, rank, cluster);
+                if is_label && let Some((width, height)) = label_size {
+                    let (breadth, depth) = if self.horizontal {
+                        (height, width)
+                    } else {
+                        (width, height)
+                    };
+                    self.items[item].breadth = breadth;
+                    self.items[item].depth = depth;
+                    self.chains[chain].label = Some(item);
+                }
+                items.push(item);
+            }
+            items.push(item_of_unit[&lower]);
+            for pair in items.windows(2) {
+                let dummies = pair
+                    .iter()
+                    .filter(|&&item| !self.items[item].is_unit())
+                    .count();
+                let weight = match dummies {
+                    0 => 1.0,
+                    1 => 2.0,
+                    _ => 8.0,
+                };
+                self.segments.push(Segment {
+                    upper: pair[0],
+                    lower: pair[1],
+                    weight,
+                });
+            }
+            self.chains[chain].items = items;
+        }
+
+        // 簇的 rank 范围（含嵌套子簇）+ 每个 rank 的左右边界
+        let mut spans: HashMap<usize, (usize, usize)> = HashMap::new();
+        for item in &self.items {
+            let mut cluster = item.cluster;
+            while let Some(current) = cluster {
+                let span = spans.entry(current).or_insert((item.rank, item.rank));
+                span.0 = span.0.min(item.rank);
+                span.1 = span.1.max(item.rank);
+                cluster = self.model.clusters[current].parent;
+            }
+        }
+        let mut clusters: Vec<usize> = spans.keys().copied().collect();
+        clusters.sort_unstable();
+        for cluster in clusters {
+            let (first, last) = spans[&cluster];
+            let mut previous: Option<(usize, usize)> = None;
+            for rank in first..=last {
+                let left = self.push_item(ItemKind::LeftBorder(cluster), rank, Some(cluster));
+                let right = self.push_item(ItemKind::RightBorder(cluster), rank, Some(cluster));
+                if let Some((prev_left, prev_right)) = previous {
+                    for (upper, lower) in [(prev_left, left), (prev_right, right)] {
+                        self.segments.push(Segment {
+                            upper,
+                            lower,
+                            weight: 8.0,
+                        });
+                    }
+                }
+                previous = Some((left, right));
+            }
+        }
+        self.cluster_span = spans;
+
+        let rank_count = self
+            .items
+            .iter()
+            .map(|item| item.rank + 1)
+            .max()
+            .unwrap_or(0);
+        self.layers = vec![Vec::new(); rank_count];
+        for (index, item) in self.items.iter().enumerate() {
+            self.layers[item.rank].push(index);
+        }
+    }
+
+    fn link_label(&self, link: usize) -> Option<&'a str> {
+        let ast = self.model.ast;
+        ast.edges[self.model.links[link].edge]
+            .label
+            .as_deref()
+            .filter(|label| !label.is_empty())
+    }
+
+    // ---------------------------- 排序 ----------------------------
+
+    fn order(&mut self) {
+        let mut index_in_layer = vec![0.0; self.items.len()];
+        let mut up: Vec<Vec<(usize, f64)>> = vec![Vec::new(); self.items.len()];
+        let mut down: Vec<Vec<(usize, f64)>> = vec![Vec::new(); self.items.len()];
+        for segment in &self.segments {
+            down[segment.upper].push((segment.lower, segment.weight));
+            up[segment.lower].push((segment.upper, segment.weight));
+        }
+
+        let mut cluster_order: HashMap<usize, f64> = HashMap::new();
+        for rank in 0..self.layers.len() {
+            let keys: HashMap<usize, f64> = self.layers[rank]
+                .iter()
+                .map(|&item| (item, item as f64))
+                .collect();
+            self.layers[rank] = self.arrange(&self.layers[rank], &keys, &cluster_order);
+        }
+        self.update_indices(&mut index_in_layer);
+        cluster_order = self.cluster_order(&index_in_layer);
+
+        let mut best = self.layers.clone();
+        let mut best_crossings = self.crossings(&index_in_layer);
+        for sweep in 0..ORDER_SWEEPS {
+            let downward = sweep % 2 == 0;
+            let ranks: Vec<usize> = if downward {
+                (1..self.layers.len()).collect()
+            } else {
+                (0..self.layers.len().saturating_sub(1)).rev().collect()
+            };
+            for rank in ranks {
+                let neighbors = if downward { &up } else { &down };
+                let keys: HashMap<usize, f64> = self.layers[rank]
+                    .iter()
+                    .map(|&item| {
+                        let (sum, weight) = neighbors[item].iter().fold(
+                            (0.0, 0.0),
+                            |(sum, weight), &(other, w)| {
+                                (sum + index_in_layer[other] * w, weight + w)
+                            },
+                        );
+                        let key = if weight > 0.0 {
+                            sum / weight
+                        } else {
+                            index_in_layer[item]
+                        };
+                        (item, key)
+                    })
+                    .collect();
+                self.layers[rank] = self.arrange(&self.layers[rank], &keys, &cluster_order);
+                for (index, &item) in self.layers[rank].iter().enumerate() {
+                    index_in_layer[item] = index as f64;
+                }
+            }
+            cluster_order = self.cluster_order(&index_in_layer);
+            let crossings = self.crossings(&index_in_layer);
+            if crossings < best_crossings {
+                best_crossings = crossings;
+                best = self.layers.clone();
+            }
+        }
+        self.layers = best;
+    }
+
+    fn update_indices(&self, index_in_layer: &mut [f64]) {
+        for layer in &self.layers {
+            for (index, &item) in layer.iter().enumerate() {
+                index_in_layer[item] = index as f64;
+            }
+        }
+    }
+
+    /// 簇的全局先后：簇内所有 item 的相对位置均值（保证各 rank 上兄弟簇的顺序一致）。
+    fn cluster_order(&self, index_in_layer: &[f64]) -> HashMap<usize, f64> {
+        let mut sums: HashMap<usize, (f64, f64)> = HashMap::new();
+        for layer in &self.layers {
+            let len = layer.len().max(1) as f64;
+            for &item in layer {
+                let mut cluster = self.items[item].cluster;
+                while let Some(current) = cluster {
+                    let entry = sums.entry(current).or_insert((0.0, 0.0));
+                    entry.0 += index_in_layer[item] / len;
+                    entry.1 += 1.0;
+                    cluster = self.model.clusters[current].parent;
+                }
+            }
+        }
+        sums.into_iter()
+            .map(|(cluster, (sum, count))| (cluster, sum / count))
+            .collect()
+    }
+
+    /// 按 key 排序，同时保证每个簇在本层连续、左右边界在两端、兄弟簇顺序全局一致。
+    fn arrange(
+        &self,
+        layer: &[usize],
+        keys: &HashMap<usize, f64>,
+        cluster_order: &HashMap<usize, f64>,
+    ) -> Vec<usize> {
+        self.arrange_block(None, layer.to_vec(), keys, cluster_order)
+    }
+
+    fn arrange_block(
+        &self,
+        parent: Option<usize>,
+        items: Vec<usize>,
+        keys: &HashMap<usize, f64>,
+        cluster_order: &HashMap<usize, f64>,
+    ) -> Vec<usize> {
+        enum Entry {
+            Single(usize),
+            Group(usize, Vec<usize>),
+        }
+        let mut entries: Vec<(f64, Entry)> = Vec::new();
+        let mut group_slot: HashMap<usize, usize> = HashMap::new();
+        for item in items {
+            let data = &self.items[item];
+            let is_own_border = matches!(data.kind, ItemKind::LeftBorder(c) | ItemKind::RightBorder(c) if Some(c) == parent);
+            if is_own_border {
+                continue;
+            }
+            match self.model.child_under(data.cluster, parent) {
+                Some(child) if data.cluster != parent => {
+                    let slot = *group_slot.entry(child).or_insert_with(|| {
+                        entries.push((0.0, Entry::Group(child, Vec::new())));
+                        entries.len() - 1
+                    });
+                    if let Entry::Group(_, members) = &mut entries[slot].1 {
+                        members.push(item);
+                    }
+                }
+                _ => entries.push((keys[&item], Entry::Single(item))),
+            }
+        }
+        for (key, entry) in &mut entries {
+            if let Entry::Group(_, members) = entry {
+                let inner: Vec<f64> = members
+                    .iter()
+                    .filter(|&&item| {
+                        !matches!(
+                            self.items[item].kind,
+                            ItemKind::LeftBorder(_) | ItemKind::RightBorder(_)
+                        )
+                    })
+                    .map(|item| keys[item])
+                    .collect();
+                let source: Vec<f64> = if inner.is_empty() {
+                    members.iter().map(|item| keys[item]).collect()
+                } else {
+                    inner
+                };
+                *key = source.iter().sum::<f64>() / source.len() as f64;
+            }
+        }
+        entries.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // 兄弟簇按全局顺序占用排序后的“簇槽位”
+        let slots: Vec<usize> = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, entry))| matches!(entry, Entry::Group(..)))
+            .map(|(index, _)| index)
+            .collect();
+        let mut groups: Vec<(f64, Entry)> = Vec::new();
+        for &slot in slots.iter().rev() {
+            groups.push(entries.remove(slot));
+        }
+        groups.sort_by(|a, b| {
+            let order = |entry: &Entry| match entry {
+                Entry::Group(cluster, _) => cluster_order.get(cluster).copied().unwrap_or(0.0),
+                Entry::Single(_) => 0.0,
+            };
+            order(&a.1).total_cmp(&order(&b.1))
+        });
+        for (slot, group) in slots.into_iter().zip(groups) {
+            entries.insert(slot, group);
+        }
+
+        let mut out = Vec::new();
+        for (_, entry) in entries {
+            match entry {
+                Entry::Single(item) => out.push(item),
+                Entry::Group(cluster, members) => {
+                    let rank = self.items[members[0]].rank;
+                    let left = self.border_at(cluster, rank, true);
+                    let right = self.border_at(cluster, rank, false);
+                    out.extend(left);
+                    out.extend(self.arrange_block(Some(cluster), members, keys, cluster_order));
+                    out.extend(right);
+                }
+            }
+        }
+        out
+    }
+
+    fn border_at(&self, cluster: usize, rank: usize, left: bool) -> Option<usize> {
+        self.layers
+            .get(rank)?
+            .iter()
+            .copied()
+            .find(|&item| match self.items[item].kind {
+                ItemKind::LeftBorder(c) => left && c == cluster,
+                ItemKind::RightBorder(c) => !left && c == cluster,
+                _ => false,
+            })
+    }
+
+    fn crossings(&self, index_in_layer: &[f64]) -> f64 {
+        let mut by_rank: Vec<Vec<(f64, f64, f64)>> = vec![Vec::new(); self.layers.len()];
+        for segment in &self.segments {
+            let rank = self.items[segment.upper].rank;
+            by_rank[rank].push((
+                index_in_layer[segment.upper],
+                index_in_layer[segment.lower],
+                segment.weight,
+            ));
+        }
+        let mut total = 0.0;
+        for segments in &by_rank {
+            for (i, a) in segments.iter().enumerate() {
+                for b in &segments[i + 1..] {
+                    if (a.0 - b.0) * (a.1 - b.1) < 0.0 {
+                        total += a.2 * b.2;
+                    }
+                }
+            }
+        }
+        total
+    }
+
+    // ---------------------------- 横向坐标 ----------------------------
+
+    fn half_sep(&self, item: usize, right_side: bool) -> f64 {
+        let data = &self.items[item];
+        let spacing = if data.is_unit() {
+            self.options.node_spacing / 2.0
+        } else {
+            EDGE_SEP / 2.0
+        };
+        let mut half = data.breadth / 2.0 + spacing;
+        if right_side {
+            half += data.extra_right;
+        }
+        // 横向布局（LR/RL）时标题栏在横向的起始侧，由左边界让出空间
+        if right_side && self.horizontal && matches!(data.kind, ItemKind::LeftBorder(_)) {
+            half += GROUP_HEADER_HEIGHT + GROUP_HEADER_GAP;
+        }
+        half
+    }
+
+    fn separation(&self, left: usize, right: usize) -> f64 {
+        self.half_sep(left, true) + self.half_sep(right, false)
+    }
+
+    fn position(&mut self) {
+        self.pos = vec![0.0; self.items.len()];
+        for layer in &self.layers {
+            let mut x = 0.0;
+            for (index, &item) in layer.iter().enumerate() {
+                if index > 0 {
+                    x += self.separation(layer[index - 1], item);
+                }
+                self.pos[item] = x;
+            }
+        }
+
+        let mut neighbors: Vec<Vec<(usize, f64)>> = vec![Vec::new(); self.items.len()];
+        for segment in &self.segments {
+            neighbors[segment.upper].push((segment.lower, segment.weight));
+            neighbors[segment.lower].push((segment.upper, segment.weight));
+        }
+
+        for round in 0..POSITION_ROUNDS {
+            let ranks: Vec<usize> = if round % 2 == 0 {
+                (0..self.layers.len()).collect()
+            } else {
+                (0..self.layers.len()).rev().collect()
+            };
+            for rank in ranks {
+                let layer = self.layers[rank].clone();
+                let mut targets = Vec::with_capacity(layer.len());
+                for (index, &item) in layer.iter().enumerate() {
+                    let (mut sum, mut weight) = neighbors[item]
+                        .iter()
+                        .fold((0.0, 0.0), |(sum, weight), &(other, w)| {
+                            (sum + self.pos[other] * w, weight + w)
+                        });
+                    // 边界贴紧簇内的相邻 item，框不留多余空白
+                    match self.items[item].kind {
+                        ItemKind::LeftBorder(_) if index + 1 < layer.len() => {
+                            let next = layer[index + 1];
+                            sum += (self.pos[next] - self.separation(item, next)) * 8.0;
+                            weight += 8.0;
+                        }
+                        ItemKind::RightBorder(_) if index > 0 => {
+                            let prev = layer[index - 1];
+                            sum += (self.pos[prev] + self.separation(prev, item)) * 8.0;
+                            weight += 8.0;
+                        }
+                        _ => {}
+                    }
+                    if weight == 0.0 {
+                        targets.push((self.pos[item], 1e-3));
+                    } else {
+                        targets.push((sum / weight, weight));
+                    }
+                }
+                let positions = self.fit_layer(&layer, &targets);
+                for (item, x) in layer.iter().zip(positions) {
+                    self.pos[*item] = x;
+                }
+            }
+        }
+    }
+
+    /// subgraph 框是矩形：同一簇各 rank 的左/右边界统一到最外侧，再把因此产生的重叠推开。
+    ///
+    /// 做法：同一簇的左（右）边界合并成一个变量，同层相邻 item 之间是“至少隔 sep”的约束，
+    /// 按拓扑序做一遍最长路（只向右推），得到满足所有约束的位置。
+    fn align_borders(&mut self) {
+        if self.cluster_span.is_empty() {
+            return;
+        }
+        self.unify_borders();
+
+        let count = self.items.len();
+        let variable = |item: usize| match self.items[item].kind {
+            ItemKind::LeftBorder(cluster) => count + 2 * cluster,
+            ItemKind::RightBorder(cluster) => count + 2 * cluster + 1,
+            _ => item,
+        };
+        let variables = count + 2 * self.model.clusters.len();
+        let mut value = vec![0.0; variables];
+        for item in 0..count {
+            value[variable(item)] = self.pos[item];
+        }
+        let mut successors: Vec<Vec<(usize, f64)>> = vec![Vec::new(); variables];
+        let mut indegree = vec![0usize; variables];
+        for layer in &self.layers {
+            for pair in layer.windows(2) {
+                let (from, to) = (variable(pair[0]), variable(pair[1]));
+                successors[from].push((to, self.separation(pair[0], pair[1])));
+                indegree[to] += 1;
+            }
+        }
+
+        let mut queue: Vec<usize> = (0..variables).filter(|&v| indegree[v] == 0).collect();
+        let mut visited = 0;
+        while let Some(from) = queue.pop() {
+            visited += 1;
+            for &(to, sep) in &successors[from] {
+                value[to] = f64::max(value[to], value[from] + sep);
+                indegree[to] -= 1;
+                if indegree[to] == 0 {
+                    queue.push(to);
+                }
+            }
+        }
+        // 各 rank 上簇的先后一致时约束图无环；万一有环就保留统一边界前的结果
+        if visited < variables {
+            return;
+        }
+        for item in 0..count {
+            self.pos[item] = value[variable(item)];
+        }
+    }
+
+    fn unify_borders(&mut self) {
+        let mut extent: HashMap<usize, (f64, f64)> = HashMap::new();
+        for (index, item) in self.items.iter().enumerate() {
+            match item.kind {
+                ItemKind::LeftBorder(cluster) => {
+                    let entry = extent
+                        .entry(cluster)
+                        .or_insert((f64::INFINITY, f64::NEG_INFINITY));
+                    entry.0 = entry.0.min(self.pos[index]);
+                }
+                ItemKind::RightBorder(cluster) => {
+                    let entry = extent
+                        .entry(cluster)
+                        .or_insert((f64::INFINITY, f64::NEG_INFINITY));
+                    entry.1 = entry.1.max(self.pos[index]);
+                }
+                _ => {}
+            }
+        }
+        for (index, item) in self.items.iter().enumerate() {
+            match item.kind {
+                ItemKind::LeftBorder(cluster) => self.pos[index] = extent[&cluster].0,
+                ItemKind::RightBorder(cluster) => self.pos[index] = extent[&cluster].1,
+                _ => {}
+            }
+        }
+    }
+
+    /// 拉直边：dummy/label 尽量与链上相邻项对齐（不违反同层最小间距），减少多余的拐弯。
+    fn straighten(&mut self) {
+        let mut slot = vec![0; self.items.len()];
+        for layer in &self.layers {
+            for (index, &item) in layer.iter().enumerate() {
+                slot[item] = index;
+            }
+        }
+        for _ in 0..2 {
+            for chain in 0..self.chains.len() {
+                let items = self.chains[chain].items.clone();
+                let last = items.len() - 1;
+                for index in 1..last {
+                    let item = items[index];
+                    let mut previous = self.pos[items[index - 1]];
+                    if index == 1 {
+                        previous += self.port(chain, true);
+                    }
+                    let mut next = self.pos[items[index + 1]];
+                    if index + 1 == last {
+                        next += self.port(chain, false);
+                    }
+                    for candidate in [previous, next] {
+                        if self.fits(item, candidate, &slot) {
+                            self.pos[item] = candidate;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn port(&self, chain: usize, upper: bool) -> f64 {
+        self.ports.get(&(chain, upper)).copied().unwrap_or(0.0)
+    }
+
+    /// `item` 移到 `x` 后是否仍与同层左右邻居保持最小间距。
+    fn fits(&self, item: usize, x: f64, slot: &[usize]) -> bool {
+        const EPSILON: f64 = 1e-6;
+        let layer = &self.layers[self.items[item].rank];
+        let index = slot[item];
+        let left_ok = index == 0 || {
+            let left = layer[index - 1];
+            self.pos[left] + self.separation(left, item) <= x + EPSILON
+        };
+        let right_ok = index + 1 >= layer.len() || {
+            let right = layer[index + 1];
+            x + self.separation(item, right) <= self.pos[right] + EPSILON
+        };
+        left_ok && right_ok
+    }
+
+    /// 加权保序回归（pool adjacent violators）：尽量靠近期望位置，同时满足相邻最小间距。
+    fn fit_layer(&self, layer: &[usize], targets: &[(f64, f64)]) -> Vec<f64> {
+        let mut offsets = Vec::with_capacity(layer.len());
+        let mut offset = 0.0;
+        for (index, &item) in layer.iter().enumerate() {
+            if index > 0 {
+                offset += self.separation(layer[index - 1], item);
+            }
+            offsets.push(offset);
+        }
+
+        // block: (加权和, 权重和, 个数)
+        let mut blocks: Vec<(f64, f64, usize)> = Vec::new();
+        for (index, &(target, weight)) in targets.iter().enumerate() {
+            blocks.push(((target - offsets[index]) * weight, weight, 1));
+            while blocks.len() >= 2 {
+                let last = blocks[blocks.len() - 1];
+                let prev = blocks[blocks.len() - 2];
+                if prev.0 / prev.1 <= last.0 / last.1 {
+                    break;
+                }
+                blocks.pop();
+                let merged = blocks.last_mut().expect("至少还剩一个 block");
+                merged.0 += last.0;
+                merged.1 += last.1;
+                merged.2 += last.2;
+            }
+        }
+        let mut out = Vec::with_capacity(layer.len());
+        for (sum, weight, count) in blocks {
+            for _ in 0..count {
+                out.push(sum / weight + offsets[out.len()]);
+            }
+        }
+        out
+    }
+
+    // ---------------------------- rank 方向坐标 ----------------------------
+
+    fn rank_positions(&mut self) {
+        let count = self.layers.len();
+        let mut depth = vec![0.0f64; count];
+        for item in &self.items {
+            depth[item.rank] = depth[item.rank].max(item.depth);
+        }
+        let header = if self.horizontal {
+            0.0
+        } else {
+            GROUP_HEADER_HEIGHT + GROUP_HEADER_GAP
+        };
+        let mut before = vec![0.0; count];
+        let mut after = vec![0.0; count];
+        for &(first, last) in self.cluster_span.values() {
+            before[first] += GROUP_PADDING;
+            after[last] += GROUP_PADDING;
+            if self.header_after {
+                after[last] += header;
+            } else {
+                before[first] += header;
+            }
+        }
+
+        let gap = self.options.layer_spacing / 2.0;
+        self.rank_center = vec![0.0; count];
+        self.band_middle = vec![0.0; count];
+        let mut cursor = 0.0;
+        for rank in 0..count {
+            if rank > 0 {
+                self.band_middle[rank] = cursor + gap / 2.0;
+                cursor += gap;
+            }
+            cursor += before[rank] + depth[rank] / 2.0;
+            self.rank_center[rank] = cursor;
+            cursor += depth[rank] / 2.0 + after[rank];
+        }
+    }
+
+    // ---------------------------- 输出 ----------------------------
+
+    fn to_global(&self, direction: Direction, cross: f64, along: f64) -> SvgPoint {
+        match direction {
+            Direction::Lr => SvgPoint { x: along, y: cross },
+            Direction::Rl => SvgPoint {
+                x: -along,
+                y: cross,
+            },
+            Direction::Bt => SvgPoint {
+                x: cross,
+                y: -along,
+            },
+            Direction::Td | Direction::Tb => SvgPoint { x: cross, y: along },
+        }
+    }
+
+    fn item_center(&self, item: usize) -> (f64, f64) {
+        (self.pos[item], self.rank_center[self.items[item].rank])
+    }
+
+    /// 边的正交折线（局部坐标：cross, along），同时返回拐弯段 `(起点下标, 所在 rank)`。
+    fn route(&self, index: usize) -> Route {
+        let chain = &self.chains[index];
+        let ports = (self.port(index, true), self.port(index, false));
+        let first = chain.items[0];
+        let last = chain.items[chain.items.len() - 1];
+
+        let (start_cross, start_along) = self.item_center(first);
+        let start_cross = start_cross + ports.0;
+        let mut points = vec![(start_cross, start_along + self.items[first].depth / 2.0)];
+        let mut cross = start_cross;
+        let mut jogs = Vec::new();
+        // 逐个经过 dummy：相邻两项横向不一致时，在两层之间空隙的中线上拐弯
+        for &item in &chain.items[1..] {
+            let (mut item_cross, item_along) = self.item_center(item);
+            if item == last {
+                item_cross += ports.1;
+            }
+            // 几个像素的错位不值得拐弯（端口偏移本来就是近似值）
+            if (item_cross - cross).abs() < MIN_JOG {
+                item_cross = cross;
+            } else {
+                let rank = self.items[item].rank;
+                let middle = self.band_middle[rank];
+                jogs.push((points.len(), rank));
+                points.push((cross, middle));
+                points.push((item_cross, middle));
+            }
+            cross = item_cross;
+            if item == last {
+                points.push((item_cross, item_along - self.items[item].depth / 2.0));
+            } else {
+                points.push((item_cross, item_along));
+            }
+        }
+        (points, jogs)
+    }
+
+    /// 同一层空隙里横向区间重叠的拐弯段错开高度（区间图着色），避免多条边共线难以分辨。
+    fn separate_jogs(&self, routes: &mut [Route]) {
+        let mut by_rank: HashMap<usize, Vec<(f64, f64, usize, usize)>> = HashMap::new();
+        for (route, (points, jogs)) in routes.iter().enumerate() {
+            for &(start, rank) in jogs {
+                let (a, b) = (points[start].0, points[start + 1].0);
+                by_rank
+                    .entry(rank)
+                    .or_default()
+                    .push((a.min(b), a.max(b), route, start));
+            }
+        }
+        let gap = self.options.layer_spacing / 2.0;
+        for (_, mut segments) in by_rank {
+            segments.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.2.cmp(&b.2)));
+            let mut track_ends: Vec<f64> = Vec::new();
+            let mut tracks = Vec::with_capacity(segments.len());
+            for &(low, high, _, _) in &segments {
+                let track = match track_ends.iter().position(|&end| end + MIN_JOG <= low) {
+                    Some(track) => track,
+                    None => {
+                        track_ends.push(f64::NEG_INFINITY);
+                        track_ends.len() - 1
+                    }
+                };
+                track_ends[track] = high;
+                tracks.push(track);
+            }
+            let count = track_ends.len() as f64;
+            if count < 2.0 {
+                continue;
+            }
+            let spacing = JOG_TRACK_SPACING.min(gap / (count + 1.0));
+            for (&(_, _, route, start), track) in segments.iter().zip(tracks) {
+                let offset = (track as f64 - (count - 1.0) / 2.0) * spacing;
+                let points = &mut routes[route].0;
+                points[start].1 += offset;
+                points[start + 1].1 += offset;
+            }
+        }
+    }
+
+    /// 自环：从节点右侧伸出再折回，label 放在回环外侧。
+    fn self_loop(
+        &self,
+        item: usize,
+        nth: usize,
+        label_breadth: f64,
+    ) -> (Vec<(f64, f64)>, (f64, f64)) {
+        let (cross, along) = self.item_center(item);
+        let data = &self.items[item];
+        let edge = cross + data.breadth / 2.0;
+        let reach = edge + SELF_LOOP_REACH * (nth + 1) as f64;
+        let (top, bottom) = (along - data.depth / 4.0, along + data.depth / 4.0);
+        (
+            vec![(edge, top), (reach, top), (reach, bottom), (edge, bottom)],
+            (reach + SELF_LOOP_LABEL_GAP + label_breadth / 2.0, along),
+        )
+    }
+
+    /// 端口偏移：同一节点同一侧的多条边按对端位置排开，避免在同一个点上重叠。
+    ///
+    /// key = (chain 下标, 是否为上端)，value = 相对节点中心的横向偏移。
+    fn port_offsets(&self) -> HashMap<(usize, bool), f64> {
+        let mut sides: HashMap<(usize, bool), Vec<(f64, usize)>> = HashMap::new();
+        for (index, chain) in self.chains.iter().enumerate() {
+            let len = chain.items.len();
+            let (upper, lower) = (chain.items[0], chain.items[len - 1]);
+            sides
+                .entry((upper, false))
+                .or_default()
+                .push((self.pos[chain.items[1]], index));
+            sides
+                .entry((lower, true))
+                .or_default()
+                .push((self.pos[chain.items[len - 2]], index));
+        }
+        let mut offsets = HashMap::new();
+        for ((item, is_top), mut attached) in sides {
+            let spreadable = match self.items[item].kind {
+                ItemKind::Unit(unit) => match self.model.units[unit].kind {
+                    UnitKind::Node(node) => !matches!(
+                        self.model.ast.nodes[node].shape,
+                        NodeShape::Diamond
+                            | NodeShape::Circle
+                            | NodeShape::DoubleCircle
+                            | NodeShape::StateStart
+                            | NodeShape::StateEnd
+                    ),
+                    UnitKind::Placeholder => true,
+                },
+                _ => false,
+            };
+            if attached.len() < 2 || !spreadable {
+                continue;
+            }
+            attached.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            let count = attached.len() as f64;
+            let step = PORT_SPACING.min(self.items[item].breadth / (count + 1.0));
+            for (slot, (_, chain)) in attached.into_iter().enumerate() {
+                let offset = (slot as f64 - (count - 1.0) / 2.0) * step;
+                // key 里的“上端”指 chain 的首个 item（位于节点的下侧）
+                offsets.insert((chain, !is_top), offset);
+            }
+        }
+        offsets
+    }
+
+    fn cluster_box(&self, cluster: usize) -> (f64, f64, f64, f64) {
+        let mut cross_min = f64::INFINITY;
+        let mut cross_max = f64::NEG_INFINITY;
+        let mut along_min = f64::INFINITY;
+        let mut along_max = f64::NEG_INFINITY;
+        for (index, item) in self.items.iter().enumerate() {
+            match item.kind {
+                ItemKind::LeftBorder(c) if c == cluster => {
+                    cross_min = cross_min.min(self.pos[index])
+                }
+                ItemKind::RightBorder(c) if c == cluster => {
+                    cross_max = cross_max.max(self.pos[index])
+                }
+                ItemKind::Unit(_) if self.model.is_within(item.cluster, cluster) => {
+                    let center = self.rank_center[item.rank];
+                    along_min = along_min.min(center - item.depth / 2.0);
+                    along_max = along_max.max(center + item.depth / 2.0);
+                }
+                _ => {}
+            }
+        }
+        for &child in &self.model.clusters[cluster].children {
+            if self.cluster_span.contains_key(&child) {
+                let (_, _, child_min, child_max) = self.cluster_box(child);
+                along_min = along_min.min(child_min);
+                along_max = along_max.max(child_max);
+            }
+        }
+        along_min -= GROUP_PADDING;
+        along_max += GROUP_PADDING;
+        if !self.horizontal {
+            let header = GROUP_HEADER_HEIGHT + GROUP_HEADER_GAP;
+            if self.header_after {
+                along_max += header;
+            } else {
+                along_min -= header;
+            }
+        }
+        (cross_min, cross_max, along_min, along_max)
+    }
+
+    fn placed_group(&self, cluster: usize, direction: Direction) -> PlacedGroup {
+        let (cross_min, cross_max, along_min, along_max) = self.cluster_box(cluster);
+        let a = self.to_global(direction, cross_min, along_min);
+        let b = self.to_global(direction, cross_max, along_max);
+        let data = &self.model.clusters[cluster];
+        PlacedGroup {
+            id: data.id.clone(),
+            label: data.label.clone(),
+            x: a.x.min(b.x),
+            y: a.y.min(b.y),
+            width: (a.x - b.x).abs(),
+            height: (a.y - b.y).abs(),
+            children: data
+                .children
+                .iter()
+                .filter(|child| self.cluster_span.contains_key(child))
+                .map(|&child| self.placed_group(child, direction))
+                .collect(),
+        }
+    }
+
+    fn finish(&self, direction: Direction) -> Layout {
+        let ast = self.model.ast;
+        let mut nodes = Vec::new();
+        let mut item_of_unit: HashMap<usize, usize> = HashMap::new();
+        for (index, item) in self.items.iter().enumerate() {
+            let ItemKind::Unit(unit) = item.kind else {
+                continue;
+            };
+            item_of_unit.insert(unit, index);
+            let UnitKind::Node(node) = self.model.units[unit].kind else {
+                continue;
+            };
+            let (cross, along) = self.item_center(index);
+            let center = self.to_global(direction, cross, along);
+            let data = &self.model.units[unit];
+            let ast_node = &ast.nodes[node];
+            nodes.push(PlacedNode {
+                id: ast_node.id.clone(),
+                label: ast_node.label.clone(),
+                shape: ast_node.shape,
+                style: inline_style(ast, &ast_node.id),
+                x: center.x - data.width / 2.0,
+                y: center.y - data.height / 2.0,
+                width: data.width,
+                height: data.height,
+            });
+        }
+
+        let mut routed: Vec<(usize, Vec<SvgPoint>, Option<SvgPoint>)> = Vec::new();
+        let global = |points: Vec<(f64, f64)>| -> Vec<SvgPoint> {
+            points
+                .into_iter()
+                .map(|(cross, along)| self.to_global(direction, cross, along))
+                .collect()
+        };
+        let mut routes: Vec<_> = (0..self.chains.len())
+            .map(|index| self.route(index))
+            .collect();
+        self.separate_jogs(&mut routes);
+        for (chain, (mut points, _)) in self.chains.iter().zip(routes) {
+            simplify(&mut points);
+            if chain.reversed {
+                points.reverse();
+            }
+            let label = chain.label.map(|item| {
+                let (cross, along) = self.item_center(item);
+                self.to_global(direction, cross, along)
+            });
+            routed.push((chain.link, global(points), label));
+        }
+        let mut loop_count: HashMap<usize, usize> = HashMap::new();
+        for &(unit, link) in &self.self_loops {
+            let nth = loop_count.entry(unit).or_insert(0);
+            let label_breadth = self.link_label(link).map_or(0.0, |label| {
+                let (width, height) = edge_label_box(label);
+                if self.horizontal { height } else { width }
+            });
+            let (points, label) = self.self_loop(item_of_unit[&unit], *nth, label_breadth);
+            *nth += 1;
+            let label = self
+                .link_label(link)
+                .map(|_| self.to_global(direction, label.0, label.1));
+            routed.push((link, global(points), label));
+        }
+        routed.sort_by_key(|(link, _, _)| *link);
+
+        let edges = routed
+            .into_iter()
+            .map(|(link, points, label_position)| {
+                let edge = &ast.edges[self.model.links[link].edge];
+                PlacedEdge {
+                    source: edge.source.clone(),
+                    target: edge.target.clone(),
+                    label: edge.label.clone(),
+                    style: edge.style,
+                    has_arrow_start: edge.has_arrow_start,
+                    has_arrow_end: edge.has_arrow_end,
+                    points,
+                    label_position,
+                }
+            })
+            .collect();
+
+        let groups = (0..self.model.clusters.len())
+            .filter(|cluster| {
+                self.model.clusters[*cluster].parent.is_none()
+                    && self.cluster_span.contains_key(cluster)
+            })
+            .map(|cluster| self.placed_group(cluster, direction))
+            .collect();
+
+        let mut layout = Layout {
+            width: 0.0,
+            height: 0.0,
+            nodes,
+            edges,
+            groups,
+        };
+        normalize(&mut layout, self.options.padding);
+        layout
+    }
+}
+
+/// 去掉共线的中间点（对齐 JS `simplifyOrthogonal`）。
+fn simplify(points: &mut Vec<(f64, f64)>) {
+    if points.len() < 3 {
+        return;
+    }
+    let mut out = vec![points[0]];
+    for index in 1..points.len() - 1 {
+        let prev = out[out.len() - 1];
+        let (current, next) = (points[index], points[index + 1]);
+        let vertical = (prev.0 - current.0).abs() < 1.0 && (current.0 - next.0).abs() < 1.0;
+        let horizontal = (prev.1 - current.1).abs() < 1.0 && (current.1 - next.1).abs() < 1.0;
+        if !vertical && !horizontal {
+            out.push(current);
+        }
+    }
+    out.push(points[points.len() - 1]);
+    *points = out;
+}
+
+/// classDef（经 `class` / `:::` 赋值）+ `style` 合并（对齐 JS `resolveNodeStyle`）。
+fn inline_style(ast: &FlowchartGraph, node_id: &str) -> Vec<(String, String)> {
+    let mut style: Vec<(String, String)> = Vec::new();
+    let mut apply = |props: &[(String, String)]| {
+        for (key, value) in props {
+            match style.iter_mut().find(|(existing, _)| existing == key) {
+                Some(entry) => entry.1 = value.clone(),
+                None => style.push((key.clone(), value.clone())),
+            }
+        }
+    };
+    if let Some((_, class_name)) = ast.class_assignments.iter().find(|(id, _)| id == node_id)
+        && let Some(props) = ast.class_defs.get(class_name)
+    {
+        apply(props);
+    }
+    if let Some(props) = ast.node_styles.get(node_id) {
+        apply(props);
+    }
+    style
+}
+
+/// 平移到 (padding, padding) 起点，并按内容算出画布尺寸。
+fn normalize(layout: &mut Layout, padding: f64) {
+    let mut min = (f64::INFINITY, f64::INFINITY);
+    let mut max = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    let mut include = |x: f64, y: f64| {
+        min = (min.0.min(x), min.1.min(y));
+        max = (max.0.max(x), max.1.max(y));
+    };
+    fn include_group(group: &PlacedGroup, include: &mut dyn FnMut(f64, f64)) {
+        include(group.x, group.y);
+        include(group.x + group.width, group.y + group.height);
+        for child in &group.children {
+            include_group(child, include);
+        }
+    }
+    for node in &layout.nodes {
+        include(node.x, node.y);
+        include(node.x + node.width, node.y + node.height);
+    }
+    for edge in &layout.edges {
+        for point in &edge.points {
+            include(point.x, point.y);
+        }
+        if let (Some(label), Some(position)) = (&edge.label, edge.label_position) {
+            let (width, height) = edge_label_box(label);
+            include(position.x - width / 2.0, position.y - height / 2.0);
+            include(position.x + width / 2.0, position.y + height / 2.0);
+        }
+    }
+    for group in &layout.groups {
+        include_group(group, &mut include);
+    }
+    if !min.0.is_finite() {
+        layout.width = padding * 2.0;
+        layout.height = padding * 2.0;
+        return;
+    }
+    translate(layout, padding - min.0, padding - min.1);
+    layout.width = max.0 - min.0 + padding * 2.0;
+    layout.height = max.1 - min.1 + padding * 2.0;
+}
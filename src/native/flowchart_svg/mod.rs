@@ -0,0 +1,244 @@
+// ============================================================================
+// Flowchart / State diagram：纯 Rust SVG 渲染（`Backend::Native`）
+//
+// 流水线：
+// 1) 解析：`native::flowchart::parse`（与 ASCII 后端共用同一个 AST）
+// 2) 布局：分层布局（`layout`，dagre 的简化版：定层 -> 排序 -> 定坐标 -> 正交走线）
+// 3) 绘制：元素标记与 bundle 的 `renderSvg` 逐项对齐（`draw`）
+// 4) 收尾：套上无障碍属性 + `<title>/<desc>`（对齐 bundle 的 `bmA11y`）
+//
+// 说明：
+// - SVG 结构、CSS 变量配色、元素 id、节点尺寸规则都与 JS 后端一致，
+//   主题/样式/交互脚本可以无差别地套在两种输出上；
+// - 坐标不保证与 dagre 逐像素一致（布局算法不同），但保证节点互不重叠、边从节点边缘出入。
+// ============================================================================
+
+mod draw;
+pub(crate) mod layout;
+
+use crate::error::Result;
+use crate::native::svg::{
+    count, edge_attrs, element_group, escape_xml, list, node_attrs, style_block, svg_open_tag,
+    with_accessibility,
+};
+use crate::native::{self, flowchart};
+use crate::svg_meta::{ElementIds, rect};
+use crate::types::{
+    RenderOptions, SvgDiagramMeta, SvgFlowchartMeta, SvgMetaEdge, SvgMetaGroup, SvgMetaNode,
+    SvgRenderWithMeta,
+};
+use layout::{Layout, LayoutOptions, PlacedGroup};
+use std::collections::HashMap;
+
+/// 默认间距（对齐 JS `LAYOUT_DEFAULTS`）。
+const DEFAULT_PADDING: f64 = 40.0;
+const DEFAULT_NODE_SPACING: f64 = 24.0;
+const DEFAULT_LAYER_SPACING: f64 = 40.0;
+
+/// 渲染 flowchart/state 为 SVG。
+pub(crate) fn render(text: &str, options: &RenderOptions) -> Result<String> {
+    Ok(run(text, options)?.0)
+}
+
+/// 渲染 flowchart/state 为 SVG + 像素坐标 meta（`element_id` 与 SVG 里的 `<g id>` 一一对应）。
+pub(crate) fn render_with_meta(text: &str, options: &RenderOptions) -> Result<SvgRenderWithMeta> {
+    let (svg, layout, prefix) = run(text, options)?;
+    let mut ids = ElementIds::new(&prefix);
+    let meta = SvgFlowchartMeta {
+        nodes: layout
+            .nodes
+            .iter()
+            .map(|node| SvgMetaNode {
+                id: node.id.clone(),
+                element_id: ids.node(&node.id),
+                label: node.label.clone(),
+                shape: node.shape.as_str().to_string(),
+                box_rect: rect(node.x, node.y, node.width, node.height),
+            })
+            .collect(),
+        edges: layout
+            .edges
+            .iter()
+            .map(|edge| SvgMetaEdge {
+                from: edge.source.clone(),
+                to: edge.target.clone(),
+                element_id: ids.edge(&edge.source, &edge.target),
+                label: edge.label.clone(),
+                style: edge.style.as_str().to_string(),
+                has_arrow_start: edge.has_arrow_start,
+                has_arrow_end: edge.has_arrow_end,
+                points: edge.points.clone(),
+                label_position: edge
+                    .label
+                    .as_ref()
+                    .filter(|label| !label.is_empty())
+                    .and(edge.label_position),
+            })
+            .collect(),
+        groups: layout.groups.iter().map(meta_group).collect(),
+    };
+    Ok(SvgRenderWithMeta {
+        svg,
+        view_box: rect(0.0, 0.0, layout.width, layout.height),
+        meta: SvgDiagramMeta::Flowchart(meta),
+    })
+}
+
+fn meta_group(group: &PlacedGroup) -> SvgMetaGroup {
+    SvgMetaGroup {
+        id: group.id.clone(),
+        label: group.label.clone(),
+        box_rect: rect(group.x, group.y, group.width, group.height),
+        children: group.children.iter().map(meta_group).collect(),
+    }
+}
+
+fn run(text: &str, options: &RenderOptions) -> Result<(String, Layout, String)> {
+    let accessibility = native::parse_accessibility(text);
+    let stripped = accessibility.text();
+    let ast = flowchart::parse(text)?;
+    let layout = layout::layout(
+        &ast,
+        &LayoutOptions {
+            padding: options.padding.unwrap_or(DEFAULT_PADDING),
+            node_spacing: options.node_spacing.unwrap_or(DEFAULT_NODE_SPACING),
+            layer_spacing: options.layer_spacing.unwrap_or(DEFAULT_LAYER_SPACING),
+            node_sizes: HashMap::new(),
+        },
+    );
+
+    let prefix = escape_xml(options.id_prefix.as_deref().unwrap_or_default());
+    let is_state = stripped
+        .trim_start()
+        .to_lowercase()
+        .starts_with("statediagram");
+    let title = options.title.clone().or(accessibility.title);
+    let description = options
+        .description
+        .clone()
+        .or(accessibility.description)
+        .unwrap_or_else(|| describe(&layout, is_state));
+
+    let body = render_body(&layout, options, &prefix);
+    let kind = if is_state { "state" } else { "flowchart" };
+    let svg = with_accessibility(&body, kind, title.as_deref(), &description, &prefix);
+    Ok((svg, layout, prefix))
+}
+
+// ----------------------------------------------------------------------------
+// SVG 组装
+// ----------------------------------------------------------------------------
+
+fn render_body(layout: &Layout, options: &RenderOptions, prefix: &str) -> String {
+    let mut parts = vec![
+        svg_open_tag(layout.width, layout.height, options),
+        style_block(options, false),
+        "<defs>".to_string(),
+        draw::arrow_markers(prefix),
+        "</defs>".to_string(),
+    ];
+    parts.extend(layout.groups.iter().map(draw::group));
+
+    let mut ids = ElementIds::new(prefix);
+    let edge_ids: Vec<String> = layout
+        .edges
+        .iter()
+        .map(|edge| ids.edge(&edge.source, &edge.target))
+        .collect();
+    for (edge, id) in layout.edges.iter().zip(&edge_ids) {
+        parts.push(element_group(
+            &edge_attrs(id, &edge.source, &edge.target, "edge"),
+            &[draw::edge(edge, prefix)],
+        ));
+    }
+    for (edge, id) in layout.edges.iter().zip(&edge_ids) {
+        if let (Some(label), Some(position)) = (&edge.label, edge.label_position)
+            && !label.is_empty()
+        {
+            parts.push(element_group(
+                &edge_attrs(
+                    &format!("{id}-label"),
+                    &edge.source,
+                    &edge.target,
+                    "edge-label",
+                ),
+                &[draw::edge_label(label, position)],
+            ));
+        }
+    }
+    for node in &layout.nodes {
+        parts.push(element_group(
+            &node_attrs(&ids.node(&node.id), &node.id),
+            &[draw::node_shape(node), draw::node_label(node)],
+        ));
+    }
+    parts.push("</svg>".to_string());
+    parts.retain(|part| !part.is_empty());
+    parts.join("\n")
+}
+
+// ----------------------------------------------------------------------------
+// 无障碍
+// ----------------------------------------------------------------------------
+
+/// 自动生成的 `<desc>`：节点/边列表（对齐 JS `bmDescribe` 的 flowchart/state 分支）。
+fn describe(layout: &Layout, is_state: bool) -> String {
+    let name_of = |id: &str| -> String {
+        layout
+            .nodes
+            .iter()
+            .find(|node| node.id == id)
+            .map_or_else(|| id.to_string(), |node| display_name(node, is_state))
+    };
+    let nodes: Vec<String> = layout
+        .nodes
+        .iter()
+        .map(|node| display_name(node, is_state))
+        .collect();
+    let edges: Vec<String> = layout
+        .edges
+        .iter()
+        .map(|edge| {
+            let label = edge
+                .label
+                .as_deref()
+                .filter(|label| !label.is_empty())
+                .map(|label| format!(" ({label})"))
+                .unwrap_or_default();
+            format!(
+                "{} → {}{label}",
+                name_of(&edge.source),
+                name_of(&edge.target)
+            )
+        })
+        .collect();
+
+    if is_state {
+        format!(
+            "State diagram with {} and {}.{}{}",
+            count(layout.nodes.len(), "state", "states"),
+            count(layout.edges.len(), "transition", "transitions"),
+            list(&nodes, "States"),
+            list(&edges, "Transitions")
+        )
+    } else {
+        format!(
+            "Flowchart with {} and {}.{}{}",
+            count(layout.nodes.len(), "node", "nodes"),
+            count(layout.edges.len(), "edge", "edges"),
+            list(&nodes, "Nodes"),
+            list(&edges, "Edges")
+        )
+    }
+}
+
+fn display_name(node: &layout::PlacedNode, is_state: bool) -> String {
+    if !node.label.is_empty() {
+        return node.label.clone();
+    }
+    match node.shape {
+        flowchart::NodeShape::StateStart if is_state => "start".to_string(),
+        flowchart::NodeShape::StateEnd if is_state => "end".to_string(),
+        _ => node.id.clone(),
+    }
+}
@@ -0,0 +1,212 @@
+// ============================================================================
+// 纯 Rust 实现（逐步替换 JS bundle）
+//
+// 背景：
+// - `js.rs` 的演进方向是“保持 Rust API 不变，把内部实现逐步替换为纯 Rust”；
+// - 这里放的是已经 Rust 化的部分：解析 + 布局（坐标计算）。
+//
+// 设计原则：
+// - parser/布局规则逐行对齐 JS bundle（同一份 Mermaid 输入 -> 同一份坐标）；
+// - 这样 Rust 侧算出来的坐标可以直接套在 JS 输出的文本上（例如 meta）。
+//
+// Features：
+// - parser + ASCII meta（class / er / sequence）两个后端都要用，始终编译；
+// - 渲染器（`*_ascii` / `*_svg` 及其共用的画布、SVG 外壳）只在 `native-backend` 下编译。
+// ============================================================================
+
+#[cfg(feature = "native-backend")]
+pub(crate) mod canvas;
+pub(crate) mod class;
+#[cfg(feature = "native-backend")]
+pub(crate) mod class_ascii;
+#[cfg(feature = "native-backend")]
+pub(crate) mod class_svg;
+pub(crate) mod er;
+#[cfg(feature = "native-backend")]
+pub(crate) mod er_ascii;
+#[cfg(feature = "native-backend")]
+pub(crate) mod er_svg;
+#[cfg(feature = "native-backend")]
+pub(crate) mod flowchart;
+#[cfg(feature = "native-backend")]
+pub(crate) mod flowchart_ascii;
+#[cfg(feature = "native-backend")]
+pub(crate) mod flowchart_svg;
+pub(crate) mod sequence;
+#[cfg(feature = "native-backend")]
+pub(crate) mod sequence_ascii;
+#[cfg(feature = "native-backend")]
+pub(crate) mod sequence_svg;
+#[cfg(feature = "native-backend")]
+pub(crate) mod svg;
+pub(crate) mod text;
+
+#[cfg(feature = "native-backend")]
+use crate::error::Result;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// JS bundle 支持的图类型（对齐 TS: `detectDiagramType`）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DiagramKind {
+    Flowchart,
+    Sequence,
+    Class,
+    Er,
+}
+
+impl DiagramKind {
+    /// 错误信息里用的名称。
+    #[cfg(not(feature = "js-backend"))]
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Flowchart => "flowchart/state diagram",
+            Self::Sequence => "sequence diagram",
+            Self::Class => "class diagram",
+            Self::Er => "ER diagram",
+        }
+    }
+}
+
+static SEQUENCE_HEADER: Lazy<Regex> = Lazy::new(|| Regex::new(r"^sequencediagram\s*$").unwrap());
+static CLASS_HEADER: Lazy<Regex> = Lazy::new(|| Regex::new(r"^classdiagram\s*$").unwrap());
+static ER_HEADER: Lazy<Regex> = Lazy::new(|| Regex::new(r"^erdiagram\s*$").unwrap());
+
+static ACC_TITLE_VALUE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^accTitle\s*:\s*(.*)$").unwrap());
+static ACC_DESCR_VALUE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^accDescr\s*:\s*(.*)$").unwrap());
+static ACC_DESCR_BLOCK_VALUE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^accDescr\s*\{\s*(.*)$").unwrap());
+
+/// 无障碍指令解析结果（对齐 bundle 的 `bmAcc`）。
+pub(crate) struct Accessibility<'a> {
+    /// 去掉指令后的行（原样，不 trim）。
+    pub lines: Vec<&'a str>,
+    /// `accTitle: ...`
+    #[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+    pub title: Option<String>,
+    /// `accDescr: ...` 或 `accDescr { ... }`（多行按 `\n` 拼接）。
+    #[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+    pub description: Option<String>,
+}
+
+impl Accessibility<'_> {
+    /// 去掉指令后的原文。
+    pub(crate) fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+}
+
+/// 提取 `accTitle` / `accDescr`，并返回去掉这些指令后的行。
+///
+/// 无障碍指令只在这里识别：图类型判断、各 parser 的预处理、SVG 的 `<title>/<desc>` 都从这里取。
+pub(crate) fn parse_accessibility(text: &str) -> Accessibility<'_> {
+    let mut kept = Vec::new();
+    let mut title = None;
+    let mut description = None;
+    let mut block: Option<Vec<&str>> = None;
+    for line in text.split('\n') {
+        let trimmed = line.trim();
+        if let Some(lines) = &mut block {
+            if trimmed.starts_with('}') {
+                description = Some(lines.join("\n").trim().to_string());
+                block = None;
+            } else {
+                lines.push(trimmed);
+            }
+            continue;
+        }
+        if let Some(caps) = ACC_TITLE_VALUE.captures(trimmed) {
+            title = Some(caps[1].trim().to_string());
+            continue;
+        }
+        if let Some(caps) = ACC_DESCR_VALUE.captures(trimmed) {
+            description = Some(caps[1].trim().to_string());
+            continue;
+        }
+        if let Some(caps) = ACC_DESCR_BLOCK_VALUE.captures(trimmed) {
+            let rest = caps.get(1).map_or("", |m| m.as_str());
+            match rest.find('}') {
+                Some(end) => description = Some(rest[..end].trim().to_string()),
+                None => {
+                    block = Some(if rest.trim().is_empty() {
+                        Vec::new()
+                    } else {
+                        vec![rest.trim()]
+                    })
+                }
+            }
+            continue;
+        }
+        kept.push(line);
+    }
+    Accessibility {
+        lines: kept,
+        title,
+        description,
+    }
+}
+
+/// 根据首行 header 判断图类型；无法识别时按 flowchart 处理（与 JS 一致）。
+///
+/// 先去掉无障碍指令（对齐 bundle 先 `bmAcc` 再判断），`accTitle:` 写在 header 前面也能识别。
+/// 所有入口（native 渲染、JS meta、后端选择）都走这里，不要各自预处理。
+pub(crate) fn detect_diagram_kind(text: &str) -> DiagramKind {
+    let header = strip_accessibility(text)
+        .trim()
+        .split(['\n', ';'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+
+    if SEQUENCE_HEADER.is_match(&header) {
+        DiagramKind::Sequence
+    } else if CLASS_HEADER.is_match(&header) {
+        DiagramKind::Class
+    } else if ER_HEADER.is_match(&header) {
+        DiagramKind::Er
+    } else {
+        DiagramKind::Flowchart
+    }
+}
+
+/// 去掉无障碍指令行，其余行原样保留（对齐 bundle 的 `bmAcc(text).text`）。
+///
+/// 与 `significant_lines` 不同：这里不 trim、不删空行/注释，交给各自的 parser 处理。
+pub(crate) fn strip_accessibility(text: &str) -> String {
+    parse_accessibility(text).text()
+}
+
+/// ASCII 渲染器的输入预处理：去掉无障碍指令后按行 trim，去掉空行与 `%%` 注释。
+pub(crate) fn significant_lines(text: &str) -> Vec<&str> {
+    parse_accessibility(text)
+        .lines
+        .into_iter()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("%%"))
+        .collect()
+}
+
+/// native 渲染的语法关口：与 `validate_mermaid` 用同一个 parser（selkie）。
+///
+/// validator 判为无效的输入，native 渲染器也返回 `Parse` 错误而不是照画；
+/// 反过来 `validate_mermaid` 也会跑 native parser（见 [`check_model`]），两边接受与拒绝的输入一致。
+/// 没编译 `validate` 时只剩 native parser 自己的检查。JS 后端不经过这里（与 TS 一样宽松）。
+#[cfg(feature = "native-backend")]
+#[cfg_attr(not(feature = "validate"), allow(unused_variables))]
+pub(crate) fn check_syntax(text: &str) -> Result<()> {
+    #[cfg(feature = "validate")]
+    selkie::parse(text).map_err(|err| crate::error::BeautifulMermaidError::Parse {
+        message: err.to_string(),
+    })?;
+    Ok(())
+}
+
+/// 只跑 native parser，看它是否接受这份输入（`validate_mermaid` 用；selkie 那一关由调用方负责）。
+#[cfg(all(feature = "native-backend", feature = "validate"))]
+pub(crate) fn check_model(text: &str) -> Result<()> {
+    match detect_diagram_kind(text) {
+        DiagramKind::Flowchart => flowchart::parse_graph(text).map(drop),
+        DiagramKind::Sequence | DiagramKind::Class | DiagramKind::Er => Ok(()),
+    }
+}
@@ -0,0 +1,688 @@
+// ============================================================================
+// Sequence diagram：解析 + ASCII 布局
+//
+// 对齐 JS bundle：
+// - parser: `parseSequenceDiagram`（逐行正则匹配）
+// - 布局:   `renderSequenceAscii` 里的列/行计算（actor 列、message 行、note/block 行）
+//
+// 说明：
+// - 这里只负责“坐标”，不负责画字符；坐标与 JS 输出的字符画逐格对齐。
+// - 字符绘制见 `sequence_ascii`，SVG 布局与绘制见 `sequence_svg`（共用这里的 parser）。
+// ============================================================================
+
+#[cfg(feature = "native-backend")]
+use super::check_syntax;
+use super::text::display_width;
+use super::{significant_lines, strip_accessibility};
+#[cfg(feature = "native-backend")]
+use crate::error::Result;
+use crate::types::{
+    AsciiBox, AsciiDrawingCoord, AsciiSequenceBlock, AsciiSequenceDivider, AsciiSequenceMessage,
+    AsciiSequenceMeta, AsciiSequenceNote, AsciiSequenceParticipant, Backend,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+// ----------------------------------------------------------------------------
+// AST
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub(crate) struct SequenceActor {
+    pub id: String,
+    pub label: String,
+    /// `participant` 或 `actor`。
+    pub actor_type: &'static str,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SequenceMessage {
+    pub from: String,
+    pub to: String,
+    pub label: String,
+    pub dashed: bool,
+    pub filled: bool,
+    /// `->>+`：激活目标 participant（只影响 SVG 的 activation bar）。
+    #[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+    pub activate: bool,
+    /// `->>-`：结束发送方最近一次激活。
+    #[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+    pub deactivate: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SequenceDivider {
+    pub index: usize,
+    pub label: String,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SequenceBlock {
+    pub block_type: String,
+    pub label: String,
+    pub start_index: usize,
+    pub end_index: usize,
+    pub dividers: Vec<SequenceDivider>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NotePosition {
+    Left,
+    Right,
+    Over,
+}
+
+impl NotePosition {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Over => "over",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SequenceNote {
+    pub actor_ids: Vec<String>,
+    pub text: String,
+    pub position: NotePosition,
+    /// 挂在哪条 message 之后（-1 表示出现在第一条 message 之前，JS 侧不会绘制）。
+    pub after_index: isize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SequenceDiagram {
+    pub actors: Vec<SequenceActor>,
+    pub messages: Vec<SequenceMessage>,
+    pub blocks: Vec<SequenceBlock>,
+    pub notes: Vec<SequenceNote>,
+}
+
+// ----------------------------------------------------------------------------
+// Parser
+// ----------------------------------------------------------------------------
+
+static ACTOR_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(participant|actor)\s+(\S+?)(?:\s+as\s+(.+))?$").unwrap());
+static NOTE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^Note\s+(left of|right of|over)\s+([^:]+):\s*(.+)$").unwrap());
+static BLOCK_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(loop|alt|opt|par|critical|break|rect)\s*(.*)$").unwrap());
+static DIVIDER_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(else|and)\s*(.*)$").unwrap());
+static MESSAGE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\S+?)\s*(--?>?>|--?[)x]|--?>>|--?>)\s*([+-]?)(\S+?)\s*:\s*(.+)$").unwrap()
+});
+static MESSAGE_FALLBACK_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\S+?)\s*(->>|-->>|-\)|--\)|-x|--x|->|-->)\s*([+-]?)(\S+?)\s*:\s*(.+)$").unwrap()
+});
+
+struct OpenBlock {
+    block_type: String,
+    label: String,
+    start_index: usize,
+    dividers: Vec<SequenceDivider>,
+}
+
+fn ensure_actor(diagram: &mut SequenceDiagram, id: &str) {
+    if !diagram.actors.iter().any(|actor| actor.id == id) {
+        diagram.actors.push(SequenceActor {
+            id: id.to_string(),
+            label: id.to_string(),
+            actor_type: "participant",
+        });
+    }
+}
+
+/// 切行 + 解析：ASCII 只按 `\n` 切，SVG 还把 `;` 当换行（对齐 bundle 的两个入口）。
+///
+/// ASCII / SVG 渲染与 ASCII meta 都从这里取同一份 AST。
+pub(crate) fn parse_text(text: &str, semicolons: bool) -> SequenceDiagram {
+    if !semicolons {
+        return parse(&significant_lines(text));
+    }
+    let text = strip_accessibility(text);
+    let lines: Vec<&str> = text
+        .split(['\n', ';'])
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("%%"))
+        .collect();
+    parse(&lines)
+}
+
+/// native 渲染器的入口：先过 [`check_syntax`]（与 `validate_mermaid` 同一个 parser），再建 AST。
+#[cfg(feature = "native-backend")]
+pub(crate) fn parse_checked(text: &str, semicolons: bool) -> Result<SequenceDiagram> {
+    check_syntax(text)?;
+    Ok(parse_text(text, semicolons))
+}
+
+/// 解析 sequence diagram（`lines[0]` 是 header，会被跳过）。
+fn parse(lines: &[&str]) -> SequenceDiagram {
+    let mut diagram = SequenceDiagram::default();
+    let mut open_blocks: Vec<OpenBlock> = Vec::new();
+
+    for line in lines.iter().skip(1).copied() {
+        if let Some(caps) = ACTOR_RE.captures(line) {
+            let id = &caps[2];
+            let label = caps.get(3).map_or(id, |m| m.as_str().trim());
+            if !diagram.actors.iter().any(|actor| actor.id == id) {
+                diagram.actors.push(SequenceActor {
+                    id: id.to_string(),
+                    label: label.to_string(),
+                    actor_type: if &caps[1] == "actor" {
+                        "actor"
+                    } else {
+                        "participant"
+                    },
+                });
+            }
+            continue;
+        }
+
+        if let Some(caps) = NOTE_RE.captures(line) {
+            let position = match caps[1].to_lowercase().as_str() {
+                "left of" => NotePosition::Left,
+                "right of" => NotePosition::Right,
+                _ => NotePosition::Over,
+            };
+            let actor_ids: Vec<String> = caps[2]
+                .trim()
+                .split(',')
+                .map(|id| id.trim().to_string())
+                .collect();
+            for id in &actor_ids {
+                ensure_actor(&mut diagram, id);
+            }
+            diagram.notes.push(SequenceNote {
+                actor_ids,
+                text: caps[3].trim().to_string(),
+                position,
+                after_index: diagram.messages.len() as isize - 1,
+            });
+            continue;
+        }
+
+        if let Some(caps) = BLOCK_RE.captures(line) {
+            open_blocks.push(OpenBlock {
+                block_type: caps[1].to_string(),
+                label: caps[2].trim().to_string(),
+                start_index: diagram.messages.len(),
+                dividers: Vec::new(),
+            });
+            continue;
+        }
+
+        if let Some(caps) = DIVIDER_RE.captures(line)
+            && let Some(block) = open_blocks.last_mut()
+        {
+            block.dividers.push(SequenceDivider {
+                index: diagram.messages.len(),
+                label: caps[2].trim().to_string(),
+            });
+            continue;
+        }
+
+        if line == "end"
+            && let Some(block) = open_blocks.pop()
+        {
+            let end_index = diagram
+                .messages
+                .len()
+                .saturating_sub(1)
+                .max(block.start_index);
+            diagram.blocks.push(SequenceBlock {
+                block_type: block.block_type,
+                label: block.label,
+                start_index: block.start_index,
+                end_index,
+                dividers: block.dividers,
+            });
+            continue;
+        }
+
+        let caps = MESSAGE_RE
+            .captures(line)
+            .or_else(|| MESSAGE_FALLBACK_RE.captures(line));
+        if let Some(caps) = caps {
+            let from = &caps[1];
+            let arrow = &caps[2];
+            let activation = &caps[3];
+            let to = &caps[4];
+            ensure_actor(&mut diagram, from);
+            ensure_actor(&mut diagram, to);
+            diagram.messages.push(SequenceMessage {
+                from: from.to_string(),
+                to: to.to_string(),
+                label: caps[5].trim().to_string(),
+                dashed: arrow.starts_with("--"),
+                filled: arrow.contains(">>") || arrow.contains('x'),
+                activate: activation == "+",
+                deactivate: activation == "-",
+            });
+        }
+    }
+
+    diagram
+}
+
+// ----------------------------------------------------------------------------
+// Layout
+// ----------------------------------------------------------------------------
+
+/// ASCII 布局里 header box 的高度（box 顶边 + label + 底边）。
+pub(crate) const HEADER_HEIGHT: i32 = 3;
+/// participant box 的左右内边距。
+const BOX_PADDING: i32 = 1;
+/// self message 回环的水平宽度。
+pub(crate) const SELF_LOOP_WIDTH: i32 = 4;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct NoteBox {
+    pub note_index: usize,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// sequence 的 ASCII 布局结果（全部是终端 cell 坐标）。
+#[derive(Debug, Clone)]
+pub(crate) struct SequenceLayout {
+    /// actor 下标 -> lifeline 所在列。
+    pub actor_x: Vec<i32>,
+    /// message 下标 -> label 所在行。
+    pub label_rows: Vec<i32>,
+    /// message 下标 -> 箭头所在行（self message 为回环第一行）。
+    pub arrow_rows: Vec<i32>,
+    /// block 下标 -> 顶边/底边所在行（未绘制的 block 为 None）。
+    pub block_top: Vec<Option<i32>>,
+    pub block_bottom: Vec<Option<i32>>,
+    /// block 下标 -> divider 下标 -> 分隔线所在行。
+    pub divider_rows: Vec<Vec<Option<i32>>>,
+    pub notes: Vec<NoteBox>,
+    /// footer box 的顶边所在行。
+    pub footer_y: i32,
+    /// 画布最右列（闭区间）。
+    pub max_x: i32,
+}
+
+impl SequenceLayout {
+    /// participant box 的矩形（header 与 footer 只差 y）。
+    pub(crate) fn actor_box(&self, diagram: &SequenceDiagram, index: usize, y: i32) -> AsciiBox {
+        let width = display_width(&diagram.actors[index].label) + 2 * BOX_PADDING + 2;
+        AsciiBox {
+            x: self.actor_x[index] - width / 2,
+            y,
+            width,
+            height: HEADER_HEIGHT,
+        }
+    }
+
+    /// block 的左右边界（闭区间），由 block 覆盖的 message 决定。
+    pub(crate) fn block_x_range(
+        &self,
+        diagram: &SequenceDiagram,
+        actor_index: &HashMap<&str, usize>,
+        block: &SequenceBlock,
+    ) -> (i32, i32) {
+        let mut min_x = self.max_x;
+        let mut max_x = 0;
+        for message in diagram
+            .messages
+            .iter()
+            .take(block.end_index + 1)
+            .skip(block.start_index)
+        {
+            let from = actor_index.get(message.from.as_str()).copied().unwrap_or(0);
+            let to = actor_index.get(message.to.as_str()).copied().unwrap_or(0);
+            min_x = min_x.min(self.actor_x[from.min(to)]);
+            max_x = max_x.max(self.actor_x[from.max(to)]);
+        }
+        ((min_x - 4).max(0), (max_x + 4).min(self.max_x - 1))
+    }
+}
+
+/// actor id -> actor 下标。
+pub(crate) fn actor_index(diagram: &SequenceDiagram) -> HashMap<&str, usize> {
+    diagram
+        .actors
+        .iter()
+        .enumerate()
+        .map(|(idx, actor)| (actor.id.as_str(), idx))
+        .collect()
+}
+
+/// note 文本按字面量 `\n` 拆行（与 JS 一致，不是真正的换行符）。
+pub(crate) fn note_lines(note: &SequenceNote) -> Vec<&str> {
+    note.text.split("\\n").collect()
+}
+
+/// 计算 ASCII 布局（调用方需保证 `diagram.actors` 非空）。
+pub(crate) fn layout(diagram: &SequenceDiagram) -> SequenceLayout {
+    let index = actor_index(diagram);
+    let actor_count = diagram.actors.len();
+
+    // ------------------------------------------------------------------
+    // 1) 列：每个 actor 的半宽 + message label 需要的最小间距
+    // ------------------------------------------------------------------
+    let half_widths: Vec<i32> = diagram
+        .actors
+        .iter()
+        .map(|actor| (display_width(&actor.label) + 2 * BOX_PADDING + 2 + 1) / 2)
+        .collect();
+
+    let mut min_gaps = vec![0; actor_count.saturating_sub(1)];
+    for message in &diagram.messages {
+        let from = index[message.from.as_str()];
+        let to = index[message.to.as_str()];
+        if from == to {
+            continue;
+        }
+        let (lo, hi) = (from.min(to), from.max(to));
+        let needed = display_width(&message.label) + 4;
+        let span = (hi - lo) as i32;
+        let per_gap = (needed + span - 1) / span;
+        for gap in &mut min_gaps[lo..hi] {
+            *gap = (*gap).max(per_gap);
+        }
+    }
+
+    let mut actor_x = vec![half_widths[0]];
+    for i in 1..actor_count {
+        let gap = (half_widths[i - 1] + half_widths[i] + 2)
+            .max(min_gaps[i - 1] + 2)
+            .max(10);
+        actor_x.push(actor_x[i - 1] + gap);
+    }
+
+    // ------------------------------------------------------------------
+    // 2) 行：block 顶边 / divider / message / note / block 底边依次向下排
+    // ------------------------------------------------------------------
+    let mut label_rows = vec![0; diagram.messages.len()];
+    let mut arrow_rows = vec![0; diagram.messages.len()];
+    let mut block_top = vec![None; diagram.blocks.len()];
+    let mut block_bottom = vec![None; diagram.blocks.len()];
+    let mut divider_rows: Vec<Vec<Option<i32>>> = diagram
+        .blocks
+        .iter()
+        .map(|block| vec![None; block.dividers.len()])
+        .collect();
+    let mut notes: Vec<NoteBox> = Vec::new();
+
+    let mut y = HEADER_HEIGHT;
+    for (msg_idx, message) in diagram.messages.iter().enumerate() {
+        for (block_idx, block) in diagram.blocks.iter().enumerate() {
+            if block.start_index == msg_idx {
+                y += 2;
+                block_top[block_idx] = Some(y - 1);
+            }
+        }
+        for (block_idx, block) in diagram.blocks.iter().enumerate() {
+            for (divider_idx, divider) in block.dividers.iter().enumerate() {
+                if divider.index == msg_idx {
+                    y += 1;
+                    divider_rows[block_idx][divider_idx] = Some(y);
+                    y += 1;
+                }
+            }
+        }
+
+        y += 1;
+        if message.from == message.to {
+            label_rows[msg_idx] = y + 1;
+            arrow_rows[msg_idx] = y;
+            y += 3;
+        } else {
+            label_rows[msg_idx] = y;
+            arrow_rows[msg_idx] = y + 1;
+            y += 2;
+        }
+
+        for (note_idx, note) in diagram.notes.iter().enumerate() {
+            if note.after_index != msg_idx as isize {
+                continue;
+            }
+            y += 1;
+            let lines = note_lines(note);
+            let width = lines
+                .iter()
+                .map(|line| display_width(line))
+                .max()
+                .unwrap_or(0)
+                + 4;
+            let height = lines.len() as i32 + 2;
+            let first = index.get(note.actor_ids[0].as_str()).copied().unwrap_or(0);
+            let x = match note.position {
+                NotePosition::Left => actor_x[first] - width - 1,
+                NotePosition::Right => actor_x[first] + 2,
+                NotePosition::Over if note.actor_ids.len() >= 2 => {
+                    let second = index
+                        .get(note.actor_ids[1].as_str())
+                        .copied()
+                        .unwrap_or(first);
+                    (actor_x[first] + actor_x[second]).div_euclid(2) - width / 2
+                }
+                NotePosition::Over => actor_x[first] - width / 2,
+            };
+            notes.push(NoteBox {
+                note_index: note_idx,
+                x: x.max(0),
+                y,
+                width,
+                height,
+            });
+            y += height;
+        }
+
+        for (block_idx, block) in diagram.blocks.iter().enumerate() {
+            if block.end_index == msg_idx {
+                y += 1;
+                block_bottom[block_idx] = Some(y);
+                y += 1;
+            }
+        }
+    }
+    y += 1;
+
+    let footer_y = y;
+
+    // ------------------------------------------------------------------
+    // 3) 画布宽度：最后一个 actor + self message label + note
+    // ------------------------------------------------------------------
+    let mut max_x = actor_x[actor_count - 1] + half_widths[actor_count - 1] + 2;
+    for message in &diagram.messages {
+        if message.from == message.to {
+            let x = actor_x[index[message.from.as_str()]];
+            max_x = max_x.max(x + SELF_LOOP_WIDTH + 2 + 2 + display_width(&message.label) + 1);
+        }
+    }
+    for note in &notes {
+        max_x = max_x.max(note.x + note.width + 1);
+    }
+
+    SequenceLayout {
+        actor_x,
+        label_rows,
+        arrow_rows,
+        block_top,
+        block_bottom,
+        divider_rows,
+        notes,
+        footer_y,
+        max_x,
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Meta
+// ----------------------------------------------------------------------------
+
+/// 计算 sequence diagram 的 ASCII meta（与 JS `renderMermaidAscii` 的输出逐格对齐）。
+pub(crate) fn ascii_meta(text: &str) -> AsciiSequenceMeta {
+    let diagram = parse_text(text, false);
+    if diagram.actors.is_empty() {
+        return AsciiSequenceMeta {
+            participants: Vec::new(),
+            messages: Vec::new(),
+            notes: Vec::new(),
+            blocks: Vec::new(),
+            backend: Backend::Native,
+        };
+    }
+
+    let layout = layout(&diagram);
+    let index = actor_index(&diagram);
+
+    let participants = diagram
+        .actors
+        .iter()
+        .enumerate()
+        .map(|(idx, actor)| AsciiSequenceParticipant {
+            id: actor.id.clone(),
+            label: actor.label.clone(),
+            participant_type: actor.actor_type.to_string(),
+            header_box: layout.actor_box(&diagram, idx, 0),
+            footer_box: layout.actor_box(&diagram, idx, layout.footer_y),
+            lifeline_x: layout.actor_x[idx],
+            lifeline_top: HEADER_HEIGHT,
+            lifeline_bottom: layout.footer_y - 1,
+        })
+        .collect();
+
+    let messages = diagram
+        .messages
+        .iter()
+        .enumerate()
+        .map(|(msg_idx, message)| {
+            let from_x = layout.actor_x[index[message.from.as_str()]];
+            let to_x = layout.actor_x[index[message.to.as_str()]];
+            let row = layout.arrow_rows[msg_idx];
+            let label_width = display_width(&message.label);
+
+            let (path, span_start_x, span_end_x, label_box) = if message.from == message.to {
+                // 回环：├───┐ / │ label / ◀───┘
+                let right = from_x + SELF_LOOP_WIDTH;
+                let mut path = Vec::new();
+                for x in from_x..=right {
+                    path.push(AsciiDrawingCoord { x, y: row });
+                }
+                path.push(AsciiDrawingCoord {
+                    x: right,
+                    y: row + 1,
+                });
+                for x in (from_x..=right).rev() {
+                    path.push(AsciiDrawingCoord { x, y: row + 2 });
+                }
+                let label_box = AsciiBox {
+                    x: right + 2,
+                    y: row + 1,
+                    width: label_width,
+                    height: 1,
+                };
+                (path, from_x, right, label_box)
+            } else {
+                let path: Vec<AsciiDrawingCoord> = if from_x < to_x {
+                    (from_x + 1..=to_x)
+                        .map(|x| AsciiDrawingCoord { x, y: row })
+                        .collect()
+                } else {
+                    (to_x..from_x)
+                        .rev()
+                        .map(|x| AsciiDrawingCoord { x, y: row })
+                        .collect()
+                };
+                let (start, end) = if from_x < to_x {
+                    (from_x + 1, to_x)
+                } else {
+                    (to_x, from_x - 1)
+                };
+                let label_x = ((from_x + to_x).div_euclid(2) - label_width / 2).max(0);
+                let label_box = AsciiBox {
+                    x: label_x,
+                    y: layout.label_rows[msg_idx],
+                    width: label_width,
+                    height: 1,
+                };
+                (path, start, end, label_box)
+            };
+
+            AsciiSequenceMessage {
+                index: msg_idx,
+                from: message.from.clone(),
+                to: message.to.clone(),
+                label: message.label.clone(),
+                line_style: if message.dashed { "dashed" } else { "solid" }.to_string(),
+                arrow_head: if message.filled { "filled" } else { "open" }.to_string(),
+                is_self: message.from == message.to,
+                row,
+                span_start_x,
+                span_end_x,
+                label_box,
+                path,
+            }
+        })
+        .collect();
+
+    let notes = layout
+        .notes
+        .iter()
+        .map(|note_box| {
+            let note = &diagram.notes[note_box.note_index];
+            AsciiSequenceNote {
+                actor_ids: note.actor_ids.clone(),
+                position: note.position.as_str().to_string(),
+                text: note.text.clone(),
+                box_rect: AsciiBox {
+                    x: note_box.x,
+                    y: note_box.y,
+                    width: note_box.width,
+                    height: note_box.height,
+                },
+            }
+        })
+        .collect();
+
+    let blocks = diagram
+        .blocks
+        .iter()
+        .enumerate()
+        .filter_map(|(block_idx, block)| {
+            let top = layout.block_top[block_idx]?;
+            let bottom = layout.block_bottom[block_idx]?;
+            let (left, right) = layout.block_x_range(&diagram, &index, block);
+            Some(AsciiSequenceBlock {
+                block_type: block.block_type.clone(),
+                label: block.label.clone(),
+                start_index: block.start_index,
+                end_index: block.end_index,
+                box_rect: AsciiBox {
+                    x: left,
+                    y: top,
+                    width: right - left + 1,
+                    height: bottom - top + 1,
+                },
+                dividers: block
+                    .dividers
+                    .iter()
+                    .zip(&layout.divider_rows[block_idx])
+                    .filter_map(|(divider, row)| {
+                        Some(AsciiSequenceDivider {
+                            label: divider.label.clone(),
+                            row: (*row)?,
+                        })
+                    })
+                    .collect(),
+            })
+        })
+        .collect();
+
+    AsciiSequenceMeta {
+        participants,
+        messages,
+        notes,
+        blocks,
+        backend: Backend::Native,
+    }
+}
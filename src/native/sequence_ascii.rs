@@ -0,0 +1,341 @@
+// ============================================================================
+// Sequence diagram：纯 Rust ASCII/Unicode 渲染（`Backend::Native`）
+//
+// 对齐 JS bundle 的 `renderSequenceAscii`：
+// - 坐标全部来自 `sequence::layout`（与 ASCII meta 共用同一份布局）；
+// - 这里只负责按 JS 的绘制顺序往画布上写字符：
+//   lifeline -> participant box -> message -> block -> note（后画的覆盖先画的）。
+// ============================================================================
+
+use super::canvas::Canvas;
+use super::sequence::{self, HEADER_HEIGHT, SELF_LOOP_WIDTH, SequenceDiagram, SequenceLayout};
+use super::text::{display_width, truncate_to_width};
+use crate::error::Result;
+use crate::types::AsciiRenderOptions;
+
+/// 一套线条字符（ASCII 或 Unicode box-drawing）。
+struct Glyphs {
+    horizontal: char,
+    vertical: char,
+    top_left: char,
+    top_right: char,
+    bottom_left: char,
+    bottom_right: char,
+    tee_down: char,
+    tee_up: char,
+    tee_right: char,
+    tee_left: char,
+    dashed: char,
+    /// block 分隔线（ASCII 下是 `-`，与虚线 message 的 `.` 不同）。
+    divider: char,
+    arrow_right_filled: char,
+    arrow_right_open: char,
+    arrow_left_filled: char,
+    arrow_left_open: char,
+}
+
+impl Glyphs {
+    fn new(use_ascii: bool) -> Self {
+        if use_ascii {
+            Self {
+                horizontal: '-',
+                vertical: '|',
+                top_left: '+',
+                top_right: '+',
+                bottom_left: '+',
+                bottom_right: '+',
+                tee_down: '+',
+                tee_up: '+',
+                tee_right: '+',
+                tee_left: '+',
+                dashed: '.',
+                divider: '-',
+                arrow_right_filled: '>',
+                arrow_right_open: '>',
+                arrow_left_filled: '<',
+                arrow_left_open: '<',
+            }
+        } else {
+            Self {
+                horizontal: '─',
+                vertical: '│',
+                top_left: '┌',
+                top_right: '┐',
+                bottom_left: '└',
+                bottom_right: '┘',
+                tee_down: '┬',
+                tee_up: '┴',
+                tee_right: '├',
+                tee_left: '┤',
+                dashed: '╌',
+                divider: '╌',
+                arrow_right_filled: '▶',
+                arrow_right_open: '▷',
+                arrow_left_filled: '◀',
+                arrow_left_open: '◁',
+            }
+        }
+    }
+}
+
+/// 渲染 sequence diagram 为字符画（没有 participant 时返回空串，与 JS 一致）。
+pub(crate) fn render(text: &str, options: &AsciiRenderOptions) -> Result<String> {
+    let diagram = sequence::parse_checked(text, false)?;
+    if diagram.actors.is_empty() {
+        return Ok(String::new());
+    }
+
+    let use_ascii = options.use_ascii.unwrap_or(false);
+    let glyphs = Glyphs::new(use_ascii);
+    let layout = sequence::layout(&diagram);
+    let mut canvas = Canvas::new(layout.max_x, layout.footer_y + HEADER_HEIGHT - 1);
+
+    for &x in &layout.actor_x {
+        for y in HEADER_HEIGHT..=layout.footer_y {
+            canvas.set(x, y, glyphs.vertical);
+        }
+    }
+    for (index, actor) in diagram.actors.iter().enumerate() {
+        draw_actor_box(
+            &mut canvas,
+            &glyphs,
+            &layout,
+            &diagram,
+            index,
+            0,
+            &actor.label,
+        );
+        draw_actor_box(
+            &mut canvas,
+            &glyphs,
+            &layout,
+            &diagram,
+            index,
+            layout.footer_y,
+            &actor.label,
+        );
+        if !use_ascii {
+            let x = layout.actor_x[index];
+            canvas.set(x, HEADER_HEIGHT - 1, glyphs.tee_down);
+            canvas.set(x, layout.footer_y, glyphs.tee_up);
+        }
+    }
+
+    draw_messages(&mut canvas, &glyphs, &layout, &diagram);
+    draw_blocks(&mut canvas, &glyphs, &layout, &diagram);
+    draw_notes(&mut canvas, &glyphs, &layout, &diagram);
+
+    Ok(canvas.to_text())
+}
+
+fn draw_actor_box(
+    canvas: &mut Canvas,
+    glyphs: &Glyphs,
+    layout: &SequenceLayout,
+    diagram: &SequenceDiagram,
+    index: usize,
+    y: i32,
+    label: &str,
+) {
+    let rect = layout.actor_box(diagram, index, y);
+    let right = rect.x + rect.width - 1;
+    draw_horizontal(
+        canvas,
+        glyphs,
+        rect.x,
+        right,
+        y,
+        glyphs.top_left,
+        glyphs.top_right,
+    );
+    canvas.set(rect.x, y + 1, glyphs.vertical);
+    canvas.set(right, y + 1, glyphs.vertical);
+    canvas.draw_text(rect.x + 2, y + 1, label);
+    draw_horizontal(
+        canvas,
+        glyphs,
+        rect.x,
+        right,
+        y + 2,
+        glyphs.bottom_left,
+        glyphs.bottom_right,
+    );
+}
+
+/// 横线 `left..=right`，两端用给定的角/接头字符。
+fn draw_horizontal(
+    canvas: &mut Canvas,
+    glyphs: &Glyphs,
+    left: i32,
+    right: i32,
+    y: i32,
+    start: char,
+    end: char,
+) {
+    canvas.set(left, y, start);
+    for x in left + 1..right {
+        canvas.set(x, y, glyphs.horizontal);
+    }
+    canvas.set(right, y, end);
+}
+
+fn draw_messages(
+    canvas: &mut Canvas,
+    glyphs: &Glyphs,
+    layout: &SequenceLayout,
+    diagram: &SequenceDiagram,
+) {
+    let index = sequence::actor_index(diagram);
+    for (msg_idx, message) in diagram.messages.iter().enumerate() {
+        let from_x = layout.actor_x[index[message.from.as_str()]];
+        let to_x = layout.actor_x[index[message.to.as_str()]];
+        let line = if message.dashed {
+            glyphs.dashed
+        } else {
+            glyphs.horizontal
+        };
+        let arrow_left = if message.filled {
+            glyphs.arrow_left_filled
+        } else {
+            glyphs.arrow_left_open
+        };
+
+        if message.from == message.to {
+            // ├───┐
+            // │   │ label
+            // ◀───┘
+            let row = layout.arrow_rows[msg_idx];
+            let right = from_x + SELF_LOOP_WIDTH;
+            canvas.set(from_x, row, glyphs.tee_right);
+            for x in from_x + 1..right {
+                canvas.set(x, row, line);
+                canvas.set(x, row + 2, line);
+            }
+            canvas.set(right, row, glyphs.top_right);
+            canvas.set(right, row + 1, glyphs.vertical);
+            canvas.draw_text(right + 2, row + 1, &message.label);
+            canvas.set(from_x, row + 2, arrow_left);
+            canvas.set(right, row + 2, glyphs.bottom_right);
+            continue;
+        }
+
+        let label_x = (from_x + to_x).div_euclid(2) - display_width(&message.label) / 2;
+        canvas.draw_text(label_x.max(0), layout.label_rows[msg_idx], &message.label);
+
+        let row = layout.arrow_rows[msg_idx];
+        let (left, right, head) = if from_x < to_x {
+            let head = if message.filled {
+                glyphs.arrow_right_filled
+            } else {
+                glyphs.arrow_right_open
+            };
+            (from_x, to_x, head)
+        } else {
+            (to_x, from_x, arrow_left)
+        };
+        for x in left + 1..right {
+            canvas.set(x, row, line);
+        }
+        canvas.set(to_x, row, head);
+    }
+}
+
+fn draw_blocks(
+    canvas: &mut Canvas,
+    glyphs: &Glyphs,
+    layout: &SequenceLayout,
+    diagram: &SequenceDiagram,
+) {
+    let index = sequence::actor_index(diagram);
+    for (block_idx, block) in diagram.blocks.iter().enumerate() {
+        let (Some(top), Some(bottom)) =
+            (layout.block_top[block_idx], layout.block_bottom[block_idx])
+        else {
+            continue;
+        };
+        let (left, right) = layout.block_x_range(diagram, &index, block);
+
+        draw_horizontal(
+            canvas,
+            glyphs,
+            left,
+            right,
+            top,
+            glyphs.top_left,
+            glyphs.top_right,
+        );
+        let title = if block.label.is_empty() {
+            block.block_type.clone()
+        } else {
+            format!("{} [{}]", block.block_type, block.label)
+        };
+        canvas.draw_text(left + 1, top, &truncate_to_width(&title, right - left - 1));
+        draw_horizontal(
+            canvas,
+            glyphs,
+            left,
+            right,
+            bottom,
+            glyphs.bottom_left,
+            glyphs.bottom_right,
+        );
+        for y in top + 1..bottom {
+            canvas.set(left, y, glyphs.vertical);
+            canvas.set(right, y, glyphs.vertical);
+        }
+
+        for (divider, row) in block.dividers.iter().zip(&layout.divider_rows[block_idx]) {
+            let Some(row) = *row else {
+                continue;
+            };
+            canvas.set(left, row, glyphs.tee_right);
+            for x in left + 1..right {
+                canvas.set(x, row, glyphs.divider);
+            }
+            canvas.set(right, row, glyphs.tee_left);
+            if !divider.label.is_empty() {
+                let label = format!("[{}]", divider.label);
+                canvas.draw_text(left + 1, row, &truncate_to_width(&label, right - left - 1));
+            }
+        }
+    }
+}
+
+fn draw_notes(
+    canvas: &mut Canvas,
+    glyphs: &Glyphs,
+    layout: &SequenceLayout,
+    diagram: &SequenceDiagram,
+) {
+    for note in &layout.notes {
+        let lines = sequence::note_lines(&diagram.notes[note.note_index]);
+        let right = note.x + note.width - 1;
+        let bottom = note.y + note.height - 1;
+        canvas.grow(note.x + note.width, note.y + note.height);
+
+        draw_horizontal(
+            canvas,
+            glyphs,
+            note.x,
+            right,
+            note.y,
+            glyphs.top_left,
+            glyphs.top_right,
+        );
+        for (offset, line) in lines.iter().enumerate() {
+            let y = note.y + 1 + offset as i32;
+            canvas.set(note.x, y, glyphs.vertical);
+            canvas.set(right, y, glyphs.vertical);
+            canvas.draw_text(note.x + 2, y, line);
+        }
+        draw_horizontal(
+            canvas,
+            glyphs,
+            note.x,
+            right,
+            bottom,
+            glyphs.bottom_left,
+            glyphs.bottom_right,
+        );
+    }
+}
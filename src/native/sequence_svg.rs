@@ -0,0 +1,673 @@
+// ============================================================================
+// Sequence diagram：纯 Rust SVG 渲染（`Backend::Native`）
+//
+// 对齐 JS bundle：
+// - 布局: `layoutSequenceDiagram`（固定行高/列距，不依赖 dagre）
+// - 绘制: `renderSequenceSvg`（block -> lifeline -> activation -> message -> note -> actor）
+//
+// 说明：
+// - 布局是纯算术，这里按 JS 的运算顺序逐项移植，输出与 JS 后端逐字节一致；
+// - 数值用 `js_num` 格式化（与模板字符串插值数字的结果相同，不做取整）；
+// - 布局结果直接构造成 `svg_meta` 的 `JsSequence*` 结构，meta 与 JS 后端共用同一个转换。
+// ============================================================================
+
+use super::sequence::{self, SequenceDiagram};
+use super::svg::{
+    ARROW_HEIGHT, ARROW_WIDTH, CONNECTOR_STROKE, EDGE_LABEL_FONT_SIZE, EDGE_LABEL_FONT_WEIGHT,
+    GROUP_HEADER_FONT_WEIGHT, INNER_BOX_STROKE, NODE_LABEL_FONT_SIZE, NODE_LABEL_FONT_WEIGHT,
+    OUTER_BOX_STROKE, TEXT_DY, count, edge_attrs, element_group, escape_xml, js_num, list,
+    node_attrs, style_block, svg_open_tag, text_width, with_accessibility,
+};
+use crate::error::Result;
+use crate::native;
+use crate::svg_meta::{
+    self, ElementIds, JsSequenceActivation, JsSequenceActor, JsSequenceBlock, JsSequenceLayout,
+    JsSequenceLifeline, JsSequenceMessage, JsSequenceNote, SELF_LOOP_HEIGHT, SELF_LOOP_WIDTH, rect,
+};
+use crate::types::{RenderOptions, SvgDiagramMeta, SvgRenderWithMeta, SvgSequenceDivider};
+use std::collections::HashMap;
+
+/// 布局常量（对齐 JS `SEQ`）。
+const PADDING: f64 = 30.0;
+const ACTOR_GAP: f64 = 140.0;
+const ACTOR_HEIGHT: f64 = 40.0;
+const ACTOR_PAD_X: f64 = 16.0;
+const ACTOR_MIN_WIDTH: f64 = 80.0;
+const HEADER_GAP: f64 = 20.0;
+const MESSAGE_ROW_HEIGHT: f64 = 40.0;
+const SELF_MESSAGE_HEIGHT: f64 = 30.0;
+const ACTIVATION_WIDTH: f64 = 10.0;
+const BLOCK_PAD_X: f64 = 10.0;
+const BLOCK_PAD_TOP: f64 = 40.0;
+const BLOCK_PAD_BOTTOM: f64 = 8.0;
+const BLOCK_HEADER_EXTRA: f64 = 28.0;
+const DIVIDER_EXTRA: f64 = 24.0;
+const NOTE_WIDTH: f64 = 120.0;
+const NOTE_PADDING: f64 = 8.0;
+const NOTE_GAP: f64 = 10.0;
+/// 画布最小尺寸。
+const MIN_WIDTH: f64 = 200.0;
+const MIN_HEIGHT: f64 = 100.0;
+
+/// 布局结果 + 画布尺寸。
+struct Layout {
+    width: f64,
+    height: f64,
+    inner: JsSequenceLayout,
+}
+
+/// 渲染 sequence diagram 为 SVG。
+pub(crate) fn render(text: &str, options: &RenderOptions) -> Result<String> {
+    Ok(run(text, options)?.0)
+}
+
+/// 渲染 sequence diagram 为 SVG + 像素坐标 meta。
+pub(crate) fn render_with_meta(text: &str, options: &RenderOptions) -> Result<SvgRenderWithMeta> {
+    let (svg, layout, prefix) = run(text, options)?;
+    let view_box = rect(0.0, 0.0, layout.width, layout.height);
+    let meta = svg_meta::convert_sequence(layout.inner, &mut ElementIds::new(&prefix));
+    Ok(SvgRenderWithMeta {
+        svg,
+        view_box,
+        meta: SvgDiagramMeta::Sequence(meta),
+    })
+}
+
+fn run(text: &str, options: &RenderOptions) -> Result<(String, Layout, String)> {
+    // SVG 入口与 ASCII 不同：`;` 也算换行（对齐 bundle 的 `renderMermaid`）
+    let diagram = sequence::parse_checked(text, true)?;
+    let accessibility = native::parse_accessibility(text);
+    let layout = layout(&diagram);
+
+    let prefix = escape_xml(options.id_prefix.as_deref().unwrap_or_default());
+    let title = options.title.clone().or(accessibility.title);
+    let description = options
+        .description
+        .clone()
+        .or(accessibility.description)
+        .unwrap_or_else(|| describe(&layout.inner));
+
+    let body = render_body(&layout, options, &prefix);
+    let svg = with_accessibility(&body, "sequence", title.as_deref(), &description, &prefix);
+    Ok((svg, layout, prefix))
+}
+
+// ----------------------------------------------------------------------------
+// 布局
+// ----------------------------------------------------------------------------
+
+fn layout(diagram: &SequenceDiagram) -> Layout {
+    if diagram.actors.is_empty() {
+        return Layout {
+            width: 0.0,
+            height: 0.0,
+            inner: JsSequenceLayout {
+                actors: Vec::new(),
+                lifelines: Vec::new(),
+                messages: Vec::new(),
+                activations: Vec::new(),
+                blocks: Vec::new(),
+                notes: Vec::new(),
+            },
+        };
+    }
+
+    // ------------------------------------------------------------------
+    // 1) 列：actor 宽度由 label 决定，相邻中心距至少 ACTOR_GAP
+    // ------------------------------------------------------------------
+    let widths: Vec<f64> = diagram
+        .actors
+        .iter()
+        .map(|actor| {
+            let label = text_width(&actor.label, NODE_LABEL_FONT_SIZE, NODE_LABEL_FONT_WEIGHT);
+            (label + ACTOR_PAD_X * 2.0).max(ACTOR_MIN_WIDTH)
+        })
+        .collect();
+    let mut centers = Vec::with_capacity(widths.len());
+    let mut x = PADDING + widths[0] / 2.0;
+    for index in 0..widths.len() {
+        if index > 0 {
+            x += ACTOR_GAP.max((widths[index - 1] + widths[index]) / 2.0 + 40.0);
+        }
+        centers.push(x);
+    }
+    let index = sequence::actor_index(diagram);
+    let position = |id: &str| index.get(id).copied().unwrap_or(0);
+
+    let mut actors: Vec<JsSequenceActor> = diagram
+        .actors
+        .iter()
+        .enumerate()
+        .map(|(i, actor)| JsSequenceActor {
+            id: actor.id.clone(),
+            label: actor.label.clone(),
+            actor_type: actor.actor_type.to_string(),
+            x: centers[i],
+            y: PADDING,
+            width: widths[i],
+            height: ACTOR_HEIGHT,
+        })
+        .collect();
+
+    // ------------------------------------------------------------------
+    // 2) 行：message 逐行向下；block 标题 / divider 前额外留白
+    // ------------------------------------------------------------------
+    let mut extra: HashMap<usize, f64> = HashMap::new();
+    for block in &diagram.blocks {
+        let entry = extra.entry(block.start_index).or_insert(0.0);
+        *entry = entry.max(BLOCK_HEADER_EXTRA);
+        for divider in &block.dividers {
+            let entry = extra.entry(divider.index).or_insert(0.0);
+            *entry = entry.max(DIVIDER_EXTRA);
+        }
+    }
+
+    let mut y = PADDING + ACTOR_HEIGHT + HEADER_GAP;
+    let mut messages: Vec<JsSequenceMessage> = Vec::with_capacity(diagram.messages.len());
+    // 未结束的激活：actor id -> 起点 y（栈）；保持首次激活的顺序
+    let mut open: Vec<(String, Vec<f64>)> = Vec::new();
+    let mut activations: Vec<JsSequenceActivation> = Vec::new();
+    for (msg_idx, message) in diagram.messages.iter().enumerate() {
+        let is_self = message.from == message.to;
+        if let Some(&gap) = extra.get(&msg_idx)
+            && gap > 0.0
+        {
+            y += gap;
+        }
+        messages.push(JsSequenceMessage {
+            from: message.from.clone(),
+            to: message.to.clone(),
+            label: message.label.clone(),
+            line_style: if message.dashed { "dashed" } else { "solid" }.to_string(),
+            arrow_head: if message.filled { "filled" } else { "open" }.to_string(),
+            x1: centers[position(&message.from)],
+            x2: centers[position(&message.to)],
+            y,
+            is_self,
+        });
+
+        if message.activate {
+            match open.iter_mut().find(|(id, _)| *id == message.to) {
+                Some((_, stack)) => stack.push(y),
+                None => open.push((message.to.clone(), vec![y])),
+            }
+        }
+        if message.deactivate
+            && let Some((_, stack)) = open.iter_mut().find(|(id, _)| *id == message.from)
+            && let Some(top) = stack.pop()
+        {
+            activations.push(JsSequenceActivation {
+                actor_id: message.from.clone(),
+                x: centers[position(&message.from)] - ACTIVATION_WIDTH / 2.0,
+                top_y: top,
+                bottom_y: y,
+                width: ACTIVATION_WIDTH,
+            });
+        }
+
+        y += if is_self {
+            SELF_MESSAGE_HEIGHT + MESSAGE_ROW_HEIGHT
+        } else {
+            MESSAGE_ROW_HEIGHT
+        };
+    }
+    // 没有显式结束的激活延伸到最后一条 message 下方
+    for (actor_id, stack) in &open {
+        for &top in stack {
+            activations.push(JsSequenceActivation {
+                actor_id: actor_id.clone(),
+                x: centers[position(actor_id)] - ACTIVATION_WIDTH / 2.0,
+                top_y: top,
+                bottom_y: y - MESSAGE_ROW_HEIGHT / 2.0,
+                width: ACTIVATION_WIDTH,
+            });
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // 3) block：覆盖区间内 message 涉及的 actor；divider 避开 message label
+    // ------------------------------------------------------------------
+    let mut blocks: Vec<JsSequenceBlock> = diagram
+        .blocks
+        .iter()
+        .map(|block| {
+            let top = messages.get(block.start_index).map_or(y, |m| m.y) - BLOCK_PAD_TOP;
+            let bottom = messages.get(block.end_index).map_or(y, |m| m.y) + BLOCK_PAD_BOTTOM + 12.0;
+            let mut involved: Vec<usize> = diagram
+                .messages
+                .iter()
+                .take(block.end_index + 1)
+                .skip(block.start_index)
+                .flat_map(|m| [position(&m.from), position(&m.to)])
+                .collect();
+            if involved.is_empty() {
+                involved = (0..diagram.actors.len()).collect();
+            }
+            let first = *involved.iter().min().expect("至少一个 actor");
+            let last = *involved.iter().max().expect("至少一个 actor");
+            let left = centers[first] - widths[first] / 2.0 - BLOCK_PAD_X;
+            let right = centers[last] + widths[last] / 2.0 + BLOCK_PAD_X;
+
+            let dividers = block
+                .dividers
+                .iter()
+                .map(|divider| {
+                    let message = messages.get(divider.index);
+                    let message_y = message.map_or(y, |m| m.y);
+                    let mut offset = 28.0;
+                    if let Some(message) = message
+                        && !divider.label.is_empty()
+                        && !message.label.is_empty()
+                    {
+                        let label_left = left + 8.0;
+                        let label_right = label_left
+                            + text_width(
+                                &format!("[{}]", divider.label),
+                                EDGE_LABEL_FONT_SIZE,
+                                EDGE_LABEL_FONT_WEIGHT,
+                            );
+                        let message_width = text_width(
+                            &message.label,
+                            EDGE_LABEL_FONT_SIZE,
+                            EDGE_LABEL_FONT_WEIGHT,
+                        );
+                        let message_left = if message.is_self {
+                            message.x1 + 36.0
+                        } else {
+                            (message.x1 + message.x2) / 2.0 - message_width / 2.0
+                        };
+                        let message_right = message_left + message_width;
+                        if label_right > message_left && label_left < message_right {
+                            offset = 36.0;
+                        }
+                    }
+                    SvgSequenceDivider {
+                        y: message_y - offset,
+                        label: divider.label.clone(),
+                    }
+                })
+                .collect();
+
+            JsSequenceBlock {
+                block_type: block.block_type.clone(),
+                label: block.label.clone(),
+                x: left,
+                y: top,
+                width: right - left,
+                height: bottom - top,
+                dividers,
+            }
+        })
+        .collect();
+
+    // ------------------------------------------------------------------
+    // 4) note：挂在 message 下方，位置由 left/right/over 决定
+    // ------------------------------------------------------------------
+    let mut notes: Vec<JsSequenceNote> = diagram
+        .notes
+        .iter()
+        .map(|note| {
+            let width = NOTE_WIDTH.max(
+                text_width(&note.text, EDGE_LABEL_FONT_SIZE, EDGE_LABEL_FONT_WEIGHT)
+                    + NOTE_PADDING * 2.0,
+            );
+            let height = EDGE_LABEL_FONT_SIZE + NOTE_PADDING * 2.0;
+            let anchor_y = usize::try_from(note.after_index)
+                .ok()
+                .and_then(|i| messages.get(i))
+                .map_or(PADDING + ACTOR_HEIGHT, |m| m.y);
+            let first = note.actor_ids.first().map_or(0, |id| position(id));
+            let x = match note.position {
+                sequence::NotePosition::Left => {
+                    centers[first] - widths[first] / 2.0 - width - NOTE_GAP
+                }
+                sequence::NotePosition::Right => centers[first] + widths[first] / 2.0 + NOTE_GAP,
+                sequence::NotePosition::Over if note.actor_ids.len() > 1 => {
+                    let last = note
+                        .actor_ids
+                        .last()
+                        .and_then(|id| index.get(id.as_str()).copied())
+                        .unwrap_or(first);
+                    (centers[first] + centers[last]) / 2.0 - width / 2.0
+                }
+                sequence::NotePosition::Over => centers[first] - width / 2.0,
+            };
+            JsSequenceNote {
+                text: note.text.clone(),
+                x,
+                y: anchor_y + 4.0,
+                width,
+                height,
+            }
+        })
+        .collect();
+
+    // ------------------------------------------------------------------
+    // 5) 左侧溢出（note / block 超出画布）时整体右移
+    // ------------------------------------------------------------------
+    let height = y + PADDING;
+    let mut min_x = PADDING;
+    let mut max_x: f64 = 0.0;
+    for actor in &actors {
+        min_x = min_x.min(actor.x - actor.width / 2.0);
+        max_x = max_x.max(actor.x + actor.width / 2.0);
+    }
+    for (x, width) in blocks
+        .iter()
+        .map(|b| (b.x, b.width))
+        .chain(notes.iter().map(|n| (n.x, n.width)))
+    {
+        min_x = min_x.min(x);
+        max_x = max_x.max(x + width);
+    }
+    let shift = if min_x < PADDING {
+        PADDING - min_x
+    } else {
+        0.0
+    };
+    if shift > 0.0 {
+        for actor in &mut actors {
+            actor.x += shift;
+        }
+        for message in &mut messages {
+            message.x1 += shift;
+            message.x2 += shift;
+        }
+        for activation in &mut activations {
+            activation.x += shift;
+        }
+        for block in &mut blocks {
+            block.x += shift;
+        }
+        for note in &mut notes {
+            note.x += shift;
+        }
+        for center in &mut centers {
+            *center += shift;
+        }
+    }
+
+    let lifelines = diagram
+        .actors
+        .iter()
+        .zip(&centers)
+        .map(|(actor, &x)| JsSequenceLifeline {
+            actor_id: actor.id.clone(),
+            x,
+            top_y: PADDING + ACTOR_HEIGHT,
+            bottom_y: height - PADDING,
+        })
+        .collect();
+
+    Layout {
+        width: (max_x + shift + PADDING).max(MIN_WIDTH),
+        height: height.max(MIN_HEIGHT),
+        inner: JsSequenceLayout {
+            actors,
+            lifelines,
+            messages,
+            activations,
+            blocks,
+            notes,
+        },
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 绘制
+// ----------------------------------------------------------------------------
+
+fn render_body(layout: &Layout, options: &RenderOptions, prefix: &str) -> String {
+    let inner = &layout.inner;
+    let mut parts = vec![
+        svg_open_tag(layout.width, layout.height, options),
+        style_block(options, false),
+        "<defs>".to_string(),
+        arrow_markers(prefix),
+        "</defs>".to_string(),
+    ];
+    parts.extend(inner.blocks.iter().map(block));
+    parts.extend(inner.lifelines.iter().map(lifeline));
+    parts.extend(inner.activations.iter().map(activation));
+    let mut ids = ElementIds::new(prefix);
+    for message in &inner.messages {
+        let id = ids.edge(&message.from, &message.to);
+        parts.push(element_group(
+            &edge_attrs(&id, &message.from, &message.to, "edge"),
+            &[self::message(message, prefix)],
+        ));
+    }
+    parts.extend(inner.notes.iter().map(note));
+    for actor in &inner.actors {
+        parts.push(element_group(
+            &node_attrs(&ids.node(&actor.id), &actor.id),
+            &[self::actor(actor)],
+        ));
+    }
+    parts.push("</svg>".to_string());
+    parts.join("\n")
+}
+
+/// 实心 / 空心两种箭头（都用 `auto-start-reverse`）。
+fn arrow_markers(prefix: &str) -> String {
+    let (w, h) = (js_num(ARROW_WIDTH), js_num(ARROW_HEIGHT));
+    let half = js_num(ARROW_HEIGHT / 2.0);
+    format!(
+        "  <marker id=\"{prefix}seq-arrow\" markerWidth=\"{w}\" markerHeight=\"{h}\" refX=\"{w}\" refY=\"{half}\" orient=\"auto-start-reverse\">\n    \
+         <polygon points=\"0 0, {w} {half}, 0 {h}\" fill=\"var(--_arrow)\" />\n  \
+         </marker>\n  \
+         <marker id=\"{prefix}seq-arrow-open\" markerWidth=\"{w}\" markerHeight=\"{h}\" refX=\"{w}\" refY=\"{half}\" orient=\"auto-start-reverse\">\n    \
+         <polyline points=\"0 0, {w} {half}, 0 {h}\" fill=\"none\" stroke=\"var(--_arrow)\" stroke-width=\"1\" />\n  \
+         </marker>"
+    )
+}
+
+/// participant 是圆角矩形；actor 是人形图标 + 下方 label。
+fn actor(actor: &JsSequenceActor) -> String {
+    let JsSequenceActor {
+        x,
+        y,
+        width,
+        height,
+        ..
+    } = *actor;
+    let label = escape_xml(&actor.label);
+    if actor.actor_type == "actor" {
+        let scale = height / 24.0 * 0.9;
+        let left = x - 12.0 * scale;
+        let top = y + (height - 24.0 * scale) / 2.0;
+        let stroke = js_num(OUTER_BOX_STROKE / scale);
+        let line = "var(--_line)";
+        return format!(
+            "<g transform=\"translate({},{}) scale({})\">\n  \
+             <path d=\"M21 12C21 16.9706 16.9706 21 12 21C7.02944 21 3 16.9706 3 12C3 7.02944 7.02944 3 12 3C16.9706 3 21 7.02944 21 12Z\" fill=\"none\" stroke=\"{line}\" stroke-width=\"{stroke}\" />\n  \
+             <path d=\"M15 10C15 11.6569 13.6569 13 12 13C10.3431 13 9 11.6569 9 10C9 8.34315 10.3431 7 12 7C13.6569 7 15 8.34315 15 10Z\" fill=\"none\" stroke=\"{line}\" stroke-width=\"{stroke}\" />\n  \
+             <path d=\"M5.62842 18.3563C7.08963 17.0398 9.39997 16 12 16C14.6 16 16.9104 17.0398 18.3716 18.3563\" fill=\"none\" stroke=\"{line}\" stroke-width=\"{stroke}\" />\n\
+             </g>\n\
+             <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"{NODE_LABEL_FONT_SIZE}\" font-weight=\"{NODE_LABEL_FONT_WEIGHT}\" fill=\"var(--_text)\">{label}</text>",
+            js_num(left),
+            js_num(top),
+            js_num(scale),
+            js_num(x),
+            js_num(y + height + 14.0),
+        );
+    }
+    format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"4\" ry=\"4\" fill=\"var(--_node-fill)\" stroke=\"var(--_node-stroke)\" stroke-width=\"{}\" />\n\
+         <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dy=\"{TEXT_DY}\" font-size=\"{NODE_LABEL_FONT_SIZE}\" font-weight=\"{NODE_LABEL_FONT_WEIGHT}\" fill=\"var(--_text)\">{label}</text>",
+        js_num(x - width / 2.0),
+        js_num(y),
+        js_num(width),
+        js_num(height),
+        js_num(OUTER_BOX_STROKE),
+        js_num(x),
+        js_num(y + height / 2.0),
+    )
+}
+
+fn lifeline(lifeline: &JsSequenceLifeline) -> String {
+    format!(
+        "<line x1=\"{x}\" y1=\"{}\" x2=\"{x}\" y2=\"{}\" stroke=\"var(--_line)\" stroke-width=\"0.75\" stroke-dasharray=\"6 4\" />",
+        js_num(lifeline.top_y),
+        js_num(lifeline.bottom_y),
+        x = js_num(lifeline.x),
+    )
+}
+
+fn activation(activation: &JsSequenceActivation) -> String {
+    format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"var(--_node-fill)\" stroke=\"var(--_node-stroke)\" stroke-width=\"{}\" />",
+        js_num(activation.x),
+        js_num(activation.top_y),
+        js_num(activation.width),
+        js_num(activation.bottom_y - activation.top_y),
+        js_num(INNER_BOX_STROKE),
+    )
+}
+
+fn message(message: &JsSequenceMessage, prefix: &str) -> String {
+    let dash = if message.line_style == "dashed" {
+        " stroke-dasharray=\"6 4\""
+    } else {
+        ""
+    };
+    let marker = if message.arrow_head == "filled" {
+        "seq-arrow"
+    } else {
+        "seq-arrow-open"
+    };
+    let stroke = js_num(CONNECTOR_STROKE);
+    let label = escape_xml(&message.label);
+    let (x1, x2, y) = (message.x1, message.x2, message.y);
+
+    if message.is_self {
+        let loop_x = x1 + SELF_LOOP_WIDTH;
+        let bottom = y + SELF_LOOP_HEIGHT;
+        return format!(
+            "<polyline points=\"{x1s},{ys} {lx},{ys} {lx},{bs} {x2s},{bs}\" fill=\"none\" stroke=\"var(--_line)\" stroke-width=\"{stroke}\"{dash} marker-end=\"url(#{prefix}{marker})\" />\n\
+             <text x=\"{}\" y=\"{}\" dy=\"{TEXT_DY}\" font-size=\"{EDGE_LABEL_FONT_SIZE}\" font-weight=\"{EDGE_LABEL_FONT_WEIGHT}\" fill=\"var(--_text-muted)\">{label}</text>",
+            js_num(loop_x + 6.0),
+            js_num(y + SELF_LOOP_HEIGHT / 2.0),
+            x1s = js_num(x1),
+            x2s = js_num(x2),
+            ys = js_num(y),
+            lx = js_num(loop_x),
+            bs = js_num(bottom),
+        );
+    }
+    format!(
+        "<line x1=\"{}\" y1=\"{ys}\" x2=\"{}\" y2=\"{ys}\" stroke=\"var(--_line)\" stroke-width=\"{stroke}\"{dash} marker-end=\"url(#{prefix}{marker})\" />\n\
+         <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"{EDGE_LABEL_FONT_SIZE}\" font-weight=\"{EDGE_LABEL_FONT_WEIGHT}\" fill=\"var(--_text-muted)\">{label}</text>",
+        js_num(x1),
+        js_num(x2),
+        js_num((x1 + x2) / 2.0),
+        js_num(y - 6.0),
+        ys = js_num(y),
+    )
+}
+
+/// block：外框 + 左上角标题标签 + divider（虚线 + `[label]`）。
+fn block(block: &JsSequenceBlock) -> String {
+    const HEADER_HEIGHT: f64 = 18.0;
+    let title = if block.label.is_empty() {
+        block.block_type.clone()
+    } else {
+        format!("{} [{}]", block.block_type, block.label)
+    };
+    let header_width = text_width(&title, EDGE_LABEL_FONT_SIZE, GROUP_HEADER_FONT_WEIGHT) + 16.0;
+    let (x, y) = (js_num(block.x), js_num(block.y));
+    let outer = js_num(OUTER_BOX_STROKE);
+    let mut parts = vec![
+        format!(
+            "<rect x=\"{x}\" y=\"{y}\" width=\"{}\" height=\"{}\" rx=\"0\" ry=\"0\" fill=\"none\" stroke=\"var(--_node-stroke)\" stroke-width=\"{outer}\" />",
+            js_num(block.width),
+            js_num(block.height),
+        ),
+        format!(
+            "<rect x=\"{x}\" y=\"{y}\" width=\"{}\" height=\"{}\" fill=\"var(--_group-hdr)\" stroke=\"var(--_node-stroke)\" stroke-width=\"{outer}\" />",
+            js_num(header_width),
+            js_num(HEADER_HEIGHT),
+        ),
+        format!(
+            "<text x=\"{}\" y=\"{}\" dy=\"{TEXT_DY}\" font-size=\"{EDGE_LABEL_FONT_SIZE}\" font-weight=\"{GROUP_HEADER_FONT_WEIGHT}\" fill=\"var(--_text-sec)\">{}</text>",
+            js_num(block.x + 6.0),
+            js_num(block.y + HEADER_HEIGHT / 2.0),
+            escape_xml(&title),
+        ),
+    ];
+    for divider in &block.dividers {
+        parts.push(format!(
+            "<line x1=\"{x}\" y1=\"{dy}\" x2=\"{}\" y2=\"{dy}\" stroke=\"var(--_line)\" stroke-width=\"0.75\" stroke-dasharray=\"6 4\" />",
+            js_num(block.x + block.width),
+            dy = js_num(divider.y),
+        ));
+        if !divider.label.is_empty() {
+            parts.push(format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"{EDGE_LABEL_FONT_SIZE}\" font-weight=\"{EDGE_LABEL_FONT_WEIGHT}\" fill=\"var(--_text-muted)\">[{}]</text>",
+                js_num(block.x + 8.0),
+                js_num(divider.y + 14.0),
+                escape_xml(&divider.label),
+            ));
+        }
+    }
+    parts.join("\n")
+}
+
+/// note：矩形 + 右上角折角 + 居中文字。
+fn note(note: &JsSequenceNote) -> String {
+    let right = note.x + note.width;
+    format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"var(--_group-hdr)\" stroke=\"var(--_node-stroke)\" stroke-width=\"{}\" />\n\
+         <polygon points=\"{},{} {},{} {},{}\" fill=\"var(--_inner-stroke)\" />\n\
+         <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dy=\"{TEXT_DY}\" font-size=\"{EDGE_LABEL_FONT_SIZE}\" font-weight=\"{EDGE_LABEL_FONT_WEIGHT}\" fill=\"var(--_text-muted)\">{}</text>",
+        js_num(note.x),
+        js_num(note.y),
+        js_num(note.width),
+        js_num(note.height),
+        js_num(INNER_BOX_STROKE),
+        js_num(right - 6.0),
+        js_num(note.y),
+        js_num(right),
+        js_num(note.y + 6.0),
+        js_num(right - 6.0),
+        js_num(note.y + 6.0),
+        js_num(note.x + note.width / 2.0),
+        js_num(note.y + note.height / 2.0),
+        escape_xml(&note.text),
+    )
+}
+
+/// 自动生成的 `<desc>`（对齐 JS `bmDescribe` 的 sequence 分支）。
+fn describe(layout: &JsSequenceLayout) -> String {
+    let label_of = |id: &str| -> String {
+        layout
+            .actors
+            .iter()
+            .find(|actor| actor.id == id)
+            .map_or_else(|| id.to_string(), |actor| actor.label.clone())
+    };
+    let participants: Vec<String> = layout.actors.iter().map(|a| a.label.clone()).collect();
+    let messages: Vec<String> = layout
+        .messages
+        .iter()
+        .map(|message| {
+            let label = if message.label.is_empty() {
+                String::new()
+            } else {
+                format!(": {}", message.label)
+            };
+            format!(
+                "{} → {}{label}",
+                label_of(&message.from),
+                label_of(&message.to)
+            )
+        })
+        .collect();
+    format!(
+        "Sequence diagram with {} and {}.{}{}",
+        count(layout.actors.len(), "participant", "participants"),
+        count(layout.messages.len(), "message", "messages"),
+        list(&participants, "Participants"),
+        list(&messages, "Messages")
+    )
+}
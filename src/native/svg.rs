@@ -0,0 +1,297 @@
+// ============================================================================
+// SVG 公共部分（各图类型的 native SVG 渲染共用）
+//
+// 对齐 JS bundle 里与图类型无关的工具函数：
+// - 根节点 / `<style>`：`svgOpenTag` / `buildStyleBlock`
+// - 元素分组与 id：`bmG` / `bmNodeAttrs` / `bmEdgeAttrs`（id 转义见 `svg_meta::ElementIds`）
+// - 无障碍：`bmA11y`
+// - 文本宽度估算、XML 转义、数值格式化
+// ============================================================================
+
+use crate::types::RenderOptions;
+
+/// 字号 / 字重（对齐 JS `FONT_SIZES` / `FONT_WEIGHTS`）。
+pub(crate) const NODE_LABEL_FONT_SIZE: f64 = 13.0;
+pub(crate) const EDGE_LABEL_FONT_SIZE: f64 = 11.0;
+pub(crate) const GROUP_HEADER_FONT_SIZE: f64 = 12.0;
+pub(crate) const NODE_LABEL_FONT_WEIGHT: u32 = 500;
+pub(crate) const EDGE_LABEL_FONT_WEIGHT: u32 = 400;
+pub(crate) const GROUP_HEADER_FONT_WEIGHT: u32 = 600;
+/// 文字垂直居中的偏移（对齐 JS `TEXT_BASELINE_SHIFT`）。
+pub(crate) const TEXT_DY: &str = "0.35em";
+
+/// 描边宽度（对齐 JS `STROKE_WIDTHS`）。
+pub(crate) const OUTER_BOX_STROKE: f64 = 1.0;
+pub(crate) const INNER_BOX_STROKE: f64 = 0.75;
+pub(crate) const CONNECTOR_STROKE: f64 = 0.75;
+
+/// 箭头 marker 尺寸（对齐 JS `ARROW_HEAD`）。
+pub(crate) const ARROW_WIDTH: f64 = 8.0;
+pub(crate) const ARROW_HEIGHT: f64 = 4.8;
+
+/// 默认配色 / 字体（对齐 JS `DEFAULTS`）。
+const DEFAULT_BG: &str = "#FFFFFF";
+const DEFAULT_FG: &str = "#27272A";
+const DEFAULT_FONT: &str = "Inter";
+
+/// `<svg ...>` 根节点：尺寸 + 配色变量（对齐 JS `svgOpenTag`）。
+pub(crate) fn svg_open_tag(width: f64, height: f64, options: &RenderOptions) -> String {
+    let mut vars = vec![
+        format!("--bg:{}", options.bg.as_deref().unwrap_or(DEFAULT_BG)),
+        format!("--fg:{}", options.fg.as_deref().unwrap_or(DEFAULT_FG)),
+    ];
+    let optional = [
+        ("line", &options.line),
+        ("accent", &options.accent),
+        ("muted", &options.muted),
+        ("surface", &options.surface),
+        ("border", &options.border),
+    ];
+    for (name, value) in optional {
+        if let Some(value) = value.as_deref().filter(|value| !value.is_empty()) {
+            vars.push(format!("--{name}:{value}"));
+        }
+    }
+    let background = if options.transparent.unwrap_or(false) {
+        ""
+    } else {
+        ";background:var(--bg)"
+    };
+    let (width, height) = (js_num(width), js_num(height));
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {width} {height}\" width=\"{width}\" height=\"{height}\" style=\"{}{background}\">",
+        vars.join(";")
+    )
+}
+
+/// 字体 `@import` + 由 `--bg/--fg` 派生的内部颜色变量（对齐 JS `buildStyleBlock`）。
+///
+/// `mono` 为 true 时额外引入等宽字体（class / ER 的成员行用）。
+pub(crate) fn style_block(options: &RenderOptions, mono: bool) -> String {
+    let font = options.font.as_deref().unwrap_or(DEFAULT_FONT);
+    let derived = [
+        ("--_text", "var(--fg)".to_string()),
+        ("--_text-sec", mix_with_override("--muted", 60)),
+        ("--_text-muted", mix_with_override("--muted", 40)),
+        ("--_text-faint", mix(25)),
+        ("--_line", mix_with_override("--line", 30)),
+        ("--_arrow", mix_with_override("--accent", 50)),
+        ("--_node-fill", mix_with_override("--surface", 3)),
+        ("--_node-stroke", mix_with_override("--border", 20)),
+        ("--_group-fill", "var(--bg)".to_string()),
+        ("--_group-hdr", mix(5)),
+        ("--_inner-stroke", mix(12)),
+        ("--_key-badge", mix(10)),
+    ];
+    let mut imports = vec![format!(
+        "@import url('https://fonts.googleapis.com/css2?family={}:wght@400;500;600;700&amp;display=swap');",
+        encode_uri_component(font)
+    )];
+    if mono {
+        imports.push(
+            "@import url('https://fonts.googleapis.com/css2?family=JetBrains+Mono:wght@400;500&amp;display=swap');"
+                .to_string(),
+        );
+    }
+    let mut lines = vec![
+        "<style>".to_string(),
+        format!("  {}", imports.join("\n  ")),
+        format!("  text {{ font-family: '{font}', system-ui, sans-serif; }}"),
+    ];
+    if mono {
+        lines.push(
+            "  .mono { font-family: 'JetBrains Mono', 'SF Mono', 'Fira Code', ui-monospace, monospace; }"
+                .to_string(),
+        );
+    }
+    lines.push("  svg {".to_string());
+    lines.push(
+        "    /* Derived from --bg and --fg (overridable via --line, --accent, etc.) */".to_string(),
+    );
+    lines.extend(
+        derived
+            .iter()
+            .map(|(name, value)| format!("    {:<18}{value};", format!("{name}:"))),
+    );
+    lines.push("  }".to_string());
+    lines.push("</style>".to_string());
+    lines.join("\n")
+}
+
+fn mix(percent: u32) -> String {
+    format!("color-mix(in srgb, var(--fg) {percent}%, var(--bg))")
+}
+
+fn mix_with_override(variable: &str, percent: u32) -> String {
+    format!("var({variable}, {})", mix(percent))
+}
+
+/// JS `encodeURIComponent`：保留 `A-Za-z0-9-_.!~*'()`，其余按 UTF-8 百分号编码。
+fn encode_uri_component(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'_'
+            | b'.'
+            | b'!'
+            | b'~'
+            | b'*'
+            | b'\''
+            | b'('
+            | b')' => out.push(byte as char),
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+// ----------------------------------------------------------------------------
+// 元素分组
+// ----------------------------------------------------------------------------
+
+/// 节点 `<g>` 的属性（对齐 JS `bmNodeAttrs`）；`element_id` 由 `ElementIds::node` 生成。
+pub(crate) fn node_attrs(element_id: &str, node_id: &str) -> String {
+    format!(
+        "id=\"{element_id}\" class=\"node\" data-node-id=\"{}\"",
+        escape_xml(node_id)
+    )
+}
+
+/// 边 `<g>` 的属性（对齐 JS `bmEdgeAttrs`）。
+pub(crate) fn edge_attrs(id: &str, from: &str, to: &str, class: &str) -> String {
+    format!(
+        "id=\"{id}\" class=\"{class}\" data-edge-from=\"{}\" data-edge-to=\"{}\"",
+        escape_xml(from),
+        escape_xml(to)
+    )
+}
+
+/// `<g {attrs}>...</g>`；子元素全为空时整组省略（对齐 JS `bmG`）。
+pub(crate) fn element_group(attrs: &str, children: &[String]) -> String {
+    let inner: Vec<&str> = children
+        .iter()
+        .map(String::as_str)
+        .filter(|child| !child.is_empty())
+        .collect();
+    if inner.is_empty() {
+        return String::new();
+    }
+    format!("<g {attrs}>\n{}\n</g>", inner.join("\n"))
+}
+
+// ----------------------------------------------------------------------------
+// 无障碍
+// ----------------------------------------------------------------------------
+
+/// 根元素加 `role/aria-*`，并在 `<style>` 前插入 `<title>/<desc>`（对齐 JS `bmA11y`）。
+///
+/// `kind` 是 `aria-roledescription` 里的图类型名（`flowchart` / `state` / `sequence` ...）。
+pub(crate) fn with_accessibility(
+    body: &str,
+    kind: &str,
+    title: Option<&str>,
+    description: &str,
+    prefix: &str,
+) -> String {
+    let labelled_by = if title.is_some() { "title" } else { "desc" };
+    let attrs = [
+        "role=\"img\"".to_string(),
+        format!("aria-roledescription=\"{kind} diagram\""),
+        format!("aria-labelledby=\"{prefix}{labelled_by}\""),
+        format!("aria-describedby=\"{prefix}desc\""),
+    ];
+    let mut elements = Vec::new();
+    if let Some(title) = title {
+        elements.push(format!(
+            "<title id=\"{prefix}title\">{}</title>",
+            escape_xml(title)
+        ));
+    }
+    elements.push(format!(
+        "<desc id=\"{prefix}desc\">{}</desc>",
+        escape_xml(description)
+    ));
+
+    let open_end = body.find('>').map_or(body.len(), |index| index + 1);
+    format!(
+        "<svg {} {}\n{}{}",
+        attrs.join(" "),
+        &body[5..open_end],
+        elements.join("\n"),
+        &body[open_end..]
+    )
+}
+
+/// 自动描述里的计数：`1 node` / `2 nodes`（对齐 `bmDescribe` 的 `count`）。
+pub(crate) fn count(n: usize, singular: &str, plural: &str) -> String {
+    format!("{n} {}", if n == 1 { singular } else { plural })
+}
+
+/// 自动描述里的列表：` Nodes: A; B.`，空列表不输出（对齐 `bmDescribe` 的 `list`）。
+pub(crate) fn list(items: &[String], heading: &str) -> String {
+    if items.is_empty() {
+        String::new()
+    } else {
+        format!(" {heading}: {}.", items.join("; "))
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 工具
+// ----------------------------------------------------------------------------
+
+/// 文本宽度估算（对齐 JS `estimateTextWidth`：按 UTF-16 长度 × 字号 × 字重系数）。
+pub(crate) fn text_width(text: &str, font_size: f64, font_weight: u32) -> f64 {
+    let ratio = if font_weight >= 600 {
+        0.58
+    } else if font_weight >= 500 {
+        0.55
+    } else {
+        0.52
+    };
+    text.encode_utf16().count() as f64 * font_size * ratio
+}
+
+/// XML 属性/文本转义（对齐 JS `escapeXml`）。
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// 坐标格式化：保留三位小数，去掉多余的 0（`12.000` -> `12`），避免输出 `-0`。
+pub(crate) fn num(value: f64) -> String {
+    js_num((value * 1000.0).round() / 1000.0)
+}
+
+/// 与 JS `String(number)` 相同的格式（模板字符串里直接插值数字时的输出）。
+///
+/// Rust 的 `Display` 同样输出最短往返表示，差别只在：`-0` 要写成 `0`，
+/// 以及极小/极大值 JS 用指数形式（`5e-7`、`1e+21`）。
+pub(crate) fn js_num(value: f64) -> String {
+    let value = value + 0.0;
+    let magnitude = value.abs();
+    if value != 0.0 && !(1e-6..1e21).contains(&magnitude) {
+        let formatted = format!("{value:e}");
+        return match formatted.split_once('e') {
+            Some((mantissa, exponent)) if !exponent.starts_with('-') => {
+                format!("{mantissa}e+{exponent}")
+            }
+            _ => formatted,
+        };
+    }
+    format!("{value}")
+}
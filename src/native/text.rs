@@ -0,0 +1,59 @@
+// ============================================================================
+// 终端显示宽度（简化版 wcwidth）
+//
+// 说明：
+// - 与 JS bundle 里的 `charDisplayWidth/displayWidth` 完全一致；
+// - 布局计算必须用“终端 cell 宽度”，否则中文/emoji 会把 box 撑歪。
+// ============================================================================
+
+fn is_combining_mark(code_point: u32) -> bool {
+    matches!(
+        code_point,
+        0x0300..=0x036F | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x20D0..=0x20FF | 0xFE20..=0xFE2F
+    )
+}
+
+fn is_wide_code_point(code_point: u32) -> bool {
+    (0x1100..=0x115F).contains(&code_point)
+        || (0x2E80..=0xA4CF).contains(&code_point)
+        || (0xAC00..=0xD7A3).contains(&code_point)
+        || (0xF900..=0xFAFF).contains(&code_point)
+        || (0xFE10..=0xFE19).contains(&code_point)
+        || (0xFE30..=0xFE6F).contains(&code_point)
+        || (0xFF00..=0xFF60).contains(&code_point)
+        || (0xFFE0..=0xFFE6).contains(&code_point)
+        || (0x1F300..=0x1FAFF).contains(&code_point)
+        || (0x1F900..=0x1F9FF).contains(&code_point)
+}
+
+/// 单个字符的终端显示宽度（0/1/2）。
+pub(crate) fn char_width(ch: char) -> i32 {
+    let code_point = ch as u32;
+    if code_point < 32 || (0x7F..0xA0).contains(&code_point) || is_combining_mark(code_point) {
+        return 0;
+    }
+    if is_wide_code_point(code_point) { 2 } else { 1 }
+}
+
+/// 字符串的终端显示宽度。
+pub(crate) fn display_width(text: &str) -> i32 {
+    text.chars().map(char_width).sum()
+}
+
+/// 按终端显示宽度截断（不超过 `max_width`；对齐 JS `truncateToWidth`）。
+pub(crate) fn truncate_to_width(text: &str, max_width: i32) -> String {
+    if max_width <= 0 {
+        return String::new();
+    }
+    let mut width = 0;
+    let mut out = String::new();
+    for ch in text.chars() {
+        let ch_width = char_width(ch);
+        if width + ch_width > max_width {
+            break;
+        }
+        out.push(ch);
+        width += ch_width;
+    }
+    out
+}
@@ -14,22 +14,31 @@
 //   - `globalThis.__bm_getPathRelaxed(...)`
 //
 // 设计原则：
-// - 输出必须与原 JS 实现一致（Rust 仓库已有 golden tests 覆盖）
+// - 输出必须与原 JS 实现一致（Rust 仓库已有 golden tests 覆盖）：
+//   最小堆的严格 `<` 比较、邻居顺序（右/左/下/上）、启发式的拐弯 +1 都照搬 bundle，
+//   `turn_penalty = 0` 且关掉封闭探测时与纯 JS A* 逐格一致
+//   （`tests/ts_compatible_pathfinder.rs` 对照，`AsciiRenderOptions.ts_compatible` 固定这组参数）
+// - A* 之前先从终点做一次有上限的反向泛洪（封闭探测）：终点被围死时直接返回 `None`，
+//   不必让正向搜索扫完整个窗口；探测不改变任何路径
 // - 避免 unsafe：TypedArray 通过 `AsRef<[T]>` 只读访问即可
 // - 复用大数组：用 stamp 技巧避免每次 search 清空整张 cost 表
 // ============================================================================
 
+use crate::types::SearchStats;
+
 /// heap（最小堆）节点：保存 idx/priority/cost 三个字段。
 ///
 /// 说明：
 /// - 这里刻意不用 `BinaryHeap<Reverse<_>>`：
 ///   - 需要包装类型与比较，开销更高
 ///   - 不利于完全复刻 JS 的“严格 < 比较” tie-break 行为
-#[derive(Default)]
+#[derive(Default, Clone)]
 struct MinHeap {
     idxs: Vec<u32>,
     priorities: Vec<u32>,
     costs: Vec<u32>,
+    /// `clear` 以来的最大长度（`SearchStats.heap_peak`）。
+    peak: usize,
 }
 
 impl MinHeap {
@@ -37,12 +46,14 @@ impl MinHeap {
         self.idxs.clear();
         self.priorities.clear();
         self.costs.clear();
+        self.peak = 0;
     }
 
     fn push(&mut self, idx: u32, priority: u32, cost: u32) {
         self.idxs.push(idx);
         self.priorities.push(priority);
         self.costs.push(cost);
+        self.peak = self.peak.max(self.idxs.len());
         self.bubble_up(self.idxs.len() - 1);
     }
 
@@ -110,10 +121,10 @@ impl MinHeap {
 }
 
 // `UsedPointSet` 里使用的方向 bit（与 TS 侧保持一致）
-const CONNECT_LEFT: u8 = 1 << 0;
-const CONNECT_RIGHT: u8 = 1 << 1;
-const CONNECT_UP: u8 = 1 << 2;
-const CONNECT_DOWN: u8 = 1 << 3;
+pub const CONNECT_LEFT: u8 = 1 << 0;
+pub const CONNECT_RIGHT: u8 = 1 << 1;
+pub const CONNECT_UP: u8 = 1 << 2;
+pub const CONNECT_DOWN: u8 = 1 << 3;
 const H_MASK: u8 = CONNECT_LEFT | CONNECT_RIGHT;
 const V_MASK: u8 = CONNECT_UP | CONNECT_DOWN;
 /// 4-bit bitcount 查表(0..15)：
@@ -121,27 +132,180 @@ const V_MASK: u8 = CONNECT_UP | CONNECT_DOWN;
 /// - 热循环里避免 popcount/循环,用查表更快也更稳定
 const BITCOUNT_4: [u8; 16] = [0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4];
 const RELAXED_PENALTY_CROSSING: u32 = 1;
+// 方向感知搜索（turn penalty）的状态编码：state = idx * TURN_STATES + dir
+const TURN_DIR_DOWN: usize = 2;
+const TURN_DIR_NONE: usize = 4;
+const TURN_STATES: usize = 5;
+/// 每个方向在（起点, 终点）上占用的 bit，下标同 `TURN_DIR_*`（右/左/下/上）。
+const TURN_DIR_BITS: [(u8, u8); 4] = [
+    (CONNECT_RIGHT, CONNECT_LEFT),
+    (CONNECT_LEFT, CONNECT_RIGHT),
+    (CONNECT_DOWN, CONNECT_UP),
+    (CONNECT_UP, CONNECT_DOWN),
+];
+/// 方向感知搜索最多展开的状态数：超过就放弃（返回 `None`，`SearchStats.budget_exhausted`）。
+///
+/// 走得通的搜索实测最多展开几千个状态（testdata 约 1000，随机小图约 5000）；
+/// 走不通又没被封闭探测拦下时，五倍的状态数会把整个窗口（最大几十万个状态）扫一遍，
+/// 候选端口 × tier 的重试再把它放大几百倍。逐格搜索（`turn_penalty = 0`）与 TS 对齐，不设预算。
+const TURN_SEARCH_BUDGET: u64 = 8_192;
+/// 封闭探测最多泛洪的格子数：超过就当作“不是小封闭区”，交给 A* 判定。
+/// 走得通的搜索最多白看这么多格子；被围死的终点所在区域通常只有几个到几十个格子。
+const ENCLOSURE_PROBE_BUDGET: usize = 64;
 // 点重叠规则(relaxed hard rule,与 TS 保持一致):
 // - relaxed 允许 crossing(交错),并对“会形成 `┼` 的潜在交叉点”加轻量惩罚；
 // - 但对 point overlap(走进已占用点)采取 hard forbid:
 //   - 除“起点第一步”与“终点前一步”的受控豁免外,禁止走进任何已占用点位；
 //   - 这样能避免在字符画里合成 `┬/┴/├/┤` 这类强歧义 junction,也能显著缩小 A* 搜索空间。
 
+/// 单步被拒绝的原因（计入 `SearchStats.rejections`）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Rejection {
+    Blocked,
+    Crossing,
+    Segment,
+}
+
+/// 调试探测记录（`RoutingGrid::start_probe`）。
+///
+/// `expanded` 只有 A* 从堆里取出的格子；A* 之前的封闭探测看过的格子不在这里
+/// （只计数到 `SearchStats.probe_cells`），但它拒绝的单步与 A* 的一样记进 `rejected`。
+#[derive(Debug, Default, Clone)]
+pub(crate) struct ProbeEvents {
+    /// 展开的格子（按 pop 顺序，可能重复）。
+    pub(crate) expanded: Vec<u32>,
+    /// 被拒绝的单步（from, to, 原因）。
+    pub(crate) rejected: Vec<(u32, u32, Rejection)>,
+    /// 正在判定的单步：`expand` 记 from，`enterable` 记 to（每一步都先过 `enterable`）。
+    step: (u32, u32),
+}
+
 /// Rust 侧复用的 A* 缓存（对应 TS 的 AStarContext，但不持有 blocked/usage 输入）。
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct NativeAStar {
     stamp: u32,
     cost_stamp: Vec<u32>,
     cost_so_far: Vec<u32>,
     came_from: Vec<i32>,
     heap: MinHeap,
+    /// 每次转弯的额外代价；0（默认）= 与 TS 完全一致的逐格搜索。
+    turn_penalty: u32,
+    /// 关掉 A* 之前的封闭探测（`ts_compatible`：搜索过程与 TS 逐格相同）。
+    skip_enclosure_probe: bool,
+    /// 封闭探测：到过的格子（与 A* 共用 stamp 计数）与待扩展的格子。
+    back_cost_stamp: Vec<u32>,
+    back_stack: Vec<u32>,
+    /// A* pop 出的格子（按 pop 顺序，封闭探测不记；`None` = 不记录）。
+    trace: Option<Vec<u32>>,
+    /// 调试探测：展开的格子与被拒绝的单步（`None` = 不记录）。
+    probe: Option<ProbeEvents>,
+    /// 最近一次 `get_path*` 的统计（`heap_peak` 在 `last_stats` 里从 `heap` 取）。
+    stats: SearchStats,
 }
 
 impl NativeAStar {
+    #[cfg_attr(not(feature = "js-backend"), allow(dead_code))]
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// 设置转弯惩罚（之后所有 `get_path*` 都生效）。
+    ///
+    /// - 0：状态只有格子本身，输出与 TS 逐字节一致；
+    /// - >0：状态变成（格子, 进入方向），每次转弯加 `penalty`，同样长度下拐点更少的路径胜出。
+    pub fn set_turn_penalty(&mut self, penalty: u32) {
+        self.turn_penalty = penalty;
+    }
+
+    /// 开关 A* 之前的封闭探测（默认开）。
+    ///
+    /// 探测只会让走不通的搜索提前返回 `None`，路径与代价不变；关掉后展开过程与 TS 逐格一致。
+    pub fn set_enclosure_probe(&mut self, enabled: bool) {
+        self.skip_enclosure_probe = !enabled;
+    }
+
+    /// 开始/停止记录搜索读过的格子（只记 A* pop 出的格子，邻居由调用方补）。
+    pub(crate) fn set_trace(&mut self, trace: Option<Vec<u32>>) {
+        self.trace = trace;
+    }
+
+    /// 取走记录并停止记录。
+    pub(crate) fn take_trace(&mut self) -> Option<Vec<u32>> {
+        self.trace.take()
+    }
+
+    /// 当前记录长度（配合 `truncate_trace` 丢掉某次搜索的记录）。
+    pub(crate) fn trace_len(&self) -> usize {
+        self.trace.as_ref().map_or(0, Vec::len)
+    }
+
+    pub(crate) fn truncate_trace(&mut self, len: usize) {
+        if let Some(trace) = &mut self.trace {
+            trace.truncate(len);
+        }
+    }
+
+    /// 开始/停止调试探测（记录展开的格子与被拒绝的单步，见 `take_probe_events`）。
+    pub(crate) fn set_probe(&mut self, enabled: bool) {
+        self.probe = enabled.then(ProbeEvents::default);
+    }
+
+    /// 取走已记录的探测事件（继续记录）。
+    pub(crate) fn take_probe_events(&mut self) -> ProbeEvents {
+        self.probe.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// 最近一次 `get_path*` 的统计，A* 之前的封闭探测也算在内：
+    /// 它看过的格子记在 `probe_cells`（不算 `expansions`），拒绝的单步计入 `rejections`。
+    pub fn last_stats(&self) -> SearchStats {
+        SearchStats {
+            heap_peak: self.heap.peak as u64,
+            ..self.stats
+        }
+    }
+
+    /// A* 展开一个格子：计数，需要时记进 trace 与调试探测。
+    fn expand(&mut self, cell: usize) {
+        self.stats.expansions += 1;
+        if let Some(trace) = &mut self.trace {
+            trace.push(cell as u32);
+        }
+        if let Some(probe) = &mut self.probe {
+            probe.expanded.push(cell as u32);
+            probe.step.0 = cell as u32;
+        }
+    }
+
+    fn reject(&mut self, rejection: Rejection) {
+        let rejections = &mut self.stats.rejections;
+        match rejection {
+            Rejection::Blocked => rejections.blocked += 1,
+            Rejection::Crossing => rejections.crossing += 1,
+            Rejection::Segment => rejections.segment += 1,
+        }
+        if let Some(probe) = &mut self.probe {
+            probe.rejected.push((probe.step.0, probe.step.1, rejection));
+        }
+    }
+
+    /// 这一步是否允许；不允许时按规则计数（写在 `if` 条件里用）。
+    fn allow(&mut self, step: Result<(), Rejection>) -> bool {
+        step.inspect_err(|&rejection| self.reject(rejection))
+            .is_ok()
+    }
+
+    /// 能否走进 `next`：blocked 格子只有终点可以进。
+    fn enterable(&mut self, blocked: &[u8], next: usize, to: usize) -> bool {
+        if let Some(probe) = &mut self.probe {
+            probe.step.1 = next as u32;
+        }
+        self.allow(if blocked[next] == 0 || next == to {
+            Ok(())
+        } else {
+            Err(Rejection::Blocked)
+        })
+    }
+
     /// 保证内部缓冲区容量足够容纳 `stride * height` 的网格。
     fn ensure_capacity(&mut self, stride: usize, height: usize) {
         let needed = stride.saturating_mul(height);
@@ -163,6 +327,7 @@ impl NativeAStar {
         if self.stamp == 0 {
             // 0 作为“未使用”保留；溢出后统一清零避免碰撞
             self.cost_stamp.fill(0);
+            self.back_cost_stamp.fill(0);
             self.stamp = 1;
         }
         self.stamp
@@ -179,6 +344,7 @@ impl NativeAStar {
         max_y: u32,
         blocked: &[u8],
     ) -> Result<Option<Vec<u32>>, String> {
+        self.stats = SearchStats::default();
         self.ensure_capacity(stride, height);
         let cell_count = stride.saturating_mul(height);
 
@@ -204,6 +370,24 @@ impl NativeAStar {
             ));
         }
 
+        if self.target_enclosed(
+            stride,
+            height,
+            from,
+            to,
+            max_x,
+            max_y,
+            blocked,
+            |_, _, _| Ok(0),
+        ) {
+            return Ok(None);
+        }
+        if self.turn_penalty > 0 {
+            let path =
+                self.search_with_turns(stride, from, to, max_x, max_y, blocked, |_, _, _| Ok(0));
+            return Ok(path.map(|(path, _)| path));
+        }
+
         let stamp = self.next_stamp();
         self.heap.clear();
 
@@ -225,8 +409,10 @@ impl NativeAStar {
             if current_cost_at_push != self.cost_so_far[current] {
                 continue;
             }
+            self.expand(current);
 
             if current == to {
+                self.stats.cost = Some(self.cost_so_far[current]);
                 return Ok(Some(self.reconstruct_path(current_idx_u32)));
             }
 
@@ -237,7 +423,7 @@ impl NativeAStar {
             // 右
             if current_x < max_x as usize {
                 let next = current + 1;
-                if blocked[next] == 0 || next == to {
+                if self.enterable(blocked, next, to) {
                     let new_cost = current_cost + 1;
                     if self.cost_stamp[next] != stamp || new_cost < self.cost_so_far[next] {
                         self.cost_stamp[next] = stamp;
@@ -266,7 +452,7 @@ impl NativeAStar {
             // 左
             if current_x > 0 {
                 let next = current - 1;
-                if blocked[next] == 0 || next == to {
+                if self.enterable(blocked, next, to) {
                     let new_cost = current_cost + 1;
                     if self.cost_stamp[next] != stamp || new_cost < self.cost_so_far[next] {
                         self.cost_stamp[next] = stamp;
@@ -295,7 +481,7 @@ impl NativeAStar {
             // 下
             if current_y < max_y as usize {
                 let next = current + stride;
-                if blocked[next] == 0 || next == to {
+                if self.enterable(blocked, next, to) {
                     let new_cost = current_cost + 1;
                     if self.cost_stamp[next] != stamp || new_cost < self.cost_so_far[next] {
                         self.cost_stamp[next] = stamp;
@@ -324,7 +510,7 @@ impl NativeAStar {
             // 上
             if current_y > 0 {
                 let next = current - stride;
-                if blocked[next] == 0 || next == to {
+                if self.enterable(blocked, next, to) {
                     let new_cost = current_cost + 1;
                     if self.cost_stamp[next] != stamp || new_cost < self.cost_so_far[next] {
                         self.cost_stamp[next] = stamp;
@@ -377,6 +563,7 @@ impl NativeAStar {
         edge_from_id: u32,
         edge_to_id: u32,
     ) -> Result<Option<Vec<u32>>, String> {
+        self.stats = SearchStats::default();
         self.ensure_capacity(stride, height);
         let cell_count = stride.saturating_mul(height);
 
@@ -430,6 +617,48 @@ impl NativeAStar {
             ));
         }
 
+        {
+            let mut step_cost = |current: usize, next: usize, dir: usize| {
+                let (from_bit, to_bit) = TURN_DIR_BITS[dir];
+                is_step_allowed_strict(
+                    current,
+                    next,
+                    segment_key(current, next, dir),
+                    from_bit,
+                    to_bit,
+                    segment_used,
+                    used_as_middle,
+                    start_source,
+                    start_source_multi,
+                    end_target,
+                    end_target_multi,
+                    used_points,
+                    route_from_idx,
+                    route_to_idx,
+                    edge_from_id,
+                    edge_to_id,
+                )
+                .map(|()| 0)
+            };
+            if self.target_enclosed(
+                stride,
+                height,
+                from,
+                to,
+                max_x,
+                max_y,
+                blocked,
+                &mut step_cost,
+            ) {
+                return Ok(None);
+            }
+            if self.turn_penalty > 0 {
+                let path =
+                    self.search_with_turns(stride, from, to, max_x, max_y, blocked, step_cost);
+                return Ok(path.map(|(path, _)| path));
+            }
+        }
+
         let stamp = self.next_stamp();
         self.heap.clear();
 
@@ -450,8 +679,10 @@ impl NativeAStar {
             if current_cost_at_push != self.cost_so_far[current] {
                 continue;
             }
+            self.expand(current);
 
             if current == to {
+                self.stats.cost = Some(self.cost_so_far[current]);
                 return Ok(Some(self.reconstruct_path(current_idx_u32)));
             }
 
@@ -466,8 +697,8 @@ impl NativeAStar {
             // 右
             if current_x < max_x as usize {
                 let next = current + 1;
-                if blocked[next] == 0 || next == to {
-                    if is_step_allowed_strict(
+                if self.enterable(blocked, next, to)
+                    && self.allow(is_step_allowed_strict(
                         current,
                         next,
                         /*seg_key=*/ current * 2,
@@ -484,29 +715,29 @@ impl NativeAStar {
                         route_to_idx,
                         edge_from_id,
                         edge_to_id,
-                    ) {
-                        let new_cost = current_cost + 1;
-                        if self.cost_stamp[next] != stamp || new_cost < self.cost_so_far[next] {
-                            self.cost_stamp[next] = stamp;
-                            self.cost_so_far[next] = new_cost;
-                            self.came_from[next] = current_idx_u32 as i32;
+                    ))
+                {
+                    let new_cost = current_cost + 1;
+                    if self.cost_stamp[next] != stamp || new_cost < self.cost_so_far[next] {
+                        self.cost_stamp[next] = stamp;
+                        self.cost_so_far[next] = new_cost;
+                        self.came_from[next] = current_idx_u32 as i32;
 
-                            let next_x = current_x + 1;
-                            let abs_x = if next_x >= to_x {
-                                next_x - to_x
-                            } else {
-                                to_x - next_x
-                            };
-                            let abs_y = if current_y >= to_y {
-                                current_y - to_y
-                            } else {
-                                to_y - current_y
-                            };
-                            let h = abs_x as u32
-                                + abs_y as u32
-                                + if abs_x == 0 || abs_y == 0 { 0 } else { 1 };
-                            self.heap.push(next as u32, new_cost + h, new_cost);
-                        }
+                        let next_x = current_x + 1;
+                        let abs_x = if next_x >= to_x {
+                            next_x - to_x
+                        } else {
+                            to_x - next_x
+                        };
+                        let abs_y = if current_y >= to_y {
+                            current_y - to_y
+                        } else {
+                            to_y - current_y
+                        };
+                        let h = abs_x as u32
+                            + abs_y as u32
+                            + if abs_x == 0 || abs_y == 0 { 0 } else { 1 };
+                        self.heap.push(next as u32, new_cost + h, new_cost);
                     }
                 }
             }
@@ -514,8 +745,8 @@ impl NativeAStar {
             // 左
             if current_x > 0 {
                 let next = current - 1;
-                if blocked[next] == 0 || next == to {
-                    if is_step_allowed_strict(
+                if self.enterable(blocked, next, to)
+                    && self.allow(is_step_allowed_strict(
                         current,
                         next,
                         /*seg_key=*/ next * 2,
@@ -532,29 +763,29 @@ impl NativeAStar {
                         route_to_idx,
                         edge_from_id,
                         edge_to_id,
-                    ) {
-                        let new_cost = current_cost + 1;
-                        if self.cost_stamp[next] != stamp || new_cost < self.cost_so_far[next] {
-                            self.cost_stamp[next] = stamp;
-                            self.cost_so_far[next] = new_cost;
-                            self.came_from[next] = current_idx_u32 as i32;
+                    ))
+                {
+                    let new_cost = current_cost + 1;
+                    if self.cost_stamp[next] != stamp || new_cost < self.cost_so_far[next] {
+                        self.cost_stamp[next] = stamp;
+                        self.cost_so_far[next] = new_cost;
+                        self.came_from[next] = current_idx_u32 as i32;
 
-                            let next_x = current_x - 1;
-                            let abs_x = if next_x >= to_x {
-                                next_x - to_x
-                            } else {
-                                to_x - next_x
-                            };
-                            let abs_y = if current_y >= to_y {
-                                current_y - to_y
-                            } else {
-                                to_y - current_y
-                            };
-                            let h = abs_x as u32
-                                + abs_y as u32
-                                + if abs_x == 0 || abs_y == 0 { 0 } else { 1 };
-                            self.heap.push(next as u32, new_cost + h, new_cost);
-                        }
+                        let next_x = current_x - 1;
+                        let abs_x = if next_x >= to_x {
+                            next_x - to_x
+                        } else {
+                            to_x - next_x
+                        };
+                        let abs_y = if current_y >= to_y {
+                            current_y - to_y
+                        } else {
+                            to_y - current_y
+                        };
+                        let h = abs_x as u32
+                            + abs_y as u32
+                            + if abs_x == 0 || abs_y == 0 { 0 } else { 1 };
+                        self.heap.push(next as u32, new_cost + h, new_cost);
                     }
                 }
             }
@@ -562,8 +793,8 @@ impl NativeAStar {
             // 下
             if current_y < max_y as usize {
                 let next = current + stride;
-                if blocked[next] == 0 || next == to {
-                    if is_step_allowed_strict(
+                if self.enterable(blocked, next, to)
+                    && self.allow(is_step_allowed_strict(
                         current,
                         next,
                         /*seg_key=*/ current * 2 + 1,
@@ -580,29 +811,29 @@ impl NativeAStar {
                         route_to_idx,
                         edge_from_id,
                         edge_to_id,
-                    ) {
-                        let new_cost = current_cost + 1;
-                        if self.cost_stamp[next] != stamp || new_cost < self.cost_so_far[next] {
-                            self.cost_stamp[next] = stamp;
-                            self.cost_so_far[next] = new_cost;
-                            self.came_from[next] = current_idx_u32 as i32;
+                    ))
+                {
+                    let new_cost = current_cost + 1;
+                    if self.cost_stamp[next] != stamp || new_cost < self.cost_so_far[next] {
+                        self.cost_stamp[next] = stamp;
+                        self.cost_so_far[next] = new_cost;
+                        self.came_from[next] = current_idx_u32 as i32;
 
-                            let next_y = current_y + 1;
-                            let abs_x = if current_x >= to_x {
-                                current_x - to_x
-                            } else {
-                                to_x - current_x
-                            };
-                            let abs_y = if next_y >= to_y {
-                                next_y - to_y
-                            } else {
-                                to_y - next_y
-                            };
-                            let h = abs_x as u32
-                                + abs_y as u32
-                                + if abs_x == 0 || abs_y == 0 { 0 } else { 1 };
-                            self.heap.push(next as u32, new_cost + h, new_cost);
-                        }
+                        let next_y = current_y + 1;
+                        let abs_x = if current_x >= to_x {
+                            current_x - to_x
+                        } else {
+                            to_x - current_x
+                        };
+                        let abs_y = if next_y >= to_y {
+                            next_y - to_y
+                        } else {
+                            to_y - next_y
+                        };
+                        let h = abs_x as u32
+                            + abs_y as u32
+                            + if abs_x == 0 || abs_y == 0 { 0 } else { 1 };
+                        self.heap.push(next as u32, new_cost + h, new_cost);
                     }
                 }
             }
@@ -610,8 +841,8 @@ impl NativeAStar {
             // 上
             if current_y > 0 {
                 let next = current - stride;
-                if blocked[next] == 0 || next == to {
-                    if is_step_allowed_strict(
+                if self.enterable(blocked, next, to)
+                    && self.allow(is_step_allowed_strict(
                         current,
                         next,
                         /*seg_key=*/ next * 2 + 1,
@@ -628,29 +859,29 @@ impl NativeAStar {
                         route_to_idx,
                         edge_from_id,
                         edge_to_id,
-                    ) {
-                        let new_cost = current_cost + 1;
-                        if self.cost_stamp[next] != stamp || new_cost < self.cost_so_far[next] {
-                            self.cost_stamp[next] = stamp;
-                            self.cost_so_far[next] = new_cost;
-                            self.came_from[next] = current_idx_u32 as i32;
+                    ))
+                {
+                    let new_cost = current_cost + 1;
+                    if self.cost_stamp[next] != stamp || new_cost < self.cost_so_far[next] {
+                        self.cost_stamp[next] = stamp;
+                        self.cost_so_far[next] = new_cost;
+                        self.came_from[next] = current_idx_u32 as i32;
 
-                            let next_y = current_y - 1;
-                            let abs_x = if current_x >= to_x {
-                                current_x - to_x
-                            } else {
-                                to_x - current_x
-                            };
-                            let abs_y = if next_y >= to_y {
-                                next_y - to_y
-                            } else {
-                                to_y - next_y
-                            };
-                            let h = abs_x as u32
-                                + abs_y as u32
-                                + if abs_x == 0 || abs_y == 0 { 0 } else { 1 };
-                            self.heap.push(next as u32, new_cost + h, new_cost);
-                        }
+                        let next_y = current_y - 1;
+                        let abs_x = if current_x >= to_x {
+                            current_x - to_x
+                        } else {
+                            to_x - current_x
+                        };
+                        let abs_y = if next_y >= to_y {
+                            next_y - to_y
+                        } else {
+                            to_y - next_y
+                        };
+                        let h = abs_x as u32
+                            + abs_y as u32
+                            + if abs_x == 0 || abs_y == 0 { 0 } else { 1 };
+                        self.heap.push(next as u32, new_cost + h, new_cost);
                     }
                 }
             }
@@ -690,6 +921,7 @@ impl NativeAStar {
         edge_to_id: u32,
         allow_end_segment_reuse: bool,
     ) -> Result<Option<(Vec<u32>, u32)>, String> {
+        self.stats = SearchStats::default();
         self.ensure_capacity(stride, height);
         let cell_count = stride.saturating_mul(height);
 
@@ -747,6 +979,86 @@ impl NativeAStar {
             ));
         }
 
+        {
+            let edge_pair_id = if edge_from_id <= 0xffff && edge_to_id <= 0xffff {
+                Some((edge_from_id << 16) | edge_to_id)
+            } else {
+                None
+            };
+            let stride_i32 = stride as i32;
+            let route_to_i32 = route_to_idx as i32;
+            // 单步规则与下面逐格版本的右/左/下/上四个分支完全相同，只是按方向查表。
+            let mut step_cost = |current: usize, next: usize, dir: usize| {
+                let (from_bit, to_bit) = TURN_DIR_BITS[dir];
+                let seg_key = segment_key(current, next, dir);
+                let same_pair_segment = edge_pair_id.is_some_and(|pair| {
+                    segment_pair_multi[seg_key] == 0 && segment_pair[seg_key] == pair
+                });
+                let mut penalty = 0;
+                if let Some(points) = used_points {
+                    penalty += crossing_penalty(points[current], from_bit);
+                    penalty += crossing_penalty(points[next], to_bit);
+                    let mask = points[next];
+                    if next != to && mask != 0 {
+                        let diff_to_target = route_to_i32 - next as i32;
+                        let is_pre_target = diff_to_target == 1
+                            || diff_to_target == -1
+                            || diff_to_target == stride_i32
+                            || diff_to_target == -stride_i32;
+                        let arms = BITCOUNT_4[((mask | to_bit) & 0x0F) as usize];
+                        let ok = if same_pair_segment && arms <= 2 {
+                            true
+                        } else if current as u32 != route_from_idx && !is_pre_target {
+                            false
+                        } else if current as u32 == route_from_idx {
+                            arms < 3
+                        } else {
+                            arms < 4
+                        };
+                        if !ok {
+                            return Err(Rejection::Crossing);
+                        }
+                    }
+                }
+                is_segment_allowed_relaxed(
+                    current,
+                    next,
+                    seg_key,
+                    segment_used,
+                    used_as_middle,
+                    segment_pair,
+                    segment_pair_multi,
+                    start_source,
+                    start_source_multi,
+                    end_target,
+                    end_target_multi,
+                    route_from_idx,
+                    route_to_idx,
+                    edge_from_id,
+                    edge_to_id,
+                    allow_end_segment_reuse,
+                )
+                .map(|()| penalty)
+            };
+            if self.target_enclosed(
+                stride,
+                height,
+                from,
+                to,
+                max_x,
+                max_y,
+                blocked,
+                &mut step_cost,
+            ) {
+                return Ok(None);
+            }
+            if self.turn_penalty > 0 {
+                return Ok(
+                    self.search_with_turns(stride, from, to, max_x, max_y, blocked, step_cost)
+                );
+            }
+        }
+
         let stamp = self.next_stamp();
         self.heap.clear();
 
@@ -774,9 +1086,11 @@ impl NativeAStar {
             if current_cost_at_push != self.cost_so_far[current] {
                 continue;
             }
+            self.expand(current);
 
             if current == to {
                 let cost = self.cost_so_far[current];
+                self.stats.cost = Some(cost);
                 return Ok(Some((self.reconstruct_path(current_idx_u32), cost)));
             }
 
@@ -791,9 +1105,9 @@ impl NativeAStar {
             // 右
             if current_x < max_x as usize {
                 let next = current + 1;
-                if blocked[next] == 0 || next == to {
+                if self.enterable(blocked, next, to) {
                     let mut penalty = 0;
-                    let mut ok = true;
+                    let mut step = Ok(());
                     let seg_key = current * 2;
                     let same_pair_segment = match edge_pair_id {
                         Some(pair) => {
@@ -826,24 +1140,24 @@ impl NativeAStar {
                                 if same_pair_segment && arms <= 2 {
                                     // ok: 复用既有直线段不会增加 arms
                                 } else if current_idx_u32 != route_from_idx && !is_pre_target {
-                                    ok = false;
+                                    step = Err(Rejection::Crossing);
                                 } else if current_idx_u32 == route_from_idx {
                                     // 起点第一步: 不允许制造 3+ arms junction
                                     if arms >= 3 {
-                                        ok = false;
+                                        step = Err(Rejection::Crossing);
                                     }
                                 } else {
                                     // 终点前一步: 允许 T junction(3 arms) 汇入,但禁止 `┼`(4 arms)
                                     if arms >= 4 {
-                                        ok = false;
+                                        step = Err(Rejection::Crossing);
                                     }
                                 }
                             }
                         }
                     }
 
-                    if ok
-                        && is_segment_allowed_relaxed(
+                    if self.allow(step.and_then(|()| {
+                        is_segment_allowed_relaxed(
                             current,
                             next,
                             /*seg_key=*/ seg_key,
@@ -861,7 +1175,7 @@ impl NativeAStar {
                             edge_to_id,
                             allow_end_segment_reuse,
                         )
-                    {
+                    })) {
                         let new_cost = current_cost + 1 + penalty;
                         if self.cost_stamp[next] != stamp || new_cost < self.cost_so_far[next] {
                             self.cost_stamp[next] = stamp;
@@ -891,9 +1205,9 @@ impl NativeAStar {
             // 左
             if current_x > 0 {
                 let next = current - 1;
-                if blocked[next] == 0 || next == to {
+                if self.enterable(blocked, next, to) {
                     let mut penalty = 0;
-                    let mut ok = true;
+                    let mut step = Ok(());
                     let seg_key = next * 2;
                     let same_pair_segment = match edge_pair_id {
                         Some(pair) => {
@@ -920,20 +1234,20 @@ impl NativeAStar {
                                 if same_pair_segment && arms <= 2 {
                                     // ok: 同端点平行边复用直线段
                                 } else if current_idx_u32 != route_from_idx && !is_pre_target {
-                                    ok = false;
+                                    step = Err(Rejection::Crossing);
                                 } else if current_idx_u32 == route_from_idx {
                                     if arms >= 3 {
-                                        ok = false;
+                                        step = Err(Rejection::Crossing);
                                     }
                                 } else if arms >= 4 {
-                                    ok = false;
+                                    step = Err(Rejection::Crossing);
                                 }
                             }
                         }
                     }
 
-                    if ok
-                        && is_segment_allowed_relaxed(
+                    if self.allow(step.and_then(|()| {
+                        is_segment_allowed_relaxed(
                             current,
                             next,
                             /*seg_key=*/ seg_key,
@@ -951,7 +1265,7 @@ impl NativeAStar {
                             edge_to_id,
                             allow_end_segment_reuse,
                         )
-                    {
+                    })) {
                         let new_cost = current_cost + 1 + penalty;
                         if self.cost_stamp[next] != stamp || new_cost < self.cost_so_far[next] {
                             self.cost_stamp[next] = stamp;
@@ -981,9 +1295,9 @@ impl NativeAStar {
             // 下
             if current_y < max_y as usize {
                 let next = current + stride;
-                if blocked[next] == 0 || next == to {
+                if self.enterable(blocked, next, to) {
                     let mut penalty = 0;
-                    let mut ok = true;
+                    let mut step = Ok(());
                     let seg_key = current * 2 + 1;
                     let same_pair_segment = match edge_pair_id {
                         Some(pair) => {
@@ -1010,20 +1324,20 @@ impl NativeAStar {
                                 if same_pair_segment && arms <= 2 {
                                     // ok: 同端点平行边复用直线段
                                 } else if current_idx_u32 != route_from_idx && !is_pre_target {
-                                    ok = false;
+                                    step = Err(Rejection::Crossing);
                                 } else if current_idx_u32 == route_from_idx {
                                     if arms >= 3 {
-                                        ok = false;
+                                        step = Err(Rejection::Crossing);
                                     }
                                 } else if arms >= 4 {
-                                    ok = false;
+                                    step = Err(Rejection::Crossing);
                                 }
                             }
                         }
                     }
 
-                    if ok
-                        && is_segment_allowed_relaxed(
+                    if self.allow(step.and_then(|()| {
+                        is_segment_allowed_relaxed(
                             current,
                             next,
                             /*seg_key=*/ seg_key,
@@ -1041,7 +1355,7 @@ impl NativeAStar {
                             edge_to_id,
                             allow_end_segment_reuse,
                         )
-                    {
+                    })) {
                         let new_cost = current_cost + 1 + penalty;
                         if self.cost_stamp[next] != stamp || new_cost < self.cost_so_far[next] {
                             self.cost_stamp[next] = stamp;
@@ -1071,9 +1385,9 @@ impl NativeAStar {
             // 上
             if current_y > 0 {
                 let next = current - stride;
-                if blocked[next] == 0 || next == to {
+                if self.enterable(blocked, next, to) {
                     let mut penalty = 0;
-                    let mut ok = true;
+                    let mut step = Ok(());
                     let seg_key = next * 2 + 1;
                     let same_pair_segment = match edge_pair_id {
                         Some(pair) => {
@@ -1100,20 +1414,20 @@ impl NativeAStar {
                                 if same_pair_segment && arms <= 2 {
                                     // ok: 同端点平行边复用直线段
                                 } else if current_idx_u32 != route_from_idx && !is_pre_target {
-                                    ok = false;
+                                    step = Err(Rejection::Crossing);
                                 } else if current_idx_u32 == route_from_idx {
                                     if arms >= 3 {
-                                        ok = false;
+                                        step = Err(Rejection::Crossing);
                                     }
                                 } else if arms >= 4 {
-                                    ok = false;
+                                    step = Err(Rejection::Crossing);
                                 }
                             }
                         }
                     }
 
-                    if ok
-                        && is_segment_allowed_relaxed(
+                    if self.allow(step.and_then(|()| {
+                        is_segment_allowed_relaxed(
                             current,
                             next,
                             /*seg_key=*/ seg_key,
@@ -1131,7 +1445,7 @@ impl NativeAStar {
                             edge_to_id,
                             allow_end_segment_reuse,
                         )
-                    {
+                    })) {
                         let new_cost = current_cost + 1 + penalty;
                         if self.cost_stamp[next] != stamp || new_cost < self.cost_so_far[next] {
                             self.cost_stamp[next] = stamp;
@@ -1162,6 +1476,202 @@ impl NativeAStar {
         Ok(None)
     }
 
+    /// 方向感知的 A*（`turn_penalty > 0` 时三种 `get_path*` 共用）。
+    ///
+    /// - 状态 = 格子 × 进入方向（起点额外一个“无方向”状态），缓冲区按状态数扩容；
+    /// - `step_cost(current, next, dir)`：`Err(rule)` = 这一步不允许（按规则计数），`Ok(extra)` = 步长之外的额外代价；
+    /// - 返回（路径, 代价），代价含转弯惩罚与 `extra`；
+    /// - 展开满 `TURN_SEARCH_BUDGET` 个状态还没到终点就放弃，返回 `None`。
+    #[allow(clippy::too_many_arguments)]
+    fn search_with_turns(
+        &mut self,
+        stride: usize,
+        from: usize,
+        to: usize,
+        max_x: u32,
+        max_y: u32,
+        blocked: &[u8],
+        mut step_cost: impl FnMut(usize, usize, usize) -> Result<u32, Rejection>,
+    ) -> Option<(Vec<u32>, u32)> {
+        let turn_penalty = self.turn_penalty;
+        let state_count = blocked.len().saturating_mul(TURN_STATES);
+        if self.cost_stamp.len() < state_count {
+            self.cost_stamp.resize(state_count, 0);
+            self.cost_so_far.resize(state_count, 0);
+            self.came_from.resize(state_count, -1);
+        }
+
+        let stamp = self.next_stamp();
+        self.heap.clear();
+
+        let start = from * TURN_STATES + TURN_DIR_NONE;
+        self.cost_stamp[start] = stamp;
+        self.cost_so_far[start] = 0;
+        self.came_from[start] = -1;
+        self.heap.push(start as u32, 0, 0);
+
+        let to_y = to / stride;
+        let to_x = to - to_y * stride;
+
+        while let Some((state_u32, cost_at_push)) = self.heap.pop() {
+            let state = state_u32 as usize;
+            if self.cost_stamp[state] != stamp || cost_at_push != self.cost_so_far[state] {
+                continue;
+            }
+
+            let current = state / TURN_STATES;
+            self.expand(current);
+            let dir = state % TURN_STATES;
+            let current_cost = self.cost_so_far[state];
+            if current == to {
+                let mut path = Vec::new();
+                let mut cursor = state_u32;
+                loop {
+                    path.push(cursor / TURN_STATES as u32);
+                    let parent = self.came_from[cursor as usize];
+                    if parent < 0 {
+                        break;
+                    }
+                    cursor = parent as u32;
+                }
+                path.reverse();
+                self.stats.cost = Some(current_cost);
+                return Some((path, current_cost));
+            }
+            if self.stats.expansions >= TURN_SEARCH_BUDGET {
+                self.stats.budget_exhausted = true;
+                return None;
+            }
+
+            let current_y = current / stride;
+            let current_x = current - current_y * stride;
+            // 顺序与逐格版本一致：右/左/下/上（下标即 `TURN_DIR_*`）
+            let neighbors = [
+                (current_x < max_x as usize).then(|| current + 1),
+                (current_x > 0).then(|| current - 1),
+                (current_y < max_y as usize).then(|| current + stride),
+                (current_y > 0).then(|| current - stride),
+            ];
+            for (next_dir, next) in neighbors.into_iter().enumerate() {
+                let Some(next) = next else {
+                    continue;
+                };
+                if !self.enterable(blocked, next, to) {
+                    continue;
+                }
+                let Ok(extra) = step_cost(current, next, next_dir)
+                    .inspect_err(|&rejection| self.reject(rejection))
+                else {
+                    continue;
+                };
+                let turn = if dir == TURN_DIR_NONE || dir == next_dir {
+                    0
+                } else {
+                    turn_penalty
+                };
+                let new_cost = current_cost + 1 + extra + turn;
+                let next_state = next * TURN_STATES + next_dir;
+                if self.cost_stamp[next_state] != stamp || new_cost < self.cost_so_far[next_state] {
+                    self.cost_stamp[next_state] = stamp;
+                    self.cost_so_far[next_state] = new_cost;
+                    self.came_from[next_state] = state_u32 as i32;
+
+                    let next_y = next / stride;
+                    let next_x = next - next_y * stride;
+                    let h = turn_heuristic(
+                        next_x.abs_diff(to_x),
+                        next_y.abs_diff(to_y),
+                        next_dir,
+                        turn_penalty,
+                    );
+                    self.heap.push(next_state as u32, new_cost + h, new_cost);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// 封闭探测：终点是否被围死（从 `from` 走不到 `to`）。
+    ///
+    /// - 从终点反向泛洪，只走“能一步走到当前格子”的前驱（边界、blocked 与 `step_cost` 的规则同正向），
+    ///   所以到过的格子恰好是正向能走到终点的格子；
+    /// - 泛洪在 `ENCLOSURE_PROBE_BUDGET` 个格子内耗尽且没碰到起点 = 走不通，返回 `true`；
+    ///   碰到起点或超出预算返回 `false`，照常跑 A*（只会多看这么多格子）；
+    /// - 问的都是正向的那一步，被拒绝的单步照常计入 `rejections` 与调试探测；
+    ///   看过的格子数记在 `SearchStats.probe_cells`（不算 `expansions`）。
+    #[allow(clippy::too_many_arguments)]
+    fn target_enclosed(
+        &mut self,
+        stride: usize,
+        height: usize,
+        from: usize,
+        to: usize,
+        max_x: u32,
+        max_y: u32,
+        blocked: &[u8],
+        mut step_cost: impl FnMut(usize, usize, usize) -> Result<u32, Rejection>,
+    ) -> bool {
+        if self.skip_enclosure_probe || from == to {
+            return false;
+        }
+        let cell_count = stride * height;
+        if self.back_cost_stamp.len() < cell_count {
+            self.back_cost_stamp.resize(cell_count, 0);
+        }
+
+        let (max_x, max_y) = (max_x as usize, max_y as usize);
+        let stamp = self.next_stamp();
+        self.back_stack.clear();
+        self.back_cost_stamp[to] = stamp;
+        self.back_stack.push(to as u32);
+        let mut seen = 1;
+
+        while let Some(cell) = self.back_stack.pop() {
+            self.stats.probe_cells += 1;
+            let cell = cell as usize;
+            let (cell_x, cell_y) = (cell % stride, cell / stride);
+            // 下标是 prev -> cell 这一步的方向（`TURN_DIR_*`），
+            // 条件与正向邻居的边界判断一一对应（prev 向右一步要求 prev_x < max_x，以此类推）。
+            let predecessors = [
+                (cell_x > 0 && cell_x - 1 < max_x).then(|| cell - 1),
+                (cell_x + 1 < stride).then(|| cell + 1),
+                (cell_y > 0 && cell_y - 1 < max_y).then(|| cell - stride),
+                (cell_y + 1 < height).then(|| cell + stride),
+            ];
+            for (dir, prev) in predecessors.into_iter().enumerate() {
+                let Some(prev) = prev else {
+                    continue;
+                };
+                if self.back_cost_stamp[prev] == stamp {
+                    continue;
+                }
+                if let Some(probe) = &mut self.probe {
+                    probe.step = (prev as u32, cell as u32);
+                }
+                // 正向只会走进不是 blocked 的格子（起点不用“走进”，终点已在栈里）
+                if prev != from && blocked[prev] != 0 {
+                    self.reject(Rejection::Blocked);
+                    continue;
+                }
+                if !self.allow(step_cost(prev, cell, dir).map(|_| ())) {
+                    continue;
+                }
+                if prev == from {
+                    return false;
+                }
+                self.back_cost_stamp[prev] = stamp;
+                seen += 1;
+                if seen > ENCLOSURE_PROBE_BUDGET {
+                    return false;
+                }
+                self.back_stack.push(prev as u32);
+            }
+        }
+
+        true
+    }
+
     /// 回溯路径（包含 fromIdx 与 toIdx），与 TS 行为一致。
     fn reconstruct_path(&self, mut current_idx: u32) -> Vec<u32> {
         let mut path: Vec<u32> = Vec::new();
@@ -1201,33 +1711,33 @@ fn is_step_allowed_strict(
     route_to_idx: u32,
     edge_from_id: u32,
     edge_to_id: u32,
-) -> bool {
+) -> Result<(), Rejection> {
     // 1) usedPoints：禁止形成 `┼` 四向交叉
     if let Some(points) = used_points {
         let from_mask = points[step_from];
         if from_mask != 0 {
             let next_mask = from_mask | from_bit;
             if (next_mask & H_MASK) == H_MASK && (next_mask & V_MASK) == V_MASK {
-                return false;
+                return Err(Rejection::Crossing);
             }
         }
         let to_mask = points[step_to];
         if to_mask != 0 {
             let next_mask = to_mask | to_bit;
             if (next_mask & H_MASK) == H_MASK && (next_mask & V_MASK) == V_MASK {
-                return false;
+                return Err(Rejection::Crossing);
             }
         }
     }
 
     // 2) segmentUsage：严格共线共享规则
     if segment_used[seg_key] == 0 {
-        return true;
+        return Ok(());
     }
 
     // 一旦有边把这段当“中间段”用过，那么任何共享都会让语义变得更难读。
     if used_as_middle[seg_key] != 0 {
-        return false;
+        return Err(Rejection::Segment);
     }
 
     let step_from_u32 = step_from as u32;
@@ -1245,20 +1755,20 @@ fn is_step_allowed_strict(
     if is_start_step && is_end_step {
         let start_ok = !ss_multi && (ss == 0 || ss == edge_from_id);
         let end_ok = !et_multi && (et == 0 || et == edge_to_id);
-        return start_ok && end_ok;
+        return segment_rule(start_ok && end_ok);
     }
 
     // 同源：只允许“起点段”共线，并且该段不能混入终点共享
     if is_start_step {
-        return !et_multi && et == 0 && !ss_multi && ss == edge_from_id;
+        return segment_rule(!et_multi && et == 0 && !ss_multi && ss == edge_from_id);
     }
 
     // 同靶：只允许“终点段”共线，并且该段不能混入起点共享
     if is_end_step {
-        return !ss_multi && ss == 0 && !et_multi && et == edge_to_id;
+        return segment_rule(!ss_multi && ss == 0 && !et_multi && et == edge_to_id);
     }
 
-    false
+    Err(Rejection::Segment)
 }
 
 /// relaxed：计算“形成 `┼`（四向交叉）”的惩罚（与 TS 的 `crossingPenalty()` 一致）。
@@ -1297,9 +1807,9 @@ fn is_segment_allowed_relaxed(
     edge_from_id: u32,
     edge_to_id: u32,
     allow_end_segment_reuse: bool,
-) -> bool {
+) -> Result<(), Rejection> {
     if segment_used[seg_key] == 0 {
-        return true;
+        return Ok(());
     }
 
     // ---------------------------------------------------------------------
@@ -1320,13 +1830,13 @@ fn is_segment_allowed_relaxed(
     if edge_from_id <= 0xffff && edge_to_id <= 0xffff {
         let edge_pair_id = (edge_from_id << 16) | edge_to_id;
         if segment_pair_multi[seg_key] == 0 && segment_pair[seg_key] == edge_pair_id {
-            return true;
+            return Ok(());
         }
     }
 
     // 中间段永不允许复用：它必然意味着“合并后再分开”的重叠，读图会崩。
     if used_as_middle[seg_key] != 0 {
-        return false;
+        return Err(Rejection::Segment);
     }
 
     let step_from_u32 = step_from as u32;
@@ -1337,7 +1847,7 @@ fn is_segment_allowed_relaxed(
     // relaxed 默认仍优先“禁止终点段复用”（更符合直觉）。
     // 只有在 JS 侧进入 fallback（不可达）时，才会打开 allow_end_segment_reuse。
     if is_end_step && !allow_end_segment_reuse {
-        return false;
+        return Err(Rejection::Segment);
     }
 
     let ss = start_source[seg_key];
@@ -1350,7 +1860,7 @@ fn is_segment_allowed_relaxed(
     if is_start_step && is_end_step {
         let start_ok = !ss_multi && (ss == 0 || ss == edge_from_id);
         let end_ok = !et_multi && (et == 0 || et == edge_to_id);
-        return start_ok && end_ok;
+        return segment_rule(start_ok && end_ok);
     }
 
     // 同源：只允许“起点段”复用，并且该段不能混入任何 end 复用（避免读图歧义）。
@@ -1362,15 +1872,41 @@ fn is_segment_allowed_relaxed(
         // 因此这里保持更强的约束:
         // - start 段只允许与“同 source 的 start 段”复用；
         // - 该 segment 不能同时作为任何边的 end 段(et 必须为 0)。
-        return !et_multi && et == 0 && !ss_multi && ss == edge_from_id;
+        return segment_rule(!et_multi && et == 0 && !ss_multi && ss == edge_from_id);
     }
 
     // 同靶：允许“终点段”复用（最后一段；仅 fallback 开启）。
     if is_end_step {
         // 同靶：允许“终点段”复用（最后一段；仅 fallback 开启）。
         // 但仍然禁止与任何 start 段混用(ss 必须为 0),否则会在 target 端口附近形成难以读懂的合并线。
-        return !ss_multi && ss == 0 && !et_multi && et == edge_to_id;
+        return segment_rule(!ss_multi && ss == 0 && !et_multi && et == edge_to_id);
     }
 
-    false
+    Err(Rejection::Segment)
+}
+
+/// 共线共享规则的判定结果。
+fn segment_rule(allowed: bool) -> Result<(), Rejection> {
+    if allowed {
+        Ok(())
+    } else {
+        Err(Rejection::Segment)
+    }
+}
+
+/// 单位线段 key（同 TS：水平段 `min*2`，垂直段 `min*2+1`）。
+fn segment_key(current: usize, next: usize, dir: usize) -> usize {
+    current.min(next) * 2 + usize::from(dir >= TURN_DIR_DOWN)
+}
+
+/// 方向感知搜索的启发式：曼哈顿距离 + 至少还要转一次弯时的惩罚（不高估，保证最优）。
+fn turn_heuristic(abs_x: usize, abs_y: usize, dir: usize, turn_penalty: u32) -> u32 {
+    let horizontal = dir < TURN_DIR_DOWN;
+    let vertical = (TURN_DIR_DOWN..TURN_DIR_NONE).contains(&dir);
+    let must_turn = if abs_x != 0 {
+        abs_y != 0 || vertical
+    } else {
+        abs_y != 0 && horizontal
+    };
+    (abs_x + abs_y) as u32 + if must_turn { turn_penalty } else { 0 }
 }
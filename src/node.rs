@@ -0,0 +1,350 @@
+// ============================================================================
+// Node.js 原生扩展（feature = "node"，napi-rs）
+//
+// 目标：
+// - 给 Node 用户一个 TS 版 `beautiful-mermaid` 的“即插即用”替代品：
+//   函数名、options 形状（camelCase）、返回值形状都与 TS 版一致；
+// - 单独导出 native A*（`getPath` / `getPathStrict` / `getPathRelaxed`），
+//   签名与 bundle 检测的 `globalThis.__bm_getPath*` 一致：
+//   继续用 TS 包的用户也可以在 V8 里挂上 Rust 路由（见 node/index.js 的 `installNativePathfinder`）。
+//
+// 约定：
+// - `renderMermaid` / `renderMermaidWithMeta` 与 TS 版一样返回 Promise，渲染在 libuv 线程池里执行
+//   （JS 引擎按线程初始化，不阻塞主线程）；ASCII 系列保持同步；
+// - options 解析失败 / 渲染失败统一抛出 `Error`（message 即 `BeautifulMermaidError` 的文案）。
+//
+// 包装（加载 .node、THEMES / DEFAULTS 常量、类型声明）在 `node/` 目录。
+//
+// 测试：napi 符号由 Node 进程提供，Rust 测试二进制无法链接，回归测试在 `node/__test__/`。
+// ============================================================================
+
+// `#[napi]` 只在非 test 构建里注册导出，test 构建下这些函数看起来是死代码。
+#![cfg_attr(test, allow(dead_code))]
+
+use crate::error::BeautifulMermaidError;
+use crate::native_pathfinder::NativeAStar;
+use crate::theme::{DEFAULTS, DiagramColors, THEMES};
+use crate::types::{AsciiRenderOptions, RenderOptions, SvgRenderWithMeta};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::cell::RefCell;
+
+thread_local! {
+    /// pathfinder 只会在 JS 主线程（或 worker 自己的线程）上被调用：每个线程一份复用的 A* 缓冲区。
+    static ASTAR: RefCell<NativeAStar> = RefCell::new(NativeAStar::new());
+}
+
+fn to_napi_error(err: BeautifulMermaidError) -> Error {
+    Error::from_reason(err.to_string())
+}
+
+/// `undefined` / `null` 视为默认参数，其余按 serde 形状解析。
+fn parse_options<T: DeserializeOwned + Default>(options: Option<Value>) -> Result<T> {
+    match options {
+        None | Some(Value::Null) => Ok(T::default()),
+        Some(value) => serde_json::from_value(value)
+            .map_err(|err| Error::new(Status::InvalidArg, format!("参数错误: {err}"))),
+    }
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value> {
+    serde_json::to_value(value).map_err(|err| Error::from_reason(err.to_string()))
+}
+
+/// TS 版的 `DiagramColors` 没有值的字段是缺省（而不是 `null`）。
+fn colors_to_value(colors: &DiagramColors) -> Result<Value> {
+    let mut value = to_value(colors)?;
+    if let Value::Object(map) = &mut value {
+        map.retain(|_, field| !field.is_null());
+    }
+    Ok(value)
+}
+
+// ============================================================================
+// 渲染（与 TS 版同名）
+// ============================================================================
+
+pub struct RenderSvgTask {
+    text: String,
+    options: Option<Value>,
+}
+
+impl Task for RenderSvgTask {
+    type Output = String;
+    type JsValue = String;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let options: RenderOptions = parse_options(self.options.take())?;
+        crate::render_mermaid(&self.text, &options).map_err(to_napi_error)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+pub struct RenderSvgWithMetaTask {
+    text: String,
+    options: Option<Value>,
+}
+
+impl Task for RenderSvgWithMetaTask {
+    type Output = SvgRenderWithMeta;
+    type JsValue = napi::JsUnknown;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let options: RenderOptions = parse_options(self.options.take())?;
+        crate::render_mermaid_with_meta(&self.text, &options).map_err(to_napi_error)
+    }
+
+    fn resolve(&mut self, env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        env.to_js_value(&output)
+    }
+}
+
+/// `renderMermaid(text, options?) -> Promise<string>`（SVG）。
+#[napi(ts_return_type = "Promise<string>")]
+pub fn render_mermaid(text: String, options: Option<Value>) -> AsyncTask<RenderSvgTask> {
+    AsyncTask::new(RenderSvgTask { text, options })
+}
+
+/// `renderMermaidWithMeta(text, options?) -> Promise<{ svg, viewBox, meta }>`。
+#[napi(ts_return_type = "Promise<SvgRenderWithMeta>")]
+pub fn render_mermaid_with_meta(
+    text: String,
+    options: Option<Value>,
+) -> AsyncTask<RenderSvgWithMetaTask> {
+    AsyncTask::new(RenderSvgWithMetaTask { text, options })
+}
+
+/// `renderMermaidAscii(text, options?) -> string`。
+#[napi]
+pub fn render_mermaid_ascii(text: String, options: Option<Value>) -> Result<String> {
+    let options: AsciiRenderOptions = parse_options(options)?;
+    crate::render_mermaid_ascii(&text, &options).map_err(to_napi_error)
+}
+
+/// `renderMermaidAsciiWithMeta(text, options?) -> { text, meta }`。
+#[napi]
+pub fn render_mermaid_ascii_with_meta(text: String, options: Option<Value>) -> Result<Value> {
+    let options: AsciiRenderOptions = parse_options(options)?;
+    let rendered = crate::render_mermaid_ascii_with_meta(&text, &options).map_err(to_napi_error)?;
+    to_value(&rendered)
+}
+
+/// `validateMermaid(text) -> { isValid, error, details }`（TS 版没有，这里额外提供）。
+#[napi]
+pub fn validate_mermaid(text: String) -> Result<Value> {
+    let validation = crate::validate_mermaid(&text).map_err(to_napi_error)?;
+    to_value(&validation)
+}
+
+/// `fromShikiTheme(theme) -> DiagramColors`。
+#[napi]
+pub fn from_shiki_theme(theme: Value) -> Result<Value> {
+    colors_to_value(&crate::theme::from_shiki_theme(&theme))
+}
+
+/// `THEMES` 的数据（node/index.js 在加载时读一次，导出成常量）。
+#[napi]
+pub fn themes() -> Result<Value> {
+    let mut map = serde_json::Map::new();
+    for (name, colors) in THEMES.iter() {
+        map.insert((*name).to_string(), colors_to_value(colors)?);
+    }
+    Ok(Value::Object(map))
+}
+
+/// `DEFAULTS` 的数据（同上）。
+#[napi]
+pub fn defaults() -> Result<Value> {
+    colors_to_value(&DEFAULTS)
+}
+
+// ============================================================================
+// Native pathfinder -> V8 glue（参数形状与 src/js.rs 里注册给 QuickJS 的版本一致）
+// ============================================================================
+
+fn grid_height(stride: u32, blocked: &[u8], name: &str) -> Result<usize> {
+    let stride = stride as usize;
+    if stride == 0 {
+        return Err(Error::new(
+            Status::InvalidArg,
+            format!("{name}: stride 不能为 0"),
+        ));
+    }
+    if !blocked.len().is_multiple_of(stride) {
+        return Err(Error::new(
+            Status::InvalidArg,
+            format!(
+                "{name}: blocked.len() 必须能被 stride 整除: blocked.len()={}, stride={stride}",
+                blocked.len()
+            ),
+        ));
+    }
+    Ok(blocked.len() / stride)
+}
+
+fn required<T: FromNapiValue + ValidateNapiValue>(object: &Object, key: &str) -> Result<T> {
+    object
+        .get::<_, T>(key)?
+        .ok_or_else(|| Error::new(Status::InvalidArg, format!("缺少字段: {key}")))
+}
+
+/// `StrictPathConstraints`（TS 侧对象）里 strict / relaxed 共用的字段。
+struct Constraints {
+    segment_used: Uint8Array,
+    used_as_middle: Uint8Array,
+    start_source: Uint32Array,
+    start_source_multi: Uint8Array,
+    end_target: Uint32Array,
+    end_target_multi: Uint8Array,
+    used_points: Option<Uint8Array>,
+    route_from_idx: u32,
+    route_to_idx: u32,
+    edge_from_id: u32,
+    edge_to_id: u32,
+}
+
+impl Constraints {
+    fn read(constraints: &Object, segment_usage: &Object) -> Result<Self> {
+        Ok(Self {
+            segment_used: required(segment_usage, "segmentUsed")?,
+            used_as_middle: required(segment_usage, "usedAsMiddle")?,
+            start_source: required(segment_usage, "startSource")?,
+            start_source_multi: required(segment_usage, "startSourceMulti")?,
+            end_target: required(segment_usage, "endTarget")?,
+            end_target_multi: required(segment_usage, "endTargetMulti")?,
+            // usedPoints 在 TS 侧是可选字段：undefined/null 都视为 None
+            used_points: constraints.get("usedPoints")?,
+            route_from_idx: required(constraints, "routeFromIdx")?,
+            route_to_idx: required(constraints, "routeToIdx")?,
+            edge_from_id: required(constraints, "edgeFromId")?,
+            edge_to_id: required(constraints, "edgeToId")?,
+        })
+    }
+}
+
+/// `getPath(stride, fromIdx, toIdx, maxX, maxY, blocked) -> number[] | null`。
+#[napi]
+pub fn get_path(
+    stride: u32,
+    from_idx: u32,
+    to_idx: u32,
+    max_x: u32,
+    max_y: u32,
+    blocked: Uint8Array,
+) -> Result<Option<Vec<u32>>> {
+    let height = grid_height(stride, &blocked, "getPath")?;
+    ASTAR.with_borrow_mut(|astar| {
+        astar
+            .get_path(
+                stride as usize,
+                height,
+                from_idx,
+                to_idx,
+                max_x,
+                max_y,
+                &blocked,
+            )
+            .map_err(Error::from_reason)
+    })
+}
+
+/// `getPathStrict(stride, fromIdx, toIdx, maxX, maxY, blocked, constraints) -> number[] | null`。
+#[napi]
+pub fn get_path_strict(
+    stride: u32,
+    from_idx: u32,
+    to_idx: u32,
+    max_x: u32,
+    max_y: u32,
+    blocked: Uint8Array,
+    constraints: Object,
+) -> Result<Option<Vec<u32>>> {
+    let height = grid_height(stride, &blocked, "getPathStrict")?;
+    let segment_usage: Object = required(&constraints, "segmentUsage")?;
+    let c = Constraints::read(&constraints, &segment_usage)?;
+    ASTAR.with_borrow_mut(|astar| {
+        astar
+            .get_path_strict(
+                stride as usize,
+                height,
+                from_idx,
+                to_idx,
+                max_x,
+                max_y,
+                &blocked,
+                &c.segment_used,
+                &c.used_as_middle,
+                &c.start_source,
+                &c.start_source_multi,
+                &c.end_target,
+                &c.end_target_multi,
+                c.used_points.as_deref(),
+                c.route_from_idx,
+                c.route_to_idx,
+                c.edge_from_id,
+                c.edge_to_id,
+            )
+            .map_err(Error::from_reason)
+    })
+}
+
+#[napi(object)]
+pub struct RelaxedPath {
+    pub path: Vec<u32>,
+    pub cost: u32,
+}
+
+/// `getPathRelaxed(stride, fromIdx, toIdx, maxX, maxY, blocked, constraints) -> { path, cost } | null`。
+#[napi]
+pub fn get_path_relaxed(
+    stride: u32,
+    from_idx: u32,
+    to_idx: u32,
+    max_x: u32,
+    max_y: u32,
+    blocked: Uint8Array,
+    constraints: Object,
+) -> Result<Option<RelaxedPath>> {
+    let height = grid_height(stride, &blocked, "getPathRelaxed")?;
+    let segment_usage: Object = required(&constraints, "segmentUsage")?;
+    let c = Constraints::read(&constraints, &segment_usage)?;
+    let segment_pair: Uint32Array = required(&segment_usage, "segmentPair")?;
+    let segment_pair_multi: Uint8Array = required(&segment_usage, "segmentPairMulti")?;
+    // relaxed 专用：仅在 fallback（不可达）时才会打开
+    let allow_end_segment_reuse: Option<bool> = constraints.get("relaxedAllowEndSegmentReuse")?;
+
+    let result = ASTAR.with_borrow_mut(|astar| {
+        astar
+            .get_path_relaxed(
+                stride as usize,
+                height,
+                from_idx,
+                to_idx,
+                max_x,
+                max_y,
+                &blocked,
+                &c.segment_used,
+                &c.used_as_middle,
+                &segment_pair,
+                &segment_pair_multi,
+                &c.start_source,
+                &c.start_source_multi,
+                &c.end_target,
+                &c.end_target_multi,
+                c.used_points.as_deref(),
+                c.route_from_idx,
+                c.route_to_idx,
+                c.edge_from_id,
+                c.edge_to_id,
+                allow_end_segment_reuse.unwrap_or(false),
+            )
+            .map_err(Error::from_reason)
+    })?;
+    Ok(result.map(|(path, cost)| RelaxedPath { path, cost }))
+}
@@ -0,0 +1,425 @@
+// ============================================================================
+// Python 绑定（feature = "python"，PyO3）
+//
+// 结构：
+// - 这里是扩展模块 `beautiful_mermaid._native`：渲染/校验函数 + 异常类 + `THEMES`；
+// - 对外的 Python 包在 `python/beautiful_mermaid/`：把 meta / validation 的 JSON
+//   转成 dataclass，并重新导出这里的函数与异常（打包见 pyproject.toml，maturin）。
+//
+// 约定：
+// - `RenderOptions` / `AsciiRenderOptions` 的字段一一对应为关键字参数（snake_case）；
+//   `theme="nord"` 先取 `theme::THEMES` 里的配色，再被显式传入的颜色覆盖；
+// - 渲染期间释放 GIL（`Python::detach`），多线程调用可以真正并行；
+// - `BeautifulMermaidError` 的每个变体映射到同名基类下的一个 Python 异常类。
+// ============================================================================
+
+use crate::error::BeautifulMermaidError;
+use crate::theme::{DiagramColors, THEMES};
+use crate::types::{AsciiRenderOptions, AsciiRouting, Backend, RenderOptions};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+create_exception!(
+    _native,
+    MermaidError,
+    PyException,
+    "beautiful-mermaid 的异常基类。"
+);
+create_exception!(
+    _native,
+    JsError,
+    MermaidError,
+    "JS 引擎错误或 JS 抛出的异常。"
+);
+create_exception!(_native, InitError, MermaidError, "JS bundle 初始化失败。");
+create_exception!(
+    _native,
+    JsonError,
+    MermaidError,
+    "JS 返回值的 JSON 解析失败。"
+);
+create_exception!(
+    _native,
+    ParseError,
+    MermaidError,
+    "Mermaid 文本解析失败（native 后端）。"
+);
+create_exception!(_native, RasterError, MermaidError, "PNG 光栅化失败。");
+create_exception!(
+    _native,
+    BackendUnavailableError,
+    MermaidError,
+    "需要的后端没有编译进来。"
+);
+create_exception!(
+    _native,
+    UnroutableError,
+    MermaidError,
+    "有边走不通或要放开约束才走通（fail_on_unroutable=True）。"
+);
+
+fn to_py_err(err: BeautifulMermaidError) -> PyErr {
+    let message = err.to_string();
+    match err {
+        BeautifulMermaidError::Js(_) | BeautifulMermaidError::JsException { .. } => {
+            JsError::new_err(message)
+        }
+        BeautifulMermaidError::Init { .. } => InitError::new_err(message),
+        BeautifulMermaidError::Json { .. } => JsonError::new_err(message),
+        BeautifulMermaidError::Parse { .. } => ParseError::new_err(message),
+        BeautifulMermaidError::Raster { .. } => RasterError::new_err(message),
+        BeautifulMermaidError::BackendUnavailable { .. } => {
+            BackendUnavailableError::new_err(message)
+        }
+        BeautifulMermaidError::Unroutable { .. } => UnroutableError::new_err(message),
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> PyResult<String> {
+    serde_json::to_string(value).map_err(|err| JsonError::new_err(err.to_string()))
+}
+
+fn parse_backend(backend: Option<&str>) -> PyResult<Option<Backend>> {
+    match backend {
+        None => Ok(None),
+        Some("js") => Ok(Some(Backend::Js)),
+        Some("native") => Ok(Some(Backend::Native)),
+        Some(other) => Err(PyValueError::new_err(format!(
+            "未知的 backend: {other:?}（可选 \"js\" / \"native\"）"
+        ))),
+    }
+}
+
+fn parse_routing(routing: Option<&str>) -> PyResult<Option<AsciiRouting>> {
+    match routing {
+        None => Ok(None),
+        Some("strict") => Ok(Some(AsciiRouting::Strict)),
+        Some("relaxed") => Ok(Some(AsciiRouting::Relaxed)),
+        Some("global") => Ok(Some(AsciiRouting::Global)),
+        Some(other) => Err(PyValueError::new_err(format!(
+            "未知的 routing: {other:?}（可选 \"strict\" / \"relaxed\" / \"global\"）"
+        ))),
+    }
+}
+
+fn lookup_theme(theme: Option<&str>) -> PyResult<Option<&'static DiagramColors>> {
+    let Some(name) = theme else {
+        return Ok(None);
+    };
+    THEMES.get(name).map(Some).ok_or_else(|| {
+        let mut names: Vec<&str> = THEMES.keys().copied().collect();
+        names.sort_unstable();
+        PyValueError::new_err(format!(
+            "未知的主题: {name:?}（可选: {}）",
+            names.join(", ")
+        ))
+    })
+}
+
+/// 关键字参数 -> `RenderOptions`（显式颜色优先于主题）。
+#[allow(clippy::too_many_arguments)]
+fn render_options(
+    theme: Option<&str>,
+    bg: Option<String>,
+    fg: Option<String>,
+    line: Option<String>,
+    accent: Option<String>,
+    muted: Option<String>,
+    surface: Option<String>,
+    border: Option<String>,
+    font: Option<String>,
+    padding: Option<f64>,
+    node_spacing: Option<f64>,
+    layer_spacing: Option<f64>,
+    transparent: Option<bool>,
+    id_prefix: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    backend: Option<&str>,
+) -> PyResult<RenderOptions> {
+    let colors = lookup_theme(theme)?;
+    let from_theme = |pick: fn(&DiagramColors) -> Option<String>| colors.and_then(pick);
+    Ok(RenderOptions {
+        bg: bg.or_else(|| from_theme(|c| Some(c.bg.clone()))),
+        fg: fg.or_else(|| from_theme(|c| Some(c.fg.clone()))),
+        line: line.or_else(|| from_theme(|c| c.line.clone())),
+        accent: accent.or_else(|| from_theme(|c| c.accent.clone())),
+        muted: muted.or_else(|| from_theme(|c| c.muted.clone())),
+        surface: surface.or_else(|| from_theme(|c| c.surface.clone())),
+        border: border.or_else(|| from_theme(|c| c.border.clone())),
+        font,
+        padding,
+        node_spacing,
+        layer_spacing,
+        transparent,
+        id_prefix,
+        title,
+        description,
+        backend: parse_backend(backend)?,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn ascii_options(
+    use_ascii: Option<bool>,
+    routing: Option<&str>,
+    turn_penalty: Option<u32>,
+    ts_compatible: Option<bool>,
+    parallel_routing: Option<bool>,
+    fail_on_unroutable: Option<bool>,
+    padding_x: Option<i32>,
+    padding_y: Option<i32>,
+    box_border_padding: Option<i32>,
+    backend: Option<&str>,
+) -> PyResult<AsciiRenderOptions> {
+    Ok(AsciiRenderOptions {
+        use_ascii,
+        routing: parse_routing(routing)?,
+        turn_penalty,
+        ts_compatible,
+        parallel_routing,
+        fail_on_unroutable,
+        padding_x,
+        padding_y,
+        box_border_padding,
+        backend: parse_backend(backend)?,
+    })
+}
+
+/// 渲染 Mermaid -> SVG。
+#[pyfunction]
+#[pyo3(signature = (
+    text, *, theme=None, bg=None, fg=None, line=None, accent=None, muted=None, surface=None,
+    border=None, font=None, padding=None, node_spacing=None, layer_spacing=None,
+    transparent=None, id_prefix=None, title=None, description=None, backend=None
+))]
+#[allow(clippy::too_many_arguments)]
+fn render_svg(
+    py: Python<'_>,
+    text: &str,
+    theme: Option<&str>,
+    bg: Option<String>,
+    fg: Option<String>,
+    line: Option<String>,
+    accent: Option<String>,
+    muted: Option<String>,
+    surface: Option<String>,
+    border: Option<String>,
+    font: Option<String>,
+    padding: Option<f64>,
+    node_spacing: Option<f64>,
+    layer_spacing: Option<f64>,
+    transparent: Option<bool>,
+    id_prefix: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    backend: Option<&str>,
+) -> PyResult<String> {
+    let options = render_options(
+        theme,
+        bg,
+        fg,
+        line,
+        accent,
+        muted,
+        surface,
+        border,
+        font,
+        padding,
+        node_spacing,
+        layer_spacing,
+        transparent,
+        id_prefix,
+        title,
+        description,
+        backend,
+    )?;
+    py.detach(|| crate::render_mermaid(text, &options))
+        .map_err(to_py_err)
+}
+
+/// 渲染 Mermaid -> SVG + meta，返回 `SvgRenderWithMeta` 的 JSON（Python 包里转成 dataclass）。
+#[pyfunction]
+#[pyo3(signature = (
+    text, *, theme=None, bg=None, fg=None, line=None, accent=None, muted=None, surface=None,
+    border=None, font=None, padding=None, node_spacing=None, layer_spacing=None,
+    transparent=None, id_prefix=None, title=None, description=None, backend=None
+))]
+#[allow(clippy::too_many_arguments)]
+fn render_svg_with_meta_json(
+    py: Python<'_>,
+    text: &str,
+    theme: Option<&str>,
+    bg: Option<String>,
+    fg: Option<String>,
+    line: Option<String>,
+    accent: Option<String>,
+    muted: Option<String>,
+    surface: Option<String>,
+    border: Option<String>,
+    font: Option<String>,
+    padding: Option<f64>,
+    node_spacing: Option<f64>,
+    layer_spacing: Option<f64>,
+    transparent: Option<bool>,
+    id_prefix: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    backend: Option<&str>,
+) -> PyResult<String> {
+    let options = render_options(
+        theme,
+        bg,
+        fg,
+        line,
+        accent,
+        muted,
+        surface,
+        border,
+        font,
+        padding,
+        node_spacing,
+        layer_spacing,
+        transparent,
+        id_prefix,
+        title,
+        description,
+        backend,
+    )?;
+    let rendered = py
+        .detach(|| crate::render_mermaid_with_meta(text, &options))
+        .map_err(to_py_err)?;
+    to_json(&rendered)
+}
+
+/// 渲染 Mermaid -> ASCII/Unicode。
+#[pyfunction]
+#[pyo3(signature = (
+    text, *, use_ascii=None, routing=None, turn_penalty=None, ts_compatible=None,
+    parallel_routing=None, fail_on_unroutable=None, padding_x=None, padding_y=None,
+    box_border_padding=None, backend=None
+))]
+#[allow(clippy::too_many_arguments)]
+fn render_ascii(
+    py: Python<'_>,
+    text: &str,
+    use_ascii: Option<bool>,
+    routing: Option<&str>,
+    turn_penalty: Option<u32>,
+    ts_compatible: Option<bool>,
+    parallel_routing: Option<bool>,
+    fail_on_unroutable: Option<bool>,
+    padding_x: Option<i32>,
+    padding_y: Option<i32>,
+    box_border_padding: Option<i32>,
+    backend: Option<&str>,
+) -> PyResult<String> {
+    let options = ascii_options(
+        use_ascii,
+        routing,
+        turn_penalty,
+        ts_compatible,
+        parallel_routing,
+        fail_on_unroutable,
+        padding_x,
+        padding_y,
+        box_border_padding,
+        backend,
+    )?;
+    py.detach(|| crate::render_mermaid_ascii(text, &options))
+        .map_err(to_py_err)
+}
+
+/// 渲染 Mermaid -> ASCII/Unicode + meta，返回 `AsciiRenderWithMeta` 的 JSON。
+#[pyfunction]
+#[pyo3(signature = (
+    text, *, use_ascii=None, routing=None, turn_penalty=None, ts_compatible=None,
+    parallel_routing=None, fail_on_unroutable=None, padding_x=None, padding_y=None,
+    box_border_padding=None, backend=None
+))]
+#[allow(clippy::too_many_arguments)]
+fn render_ascii_with_meta_json(
+    py: Python<'_>,
+    text: &str,
+    use_ascii: Option<bool>,
+    routing: Option<&str>,
+    turn_penalty: Option<u32>,
+    ts_compatible: Option<bool>,
+    parallel_routing: Option<bool>,
+    fail_on_unroutable: Option<bool>,
+    padding_x: Option<i32>,
+    padding_y: Option<i32>,
+    box_border_padding: Option<i32>,
+    backend: Option<&str>,
+) -> PyResult<String> {
+    let options = ascii_options(
+        use_ascii,
+        routing,
+        turn_penalty,
+        ts_compatible,
+        parallel_routing,
+        fail_on_unroutable,
+        padding_x,
+        padding_y,
+        box_border_padding,
+        backend,
+    )?;
+    let rendered = py
+        .detach(|| crate::render_mermaid_ascii_with_meta(text, &options))
+        .map_err(to_py_err)?;
+    to_json(&rendered)
+}
+
+/// 校验 Mermaid 语法，返回 `MermaidValidation` 的 JSON。
+#[pyfunction]
+fn validate_json(py: Python<'_>, text: &str) -> PyResult<String> {
+    let validation = py
+        .detach(|| crate::validate_mermaid(text))
+        .map_err(to_py_err)?;
+    to_json(&validation)
+}
+
+/// `THEMES` -> `{name: {"bg": ..., "fg": ..., "line": ... | None, ...}}`。
+fn themes_dict(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let themes = PyDict::new(py);
+    let mut names: Vec<&str> = THEMES.keys().copied().collect();
+    names.sort_unstable();
+    for name in names {
+        let colors = &THEMES[name];
+        let entry = PyDict::new(py);
+        entry.set_item("bg", &colors.bg)?;
+        entry.set_item("fg", &colors.fg)?;
+        entry.set_item("line", &colors.line)?;
+        entry.set_item("accent", &colors.accent)?;
+        entry.set_item("muted", &colors.muted)?;
+        entry.set_item("surface", &colors.surface)?;
+        entry.set_item("border", &colors.border)?;
+        themes.set_item(name, entry)?;
+    }
+    Ok(themes)
+}
+
+#[pymodule]
+fn _native(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add_function(wrap_pyfunction!(render_svg, m)?)?;
+    m.add_function(wrap_pyfunction!(render_svg_with_meta_json, m)?)?;
+    m.add_function(wrap_pyfunction!(render_ascii, m)?)?;
+    m.add_function(wrap_pyfunction!(render_ascii_with_meta_json, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_json, m)?)?;
+    m.add("THEMES", themes_dict(py)?)?;
+    m.add("MermaidError", py.get_type::<MermaidError>())?;
+    m.add("JsError", py.get_type::<JsError>())?;
+    m.add("InitError", py.get_type::<InitError>())?;
+    m.add("JsonError", py.get_type::<JsonError>())?;
+    m.add("ParseError", py.get_type::<ParseError>())?;
+    m.add("RasterError", py.get_type::<RasterError>())?;
+    m.add(
+        "BackendUnavailableError",
+        py.get_type::<BackendUnavailableError>(),
+    )?;
+    m.add("UnroutableError", py.get_type::<UnroutableError>())?;
+    Ok(())
+}
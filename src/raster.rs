@@ -0,0 +1,378 @@
+// ============================================================================
+// PNG 光栅化（feature = "png"）
+//
+// 背景：
+// - 下游（聊天工具、PDF 生成器）经常只收位图，不收 SVG；
+// - 这里用纯 Rust 的 resvg/tiny-skia 把 `render_mermaid` 的 SVG 画成 PNG，不依赖系统库。
+//
+// 两个需要预处理的点：
+// - 颜色：SVG 里几乎所有颜色都是 CSS 变量（`var(--_line)`）+ `color-mix()`，
+//   resvg 不支持 CSS 变量，这里先按 `<style>` 里的定义把它们解析成具体颜色；
+// - 字体：随 crate 打包 DejaVu Sans/Bold/Mono，不读系统字体，任何环境输出一致。
+//   （DejaVu 不含 CJK 字形，中文会显示为缺字框；需要时请先用系统字体转换。）
+// ============================================================================
+
+use crate::error::{BeautifulMermaidError, Result};
+use crate::types::RasterOptions;
+use once_cell::sync::Lazy;
+use resvg::{tiny_skia, usvg};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// SVG 的 px 约定：1in = 96px（CSS 规范）。
+const CSS_DPI: f32 = 96.0;
+
+static FONT_DB: Lazy<Arc<usvg::fontdb::Database>> = Lazy::new(|| {
+    let mut db = usvg::fontdb::Database::new();
+    for data in [
+        &include_bytes!("../vendor/fonts/DejaVuSans.ttf")[..],
+        &include_bytes!("../vendor/fonts/DejaVuSans-Bold.ttf")[..],
+        &include_bytes!("../vendor/fonts/DejaVuSansMono.ttf")[..],
+    ] {
+        db.load_font_data(data.to_vec());
+    }
+
+    // SVG 里写的是 `'Inter', system-ui, sans-serif` / `'JetBrains Mono', ..., monospace`，
+    // Inter/JetBrains Mono 不在库里，最终都会落到这里的通用族。
+    db.set_sans_serif_family("DejaVu Sans");
+    db.set_serif_family("DejaVu Sans");
+    db.set_cursive_family("DejaVu Sans");
+    db.set_fantasy_family("DejaVu Sans");
+    db.set_monospace_family("DejaVu Sans Mono");
+    Arc::new(db)
+});
+
+fn raster_error(message: impl Into<String>) -> BeautifulMermaidError {
+    BeautifulMermaidError::Raster {
+        message: message.into(),
+    }
+}
+
+// ----------------------------------------------------------------------------
+// CSS 变量解析
+// ----------------------------------------------------------------------------
+
+/// 在 `text` 里找到与 `open`（指向 `(`）配对的 `)`。
+fn matching_paren(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (idx, ch) in text[open..].char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + idx);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 按顶层逗号切分函数参数（忽略括号内的逗号）。
+fn split_args(args: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (idx, ch) in args.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(args[start..idx].trim());
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(args[start..].trim());
+    parts
+}
+
+/// `color-mix(in srgb, A p%, B q%)` 的一个颜色参数：`(颜色表达式, 百分比)`。
+fn split_mix_stop(stop: &str) -> (&str, Option<f32>) {
+    if let Some((color, percent)) = stop.rsplit_once(char::is_whitespace)
+        && let Some(value) = percent.strip_suffix('%')
+        && let Ok(value) = value.trim().parse::<f32>()
+    {
+        return (color.trim(), Some(value / 100.0));
+    }
+    (stop, None)
+}
+
+fn format_color(color: svgtypes::Color) -> String {
+    if color.alpha == 255 {
+        format!("#{:02x}{:02x}{:02x}", color.red, color.green, color.blue)
+    } else {
+        format!(
+            "#{:02x}{:02x}{:02x}{:02x}",
+            color.red, color.green, color.blue, color.alpha
+        )
+    }
+}
+
+/// 按 CSS Color 5 的规则在 sRGB 空间混色（预乘 alpha）。
+fn mix_colors(a: svgtypes::Color, pa: f32, b: svgtypes::Color, pb: f32) -> svgtypes::Color {
+    let total = pa + pb;
+    let (pa, pb) = if total > 0.0 {
+        (pa / total, pb / total)
+    } else {
+        (0.5, 0.5)
+    };
+    let alpha_a = f32::from(a.alpha) / 255.0;
+    let alpha_b = f32::from(b.alpha) / 255.0;
+    let alpha = alpha_a * pa + alpha_b * pb;
+    let channel = |ca: u8, cb: u8| {
+        if alpha == 0.0 {
+            return 0;
+        }
+        let value = (f32::from(ca) * alpha_a * pa + f32::from(cb) * alpha_b * pb) / alpha;
+        value.round().clamp(0.0, 255.0) as u8
+    };
+    svgtypes::Color::new_rgba(
+        channel(a.red, b.red),
+        channel(a.green, b.green),
+        channel(a.blue, b.blue),
+        (alpha * 255.0).round().clamp(0.0, 255.0) as u8,
+    )
+}
+
+/// CSS 变量表：根 `<svg style>` 上的 `--bg/--fg/...` + `<style>` 里 `svg { --_xxx: ... }` 的派生变量。
+struct CssVars {
+    vars: HashMap<String, String>,
+}
+
+impl CssVars {
+    fn from_svg(svg: &str) -> Self {
+        let mut vars = HashMap::new();
+
+        let mut collect = |declarations: &str| {
+            for declaration in declarations.split(';') {
+                if let Some((name, value)) = declaration.split_once(':')
+                    && name.trim().starts_with("--")
+                {
+                    vars.insert(name.trim().to_string(), value.trim().to_string());
+                }
+            }
+        };
+
+        if let Some(style) = root_style(svg) {
+            collect(style);
+        }
+        if let Some(start) = svg.find("<style>")
+            && let Some(end) = svg[start..].find("</style>")
+        {
+            let css = &svg[start..start + end];
+            let mut rest = css;
+            while let Some(open) = rest.find('{') {
+                let Some(close) = rest[open..].find('}') else {
+                    break;
+                };
+                let body = &rest[open + 1..open + close];
+                // 去掉 /* ... */ 注释
+                let mut cleaned = String::new();
+                let mut body_rest = body;
+                while let Some(comment) = body_rest.find("/*") {
+                    cleaned.push_str(&body_rest[..comment]);
+                    body_rest = body_rest[comment..]
+                        .find("*/")
+                        .map_or("", |end| &body_rest[comment + end + 2..]);
+                }
+                cleaned.push_str(body_rest);
+                collect(&cleaned);
+                rest = &rest[open + close + 1..];
+            }
+        }
+
+        Self { vars }
+    }
+
+    /// 把一个 CSS 颜色表达式求值成具体颜色；无法求值时返回 None。
+    fn eval(&self, expr: &str, depth: usize) -> Option<svgtypes::Color> {
+        // 变量之间互相引用，限制深度避免恶意输入导致死循环
+        if depth > 16 {
+            return None;
+        }
+        let expr = expr.trim();
+
+        if let Some(inner) = expr.strip_prefix("var(").and_then(|s| s.strip_suffix(')')) {
+            let args = split_args(inner);
+            return match self.vars.get(args[0]) {
+                Some(value) => self.eval(value, depth + 1),
+                None => args
+                    .get(1)
+                    .and_then(|fallback| self.eval(fallback, depth + 1)),
+            };
+        }
+
+        if let Some(inner) = expr
+            .strip_prefix("color-mix(")
+            .and_then(|s| s.strip_suffix(')'))
+        {
+            let args = split_args(inner);
+            if args.len() != 3 || !args[0].eq_ignore_ascii_case("in srgb") {
+                return None;
+            }
+            let (color_a, pa) = split_mix_stop(args[1]);
+            let (color_b, pb) = split_mix_stop(args[2]);
+            let (pa, pb) = match (pa, pb) {
+                (Some(pa), Some(pb)) => (pa, pb),
+                (Some(pa), None) => (pa, 1.0 - pa),
+                (None, Some(pb)) => (1.0 - pb, pb),
+                (None, None) => (0.5, 0.5),
+            };
+            return Some(mix_colors(
+                self.eval(color_a, depth + 1)?,
+                pa,
+                self.eval(color_b, depth + 1)?,
+                pb,
+            ));
+        }
+
+        svgtypes::Color::from_str(expr).ok()
+    }
+
+    /// 把文档里所有 `var(...)` / `color-mix(...)` 替换成具体颜色。
+    fn resolve_document(&self, svg: &str) -> String {
+        let mut out = String::with_capacity(svg.len());
+        let mut rest = svg;
+        loop {
+            let next = ["var(", "color-mix("]
+                .iter()
+                .filter_map(|needle| rest.find(needle).map(|idx| (idx, needle.len())))
+                .min();
+            let Some((start, needle_len)) = next else {
+                break;
+            };
+            let Some(end) = matching_paren(rest, start + needle_len - 1) else {
+                break;
+            };
+            out.push_str(&rest[..start]);
+            match self.eval(&rest[start..=end], 0) {
+                Some(color) => out.push_str(&format_color(color)),
+                None => out.push_str(&rest[start..=end]),
+            }
+            rest = &rest[end + 1..];
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// 根 `<svg>` 标签的 `style` 属性值。
+fn root_style(svg: &str) -> Option<&str> {
+    let tag_end = svg.find('>')?;
+    let tag = &svg[..tag_end];
+    let start = tag.find(" style=\"")? + " style=\"".len();
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len])
+}
+
+/// 根 `<svg>` 上的 `background:`（`transparent: true` 渲染出来的 SVG 没有这一项）。
+fn root_background(svg: &str, vars: &CssVars) -> Option<svgtypes::Color> {
+    root_style(svg)?
+        .split(';')
+        .filter_map(|declaration| declaration.split_once(':'))
+        .find(|(name, _)| name.trim() == "background")
+        .and_then(|(_, value)| vars.eval(value, 0))
+}
+
+// ----------------------------------------------------------------------------
+// 入口
+// ----------------------------------------------------------------------------
+
+/// SVG 字符串 -> PNG 字节。
+pub(crate) fn svg_to_png(svg: &str, options: &RasterOptions) -> Result<Vec<u8>> {
+    let scale = options.scale.unwrap_or(1.0);
+    let dpi = options.dpi.unwrap_or(CSS_DPI);
+    if !(scale.is_finite() && scale > 0.0) {
+        return Err(raster_error(format!("scale 必须是正数，当前为 {scale}")));
+    }
+    if !(dpi.is_finite() && dpi > 0.0) {
+        return Err(raster_error(format!("dpi 必须是正数，当前为 {dpi}")));
+    }
+    let zoom = scale * dpi / CSS_DPI;
+
+    let vars = CssVars::from_svg(svg);
+    let background = match options.background.as_deref() {
+        Some(value) => Some(
+            svgtypes::Color::from_str(value.trim())
+                .map_err(|err| raster_error(format!("background 颜色无效 `{value}`: {err}")))?,
+        ),
+        None => root_background(svg, &vars),
+    };
+    let resolved = vars.resolve_document(svg);
+
+    let usvg_options = usvg::Options {
+        fontdb: FONT_DB.clone(),
+        font_family: "DejaVu Sans".to_string(),
+        ..Default::default()
+    };
+    let tree = usvg::Tree::from_str(&resolved, &usvg_options)
+        .map_err(|err| raster_error(format!("解析 SVG 失败: {err}")))?;
+
+    let size = tree.size().to_int_size().scale_by(zoom).ok_or_else(|| {
+        raster_error(format!(
+            "图像尺寸无效: {}x{} * {zoom}",
+            tree.size().width(),
+            tree.size().height()
+        ))
+    })?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height()).ok_or_else(|| {
+        raster_error(format!(
+            "无法分配 {}x{} 的画布",
+            size.width(),
+            size.height()
+        ))
+    })?;
+
+    if let Some(color) = background {
+        pixmap.fill(tiny_skia::Color::from_rgba8(
+            color.red,
+            color.green,
+            color.blue,
+            color.alpha,
+        ));
+    }
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(zoom, zoom),
+        &mut pixmap.as_mut(),
+    );
+
+    encode_png(&pixmap, dpi)
+}
+
+/// 编码 PNG，并写入 pHYs（物理分辨率 = dpi），让打印/排版软件按正确尺寸摆放。
+fn encode_png(pixmap: &tiny_skia::Pixmap, dpi: f32) -> Result<Vec<u8>> {
+    let data: Vec<u8> = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+
+    let mut out = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut out, pixmap.width(), pixmap.height());
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let pixels_per_meter = (dpi / 0.0254).round() as u32;
+        encoder.set_pixel_dims(Some(png::PixelDimensions {
+            xppu: pixels_per_meter,
+            yppu: pixels_per_meter,
+            unit: png::Unit::Meter,
+        }));
+        let mut writer = encoder
+            .write_header()
+            .map_err(|err| raster_error(format!("写入 PNG 头失败: {err}")))?;
+        writer
+            .write_image_data(&data)
+            .map_err(|err| raster_error(format!("写入 PNG 数据失败: {err}")))?;
+    }
+    Ok(out)
+}
@@ -0,0 +1,729 @@
+// ============================================================================
+// 走线网格的调试视图：占用表快照 + 搜索探测 -> 带注释的文本 / SVG 热力图
+//
+// 背景：
+// - 排查“这条边为什么绕远 / 走不通”时，以前只能在 example 里手工打印路径，
+//   看不到 A* 到底在哪里被 blocked / crossing / 线段占用挡了回来。
+//
+// 这里提供：
+// - `RoutingGrid::snapshot()`：blocked 掩码、used points 方向位、segment usage 表的只读拷贝；
+// - `SearchProbe`（`RoutingGrid::start_probe` / `take_probe`）：展开过的格子（热度）
+//   与被拒绝的单步（按规则计数）；
+// - `DebugFrame`：快照 + 探测 + 最终路径，`to_text()` / `to_svg()` 画出来；
+//   `animated_svg(frames)` 把多帧串成逐帧播放的 SVG（SMIL），
+//   native flowchart 逐条边的帧见 `render_mermaid_ascii_with_debug`。
+//
+// 说明：
+// - 快照与探测都是旁路记录，不改变占用表，也不改变搜索结果；
+// - 只画“有内容”的窗口（blocked / 占用 / 搜索到过的格子，外扩一格）：
+//   flowchart 的走线网格四周留了很宽的搜索余量，整张画出来几乎全是空白。
+// ============================================================================
+
+use super::{CONNECT_DOWN, CONNECT_LEFT, CONNECT_RIGHT, CONNECT_UP, GridPoint, RoutingGrid};
+use crate::native_pathfinder::{ProbeEvents, Rejection};
+use crate::types::StepRejections;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+// ----------------------------------------------------------------------------
+// 快照
+// ----------------------------------------------------------------------------
+
+/// segment usage 表里一条已占用的单位线段。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentSnapshot {
+    /// 线段两端（`from` 在左 / 上）。
+    pub from: GridPoint,
+    pub to: GridPoint,
+    /// 被某条路径当作中段使用（中段不与任何路径共享）。
+    pub used_as_middle: bool,
+    /// 第一对经过它的端点 id（source, target）；id 超过 65535 时不记。
+    pub pair: Option<(u32, u32)>,
+    /// 不止一对端点经过它。
+    pub pair_multi: bool,
+    /// 作为起点段时的 source id。
+    pub start_source: Option<u32>,
+    pub start_source_multi: bool,
+    /// 作为终点段时的 target id。
+    pub end_target: Option<u32>,
+    pub end_target_multi: bool,
+}
+
+/// `RoutingGrid::snapshot` 的结果：只保存包含全部 blocked 与已占用格子的矩形区域。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GridSnapshot {
+    /// 整张网格的尺寸。
+    pub width: u32,
+    pub height: u32,
+    /// 保存下来的区域（左上角 + 宽高）；区域外都是空格子。
+    pub origin: GridPoint,
+    pub region_width: u32,
+    pub region_height: u32,
+    blocked: Vec<bool>,
+    connections: Vec<u8>,
+    /// 已占用的单位线段（按 `from` 行优先）。
+    pub segments: Vec<SegmentSnapshot>,
+}
+
+impl GridSnapshot {
+    fn region_index(&self, point: GridPoint) -> Option<usize> {
+        let x = point.x.checked_sub(self.origin.x)?;
+        let y = point.y.checked_sub(self.origin.y)?;
+        (x < self.region_width && y < self.region_height)
+            .then(|| (y * self.region_width + x) as usize)
+    }
+
+    pub fn is_blocked(&self, point: GridPoint) -> bool {
+        self.region_index(point)
+            .is_some_and(|index| self.blocked[index])
+    }
+
+    /// 格子上的 used points 方向位（`CONNECT_*`）。
+    pub fn connections(&self, point: GridPoint) -> u8 {
+        self.region_index(point)
+            .map_or(0, |index| self.connections[index])
+    }
+
+    /// 有内容的格子范围（blocked 或已占用）。
+    fn bounds(&self) -> Option<Window> {
+        (self.region_width > 0 && self.region_height > 0).then(|| Window {
+            min: self.origin,
+            max: GridPoint::new(
+                self.origin.x + self.region_width - 1,
+                self.origin.y + self.region_height - 1,
+            ),
+        })
+    }
+}
+
+impl RoutingGrid {
+    /// 当前 blocked 掩码、used points 方向位与 segment usage 表的快照（见 `debug`）。
+    pub fn snapshot(&self) -> GridSnapshot {
+        let mut bounds: Option<Window> = None;
+        for index in 0..self.width * self.height {
+            if self.blocked[index] != 0 || self.used_points[index] != 0 {
+                let point = self.point(index as u32);
+                bounds = Some(Window::include(bounds, point));
+            }
+        }
+
+        let mut segments = Vec::new();
+        for (segment, &used) in self.segments.segment_used.iter().enumerate() {
+            if used == 0 {
+                continue;
+            }
+            let cell = (segment / 2) as u32;
+            let other = if segment % 2 == 0 {
+                cell + 1
+            } else {
+                cell + self.width as u32
+            };
+            let (from, to) = (self.point(cell), self.point(other));
+            bounds = Some(Window::include(Some(Window::include(bounds, from)), to));
+            let usage = &self.segments;
+            let id = |value: u32| (value != 0).then_some(value);
+            let pair = usage.segment_pair[segment];
+            segments.push(SegmentSnapshot {
+                from,
+                to,
+                used_as_middle: usage.used_as_middle[segment] != 0,
+                pair: id(pair).map(|pair| (pair >> 16, pair & 0xFFFF)),
+                pair_multi: usage.segment_pair_multi[segment] != 0,
+                start_source: id(usage.start_source[segment]),
+                start_source_multi: usage.start_source_multi[segment] != 0,
+                end_target: id(usage.end_target[segment]),
+                end_target_multi: usage.end_target_multi[segment] != 0,
+            });
+        }
+
+        let mut snapshot = GridSnapshot {
+            width: self.width as u32,
+            height: self.height as u32,
+            segments,
+            ..GridSnapshot::default()
+        };
+        if let Some(bounds) = bounds {
+            snapshot.origin = bounds.min;
+            snapshot.region_width = bounds.max.x - bounds.min.x + 1;
+            snapshot.region_height = bounds.max.y - bounds.min.y + 1;
+            for y in bounds.min.y..=bounds.max.y {
+                for x in bounds.min.x..=bounds.max.x {
+                    let index = y as usize * self.width + x as usize;
+                    snapshot.blocked.push(self.blocked[index] != 0);
+                    snapshot.connections.push(self.used_points[index]);
+                }
+            }
+        }
+        snapshot
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 搜索探测
+// ----------------------------------------------------------------------------
+
+/// `start_probe` 之后累计的搜索过程。
+///
+/// `expanded` 只有 A* 展开的格子；A* 之前的封闭探测看过的格子不记（数量见 `SearchStats.probe_cells`），
+/// 它拒绝的单步与 A* 的一起记进 `rejected`。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchProbe {
+    /// 记录到的搜索次数（每次 `route` 算一次，走不通的也算）。
+    pub searches: u32,
+    /// 每个格子被展开的次数（多次搜索累加）。
+    pub expanded: HashMap<GridPoint, u32>,
+    /// 被拒绝的单步 `(from, to)` 及按规则的次数。
+    pub rejected: HashMap<(GridPoint, GridPoint), StepRejections>,
+}
+
+impl SearchProbe {
+    /// 并入一次搜索的事件（`width` 用来把 cell 下标换成坐标）。
+    pub(super) fn add(&mut self, events: &ProbeEvents, width: u32) {
+        let point = |index: u32| GridPoint::new(index % width, index / width);
+        self.searches += 1;
+        for &cell in &events.expanded {
+            *self.expanded.entry(point(cell)).or_default() += 1;
+        }
+        for &(from, to, rejection) in &events.rejected {
+            let counts = self.rejected.entry((point(from), point(to))).or_default();
+            match rejection {
+                Rejection::Blocked => counts.blocked += 1,
+                Rejection::Crossing => counts.crossing += 1,
+                Rejection::Segment => counts.segment += 1,
+            }
+        }
+    }
+
+    /// 所有格子的展开次数之和。
+    pub fn expansions(&self) -> u64 {
+        self.expanded.values().map(|&count| u64::from(count)).sum()
+    }
+
+    /// 按规则汇总的拒绝次数。
+    pub fn rejections(&self) -> StepRejections {
+        let mut total = StepRejections::default();
+        for counts in self.rejected.values() {
+            total.blocked += counts.blocked;
+            total.crossing += counts.crossing;
+            total.segment += counts.segment;
+        }
+        total
+    }
+
+    /// 搜索到过的格子范围（展开过的格子 + 被拒单步的两端）。
+    fn bounds(&self) -> Option<Window> {
+        let mut bounds = None;
+        for &point in self.expanded.keys() {
+            bounds = Some(Window::include(bounds, point));
+        }
+        for &(from, to) in self.rejected.keys() {
+            bounds = Some(Window::include(Some(Window::include(bounds, from)), to));
+        }
+        bounds
+    }
+
+    /// 以 `to` 为目标格被拒绝的次数（按规则汇总；文本视图在目标格上标注）。
+    fn rejections_into(&self) -> HashMap<GridPoint, StepRejections> {
+        let mut out: HashMap<GridPoint, StepRejections> = HashMap::new();
+        for (&(_, to), counts) in &self.rejected {
+            let entry = out.entry(to).or_default();
+            entry.blocked += counts.blocked;
+            entry.crossing += counts.crossing;
+            entry.segment += counts.segment;
+        }
+        out
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 帧
+// ----------------------------------------------------------------------------
+
+/// 一帧调试视图：搜索前的网格 + 这段时间的搜索过程 + 最终路径。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DebugFrame {
+    /// 标题（native flowchart 的帧是 `A -> B`、label 与走线结果）。
+    pub title: String,
+    /// 搜索开始前的网格（探测到的拒绝都是相对它而言的）。
+    pub grid: GridSnapshot,
+    pub probe: SearchProbe,
+    /// 最终走出的逐格路径（走不通或没有搜索时为空）。
+    pub path: Vec<GridPoint>,
+}
+
+impl DebugFrame {
+    /// 带坐标尺的文本视图：占用图、搜索热度图、segment usage 表。
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "{}", self.title);
+        let Some(window) = self.window() else {
+            out.push_str("（网格是空的）\n");
+            return out;
+        };
+        let rejections = self.probe.rejections();
+        let _ = writeln!(
+            out,
+            "窗口 ({},{})-({},{})，网格 {}×{}；搜索 {} 次，展开 {} 格次；拒绝 blocked {} / crossing {} / segment {}",
+            window.min.x,
+            window.min.y,
+            window.max.x,
+            window.max.y,
+            self.grid.width,
+            self.grid.height,
+            self.probe.searches,
+            self.probe.expansions(),
+            rejections.blocked,
+            rejections.crossing,
+            rejections.segment,
+        );
+
+        let path: HashMap<GridPoint, ()> = self.path.iter().map(|&point| (point, ())).collect();
+        out.push_str("\n占用（# = blocked，线条 = used points 方向位，* = 本帧路径）：\n");
+        write_map(&mut out, window, |point| {
+            if path.contains_key(&point) {
+                '*'
+            } else if self.grid.is_blocked(point) {
+                '#'
+            } else {
+                connection_glyph(self.grid.connections(point))
+            }
+        });
+
+        if self.probe.searches == 0 {
+            out.push_str("\n搜索：本帧没有搜索\n");
+        } else {
+            let into = self.probe.rejections_into();
+            out.push_str(
+                "\n搜索（1-9 = 展开次数，+ = 10 次以上；c / s = 作为目标格被 crossing / segment 规则拒绝；# = blocked）：\n",
+            );
+            write_map(&mut out, window, |point| {
+                let rejected = into.get(&point).copied().unwrap_or_default();
+                if rejected.crossing > 0 && rejected.crossing >= rejected.segment {
+                    'c'
+                } else if rejected.segment > 0 {
+                    's'
+                } else if let Some(&count) = self.probe.expanded.get(&point) {
+                    heat_digit(count)
+                } else if self.grid.is_blocked(point) {
+                    '#'
+                } else {
+                    '.'
+                }
+            });
+        }
+
+        if self.grid.segments.is_empty() {
+            out.push_str("\n线段占用：无\n");
+        } else {
+            let _ = writeln!(
+                out,
+                "\n线段占用（{} 段；+ = 不止一个 id）：",
+                self.grid.segments.len()
+            );
+            for segment in &self.grid.segments {
+                out.push_str(&describe_segment(segment));
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// SVG 热力图（单帧，不带动画）。
+    pub fn to_svg(&self) -> String {
+        svg_document(std::slice::from_ref(self), None)
+    }
+
+    /// 快照、探测与路径的并集外扩一格（不超出网格）。
+    fn window(&self) -> Option<Window> {
+        let mut bounds = self.grid.bounds();
+        if let Some(probe) = self.probe.bounds() {
+            bounds = Some(bounds.map_or(probe, |bounds| bounds.union(probe)));
+        }
+        for &point in &self.path {
+            bounds = Some(Window::include(bounds, point));
+        }
+        bounds.map(|bounds| bounds.grow(self.grid.width, self.grid.height))
+    }
+}
+
+/// `render_mermaid_ascii_with_debug` 的结果：字符画 + 按走线顺序的调试帧。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AsciiRenderWithDebug {
+    pub text: String,
+    /// 最后一次布局尝试里每次走线一帧：复用平行边路径的边没有自己的帧，
+    /// global 的 rip-up-and-reroute 每重走一条边再加一帧。坐标是走线网格坐标（BT 不翻转）。
+    pub frames: Vec<DebugFrame>,
+}
+
+/// 把多帧串成逐帧播放的 SVG（SMIL，循环播放；每帧 `seconds_per_frame` 秒）。
+///
+/// 所有帧共用一个窗口；不支持 SMIL 的查看器只显示最后一帧。
+pub fn animated_svg(frames: &[DebugFrame], seconds_per_frame: f64) -> String {
+    svg_document(frames, Some(seconds_per_frame))
+}
+
+// ----------------------------------------------------------------------------
+// 窗口
+// ----------------------------------------------------------------------------
+
+/// 网格上的矩形范围（含两端）。
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    min: GridPoint,
+    max: GridPoint,
+}
+
+impl Window {
+    fn include(window: Option<Window>, point: GridPoint) -> Window {
+        match window {
+            None => Window {
+                min: point,
+                max: point,
+            },
+            Some(window) => Window {
+                min: GridPoint::new(window.min.x.min(point.x), window.min.y.min(point.y)),
+                max: GridPoint::new(window.max.x.max(point.x), window.max.y.max(point.y)),
+            },
+        }
+    }
+
+    fn union(self, other: Window) -> Window {
+        Window::include(Some(Window::include(Some(self), other.min)), other.max)
+    }
+
+    fn grow(self, width: u32, height: u32) -> Window {
+        Window {
+            min: GridPoint::new(self.min.x.saturating_sub(1), self.min.y.saturating_sub(1)),
+            max: GridPoint::new(
+                (self.max.x + 1)
+                    .min(width.saturating_sub(1))
+                    .max(self.max.x),
+                (self.max.y + 1)
+                    .min(height.saturating_sub(1))
+                    .max(self.max.y),
+            ),
+        }
+    }
+
+    fn contains(&self, point: GridPoint) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    fn columns(&self) -> u32 {
+        self.max.x - self.min.x + 1
+    }
+
+    fn rows(&self) -> u32 {
+        self.max.y - self.min.y + 1
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 文本
+// ----------------------------------------------------------------------------
+
+/// 带坐标尺的字符图：上面两行是 x 的十位 / 个位，左边是 y。
+fn write_map(out: &mut String, window: Window, cell: impl Fn(GridPoint) -> char) {
+    let label_width = window.max.y.to_string().len();
+    let pad = " ".repeat(label_width + 1);
+    let mut tens = pad.clone();
+    let mut units = pad;
+    for x in window.min.x..=window.max.x {
+        tens.push(if x % 10 == 0 || x == window.min.x {
+            char::from_digit(x / 10 % 10, 10).expect("个位数")
+        } else {
+            ' '
+        });
+        units.push(char::from_digit(x % 10, 10).expect("个位数"));
+    }
+    let _ = writeln!(out, "{}", tens.trim_end());
+    let _ = writeln!(out, "{units}");
+    for y in window.min.y..=window.max.y {
+        let row: String = (window.min.x..=window.max.x)
+            .map(|x| cell(GridPoint::new(x, y)))
+            .collect();
+        let _ = writeln!(out, "{y:>label_width$} {row}");
+    }
+}
+
+/// used points 方向位 -> 框线字符（空格子是 `.`）。
+fn connection_glyph(bits: u8) -> char {
+    let left = bits & CONNECT_LEFT != 0;
+    let right = bits & CONNECT_RIGHT != 0;
+    let up = bits & CONNECT_UP != 0;
+    let down = bits & CONNECT_DOWN != 0;
+    match (up, down, left, right) {
+        (false, false, false, false) => '.',
+        (false, false, true, false) => '╴',
+        (false, false, false, true) => '╶',
+        (true, false, false, false) => '╵',
+        (false, true, false, false) => '╷',
+        (false, false, true, true) => '─',
+        (true, true, false, false) => '│',
+        (false, true, false, true) => '┌',
+        (false, true, true, false) => '┐',
+        (true, false, false, true) => '└',
+        (true, false, true, false) => '┘',
+        (true, true, false, true) => '├',
+        (true, true, true, false) => '┤',
+        (false, true, true, true) => '┬',
+        (true, false, true, true) => '┴',
+        (true, true, true, true) => '┼',
+    }
+}
+
+fn heat_digit(count: u32) -> char {
+    match count {
+        0 => '.',
+        1..=9 => char::from_digit(count, 10).expect("个位数"),
+        _ => '+',
+    }
+}
+
+fn describe_segment(segment: &SegmentSnapshot) -> String {
+    let id = |value: Option<u32>, multi: bool| {
+        let mut text = value.map_or_else(|| "-".to_string(), |value| value.to_string());
+        if multi {
+            text.push('+');
+        }
+        text
+    };
+    let mut line = format!(
+        "  ({},{})-({},{})  {}",
+        segment.from.x,
+        segment.from.y,
+        segment.to.x,
+        segment.to.y,
+        if segment.used_as_middle {
+            "中段"
+        } else {
+            "端段"
+        },
+    );
+    let _ = write!(
+        line,
+        "  起点 {}  终点 {}",
+        id(segment.start_source, segment.start_source_multi),
+        id(segment.end_target, segment.end_target_multi),
+    );
+    if let Some((source, target)) = segment.pair {
+        let _ = write!(line, "  端点对 {source}->{target}");
+        if segment.pair_multi {
+            line.push('+');
+        }
+    }
+    line
+}
+
+// ----------------------------------------------------------------------------
+// SVG
+// ----------------------------------------------------------------------------
+
+const CELL: u32 = 12;
+const PAD: u32 = 8;
+const HEADER: u32 = 46;
+const MIN_WIDTH: u32 = 560;
+
+const BLOCKED_FILL: &str = "#d1d5db";
+const HEAT_FILL: &str = "#f97316";
+const MIDDLE_STROKE: &str = "#111827";
+const END_STROKE: &str = "#6b7280";
+const PATH_STROKE: &str = "#2563eb";
+const CROSSING_STROKE: &str = "#dc2626";
+const SEGMENT_STROKE: &str = "#7c3aed";
+
+fn svg_document(frames: &[DebugFrame], seconds_per_frame: Option<f64>) -> String {
+    let window = frames
+        .iter()
+        .filter_map(DebugFrame::window)
+        .reduce(Window::union);
+    let (columns, rows) = window.map_or((0, 0), |window| (window.columns(), window.rows()));
+    let width = (columns * CELL + PAD * 2).max(MIN_WIDTH);
+    let height = rows * CELL + PAD * 2 + HEADER;
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="monospace">"#
+    );
+    let _ = writeln!(
+        out,
+        r##"<rect width="{width}" height="{height}" fill="#ffffff"/>"##
+    );
+    let count = frames.len();
+    for (index, frame) in frames.iter().enumerate() {
+        let visible = index + 1 == count;
+        let _ = writeln!(
+            out,
+            r#"<g visibility="{}">"#,
+            if visible { "visible" } else { "hidden" }
+        );
+        if let Some(seconds) = seconds_per_frame.filter(|_| count > 1) {
+            write_frame_animation(&mut out, index, count, seconds);
+        }
+        let title = if seconds_per_frame.is_some() {
+            format!("[{}/{count}] {}", index + 1, frame.title)
+        } else {
+            frame.title.clone()
+        };
+        write_frame(&mut out, frame, window, &title);
+        out.push_str("</g>\n");
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// 第 `index` 帧只在 `[index, index+1) / count` 这段时间可见（离散动画，循环播放）。
+fn write_frame_animation(out: &mut String, index: usize, count: usize, seconds: f64) {
+    let start = index as f64 / count as f64;
+    let end = (index + 1) as f64 / count as f64;
+    let (values, key_times) = if index == 0 {
+        ("visible;hidden".to_string(), format!("0;{end:.4}"))
+    } else if index + 1 == count {
+        ("hidden;visible".to_string(), format!("0;{start:.4}"))
+    } else {
+        (
+            "hidden;visible;hidden".to_string(),
+            format!("0;{start:.4};{end:.4}"),
+        )
+    };
+    let _ = writeln!(
+        out,
+        r#"<animate attributeName="visibility" values="{values}" keyTimes="{key_times}" dur="{:.3}s" calcMode="discrete" repeatCount="indefinite"/>"#,
+        seconds * count as f64
+    );
+}
+
+fn write_frame(out: &mut String, frame: &DebugFrame, window: Option<Window>, title: &str) {
+    let rejections = frame.probe.rejections();
+    let _ = writeln!(
+        out,
+        r#"<text x="{PAD}" y="14" font-size="11">{}</text>"#,
+        escape_xml(title)
+    );
+    let _ = writeln!(
+        out,
+        r#"<text x="{PAD}" y="28" font-size="9">搜索 {} 次 · 展开 {} 格次 · 拒绝 blocked {} / crossing {} / segment {}</text>"#,
+        frame.probe.searches,
+        frame.probe.expansions(),
+        rejections.blocked,
+        rejections.crossing,
+        rejections.segment,
+    );
+    let _ = writeln!(
+        out,
+        r#"<text x="{PAD}" y="40" font-size="9">灰格 = blocked · 橙格 = 展开热度 · 黑 / 灰线 = 中段 / 端段占用 · 蓝线 = 路径 · 红 / 紫短线 = crossing / segment 拒绝</text>"#
+    );
+    let Some(window) = window else {
+        return;
+    };
+    let origin = |point: GridPoint| {
+        (
+            PAD + (point.x - window.min.x) * CELL,
+            HEADER + PAD + (point.y - window.min.y) * CELL,
+        )
+    };
+    let center = |point: GridPoint| {
+        let (x, y) = origin(point);
+        (x + CELL / 2, y + CELL / 2)
+    };
+
+    // blocked
+    for y in window.min.y..=window.max.y {
+        for x in window.min.x..=window.max.x {
+            let point = GridPoint::new(x, y);
+            if frame.grid.is_blocked(point) {
+                let (px, py) = origin(point);
+                let _ = writeln!(
+                    out,
+                    r#"<rect x="{px}" y="{py}" width="{CELL}" height="{CELL}" fill="{BLOCKED_FILL}"/>"#
+                );
+            }
+        }
+    }
+
+    // 展开热度
+    let mut expanded: Vec<(GridPoint, u32)> = frame
+        .probe
+        .expanded
+        .iter()
+        .filter(|(point, _)| window.contains(**point))
+        .map(|(&point, &count)| (point, count))
+        .collect();
+    expanded.sort_by_key(|&(point, _)| (point.y, point.x));
+    let hottest = expanded.iter().map(|&(_, count)| count).max().unwrap_or(1);
+    for (point, count) in expanded {
+        let (px, py) = origin(point);
+        let opacity = 0.15 + 0.75 * f64::from(count) / f64::from(hottest);
+        let _ = writeln!(
+            out,
+            r#"<rect x="{px}" y="{py}" width="{CELL}" height="{CELL}" fill="{HEAT_FILL}" fill-opacity="{opacity:.2}"><title>({},{}) 展开 {count} 次</title></rect>"#,
+            point.x, point.y
+        );
+    }
+
+    // 已占用的线段
+    for segment in &frame.grid.segments {
+        let ((x1, y1), (x2, y2)) = (center(segment.from), center(segment.to));
+        let stroke = if segment.used_as_middle {
+            MIDDLE_STROKE
+        } else {
+            END_STROKE
+        };
+        let _ = writeln!(
+            out,
+            r#"<line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}" stroke="{stroke}" stroke-width="2" stroke-linecap="round"><title>{}</title></line>"#,
+            escape_xml(describe_segment(segment).trim())
+        );
+    }
+
+    // 本帧路径
+    if frame.path.len() >= 2 {
+        let points: Vec<String> = frame
+            .path
+            .iter()
+            .map(|&point| {
+                let (x, y) = center(point);
+                format!("{x},{y}")
+            })
+            .collect();
+        let _ = writeln!(
+            out,
+            r#"<polyline points="{}" fill="none" stroke="{PATH_STROKE}" stroke-width="3" stroke-opacity="0.85" stroke-linejoin="round"/>"#,
+            points.join(" ")
+        );
+    }
+
+    // crossing / segment 拒绝：从 from 指向 to 的半截短线（blocked 拒绝太多，只计数不画）
+    let mut rejected: Vec<(&(GridPoint, GridPoint), &StepRejections)> = frame
+        .probe
+        .rejected
+        .iter()
+        .filter(|(_, counts)| counts.crossing + counts.segment > 0)
+        .collect();
+    rejected.sort_by_key(|((from, to), _)| (from.y, from.x, to.y, to.x));
+    for (&(from, to), counts) in rejected {
+        let ((x1, y1), (x2, y2)) = (center(from), center(to));
+        let (x2, y2) = (
+            f64::from(x1) + (f64::from(x2) - f64::from(x1)) * 0.45,
+            f64::from(y1) + (f64::from(y2) - f64::from(y1)) * 0.45,
+        );
+        let stroke = if counts.crossing >= counts.segment {
+            CROSSING_STROKE
+        } else {
+            SEGMENT_STROKE
+        };
+        let _ = writeln!(
+            out,
+            r#"<line x1="{x1}" y1="{y1}" x2="{x2:.1}" y2="{y2:.1}" stroke="{stroke}" stroke-width="3"><title>({},{}) -&gt; ({},{}): crossing {} / segment {}</title></line>"#,
+            from.x, from.y, to.x, to.y, counts.crossing, counts.segment
+        );
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,650 @@
+// ============================================================================
+// 网格正交走线（A*）：可复用的公共 API
+//
+// 背景：
+// - `native_pathfinder::NativeAStar` 原本只通过 `__bm_getPath*` 暴露给 JS bundle，
+//   入参是 20 个位置参数的裸数组（blocked / segmentUsage / usedPoints ...），外部没法直接用；
+// - native ASCII 后端（`native::flowchart_ascii`）也要维护同一套占用表。
+//
+// 这里把“网格 + 占用表 + A* 缓冲区”收拢成 `RoutingGrid`：
+// - `route(from, to, &RouteConstraints) -> Option<Route>`：按 free / strict / relaxed 规则找一条路；
+// - `commit(&route)`：把路径记进占用表（segment usage + used points），影响之后的 `route`；
+// - `last_stats()`：最近一次 `route` 的搜索统计（展开格子数、堆峰值、按规则拒绝的单步）；
+// - `start_probe()` / `take_probe()` / `snapshot()`：调试用，逐格记录搜索过程与占用表，
+//   画成带注释的文本或 SVG 热力图（见 `debug`）；
+// - native ASCII 后端与其他画网格图的工具用的是同一份实现。
+//
+// 规则（与 TS 版 `getPath*` 一致）：
+// - free：只避开 blocked 格子；
+// - strict：每一步都不能把已有连线的格子补成 `┼`（逐步判定：直线穿过 `─` 的格子不算）；
+//   已占用的线段只允许“同源起点段 / 同靶终点段”共享；
+// - relaxed：允许交叉（每个 `┼` +1 代价），线段共享规则同 strict，另外同一对端点的平行边可共用干线，
+//   终点段共享默认关闭（`allow_end_segment_reuse` 打开）。
+// - 终点格子即使 blocked 也可以进入（节点本身通常是 blocked 的，路径要能连到它）。
+// - 每次搜索前先从终点做一次有上限的反向泛洪：终点被围死在一小块区域里时直接返回 `None`，
+//   不让 A* 扫完整个窗口（`SearchStats.probe_cells` 记探测看过的格子）。
+// ============================================================================
+
+pub mod debug;
+
+use crate::native_pathfinder::NativeAStar;
+use crate::types::SearchStats;
+use debug::SearchProbe;
+
+pub use crate::native_pathfinder::{CONNECT_DOWN, CONNECT_LEFT, CONNECT_RIGHT, CONNECT_UP};
+
+/// 网格坐标（以格子为单位，原点在左上角）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct GridPoint {
+    pub x: u32,
+    pub y: u32,
+}
+
+impl GridPoint {
+    pub fn new(x: u32, y: u32) -> Self {
+        Self { x, y }
+    }
+}
+
+/// 走线规则。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RouteRules {
+    /// 只避开 blocked 格子，不看已有路径。
+    #[default]
+    Free,
+    /// 禁止补出 `┼`，禁止非法共线。
+    Strict,
+    /// 允许交叉（计入代价），禁止非法共线。
+    Relaxed,
+}
+
+/// 一次 `route` 的约束。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RouteConstraints {
+    pub rules: RouteRules,
+    /// 起点所属对象的 id（从 1 开始）：同源的路径可以共享起点段。
+    /// 0 表示匿名，不与任何路径共享线段。
+    pub source_id: u32,
+    /// 终点所属对象的 id（从 1 开始）：同靶的路径可以共享终点段。0 表示匿名。
+    pub target_id: u32,
+    /// 搜索窗口的右下角（含）；`None` 表示整张网格。窗口越小搜索越快。
+    pub bounds: Option<GridPoint>,
+    /// relaxed 专用：允许复用其他同靶路径的终点段（一般只在走不通时作为兜底打开）。
+    pub allow_end_segment_reuse: bool,
+    /// 每次转弯的额外代价：0（默认）只比步数，调大后同样长度下拐点更少的路径胜出，
+    /// 甚至愿意多走几步换少拐一次弯。计入 `Route.cost`。
+    pub turn_penalty: u32,
+}
+
+/// `route` 找到的路径。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    /// 逐格路径（含起点与终点），相邻两点正交相邻。
+    pub points: Vec<GridPoint>,
+    /// A* 的累计代价：步数 + 转弯惩罚，relaxed 下再加上交叉惩罚。
+    pub cost: u32,
+    /// 实际参与共享判定的端点 id（匿名时是 `route` 分配的唯一 id）；`commit` 按它记录占用。
+    pub source_id: u32,
+    pub target_id: u32,
+}
+
+impl Route {
+    /// 只保留端点与拐点。
+    pub fn corners(&self) -> Vec<GridPoint> {
+        if self.points.len() <= 2 {
+            return self.points.clone();
+        }
+        let step = |a: GridPoint, b: GridPoint| {
+            (
+                i64::from(b.x) - i64::from(a.x),
+                i64::from(b.y) - i64::from(a.y),
+            )
+        };
+        let mut out = vec![self.points[0]];
+        for window in self.points.windows(3) {
+            if step(window[0], window[1]) != step(window[1], window[2]) {
+                out.push(window[1]);
+            }
+        }
+        out.push(self.points[self.points.len() - 1]);
+        out
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 占用表
+// ----------------------------------------------------------------------------
+
+/// 每条单位线段的使用情况（index = `segment_index(a, b)`，布局与 TS 版 `SegmentUsage` 一致）。
+#[derive(Debug, Clone)]
+struct SegmentUsage {
+    segment_used: Vec<u8>,
+    used_as_middle: Vec<u8>,
+    segment_pair: Vec<u32>,
+    segment_pair_multi: Vec<u8>,
+    start_source: Vec<u32>,
+    start_source_multi: Vec<u8>,
+    end_target: Vec<u32>,
+    end_target_multi: Vec<u8>,
+    used_count: usize,
+}
+
+impl SegmentUsage {
+    fn new(cell_count: usize) -> Self {
+        let len = cell_count * 2;
+        Self {
+            segment_used: vec![0; len],
+            used_as_middle: vec![0; len],
+            segment_pair: vec![0; len],
+            segment_pair_multi: vec![0; len],
+            start_source: vec![0; len],
+            start_source_multi: vec![0; len],
+            end_target: vec![0; len],
+            end_target_multi: vec![0; len],
+            used_count: 0,
+        }
+    }
+
+    /// 记录一条走通的路径（`path` 是未压缩的 cell 下标序列）。
+    fn record(&mut self, from_id: u32, to_id: u32, path: &[u32]) {
+        if path.len() < 2 {
+            return;
+        }
+        let pair = if from_id > 65535 || to_id > 65535 {
+            0
+        } else {
+            (from_id << 16) | to_id
+        };
+        for i in 1..path.len() {
+            let segment = segment_index(path[i - 1], path[i]);
+            let is_first = i == 1;
+            let is_last = i == path.len() - 1;
+            if self.segment_used[segment] == 0 {
+                self.segment_used[segment] = 1;
+                self.used_count += 1;
+            }
+            if pair != 0 {
+                let existing = self.segment_pair[segment];
+                if existing == 0 {
+                    self.segment_pair[segment] = pair;
+                } else if existing != pair {
+                    self.segment_pair_multi[segment] = 1;
+                }
+            }
+            if is_first {
+                let existing = self.start_source[segment];
+                if existing == 0 {
+                    self.start_source[segment] = from_id;
+                } else if existing != from_id {
+                    self.start_source_multi[segment] = 1;
+                }
+            }
+            if is_last {
+                let existing = self.end_target[segment];
+                if existing == 0 {
+                    self.end_target[segment] = to_id;
+                } else if existing != to_id {
+                    self.end_target_multi[segment] = 1;
+                }
+            }
+            if !is_first && !is_last {
+                self.used_as_middle[segment] = 1;
+            }
+        }
+    }
+
+    /// 把 `path` 经过的线段整条清零（不做引用计数，共享这些线段的路径要一起撤销）。
+    #[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+    fn forget(&mut self, path: &[u32]) {
+        for pair in path.windows(2) {
+            let segment = segment_index(pair[0], pair[1]);
+            if self.segment_used[segment] != 0 {
+                self.used_count -= 1;
+            }
+            self.segment_used[segment] = 0;
+            self.used_as_middle[segment] = 0;
+            self.segment_pair[segment] = 0;
+            self.segment_pair_multi[segment] = 0;
+            self.start_source[segment] = 0;
+            self.start_source_multi[segment] = 0;
+            self.end_target[segment] = 0;
+            self.end_target_multi[segment] = 0;
+        }
+    }
+
+    /// 已被占用的线段能否被当前边复用（只允许同源起点段 / 同目标终点段共享）。
+    #[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+    fn can_share(
+        &self,
+        segment: usize,
+        edge_from_id: u32,
+        edge_to_id: u32,
+        starts_at_route_from: bool,
+        ends_at_route_to: bool,
+    ) -> bool {
+        if self.used_as_middle[segment] != 0 {
+            return false;
+        }
+        let start_source = self.start_source[segment];
+        let end_target = self.end_target[segment];
+        let start_multi = self.start_source_multi[segment] != 0;
+        let end_multi = self.end_target_multi[segment] != 0;
+        match (starts_at_route_from, ends_at_route_to) {
+            (true, true) => {
+                !start_multi
+                    && (start_source == 0 || start_source == edge_from_id)
+                    && !end_multi
+                    && (end_target == 0 || end_target == edge_to_id)
+            }
+            (true, false) => {
+                !end_multi && end_target == 0 && !start_multi && start_source == edge_from_id
+            }
+            (false, true) => {
+                !start_multi && start_source == 0 && !end_multi && end_target == edge_to_id
+            }
+            (false, false) => false,
+        }
+    }
+}
+
+/// 单位线段下标：水平段 `min*2`，垂直段 `min*2+1`。
+fn segment_index(a: u32, b: u32) -> usize {
+    let horizontal = a.abs_diff(b) == 1;
+    (a.min(b) as usize) * 2 + usize::from(!horizontal)
+}
+
+/// 线段 a->b 在两端点上分别占用的方向位；两格不正交相邻时返回 `None`。
+///
+/// 按坐标判断而不是看下标差：行尾与下一行行首的下标只差 1，但并不相邻；
+/// 宽度为 1 时下标差 1 是竖直方向。
+fn point_bits(a: u32, b: u32, stride: usize) -> Option<(u8, u8)> {
+    let stride = stride as i64;
+    let (a, b) = (i64::from(a), i64::from(b));
+    match (b % stride - a % stride, b / stride - a / stride) {
+        (1, 0) => Some((CONNECT_RIGHT, CONNECT_LEFT)),
+        (-1, 0) => Some((CONNECT_LEFT, CONNECT_RIGHT)),
+        (0, 1) => Some((CONNECT_DOWN, CONNECT_UP)),
+        (0, -1) => Some((CONNECT_UP, CONNECT_DOWN)),
+        _ => None,
+    }
+}
+
+/// 加上 `bit` 之后是否形成四向交叉（`┼`）。
+#[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+fn forms_crossing(existing: u8, bit: u8) -> bool {
+    let merged = existing | bit;
+    let horizontal = merged & CONNECT_LEFT != 0 && merged & CONNECT_RIGHT != 0;
+    let vertical = merged & CONNECT_UP != 0 && merged & CONNECT_DOWN != 0;
+    horizontal && vertical
+}
+
+// ----------------------------------------------------------------------------
+// RoutingGrid
+// ----------------------------------------------------------------------------
+
+/// 正交走线网格：blocked 格子 + 已提交路径的占用表 + 复用的 A* 缓冲区。
+#[derive(Clone)]
+pub struct RoutingGrid {
+    width: usize,
+    height: usize,
+    blocked: Vec<u8>,
+    segments: SegmentUsage,
+    /// 每个格子被哪些方向的线段连到（`CONNECT_*` 位）。
+    used_points: Vec<u8>,
+    /// 匿名端点分配的 id：从 `u32::MAX` 往下数，不会与调用方的小 id 撞上，也不参与平行边共享。
+    next_anonymous_id: u32,
+    astar: NativeAStar,
+    /// `start_probe` 之后累计的搜索过程（`None` = 不记录）。
+    probe: Option<SearchProbe>,
+}
+
+impl RoutingGrid {
+    /// `width × height` 的空网格（没有 blocked 格子，也没有已提交的路径）。
+    pub fn new(width: u32, height: u32) -> Self {
+        let (width, height) = (width as usize, height as usize);
+        let cell_count = width * height;
+        Self {
+            width,
+            height,
+            blocked: vec![0; cell_count],
+            segments: SegmentUsage::new(cell_count),
+            used_points: vec![0; cell_count],
+            next_anonymous_id: u32::MAX,
+            astar: NativeAStar::default(),
+            probe: None,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width as u32
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height as u32
+    }
+
+    fn index(&self, point: GridPoint) -> Option<u32> {
+        let (x, y) = (point.x as usize, point.y as usize);
+        (x < self.width && y < self.height).then(|| (y * self.width + x) as u32)
+    }
+
+    pub(crate) fn point(&self, index: u32) -> GridPoint {
+        let width = self.width as u32;
+        GridPoint::new(index % width, index / width)
+    }
+
+    /// 网格外的点视为 blocked。
+    pub fn is_blocked(&self, point: GridPoint) -> bool {
+        self.index(point)
+            .is_none_or(|index| self.blocked[index as usize] != 0)
+    }
+
+    /// 设置单个格子（网格外的点忽略）。
+    pub fn set_blocked(&mut self, point: GridPoint, blocked: bool) {
+        if let Some(index) = self.index(point) {
+            self.blocked[index as usize] = u8::from(blocked);
+        }
+    }
+
+    /// 把左上角为 `origin` 的 `width × height` 矩形标成 blocked（超出网格的部分忽略）。
+    pub fn block_rect(&mut self, origin: GridPoint, width: u32, height: u32) {
+        for y in origin.y..origin.y.saturating_add(height).min(self.height as u32) {
+            for x in origin.x..origin.x.saturating_add(width).min(self.width as u32) {
+                self.blocked[y as usize * self.width + x as usize] = 1;
+            }
+        }
+    }
+
+    /// 格子被已提交路径连到的方向（`CONNECT_*` 位的组合；网格外为 0）。
+    pub fn connections(&self, point: GridPoint) -> u8 {
+        self.index(point)
+            .map_or(0, |index| self.used_points[index as usize])
+    }
+
+    /// 最近一次 `route` 的搜索统计（走不通时 `cost` 为 `None`；端点在网格外时不搜索，统计不变）。
+    pub fn last_stats(&self) -> SearchStats {
+        self.astar.last_stats()
+    }
+
+    /// 开始记录之后每次 `route` 的搜索过程：展开过的格子与被拒绝的单步（见 `take_probe`）。
+    ///
+    /// 已经在记录时清空重来。记录会拖慢搜索，只在调试时打开。
+    pub fn start_probe(&mut self) {
+        self.probe = Some(SearchProbe::default());
+        self.astar.set_probe(true);
+    }
+
+    /// 取走记录并停止记录；没有 `start_probe` 过时返回空记录。
+    pub fn take_probe(&mut self) -> SearchProbe {
+        self.astar.set_probe(false);
+        self.probe.take().unwrap_or_default()
+    }
+
+    /// 已被占用的单位线段数。
+    pub fn used_segments(&self) -> usize {
+        self.segments.used_count
+    }
+
+    /// 开关搜索前的封闭探测（默认开）。
+    ///
+    /// 探测不改变任何路径与代价，只让走不通的搜索提前返回；关掉后展开过程与 TS 逐格一致
+    /// （`AsciiRenderOptions.ts_compatible` 就是这么做的），也方便对照探测省下了多少展开。
+    pub fn set_enclosure_probe(&mut self, enabled: bool) {
+        self.astar.set_enclosure_probe(enabled);
+    }
+
+    /// 清空所有已提交的路径（blocked 格子保留）。
+    pub fn clear_routes(&mut self) {
+        let cell_count = self.width * self.height;
+        self.segments = SegmentUsage::new(cell_count);
+        self.used_points.fill(0);
+    }
+
+    /// 在 `from` 与 `to` 之间找一条代价最小的正交路径；不可达或端点在网格外时返回 `None`。
+    ///
+    /// 只读占用表：找到的路径要 `commit` 之后才会影响后续的 `route`。
+    pub fn route(
+        &mut self,
+        from: GridPoint,
+        to: GridPoint,
+        constraints: &RouteConstraints,
+    ) -> Option<Route> {
+        let (from_idx, to_idx) = (self.index(from)?, self.index(to)?);
+        let source_id = self.endpoint_id(constraints.source_id);
+        let target_id = self.endpoint_id(constraints.target_id);
+        let (path, cost) =
+            self.route_indices(from_idx, to_idx, constraints, (source_id, target_id))?;
+        Some(Route {
+            points: path.into_iter().map(|index| self.point(index)).collect(),
+            cost,
+            source_id,
+            target_id,
+        })
+    }
+
+    /// 把路径记进占用表。
+    ///
+    /// # Panics
+    /// 路径里有点在网格外，或相邻两点不是正交相邻时 panic（路径应当来自本网格的 `route`）。
+    pub fn commit(&mut self, route: &Route) {
+        let indices: Vec<u32> = route
+            .points
+            .iter()
+            .map(|&point| {
+                self.index(point)
+                    .unwrap_or_else(|| panic!("路径上的点 {point:?} 不在网格内"))
+            })
+            .collect();
+        for pair in indices.windows(2) {
+            assert!(
+                point_bits(pair[0], pair[1], self.width).is_some(),
+                "路径上的 {:?} -> {:?} 不是正交相邻的两格",
+                self.point(pair[0]),
+                self.point(pair[1]),
+            );
+        }
+        self.record(route.source_id, route.target_id, &indices);
+    }
+
+    fn endpoint_id(&mut self, id: u32) -> u32 {
+        if id != 0 {
+            return id;
+        }
+        let id = self.next_anonymous_id;
+        self.next_anonymous_id -= 1;
+        id
+    }
+
+    // ------------------------------------------------------------------
+    // 以 cell 下标为单位的内部接口（native ASCII 后端直接用）
+    // ------------------------------------------------------------------
+
+    #[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+    pub(crate) fn is_blocked_index(&self, index: u32) -> bool {
+        self.blocked[index as usize] != 0
+    }
+
+    #[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+    pub(crate) fn connections_index(&self, index: u32) -> u8 {
+        self.used_points.get(index as usize).copied().unwrap_or(0)
+    }
+
+    /// 返回（逐格路径, 代价）；`constraints` 里的 id 已由调用方换成实际 id（`ids`）。
+    pub(crate) fn route_indices(
+        &mut self,
+        from_idx: u32,
+        to_idx: u32,
+        constraints: &RouteConstraints,
+        ids: (u32, u32),
+    ) -> Option<(Vec<u32>, u32)> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+        let (max_x, max_y) = match constraints.bounds {
+            Some(bounds) => (
+                bounds.x.min(self.width as u32 - 1),
+                bounds.y.min(self.height as u32 - 1),
+            ),
+            None => (self.width as u32 - 1, self.height as u32 - 1),
+        };
+        self.astar.set_turn_penalty(constraints.turn_penalty);
+        let trace_len = self.astar.trace_len();
+        let segments = &self.segments;
+        // 缓冲区都由本结构体维护（长度一致），NativeAStar 只会在缓冲区不一致时报错。
+        let result = match constraints.rules {
+            RouteRules::Free => self
+                .astar
+                .get_path(
+                    self.width,
+                    self.height,
+                    from_idx,
+                    to_idx,
+                    max_x,
+                    max_y,
+                    &self.blocked,
+                )
+                .map(|path| path.map(|path| with_step_cost(path, constraints.turn_penalty))),
+            RouteRules::Strict => self
+                .astar
+                .get_path_strict(
+                    self.width,
+                    self.height,
+                    from_idx,
+                    to_idx,
+                    max_x,
+                    max_y,
+                    &self.blocked,
+                    &segments.segment_used,
+                    &segments.used_as_middle,
+                    &segments.start_source,
+                    &segments.start_source_multi,
+                    &segments.end_target,
+                    &segments.end_target_multi,
+                    Some(&self.used_points),
+                    from_idx,
+                    to_idx,
+                    ids.0,
+                    ids.1,
+                )
+                .map(|path| path.map(|path| with_step_cost(path, constraints.turn_penalty))),
+            RouteRules::Relaxed => self.astar.get_path_relaxed(
+                self.width,
+                self.height,
+                from_idx,
+                to_idx,
+                max_x,
+                max_y,
+                &self.blocked,
+                &segments.segment_used,
+                &segments.used_as_middle,
+                &segments.segment_pair,
+                &segments.segment_pair_multi,
+                &segments.start_source,
+                &segments.start_source_multi,
+                &segments.end_target,
+                &segments.end_target_multi,
+                Some(&self.used_points),
+                from_idx,
+                to_idx,
+                ids.0,
+                ids.1,
+                constraints.allow_end_segment_reuse,
+            ),
+        };
+        debug_assert!(result.is_ok(), "{result:?}");
+        let result = result.ok().flatten();
+        if let Some(probe) = &mut self.probe {
+            probe.add(&self.astar.take_probe_events(), self.width as u32);
+        }
+        // free 不读占用表；走不通的搜索在占用只增不减时依旧走不通，也不用记
+        if result.is_none() || constraints.rules == RouteRules::Free {
+            self.astar.truncate_trace(trace_len);
+        }
+        result
+    }
+
+    /// 开始记录之后的 strict / relaxed 搜索读过哪些格子（见 `take_trace`）。
+    #[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+    pub(crate) fn start_trace(&mut self) {
+        self.astar.set_trace(Some(Vec::new()));
+    }
+
+    /// 取走记录：走通的 strict / relaxed 搜索展开过的格子（可能重复）。
+    ///
+    /// 占用表只在这些格子及其四邻域上读过；其余格子上的占用变化不会改变这些搜索的结果。
+    #[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+    pub(crate) fn take_trace(&mut self) -> Vec<u32> {
+        self.astar.take_trace().unwrap_or_default()
+    }
+
+    /// 记录 segment usage 与 used points（被 blocked 的格子不记方向位）。
+    pub(crate) fn record(&mut self, source_id: u32, target_id: u32, path: &[u32]) {
+        self.segments.record(source_id, target_id, path);
+        for pair in path.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            if let Some((from_bit, to_bit)) = point_bits(a, b, self.width) {
+                if self.blocked[a as usize] == 0 {
+                    self.used_points[a as usize] |= from_bit;
+                }
+                if self.blocked[b as usize] == 0 {
+                    self.used_points[b as usize] |= to_bit;
+                }
+            }
+        }
+    }
+
+    /// 撤销 `record` 过的路径：这些格子与线段上的占用全部清零。
+    ///
+    /// 网格上的路径全部在 `paths` 里时等同 `clear_routes`，代价只与路径长度成正比。
+    #[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+    pub(crate) fn forget<'a>(&mut self, paths: impl IntoIterator<Item = &'a [u32]>) {
+        for path in paths {
+            self.segments.forget(path);
+            for &cell in path {
+                self.used_points[cell as usize] = 0;
+            }
+        }
+    }
+
+    /// 单步 a->b 在 strict 规则下是否允许（不形成 `┼`，且线段未占用或可共享）。
+    #[cfg_attr(not(feature = "native-backend"), allow(dead_code))]
+    pub(crate) fn step_allowed_strict(
+        &self,
+        a: u32,
+        b: u32,
+        ids: (u32, u32),
+        starts_at_route_from: bool,
+        ends_at_route_to: bool,
+    ) -> bool {
+        let Some((from_bit, to_bit)) = point_bits(a, b, self.width) else {
+            return true;
+        };
+        for (index, bit) in [(a, from_bit), (b, to_bit)] {
+            if self.blocked[index as usize] == 0 {
+                let existing = self.used_points[index as usize];
+                if existing != 0 && forms_crossing(existing, bit) {
+                    return false;
+                }
+            }
+        }
+        let segment = segment_index(a, b);
+        self.segments.segment_used[segment] == 0
+            || self.segments.can_share(
+                segment,
+                ids.0,
+                ids.1,
+                starts_at_route_from,
+                ends_at_route_to,
+            )
+    }
+}
+
+/// free / strict 的代价：步数 + 转弯惩罚（A* 只返回路径，这里按同样的口径重算）。
+fn with_step_cost(path: Vec<u32>, turn_penalty: u32) -> (Vec<u32>, u32) {
+    let steps = path.len().saturating_sub(1) as u32;
+    let turns = path
+        .windows(3)
+        .filter(|w| w[1].wrapping_sub(w[0]) != w[2].wrapping_sub(w[1]))
+        .count() as u32;
+    (path, steps + turns * turn_penalty)
+}
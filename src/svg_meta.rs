@@ -0,0 +1,622 @@
+// ============================================================================
+// SVG meta：JS 布局结果 -> Rust 公共类型
+//
+// 背景：
+// - bundle 的 `renderMermaidWithMeta` 返回 `{ svg, kind, layout }`，
+//   其中 `layout` 是 JS 布局阶段的原始对象（字段名/坐标语义随图类型不同）。
+// - 这里把它转换成形状稳定的 `SvgDiagramMeta`：
+//   - 统一成“左上角 + 宽高”的矩形；
+//   - 补齐 label 的位置（JS 在绘制阶段才算，这里复刻同一个折线中点算法）；
+//   - 补齐元素 id（JS 在绘制阶段才生成，这里复刻同一套转义/计数规则）。
+// - native 后端只复用其中的 `ElementIds` / `rect` / sequence 部分；
+//   JSON 反序列化与其余图类型的转换只在 `js-backend` 下用到。
+// ============================================================================
+
+#![cfg_attr(not(feature = "js-backend"), allow(dead_code))]
+
+use crate::error::{BeautifulMermaidError, Result};
+use crate::types::{
+    SvgClassMeta, SvgClassNode, SvgClassRelationship, SvgDiagramMeta, SvgErEntity, SvgErMeta,
+    SvgErRelationship, SvgFlowchartMeta, SvgMetaEdge, SvgMetaGroup, SvgMetaNode, SvgPoint, SvgRect,
+    SvgSequenceActivation, SvgSequenceBlock, SvgSequenceDivider, SvgSequenceMessage,
+    SvgSequenceMeta, SvgSequenceNote, SvgSequenceParticipant,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// self message 回环的宽/高（对齐 JS SVG renderer）。
+pub(crate) const SELF_LOOP_WIDTH: f64 = 30.0;
+pub(crate) const SELF_LOOP_HEIGHT: f64 = 20.0;
+
+// ----------------------------------------------------------------------------
+// JS 侧原始结构
+// ----------------------------------------------------------------------------
+
+#[derive(Deserialize)]
+pub(crate) struct JsSvgRenderWithMeta {
+    pub svg: String,
+    pub kind: String,
+    pub layout: serde_json::Value,
+}
+
+/// 空图时 JS 可能给出 `NaN`（JSON 里变成 null），这里按 0 处理。
+#[derive(Deserialize)]
+struct JsSize {
+    width: Option<f64>,
+    height: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct JsFlowchartLayout {
+    nodes: Vec<JsFlowchartNode>,
+    edges: Vec<JsFlowchartEdge>,
+    groups: Vec<JsFlowchartGroup>,
+}
+
+#[derive(Deserialize)]
+struct JsFlowchartNode {
+    id: String,
+    label: String,
+    shape: String,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsFlowchartEdge {
+    source: String,
+    target: String,
+    label: Option<String>,
+    style: String,
+    has_arrow_start: bool,
+    has_arrow_end: bool,
+    points: Vec<SvgPoint>,
+    label_position: Option<SvgPoint>,
+}
+
+/// 空 subgraph 没有成员，dagre 不给它坐标：JS 的 x/y 是 `NaN`（JSON 里变成 null），
+/// 嵌套时父级的 y/height 也跟着变成 `NaN`。
+#[derive(Deserialize)]
+struct JsFlowchartGroup {
+    id: String,
+    label: String,
+    x: Option<f64>,
+    y: Option<f64>,
+    width: Option<f64>,
+    height: Option<f64>,
+    children: Vec<JsFlowchartGroup>,
+}
+
+/// 注意：native 后端（`native::sequence_svg`）按同一套规则直接构造这些结构。
+#[derive(Deserialize)]
+pub(crate) struct JsSequenceLayout {
+    pub(crate) actors: Vec<JsSequenceActor>,
+    pub(crate) lifelines: Vec<JsSequenceLifeline>,
+    pub(crate) messages: Vec<JsSequenceMessage>,
+    pub(crate) activations: Vec<JsSequenceActivation>,
+    pub(crate) blocks: Vec<JsSequenceBlock>,
+    pub(crate) notes: Vec<JsSequenceNote>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct JsSequenceActor {
+    pub(crate) id: String,
+    pub(crate) label: String,
+    #[serde(rename = "type")]
+    pub(crate) actor_type: String,
+    /// 注意：JS 里 actor 的 x 是中心点。
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsSequenceLifeline {
+    pub(crate) actor_id: String,
+    pub(crate) x: f64,
+    pub(crate) top_y: f64,
+    pub(crate) bottom_y: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsSequenceMessage {
+    pub(crate) from: String,
+    pub(crate) to: String,
+    pub(crate) label: String,
+    pub(crate) line_style: String,
+    pub(crate) arrow_head: String,
+    pub(crate) x1: f64,
+    pub(crate) x2: f64,
+    pub(crate) y: f64,
+    pub(crate) is_self: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsSequenceActivation {
+    pub(crate) actor_id: String,
+    pub(crate) x: f64,
+    pub(crate) top_y: f64,
+    pub(crate) bottom_y: f64,
+    pub(crate) width: f64,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct JsSequenceBlock {
+    #[serde(rename = "type")]
+    pub(crate) block_type: String,
+    pub(crate) label: String,
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+    pub(crate) dividers: Vec<SvgSequenceDivider>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct JsSequenceNote {
+    pub(crate) text: String,
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+}
+
+/// 注意：native 后端（`native::class_svg` / `native::er_svg`）同样直接构造这些结构。
+#[derive(Deserialize)]
+pub(crate) struct JsClassLayout {
+    pub(crate) classes: Vec<JsClassNode>,
+    pub(crate) relationships: Vec<JsClassRelationship>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsClassNode {
+    pub(crate) id: String,
+    pub(crate) label: String,
+    pub(crate) annotation: Option<String>,
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+    pub(crate) header_height: f64,
+    pub(crate) attr_height: f64,
+    pub(crate) method_height: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsClassRelationship {
+    pub(crate) from: String,
+    pub(crate) to: String,
+    #[serde(rename = "type")]
+    pub(crate) relationship_type: String,
+    pub(crate) marker_at: String,
+    pub(crate) label: Option<String>,
+    pub(crate) points: Vec<SvgPoint>,
+    pub(crate) label_position: Option<SvgPoint>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct JsErLayout {
+    pub(crate) entities: Vec<JsErEntity>,
+    pub(crate) relationships: Vec<JsErRelationship>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsErEntity {
+    pub(crate) id: String,
+    pub(crate) label: String,
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+    pub(crate) header_height: f64,
+    pub(crate) row_height: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsErRelationship {
+    pub(crate) entity1: String,
+    pub(crate) entity2: String,
+    pub(crate) cardinality1: String,
+    pub(crate) cardinality2: String,
+    pub(crate) label: String,
+    pub(crate) identifying: bool,
+    pub(crate) points: Vec<SvgPoint>,
+}
+
+// ----------------------------------------------------------------------------
+// 转换
+// ----------------------------------------------------------------------------
+
+/// SVG 元素 id 生成器（对齐 bundle 的 `bmEsc` / `bmEdgeIds`）。
+///
+/// - node：`{prefix}node-{esc(id)}`
+/// - edge：`{prefix}edge-{esc(from)}-{esc(to)}-{n}`，n 是同一对端点的第几条边（从 0 开始）
+pub(crate) struct ElementIds<'a> {
+    prefix: &'a str,
+    edge_counts: HashMap<String, usize>,
+}
+
+impl<'a> ElementIds<'a> {
+    pub(crate) fn new(prefix: &'a str) -> Self {
+        Self {
+            prefix,
+            edge_counts: HashMap::new(),
+        }
+    }
+
+    /// 转义规则：ASCII 字母数字原样保留，`_` -> `__`，其余字符 -> `_{十六进制码点}_`。
+    /// 输出只含 `[A-Za-z0-9_]`，且是单射（不同的 Mermaid id 不会撞成同一个元素 id）。
+    fn escape(id: &str) -> String {
+        let mut out = String::with_capacity(id.len());
+        for ch in id.chars() {
+            match ch {
+                'A'..='Z' | 'a'..='z' | '0'..='9' => out.push(ch),
+                '_' => out.push_str("__"),
+                _ => out.push_str(&format!("_{:x}_", ch as u32)),
+            }
+        }
+        out
+    }
+
+    pub(crate) fn node(&self, id: &str) -> String {
+        format!("{}node-{}", self.prefix, Self::escape(id))
+    }
+
+    pub(crate) fn edge(&mut self, from: &str, to: &str) -> String {
+        let key = format!("{}-{}", Self::escape(from), Self::escape(to));
+        let count = self.edge_counts.entry(key.clone()).or_insert(0);
+        let id = format!("{}edge-{key}-{count}", self.prefix);
+        *count += 1;
+        id
+    }
+}
+
+pub(crate) fn rect(x: f64, y: f64, width: f64, height: f64) -> SvgRect {
+    SvgRect {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+/// 折线按长度的中点（对齐 JS 绘制 edge label 时用的算法）。
+pub(crate) fn polyline_midpoint(points: &[SvgPoint]) -> SvgPoint {
+    match points {
+        [] => SvgPoint { x: 0.0, y: 0.0 },
+        [only] => *only,
+        _ => {
+            let segment_len = |a: &SvgPoint, b: &SvgPoint| (b.x - a.x).hypot(b.y - a.y);
+            let total: f64 = points.windows(2).map(|w| segment_len(&w[0], &w[1])).sum();
+            if total == 0.0 {
+                return points[0];
+            }
+
+            let half = total / 2.0;
+            let mut walked = 0.0;
+            for w in points.windows(2) {
+                let len = segment_len(&w[0], &w[1]);
+                if walked + len >= half {
+                    let t = if len > 0.0 {
+                        (half - walked) / len
+                    } else {
+                        0.0
+                    };
+                    return SvgPoint {
+                        x: w[0].x + (w[1].x - w[0].x) * t,
+                        y: w[0].y + (w[1].y - w[0].y) * t,
+                    };
+                }
+                walked += len;
+            }
+            points[points.len() - 1]
+        }
+    }
+}
+
+fn edge_label_position(
+    label: Option<&str>,
+    explicit: Option<SvgPoint>,
+    points: &[SvgPoint],
+) -> Option<SvgPoint> {
+    label
+        .filter(|label| !label.is_empty())
+        .map(|_| explicit.unwrap_or_else(|| polyline_midpoint(points)))
+}
+
+/// 没有坐标的 group（空 subgraph，JS 把框画在 `NaN` 处，看不见）不进 meta。
+fn convert_group(group: JsFlowchartGroup) -> Option<SvgMetaGroup> {
+    Some(SvgMetaGroup {
+        id: group.id,
+        label: group.label,
+        box_rect: rect(group.x?, group.y?, group.width?, group.height?),
+        children: convert_groups(group.children),
+    })
+}
+
+fn convert_groups(groups: Vec<JsFlowchartGroup>) -> Vec<SvgMetaGroup> {
+    groups.into_iter().filter_map(convert_group).collect()
+}
+
+fn convert_flowchart(layout: JsFlowchartLayout, ids: &mut ElementIds) -> SvgFlowchartMeta {
+    SvgFlowchartMeta {
+        nodes: layout
+            .nodes
+            .into_iter()
+            .map(|node| SvgMetaNode {
+                element_id: ids.node(&node.id),
+                id: node.id,
+                label: node.label,
+                shape: node.shape,
+                box_rect: rect(node.x, node.y, node.width, node.height),
+            })
+            .collect(),
+        edges: layout
+            .edges
+            .into_iter()
+            .map(|edge| SvgMetaEdge {
+                element_id: ids.edge(&edge.source, &edge.target),
+                label_position: edge_label_position(
+                    edge.label.as_deref(),
+                    edge.label_position,
+                    &edge.points,
+                ),
+                from: edge.source,
+                to: edge.target,
+                label: edge.label,
+                style: edge.style,
+                has_arrow_start: edge.has_arrow_start,
+                has_arrow_end: edge.has_arrow_end,
+                points: edge.points,
+            })
+            .collect(),
+        groups: convert_groups(layout.groups),
+    }
+}
+
+pub(crate) fn convert_sequence(layout: JsSequenceLayout, ids: &mut ElementIds) -> SvgSequenceMeta {
+    let participants = layout
+        .actors
+        .into_iter()
+        .map(|actor| {
+            let lifeline = layout
+                .lifelines
+                .iter()
+                .find(|lifeline| lifeline.actor_id == actor.id);
+            SvgSequenceParticipant {
+                element_id: ids.node(&actor.id),
+                box_rect: rect(
+                    actor.x - actor.width / 2.0,
+                    actor.y,
+                    actor.width,
+                    actor.height,
+                ),
+                lifeline_x: lifeline.map_or(actor.x, |l| l.x),
+                lifeline_top: lifeline.map_or(actor.y + actor.height, |l| l.top_y),
+                lifeline_bottom: lifeline.map_or(actor.y + actor.height, |l| l.bottom_y),
+                id: actor.id,
+                label: actor.label,
+                participant_type: actor.actor_type,
+            }
+        })
+        .collect();
+
+    let messages = layout
+        .messages
+        .into_iter()
+        .enumerate()
+        .map(|(index, message)| {
+            let (points, label_position) = if message.is_self {
+                let loop_x = message.x1 + SELF_LOOP_WIDTH;
+                let bottom = message.y + SELF_LOOP_HEIGHT;
+                (
+                    vec![
+                        SvgPoint {
+                            x: message.x1,
+                            y: message.y,
+                        },
+                        SvgPoint {
+                            x: loop_x,
+                            y: message.y,
+                        },
+                        SvgPoint {
+                            x: loop_x,
+                            y: bottom,
+                        },
+                        SvgPoint {
+                            x: message.x2,
+                            y: bottom,
+                        },
+                    ],
+                    SvgPoint {
+                        x: loop_x + 6.0,
+                        y: message.y + SELF_LOOP_HEIGHT / 2.0,
+                    },
+                )
+            } else {
+                (
+                    vec![
+                        SvgPoint {
+                            x: message.x1,
+                            y: message.y,
+                        },
+                        SvgPoint {
+                            x: message.x2,
+                            y: message.y,
+                        },
+                    ],
+                    SvgPoint {
+                        x: (message.x1 + message.x2) / 2.0,
+                        y: message.y - 6.0,
+                    },
+                )
+            };
+            SvgSequenceMessage {
+                element_id: ids.edge(&message.from, &message.to),
+                index,
+                from: message.from,
+                to: message.to,
+                label: message.label,
+                line_style: message.line_style,
+                arrow_head: message.arrow_head,
+                is_self: message.is_self,
+                points,
+                label_position,
+            }
+        })
+        .collect();
+
+    SvgSequenceMeta {
+        participants,
+        messages,
+        activations: layout
+            .activations
+            .into_iter()
+            .map(|activation| SvgSequenceActivation {
+                actor_id: activation.actor_id,
+                box_rect: rect(
+                    activation.x,
+                    activation.top_y,
+                    activation.width,
+                    activation.bottom_y - activation.top_y,
+                ),
+            })
+            .collect(),
+        notes: layout
+            .notes
+            .into_iter()
+            .map(|note| SvgSequenceNote {
+                text: note.text,
+                box_rect: rect(note.x, note.y, note.width, note.height),
+            })
+            .collect(),
+        blocks: layout
+            .blocks
+            .into_iter()
+            .map(|block| SvgSequenceBlock {
+                block_type: block.block_type,
+                label: block.label,
+                box_rect: rect(block.x, block.y, block.width, block.height),
+                dividers: block.dividers,
+            })
+            .collect(),
+    }
+}
+
+pub(crate) fn convert_class(layout: JsClassLayout, ids: &mut ElementIds) -> SvgClassMeta {
+    SvgClassMeta {
+        classes: layout
+            .classes
+            .into_iter()
+            .map(|class| SvgClassNode {
+                element_id: ids.node(&class.id),
+                id: class.id,
+                label: class.label,
+                annotation: class.annotation,
+                box_rect: rect(class.x, class.y, class.width, class.height),
+                header_height: class.header_height,
+                attributes_height: class.attr_height,
+                methods_height: class.method_height,
+            })
+            .collect(),
+        relationships: layout
+            .relationships
+            .into_iter()
+            .map(|rel| SvgClassRelationship {
+                element_id: ids.edge(&rel.from, &rel.to),
+                label_position: edge_label_position(
+                    rel.label.as_deref(),
+                    rel.label_position,
+                    &rel.points,
+                ),
+                from: rel.from,
+                to: rel.to,
+                relationship_type: rel.relationship_type,
+                marker_at: rel.marker_at,
+                label: rel.label,
+                points: rel.points,
+            })
+            .collect(),
+    }
+}
+
+pub(crate) fn convert_er(layout: JsErLayout, ids: &mut ElementIds) -> SvgErMeta {
+    SvgErMeta {
+        entities: layout
+            .entities
+            .into_iter()
+            .map(|entity| SvgErEntity {
+                element_id: ids.node(&entity.id),
+                id: entity.id,
+                label: entity.label,
+                box_rect: rect(entity.x, entity.y, entity.width, entity.height),
+                header_height: entity.header_height,
+                row_height: entity.row_height,
+            })
+            .collect(),
+        relationships: layout
+            .relationships
+            .into_iter()
+            .map(|rel| SvgErRelationship {
+                element_id: ids.edge(&rel.entity1, &rel.entity2),
+                // JS 只在至少两个点时绘制 label
+                label_position: if rel.points.len() >= 2 {
+                    edge_label_position(Some(&rel.label), None, &rel.points)
+                } else {
+                    None
+                },
+                from: rel.entity1,
+                to: rel.entity2,
+                label: rel.label,
+                identifying: rel.identifying,
+                from_cardinality: rel.cardinality1,
+                to_cardinality: rel.cardinality2,
+                points: rel.points,
+            })
+            .collect(),
+    }
+}
+
+fn parse_layout<T: for<'de> Deserialize<'de>>(layout: serde_json::Value) -> Result<T> {
+    serde_json::from_value(layout).map_err(|err| BeautifulMermaidError::Json {
+        message: format!("解析 renderMermaidWithMeta 布局失败: {err}"),
+    })
+}
+
+/// 把 JS 的 `{ kind, layout }` 转成 `(viewBox, meta)`。
+///
+/// `id_prefix` 必须与传给 JS 的 `idPrefix` 一致，否则 meta 里的 `element_id` 对不上 SVG。
+pub(crate) fn convert(
+    kind: &str,
+    layout: serde_json::Value,
+    id_prefix: &str,
+) -> Result<(SvgRect, SvgDiagramMeta)> {
+    let size: JsSize = parse_layout(layout.clone())?;
+    let view_box = rect(
+        0.0,
+        0.0,
+        size.width.unwrap_or_default(),
+        size.height.unwrap_or_default(),
+    );
+
+    let mut ids = ElementIds::new(id_prefix);
+    let meta = match kind {
+        "sequence" => SvgDiagramMeta::Sequence(convert_sequence(parse_layout(layout)?, &mut ids)),
+        "class" => SvgDiagramMeta::Class(convert_class(parse_layout(layout)?, &mut ids)),
+        "er" => SvgDiagramMeta::Er(convert_er(parse_layout(layout)?, &mut ids)),
+        _ => SvgDiagramMeta::Flowchart(convert_flowchart(parse_layout(layout)?, &mut ids)),
+    };
+
+    Ok((view_box, meta))
+}
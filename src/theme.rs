@@ -41,6 +41,18 @@ pub static THEMES: Lazy<HashMap<&'static str, DiagramColors>> = Lazy::new(|| {
     // --------------------------------------------------------------------
     // 注意：这里保持与 TS 版 `src/theme.ts` 完全一致
     // --------------------------------------------------------------------
+    themes.insert(
+        "zinc-light",
+        DiagramColors {
+            bg: "#FFFFFF".to_string(),
+            fg: "#27272A".to_string(),
+            line: None,
+            accent: None,
+            muted: None,
+            surface: None,
+            border: None,
+        },
+    );
     themes.insert(
         "zinc-dark",
         DiagramColors {
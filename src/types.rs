@@ -4,13 +4,17 @@
 // 这里的字段名尽量保持与 TypeScript 版 `beautiful-mermaid` 的 options 对齐：
 // - 颜色：bg/fg/line/accent/muted/surface/border
 // - 版式：font/padding/nodeSpacing/layerSpacing/transparent
+// - 元素 id：idPrefix（SVG 节点/边 `<g>` 的 id 前缀）
+// - 无障碍：title/description（覆盖 Mermaid 的 accTitle/accDescr）
 // - ASCII：useAscii/paddingX/paddingY/boxBorderPadding
+// - PNG（feature = "png"）：scale/dpi/background
 // ============================================================================
 
 use serde::{Deserialize, Serialize};
 
-/// SVG 渲染参数（对齐 TS: `RenderOptions`）。
+/// SVG 渲染参数（对齐 TS: `RenderOptions`，serde 字段名同样是 camelCase，也接受 snake_case 别名）。
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct RenderOptions {
     // --------------------------------------------------------------------
     // 颜色（CSS variables）
@@ -39,17 +43,48 @@ pub struct RenderOptions {
     /// 画布 padding（单位 px）。
     pub padding: Option<f64>,
     /// 同层节点水平间距。
+    #[serde(alias = "node_spacing")]
     pub node_spacing: Option<f64>,
     /// 层与层之间的垂直间距。
+    #[serde(alias = "layer_spacing")]
     pub layer_spacing: Option<f64>,
     /// 是否透明背景（true 时 SVG 不画背景）。
     pub transparent: Option<bool>,
+
+    // --------------------------------------------------------------------
+    // 元素 id
+    // --------------------------------------------------------------------
+    /// SVG 元素 id 前缀（默认空）。
+    ///
+    /// 节点/边的 `<g>` 会带稳定 id（`{prefix}node-{id}`、`{prefix}edge-{from}-{to}-{n}`），
+    /// 箭头 marker 的 id 也会加上前缀；同一页面放多张图时用不同前缀避免 id 冲突。
+    #[serde(alias = "id_prefix")]
+    pub id_prefix: Option<String>,
+
+    // --------------------------------------------------------------------
+    // 无障碍（accessibility）
+    // --------------------------------------------------------------------
+    /// SVG `<title>`（优先于图里的 `accTitle:`；都没有时不输出 `<title>`）。
+    pub title: Option<String>,
+    /// SVG `<desc>`（优先于图里的 `accDescr`；都没有时自动生成节点/边列表）。
+    pub description: Option<String>,
+
+    // --------------------------------------------------------------------
+    // 后端
+    // --------------------------------------------------------------------
+    /// 渲染后端（Rust 独有，TS 版没有对应选项）。
+    ///
+    /// - `Js`（默认）：QuickJS 执行 vendored bundle；
+    /// - `Native`：纯 Rust 实现，目前覆盖 flowchart/state/sequence，其余图类型仍走 JS。
+    pub backend: Option<Backend>,
 }
 
-/// ASCII/Unicode 渲染参数（对齐 TS: `AsciiRenderOptions`）。
+/// ASCII/Unicode 渲染参数（对齐 TS: `AsciiRenderOptions`，serde 字段名同样是 camelCase，也接受 snake_case 别名）。
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct AsciiRenderOptions {
     /// true = 纯 ASCII（+ - | >），false = Unicode 线条（┌ ─ │ ►）。
+    #[serde(alias = "use_ascii")]
     pub use_ascii: Option<bool>,
     /// 边走线策略(对齐 TS: `routing`)。
     ///
@@ -61,12 +96,89 @@ pub struct AsciiRenderOptions {
     /// - `use_ascii=true` 时默认 `strict`
     /// - `use_ascii=false` 时默认 `relaxed`
     pub routing: Option<AsciiRouting>,
+    /// 走线时每次转弯的额外代价（Rust 独有，TS 版没有对应选项）。
+    ///
+    /// - 不设置 / 0：A* 只比步数，输出与 TS 逐字节一致；
+    /// - 调大（如 2~4）后同样长度下拐点更少的路径胜出，必要时宁可多走几步也少拐一次弯。
+    ///
+    /// 对 flowchart/state 的 native 后端与 JS 后端（走 native pathfinder 时）都生效；
+    /// JS 引擎关闭 native pathfinder（`EngineConfig::native_pathfinder(false)`）时 JS 用自己的 A*，忽略该选项。
+    #[serde(alias = "turn_penalty")]
+    pub turn_penalty: Option<u32>,
+    /// TS 兼容模式（Rust 独有，TS 版没有对应选项）。
+    ///
+    /// native pathfinder 的最小堆（严格 `<` 比较）、邻居顺序（右、左、下、上）与启发式都照搬 bundle，
+    /// 默认参数下与纯 JS A* 逐字节一致（`tests/ts_compatible_pathfinder.rs` 在全部 testdata 上对照）。
+    /// 设为 `true` 时把会改变走线的 Rust 独有参数固定下来，保证这一点不被调用方的其他选项打破：
+    ///
+    /// - `turn_penalty` 按 0 处理；
+    /// - 关掉 A* 之前的封闭探测（探测不改变输出，关掉后每次搜索展开的格子也与 TS 相同）。
+    ///
+    /// `routing = Global` 是另一种走线策略（TS 没有），不受这个开关影响。
+    #[serde(alias = "ts_compatible")]
+    pub ts_compatible: Option<bool>,
+    /// 互不相连的子图是否在多个线程上并行走线（Rust 独有，TS 版没有对应选项）。
+    ///
+    /// native 后端先在各线程上分别预演每个连通分量，再按原走线顺序校验、落定；
+    /// 校验不过就从那条边起退回逐条走线，所以输出总是与逐条走线逐字节相同。
+    ///
+    /// 分量按节点在 grid 上的外接矩形归组，矩形重叠的分量算一个区域（一起预演），
+    /// 所以只有几个分量在 grid 上彼此分开（比如几条并排的 LR 流水线）时才真正并行。
+    ///
+    /// - 不设置：多核、多个区域且边足够多时才并行；
+    /// - `true`：只要有多个区域就并行（单核上也开两个线程，测试用）；
+    /// - `false`：总是逐条走线（对照 / 排查用）。
+    ///
+    /// 只对 native 后端的 flowchart/state 生效；wasm32 上总是逐条走线。
+    /// 用 `render_mermaid_ascii_with_stats` 可以看到分了几个区域、哪些边取自预演
+    /// （`AsciiRoutingStats.parallel_regions` / `AsciiEdgeRoutingStats.rehearsed`）。
+    #[serde(alias = "parallel_routing")]
+    pub parallel_routing: Option<bool>,
+    /// 有边走不通、或要放宽约束才走通时返回错误，而不是照常输出（Rust 独有，TS 版没有对应选项）。
+    ///
+    /// - 不设置 / `false`：与 TS 一致，走不通的边不画，兜底走通的边照常画（可能与其他边重叠）；
+    /// - `true`：返回 `BeautifulMermaidError::Unroutable`，消息里列出每条边的结果与原因
+    ///   （与 `AsciiRenderMetaEdge.outcome` / `reason` 相同）。
+    ///
+    /// 只有 native 后端能给出走线结果：设为 `true` 时 flowchart/state 总是走 native，
+    /// 即使 `backend` 是 `Some(Backend::Js)` 也一样（不报错，meta 的 `backend` 字段记实际用的后端）；
+    /// 没编译 `native-backend` 时 flowchart/state 返回 `BackendUnavailable`。
+    #[serde(alias = "fail_on_unroutable")]
+    pub fail_on_unroutable: Option<bool>,
     /// 节点水平间距。
+    #[serde(alias = "padding_x")]
     pub padding_x: Option<i32>,
     /// 节点垂直间距。
+    #[serde(alias = "padding_y")]
     pub padding_y: Option<i32>,
     /// 节点盒子内部边框 padding。
+    #[serde(alias = "box_border_padding")]
     pub box_border_padding: Option<i32>,
+    /// 渲染后端（Rust 独有，TS 版没有对应选项）。
+    ///
+    /// - `Js`（默认）：QuickJS 执行 vendored bundle；
+    /// - `Native`：纯 Rust 实现，目前覆盖 flowchart/state/sequence，其余图类型仍走 JS。
+    ///
+    /// `routing: Some(AsciiRouting::Global)` 或 `fail_on_unroutable: Some(true)` 时 flowchart/state
+    /// 不看这个选项，总是走 `Native`（两者只有 native 实现）；实际用的后端见 `AsciiRenderMeta.backend`。
+    pub backend: Option<Backend>,
+}
+
+/// PNG 光栅化参数（feature = "png"，Rust 独有，TS 版没有对应选项）。
+///
+/// 输出像素尺寸 = SVG 尺寸 × `scale` × `dpi / 96`（向上取整）。
+#[cfg(feature = "png")]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RasterOptions {
+    /// 缩放倍数（默认 1.0；2.0 适合高分屏）。
+    pub scale: Option<f32>,
+    /// 目标 DPI（默认 96，即 1 SVG px = 1 像素），同时写入 PNG 的 pHYs 块。
+    pub dpi: Option<f32>,
+    /// 背景色（任意 CSS 颜色，如 `#ffffff` / `white` / `transparent`）。
+    ///
+    /// 不设置时沿用 SVG 自身背景（`RenderOptions.bg`）；
+    /// SVG 是透明背景（`transparent: Some(true)`）时 PNG 也是透明的。
+    pub background: Option<String>,
 }
 
 /// ASCII/Unicode 的边走线策略(对齐 TS: `routing`)。
@@ -75,6 +187,28 @@ pub struct AsciiRenderOptions {
 pub enum AsciiRouting {
     Strict,
     Relaxed,
+    /// relaxed 规则逐条走完后，再整体打分并反复拆掉/重走最差的边（rip-up-and-reroute）。
+    ///
+    /// 只有 native 后端实现；flowchart/state 遇到它时总是走 native，
+    /// 没编译 `native-backend` 时返回 `BackendUnavailable`（其余图类型没有走线，照常渲染）。
+    Global,
+}
+
+#[cfg(any(feature = "js-backend", feature = "native-backend"))]
+impl AsciiRenderOptions {
+    /// 实际生效的转弯代价（`ts_compatible` 时总是 0）。
+    pub(crate) fn effective_turn_penalty(&self) -> u32 {
+        if self.ts_compatible == Some(true) {
+            0
+        } else {
+            self.turn_penalty.unwrap_or(0)
+        }
+    }
+
+    /// A* 之前是否先做封闭探测（`ts_compatible` 时关掉）。
+    pub(crate) fn enclosure_probe(&self) -> bool {
+        self.ts_compatible != Some(true)
+    }
 }
 
 impl AsciiRouting {
@@ -83,6 +217,69 @@ impl AsciiRouting {
         match self {
             Self::Strict => "strict",
             Self::Relaxed => "relaxed",
+            Self::Global => "global",
+        }
+    }
+}
+
+/// 一条边的走线结果（见 `AsciiRenderMetaEdge.outcome`）。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AsciiRouteOutcome {
+    /// 按当前 routing 规则走通（含 relaxed 下与同靶边共用终点段、退到对角端口）。
+    #[default]
+    Routed,
+    /// 规则内走不通，最后一次布局尝试放开约束才走通：可能与其他边重叠 / 交叉。
+    RoutedWithFallback,
+    /// 5 次布局尝试都走不通：这条边没有画出来。
+    Unroutable,
+}
+
+/// 边没有按规则走通的原因（`RoutedWithFallback` / `Unroutable` 时给出）。
+///
+/// 只避开节点时总能走通（节点之间至少隔一格），所以原因总是某条规则：
+/// strict 的边可能 `Unroutable`，relaxed 的边最多 `RoutedWithFallback`。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AsciiRouteReason {
+    /// strict：每个候选端口的路径都会补出 `┼`，或与其他边非法共线。
+    StrictRules,
+    /// relaxed：每个候选端口的路径都会与其他边的拐点 / 端点重合（交叉只加罚，不会走不通）。
+    RelaxedRules,
+}
+
+impl AsciiRouteReason {
+    /// 给人看的说明（错误消息 / CLI 用）。
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::StrictRules => "strict 规则下每条候选路径都会补出 `┼` 或与其他边非法共线",
+            Self::RelaxedRules => "relaxed 规则下每条候选路径都会与其他边的拐点或端点重合",
+        }
+    }
+}
+
+/// 渲染后端（SVG 与 ASCII/Unicode 共用）。
+///
+/// - ASCII：`Native` 的输出与 `Js` 逐字符一致（由 `tests/testdata` 的 golden 文件把关）；
+/// - SVG：flowchart/state 的元素结构、配色变量、元素 id 与 `Js` 一致，但坐标来自 Rust 自己的分层布局，
+///   不保证与 dagre 逐像素相同；sequence 不依赖 dagre，与 `Js` 逐字节一致；
+/// - SVG 不支持的图类型（class/ER）会自动回退到 `Js`；
+/// - 只编译了一个后端（`js-backend` / `native-backend` feature）时忽略该选项，
+///   没有 `js-backend` 又遇到 native 不支持的输出时返回 `BackendUnavailable`。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    #[default]
+    Js,
+    Native,
+}
+
+impl Backend {
+    /// 小写名称（CLI 参数值）。
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Js => "js",
+            Self::Native => "native",
         }
     }
 }
@@ -97,7 +294,8 @@ impl AsciiRouting {
 //
 // 重要说明：
 // - 当前实现是“纯语法校验”：
-//   - 后端使用纯 Rust parser（`selkie::parse`）判断 Mermaid 是否可被解析。
+//   - 后端使用纯 Rust parser（`selkie::parse`）判断 Mermaid 是否可被解析；
+//   - 编译了 `native-backend` 时还要过 native parser：与 `Backend::Native` 接受/拒绝同样的输入。
 //   - 它更适合做 CI gate, 以及在无 Node 环境下做快速检查。
 // - 它不保证“本仓库渲染器一定能渲染”：
 //   - 本仓库的渲染 JS bundle 目前只明确支持 Flowchart/State、Sequence、Class、ER。
@@ -161,6 +359,16 @@ pub struct AsciiRenderMetaEdge {
     pub label: String,
     /// edge stroke 的“有序坐标序列”（包含拐点/箭头等关键格子）。
     pub path: Vec<AsciiDrawingCoord>,
+    /// 走线结果（Rust 独有）。
+    ///
+    /// 只有 native 后端（`AsciiRenderMeta.backend == Backend::Native`）给出完整结果。
+    /// JS 后端不报告兜底，也不给原因：有 path 的边都记为 `Routed`（包括放开约束才走通的），
+    /// 没有 path 的都记为 `Unroutable`，`reason` 总是 `None`，不会出现 `RoutedWithFallback`。
+    #[serde(default)]
+    pub outcome: AsciiRouteOutcome,
+    /// `outcome` 不是 `Routed` 时的原因（Rust 独有）。
+    #[serde(default)]
+    pub reason: Option<AsciiRouteReason>,
 }
 
 /// ASCII/Unicode 渲染的完整 meta（nodes + edges）。
@@ -168,11 +376,717 @@ pub struct AsciiRenderMetaEdge {
 pub struct AsciiRenderMeta {
     pub nodes: Vec<AsciiRenderMetaNode>,
     pub edges: Vec<AsciiRenderMetaEdge>,
+    /// 实际走线的后端（Rust 独有）。
+    ///
+    /// 不一定是 `AsciiRenderOptions.backend`：`routing: Global` 与 `fail_on_unroutable: true`
+    /// 会把 flowchart/state 换到 `Native`；`Js` 时边的 `outcome` / `reason` 不完整（见 `AsciiRenderMetaEdge.outcome`）。
+    #[serde(default)]
+    pub backend: Backend,
 }
 
 /// ASCII/Unicode 渲染的输出：text + meta。
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AsciiRenderWithMeta {
     pub text: String,
-    pub meta: AsciiRenderMeta,
+    pub meta: AsciiDiagramMeta,
+}
+
+// ============================================================================
+// 按图类型区分的 meta（sequence / class / ER）
+// ============================================================================
+//
+// 背景：
+// - `AsciiRenderMeta` 是 flowchart 形状的（node box + edge path），
+//   套在 sequence/class/ER 上只能返回空数组，上层 UI 无从高亮。
+// - 不同图类型的“可高亮单元”本质不同：
+//   - sequence：participant + lifeline 列范围、message 所在行与箭头跨度；
+//   - class：class box 内的成员行；
+//   - ER：entity box 内的属性行、关系两端的 cardinality 标记。
+//
+// 设计取舍：
+// - 用 `kind` tag 的枚举承载，JSON 形态为 `{ "kind": "sequence", ... }`；
+// - 坐标单位与 flowchart meta 一致（终端 cell），可直接复用 `AsciiBox/AsciiDrawingCoord`。
+
+/// ASCII/Unicode 渲染 meta（按图类型区分）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum AsciiDiagramMeta {
+    /// Flowchart/State：node box + edge path。
+    Flowchart(AsciiRenderMeta),
+    /// Sequence：participant/lifeline/message/note/block。
+    Sequence(AsciiSequenceMeta),
+    /// Class：class box + 成员行 + relationship path。
+    Class(AsciiClassMeta),
+    /// ER：entity box + 属性行 + relationship path/cardinality。
+    Er(AsciiErMeta),
+}
+
+impl AsciiDiagramMeta {
+    /// flowchart meta（其他图类型返回 None）。
+    pub fn as_flowchart(&self) -> Option<&AsciiRenderMeta> {
+        match self {
+            Self::Flowchart(meta) => Some(meta),
+            _ => None,
+        }
+    }
+
+    /// sequence meta（其他图类型返回 None）。
+    pub fn as_sequence(&self) -> Option<&AsciiSequenceMeta> {
+        match self {
+            Self::Sequence(meta) => Some(meta),
+            _ => None,
+        }
+    }
+
+    /// class meta（其他图类型返回 None）。
+    pub fn as_class(&self) -> Option<&AsciiClassMeta> {
+        match self {
+            Self::Class(meta) => Some(meta),
+            _ => None,
+        }
+    }
+
+    /// ER meta（其他图类型返回 None）。
+    pub fn as_er(&self) -> Option<&AsciiErMeta> {
+        match self {
+            Self::Er(meta) => Some(meta),
+            _ => None,
+        }
+    }
+}
+
+/// sequence/class/ER meta 的 `backend` 缺省值（旧 JSON 没有该字段）。
+fn native_meta_backend() -> Backend {
+    Backend::Native
+}
+
+/// sequence 图的 meta。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsciiSequenceMeta {
+    pub participants: Vec<AsciiSequenceParticipant>,
+    pub messages: Vec<AsciiSequenceMessage>,
+    pub notes: Vec<AsciiSequenceNote>,
+    pub blocks: Vec<AsciiSequenceBlock>,
+    /// 计算这份 meta 的后端（Rust 独有）：总是 `Native`。
+    ///
+    /// JS bundle 只给 flowchart/state 出 meta，sequence 的坐标一律由 native 布局算出。
+    /// 用 `Js` 渲染时 text 来自 bundle：内嵌 bundle 与 native 布局逐格对齐（`tests/testdata` 的 golden 把关），
+    /// 外部 bundle（`EngineConfig.bundle_path` / `bundle_source`）改过布局时两者可能对不上。
+    #[serde(default = "native_meta_backend")]
+    pub backend: Backend,
+}
+
+/// sequence participant（含顶部/底部两个 box 与 lifeline）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsciiSequenceParticipant {
+    pub id: String,
+    pub label: String,
+    /// `participant` 或 `actor`（ASCII 下两者画法相同）。
+    #[serde(rename = "type")]
+    pub participant_type: String,
+    /// 顶部 header box。
+    pub header_box: AsciiBox,
+    /// 底部 footer box（与 header 同宽同高）。
+    pub footer_box: AsciiBox,
+    /// lifeline 所在列。
+    pub lifeline_x: i32,
+    /// lifeline 的行范围（闭区间，不含上下两个 box）。
+    pub lifeline_top: i32,
+    pub lifeline_bottom: i32,
+}
+
+/// sequence message（箭头行 + 箭头跨度 + label 位置）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsciiSequenceMessage {
+    /// message 在源码中的顺序（从 0 开始）。
+    pub index: usize,
+    pub from: String,
+    pub to: String,
+    pub label: String,
+    /// `solid` 或 `dashed`。
+    pub line_style: String,
+    /// `filled` 或 `open`。
+    pub arrow_head: String,
+    /// true 表示 self message（from == to）。
+    pub is_self: bool,
+    /// 箭头所在行（self message 为回环的第一行）。
+    pub row: i32,
+    /// 箭头的列跨度（闭区间；self message 为回环的左右边界）。
+    pub span_start_x: i32,
+    pub span_end_x: i32,
+    /// label 文本所在矩形（高度恒为 1）。
+    pub label_box: AsciiBox,
+    /// 箭头 stroke 的有序坐标（从 from 一侧到箭头头部）。
+    pub path: Vec<AsciiDrawingCoord>,
+}
+
+/// sequence note（`Note left of/right of/over`）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsciiSequenceNote {
+    pub actor_ids: Vec<String>,
+    /// `left` / `right` / `over`。
+    pub position: String,
+    pub text: String,
+    #[serde(rename = "box")]
+    pub box_rect: AsciiBox,
+}
+
+/// sequence block（`loop/alt/opt/par/critical/break/rect`）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsciiSequenceBlock {
+    #[serde(rename = "type")]
+    pub block_type: String,
+    pub label: String,
+    /// 覆盖的 message 下标范围（闭区间）。
+    pub start_index: usize,
+    pub end_index: usize,
+    #[serde(rename = "box")]
+    pub box_rect: AsciiBox,
+    /// `else/and` 分隔线。
+    pub dividers: Vec<AsciiSequenceDivider>,
+}
+
+/// sequence block 内的 `else/and` 分隔线。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsciiSequenceDivider {
+    pub label: String,
+    /// 分隔线所在行。
+    pub row: i32,
+}
+
+/// class 图的 meta。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsciiClassMeta {
+    pub classes: Vec<AsciiClassNode>,
+    pub relationships: Vec<AsciiClassRelationship>,
+    /// 计算这份 meta 的后端（Rust 独有）：总是 `Native`。
+    ///
+    /// JS bundle 只给 flowchart/state 出 meta，class 的坐标一律由 native 布局算出。
+    /// 用 `Js` 渲染时 text 来自 bundle：内嵌 bundle 与 native 布局逐格对齐（`tests/testdata` 的 golden 把关），
+    /// 外部 bundle（`EngineConfig.bundle_path` / `bundle_source`）改过布局时两者可能对不上。
+    #[serde(default = "native_meta_backend")]
+    pub backend: Backend,
+}
+
+/// class box（含成员行）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsciiClassNode {
+    pub id: String,
+    pub label: String,
+    pub annotation: Option<String>,
+    #[serde(rename = "box")]
+    pub box_rect: AsciiBox,
+    /// 属性行（顺序与源码一致）。
+    pub attributes: Vec<AsciiMemberRow>,
+    /// 方法行（顺序与源码一致）。
+    pub methods: Vec<AsciiMemberRow>,
+}
+
+/// box 内的一行成员文本（class 成员 / ER 属性）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsciiMemberRow {
+    /// 实际绘制的文本（例如 `+name: String`、`PK int id`）。
+    pub text: String,
+    /// 文本所在矩形（高度恒为 1，宽度为终端显示宽度）。
+    #[serde(rename = "box")]
+    pub box_rect: AsciiBox,
+}
+
+/// class relationship。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsciiClassRelationship {
+    pub from: String,
+    pub to: String,
+    /// `inheritance` / `composition` / `aggregation` / `association` / `dependency` / `realization`。
+    #[serde(rename = "type")]
+    pub relationship_type: String,
+    pub label: Option<String>,
+    /// label 文本（含左右各 1 格空白）所在矩形；无 label 或 label 落在画布外时为 None。
+    pub label_box: Option<AsciiBox>,
+    /// 线条的有序坐标（从 from box 一侧到 to box 一侧）。
+    pub path: Vec<AsciiDrawingCoord>,
+    /// 关系标记（三角/菱形/箭头）所在的格子。
+    pub marker: Option<AsciiDrawingCoord>,
+}
+
+/// ER 图的 meta。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsciiErMeta {
+    pub entities: Vec<AsciiErEntity>,
+    pub relationships: Vec<AsciiErRelationship>,
+    /// 计算这份 meta 的后端（Rust 独有）：总是 `Native`。
+    ///
+    /// JS bundle 只给 flowchart/state 出 meta，ER 的坐标一律由 native 布局算出。
+    /// 用 `Js` 渲染时 text 来自 bundle：内嵌 bundle 与 native 布局逐格对齐（`tests/testdata` 的 golden 把关），
+    /// 外部 bundle（`EngineConfig.bundle_path` / `bundle_source`）改过布局时两者可能对不上。
+    #[serde(default = "native_meta_backend")]
+    pub backend: Backend,
+}
+
+/// ER entity box（含属性行）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsciiErEntity {
+    pub id: String,
+    pub label: String,
+    #[serde(rename = "box")]
+    pub box_rect: AsciiBox,
+    pub attributes: Vec<AsciiMemberRow>,
+}
+
+/// ER relationship（含两端 cardinality 标记）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsciiErRelationship {
+    pub from: String,
+    pub to: String,
+    pub label: String,
+    /// true = `--`（实线，identifying），false = `..`（虚线）。
+    pub identifying: bool,
+    /// label 实际绘制的矩形（可能被截断）；label 落在画布外时为 None。
+    pub label_box: Option<AsciiBox>,
+    /// 线条的有序坐标（从 from entity 一侧到 to entity 一侧）。
+    pub path: Vec<AsciiDrawingCoord>,
+    pub from_cardinality: AsciiCardinalityMarker,
+    pub to_cardinality: AsciiCardinalityMarker,
+}
+
+/// ER cardinality 标记（例如 `||`、`o{`，Unicode 下为 `║`、`o╟`）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsciiCardinalityMarker {
+    /// `one` / `zero-one` / `many` / `zero-many`。
+    pub cardinality: String,
+    /// 实际绘制的字符。
+    pub text: String,
+    /// 标记所在矩形（高度恒为 1）。
+    #[serde(rename = "box")]
+    pub box_rect: AsciiBox,
+}
+
+// ============================================================================
+// 走线统计（A* 调用次数 / 展开格子数 / 按规则拒绝的单步 ...）
+// ============================================================================
+//
+// 背景：
+// - 字符画渲染慢或者线走得难看时，只看输出猜不出原因：是 A* 调了几千次、
+//   每次都翻遍整个窗口，还是某条规则把通道全堵死了；
+// - `NativeAStar` 逐次记录搜索统计，native flowchart/state 走线时按边汇总，
+//   经 `render_mermaid_ascii_with_stats` / CLI `--stats` 带出来。
+
+/// 按规则统计被拒绝的单步（一步 = 从当前格子走向一个邻居）。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StepRejections {
+    /// 走进 blocked 格子（节点占用的格子等；终点除外）。
+    pub blocked: u64,
+    /// strict：会补出 `┼`；relaxed：走进已占用的点位（只有起点第一步 / 终点前一步有受控豁免）。
+    pub crossing: u64,
+    /// 线段已被占用且不允许共享（共线重叠规则）。
+    pub segment: u64,
+}
+
+impl StepRejections {
+    /// 三类拒绝之和。
+    pub fn total(&self) -> u64 {
+        self.blocked + self.crossing + self.segment
+    }
+}
+
+/// 一次路径搜索（一次 A* 调用）的统计，见 `routing::RoutingGrid::last_stats`。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchStats {
+    /// 展开（从堆里取出、检查邻居）的格子数。
+    pub expansions: u64,
+    /// 堆的峰值长度。
+    pub heap_peak: u64,
+    /// 找到的路径的代价（同 `Route.cost`）；走不通时为 `None`。
+    pub cost: Option<u32>,
+    /// 被拒绝的单步（A* 与封闭探测的都算）。
+    pub rejections: StepRejections,
+    /// A* 之前的封闭探测看过的格子数（探测出走不通时 `expansions` 为 0）。
+    pub probe_cells: u64,
+    /// 展开预算用完、放弃了搜索（`cost` 为 `None`，但不一定真的走不通）。
+    ///
+    /// 只有 `turn_penalty > 0` 的方向感知搜索有预算；逐格搜索与 TS 一致，总会搜到底。
+    #[serde(default)]
+    pub budget_exhausted: bool,
+}
+
+/// 一条边的走线统计（最后一次布局尝试里的，global 下含 rip-up 之后的重走）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsciiEdgeRoutingStats {
+    pub from: String,
+    pub to: String,
+    pub label: String,
+    /// A* 调用次数（候选端口 × 搜索半径 × 规则）。
+    pub searches: u32,
+    /// 其中走不通的次数（含展开预算用完而放弃的）。
+    pub unreachable: u32,
+    /// 其中展开预算用完而放弃的次数（见 `SearchStats.budget_exhausted`）。
+    #[serde(default)]
+    pub budget_exhausted: u32,
+    /// 所有调用展开的格子数之和。
+    pub expansions: u64,
+    /// 所有调用的封闭探测看过的格子数之和（见 `SearchStats.probe_cells`）。
+    pub probe_cells: u64,
+    /// 所有调用里堆的最大峰值。
+    pub heap_peak: u64,
+    /// 所有调用里被拒绝的单步之和。
+    pub rejections: StepRejections,
+    /// 最终路径的搜索代价（固定形状的自环、复用平行边路径、没走通时为 `None`）。
+    pub path_cost: Option<u32>,
+    /// 最终路径来自打开 `allow_end_segment_reuse` 的搜索（relaxed：与其他同靶边共用终点段）。
+    pub end_segment_reuse: bool,
+    /// 最终路径来自最后一次布局尝试的无约束兜底（只避开节点，可能与其他边重叠）。
+    pub unconstrained: bool,
+    /// 直接复用同向平行边的路径，没有自己搜索（relaxed + Unicode）。
+    pub shared_path: bool,
+    /// 结果取自并行预演（只在 `parallel_routing: Some(true)` 时出现）。
+    ///
+    /// 统计也是预演时的搜索：预演在只有本区域路径的占用表上进行，走不通的那几次展开的格子
+    /// 可能比逐条走线时多；要逐条走线的统计就设 `parallel_routing: Some(false)`。
+    pub rehearsed: bool,
+    /// 是否走通（没走通的边不画）。
+    pub routed: bool,
+}
+
+/// 一次 ASCII/Unicode 渲染的走线统计。
+///
+/// 只有 flowchart/state 有 A* 走线；其余图类型 `layout_attempts = 0`、`edges` 为空。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsciiRoutingStats {
+    /// 布局尝试次数（有边走不通时整体换起始偏移重来，最多 5 次）。
+    pub layout_attempts: u32,
+    /// 最后一次布局尝试里分给多个线程预演的走线区域数（0 = 没有并行预演）。
+    ///
+    /// 统计时只有显式 `parallel_routing: Some(true)` 才并行预演，自动模式总是逐条走线。
+    pub parallel_regions: u32,
+    /// 按边的声明顺序。
+    pub edges: Vec<AsciiEdgeRoutingStats>,
+}
+
+/// ASCII/Unicode 渲染的输出：text + 走线统计。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AsciiRenderWithStats {
+    pub text: String,
+    pub stats: AsciiRoutingStats,
+}
+
+// ============================================================================
+// SVG meta（像素坐标）
+// ============================================================================
+//
+// 背景：
+// - `render_mermaid` 只返回 SVG 字符串，Web UI 想做高亮/动画/点击命中时拿不到几何信息；
+// - 这里把 JS 布局阶段的结果（像素坐标）原样带出来，形状对齐 `AsciiDiagramMeta`。
+//
+// 约定：
+// - 坐标系与 SVG 的 user space 一致（viewBox 原点在左上角，单位 px）；
+// - 所有矩形都是“左上角 + 宽高”（sequence actor 在 JS 侧是中心点，这里已换算）。
+
+/// SVG 坐标系里的点。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct SvgPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// SVG 坐标系里的矩形（左上角 + 宽高）。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct SvgRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// SVG 渲染的输出：svg + viewBox + meta。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SvgRenderWithMeta {
+    pub svg: String,
+    /// 根 `<svg>` 的 viewBox（恒为 `0 0 width height`）。
+    pub view_box: SvgRect,
+    pub meta: SvgDiagramMeta,
+}
+
+/// SVG 渲染 meta（按图类型区分，JSON 形态为 `{ "kind": "flowchart", ... }`）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SvgDiagramMeta {
+    /// Flowchart/State：node box + edge polyline + subgraph。
+    Flowchart(SvgFlowchartMeta),
+    /// Sequence：actor/lifeline/message/activation/note/block。
+    Sequence(SvgSequenceMeta),
+    /// Class：class box + relationship polyline。
+    Class(SvgClassMeta),
+    /// ER：entity box + relationship polyline。
+    Er(SvgErMeta),
+}
+
+impl SvgDiagramMeta {
+    /// flowchart meta（其他图类型返回 None）。
+    pub fn as_flowchart(&self) -> Option<&SvgFlowchartMeta> {
+        match self {
+            Self::Flowchart(meta) => Some(meta),
+            _ => None,
+        }
+    }
+
+    /// sequence meta（其他图类型返回 None）。
+    pub fn as_sequence(&self) -> Option<&SvgSequenceMeta> {
+        match self {
+            Self::Sequence(meta) => Some(meta),
+            _ => None,
+        }
+    }
+
+    /// class meta（其他图类型返回 None）。
+    pub fn as_class(&self) -> Option<&SvgClassMeta> {
+        match self {
+            Self::Class(meta) => Some(meta),
+            _ => None,
+        }
+    }
+
+    /// ER meta（其他图类型返回 None）。
+    pub fn as_er(&self) -> Option<&SvgErMeta> {
+        match self {
+            Self::Er(meta) => Some(meta),
+            _ => None,
+        }
+    }
+}
+
+/// Flowchart/State 的 SVG meta。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SvgFlowchartMeta {
+    pub nodes: Vec<SvgMetaNode>,
+    pub edges: Vec<SvgMetaEdge>,
+    /// subgraph（可嵌套）。
+    pub groups: Vec<SvgMetaGroup>,
+}
+
+/// Flowchart node。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SvgMetaNode {
+    pub id: String,
+    /// SVG 里对应 `<g>` 的 id（含 `RenderOptions.id_prefix`）。
+    pub element_id: String,
+    pub label: String,
+    /// `rectangle` / `rounded` / `diamond` / `circle` / ...（与 JS shape 名一致）。
+    pub shape: String,
+    /// 外接矩形（diamond/circle 等也给外接矩形）。
+    #[serde(rename = "box")]
+    pub box_rect: SvgRect,
+}
+
+/// Flowchart edge。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SvgMetaEdge {
+    pub from: String,
+    pub to: String,
+    /// SVG 里对应 `<g>` 的 id（含 `RenderOptions.id_prefix`）。
+    pub element_id: String,
+    pub label: Option<String>,
+    /// `solid` / `dotted` / `thick`。
+    pub style: String,
+    pub has_arrow_start: bool,
+    pub has_arrow_end: bool,
+    /// `<polyline>` 的顶点（SVG 里边都是折线，没有 bezier 控制点）。
+    pub points: Vec<SvgPoint>,
+    /// label 中心点（无 label 时为 None）。
+    pub label_position: Option<SvgPoint>,
+}
+
+/// Flowchart subgraph。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SvgMetaGroup {
+    pub id: String,
+    pub label: String,
+    #[serde(rename = "box")]
+    pub box_rect: SvgRect,
+    pub children: Vec<SvgMetaGroup>,
+}
+
+/// Sequence 的 SVG meta。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SvgSequenceMeta {
+    pub participants: Vec<SvgSequenceParticipant>,
+    pub messages: Vec<SvgSequenceMessage>,
+    pub activations: Vec<SvgSequenceActivation>,
+    pub notes: Vec<SvgSequenceNote>,
+    pub blocks: Vec<SvgSequenceBlock>,
+}
+
+/// Sequence participant（头部 box + lifeline）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SvgSequenceParticipant {
+    pub id: String,
+    /// SVG 里对应 `<g>` 的 id（含 `RenderOptions.id_prefix`）。
+    pub element_id: String,
+    pub label: String,
+    /// `participant` / `actor`。
+    #[serde(rename = "type")]
+    pub participant_type: String,
+    #[serde(rename = "box")]
+    pub box_rect: SvgRect,
+    pub lifeline_x: f64,
+    pub lifeline_top: f64,
+    pub lifeline_bottom: f64,
+}
+
+/// Sequence message。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SvgSequenceMessage {
+    /// 在源码中的序号（从 0 开始）。
+    pub index: usize,
+    pub from: String,
+    pub to: String,
+    /// SVG 里对应 `<g>` 的 id（含 `RenderOptions.id_prefix`）。
+    pub element_id: String,
+    pub label: String,
+    /// `solid` / `dashed`。
+    pub line_style: String,
+    /// `filled` / `open`。
+    pub arrow_head: String,
+    pub is_self: bool,
+    /// 线条顶点（普通 message 两个点；self message 是 4 个点的回环）。
+    pub points: Vec<SvgPoint>,
+    /// label 文本锚点（普通 message 为水平居中的基线位置）。
+    pub label_position: SvgPoint,
+}
+
+/// Sequence activation bar。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SvgSequenceActivation {
+    pub actor_id: String,
+    #[serde(rename = "box")]
+    pub box_rect: SvgRect,
+}
+
+/// Sequence note。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SvgSequenceNote {
+    pub text: String,
+    #[serde(rename = "box")]
+    pub box_rect: SvgRect,
+}
+
+/// Sequence block（loop/alt/opt/...）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SvgSequenceBlock {
+    #[serde(rename = "type")]
+    pub block_type: String,
+    pub label: String,
+    #[serde(rename = "box")]
+    pub box_rect: SvgRect,
+    pub dividers: Vec<SvgSequenceDivider>,
+}
+
+/// Sequence block 内的 `else/and` 分隔线。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SvgSequenceDivider {
+    pub label: String,
+    pub y: f64,
+}
+
+/// Class diagram 的 SVG meta。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SvgClassMeta {
+    pub classes: Vec<SvgClassNode>,
+    pub relationships: Vec<SvgClassRelationship>,
+}
+
+/// Class box（含三个分区的高度，便于定位成员区）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SvgClassNode {
+    pub id: String,
+    /// SVG 里对应 `<g>` 的 id（含 `RenderOptions.id_prefix`）。
+    pub element_id: String,
+    pub label: String,
+    pub annotation: Option<String>,
+    #[serde(rename = "box")]
+    pub box_rect: SvgRect,
+    pub header_height: f64,
+    pub attributes_height: f64,
+    pub methods_height: f64,
+}
+
+/// Class relationship。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SvgClassRelationship {
+    pub from: String,
+    pub to: String,
+    /// SVG 里对应 `<g>` 的 id（含 `RenderOptions.id_prefix`）。
+    pub element_id: String,
+    /// `inheritance` / `composition` / `aggregation` / `association` / `dependency` / `realization`。
+    #[serde(rename = "type")]
+    pub relationship_type: String,
+    /// 关系标记画在哪一端：`from` / `to`。
+    pub marker_at: String,
+    pub label: Option<String>,
+    pub points: Vec<SvgPoint>,
+    pub label_position: Option<SvgPoint>,
+}
+
+/// ER diagram 的 SVG meta。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SvgErMeta {
+    pub entities: Vec<SvgErEntity>,
+    pub relationships: Vec<SvgErRelationship>,
+}
+
+/// ER entity box。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SvgErEntity {
+    pub id: String,
+    /// SVG 里对应 `<g>` 的 id（含 `RenderOptions.id_prefix`）。
+    pub element_id: String,
+    pub label: String,
+    #[serde(rename = "box")]
+    pub box_rect: SvgRect,
+    pub header_height: f64,
+    /// 每个属性行的高度（第 i 行的顶边 = box.y + header_height + i * row_height）。
+    pub row_height: f64,
+}
+
+/// ER relationship。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SvgErRelationship {
+    pub from: String,
+    pub to: String,
+    /// SVG 里对应 `<g>` 的 id（含 `RenderOptions.id_prefix`）。
+    pub element_id: String,
+    pub label: String,
+    pub identifying: bool,
+    /// `one` / `zero-one` / `many` / `zero-many`。
+    pub from_cardinality: String,
+    pub to_cardinality: String,
+    pub points: Vec<SvgPoint>,
+    pub label_position: Option<SvgPoint>,
 }
@@ -0,0 +1,69 @@
+// ============================================================================
+// WebAssembly 绑定（feature = "wasm"，wasm-bindgen）
+//
+// 目标：
+// - 浏览器 / edge worker 里直接调用，函数名与 TS 版一致（camelCase）；
+// - 参数/返回值用 serde 形状（`RenderOptions` / `AsciiRenderOptions` 的 camelCase 字段），
+//   与 TS 版的 options 对象同构，JS 侧不需要额外适配。
+//
+// 取舍：
+// - wasm 里不嵌 QuickJS（需要 wasm32 的 C 工具链，体积也大），只用 native 后端：
+//   四种图类型的 SVG / ASCII 都有 native 实现（只开 native 的构建见 `tests/native_only_build.rs`），
+//   flowchart/state 与 class/ER 的 SVG 坐标与 JS 不同；
+// - `validateMermaid` 需要同时开启 `validate` feature。
+// ============================================================================
+
+use crate::error::BeautifulMermaidError;
+use crate::types::{AsciiRenderOptions, RenderOptions};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use wasm_bindgen::prelude::*;
+
+/// `undefined` / `null` 视为默认参数，其余按 serde 形状解析。
+fn parse_options<T: DeserializeOwned + Default>(options: JsValue) -> Result<T, JsError> {
+    if options.is_undefined() || options.is_null() {
+        return Ok(T::default());
+    }
+    serde_wasm_bindgen::from_value(options).map_err(|err| JsError::new(&format!("参数错误: {err}")))
+}
+
+/// 序列化为普通 JS 对象（`None` -> `null`，与 JSON 形状一致）。
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|err| JsError::new(&err.to_string()))
+}
+
+fn to_js_error(err: BeautifulMermaidError) -> JsError {
+    JsError::new(&err.to_string())
+}
+
+/// `renderMermaid(text, options?) -> string`（SVG）。
+#[wasm_bindgen(js_name = renderMermaid)]
+pub fn render_mermaid(text: &str, options: JsValue) -> Result<String, JsError> {
+    let options: RenderOptions = parse_options(options)?;
+    crate::render_mermaid(text, &options).map_err(to_js_error)
+}
+
+/// `renderMermaidAscii(text, options?) -> string`。
+#[wasm_bindgen(js_name = renderMermaidAscii)]
+pub fn render_mermaid_ascii(text: &str, options: JsValue) -> Result<String, JsError> {
+    let options: AsciiRenderOptions = parse_options(options)?;
+    crate::render_mermaid_ascii(text, &options).map_err(to_js_error)
+}
+
+/// `renderMermaidAsciiWithMeta(text, options?) -> { text, meta }`。
+#[wasm_bindgen(js_name = renderMermaidAsciiWithMeta)]
+pub fn render_mermaid_ascii_with_meta(text: &str, options: JsValue) -> Result<JsValue, JsError> {
+    let options: AsciiRenderOptions = parse_options(options)?;
+    let rendered = crate::render_mermaid_ascii_with_meta(text, &options).map_err(to_js_error)?;
+    to_js(&rendered)
+}
+
+/// `validateMermaid(text) -> { isValid, error, details }`（需要 `validate` feature）。
+#[cfg(feature = "validate")]
+#[wasm_bindgen(js_name = validateMermaid)]
+pub fn validate_mermaid(text: &str) -> Result<JsValue, JsError> {
+    let validation = crate::validate_mermaid(text).map_err(to_js_error)?;
+    to_js(&validation)
+}
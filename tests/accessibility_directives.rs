@@ -0,0 +1,119 @@
+// ============================================================================
+// 无障碍指令（accTitle / accDescr）预处理测试
+//
+// 目的：
+// - native 一侧只有一个识别指令的 helper（`native::parse_accessibility`），
+//   图类型判断、四类 parser 的预处理、SVG 的 `<title>/<desc>` 都从它取；
+// - 这里对每种写法 × 每种图类型 × 指令在 header 前 / 后各验证一遍：
+//   - ASCII 输出与 meta 与去掉指令后的原文完全相同（指令不是图元素，也不影响图类型判断）；
+//   - native SVG（flowchart / sequence）的 `<title>/<desc>` 取到的是指令里的文字。
+// ============================================================================
+
+#![cfg(feature = "native-backend")]
+
+use beautiful_mermaid_rs::{
+    AsciiRenderOptions, Backend, RenderOptions, render_mermaid, render_mermaid_ascii_with_meta,
+};
+
+/// (图类型, header, 正文)：指令插在 header 前面或后面。
+const DIAGRAMS: &[(&str, &str, &str)] = &[
+    ("flowchart", "graph TD", "A[Start] --> B[Done]"),
+    ("state", "stateDiagram-v2", "[*] --> Idle\nIdle --> Busy"),
+    ("sequence", "sequenceDiagram", "Alice->>Bob: Hi"),
+    ("class", "classDiagram", "Animal <|-- Dog"),
+    ("er", "erDiagram", "CUSTOMER ||--o{ ORDER : places"),
+];
+
+/// (指令写法, 期望的 title, 期望的 description)。
+const DIRECTIVES: &[(&str, Option<&str>, Option<&str>)] = &[
+    ("accTitle: Login <flow>", Some("Login &lt;flow&gt;"), None),
+    ("accTitle:T", Some("T"), None),
+    (
+        "accDescr: How a user signs in",
+        None,
+        Some("How a user signs in"),
+    ),
+    ("accDescr { One line }", None, Some("One line")),
+    (
+        "accDescr {\n  first\n  second\n}",
+        None,
+        Some("first\nsecond"),
+    ),
+    ("accDescr { first\n  second\n}", None, Some("first\nsecond")),
+    (
+        "accTitle: Both\naccDescr {\n  body\n}",
+        Some("Both"),
+        Some("body"),
+    ),
+];
+
+fn native_ascii() -> AsciiRenderOptions {
+    AsciiRenderOptions {
+        backend: Some(Backend::Native),
+        ..Default::default()
+    }
+}
+
+fn native_svg() -> RenderOptions {
+    RenderOptions {
+        backend: Some(Backend::Native),
+        ..Default::default()
+    }
+}
+
+/// 指令放在 header 前面 / 后面的两种写法。
+fn with_directive(header: &str, body: &str, directive: &str) -> [String; 2] {
+    [
+        format!("{directive}\n{header}\n{body}\n"),
+        format!("{header}\n  {directive}\n{body}\n"),
+    ]
+}
+
+#[test]
+fn directives_never_reach_the_ascii_output() {
+    for (kind, header, body) in DIAGRAMS {
+        let expected =
+            render_mermaid_ascii_with_meta(&format!("{header}\n{body}\n"), &native_ascii())
+                .unwrap_or_else(|err| panic!("{kind}: {err}"));
+        for (directive, _, _) in DIRECTIVES {
+            for diagram in with_directive(header, body, directive) {
+                let actual = render_mermaid_ascii_with_meta(&diagram, &native_ascii())
+                    .unwrap_or_else(|err| panic!("{kind}: {diagram:?}: {err}"));
+                assert_eq!(actual.text, expected.text, "{kind}: {diagram:?}");
+                assert_eq!(actual.meta, expected.meta, "{kind}: {diagram:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn directives_become_svg_title_and_desc() {
+    for (kind, header, body) in DIAGRAMS {
+        if matches!(*kind, "class" | "er") {
+            continue; // 没有 native SVG
+        }
+        for (directive, title, description) in DIRECTIVES {
+            for diagram in with_directive(header, body, directive) {
+                let svg = render_mermaid(&diagram, &native_svg())
+                    .unwrap_or_else(|err| panic!("{kind}: {diagram:?}: {err}"));
+                assert!(
+                    !svg.contains("accTitle") && !svg.contains("accDescr"),
+                    "{kind}: 指令被当成了图元素: {diagram:?}"
+                );
+                match title {
+                    Some(title) => assert!(
+                        svg.contains(&format!(r#"<title id="title">{title}</title>"#)),
+                        "{kind}: {diagram:?}"
+                    ),
+                    None => assert!(!svg.contains("<title"), "{kind}: {diagram:?}"),
+                }
+                if let Some(description) = description {
+                    assert!(
+                        svg.contains(&format!(r#"<desc id="desc">{description}</desc>"#)),
+                        "{kind}: {diagram:?}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -6,6 +6,8 @@
 // - 之前会出现“箭头停在远处，和 box 脱离”的视觉错位。
 // ============================================================================
 
+#![cfg(feature = "js-backend")]
+
 use beautiful_mermaid_rs::{
     AsciiBox, AsciiDrawingCoord, AsciiRenderOptions, render_mermaid_ascii_with_meta,
 };
@@ -73,16 +75,18 @@ Hat_ralph -->|integration.task| Hat_experiment_integrator
         },
     )
     .expect("render_mermaid_ascii_with_meta should work");
-
-    let ralph = result
+    let meta = result
         .meta
+        .as_flowchart()
+        .expect("flowchart should produce flowchart meta");
+
+    let ralph = meta
         .nodes
         .iter()
         .find(|node| node.id == "Hat_ralph")
         .expect("meta should include Hat_ralph node");
 
-    let edges_to_ralph: Vec<_> = result
-        .meta
+    let edges_to_ralph: Vec<_> = meta
         .edges
         .iter()
         .filter(|edge| edge.to == "Hat_ralph")
@@ -133,8 +137,7 @@ Hat_ralph -->|integration.task| Hat_experiment_integrator
         );
     }
 
-    let edges_from_ralph: Vec<_> = result
-        .meta
+    let edges_from_ralph: Vec<_> = meta
         .edges
         .iter()
         .filter(|edge| edge.from == "Hat_ralph")
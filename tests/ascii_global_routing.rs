@@ -0,0 +1,151 @@
+// ============================================================================
+// `AsciiRouting::Global`（relaxed + rip-up-and-reroute）回归测试
+//
+// 说明：
+// - golden 在 `tests/testdata/global/{ascii,unicode}`，格式同 `ascii_testdata.rs`（无配置行）；
+// - global 总是走 native：指定 `Backend::Js` 也必须产出同样的结果；
+// - golden 里的用例都是 relaxed 会绕远/交叉的图，global 的拐点必须更少；
+// - 全部 flowchart testdata 上，global 的 `┼` 数不能比 relaxed 多；
+// - 预算按工作量计：用完预算的图在多线程争抢 CPU 时也给出同样的结果；
+// - `UPDATE_GOLDEN=1` 时把当前输出写回 golden。
+// ============================================================================
+
+#![cfg(feature = "native-backend")]
+
+use beautiful_mermaid_rs::{AsciiRenderOptions, AsciiRouting, Backend, render_mermaid_ascii};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 每行 trimEnd + 去掉首尾空行（同 `ascii_testdata.rs`）。
+fn normalize_whitespace(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let start = lines.iter().position(|line| !line.is_empty()).unwrap_or(0);
+    let end = lines
+        .iter()
+        .rposition(|line| !line.is_empty())
+        .map_or(start, |end| end + 1);
+    lines[start..end].join("\n")
+}
+
+fn render(diagram: &str, use_ascii: bool, routing: AsciiRouting, backend: Backend) -> String {
+    let options = AsciiRenderOptions {
+        use_ascii: Some(use_ascii),
+        routing: Some(routing),
+        backend: Some(backend),
+        ..Default::default()
+    };
+    let output = render_mermaid_ascii(diagram, &options)
+        .unwrap_or_else(|err| panic!("渲染失败: routing={routing:?}, err={err}"));
+    normalize_whitespace(&output)
+}
+
+fn count(text: &str, chars: &str) -> usize {
+    text.chars().filter(|c| chars.contains(*c)).count()
+}
+
+fn txt_files(dir: &Path) -> Vec<PathBuf> {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .unwrap_or_else(|err| panic!("读取目录失败: dir={dir:?}, err={err}"))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    entries.sort();
+    entries
+}
+
+fn run_golden_dir(dir: &Path, use_ascii: bool) {
+    let update_golden = std::env::var("UPDATE_GOLDEN").is_ok_and(|value| value == "1");
+    let entries = txt_files(dir);
+    assert!(!entries.is_empty(), "{dir:?} 下没有 golden");
+
+    for path in entries {
+        let raw = fs::read_to_string(&path).unwrap().replace("\r\n", "\n");
+        let (diagram, expected) = raw.split_once("\n---\n").expect("golden 缺少分隔符 `---`");
+        let diagram = format!("{diagram}\n");
+        let actual = render(&diagram, use_ascii, AsciiRouting::Global, Backend::Native);
+
+        if update_golden {
+            fs::write(&path, format!("{}---\n{actual}\n", diagram)).unwrap();
+        } else {
+            assert_eq!(actual, normalize_whitespace(expected), "path={path:?}");
+        }
+
+        #[cfg(feature = "js-backend")]
+        assert_eq!(
+            render(&diagram, use_ascii, AsciiRouting::Global, Backend::Js),
+            actual,
+            "global 应当总是走 native: path={path:?}"
+        );
+
+        let corners = if use_ascii { "+" } else { "┌┐└┘" };
+        let relaxed = render(&diagram, use_ascii, AsciiRouting::Relaxed, Backend::Native);
+        assert!(
+            count(&actual, corners) < count(&relaxed, corners),
+            "global 没有减少拐点: path={path:?}\n{relaxed}\n---\n{actual}"
+        );
+    }
+}
+
+#[test]
+fn ascii_global_routing_matches_golden() {
+    run_golden_dir(Path::new("tests/testdata/global/ascii"), true);
+}
+
+#[test]
+fn unicode_global_routing_matches_golden() {
+    run_golden_dir(Path::new("tests/testdata/global/unicode"), false);
+}
+
+#[test]
+fn global_routing_never_adds_crossings() {
+    for (dir, use_ascii) in [
+        ("tests/testdata/ascii", true),
+        ("tests/testdata/unicode", false),
+    ] {
+        for path in txt_files(Path::new(dir)) {
+            let raw = fs::read_to_string(&path).unwrap().replace("\r\n", "\n");
+            let diagram = raw.split_once("\n---\n").unwrap().0;
+            if !diagram.starts_with("graph ") {
+                continue;
+            }
+            let relaxed = render(diagram, use_ascii, AsciiRouting::Relaxed, Backend::Native);
+            let global = render(diagram, use_ascii, AsciiRouting::Global, Backend::Native);
+            assert!(
+                count(&global, "┼") <= count(&relaxed, "┼"),
+                "global 反而增加了交叉: path={path:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn global_routing_is_deterministic() {
+    let diagram = "graph TD\nA-->B\nA-->C\nB-->D\nC-->D\nA-->D\nB-->C\nD-->A\n";
+    for use_ascii in [true, false] {
+        let first = render(diagram, use_ascii, AsciiRouting::Global, Backend::Native);
+        let second = render(diagram, use_ascii, AsciiRouting::Global, Backend::Native);
+        assert_eq!(first, second);
+    }
+}
+
+#[test]
+fn exhausted_budget_stops_at_the_same_place() {
+    // 随机生成的 9 节点 14 边小图：rip-up-and-reroute 会用完工作量预算（release 约 1 秒）。
+    // 以前按 2 秒的时间预算停，机器一忙输出就变；现在四个线程同时渲染也必须与单独渲染一致。
+    let diagram = "graph BT\nsubgraph S[Group]\n  N0[Node 0]\n  N1[Node 1]\n  N2[Node 2]\n  N3[Node 3]\nend\n\
+        N1 --> N5\nN7 --> N6\nN2 --> N7\nN2 --> N8\nN6 --> N4\nN3 --> N8\nN3 --> N1\n\
+        N5 --> N6\nN1 --> N4\nN5 --> N2\nN1 --> N2\nN6 --> N5\nN8 --> N4\nN1 --> N4\n";
+    let alone = render(diagram, true, AsciiRouting::Global, Backend::Native);
+    let busy: Vec<String> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..4)
+            .map(|_| scope.spawn(|| render(diagram, true, AsciiRouting::Global, Backend::Native)))
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .collect()
+    });
+    for output in busy {
+        assert_eq!(output, alone);
+    }
+}
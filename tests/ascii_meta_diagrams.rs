@@ -0,0 +1,321 @@
+// ============================================================================
+// sequence / class / ER 的 meta 对齐测试
+//
+// 目的：
+// - 这三类图的 meta 由 Rust 侧的同构布局计算，文本仍由 JS bundle 渲染；
+// - 这里把 meta 里的坐标“套回”渲染出来的字符画，逐格检查二者一致：
+//   - box 四角必须是边框字符
+//   - 成员/属性行、label、cardinality 标记的文本必须出现在对应矩形里
+//   - 线条 path 上的每个格子都必须被画过（非空白）
+// ============================================================================
+
+#![cfg(feature = "js-backend")]
+
+use beautiful_mermaid_rs::{
+    AsciiBox, AsciiDiagramMeta, AsciiDrawingCoord, AsciiRenderOptions,
+    render_mermaid_ascii_with_meta,
+};
+use std::fs;
+use std::path::Path;
+use unicode_width::UnicodeWidthChar;
+
+/// 字符画的二维网格（按终端列对齐：宽字符后面补一个占位格）。
+struct Grid {
+    rows: Vec<Vec<char>>,
+}
+
+impl Grid {
+    fn new(text: &str) -> Self {
+        let rows = text
+            .split('\n')
+            .map(|line| {
+                let mut row = Vec::new();
+                for ch in line.chars() {
+                    row.push(ch);
+                    if ch.width().unwrap_or(0) == 2 {
+                        row.push('\0');
+                    }
+                }
+                row
+            })
+            .collect();
+        Self { rows }
+    }
+
+    fn at(&self, x: i32, y: i32) -> char {
+        if x < 0 || y < 0 {
+            return ' ';
+        }
+        self.rows
+            .get(y as usize)
+            .and_then(|row| row.get(x as usize))
+            .copied()
+            .unwrap_or(' ')
+    }
+
+    fn text_in(&self, rect: &AsciiBox) -> String {
+        (rect.x..rect.x + rect.width)
+            .map(|x| self.at(x, rect.y))
+            .filter(|&ch| ch != '\0')
+            .collect()
+    }
+}
+
+const BORDER_CHARS: &str = "+-|┌┐└┘├┤┬┴┼─│╭╮╰╯";
+
+fn assert_box_corners(grid: &Grid, rect: &AsciiBox, what: &str) {
+    let corners = [
+        (rect.x, rect.y),
+        (rect.x + rect.width - 1, rect.y),
+        (rect.x, rect.y + rect.height - 1),
+        (rect.x + rect.width - 1, rect.y + rect.height - 1),
+    ];
+    for (x, y) in corners {
+        let ch = grid.at(x, y);
+        assert!(
+            BORDER_CHARS.contains(ch),
+            "{what}: box corner ({x},{y}) should be a border char, got {ch:?}, box={rect:?}"
+        );
+    }
+}
+
+fn assert_path_drawn(grid: &Grid, path: &[AsciiDrawingCoord], what: &str) {
+    assert!(!path.is_empty(), "{what}: path should not be empty");
+    for coord in path {
+        let ch = grid.at(coord.x, coord.y);
+        assert!(
+            ch != ' ',
+            "{what}: path cell ({},{}) should be drawn, got blank",
+            coord.x,
+            coord.y
+        );
+    }
+}
+
+fn check_meta(text: &str, meta: &AsciiDiagramMeta, what: &str) {
+    let grid = Grid::new(text);
+
+    match meta {
+        AsciiDiagramMeta::Flowchart(_) => panic!("{what}: expected non-flowchart meta"),
+        AsciiDiagramMeta::Sequence(seq) => {
+            assert!(!seq.participants.is_empty(), "{what}: no participants");
+            for participant in &seq.participants {
+                for header in [&participant.header_box, &participant.footer_box] {
+                    assert_box_corners(&grid, header, what);
+                    let middle = AsciiBox {
+                        y: header.y + 1,
+                        height: 1,
+                        ..*header
+                    };
+                    assert!(
+                        grid.text_in(&middle).contains(&participant.label),
+                        "{what}: participant box should contain label {:?}",
+                        participant.label
+                    );
+                }
+            }
+            for message in &seq.messages {
+                if !message.label.is_empty() {
+                    assert_eq!(
+                        grid.text_in(&message.label_box),
+                        message.label,
+                        "{what}: message #{} label",
+                        message.index
+                    );
+                }
+                assert_path_drawn(&grid, &message.path, what);
+            }
+            for note in &seq.notes {
+                assert_box_corners(&grid, &note.box_rect, what);
+            }
+            for block in &seq.blocks {
+                assert_box_corners(&grid, &block.box_rect, what);
+            }
+        }
+        AsciiDiagramMeta::Class(class) => {
+            assert!(!class.classes.is_empty(), "{what}: no classes");
+            for node in &class.classes {
+                assert_box_corners(&grid, &node.box_rect, what);
+                for row in node.attributes.iter().chain(&node.methods) {
+                    assert_eq!(grid.text_in(&row.box_rect), row.text, "{what}: member row");
+                }
+            }
+            for rel in &class.relationships {
+                assert_path_drawn(&grid, &rel.path, what);
+                if let Some(marker) = rel.marker {
+                    assert_ne!(grid.at(marker.x, marker.y), ' ', "{what}: marker cell");
+                }
+                if let (Some(label), Some(label_box)) = (&rel.label, &rel.label_box) {
+                    assert_eq!(
+                        grid.text_in(label_box),
+                        format!(" {label} "),
+                        "{what}: relationship label"
+                    );
+                }
+            }
+        }
+        AsciiDiagramMeta::Er(er) => {
+            assert!(!er.entities.is_empty(), "{what}: no entities");
+            for entity in &er.entities {
+                assert_box_corners(&grid, &entity.box_rect, what);
+                for row in &entity.attributes {
+                    assert_eq!(
+                        grid.text_in(&row.box_rect),
+                        row.text,
+                        "{what}: attribute row"
+                    );
+                }
+            }
+            for rel in &er.relationships {
+                assert_path_drawn(&grid, &rel.path, what);
+                for marker in [&rel.from_cardinality, &rel.to_cardinality] {
+                    assert_eq!(
+                        grid.text_in(&marker.box_rect),
+                        marker.text,
+                        "{what}: cardinality marker"
+                    );
+                }
+                if let Some(label_box) = &rel.label_box {
+                    let drawn = grid.text_in(label_box);
+                    assert!(
+                        rel.label.starts_with(&drawn) && !drawn.is_empty(),
+                        "{what}: relationship label {:?}, drawn {drawn:?}",
+                        rel.label
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn render_and_check(mermaid: &str, use_ascii: bool, what: &str) -> AsciiDiagramMeta {
+    let options = AsciiRenderOptions {
+        use_ascii: Some(use_ascii),
+        ..Default::default()
+    };
+    let result = render_mermaid_ascii_with_meta(mermaid, &options)
+        .unwrap_or_else(|err| panic!("{what}: render failed: {err}"));
+    check_meta(&result.text, &result.meta, what);
+    result.meta
+}
+
+#[test]
+fn testdata_sequence_class_er_meta_matches_text() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/testdata");
+    let mut checked = 0;
+
+    for (sub, use_ascii) in [("ascii", true), ("unicode", false)] {
+        let mut files: Vec<_> = fs::read_dir(dir.join(sub))
+            .expect("read testdata dir")
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                let name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+                name.starts_with("seq_") || name.starts_with("cls_") || name.starts_with("er_")
+            })
+            .collect();
+        files.sort();
+
+        for path in files {
+            let raw = fs::read_to_string(&path).expect("read testdata file");
+            let raw = raw.replace("\r\n", "\n");
+            let mermaid = raw.split("\n---\n").next().unwrap_or_default();
+            render_and_check(mermaid, use_ascii, &path.display().to_string());
+            checked += 1;
+        }
+    }
+
+    assert!(checked > 0, "should find sequence/class/ER testdata");
+}
+
+#[test]
+fn sequence_meta_covers_notes_and_blocks() {
+    let mermaid = r#"sequenceDiagram
+    participant A as Alice
+    actor B as Bob
+    A->>B: Hello
+    Note right of B: Think
+    loop Every minute
+      B-->>A: Ping
+    end
+    alt ok
+      A->>B: Yes
+    else fail
+      A-xB: No
+    end
+    B->>B: Self
+    Note over A,B: Shared
+"#;
+
+    for use_ascii in [true, false] {
+        let meta = render_and_check(mermaid, use_ascii, "sequence notes/blocks");
+        let seq = meta.as_sequence().expect("sequence meta");
+
+        assert_eq!(seq.participants.len(), 2);
+        assert_eq!(seq.participants[1].participant_type, "actor");
+        assert_eq!(seq.messages.len(), 5);
+        assert!(seq.messages[4].is_self);
+        assert_eq!(seq.notes.len(), 2);
+        assert_eq!(seq.blocks.len(), 2);
+
+        let alt = seq
+            .blocks
+            .iter()
+            .find(|block| block.block_type == "alt")
+            .expect("alt block");
+        assert_eq!(alt.dividers.len(), 1);
+        assert_eq!(alt.dividers[0].label, "fail");
+    }
+}
+
+#[test]
+fn class_and_er_meta_identify_members_and_cardinality() {
+    let class_meta = render_and_check(
+        "classDiagram\n  Animal <|-- Dog : extends\n  class Dog {\n    +String name\n    +bark() void\n  }\n",
+        false,
+        "class members",
+    );
+    let class = class_meta.as_class().expect("class meta");
+    let dog = class
+        .classes
+        .iter()
+        .find(|node| node.id == "Dog")
+        .expect("Dog class");
+    assert_eq!(dog.attributes[0].text, "+name: String");
+    assert_eq!(dog.methods[0].text, "+bark: void");
+    assert_eq!(class.relationships[0].relationship_type, "inheritance");
+
+    let er_meta = render_and_check(
+        "erDiagram\n  CUSTOMER ||--o{ ORDER : places\n  ORDER {\n    int id PK\n  }\n",
+        true,
+        "er cardinality",
+    );
+    let er = er_meta.as_er().expect("er meta");
+    assert_eq!(er.relationships[0].from_cardinality.cardinality, "one");
+    assert_eq!(er.relationships[0].to_cardinality.text, "o{");
+    assert_eq!(er.entities[1].attributes[0].text, "PK int id");
+}
+
+#[test]
+fn accessibility_lines_before_header_do_not_change_diagram_kind() {
+    // bundle 先去掉 accTitle/accDescr 再判断图类型，meta 必须跟着走同一个类型
+    let sequence = render_and_check(
+        "accTitle: Greeting\naccDescr {\n  Alice says hi\n}\nsequenceDiagram\n  Alice->>Bob: Hi\n",
+        true,
+        "sequence after accTitle",
+    );
+    assert_eq!(
+        sequence
+            .as_sequence()
+            .expect("sequence meta")
+            .participants
+            .len(),
+        2
+    );
+
+    let er = render_and_check(
+        "accDescr: Orders\nerDiagram\n  CUSTOMER ||--o{ ORDER : places\n",
+        false,
+        "er after accDescr",
+    );
+    assert_eq!(er.as_er().expect("er meta").entities.len(), 2);
+}
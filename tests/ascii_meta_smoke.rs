@@ -7,6 +7,8 @@
 // - text 必须与旧 API 完全一致（避免引入渲染差异）
 // ============================================================================
 
+#![cfg(feature = "js-backend")]
+
 use beautiful_mermaid_rs::{
     AsciiRenderOptions, render_mermaid_ascii, render_mermaid_ascii_with_meta,
 };
@@ -53,25 +55,26 @@ fn render_mermaid_ascii_with_meta_returns_text_and_meta() {
     );
 
     // meta 至少能定位到 node/edge
+    let meta = with_meta
+        .meta
+        .as_flowchart()
+        .expect("flowchart should produce flowchart meta");
     assert!(
-        with_meta.meta.nodes.iter().any(|n| n.id == "A"),
+        meta.nodes.iter().any(|n| n.id == "A"),
         "meta.nodes should include node A"
     );
     assert!(
-        with_meta.meta.nodes.iter().any(|n| n.id == "B"),
+        meta.nodes.iter().any(|n| n.id == "B"),
         "meta.nodes should include node B"
     );
     assert!(
-        with_meta
-            .meta
-            .edges
+        meta.edges
             .iter()
             .any(|e| e.from == "A" && e.to == "B" && e.label == "t"),
         "meta.edges should include A -> B with label t"
     );
 
-    let edge = with_meta
-        .meta
+    let edge = meta
         .edges
         .iter()
         .find(|e| e.from == "A" && e.to == "B" && e.label == "t")
@@ -0,0 +1,189 @@
+// ============================================================================
+// `Backend::Native`（纯 Rust ASCII 渲染）回归测试
+//
+// 说明：
+// - golden 文件与 `ascii_testdata.rs` 共用（tests/testdata/ascii + unicode），
+//   所有 diagram 类型都走 native；
+// - 额外把 native 与 JS 的输出（text + meta）逐字节对比，覆盖 golden 之外的形态：
+//   strict/relaxed、LR/BT、自环、平行边、带 label 的边等；
+// - sequence 的逐字节对比见 `sequence_native_backend.rs`，
+//   class/ER 见 `class_er_native_backend.rs`。
+// ============================================================================
+
+#![cfg(all(feature = "js-backend", feature = "native-backend"))]
+
+use beautiful_mermaid_rs::{
+    AsciiDiagramMeta, AsciiRenderOptions, AsciiRouting, Backend, render_mermaid_ascii,
+    render_mermaid_ascii_with_meta,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// 每行 trimEnd + 去掉首尾空行（对齐 TS 测试里的 normalizeWhitespace）。
+fn normalize_whitespace(text: &str) -> String {
+    let normalized = text.replace("\r\n", "\n");
+    let lines: Vec<&str> = normalized.split('\n').map(str::trim_end).collect();
+    let start = lines.iter().position(|line| !line.is_empty());
+    let end = lines.iter().rposition(|line| !line.is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end].join("\n"),
+        _ => String::new(),
+    }
+}
+
+fn collect_txt_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| entry.path().extension().and_then(|s| s.to_str()) == Some("txt"))
+        .map(|entry| entry.path().to_path_buf())
+        .collect();
+    files.sort();
+    files
+}
+
+/// 解析 golden 文件：可选 padding 配置行 + Mermaid + `---` + 期望输出。
+fn parse_test_case(content: &str, use_ascii: bool) -> (String, AsciiRenderOptions, String) {
+    let content = content.replace("\r\n", "\n");
+    let mut options = AsciiRenderOptions {
+        use_ascii: Some(use_ascii),
+        padding_x: Some(5),
+        padding_y: Some(5),
+        backend: Some(Backend::Native),
+        ..Default::default()
+    };
+
+    let (head, expected) = content
+        .split_once("\n---\n")
+        .unwrap_or_else(|| panic!("golden 文件缺少分隔符 `---`"));
+    let mut mermaid_lines = Vec::new();
+    for line in head.split('\n') {
+        let trimmed = line.trim();
+        if mermaid_lines.is_empty() {
+            if trimmed.is_empty() {
+                continue;
+            }
+            if let Some((key, value)) = trimmed.split_once('=') {
+                let value = value.trim();
+                let slot = match key.trim() {
+                    "paddingX" | "paddingx" => Some(&mut options.padding_x),
+                    "paddingY" | "paddingy" => Some(&mut options.padding_y),
+                    "boxBorderPadding" | "boxborderpadding" => {
+                        Some(&mut options.box_border_padding)
+                    }
+                    _ => None,
+                };
+                if let Some(slot) = slot {
+                    *slot = Some(value.parse().expect("配置值必须是整数"));
+                    continue;
+                }
+            }
+        }
+        mermaid_lines.push(line);
+    }
+
+    let mut mermaid = mermaid_lines.join("\n");
+    mermaid.push('\n');
+    (mermaid, options, expected.to_string())
+}
+
+fn run_native_testdata(dir: &Path, use_ascii: bool) {
+    let mut checked = 0;
+    for file_path in collect_txt_files(dir) {
+        let raw = fs::read_to_string(&file_path)
+            .unwrap_or_else(|err| panic!("读取 testdata 失败: path={file_path:?}, err={err}"));
+        let (diagram, options, expected) = parse_test_case(&raw, use_ascii);
+
+        let actual = render_mermaid_ascii(&diagram, &options)
+            .unwrap_or_else(|err| panic!("native 渲染失败: path={file_path:?}, err={err}"));
+        assert_eq!(
+            normalize_whitespace(&actual),
+            normalize_whitespace(&expected),
+            "native 输出与 golden 不一致: path={file_path:?}"
+        );
+        checked += 1;
+    }
+    assert!(checked > 0, "{dir:?} 下没有用例");
+}
+
+#[test]
+fn native_ascii_testdata_matches_reference() {
+    run_native_testdata(Path::new("tests/testdata/ascii"), true);
+}
+
+#[test]
+fn native_unicode_testdata_matches_reference() {
+    run_native_testdata(Path::new("tests/testdata/unicode"), false);
+}
+
+const PARITY_DIAGRAMS: &[&str] = &[
+    "graph LR\n  A --> B\n  B --> C\n  A --> C\n",
+    "graph TD\n  A -->|yes| B\n  A -->|no| C\n  B --> D\n  C --> D\n",
+    "graph BT\n  A --> B\n  A --> C\n  C --> A\n",
+    "flowchart RL\n  A[Start] --> B{Check}\n  B -->|ok| C(Done)\n  B -->|retry| A\n",
+    "graph TD\n  A --> A\n  A --> B\n  B --> B\n",
+    "graph LR\n  A -->|one| B\n  A -->|two| B\n  A -->|three| B\n",
+    "graph TD\n  A --> B & C & D\n  B & C --> E\n  D -.-> E\n  E ==> F\n",
+    "graph TD\n  subgraph one [First]\n    A --> B\n  end\n  subgraph two\n    C --> D\n  end\n  B --> C\n  A --> D\n",
+    "stateDiagram-v2\n  [*] --> Idle\n  Idle --> Running : start\n  Running --> Idle : stop\n  Running --> [*]\n",
+    "graph TD\n  accTitle: Title\n  accDescr {\n    multi\n    line\n  }\n  A[中文节点] --> B[emoji 🚀]\n",
+];
+
+#[test]
+fn native_matches_js_text_and_meta() {
+    let routings = [
+        None,
+        Some(AsciiRouting::Strict),
+        Some(AsciiRouting::Relaxed),
+    ];
+    for diagram in PARITY_DIAGRAMS {
+        for use_ascii in [true, false] {
+            for routing in routings {
+                let js_options = AsciiRenderOptions {
+                    use_ascii: Some(use_ascii),
+                    routing,
+                    ..Default::default()
+                };
+                let native_options = AsciiRenderOptions {
+                    backend: Some(Backend::Native),
+                    ..js_options.clone()
+                };
+                let what = format!("diagram={diagram:?} use_ascii={use_ascii} routing={routing:?}");
+
+                let js = render_mermaid_ascii_with_meta(diagram, &js_options)
+                    .unwrap_or_else(|err| panic!("JS 渲染失败: {what}: {err}"));
+                let native = render_mermaid_ascii_with_meta(diagram, &native_options)
+                    .unwrap_or_else(|err| panic!("native 渲染失败: {what}: {err}"));
+                assert_eq!(native.text, js.text, "text 不一致: {what}");
+                // flowchart meta 带着实际走线的后端，除此之外逐字段相同
+                let mut native_meta = native.meta;
+                if let (AsciiDiagramMeta::Flowchart(native), AsciiDiagramMeta::Flowchart(js)) =
+                    (&mut native_meta, &js.meta)
+                {
+                    assert_eq!(
+                        (native.backend, js.backend),
+                        (Backend::Native, Backend::Js),
+                        "{what}"
+                    );
+                    native.backend = Backend::Js;
+                }
+                assert_eq!(native_meta, js.meta, "meta 不一致: {what}");
+            }
+        }
+    }
+}
+
+#[test]
+fn native_backend_reports_parse_errors() {
+    let err = render_mermaid_ascii(
+        "   \n",
+        &AsciiRenderOptions {
+            backend: Some(Backend::Native),
+            ..Default::default()
+        },
+    )
+    .expect_err("空输入应当报错");
+    assert!(err.to_string().contains("Empty mermaid diagram"), "{err}");
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "js-backend")]
+
 use beautiful_mermaid_rs::{AsciiRenderOptions, AsciiRouting};
 
 // ============================================================================
@@ -12,6 +12,8 @@
 //   - 更新完成后会 panic 提示你重新运行测试（确保输出稳定且无其他回归）
 // ============================================================================
 
+#![cfg(feature = "js-backend")]
+
 use beautiful_mermaid_rs::{AsciiRenderOptions, render_mermaid_ascii};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -0,0 +1,198 @@
+// ============================================================================
+// `AsciiRenderOptions.turn_penalty`（A* 转弯惩罚）回归测试
+//
+// 说明：
+// - golden 在 `tests/testdata/turn_penalty/{ascii,unicode}`，格式同 `ascii_testdata.rs`，
+//   第一行额外写 `turnPenalty=N`；
+// - native 后端与 JS 后端（走 native pathfinder）都必须产出 golden 里的结果；
+// - 每个 golden 的拐点（`+` / `┌┐└┘`）都必须比不加惩罚时少：与默认输出相同的用例证明不了任何事；
+//   `subgraph_complex_*` 因此不在这里（nested 没有连线，mixed 每条边本来就只拐一次），
+//   子图场景由 `subgraph_*` 覆盖；
+// - `UPDATE_GOLDEN=1` 时把当前 native 输出写回 golden。
+// - 随机小图（固定种子，9 个节点 14 条边 + 一个子图）：曾经在 `turn_penalty >= 1` 时跑上几分钟，
+//   这里限定每张图的渲染时间与每次搜索的展开数。
+// ============================================================================
+
+#![cfg(feature = "native-backend")]
+
+use beautiful_mermaid_rs::{
+    AsciiRenderOptions, AsciiRouting, Backend, render_mermaid_ascii,
+    render_mermaid_ascii_with_stats,
+};
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// 每行 trimEnd + 去掉首尾空行（同 `ascii_testdata.rs`）。
+fn normalize_whitespace(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let start = lines.iter().position(|line| !line.is_empty()).unwrap_or(0);
+    let end = lines
+        .iter()
+        .rposition(|line| !line.is_empty())
+        .map_or(start, |end| end + 1);
+    lines[start..end].join("\n")
+}
+
+/// 解析 golden：`turnPenalty=N` / Mermaid / `---` / 期望输出。
+fn parse_case(raw: &str) -> (u32, String, String) {
+    let raw = raw.replace("\r\n", "\n");
+    let (head, expected) = raw.split_once("\n---\n").expect("golden 缺少分隔符 `---`");
+    let (first, diagram) = head.split_once('\n').expect("golden 缺少 Mermaid 文本");
+    let turn_penalty = first
+        .trim()
+        .strip_prefix("turnPenalty=")
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| panic!("golden 第一行应为 turnPenalty=N: {first:?}"));
+    (turn_penalty, format!("{diagram}\n"), expected.to_string())
+}
+
+fn render(diagram: &str, use_ascii: bool, turn_penalty: u32, backend: Backend) -> String {
+    let options = AsciiRenderOptions {
+        use_ascii: Some(use_ascii),
+        padding_x: Some(5),
+        padding_y: Some(5),
+        turn_penalty: Some(turn_penalty),
+        backend: Some(backend),
+        ..Default::default()
+    };
+    let output = render_mermaid_ascii(diagram, &options)
+        .unwrap_or_else(|err| panic!("渲染失败: backend={backend:?}, err={err}"));
+    normalize_whitespace(&output)
+}
+
+fn count_corners(text: &str) -> usize {
+    text.chars().filter(|c| "+┌┐└┘".contains(*c)).count()
+}
+
+fn run_dir(dir: &Path, use_ascii: bool) {
+    let update_golden = std::env::var("UPDATE_GOLDEN").is_ok_and(|value| value == "1");
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .unwrap_or_else(|err| panic!("读取目录失败: dir={dir:?}, err={err}"))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    entries.sort();
+    assert!(!entries.is_empty(), "{dir:?} 下没有 golden");
+
+    for path in entries {
+        let raw = fs::read_to_string(&path).unwrap();
+        let (turn_penalty, diagram, expected) = parse_case(&raw);
+        let actual = render(&diagram, use_ascii, turn_penalty, Backend::Native);
+
+        if update_golden {
+            let head = raw.split_once("\n---\n").unwrap().0;
+            fs::write(&path, format!("{head}\n---\n{actual}\n")).unwrap();
+        } else {
+            assert_eq!(actual, normalize_whitespace(&expected), "path={path:?}");
+        }
+
+        #[cfg(feature = "js-backend")]
+        assert_eq!(
+            render(&diagram, use_ascii, turn_penalty, Backend::Js),
+            actual,
+            "JS 后端（native pathfinder）与 native 后端不一致: path={path:?}"
+        );
+
+        let plain = count_corners(&render(&diagram, use_ascii, 0, Backend::Native));
+        let penalized = count_corners(&actual);
+        assert!(
+            penalized < plain,
+            "转弯惩罚没有减少拐点: path={path:?}, {plain} -> {penalized}"
+        );
+    }
+}
+
+#[test]
+fn ascii_turn_penalty_matches_golden() {
+    run_dir(Path::new("tests/testdata/turn_penalty/ascii"), true);
+}
+
+#[test]
+fn unicode_turn_penalty_matches_golden() {
+    run_dir(Path::new("tests/testdata/turn_penalty/unicode"), false);
+}
+
+#[test]
+fn zero_turn_penalty_is_the_default() {
+    let diagram = "graph LR\nA --> B\nB --> C\nC --> A\n";
+    for use_ascii in [true, false] {
+        let default = render_mermaid_ascii(
+            diagram,
+            &AsciiRenderOptions {
+                use_ascii: Some(use_ascii),
+                backend: Some(Backend::Native),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            normalize_whitespace(&default),
+            render(diagram, use_ascii, 0, Backend::Native)
+        );
+    }
+}
+
+/// 线性同余发生器：测试只需要可复现的伪随机数。
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % bound
+    }
+}
+
+/// `graph BT`，9 个节点，前 2~4 个放进子图，14 条随机边（可能有自环与重边）。
+fn random_graph(seed: u64) -> String {
+    let mut rng = Lcg(seed);
+    let mut diagram = String::from("graph BT\nsubgraph S[Group]\n");
+    for node in 0..2 + rng.next(3) {
+        diagram.push_str(&format!("  N{node}[Node {node}]\n"));
+    }
+    diagram.push_str("end\n");
+    for _ in 0..14 {
+        let (from, to) = (rng.next(9), rng.next(9));
+        diagram.push_str(&format!("N{from} --> N{to}\n"));
+    }
+    diagram
+}
+
+#[test]
+fn random_graphs_finish_within_budget() {
+    // relaxed 下种子 3、13 修复前在 turn_penalty >= 1 时跑不完（每次走不通的搜索都扫满整个窗口）；
+    // ASCII 默认的 strict 规则早早就判定走不通，不受影响
+    for seed in 0..16 {
+        let diagram = random_graph(seed);
+        for turn_penalty in 1..=3 {
+            let options = AsciiRenderOptions {
+                use_ascii: Some(true),
+                routing: Some(AsciiRouting::Relaxed),
+                turn_penalty: Some(turn_penalty),
+                backend: Some(Backend::Native),
+                ..Default::default()
+            };
+            let (tx, rx) = mpsc::channel();
+            let job = diagram.clone();
+            thread::spawn(move || {
+                let _ = tx.send(render_mermaid_ascii_with_stats(&job, &options));
+            });
+            let rendered = rx
+                .recv_timeout(Duration::from_secs(20))
+                .unwrap_or_else(|_| panic!("超时: turn_penalty={turn_penalty}\n{diagram}"))
+                .unwrap_or_else(|err| panic!("渲染失败: {err}\n{diagram}"));
+            for edge in &rendered.stats.edges {
+                // 每次方向感知搜索最多展开 8192 个状态
+                assert!(
+                    edge.expansions <= u64::from(edge.searches) * 8_192,
+                    "turn_penalty={turn_penalty}: {edge:?}\n{diagram}"
+                );
+            }
+        }
+    }
+}
@@ -14,6 +14,8 @@
 // - 绘制端点与 meta 不一致导致的断线/错位。
 // ============================================================================
 
+#![cfg(feature = "js-backend")]
+
 use std::collections::HashMap;
 use std::collections::HashSet;
 
@@ -152,11 +154,14 @@ Hat_ralph -->|integration.task| Hat_experiment_integrator
         },
     )
     .expect("render_mermaid_ascii_with_meta should work");
+    let meta = result
+        .meta
+        .as_flowchart()
+        .expect("flowchart should produce flowchart meta");
 
     let cell_grid = build_cell_grid(&result.text);
 
-    let node_boxes: HashMap<_, _> = result
-        .meta
+    let node_boxes: HashMap<_, _> = meta
         .nodes
         .iter()
         .map(|node| (node.id.as_str(), node.box_rect))
@@ -165,8 +170,7 @@ Hat_ralph -->|integration.task| Hat_experiment_integrator
     // 右侧“最靠右的 node box 边界”(drawing coord)。
     // 用它做相对阈值,比写死 max_x=xx 更稳健:
     // - 未来如果整体布局间距变化,节点会整体平移,但“绕到节点外圈多远”的相对指标仍然有效。
-    let max_node_right = result
-        .meta
+    let max_node_right = meta
         .nodes
         .iter()
         .map(|node| node.box_rect.x + node.box_rect.width - 1)
@@ -174,7 +178,7 @@ Hat_ralph -->|integration.task| Hat_experiment_integrator
         .unwrap_or(0);
 
     if std::env::var("BM_DEBUG_NODE_BOXES").is_ok() {
-        for node in &result.meta.nodes {
+        for node in &meta.nodes {
             eprintln!(
                 "[debug] node box: {} label={:?} box={:?}",
                 node.id, node.label, node.box_rect
@@ -182,10 +186,7 @@ Hat_ralph -->|integration.task| Hat_experiment_integrator
         }
     }
 
-    assert!(
-        !result.meta.edges.is_empty(),
-        "repro case should contain edges"
-    );
+    assert!(!meta.edges.is_empty(), "repro case should contain edges");
 
     // ---------------------------------------------------------------------
     // 回归不变量: 用户复现的“共享走线假象”必须消失
@@ -199,9 +200,7 @@ Hat_ralph -->|integration.task| Hat_experiment_integrator
     // ---------------------------------------------------------------------
     {
         let find_edge = |from: &str, to: &str, label: &str| {
-            result
-                .meta
-                .edges
+            meta.edges
                 .iter()
                 .find(|e| e.from == from && e.to == to && e.label == label)
                 .unwrap_or_else(|| {
@@ -268,7 +267,7 @@ Hat_ralph -->|integration.task| Hat_experiment_integrator
         let edge_key = |from: &str, to: &str, label: &str| format!("{from}->{to}::{label}");
         let mut edges_by_key: HashMap<String, &beautiful_mermaid_rs::AsciiRenderMetaEdge> =
             HashMap::new();
-        for edge in &result.meta.edges {
+        for edge in &meta.edges {
             edges_by_key.insert(edge_key(&edge.from, &edge.to, &edge.label), edge);
         }
 
@@ -323,7 +322,7 @@ Hat_ralph -->|integration.task| Hat_experiment_integrator
     // - `BM_DEBUG_WIDE_EDGES=1 cargo test --test ascii_user_case_edge_endpoint_invariants -- --nocapture`
     // ---------------------------------------------------------------------
     if std::env::var("BM_DEBUG_WIDE_EDGES").is_ok() {
-        for edge in &result.meta.edges {
+        for edge in &meta.edges {
             let mut max_x = i32::MIN;
             let mut min_x = i32::MAX;
             let mut max_y = i32::MIN;
@@ -346,7 +345,7 @@ Hat_ralph -->|integration.task| Hat_experiment_integrator
         }
     }
 
-    for edge in &result.meta.edges {
+    for edge in &meta.edges {
         let source_box = node_boxes.get(edge.from.as_str()).unwrap_or_else(|| {
             panic!(
                 "meta should include source node box: from={}, to={}, label={}",
@@ -0,0 +1,144 @@
+// ============================================================================
+// `Backend::Native` class / ER diagram 回归测试
+//
+// 说明：
+// - class/ER 的 ASCII 布局是纯整数运算，native 按 JS 的运算顺序移植，
+//   text 与 meta 都要求与 JS 后端逐字节一致；
+// - golden（tests/testdata 下的 cls_* / er_*）同时对 native 与 JS 两个后端断言，
+//   任何一侧漂移都能定位到具体文件；
+// - SVG 见 `class_er_svg_native_backend.rs`。
+// ============================================================================
+
+#![cfg(all(feature = "js-backend", feature = "native-backend"))]
+
+use beautiful_mermaid_rs::{
+    AsciiRenderOptions, Backend, render_mermaid_ascii, render_mermaid_ascii_with_meta,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 每行 trimEnd + 去掉首尾空行（对齐 TS 测试里的 normalizeWhitespace）。
+fn normalize_whitespace(text: &str) -> String {
+    let lines: Vec<&str> = text.split('\n').map(str::trim_end).collect();
+    let start = lines.iter().position(|line| !line.is_empty());
+    let end = lines.iter().rposition(|line| !line.is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end].join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// 收集 `dir` 下 class（`cls_*`）与 ER（`er_*`）的 golden 文件。
+fn collect_class_er_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|err| panic!("读取 testdata 目录失败: dir={dir:?}, err={err}"))
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default();
+            (name.starts_with("cls_") || name.starts_with("er_")) && name.ends_with(".txt")
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// golden 文件：Mermaid + `---` + 期望输出（class/ER 用例没有 padding 配置行）。
+fn parse_test_case(content: &str) -> (String, String) {
+    let content = content.replace("\r\n", "\n");
+    let (mermaid, expected) = content
+        .split_once("\n---\n")
+        .unwrap_or_else(|| panic!("golden 文件缺少分隔符 `---`"));
+    (format!("{mermaid}\n"), expected.to_string())
+}
+
+fn run_testdata(dir: &Path, use_ascii: bool) {
+    let files = collect_class_er_files(dir);
+    assert!(!files.is_empty(), "{dir:?} 下没有 class/ER 用例");
+    for file_path in files {
+        let raw = fs::read_to_string(&file_path)
+            .unwrap_or_else(|err| panic!("读取 testdata 失败: path={file_path:?}, err={err}"));
+        let (diagram, expected) = parse_test_case(&raw);
+        for backend in [Backend::Native, Backend::Js] {
+            let options = AsciiRenderOptions {
+                use_ascii: Some(use_ascii),
+                padding_x: Some(5),
+                padding_y: Some(5),
+                backend: Some(backend),
+                ..Default::default()
+            };
+            let actual = render_mermaid_ascii(&diagram, &options).unwrap_or_else(|err| {
+                panic!("渲染失败: path={file_path:?}, backend={backend:?}, err={err}")
+            });
+            assert_eq!(
+                normalize_whitespace(&actual),
+                normalize_whitespace(&expected),
+                "输出与 golden 不一致: path={file_path:?}, backend={backend:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn class_er_ascii_testdata_matches_reference() {
+    run_testdata(Path::new("tests/testdata/ascii"), true);
+}
+
+#[test]
+fn class_er_unicode_testdata_matches_reference() {
+    run_testdata(Path::new("tests/testdata/unicode"), false);
+}
+
+const PARITY_DIAGRAMS: &[&str] = &[
+    "classDiagram\n  Animal <|-- Duck\n  Animal <|-- Fish\n  Animal <|-- Zebra\n",
+    "classDiagram\n  class Animal {\n    <<abstract>>\n    +String name\n    +int age\n    +makeSound() void\n    -digest()$\n  }\n  class Duck~T~\n  Animal <|-- Duck\n",
+    "classDiagram\n  A *-- B : owns\n  C o-- D\n  E --> F : uses\n  G ..> H\n  I ..|> J\n  K -- L\n  M --* N\n  O --o P\n",
+    "classDiagram\n  B <|-- A\n  A --> B : cycle\n  A --> A\n",
+    "classDiagram\n  class Service\n  <<interface>> Service\n  Service : +run() bool\n  Service : +String id\n  Impl ..|> Service\n  Impl --> Repo : a fairly long relationship label\n",
+    "classDiagram\n  namespace Shapes {\n    class Circle\n    class Square\n  }\n  Shape <|-- Circle\n  Shape <|-- Square\n",
+    "erDiagram\n  CUSTOMER ||--o{ ORDER : places\n  ORDER ||--|{ LINE_ITEM : contains\n  PRODUCT ||--o{ LINE_ITEM : \"is in\"\n",
+    "erDiagram\n  CUSTOMER {\n    int id PK\n    string email UK \"login\"\n    int address_id FK\n  }\n  ADDRESS {\n    int id PK\n  }\n  CUSTOMER }o..o| ADDRESS : lives_at\n",
+    "erDiagram\n  A ||--|| B : one\n  A |o--o{ C : many\n  B }|..|{ C : weak\n  C ||--o{ A : back\n",
+    "erDiagram\n  PERSON {\n    string 中文名\n  }\n  PERSON ||--o{ PERSON : knows\n",
+];
+
+#[test]
+fn native_matches_js_text_and_meta() {
+    for diagram in PARITY_DIAGRAMS {
+        for use_ascii in [true, false] {
+            let js_options = AsciiRenderOptions {
+                use_ascii: Some(use_ascii),
+                ..Default::default()
+            };
+            let native_options = AsciiRenderOptions {
+                backend: Some(Backend::Native),
+                ..js_options.clone()
+            };
+            let what = format!("diagram={diagram:?} use_ascii={use_ascii}");
+
+            let js = render_mermaid_ascii_with_meta(diagram, &js_options)
+                .unwrap_or_else(|err| panic!("JS 渲染失败: {what}: {err}"));
+            let native = render_mermaid_ascii_with_meta(diagram, &native_options)
+                .unwrap_or_else(|err| panic!("native 渲染失败: {what}: {err}"));
+            assert_eq!(native.text, js.text, "text 不一致: {what}");
+            assert_eq!(native.meta, js.meta, "meta 不一致: {what}");
+        }
+    }
+}
+
+#[test]
+fn native_renders_empty_diagrams() {
+    let native_options = AsciiRenderOptions {
+        backend: Some(Backend::Native),
+        ..Default::default()
+    };
+    for diagram in ["classDiagram\n", "erDiagram\n"] {
+        let native = render_mermaid_ascii(diagram, &native_options).expect("空图也应能渲染");
+        let js =
+            render_mermaid_ascii(diagram, &AsciiRenderOptions::default()).expect("JS 渲染失败");
+        assert_eq!(native, js, "diagram={diagram:?}");
+    }
+}
@@ -0,0 +1,248 @@
+// ============================================================================
+// `Backend::Native` class / ER diagram SVG 回归测试
+//
+// 说明：
+// - box 尺寸、SVG 结构、marker、元素 id 与 JS 后端一致；布局复用 flowchart 的分层布局，
+//   坐标不与 dagre 逐字节对齐，所以对照时把数字和折线点都抹掉，只比较结构；
+// - 几何上检查不变量：box 互不重叠、尺寸与 JS 相同、连线端点落在 box 边上；
+// - meta 必须与 SVG 一致（`render_mermaid_with_meta` 的 svg == `render_mermaid`）；
+// - 语料：tests/testdata 下的 cls_* / er_* + 几个覆盖 annotation、多重性、重复关系的片段。
+// ============================================================================
+
+#![cfg(all(feature = "js-backend", feature = "native-backend"))]
+
+use beautiful_mermaid_rs::{
+    Backend, RenderOptions, SvgDiagramMeta, SvgPoint, SvgRect, render_mermaid,
+    render_mermaid_with_meta,
+};
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+
+const EXTRA_DIAGRAMS: &[&str] = &[
+    "classDiagram\n  class Animal {\n    <<abstract>>\n    +String name\n    +makeSound()* void\n    +count$ int\n  }\n  Animal <|-- Dog\n  Animal \"1\" *-- \"many\" Leg : has\n  Dog ..> Bone : eats\n",
+    "classDiagram\n  A --> B : first\n  A --> B : second\n  B --o C\n  C --* A\n  D ..|> A; E <|.. D\n",
+    "classDiagram\n  accTitle: Zoo\n  accDescr: Animals & <friends>\n  class Shape~T~\n  Shape : +area() double\n",
+    "erDiagram\n  CUSTOMER ||--o{ ORDER : places\n  ORDER ||--|{ LINE_ITEM : contains\n  CUSTOMER {\n    string name PK\n    int age\n  }\n",
+    "erDiagram\n  A |o..o{ B : maybe\n  A ||--|| B : exactly\n  B }|--|| C : \"quoted label\"\n",
+];
+
+fn native(options: &RenderOptions) -> RenderOptions {
+    RenderOptions {
+        backend: Some(Backend::Native),
+        ..options.clone()
+    }
+}
+
+/// tests/testdata/ascii 下的 class / ER 用例（只取 Mermaid 部分）。
+fn diagrams() -> Vec<String> {
+    let dir = Path::new("tests/testdata/ascii");
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.starts_with("cls_") || name.starts_with("er_")
+        })
+        .collect();
+    files.sort();
+    let mut diagrams: Vec<String> = files
+        .iter()
+        .map(|path| {
+            let content = fs::read_to_string(path).unwrap().replace("\r\n", "\n");
+            format!("{}\n", content.split_once("\n---\n").unwrap().0)
+        })
+        .collect();
+    diagrams.extend(EXTRA_DIAGRAMS.iter().map(|diagram| diagram.to_string()));
+    diagrams
+}
+
+/// 抹掉坐标：折线点整体替换，其余数字替换成 `#`。
+fn structure(svg: &str) -> String {
+    let points = Regex::new(r#"points="[^"]*""#).unwrap();
+    let numbers = Regex::new(r"-?\d+(\.\d+)?(e[+-]?\d+)?").unwrap();
+    let svg = points.replace_all(svg, r#"points="*""#);
+    numbers.replace_all(&svg, "#").into_owned()
+}
+
+fn overlaps(a: &SvgRect, b: &SvgRect) -> bool {
+    a.x < b.x + b.width && b.x < a.x + a.width && a.y < b.y + b.height && b.y < a.y + a.height
+}
+
+/// 点落在 box 的边上（布局坐标保留 3 位小数）。
+fn on_border(point: &SvgPoint, rect: &SvgRect) -> bool {
+    let near = |a: f64, b: f64| (a - b).abs() < 0.01;
+    let inside_x = rect.x - 0.01 <= point.x && point.x <= rect.x + rect.width + 0.01;
+    let inside_y = rect.y - 0.01 <= point.y && point.y <= rect.y + rect.height + 0.01;
+    (inside_x && (near(point.y, rect.y) || near(point.y, rect.y + rect.height)))
+        || (inside_y && (near(point.x, rect.x) || near(point.x, rect.x + rect.width)))
+}
+
+#[test]
+fn native_svg_has_same_structure_as_js() {
+    let option_sets = [
+        RenderOptions::default(),
+        RenderOptions {
+            bg: Some("#101010".to_string()),
+            fg: Some("#EEEEEE".to_string()),
+            font: Some("IBM Plex Sans".to_string()),
+            transparent: Some(true),
+            id_prefix: Some("cls-".to_string()),
+            title: Some("Custom title".to_string()),
+            ..Default::default()
+        },
+    ];
+    for diagram in diagrams() {
+        for options in &option_sets {
+            let js = render_mermaid(&diagram, options).expect("JS 渲染应当成功");
+            let svg = render_mermaid(&diagram, &native(options)).expect("native 渲染应当成功");
+            assert_eq!(structure(&svg), structure(&js), "{diagram}");
+        }
+    }
+}
+
+#[test]
+fn native_meta_matches_svg_and_js() {
+    let options = native(&RenderOptions::default());
+    for diagram in diagrams() {
+        let js =
+            render_mermaid_with_meta(&diagram, &RenderOptions::default()).expect("JS 渲染应当成功");
+        let native = render_mermaid_with_meta(&diagram, &options).expect("native 渲染应当成功");
+        assert_eq!(
+            native.svg,
+            render_mermaid(&diagram, &options).unwrap(),
+            "meta 不能影响渲染: {diagram}"
+        );
+
+        let (boxes, js_boxes, edges) = match (&native.meta, &js.meta) {
+            (SvgDiagramMeta::Class(meta), SvgDiagramMeta::Class(js_meta)) => {
+                for (rel, js_rel) in meta.relationships.iter().zip(&js_meta.relationships) {
+                    assert_eq!(
+                        (&rel.element_id, &rel.relationship_type, &rel.label),
+                        (&js_rel.element_id, &js_rel.relationship_type, &js_rel.label),
+                        "{diagram}"
+                    );
+                }
+                assert_eq!(meta.relationships.len(), js_meta.relationships.len());
+                (
+                    meta.classes
+                        .iter()
+                        .map(|c| (c.id.clone(), c.element_id.clone(), c.box_rect))
+                        .collect::<Vec<_>>(),
+                    js_meta
+                        .classes
+                        .iter()
+                        .map(|c| (c.id.clone(), c.element_id.clone(), c.box_rect))
+                        .collect::<Vec<_>>(),
+                    meta.relationships
+                        .iter()
+                        .map(|r| (r.from.clone(), r.to.clone(), r.points.clone()))
+                        .collect::<Vec<_>>(),
+                )
+            }
+            (SvgDiagramMeta::Er(meta), SvgDiagramMeta::Er(js_meta)) => {
+                for (rel, js_rel) in meta.relationships.iter().zip(&js_meta.relationships) {
+                    assert_eq!(
+                        (&rel.element_id, &rel.from_cardinality, &rel.to_cardinality),
+                        (
+                            &js_rel.element_id,
+                            &js_rel.from_cardinality,
+                            &js_rel.to_cardinality
+                        ),
+                        "{diagram}"
+                    );
+                }
+                assert_eq!(meta.relationships.len(), js_meta.relationships.len());
+                (
+                    meta.entities
+                        .iter()
+                        .map(|e| (e.id.clone(), e.element_id.clone(), e.box_rect))
+                        .collect(),
+                    js_meta
+                        .entities
+                        .iter()
+                        .map(|e| (e.id.clone(), e.element_id.clone(), e.box_rect))
+                        .collect(),
+                    meta.relationships
+                        .iter()
+                        .map(|r| (r.from.clone(), r.to.clone(), r.points.clone()))
+                        .collect(),
+                )
+            }
+            (native, js) => panic!("图类型不一致: native={native:?} js={js:?}"),
+        };
+
+        // box：id 顺序与尺寸与 JS 相同，互不重叠，element_id 都在 SVG 里
+        assert_eq!(boxes.len(), js_boxes.len(), "{diagram}");
+        for ((id, element_id, rect), (js_id, js_element_id, js_rect)) in boxes.iter().zip(&js_boxes)
+        {
+            assert_eq!((id, element_id), (js_id, js_element_id), "{diagram}");
+            assert!((rect.width - js_rect.width).abs() < 0.01, "{diagram}: {id}");
+            assert!(
+                (rect.height - js_rect.height).abs() < 0.01,
+                "{diagram}: {id}"
+            );
+            assert!(
+                native.svg.contains(&format!("<g id=\"{element_id}\"")),
+                "{id}"
+            );
+        }
+        for (i, (a, _, rect_a)) in boxes.iter().enumerate() {
+            for (b, _, rect_b) in &boxes[i + 1..] {
+                assert!(!overlaps(rect_a, rect_b), "{diagram}: {a} 与 {b} 重叠");
+            }
+        }
+
+        // 连线：端点落在两端 box 的边上
+        let rect_of = |id: &str| {
+            boxes
+                .iter()
+                .find(|(box_id, _, _)| box_id == id)
+                .map(|(_, _, rect)| *rect)
+        };
+        for (from, to, points) in edges {
+            if from == to {
+                continue;
+            }
+            let (Some(first), Some(last)) = (points.first(), points.last()) else {
+                panic!("{diagram}: {from} -> {to} 没有折线点");
+            };
+            assert!(
+                on_border(first, &rect_of(&from).unwrap()),
+                "{diagram}: {from}"
+            );
+            assert!(on_border(last, &rect_of(&to).unwrap()), "{diagram}: {to}");
+        }
+    }
+}
+
+#[test]
+fn native_svg_is_accessible() {
+    let svg = render_mermaid(EXTRA_DIAGRAMS[0], &native(&RenderOptions::default())).unwrap();
+    assert!(svg.contains(r#"aria-roledescription="class diagram""#));
+    assert!(svg.contains(
+        "<desc id=\"desc\">Class diagram with 4 classes and 3 relationships. \
+         Classes: Animal; Dog; Leg; Bone. \
+         Relationships: Animal → Dog (inheritance); Animal → Leg (composition: has); \
+         Dog → Bone (dependency: eats).</desc>"
+    ));
+    // 多重性与 static / abstract 成员
+    assert!(svg.contains(">many</text>"));
+    assert!(svg.contains(r#"font-style="italic"><tspan fill="var(--_text-faint)">+ </tspan><tspan fill="var(--_text-sec)">makeSound</tspan>"#));
+
+    let svg = render_mermaid(EXTRA_DIAGRAMS[3], &native(&RenderOptions::default())).unwrap();
+    assert!(svg.contains(r#"aria-roledescription="entity relationship diagram""#));
+    assert!(svg.contains(
+        "Relationships: CUSTOMER → ORDER (places, one to zero-many); \
+         ORDER → LINE_ITEM (contains, one to many).</desc>"
+    ));
+}
+
+#[test]
+fn native_renders_empty_diagrams() {
+    for diagram in ["classDiagram\n", "erDiagram\n"] {
+        let js = render_mermaid(diagram, &RenderOptions::default()).unwrap();
+        let svg = render_mermaid(diagram, &native(&RenderOptions::default())).unwrap();
+        assert_eq!(svg, js, "{diagram}");
+    }
+}
@@ -0,0 +1,255 @@
+// ============================================================================
+// 封闭探测（A* 之前从终点做一次有上限的反向泛洪）回归测试
+//
+// 说明：
+// - 探测只会让走不通的搜索提前返回 `None`，所以开/关探测的路径与代价必须逐格相同；
+// - 随机网格（固定种子）上对比：free 直接比，relaxed / strict 先提交若干条路径，
+//   让交叉惩罚与占用规则都参与进来（走不通的情况也就多起来了）；
+// - 全部 testdata（ASCII/Unicode）上，默认输出与 `ts_compatible`（关掉探测）逐字节相同；
+// - 用 `SearchStats` / `AsciiEdgeRoutingStats` 的计数证明探测确实省下了展开。
+// ============================================================================
+
+#![cfg(feature = "native-backend")]
+
+use beautiful_mermaid_rs::routing::{GridPoint, Route, RouteConstraints, RouteRules, RoutingGrid};
+use beautiful_mermaid_rs::{
+    AsciiRenderOptions, AsciiRouting, Backend, render_mermaid_ascii,
+    render_mermaid_ascii_with_stats,
+};
+use std::fs;
+use std::path::Path;
+
+/// 线性同余发生器：测试只需要可复现的伪随机数。
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u32) -> u32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % bound as u64) as u32
+    }
+
+    fn point(&mut self, grid: &RoutingGrid) -> GridPoint {
+        GridPoint::new(self.next(grid.width()), self.next(grid.height()))
+    }
+}
+
+fn random_grid(rng: &mut Lcg, width: u32, height: u32, rects: usize) -> RoutingGrid {
+    let mut grid = RoutingGrid::new(width, height);
+    for _ in 0..rects {
+        let origin = rng.point(&grid);
+        grid.block_rect(origin, 1 + rng.next(4), 1 + rng.next(4));
+    }
+    grid
+}
+
+/// 开/关探测各搜一次，路径与代价必须相同；返回这次是否由探测直接判定走不通。
+fn compare(
+    grid: &mut RoutingGrid,
+    from: GridPoint,
+    to: GridPoint,
+    constraints: RouteConstraints,
+) -> bool {
+    grid.set_enclosure_probe(false);
+    let plain = grid.route(from, to, &constraints);
+    grid.set_enclosure_probe(true);
+    let probed = grid.route(from, to, &constraints);
+    let stats = grid.last_stats();
+    // 匿名端点每次分配新 id，只比路径与代价
+    let key = |route: Option<Route>| route.map(|route| (route.points, route.cost));
+    let rejected_early = probed.is_none() && stats.probe_cells > 0 && stats.expansions == 0;
+    assert_eq!(
+        key(plain),
+        key(probed),
+        "{from:?} -> {to:?}: {constraints:?}"
+    );
+    rejected_early
+}
+
+#[test]
+fn free_search_is_unchanged_by_probe() {
+    let mut rng = Lcg(1);
+    let mut rejected_early = 0;
+    for _ in 0..20 {
+        let mut grid = random_grid(&mut rng, 40, 30, 40);
+        for _ in 0..50 {
+            let (from, to) = (rng.point(&grid), rng.point(&grid));
+            let constraints = RouteConstraints {
+                bounds: (rng.next(3) == 0).then(|| rng.point(&grid)),
+                turn_penalty: rng.next(3),
+                ..Default::default()
+            };
+            rejected_early += usize::from(compare(&mut grid, from, to, constraints));
+        }
+    }
+    assert!(
+        rejected_early > 0,
+        "随机网格上应当有被探测直接判定走不通的搜索"
+    );
+}
+
+#[test]
+fn constrained_search_is_unchanged_by_probe() {
+    let mut rng = Lcg(7);
+    for rules in [RouteRules::Strict, RouteRules::Relaxed] {
+        let mut rejected_early = 0;
+        for _ in 0..20 {
+            let mut grid = random_grid(&mut rng, 40, 30, 30);
+            let mut id = 1;
+            for _ in 0..200 {
+                let (from, to) = (rng.point(&grid), rng.point(&grid));
+                let constraints = RouteConstraints {
+                    rules,
+                    source_id: id,
+                    target_id: id + 1,
+                    bounds: (rng.next(3) == 0).then(|| rng.point(&grid)),
+                    allow_end_segment_reuse: rng.next(2) == 0,
+                    turn_penalty: rng.next(3),
+                };
+                rejected_early += usize::from(compare(&mut grid, from, to, constraints));
+                // 一部分路径落定，后面的搜索要面对占用规则（走不通的情况也就多起来了）
+                if rng.next(3) == 0
+                    && let Some(route) = grid.route(from, to, &constraints)
+                {
+                    grid.commit(&route);
+                    id += 2;
+                }
+            }
+        }
+        assert!(
+            rejected_early > 0,
+            "{rules:?}: 应当有被探测直接判定走不通的搜索"
+        );
+    }
+}
+
+fn options(use_ascii: bool, routing: AsciiRouting, ts_compatible: bool) -> AsciiRenderOptions {
+    AsciiRenderOptions {
+        use_ascii: Some(use_ascii),
+        routing: Some(routing),
+        backend: Some(Backend::Native),
+        ts_compatible: Some(ts_compatible),
+        ..Default::default()
+    }
+}
+
+fn render(diagram: &str, use_ascii: bool, routing: AsciiRouting, ts_compatible: bool) -> String {
+    render_mermaid_ascii(diagram, &options(use_ascii, routing, ts_compatible))
+        .unwrap_or_else(|err| panic!("渲染失败: ts_compatible={ts_compatible}, err={err}"))
+}
+
+#[test]
+fn testdata_renders_identically() {
+    for (dir, use_ascii) in [
+        ("tests/testdata/ascii", true),
+        ("tests/testdata/unicode", false),
+    ] {
+        let mut entries: Vec<_> = fs::read_dir(Path::new(dir))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        entries.sort();
+        for path in entries {
+            let raw = fs::read_to_string(&path).unwrap().replace("\r\n", "\n");
+            let diagram = raw.split_once("\n---\n").unwrap().0;
+            // 跳过 `paddingX=2` 之类的配置行
+            let diagram: String = diagram
+                .lines()
+                .skip_while(|line| line.contains('='))
+                .map(|line| format!("{line}\n"))
+                .collect();
+            if !diagram.starts_with("graph ") && !diagram.starts_with("flowchart ") {
+                continue;
+            }
+            for routing in [AsciiRouting::Strict, AsciiRouting::Relaxed] {
+                assert_eq!(
+                    render(&diagram, use_ascii, routing, false),
+                    render(&diagram, use_ascii, routing, true),
+                    "path={path:?}, routing={routing:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn enclosed_target_is_rejected_without_expanding() {
+    // 60×40 的空网格，终点被一圈障碍围死：不探测时 A* 要翻遍整张网格才知道走不通。
+    let mut grid = RoutingGrid::new(60, 40);
+    let to = GridPoint::new(50, 20);
+    grid.block_rect(GridPoint::new(48, 18), 5, 5);
+    grid.set_blocked(to, false);
+    grid.set_blocked(GridPoint::new(49, 20), false);
+    let from = GridPoint::new(2, 2);
+
+    grid.set_enclosure_probe(false);
+    assert!(grid.route(from, to, &RouteConstraints::default()).is_none());
+    let plain = grid.last_stats();
+    assert!(plain.expansions > 2000, "{plain:?}");
+    assert_eq!(plain.probe_cells, 0);
+
+    grid.set_enclosure_probe(true);
+    grid.start_probe();
+    assert!(grid.route(from, to, &RouteConstraints::default()).is_none());
+    let probed = grid.last_stats();
+    assert_eq!(probed.expansions, 0, "{probed:?}");
+    assert_eq!(probed.probe_cells, 2, "终点与它左边那一格");
+    // 探测拒绝的单步（两个格子四周的障碍）照常计数、记进调试探测，看过的格子不算展开
+    assert_eq!(probed.rejections.blocked, 6, "{probed:?}");
+    let probe = grid.take_probe();
+    assert!(probe.expanded.is_empty(), "{probe:?}");
+    assert_eq!(
+        probe
+            .rejected
+            .values()
+            .map(|rejections| rejections.blocked)
+            .sum::<u64>(),
+        6,
+        "{probe:?}"
+    );
+
+    // 走得通时探测最多多看 64 个格子，路径不变
+    let open = GridPoint::new(10, 30);
+    let route = grid
+        .route(from, open, &RouteConstraints::default())
+        .unwrap();
+    assert_eq!(route.cost, 8 + 28);
+    assert!(
+        grid.last_stats().probe_cells <= 64,
+        "{:?}",
+        grid.last_stats()
+    );
+}
+
+#[test]
+fn user_case_probe_saves_expansions() {
+    // 走不通的候选端口最多的图（见 benches/routing.rs）；strict 不探测时在 debug 下太慢，只比 relaxed
+    let raw = fs::read_to_string("tests/testdata/unicode/user_repro_case.txt").unwrap();
+    let diagram = format!("{}\n", raw.split_once("\n---\n").unwrap().0);
+    for use_ascii in [true, false] {
+        let run = |ts_compatible| {
+            let options = options(use_ascii, AsciiRouting::Relaxed, ts_compatible);
+            render_mermaid_ascii_with_stats(&diagram, &options).unwrap()
+        };
+        let (probed, plain) = (run(false), run(true));
+        assert_eq!(probed.text, plain.text, "use_ascii={use_ascii}");
+        let work = |edges: &[beautiful_mermaid_rs::AsciiEdgeRoutingStats]| -> u64 {
+            edges
+                .iter()
+                .map(|edge| edge.expansions + edge.probe_cells)
+                .sum()
+        };
+        let (probed_work, plain_work) = (work(&probed.stats.edges), work(&plain.stats.edges));
+        assert!(
+            plain.stats.edges.iter().all(|edge| edge.probe_cells == 0),
+            "ts_compatible 不应探测"
+        );
+        // 实测 ASCII 约 11 万 vs 115 万，Unicode 约 5 千 vs 280 万
+        assert!(
+            probed_work * 5 < plain_work,
+            "use_ascii={use_ascii}: 探测 + 展开 {probed_work}，不探测 {plain_work}"
+        );
+    }
+}
@@ -0,0 +1,354 @@
+// ============================================================================
+// JS 引擎配置（`EngineConfig` / `set_js_engine_config`）回归测试
+//
+// 说明：
+// - 引擎按线程缓存：每个测试先在自己的线程上 `set_js_engine_config`，互不影响，不碰环境变量；
+// - native pathfinder 开关、内存上限、自定义 bundle（源码 / 文件）都在创建引擎时生效；
+// - 外部 bundle 要先通过握手（三个渲染函数都在）才会被接受，否则原引擎保持不变；
+//   本仓库的扩展按实际输出探测，缺了就在用到时报 `BackendUnavailable`；
+// - 进程级默认配置只影响之后第一次创建引擎的线程（测试结束前恢复成默认）。
+// ============================================================================
+
+#![cfg(feature = "js-backend")]
+
+use beautiful_mermaid_rs::{
+    AsciiDiagramMeta, AsciiRenderOptions, AsciiRouting, Backend, BeautifulMermaidError,
+    BundleCapabilities, EngineConfig, RenderOptions, render_mermaid_ascii,
+    render_mermaid_ascii_with_meta, set_default_js_engine_config, set_js_engine_config,
+};
+
+const FLOWCHART: &str = "graph LR\nA --> B\nB --> C\nA --> C\n";
+
+/// 假 bundle：满足握手，ASCII 返回固定文本，方便确认用的是哪份源码。
+const STUB_BUNDLE: &str = r#"
+var beautifulMermaid = {
+  renderMermaid: async function (text, options) { return "<svg/>"; },
+  renderMermaidAscii: function (text, options) { return "stub:" + text.split("\n")[0]; },
+  renderMermaidAsciiWithMeta: function (text, options) { return { text: "stub", meta: {} }; },
+};
+"#;
+
+const VENDORED_BUNDLE: &str = "vendor/beautiful-mermaid/beautiful-mermaid.browser.global.js";
+
+fn js_options() -> AsciiRenderOptions {
+    AsciiRenderOptions {
+        use_ascii: Some(true),
+        backend: Some(Backend::Js),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn native_pathfinder_switch_is_per_thread() {
+    set_js_engine_config(&EngineConfig::new().native_pathfinder(true)).unwrap();
+    let native = render_mermaid_ascii(FLOWCHART, &js_options()).unwrap();
+
+    let pure_js = std::thread::spawn(|| {
+        let config = EngineConfig::new().native_pathfinder(false);
+        assert!(!config.build().unwrap().native_pathfinder());
+        set_js_engine_config(&config).unwrap();
+        render_mermaid_ascii(FLOWCHART, &js_options()).unwrap()
+    })
+    .join()
+    .unwrap();
+    assert_eq!(native, pure_js);
+
+    // 另一个线程的配置不影响当前线程
+    assert!(
+        EngineConfig::new()
+            .native_pathfinder(true)
+            .build()
+            .unwrap()
+            .native_pathfinder()
+    );
+    assert_eq!(
+        render_mermaid_ascii(FLOWCHART, &js_options()).unwrap(),
+        native
+    );
+}
+
+#[test]
+fn built_engine_renders_like_the_js_backend() {
+    set_js_engine_config(&EngineConfig::new()).unwrap();
+    let engine = EngineConfig::new().build().unwrap();
+    assert_eq!(
+        engine
+            .render_mermaid_ascii(FLOWCHART, &js_options())
+            .unwrap(),
+        render_mermaid_ascii(FLOWCHART, &js_options()).unwrap()
+    );
+}
+
+#[test]
+fn memory_limit_too_small_keeps_previous_engine() {
+    set_js_engine_config(&EngineConfig::new()).unwrap();
+    let expected = render_mermaid_ascii(FLOWCHART, &js_options()).unwrap();
+
+    assert!(EngineConfig::new().memory_limit(256 << 10).build().is_err());
+    assert!(set_js_engine_config(&EngineConfig::new().memory_limit(256 << 10)).is_err());
+    assert_eq!(
+        render_mermaid_ascii(FLOWCHART, &js_options()).unwrap(),
+        expected
+    );
+
+    // 足够大的上限不改变输出
+    set_js_engine_config(&EngineConfig::new().memory_limit(256 << 20)).unwrap();
+    assert_eq!(
+        render_mermaid_ascii(FLOWCHART, &js_options()).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn bundle_source_replaces_the_embedded_bundle() {
+    set_js_engine_config(&EngineConfig::new().bundle_source(STUB_BUNDLE)).unwrap();
+    assert_eq!(
+        render_mermaid_ascii(FLOWCHART, &js_options()).unwrap(),
+        "stub:graph LR"
+    );
+
+    let err = EngineConfig::new()
+        .bundle_source("throw new Error('broken bundle')")
+        .build()
+        .err()
+        .expect("eval 抛异常时创建失败");
+    assert!(err.to_string().contains("broken bundle"), "{err}");
+
+    set_js_engine_config(&EngineConfig::new()).unwrap();
+    assert!(
+        render_mermaid_ascii(FLOWCHART, &js_options())
+            .unwrap()
+            .contains('A')
+    );
+}
+
+#[test]
+fn non_flowchart_meta_reports_the_native_layout() {
+    // 假 bundle 画出来的 text 与 native 布局毫无关系，meta 必须如实标出坐标来自 native
+    let stub = r#"
+var beautifulMermaid = {
+  renderMermaid: async function (text, options) { return "<svg/>"; },
+  renderMermaidAscii: function (text, options) { return "stub"; },
+  renderMermaidAsciiWithMeta: function (text, options) {
+    return { text: "stub", meta: { nodes: [], edges: [] } };
+  },
+};
+"#;
+    set_js_engine_config(&EngineConfig::new().bundle_source(stub)).unwrap();
+    let diagrams = [
+        "sequenceDiagram\nAlice->>Bob: Hi\n",
+        "classDiagram\nAnimal <|-- Dog\n",
+        "erDiagram\nCUSTOMER ||--o{ ORDER : places\n",
+    ];
+    for diagram in diagrams {
+        let rendered = render_mermaid_ascii_with_meta(diagram, &js_options()).unwrap();
+        assert_eq!(rendered.text, "stub");
+        let backend = match rendered.meta {
+            AsciiDiagramMeta::Sequence(meta) => meta.backend,
+            AsciiDiagramMeta::Class(meta) => meta.backend,
+            AsciiDiagramMeta::Er(meta) => meta.backend,
+            AsciiDiagramMeta::Flowchart(_) => panic!("{diagram:?}"),
+        };
+        assert_eq!(backend, Backend::Native, "{diagram:?}");
+    }
+
+    // 没有 `backend` 字段的旧 JSON 按 native 读回
+    let old = r#"{"kind":"sequence","participants":[],"messages":[],"notes":[],"blocks":[]}"#;
+    let meta: AsciiDiagramMeta = serde_json::from_str(old).unwrap();
+    assert_eq!(meta.as_sequence().unwrap().backend, Backend::Native);
+
+    set_js_engine_config(&EngineConfig::new()).unwrap();
+}
+
+#[test]
+fn default_config_applies_to_new_threads() {
+    set_js_engine_config(&EngineConfig::new()).unwrap();
+    set_default_js_engine_config(EngineConfig::new().bundle_source(STUB_BUNDLE));
+    let on_new_thread = std::thread::spawn(|| render_mermaid_ascii(FLOWCHART, &js_options()))
+        .join()
+        .unwrap();
+    // 当前线程的引擎也按默认配置重建
+    let on_this_thread = render_mermaid_ascii(FLOWCHART, &js_options());
+    set_default_js_engine_config(EngineConfig::default());
+
+    assert_eq!(on_new_thread.unwrap(), "stub:graph LR");
+    assert_eq!(on_this_thread.unwrap(), "stub:graph LR");
+    assert_ne!(
+        render_mermaid_ascii(FLOWCHART, &js_options()).unwrap(),
+        "stub:graph LR"
+    );
+}
+
+#[test]
+fn bundle_path_loads_an_external_bundle() {
+    set_js_engine_config(&EngineConfig::new()).unwrap();
+    let expected = render_mermaid_ascii(FLOWCHART, &js_options()).unwrap();
+    set_js_engine_config(&EngineConfig::new().bundle_path(VENDORED_BUNDLE)).unwrap();
+    assert_eq!(
+        render_mermaid_ascii(FLOWCHART, &js_options()).unwrap(),
+        expected
+    );
+
+    let dir = std::env::temp_dir().join(format!("bm-engine-config-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let stub = dir.join("stub.global.js");
+    std::fs::write(&stub, STUB_BUNDLE).unwrap();
+    set_js_engine_config(&EngineConfig::new().bundle_path(&stub)).unwrap();
+    assert_eq!(
+        render_mermaid_ascii(FLOWCHART, &js_options()).unwrap(),
+        "stub:graph LR"
+    );
+
+    // 后设置的覆盖先设置的
+    let config = EngineConfig::new()
+        .bundle_source("throw new Error('not me')")
+        .bundle_path(&stub);
+    assert!(config.build().is_ok());
+
+    let missing = dir.join("missing.global.js");
+    let err = EngineConfig::new()
+        .bundle_path(&missing)
+        .build()
+        .err()
+        .expect("文件不存在时创建失败");
+    assert!(matches!(err, BeautifulMermaidError::Init { .. }), "{err:?}");
+    assert!(err.to_string().contains("missing.global.js"), "{err}");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn handshake_rejects_bundles_missing_the_render_api() {
+    set_js_engine_config(&EngineConfig::new()).unwrap();
+    let expected = render_mermaid_ascii(FLOWCHART, &js_options()).unwrap();
+
+    let partial =
+        r#"var beautifulMermaid = { renderMermaid: function () {}, renderMermaidAscii: 1 };"#;
+    let err = set_js_engine_config(&EngineConfig::new().bundle_source(partial))
+        .expect_err("缺 API 的 bundle 应当被拒绝");
+    assert!(matches!(err, BeautifulMermaidError::Init { .. }), "{err:?}");
+    let message = err.to_string();
+    assert!(
+        message.contains("renderMermaidAscii, renderMermaidAsciiWithMeta"),
+        "{message}"
+    );
+    assert!(!message.contains("缺少 API: renderMermaid,"), "{message}");
+
+    let err = EngineConfig::new()
+        .bundle_source("var somethingElse = {};")
+        .build()
+        .err()
+        .expect("没有 beautifulMermaid 全局对象时拒绝");
+    assert!(err.to_string().contains("beautifulMermaid"), "{err}");
+
+    // 拒绝之后原引擎照常工作
+    assert_eq!(
+        render_mermaid_ascii(FLOWCHART, &js_options()).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn stock_bundles_report_missing_extensions_instead_of_ignoring_them() {
+    let all = BundleCapabilities {
+        svg_meta: true,
+        element_ids: true,
+        accessibility: true,
+    };
+    assert_eq!(EngineConfig::new().build().unwrap().capabilities(), all);
+    // 外部加载的 vendor bundle 靠探测得出同样的结果
+    let vendored = EngineConfig::new()
+        .bundle_path(VENDORED_BUNDLE)
+        .build()
+        .unwrap();
+    assert_eq!(vendored.capabilities(), all);
+
+    // 假 bundle 只有握手要求的三个函数，`renderMermaid` 不认识任何扩展选项
+    let stub = EngineConfig::new()
+        .bundle_source(STUB_BUNDLE)
+        .build()
+        .unwrap();
+    assert_eq!(
+        stub.capabilities(),
+        BundleCapabilities {
+            svg_meta: false,
+            element_ids: false,
+            accessibility: false,
+        }
+    );
+    assert_eq!(
+        stub.render_mermaid_svg(FLOWCHART, &RenderOptions::default())
+            .unwrap(),
+        "<svg/>"
+    );
+
+    let rejected = [
+        (
+            stub.render_mermaid_svg_with_meta(FLOWCHART, &RenderOptions::default())
+                .map(|_| ()),
+            "renderMermaidWithMeta",
+        ),
+        (
+            stub.render_mermaid_svg(
+                FLOWCHART,
+                &RenderOptions {
+                    id_prefix: Some("p-".to_string()),
+                    ..Default::default()
+                },
+            )
+            .map(|_| ()),
+            "id_prefix",
+        ),
+        (
+            stub.render_mermaid_svg(
+                FLOWCHART,
+                &RenderOptions {
+                    description: Some("D".to_string()),
+                    ..Default::default()
+                },
+            )
+            .map(|_| ()),
+            "description",
+        ),
+    ];
+    for (result, extension) in rejected {
+        let err = result.expect_err(extension);
+        assert!(
+            matches!(err, BeautifulMermaidError::BackendUnavailable { .. }),
+            "{extension}: {err:?}"
+        );
+        let message = err.to_string();
+        assert!(
+            message.contains(extension) && message.contains("bundle_source"),
+            "{message}"
+        );
+    }
+}
+
+#[test]
+fn engine_rejects_global_routing_instead_of_downgrading() {
+    // bundle 不会 global 走线：直接用引擎渲染 flowchart 时报错，而不是悄悄按 relaxed 画
+    let engine = EngineConfig::new().build().unwrap();
+    let global = AsciiRenderOptions {
+        use_ascii: Some(true),
+        routing: Some(AsciiRouting::Global),
+        ..Default::default()
+    };
+    for err in [
+        engine.render_mermaid_ascii(FLOWCHART, &global).unwrap_err(),
+        engine
+            .render_mermaid_ascii_with_meta(FLOWCHART, &global)
+            .unwrap_err(),
+    ] {
+        assert!(
+            matches!(err, BeautifulMermaidError::BackendUnavailable { .. }),
+            "{err:?}"
+        );
+        assert!(err.to_string().contains("native-backend"), "{err}");
+    }
+
+    // 其余图类型没有走线，routing 不起作用，照常渲染
+    let sequence = engine
+        .render_mermaid_ascii("sequenceDiagram\n  A->>B: hi\n", &global)
+        .unwrap();
+    assert!(sequence.contains("hi"), "{sequence}");
+}
@@ -0,0 +1,132 @@
+// ============================================================================
+// C ABI 回归测试（feature = "ffi"）
+//
+// 运行：`cargo test --features ffi`
+//
+// 目的：
+// - 输出与 Rust API 一致（SVG / ASCII / meta JSON / validation JSON）；
+// - 错误码映射：NULL / 非法 options -> INVALID_ARGUMENT，native 解析失败 -> PARSE；
+// - 多线程并发调用不出错（JS 引擎按线程初始化）。
+// ============================================================================
+
+#![cfg(all(
+    feature = "ffi",
+    feature = "js-backend",
+    feature = "native-backend",
+    feature = "validate"
+))]
+
+use beautiful_mermaid_rs::ffi::{
+    BmStatus, bm_free_string, bm_render_ascii, bm_render_ascii_meta_json, bm_render_svg,
+    bm_validate,
+};
+use beautiful_mermaid_rs::{
+    AsciiRenderOptions, AsciiRenderWithMeta, MermaidValidation, RenderOptions, render_mermaid,
+    render_mermaid_ascii, render_mermaid_ascii_with_meta,
+};
+use std::ffi::{CStr, CString, c_char};
+use std::ptr;
+
+const DIAGRAM: &str = "graph LR\n  A --> B\n";
+
+type RenderFn = unsafe extern "C" fn(*const c_char, *const c_char, *mut *mut c_char) -> BmStatus;
+
+/// 调用一个渲染函数，返回（状态码, `*out` 的内容）并释放 `*out`。
+fn call(f: RenderFn, text: Option<&str>, options: Option<&str>) -> (BmStatus, String) {
+    let text = text.map(|text| CString::new(text).unwrap());
+    let options = options.map(|options| CString::new(options).unwrap());
+    let mut out: *mut c_char = ptr::null_mut();
+    let status = unsafe {
+        f(
+            text.as_ref().map_or(ptr::null(), |text| text.as_ptr()),
+            options
+                .as_ref()
+                .map_or(ptr::null(), |options| options.as_ptr()),
+            &mut out,
+        )
+    };
+    assert!(!out.is_null(), "out 应当总是被写入");
+    let value = unsafe { CStr::from_ptr(out) }.to_str().unwrap().to_string();
+    unsafe { bm_free_string(out) };
+    (status, value)
+}
+
+#[test]
+fn ffi_matches_rust_api() {
+    let (status, svg) = call(bm_render_svg, Some(DIAGRAM), None);
+    assert_eq!(status, BmStatus::Ok, "{svg}");
+    assert_eq!(
+        svg,
+        render_mermaid(DIAGRAM, &RenderOptions::default()).unwrap()
+    );
+
+    let options = AsciiRenderOptions {
+        use_ascii: Some(true),
+        ..Default::default()
+    };
+    let (status, ascii) = call(bm_render_ascii, Some(DIAGRAM), Some(r#"{"useAscii":true}"#));
+    assert_eq!(status, BmStatus::Ok, "{ascii}");
+    assert_eq!(ascii, render_mermaid_ascii(DIAGRAM, &options).unwrap());
+
+    let (status, json) = call(
+        bm_render_ascii_meta_json,
+        Some(DIAGRAM),
+        Some(r#"{"useAscii":true}"#),
+    );
+    assert_eq!(status, BmStatus::Ok, "{json}");
+    let parsed: AsciiRenderWithMeta = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        parsed,
+        render_mermaid_ascii_with_meta(DIAGRAM, &options).unwrap()
+    );
+}
+
+#[test]
+fn ffi_validate_returns_json() {
+    let text = CString::new(DIAGRAM).unwrap();
+    let mut out: *mut c_char = ptr::null_mut();
+    let status = unsafe { bm_validate(text.as_ptr(), &mut out) };
+    assert_eq!(status, BmStatus::Ok);
+    let json = unsafe { CStr::from_ptr(out) }.to_str().unwrap().to_string();
+    unsafe { bm_free_string(out) };
+    let validation: MermaidValidation = serde_json::from_str(&json).unwrap();
+    assert!(validation.is_valid, "{json}");
+    assert!(json.contains(r#""isValid":true"#), "{json}");
+}
+
+#[test]
+fn ffi_maps_errors_to_status_codes() {
+    let (status, message) = call(bm_render_svg, None, None);
+    assert_eq!(status, BmStatus::InvalidArgument);
+    assert!(message.contains("text"), "{message}");
+
+    let (status, message) = call(bm_render_ascii, Some(DIAGRAM), Some("{not json"));
+    assert_eq!(status, BmStatus::InvalidArgument);
+    assert!(message.contains("options_json"), "{message}");
+
+    let (status, message) = call(
+        bm_render_ascii,
+        Some("   \n"),
+        Some(r#"{"backend":"native"}"#),
+    );
+    assert_eq!(status, BmStatus::Parse, "{message}");
+    assert!(message.contains("Empty mermaid diagram"), "{message}");
+
+    // out 为 NULL 时只返回状态码
+    let status = unsafe { bm_render_svg(ptr::null(), ptr::null(), ptr::null_mut()) };
+    assert_eq!(status, BmStatus::InvalidArgument);
+    unsafe { bm_free_string(ptr::null_mut()) };
+}
+
+#[test]
+fn ffi_is_thread_safe() {
+    let expected = render_mermaid_ascii(DIAGRAM, &AsciiRenderOptions::default()).unwrap();
+    let handles: Vec<_> = (0..4)
+        .map(|_| std::thread::spawn(|| call(bm_render_ascii, Some(DIAGRAM), None)))
+        .collect();
+    for handle in handles {
+        let (status, ascii) = handle.join().unwrap();
+        assert_eq!(status, BmStatus::Ok);
+        assert_eq!(ascii, expected);
+    }
+}
@@ -0,0 +1,53 @@
+// ============================================================================
+// 只编译 `native-backend`（不带 QuickJS）时的行为
+//
+// 运行：`cargo test --no-default-features --features native-backend`
+//
+// 约定：
+// - 没有 JS 后端时 `backend` 选项被忽略，一律走 native（默认值 `Js` 也不例外）；
+// - 四种图类型的 ASCII 与 SVG 都有 native 实现，不需要 JS 后端。
+// ============================================================================
+
+#![cfg(all(feature = "native-backend", not(feature = "js-backend")))]
+
+use beautiful_mermaid_rs::{
+    AsciiRenderOptions, RenderOptions, render_mermaid, render_mermaid_ascii,
+    render_mermaid_with_meta,
+};
+
+#[test]
+fn default_backend_renders_natively() {
+    let ascii = render_mermaid_ascii("graph LR\n  A --> B\n", &AsciiRenderOptions::default())
+        .expect("native ASCII 渲染应当成功");
+    assert!(ascii.contains('A') && ascii.contains('B'), "{ascii}");
+
+    let ascii = render_mermaid_ascii(
+        "classDiagram\n  Animal <|-- Duck\n",
+        &AsciiRenderOptions::default(),
+    )
+    .expect("native class ASCII 渲染应当成功");
+    assert!(ascii.contains("Duck"), "{ascii}");
+
+    let svg = render_mermaid("sequenceDiagram\n  A->>B: hi\n", &RenderOptions::default())
+        .expect("native sequence SVG 渲染应当成功");
+    assert!(svg.starts_with("<svg"), "{svg}");
+}
+
+#[test]
+fn class_and_er_svg_render_natively() {
+    for (diagram, kind) in [
+        ("classDiagram\n  A <|-- B\n", "class"),
+        ("erDiagram\n  A ||--o{ B : has\n", "entity relationship"),
+    ] {
+        let svg = render_mermaid(diagram, &RenderOptions::default())
+            .unwrap_or_else(|err| panic!("没有 JS 后端时 class/ER 的 SVG 也应当渲染: {err}"));
+        assert!(
+            svg.contains(&format!("aria-roledescription=\"{kind} diagram\"")),
+            "{svg}"
+        );
+
+        let rendered = render_mermaid_with_meta(diagram, &RenderOptions::default())
+            .expect("meta 版本同样应当渲染");
+        assert_eq!(rendered.svg, svg);
+    }
+}
@@ -0,0 +1,390 @@
+// ============================================================================
+// native parser 与 JS parser 的对照
+//
+// 背景：
+// - `Backend::Native` 的四类 parser（src/native/{flowchart,sequence,class,er}.rs）是手写的，
+//   与 bundle 里的 TS parser 是两份实现，语法覆盖可能悄悄分叉；
+// - 渲染输出的逐字节对比（`*_native_backend.rs`）只覆盖 golden 里出现过的写法，
+//   这里单独对比“解析出来的模型”：
+//   - JS 一侧：JS 后端的 meta（SVG 来自 bundle 的 parse + layout，flowchart 另有 ASCII meta）；
+//   - native 一侧：native 后端的 meta（SVG 与 ASCII：全部四类）；
+//   两边都归一化成只含解析结果的模型（id、label、边的端点、class 成员……），不比坐标。
+// - 语料：testdata 里的全部图 + 每种图类型一组覆盖更多语法的片段。
+// ============================================================================
+
+#![cfg(all(feature = "js-backend", feature = "native-backend"))]
+
+use beautiful_mermaid_rs::{
+    AsciiDiagramMeta, AsciiRenderOptions, Backend, RenderOptions, SvgDiagramMeta,
+    render_mermaid_ascii_with_meta, render_mermaid_with_meta,
+};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// 解析结果的归一化形式：每行一个事实，排序后比较（不依赖两边的输出顺序）。
+type Model = Vec<String>;
+
+/// 去掉标签，反转义 SVG 里的实体。
+fn strip_tags(xml: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for ch in xml.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(ch),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 去掉全部空白（SVG 在可见性符号、类型前后加了空格，ASCII 没有）。
+fn compact(text: &str) -> String {
+    text.chars().filter(|ch| !ch.is_whitespace()).collect()
+}
+
+fn sorted(mut model: Model) -> Model {
+    model.sort();
+    model
+}
+
+/// SVG meta 的模型（JS 后端来自 bundle 的 parse + layout，native 后端来自 native parser）。
+///
+/// class 成员不在 meta 里：从 SVG 的等宽 `<text class="mono">` 行取（去掉 tspan 与空白）。
+fn svg_model(meta: &SvgDiagramMeta, svg: &str) -> Model {
+    let mut model = Vec::new();
+    match meta {
+        SvgDiagramMeta::Flowchart(flow) => {
+            for node in &flow.nodes {
+                model.push(format!("node {} {:?}", node.id, node.label));
+            }
+            for edge in &flow.edges {
+                let label = edge.label.clone().unwrap_or_default();
+                model.push(format!("edge {} -> {} {label:?}", edge.from, edge.to));
+            }
+        }
+        SvgDiagramMeta::Sequence(seq) => {
+            for actor in &seq.participants {
+                model.push(format!(
+                    "participant {} {:?} {}",
+                    actor.id, actor.label, actor.participant_type
+                ));
+            }
+            for message in &seq.messages {
+                model.push(format!(
+                    "message {} {} -> {} {:?} {} {}",
+                    message.index,
+                    message.from,
+                    message.to,
+                    message.label,
+                    message.line_style,
+                    message.arrow_head
+                ));
+            }
+            for note in &seq.notes {
+                model.push(format!("note {:?}", note.text));
+            }
+            for block in &seq.blocks {
+                let dividers: Vec<&str> = block.dividers.iter().map(|d| d.label.as_str()).collect();
+                model.push(format!(
+                    "block {} {:?} {dividers:?}",
+                    block.block_type, block.label
+                ));
+            }
+        }
+        SvgDiagramMeta::Class(class) => {
+            for node in &class.classes {
+                model.push(format!(
+                    "class {} {:?} {:?}",
+                    node.id, node.label, node.annotation
+                ));
+            }
+            for rel in &class.relationships {
+                model.push(format!(
+                    "relationship {} -> {} {} {:?}",
+                    rel.from, rel.to, rel.relationship_type, rel.label
+                ));
+            }
+            for row in svg.split(r#"class="mono""#).skip(1) {
+                let row = row.split_once('>').map_or("", |(_, rest)| rest);
+                let row = row.split("</text>").next().unwrap_or_default();
+                model.push(format!("member {}", compact(&strip_tags(row))));
+            }
+        }
+        SvgDiagramMeta::Er(er) => {
+            for entity in &er.entities {
+                // 属性行数 = (总高 - 表头) / 行高；没有属性的实体也留一行高度
+                let rows = ((entity.box_rect.height - entity.header_height) / entity.row_height)
+                    .round() as usize;
+                model.push(format!(
+                    "entity {} {:?} {rows} attributes",
+                    entity.id, entity.label
+                ));
+            }
+            for rel in &er.relationships {
+                model.push(format!(
+                    "relationship {} -> {} {:?} {} {} {}",
+                    rel.from,
+                    rel.to,
+                    rel.label,
+                    rel.identifying,
+                    rel.from_cardinality,
+                    rel.to_cardinality
+                ));
+            }
+        }
+    }
+    sorted(model)
+}
+
+/// ASCII meta 的模型（与 `svg_model` 输出同样格式的事实，可以交叉对比）。
+fn ascii_model(meta: &AsciiDiagramMeta) -> Model {
+    let mut model = Vec::new();
+    match meta {
+        AsciiDiagramMeta::Flowchart(flow) => {
+            for node in &flow.nodes {
+                model.push(format!("node {} {:?}", node.id, node.label));
+            }
+            for edge in &flow.edges {
+                model.push(format!(
+                    "edge {} -> {} {:?}",
+                    edge.from, edge.to, edge.label
+                ));
+            }
+        }
+        AsciiDiagramMeta::Sequence(seq) => {
+            for actor in &seq.participants {
+                model.push(format!(
+                    "participant {} {:?} {}",
+                    actor.id, actor.label, actor.participant_type
+                ));
+            }
+            for message in &seq.messages {
+                model.push(format!(
+                    "message {} {} -> {} {:?} {} {}",
+                    message.index,
+                    message.from,
+                    message.to,
+                    message.label,
+                    message.line_style,
+                    message.arrow_head
+                ));
+            }
+            for note in &seq.notes {
+                model.push(format!("note {:?}", note.text));
+            }
+            for block in &seq.blocks {
+                let dividers: Vec<&str> = block.dividers.iter().map(|d| d.label.as_str()).collect();
+                model.push(format!(
+                    "block {} {:?} {dividers:?}",
+                    block.block_type, block.label
+                ));
+            }
+        }
+        AsciiDiagramMeta::Class(class) => {
+            for node in &class.classes {
+                model.push(format!(
+                    "class {} {:?} {:?}",
+                    node.id, node.label, node.annotation
+                ));
+            }
+            for rel in &class.relationships {
+                model.push(format!(
+                    "relationship {} -> {} {} {:?}",
+                    rel.from, rel.to, rel.relationship_type, rel.label
+                ));
+            }
+            for node in &class.classes {
+                for row in node.attributes.iter().chain(&node.methods) {
+                    model.push(format!("member {}", compact(&row.text)));
+                }
+            }
+        }
+        AsciiDiagramMeta::Er(er) => {
+            for entity in &er.entities {
+                model.push(format!(
+                    "entity {} {:?} {} attributes",
+                    entity.id,
+                    entity.label,
+                    entity.attributes.len().max(1)
+                ));
+            }
+            for rel in &er.relationships {
+                model.push(format!(
+                    "relationship {} -> {} {:?} {} {} {}",
+                    rel.from,
+                    rel.to,
+                    rel.label,
+                    rel.identifying,
+                    rel.from_cardinality.cardinality,
+                    rel.to_cardinality.cardinality
+                ));
+            }
+        }
+    }
+    sorted(model)
+}
+
+fn js_svg(name: &str, diagram: &str) -> Model {
+    let rendered = render_mermaid_with_meta(
+        diagram,
+        &RenderOptions {
+            backend: Some(Backend::Js),
+            ..Default::default()
+        },
+    )
+    .unwrap_or_else(|err| panic!("{name}: JS 解析失败: {err}"));
+    svg_model(&rendered.meta, &rendered.svg)
+}
+
+fn ascii(name: &str, diagram: &str, backend: Backend) -> Model {
+    let rendered = render_mermaid_ascii_with_meta(
+        diagram,
+        &AsciiRenderOptions {
+            backend: Some(backend),
+            ..Default::default()
+        },
+    )
+    .unwrap_or_else(|err| panic!("{name}: {backend:?} 解析失败: {err}"));
+    ascii_model(&rendered.meta)
+}
+
+fn assert_same(name: &str, path: &str, diagram: &str, js: &Model, native: &Model) {
+    let only_js: Vec<&String> = js.iter().filter(|fact| !native.contains(fact)).collect();
+    let only_native: Vec<&String> = native.iter().filter(|fact| !js.contains(fact)).collect();
+    assert!(
+        only_js.is_empty() && only_native.is_empty(),
+        "{name}（{path}）: native parser 与 JS parser 不一致\n只在 JS: {only_js:#?}\n只在 native: {only_native:#?}\n---\n{diagram}"
+    );
+}
+
+/// 按输出路径对照：两边走的是同一条入口（预处理、`;` 是否算换行都一样）。
+///
+/// - flowchart/state：ASCII meta（JS 的 ASCII 转换与 native 一致地把 subgraph 端点当节点）
+///   与 SVG meta 各比一次；
+/// - sequence/class/ER：SVG meta 比一次；bundle 的 ASCII 入口只按 `\n` 切行，SVG 入口还按 `;` 切，
+///   没有 `;` 时 native ASCII 也要与 JS 的解析一致。
+fn assert_parity(name: &str, diagram: &str) {
+    let js = js_svg(name, diagram);
+    let native_svg = || {
+        let rendered = render_mermaid_with_meta(
+            diagram,
+            &RenderOptions {
+                backend: Some(Backend::Native),
+                ..Default::default()
+            },
+        )
+        .unwrap_or_else(|err| panic!("{name}: native 解析失败: {err}"));
+        svg_model(&rendered.meta, &rendered.svg)
+    };
+    let splits_on_semicolon = diagram.contains(';');
+    if ["sequenceDiagram", "classDiagram", "erDiagram"]
+        .iter()
+        .any(|kind| diagram.trim_start().starts_with(kind))
+    {
+        assert_same(name, "SVG", diagram, &js, &native_svg());
+        if !splits_on_semicolon {
+            let native = ascii(name, diagram, Backend::Native);
+            assert_same(name, "ASCII", diagram, &js, &native);
+        }
+    } else {
+        assert_same(name, "SVG", diagram, &js, &native_svg());
+        let js_ascii = ascii(name, diagram, Backend::Js);
+        let native = ascii(name, diagram, Backend::Native);
+        assert_same(name, "ASCII", diagram, &js_ascii, &native);
+    }
+}
+
+/// testdata 里的全部图（ascii/unicode 里重复的只跑一次）。
+fn testdata_diagrams() -> Vec<(String, String)> {
+    let mut seen = BTreeSet::new();
+    let mut diagrams = Vec::new();
+    for dir in ["ascii", "unicode"] {
+        let mut entries: Vec<_> = fs::read_dir(Path::new("tests/testdata").join(dir))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        entries.sort();
+        for path in entries {
+            let raw = fs::read_to_string(&path).unwrap().replace("\r\n", "\n");
+            let diagram: String = raw
+                .split_once("\n---\n")
+                .unwrap()
+                .0
+                .lines()
+                .skip_while(|line| line.contains('=') && !line.contains("--"))
+                .map(|line| format!("{line}\n"))
+                .collect();
+            if seen.insert(diagram.clone()) {
+                diagrams.push((path.display().to_string(), diagram));
+            }
+        }
+    }
+    diagrams
+}
+
+#[test]
+fn native_parser_matches_js_on_testdata() {
+    let diagrams = testdata_diagrams();
+    assert!(diagrams.len() > 50);
+    for (name, diagram) in diagrams {
+        assert_parity(&name, &diagram);
+    }
+}
+
+#[test]
+fn flowchart_and_state_parser_parity() {
+    for diagram in [
+        "graph TD\nA[Start] --> B{Is it?}\nB -->|Yes| C((Circle))\nB -- No --> D>Flag]\nC & D --> E[(Database)]\n",
+        "flowchart LR\n%% comment\nA([Stadium]) -.-> B[[Subroutine]]\nB ==> C{{Hex}}\nC --- D[/Para/]\nD <--> E[\\Alt\\]\nE --o F\nF --x G\n",
+        "graph TD; A-->B; B-->C; C-->A\n",
+        "graph LR\nA[\"quoted [label]\"] --> B(\"round (label)\")\nA -->|\"edge | label\"| B\nclassDef hot fill:#f00\nclass A hot\nB:::hot --> C\nstyle C fill:#0f0\nlinkStyle 0 stroke:#00f\n",
+        "flowchart TB\nsubgraph one [First group]\ndirection LR\nA1 --> A2\nend\nsubgraph two\nB1 --> B2\nend\none --> two\nA2 --> B1\n",
+        "graph TD\nA --> A\nA --> B\nA --> B\nB --> A\n",
+        "stateDiagram-v2\n[*] --> Still\nStill --> Moving : push\nMoving --> Still\nMoving --> Crash\nCrash --> [*]\n",
+        "stateDiagram-v2\nstate \"Waiting for input\" as Wait\n[*] --> Wait\nWait --> Done : ok\nstate Done {\n  [*] --> Inner\n  Inner --> [*]\n}\n",
+    ] {
+        assert_parity("flowchart/state", diagram);
+    }
+}
+
+#[test]
+fn sequence_parser_parity() {
+    for diagram in [
+        "sequenceDiagram\nparticipant A as Alice\nactor B as Bob\nA->>B: Hello\nB-->>A: Hi\nA-)B: async\nA--)B: async dotted\nA-xB: lost\nA--xB: lost dotted\nA->B: open\nA-->B: open dotted\n",
+        "sequenceDiagram\nAlice->>+Bob: start\nBob->>-Alice: done\nAlice->>Alice: think\nNote right of Bob: a note\nNote over Alice,Bob: shared\nNote left of Alice: left\n",
+        "sequenceDiagram\nloop Every minute\nA->>B: ping\nend\nalt ok\nB->>A: pong\nelse failed\nB->>A: error\nend\nopt maybe\nA->>B: extra\nend\npar first\nA->>B: one\nand second\nA->>C: two\nend\ncritical lock\nA->>B: x\nend\nbreak stop\nA->>B: y\nend\nrect rgb(0,0,255)\nA->>B: z\nend\n",
+        "sequenceDiagram\n%% comment\nautonumber\nparticipant C\nparticipant A\nA->>C: first; C->>A: second\n",
+    ] {
+        assert_parity("sequence", diagram);
+    }
+}
+
+#[test]
+fn class_parser_parity() {
+    for diagram in [
+        "classDiagram\nclass Animal {\n  <<abstract>>\n  +String name\n  -int age$\n  +makeSound()* void\n  #move(int distance) bool\n}\nAnimal <|-- Dog\nAnimal <|-- Cat : extends\n",
+        "classDiagram\nA *-- B : composition\nC o-- D : aggregation\nE --> F : association\nG ..> H : dependency\nI ..|> J : realization\nK -- L : link\nM .. N\nO <-- P\n",
+        "classDiagram\nclass Shape\n<<interface>> Shape\nShape : +area() double\nShape : +String id\nclass List~T~\nList~T~ --> Shape\n",
+        "classDiagram\ndirection LR\nclass Customer[\"Customer Account\"]\nCustomer \"1\" --> \"*\" Order : places\n",
+    ] {
+        assert_parity("class", diagram);
+    }
+}
+
+#[test]
+fn er_parser_parity() {
+    for diagram in [
+        "erDiagram\nCUSTOMER ||--o{ ORDER : places\nORDER ||--|{ LINE-ITEM : contains\nCUSTOMER }|..|{ DELIVERY-ADDRESS : uses\n",
+        "erDiagram\nCUSTOMER {\n  string name\n  string custNumber PK\n  int age \"years old\"\n  int addressId FK\n}\nORDER {\n  int id PK, FK\n}\nCUSTOMER |o--o| ORDER : \"may have\"\n",
+        "erDiagram\nA }o--|| B : one\nB ||--|| C : \"exactly one\"\nC |o..o{ D : optional\n%% comment\nD {\n}\n",
+    ] {
+        assert_parity("er", diagram);
+    }
+}
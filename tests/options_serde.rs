@@ -0,0 +1,128 @@
+// ============================================================================
+// options 的 serde 形状（camelCase，与 TS 版 options 对象同构）
+//
+// 目的：
+// - wasm 绑定（feature = "wasm"）直接把 JS 对象按 serde 反序列化成 options，
+//   字段名一旦漂移，JS 调用方传的参数会被静默忽略；
+// - Rust 侧的配置文件习惯写 snake_case（与字段名相同），每个字段都要有 snake_case 别名，
+//   否则同样静默回落到默认值（下面的 round-trip 用例把全部字段都转一遍，新增字段漏了别名会失败）。
+// ============================================================================
+
+use beautiful_mermaid_rs::{AsciiRenderOptions, AsciiRouting, Backend, RenderOptions};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+#[test]
+fn render_options_use_camel_case() {
+    let options: RenderOptions = serde_json::from_str(
+        r##"{"bg":"#000","idPrefix":"a-","nodeSpacing":12,"layerSpacing":34,"backend":"native"}"##,
+    )
+    .expect("camelCase 字段应当能解析");
+    assert_eq!(options.bg.as_deref(), Some("#000"));
+    assert_eq!(options.id_prefix.as_deref(), Some("a-"));
+    assert_eq!(options.node_spacing, Some(12.0));
+    assert_eq!(options.layer_spacing, Some(34.0));
+    assert_eq!(options.backend, Some(Backend::Native));
+
+    let json = serde_json::to_value(&options).unwrap();
+    assert_eq!(json["idPrefix"], "a-");
+    assert!(json.get("id_prefix").is_none());
+}
+
+#[test]
+fn ascii_render_options_use_camel_case() {
+    let options: AsciiRenderOptions = serde_json::from_str(
+        r#"{"useAscii":true,"routing":"strict","turnPenalty":2,"tsCompatible":true,"parallelRouting":false,"failOnUnroutable":true,"paddingX":3,"paddingY":4,"boxBorderPadding":1}"#,
+    )
+    .expect("camelCase 字段应当能解析");
+    assert_eq!(
+        options,
+        AsciiRenderOptions {
+            use_ascii: Some(true),
+            routing: Some(AsciiRouting::Strict),
+            turn_penalty: Some(2),
+            ts_compatible: Some(true),
+            parallel_routing: Some(false),
+            fail_on_unroutable: Some(true),
+            padding_x: Some(3),
+            padding_y: Some(4),
+            box_border_padding: Some(1),
+            backend: None,
+        }
+    );
+}
+
+/// `nodeSpacing` -> `node_spacing`
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for ch in name.chars() {
+        if ch.is_ascii_uppercase() {
+            snake.push('_');
+            snake.push(ch.to_ascii_lowercase());
+        } else {
+            snake.push(ch);
+        }
+    }
+    snake
+}
+
+/// 序列化（camelCase）-> 键全部改成 snake_case -> 反序列化，必须得到原值。
+fn assert_snake_case_round_trip<T>(options: &T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug,
+{
+    let Value::Object(camel) = serde_json::to_value(options).unwrap() else {
+        panic!("options 应当序列化成对象");
+    };
+    assert!(
+        camel.values().all(|value| !value.is_null()),
+        "测试要给每个字段赋值: {camel:?}"
+    );
+    let snake: Map<String, Value> = camel
+        .into_iter()
+        .map(|(key, value)| (snake_case(&key), value))
+        .collect();
+    let parsed: T = serde_json::from_value(Value::Object(snake.clone()))
+        .unwrap_or_else(|err| panic!("snake_case 字段应当能解析: {err}"));
+    assert_eq!(&parsed, options, "snake_case 字段被忽略了: {snake:?}");
+}
+
+#[test]
+fn options_accept_snake_case_fields() {
+    assert_snake_case_round_trip(&RenderOptions {
+        bg: Some("#000".to_string()),
+        fg: Some("#fff".to_string()),
+        line: Some("#111".to_string()),
+        accent: Some("#222".to_string()),
+        muted: Some("#333".to_string()),
+        surface: Some("#444".to_string()),
+        border: Some("#555".to_string()),
+        font: Some("Inter".to_string()),
+        padding: Some(8.0),
+        node_spacing: Some(12.0),
+        layer_spacing: Some(34.0),
+        transparent: Some(true),
+        id_prefix: Some("a-".to_string()),
+        title: Some("T".to_string()),
+        description: Some("D".to_string()),
+        backend: Some(Backend::Native),
+    });
+    assert_snake_case_round_trip(&AsciiRenderOptions {
+        use_ascii: Some(true),
+        routing: Some(AsciiRouting::Strict),
+        turn_penalty: Some(2),
+        ts_compatible: Some(true),
+        parallel_routing: Some(false),
+        fail_on_unroutable: Some(true),
+        padding_x: Some(3),
+        padding_y: Some(4),
+        box_border_padding: Some(1),
+        backend: Some(Backend::Js),
+    });
+
+    let options: AsciiRenderOptions =
+        serde_json::from_str(r#"{"use_ascii":true,"padding_x":7}"#).unwrap();
+    assert_eq!(options.use_ascii, Some(true));
+    assert_eq!(options.padding_x, Some(7));
+}
@@ -0,0 +1,196 @@
+// ============================================================================
+// `parallel_routing`（互不相连的区域并行预演 + 串行回放校验）回归测试
+//
+// 说明：
+// - 并行只是加速手段：无论预演结果是被整段回放还是中途作废，输出都必须与逐条走线
+//   逐字节相同；
+// - `Some(true)` 在单核机器上也强制并行，测试才能覆盖到预演/回放路径；
+// - 多条互不相连的 LR 流水线：各自成一个区域，预演结果应整段回放；
+// - 流水线带回边、互相挨得很近：部分区域的预演会失效，要退回逐条走线；
+// - 全部 testdata（ASCII/Unicode）上同样逐字节对比；
+// - `tests/testdata/parallel` 下的多分量图（golden 与 JS 后端一致）：用走线统计确认真的分出了
+//   多个区域、每条边都取自并行预演——区域重叠会合并，普通的图往往只有一个区域，不并行。
+// ============================================================================
+
+#![cfg(feature = "native-backend")]
+
+use beautiful_mermaid_rs::{
+    AsciiRenderOptions, AsciiRouting, Backend, render_mermaid_ascii,
+    render_mermaid_ascii_with_stats,
+};
+use std::fs;
+use std::path::Path;
+
+fn render(diagram: &str, use_ascii: bool, routing: AsciiRouting, parallel: bool) -> String {
+    let options = AsciiRenderOptions {
+        use_ascii: Some(use_ascii),
+        routing: Some(routing),
+        backend: Some(Backend::Native),
+        parallel_routing: Some(parallel),
+        ..Default::default()
+    };
+    render_mermaid_ascii(diagram, &options)
+        .unwrap_or_else(|err| panic!("渲染失败: parallel={parallel}, err={err}"))
+}
+
+fn assert_same(diagram: &str, use_ascii: bool, routing: AsciiRouting) {
+    assert_eq!(
+        render(diagram, use_ascii, routing, true),
+        render(diagram, use_ascii, routing, false),
+        "use_ascii={use_ascii}, routing={routing:?}\n{diagram}"
+    );
+}
+
+const PIPELINES: &str = "flowchart LR
+  A1[fetch] --> A2[parse] --> A3[validate] --> A4[store]
+  A3 -->|retry| A1
+  B1[fetch] --> B2[parse] --> B3[validate] --> B4[store]
+  B4 -->|again| B2
+  C1[fetch] --> C2[parse] --> C3[validate] --> C4[store]
+  C2 --> C2
+  D1[fetch] --> D2[parse] --> D3[validate] --> D4[store]
+";
+
+#[test]
+fn separate_pipelines_render_identically() {
+    for routing in [
+        AsciiRouting::Strict,
+        AsciiRouting::Relaxed,
+        AsciiRouting::Global,
+    ] {
+        for use_ascii in [true, false] {
+            assert_same(PIPELINES, use_ascii, routing);
+        }
+    }
+}
+
+#[test]
+fn diverging_regions_render_identically() {
+    // A4 -> A1 的回边绕过其他流水线附近：回放时校验失败，区域退回逐条走线
+    let diagram = "flowchart LR
+  A1[fetch] --> A2[parse] --> A3[validate] --> A4[store]
+  A4 -->|again| A1
+  A1 --> A3
+  B1[fetch] --> B2[parse] --> B3[validate] --> B4[store]
+  B4 -->|again| B2
+  C1[fetch] --> C2[parse] --> C3[validate] --> C4[store]
+  C2 --> C2
+";
+    for routing in [AsciiRouting::Strict, AsciiRouting::Relaxed] {
+        for use_ascii in [true, false] {
+            assert_same(diagram, use_ascii, routing);
+        }
+    }
+}
+
+#[test]
+fn testdata_renders_identically() {
+    for (dir, use_ascii) in [
+        ("tests/testdata/ascii", true),
+        ("tests/testdata/unicode", false),
+    ] {
+        let mut entries: Vec<_> = fs::read_dir(Path::new(dir))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        entries.sort();
+        for path in entries {
+            let raw = fs::read_to_string(&path).unwrap().replace("\r\n", "\n");
+            let diagram = raw.split_once("\n---\n").unwrap().0;
+            // 跳过 `paddingX=2` 之类的配置行
+            let diagram: String = diagram
+                .lines()
+                .skip_while(|line| line.contains('='))
+                .map(|line| format!("{line}\n"))
+                .collect();
+            if !diagram.starts_with("graph ") && !diagram.starts_with("flowchart ") {
+                continue;
+            }
+            for routing in [AsciiRouting::Strict, AsciiRouting::Relaxed] {
+                assert_eq!(
+                    render(&diagram, use_ascii, routing, true),
+                    render(&diagram, use_ascii, routing, false),
+                    "path={path:?}, routing={routing:?}"
+                );
+            }
+        }
+    }
+}
+
+/// 每行 trimEnd + 去掉首尾空行（同 `ascii_testdata.rs`）。
+fn normalize_whitespace(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let start = lines.iter().position(|line| !line.is_empty()).unwrap_or(0);
+    let end = lines
+        .iter()
+        .rposition(|line| !line.is_empty())
+        .map_or(start, |end| end + 1);
+    lines[start..end].join("\n")
+}
+
+#[test]
+fn separate_clusters_are_rehearsed_in_parallel() {
+    for (dir, use_ascii) in [
+        ("tests/testdata/parallel/ascii", true),
+        ("tests/testdata/parallel/unicode", false),
+    ] {
+        let mut entries: Vec<_> = fs::read_dir(Path::new(dir))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        entries.sort();
+        assert!(!entries.is_empty(), "{dir} 下没有 golden");
+        for path in entries {
+            let raw = fs::read_to_string(&path).unwrap().replace("\r\n", "\n");
+            let (diagram, expected) = raw.split_once("\n---\n").unwrap();
+            let diagram = format!("{diagram}\n");
+            // 默认走线规则（ASCII strict / Unicode relaxed），padding 同 `ascii_testdata.rs`
+            let options = |parallel, backend| AsciiRenderOptions {
+                use_ascii: Some(use_ascii),
+                padding_x: Some(5),
+                padding_y: Some(5),
+                backend: Some(backend),
+                parallel_routing: Some(parallel),
+                ..Default::default()
+            };
+
+            #[cfg(feature = "js-backend")]
+            assert_eq!(
+                normalize_whitespace(
+                    &render_mermaid_ascii(&diagram, &options(false, Backend::Js)).unwrap()
+                ),
+                expected.trim_end(),
+                "JS 后端: path={path:?}"
+            );
+
+            let serial =
+                render_mermaid_ascii_with_stats(&diagram, &options(false, Backend::Native))
+                    .unwrap();
+            assert_eq!(
+                normalize_whitespace(&serial.text),
+                expected.trim_end(),
+                "path={path:?}"
+            );
+            assert_eq!(serial.stats.parallel_regions, 0, "path={path:?}");
+            assert!(serial.stats.edges.iter().all(|edge| !edge.rehearsed));
+
+            let parallel =
+                render_mermaid_ascii_with_stats(&diagram, &options(true, Backend::Native)).unwrap();
+            assert_eq!(parallel.text, serial.text, "path={path:?}");
+            assert_eq!(parallel.stats.layout_attempts, 1, "path={path:?}");
+            assert!(
+                parallel.stats.parallel_regions > 1,
+                "应当分出多个区域: path={path:?}, stats={:?}",
+                parallel.stats
+            );
+            // 每条边（复用平行边路径的除外）都直接落定预演结果，没有区域退回逐条走线
+            for edge in &parallel.stats.edges {
+                assert!(edge.routed, "path={path:?}, edge={edge:?}");
+                assert!(
+                    edge.rehearsed || edge.shared_path,
+                    "path={path:?}, edge={edge:?}"
+                );
+            }
+        }
+    }
+}
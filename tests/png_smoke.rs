@@ -0,0 +1,194 @@
+// ============================================================================
+// PNG 光栅化冒烟测试（feature = "png"）
+//
+// 目的：
+// - 输出是合法 PNG（签名 + 可解码）；
+// - 尺寸随 scale/dpi 变化，dpi 写入 pHYs；
+// - 背景：默认沿用 SVG 背景，`transparent` 时透明，`background` 可覆盖；
+// - 文字确实被画出来（打包字体生效，而不是一片空白）。
+//
+// 运行：`cargo test --features png`
+// ============================================================================
+
+#![cfg(all(feature = "png", feature = "js-backend"))]
+
+use beautiful_mermaid_rs::{RasterOptions, RenderOptions, render_mermaid_png, svg_to_png};
+
+const DIAGRAM: &str = "graph TD\n  A[Start] --> B[End]\n";
+
+struct Decoded {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+    pixels_per_meter: Option<u32>,
+}
+
+fn decode(bytes: &[u8]) -> Decoded {
+    assert!(
+        bytes.starts_with(b"\x89PNG\r\n\x1a\n"),
+        "输出应以 PNG 签名开头"
+    );
+    let decoder = png::Decoder::new(std::io::Cursor::new(bytes));
+    let mut reader = decoder.read_info().expect("PNG 应当可解码");
+    let pixels_per_meter = reader.info().pixel_dims.map(|dims| dims.xppu);
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).expect("PNG 帧应当可解码");
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+    pixels.truncate(info.buffer_size());
+    Decoded {
+        width: info.width,
+        height: info.height,
+        pixels,
+        pixels_per_meter,
+    }
+}
+
+fn pixel(image: &Decoded, x: u32, y: u32) -> [u8; 4] {
+    let offset = ((y * image.width + x) * 4) as usize;
+    image.pixels[offset..offset + 4].try_into().unwrap()
+}
+
+#[test]
+fn png_size_follows_scale_and_dpi() {
+    let base = decode(
+        &render_mermaid_png(
+            DIAGRAM,
+            &RenderOptions::default(),
+            &RasterOptions::default(),
+        )
+        .expect("PNG 渲染应当成功"),
+    );
+    assert!(base.width > 0 && base.height > 0);
+    // 96 dpi = 3780 像素/米
+    assert_eq!(base.pixels_per_meter, Some(3780));
+
+    let scaled = decode(
+        &render_mermaid_png(
+            DIAGRAM,
+            &RenderOptions::default(),
+            &RasterOptions {
+                scale: Some(2.0),
+                ..Default::default()
+            },
+        )
+        .expect("PNG 渲染应当成功"),
+    );
+    assert_eq!(scaled.width, base.width * 2);
+    assert_eq!(scaled.height, base.height * 2);
+
+    let print = decode(
+        &render_mermaid_png(
+            DIAGRAM,
+            &RenderOptions::default(),
+            &RasterOptions {
+                dpi: Some(192.0),
+                ..Default::default()
+            },
+        )
+        .expect("PNG 渲染应当成功"),
+    );
+    assert_eq!((print.width, print.height), (scaled.width, scaled.height));
+    assert_eq!(print.pixels_per_meter, Some(7559));
+}
+
+#[test]
+fn png_background_follows_svg_and_options() {
+    // 默认：沿用 SVG 的 `--bg`
+    let themed = decode(
+        &render_mermaid_png(
+            DIAGRAM,
+            &RenderOptions {
+                bg: Some("#102030".to_string()),
+                ..Default::default()
+            },
+            &RasterOptions::default(),
+        )
+        .expect("PNG 渲染应当成功"),
+    );
+    assert_eq!(pixel(&themed, 0, 0), [0x10, 0x20, 0x30, 0xff]);
+
+    // transparent：四角全透明
+    let transparent = decode(
+        &render_mermaid_png(
+            DIAGRAM,
+            &RenderOptions {
+                transparent: Some(true),
+                ..Default::default()
+            },
+            &RasterOptions::default(),
+        )
+        .expect("PNG 渲染应当成功"),
+    );
+    assert_eq!(pixel(&transparent, 0, 0)[3], 0);
+
+    // background 覆盖 SVG 背景
+    let white = decode(
+        &render_mermaid_png(
+            DIAGRAM,
+            &RenderOptions {
+                transparent: Some(true),
+                ..Default::default()
+            },
+            &RasterOptions {
+                background: Some("white".to_string()),
+                ..Default::default()
+            },
+        )
+        .expect("PNG 渲染应当成功"),
+    );
+    assert_eq!(pixel(&white, 0, 0), [0xff, 0xff, 0xff, 0xff]);
+}
+
+#[test]
+fn png_draws_nodes_text_and_edges() {
+    let image = decode(
+        &render_mermaid_png(
+            DIAGRAM,
+            &RenderOptions::default(),
+            &RasterOptions::default(),
+        )
+        .expect("PNG 渲染应当成功"),
+    );
+    let background = pixel(&image, 0, 0);
+    let distinct = (0..image.height)
+        .flat_map(|y| (0..image.width).map(move |x| (x, y)))
+        .filter(|&(x, y)| pixel(&image, x, y) != background)
+        .count();
+    // 节点边框 + 文字 + 连线，至少占画布的几个百分点
+    let total = (image.width * image.height) as usize;
+    assert!(
+        distinct * 50 > total,
+        "前景像素太少（{distinct}/{total}），可能 CSS 变量或字体没生效"
+    );
+
+    // 颜色应被解析（而不是 resvg 忽略 var() 后的默认黑色）：默认主题的 fg 是 #27272A
+    let has_dark_text = image
+        .pixels
+        .chunks(4)
+        .any(|px| px[3] == 0xff && px[0] < 0x40 && px[1] < 0x40 && px[2] < 0x40 && px[0] > 0x10);
+    assert!(has_dark_text, "应能找到文字颜色的像素");
+}
+
+#[test]
+fn invalid_raster_options_are_errors() {
+    let svg = beautiful_mermaid_rs::render_mermaid(DIAGRAM, &RenderOptions::default())
+        .expect("SVG 渲染应当成功");
+
+    for raster in [
+        RasterOptions {
+            scale: Some(0.0),
+            ..Default::default()
+        },
+        RasterOptions {
+            dpi: Some(f32::NAN),
+            ..Default::default()
+        },
+        RasterOptions {
+            background: Some("not-a-color".to_string()),
+            ..Default::default()
+        },
+    ] {
+        let err = svg_to_png(&svg, &raster).expect_err("无效参数应当报错");
+        assert!(err.to_string().starts_with("光栅化失败"), "{err}");
+    }
+}
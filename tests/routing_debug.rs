@@ -0,0 +1,257 @@
+// ============================================================================
+// 走线网格调试视图（`routing::debug` / `render_mermaid_ascii_with_debug`）回归测试
+//
+// 说明：
+// - 快照：只保存有内容的区域，blocked / used points 方向位 / segment usage 与网格一致；
+// - 探测：与 `last_stats` 的计数一致，并能定位到具体被拒的单步；`take_probe` 之后不再记录；
+// - 帧：文本 / SVG 视图包含占用图、热度图与线段表，多帧动画每帧一个 `<animate>`；
+// - flowchart：记录帧不改变输出；走不通的边在帧里能看到 crossing / segment 拒绝。
+// ============================================================================
+
+#![cfg(feature = "native-backend")]
+
+use beautiful_mermaid_rs::routing::debug::{DebugFrame, animated_svg};
+use beautiful_mermaid_rs::routing::{
+    CONNECT_DOWN, CONNECT_LEFT, CONNECT_RIGHT, CONNECT_UP, GridPoint, RouteConstraints, RouteRules,
+    RoutingGrid,
+};
+use beautiful_mermaid_rs::{
+    AsciiRenderOptions, AsciiRouting, Backend, render_mermaid_ascii,
+    render_mermaid_ascii_with_debug,
+};
+use std::fs;
+use std::path::Path;
+
+fn p(x: u32, y: u32) -> GridPoint {
+    GridPoint::new(x, y)
+}
+
+fn strict(source_id: u32, target_id: u32) -> RouteConstraints {
+    RouteConstraints {
+        rules: RouteRules::Strict,
+        source_id,
+        target_id,
+        ..Default::default()
+    }
+}
+
+/// 横线 + 从上方接到 (2,2) 的竖线形成 `┴`；从下方再接到 (2,2) 会补出 `┼`。
+fn tee_grid() -> RoutingGrid {
+    let mut grid = RoutingGrid::new(5, 5);
+    grid.set_blocked(p(0, 2), true);
+    grid.set_blocked(p(4, 2), true);
+    let wall = grid.route(p(0, 2), p(4, 2), &strict(1, 2)).unwrap();
+    grid.commit(&wall);
+    let stub = grid.route(p(2, 0), p(2, 2), &strict(3, 4)).unwrap();
+    grid.commit(&stub);
+    grid
+}
+
+#[test]
+fn snapshot_keeps_only_the_occupied_region() {
+    let mut grid = RoutingGrid::new(40, 30);
+    let empty = grid.snapshot();
+    assert_eq!((empty.width, empty.height), (40, 30));
+    assert_eq!((empty.region_width, empty.region_height), (0, 0));
+    assert!(empty.segments.is_empty());
+
+    grid.block_rect(p(10, 5), 3, 3);
+    let route = grid
+        .route(p(5, 6), p(5, 12), &RouteConstraints::default())
+        .unwrap();
+    grid.commit(&route);
+    let snapshot = grid.snapshot();
+    assert_eq!(snapshot.origin, p(5, 5));
+    assert_eq!((snapshot.region_width, snapshot.region_height), (8, 8));
+    assert!(snapshot.is_blocked(p(11, 6)));
+    assert!(!snapshot.is_blocked(p(9, 6)));
+    // 区域外的格子都是空的
+    assert!(!snapshot.is_blocked(p(0, 0)));
+    assert_eq!(snapshot.connections(p(39, 29)), 0);
+    assert_eq!(snapshot.connections(p(5, 6)), CONNECT_DOWN);
+    assert_eq!(snapshot.connections(p(5, 9)), grid.connections(p(5, 9)));
+
+    assert_eq!(snapshot.segments.len(), grid.used_segments());
+    let first = &snapshot.segments[0];
+    assert_eq!((first.from, first.to), (p(5, 6), p(5, 7)));
+    assert!(!first.used_as_middle, "起点段: {first:?}");
+    assert!(first.start_source.is_some(), "{first:?}");
+    assert!(snapshot.segments[1].used_as_middle);
+}
+
+#[test]
+fn probe_locates_rejected_steps() {
+    let mut grid = tee_grid();
+    assert_eq!(
+        grid.snapshot().connections(p(2, 2)),
+        CONNECT_LEFT | CONNECT_RIGHT | CONNECT_UP
+    );
+
+    grid.start_probe();
+    assert!(grid.route(p(2, 4), p(2, 2), &strict(5, 6)).is_none());
+    let stats = grid.last_stats();
+    let probe = grid.take_probe();
+    assert_eq!(probe.searches, 1);
+    assert_eq!(probe.expansions(), stats.expansions);
+    assert_eq!(probe.rejections(), stats.rejections);
+    let into_tee = probe
+        .rejected
+        .get(&(p(2, 3), p(2, 2)))
+        .expect("从正下方接进 `┴` 应当被拒");
+    assert!(into_tee.crossing + into_tee.segment > 0, "{into_tee:?}");
+
+    // take_probe 之后不再记录
+    assert!(grid.route(p(2, 4), p(2, 2), &strict(5, 6)).is_none());
+    assert_eq!(grid.take_probe(), Default::default());
+}
+
+#[test]
+fn frame_views_annotate_grid_and_search() {
+    let mut grid = tee_grid();
+    let before = grid.snapshot();
+    grid.start_probe();
+    let relaxed = grid
+        .route(
+            p(2, 4),
+            p(2, 2),
+            &RouteConstraints {
+                rules: RouteRules::Relaxed,
+                ..strict(5, 6)
+            },
+        )
+        .unwrap();
+    let frame = DebugFrame {
+        title: "tee <relaxed>".to_string(),
+        grid: before,
+        probe: grid.take_probe(),
+        path: relaxed.points.clone(),
+    };
+
+    let text = frame.to_text();
+    assert!(text.starts_with("tee <relaxed>\n"), "{text}");
+    assert!(
+        text.contains("#─*─#"),
+        "占用图画出方向位与本帧路径:\n{text}"
+    );
+    assert!(text.contains("搜索（"), "{text}");
+    assert!(text.contains("线段占用（"), "{text}");
+
+    let svg = frame.to_svg();
+    assert!(svg.starts_with("<svg "), "{svg}");
+    assert!(svg.contains("tee &lt;relaxed&gt;"), "标题要转义: {svg}");
+    assert!(svg.contains("<polyline "), "{svg}");
+    assert!(!svg.contains("<animate "), "{svg}");
+
+    let frames = vec![frame.clone(), frame.clone(), frame];
+    let animated = animated_svg(&frames, 0.5);
+    assert_eq!(animated.matches("<animate ").count(), 3);
+    assert_eq!(animated.matches(r#"<g visibility="hidden">"#).count(), 2);
+    assert!(animated.contains("[3/3] tee"), "{animated}");
+    assert!(animated.trim_end().ends_with("</svg>"));
+
+    let empty = DebugFrame::default();
+    assert!(empty.to_text().contains("网格是空的"));
+}
+
+#[test]
+fn debug_frames_do_not_change_output() {
+    for (dir, use_ascii) in [
+        ("tests/testdata/ascii", true),
+        ("tests/testdata/unicode", false),
+    ] {
+        let mut entries: Vec<_> = fs::read_dir(Path::new(dir))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        entries.sort();
+        for path in entries {
+            let raw = fs::read_to_string(&path).unwrap().replace("\r\n", "\n");
+            let diagram = raw.split_once("\n---\n").unwrap().0;
+            // 跳过 `paddingX=2` 之类的配置行
+            let diagram: String = diagram
+                .lines()
+                .skip_while(|line| line.contains('='))
+                .map(|line| format!("{line}\n"))
+                .collect();
+            if !diagram.starts_with("graph ") && !diagram.starts_with("flowchart ") {
+                continue;
+            }
+            for routing in [AsciiRouting::Strict, AsciiRouting::Relaxed] {
+                let options = AsciiRenderOptions {
+                    use_ascii: Some(use_ascii),
+                    routing: Some(routing),
+                    backend: Some(Backend::Native),
+                    ..Default::default()
+                };
+                let rendered = render_mermaid_ascii_with_debug(&diagram, &options).unwrap();
+                assert_eq!(
+                    rendered.text,
+                    render_mermaid_ascii(&diagram, &options).unwrap(),
+                    "path={path:?}, routing={routing:?}"
+                );
+                for frame in &rendered.frames {
+                    if let (Some(first), Some(last)) = (frame.path.first(), frame.path.last()) {
+                        assert_ne!(first, last, "path={path:?}, frame={}", frame.title);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn unroutable_edge_frames_show_rejections() {
+    let raw = fs::read_to_string("tests/testdata/unicode/user_repro_case.txt")
+        .unwrap()
+        .replace("\r\n", "\n");
+    let diagram = raw.split_once("\n---\n").unwrap().0;
+    let options = AsciiRenderOptions {
+        use_ascii: Some(false),
+        routing: Some(AsciiRouting::Strict),
+        backend: Some(Backend::Native),
+        ..Default::default()
+    };
+    let rendered = render_mermaid_ascii_with_debug(diagram, &options).unwrap();
+    // strict：每条边一帧（没有平行边共享路径）
+    assert_eq!(rendered.frames.len(), 10);
+
+    let failed: Vec<_> = rendered
+        .frames
+        .iter()
+        .filter(|frame| frame.path.is_empty())
+        .collect();
+    assert_eq!(failed.len(), 2);
+    for frame in failed {
+        assert!(
+            frame.title.starts_with("Hat_ralph -> Hat_experiment_")
+                && frame.title.contains("走不通"),
+            "{}",
+            frame.title
+        );
+        assert!(frame.probe.searches > 0);
+        let rejections = frame.probe.rejections();
+        assert!(
+            rejections.crossing + rejections.segment > 0,
+            "{rejections:?}"
+        );
+        assert!(!frame.grid.segments.is_empty(), "走线前已有其他边的占用");
+    }
+    assert!(
+        rendered.frames[0].grid.segments.is_empty(),
+        "第一条边走线前没有占用"
+    );
+}
+
+#[test]
+fn diagrams_without_routing_have_no_frames() {
+    let diagram = "sequenceDiagram
+  Alice->>Bob: hello
+";
+    let options = AsciiRenderOptions::default();
+    let rendered = render_mermaid_ascii_with_debug(diagram, &options).unwrap();
+    assert_eq!(
+        rendered.text,
+        render_mermaid_ascii(diagram, &options).unwrap()
+    );
+    assert!(rendered.frames.is_empty());
+}
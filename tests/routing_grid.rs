@@ -0,0 +1,250 @@
+// ============================================================================
+// RoutingGrid（网格 A* 走线公共 API）回归测试
+//
+// 目的:
+// - free / strict / relaxed 三种规则的语义与 native ASCII 后端一致；
+// - `commit` 之后占用表生效：strict 禁止补出 `┼`，只允许同源起点段 / 同靶终点段共享；
+// - `bounds` 搜索窗口与非法 `commit` 的行为有明确约定。
+// ============================================================================
+
+#![cfg(any(feature = "js-backend", feature = "native-backend"))]
+
+use beautiful_mermaid_rs::routing::{
+    CONNECT_DOWN, CONNECT_LEFT, CONNECT_RIGHT, CONNECT_UP, GridPoint, Route, RouteConstraints,
+    RouteRules, RoutingGrid,
+};
+
+fn p(x: u32, y: u32) -> GridPoint {
+    GridPoint::new(x, y)
+}
+
+fn strict(source_id: u32, target_id: u32) -> RouteConstraints {
+    RouteConstraints {
+        rules: RouteRules::Strict,
+        source_id,
+        target_id,
+        ..Default::default()
+    }
+}
+
+/// 路径逐格正交相邻，且中间格子都不是 blocked。
+fn assert_well_formed(grid: &RoutingGrid, route: &Route) {
+    for pair in route.points.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        assert_eq!(a.x.abs_diff(b.x) + a.y.abs_diff(b.y), 1, "{route:?}");
+    }
+    for point in &route.points[1..route.points.len() - 1] {
+        assert!(!grid.is_blocked(*point), "{route:?}");
+    }
+}
+
+#[test]
+fn free_route_goes_around_obstacles() {
+    // 7×5 网格，x=3 这一列只有最下面一格是通的。
+    let mut grid = RoutingGrid::new(7, 5);
+    grid.block_rect(p(3, 0), 1, 4);
+
+    let route = grid
+        .route(p(0, 0), p(6, 0), &RouteConstraints::default())
+        .expect("绕过障碍应当可达");
+    assert_well_formed(&grid, &route);
+    assert!(route.points.contains(&p(3, 4)), "{route:?}");
+    assert_eq!(route.cost, 14, "最短绕行是 6 + 2×4 步");
+    assert_eq!(route.cost as usize, route.points.len() - 1);
+    let corners = route.corners();
+    assert_eq!(corners.first(), Some(&p(0, 0)));
+    assert_eq!(corners.last(), Some(&p(6, 0)));
+    assert!(corners.len() >= 4, "{corners:?}");
+
+    // 端点在网格外直接返回 None。
+    assert!(
+        grid.route(p(0, 0), p(7, 0), &RouteConstraints::default())
+            .is_none()
+    );
+}
+
+#[test]
+fn strict_forbids_crossing_but_relaxed_pays_for_it() {
+    // 一条横线把 5×5 网格分成上下两半（两端是 blocked 的“节点”），
+    // 再从上方接一条竖线到 (2,2)，形成 `┴`。
+    let mut grid = RoutingGrid::new(5, 5);
+    grid.set_blocked(p(0, 2), true);
+    grid.set_blocked(p(4, 2), true);
+    let wall = grid.route(p(0, 2), p(4, 2), &strict(1, 2)).unwrap();
+    assert_eq!(wall.points.len(), 5, "{wall:?}");
+    grid.commit(&wall);
+    assert_eq!(grid.connections(p(0, 2)), 0, "blocked 格子不记方向位");
+    assert_eq!(grid.connections(p(2, 2)), CONNECT_LEFT | CONNECT_RIGHT);
+    let stub = grid.route(p(2, 0), p(2, 2), &strict(3, 4)).unwrap();
+    grid.commit(&stub);
+    assert_eq!(grid.used_segments(), 6);
+    assert_eq!(
+        grid.connections(p(2, 2)),
+        CONNECT_LEFT | CONNECT_RIGHT | CONNECT_UP
+    );
+
+    // 从下方连到 (2,2) 会把 `┴` 补成 `┼`。
+    let crossing = grid.route(p(2, 4), p(2, 2), &strict(5, 6));
+    assert!(crossing.is_none(), "strict 禁止补出 ┼: {crossing:?}");
+
+    let relaxed = grid
+        .route(
+            p(2, 4),
+            p(2, 2),
+            &RouteConstraints {
+                rules: RouteRules::Relaxed,
+                ..strict(5, 6)
+            },
+        )
+        .expect("relaxed 允许交叉");
+    assert_well_formed(&grid, &relaxed);
+    assert!(
+        relaxed.cost as usize > relaxed.points.len() - 1,
+        "交叉计入代价: {relaxed:?}"
+    );
+
+    // free 完全不看占用表。
+    assert!(
+        grid.route(p(2, 4), p(2, 2), &RouteConstraints::default())
+            .is_some()
+    );
+
+    // 清空路径后 strict 又能走通。
+    grid.clear_routes();
+    assert_eq!(grid.used_segments(), 0);
+    assert!(grid.route(p(2, 4), p(2, 2), &strict(5, 6)).is_some());
+}
+
+#[test]
+fn corners_keep_endpoints_and_turns() {
+    let route = Route {
+        points: vec![p(0, 0), p(1, 0), p(2, 0), p(2, 1), p(2, 2), p(3, 2)],
+        cost: 5,
+        source_id: 1,
+        target_id: 2,
+    };
+    assert_eq!(route.corners(), vec![p(0, 0), p(2, 0), p(2, 2), p(3, 2)]);
+}
+
+#[test]
+fn strict_shares_start_segment_only_with_same_source() {
+    // 只开放一个 “T” 形通道：
+    //   S . A
+    //   # . #
+    //   # B #
+    let mut grid = RoutingGrid::new(3, 3);
+    grid.block_rect(p(0, 1), 1, 2);
+    grid.block_rect(p(2, 1), 1, 2);
+
+    let first = grid.route(p(0, 0), p(2, 0), &strict(1, 2)).unwrap();
+    grid.commit(&first);
+    assert_eq!(grid.connections(p(1, 0)), CONNECT_LEFT | CONNECT_RIGHT);
+
+    assert!(
+        grid.route(p(0, 0), p(1, 2), &strict(5, 3)).is_none(),
+        "不同起点不能复用别人的起点段"
+    );
+    assert!(
+        grid.route(
+            p(0, 0),
+            p(1, 2),
+            &RouteConstraints {
+                rules: RouteRules::Strict,
+                ..Default::default()
+            }
+        )
+        .is_none(),
+        "匿名端点不与任何路径共享"
+    );
+
+    let second = grid
+        .route(p(0, 0), p(1, 2), &strict(1, 3))
+        .expect("同源边可以共享起点段");
+    assert_eq!(second.points, vec![p(0, 0), p(1, 0), p(1, 1), p(1, 2)]);
+    grid.commit(&second);
+    assert_eq!(
+        grid.connections(p(1, 0)),
+        CONNECT_LEFT | CONNECT_RIGHT | CONNECT_DOWN,
+        "共享后是 ┬，而不是 ┼"
+    );
+    assert_eq!(grid.used_segments(), 4);
+}
+
+#[test]
+fn turn_penalty_prefers_fewer_corners() {
+    // 空网格对角两点：所有单调路径一样长，只有转弯惩罚能区分。
+    let mut grid = RoutingGrid::new(6, 6);
+    for rules in [RouteRules::Free, RouteRules::Strict, RouteRules::Relaxed] {
+        let constraints = RouteConstraints {
+            rules,
+            turn_penalty: 3,
+            ..strict(1, 2)
+        };
+        let route = grid.route(p(0, 0), p(5, 5), &constraints).unwrap();
+        assert_well_formed(&grid, &route);
+        assert_eq!(route.points.len(), 11, "{rules:?}: 仍是最短路径");
+        assert_eq!(route.corners().len(), 3, "{rules:?}: 只拐一次弯: {route:?}");
+        assert_eq!(route.cost, 10 + 3, "{rules:?}: 代价含一次转弯惩罚");
+    }
+}
+
+#[test]
+fn bounds_limit_the_search_window() {
+    let mut grid = RoutingGrid::new(5, 5);
+    grid.block_rect(p(2, 0), 1, 4);
+
+    let windowed = RouteConstraints {
+        bounds: Some(p(4, 3)),
+        ..Default::default()
+    };
+    assert!(grid.route(p(0, 0), p(4, 0), &windowed).is_none());
+    assert!(
+        grid.route(p(0, 0), p(4, 0), &RouteConstraints::default())
+            .is_some()
+    );
+
+    // 超出网格的窗口按整张网格处理。
+    let oversized = RouteConstraints {
+        bounds: Some(p(100, 100)),
+        ..Default::default()
+    };
+    assert!(grid.route(p(0, 0), p(4, 0), &oversized).is_some());
+}
+
+#[test]
+#[should_panic(expected = "不是正交相邻")]
+fn commit_rejects_non_adjacent_points() {
+    let mut grid = RoutingGrid::new(4, 4);
+    grid.commit(&Route {
+        points: vec![p(0, 0), p(2, 0)],
+        cost: 1,
+        source_id: 1,
+        target_id: 2,
+    });
+}
+
+#[test]
+#[should_panic(expected = "不是正交相邻")]
+fn commit_rejects_row_wrap() {
+    // (3,0) 与 (0,1) 的下标只差 1，但分处两行的两端。
+    let mut grid = RoutingGrid::new(4, 4);
+    grid.commit(&Route {
+        points: vec![p(3, 0), p(0, 1)],
+        cost: 1,
+        source_id: 1,
+        target_id: 2,
+    });
+}
+
+#[test]
+fn single_column_grid_records_vertical_connections() {
+    // 宽度为 1 时下标差 1 就是上下相邻，不能记成左右方向位。
+    let mut grid = RoutingGrid::new(1, 3);
+    let route = grid
+        .route(p(0, 0), p(0, 2), &RouteConstraints::default())
+        .unwrap();
+    grid.commit(&route);
+    assert_eq!(grid.connections(p(0, 0)), CONNECT_DOWN);
+    assert_eq!(grid.connections(p(0, 1)), CONNECT_UP | CONNECT_DOWN);
+    assert_eq!(grid.connections(p(0, 2)), CONNECT_UP);
+}
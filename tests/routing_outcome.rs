@@ -0,0 +1,318 @@
+// ============================================================================
+// 走线结果（`AsciiRenderMetaEdge.outcome` / `reason`）与 `fail_on_unroutable` 回归测试
+//
+// 说明：
+// - 正常走通的边记为 `Routed`，`fail_on_unroutable` 打开时输出不变；
+// - `user_repro_case` 在 strict + Unicode 下有两条边 5 次布局尝试都走不通（不画出来），
+//   meta 里要记为 `Unroutable` + `StrictRules`，打开 `fail_on_unroutable` 时返回错误并列出这两条边；
+// - `FALLBACK`（relaxed + Unicode）有一条边要在最后一次布局尝试里放开约束才走通：`RoutedWithFallback` + `RelaxedRules`；
+// - 只避开节点时总能走通：全部 flowchart testdata 上，relaxed 没有 `Unroutable`，原因总与规则对应；
+// - JS 后端只能从 path 判断：兜底走通的边记为 `Routed`，原因总是 `None`，meta 的 `backend` 是 `Js`；
+//   `fail_on_unroutable` 把 flowchart/state 换到 native，meta 的 `backend` 跟着变成 `Native`；
+// - serde 形状：camelCase 字符串，旧 JSON（没有这两个字段）按 `Routed` 解析，没有 `backend` 的 meta 按 `Js` 解析。
+// ============================================================================
+
+#![cfg(feature = "native-backend")]
+
+use beautiful_mermaid_rs::{
+    AsciiRenderMeta, AsciiRenderMetaEdge, AsciiRenderOptions, AsciiRouteOutcome, AsciiRouteReason,
+    AsciiRouting, Backend, BeautifulMermaidError, render_mermaid_ascii,
+    render_mermaid_ascii_with_meta,
+};
+use std::fs;
+use std::path::Path;
+
+fn flowchart_meta(diagram: &str, options: &AsciiRenderOptions) -> AsciiRenderMeta {
+    let rendered = render_mermaid_ascii_with_meta(diagram, options).unwrap();
+    rendered
+        .meta
+        .as_flowchart()
+        .expect("flowchart meta")
+        .clone()
+}
+
+fn flowchart_edges(diagram: &str, options: &AsciiRenderOptions) -> Vec<AsciiRenderMetaEdge> {
+    flowchart_meta(diagram, options).edges
+}
+
+/// relaxed + Unicode 下 `N2 -> N1` 的每条候选路径都会与其他边的拐点 / 端点重合，
+/// 最后一次布局尝试里放开约束才走通（随机图里找到的最小例子）。
+const FALLBACK: &str = "graph LR
+  N4 --> N1
+  N3 --> N1
+  N2 --> N1
+  N4 --> N2
+";
+
+fn fallback_options(backend: Backend) -> AsciiRenderOptions {
+    AsciiRenderOptions {
+        use_ascii: Some(false),
+        routing: Some(AsciiRouting::Relaxed),
+        backend: Some(backend),
+        ..Default::default()
+    }
+}
+
+fn user_repro_case() -> String {
+    let raw = fs::read_to_string("tests/testdata/unicode/user_repro_case.txt")
+        .unwrap()
+        .replace("\r\n", "\n");
+    raw.split_once("\n---\n").unwrap().0.to_string()
+}
+
+#[test]
+fn routed_edges_are_reported_as_routed() {
+    let diagram = "flowchart TD
+  A --> B
+  A --> C
+  B --> D
+  C --> D
+  D -->|loop| A
+";
+    for routing in [AsciiRouting::Strict, AsciiRouting::Relaxed] {
+        for use_ascii in [true, false] {
+            let options = AsciiRenderOptions {
+                use_ascii: Some(use_ascii),
+                routing: Some(routing),
+                backend: Some(Backend::Native),
+                ..Default::default()
+            };
+            for edge in flowchart_edges(diagram, &options) {
+                assert_eq!(edge.outcome, AsciiRouteOutcome::Routed, "{edge:?}");
+                assert_eq!(edge.reason, None, "{edge:?}");
+            }
+
+            let strict_check = AsciiRenderOptions {
+                fail_on_unroutable: Some(true),
+                ..options.clone()
+            };
+            assert_eq!(
+                render_mermaid_ascii(diagram, &strict_check).unwrap(),
+                render_mermaid_ascii(diagram, &options).unwrap(),
+                "routing={routing:?}, use_ascii={use_ascii}"
+            );
+        }
+    }
+}
+
+#[test]
+fn unroutable_edges_are_reported_with_reason() {
+    let diagram = user_repro_case();
+    let options = AsciiRenderOptions {
+        use_ascii: Some(false),
+        routing: Some(AsciiRouting::Strict),
+        backend: Some(Backend::Native),
+        ..Default::default()
+    };
+    let unroutable: Vec<_> = flowchart_edges(&diagram, &options)
+        .into_iter()
+        .filter(|edge| edge.outcome != AsciiRouteOutcome::Routed)
+        .collect();
+    let names: Vec<_> = unroutable
+        .iter()
+        .map(|edge| (edge.from.as_str(), edge.to.as_str()))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            ("Hat_ralph", "Hat_experiment_runner"),
+            ("Hat_ralph", "Hat_experiment_integrator")
+        ]
+    );
+    for edge in &unroutable {
+        assert_eq!(edge.outcome, AsciiRouteOutcome::Unroutable, "{edge:?}");
+        assert_eq!(edge.reason, Some(AsciiRouteReason::StrictRules), "{edge:?}");
+        assert!(edge.path.is_empty(), "{edge:?}");
+    }
+
+    let err = render_mermaid_ascii(
+        &diagram,
+        &AsciiRenderOptions {
+            fail_on_unroutable: Some(true),
+            ..options
+        },
+    )
+    .expect_err("有边走不通时应当报错");
+    assert!(
+        matches!(err, BeautifulMermaidError::Unroutable { .. }),
+        "{err:?}"
+    );
+    let message = err.to_string();
+    assert!(
+        message.contains("Hat_ralph -> Hat_experiment_runner")
+            && message.contains("Hat_ralph -> Hat_experiment_integrator"),
+        "{message}"
+    );
+}
+
+#[test]
+fn fallback_edges_are_reported_as_routed_with_fallback() {
+    let options = fallback_options(Backend::Native);
+    let meta = flowchart_meta(FALLBACK, &options);
+    assert_eq!(meta.backend, Backend::Native);
+    let fallback: Vec<_> = meta
+        .edges
+        .iter()
+        .filter(|edge| edge.outcome != AsciiRouteOutcome::Routed)
+        .collect();
+    assert_eq!(fallback.len(), 1, "{:?}", meta.edges);
+    let edge = fallback[0];
+    assert_eq!((edge.from.as_str(), edge.to.as_str()), ("N2", "N1"));
+    assert_eq!(edge.outcome, AsciiRouteOutcome::RoutedWithFallback);
+    assert_eq!(edge.reason, Some(AsciiRouteReason::RelaxedRules));
+    assert!(!edge.path.is_empty(), "兜底走通的边照常画出来: {edge:?}");
+
+    let err = render_mermaid_ascii(
+        FALLBACK,
+        &AsciiRenderOptions {
+            fail_on_unroutable: Some(true),
+            ..options
+        },
+    )
+    .expect_err("兜底走通也应当报错");
+    let message = err.to_string();
+    assert!(
+        matches!(err, BeautifulMermaidError::Unroutable { .. })
+            && message.contains("N2 -> N1")
+            && message.contains("放开约束才走通"),
+        "{message}"
+    );
+}
+
+#[test]
+fn reasons_always_name_the_rules() {
+    // 只避开节点时总有路，所以 relaxed 不会留下走不通的边，原因也总是当前规则
+    for (dir, use_ascii) in [
+        ("tests/testdata/ascii", true),
+        ("tests/testdata/unicode", false),
+    ] {
+        let mut entries: Vec<_> = fs::read_dir(Path::new(dir))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        entries.sort();
+        for path in entries {
+            let raw = fs::read_to_string(&path).unwrap().replace("\r\n", "\n");
+            let diagram: String = raw
+                .split_once("\n---\n")
+                .unwrap()
+                .0
+                .lines()
+                .skip_while(|line| line.contains('='))
+                .map(|line| format!("{line}\n"))
+                .collect();
+            if !diagram.starts_with("graph ") && !diagram.starts_with("flowchart ") {
+                continue;
+            }
+            for (routing, reason) in [
+                (AsciiRouting::Strict, AsciiRouteReason::StrictRules),
+                (AsciiRouting::Relaxed, AsciiRouteReason::RelaxedRules),
+            ] {
+                let options = AsciiRenderOptions {
+                    use_ascii: Some(use_ascii),
+                    routing: Some(routing),
+                    backend: Some(Backend::Native),
+                    ..Default::default()
+                };
+                for edge in flowchart_edges(&diagram, &options) {
+                    let expected = match edge.outcome {
+                        AsciiRouteOutcome::Routed => None,
+                        AsciiRouteOutcome::Unroutable if routing == AsciiRouting::Relaxed => {
+                            panic!("relaxed 不应留下走不通的边: path={path:?}, edge={edge:?}")
+                        }
+                        _ => Some(reason),
+                    };
+                    assert_eq!(edge.reason, expected, "path={path:?}, edge={edge:?}");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "js-backend")]
+#[test]
+fn js_backend_reports_only_missing_paths() {
+    // 兜底走通的边在 JS 后端的 meta 里只是 `Routed`
+    let meta = flowchart_meta(FALLBACK, &fallback_options(Backend::Js));
+    assert_eq!(meta.backend, Backend::Js);
+    for edge in &meta.edges {
+        assert_eq!(edge.outcome, AsciiRouteOutcome::Routed, "{edge:?}");
+        assert_eq!(edge.reason, None, "{edge:?}");
+    }
+
+    // 走不通的边：`Unroutable`，没有原因
+    let options = AsciiRenderOptions {
+        use_ascii: Some(false),
+        routing: Some(AsciiRouting::Strict),
+        backend: Some(Backend::Js),
+        ..Default::default()
+    };
+    let edges = flowchart_edges(&user_repro_case(), &options);
+    let unroutable: Vec<_> = edges
+        .iter()
+        .filter(|edge| edge.outcome != AsciiRouteOutcome::Routed)
+        .collect();
+    assert_eq!(unroutable.len(), 2, "{unroutable:?}");
+    for edge in unroutable {
+        assert_eq!(edge.outcome, AsciiRouteOutcome::Unroutable, "{edge:?}");
+        assert_eq!(edge.reason, None, "{edge:?}");
+        assert!(edge.path.is_empty(), "{edge:?}");
+    }
+
+    // `fail_on_unroutable` 覆盖 `backend: Js`：meta 记实际走线的 native
+    let switched = AsciiRenderOptions {
+        fail_on_unroutable: Some(true),
+        ..fallback_options(Backend::Js)
+    };
+    assert!(render_mermaid_ascii_with_meta(FALLBACK, &switched).is_err());
+    let meta = flowchart_meta(
+        "graph LR\n  A --> B\n",
+        &AsciiRenderOptions {
+            fail_on_unroutable: Some(true),
+            backend: Some(Backend::Js),
+            ..Default::default()
+        },
+    );
+    assert_eq!(meta.backend, Backend::Native);
+}
+
+#[test]
+fn fail_on_unroutable_ignores_diagrams_without_routing() {
+    let diagram = "sequenceDiagram
+  Alice->>Bob: hello
+";
+    let options = AsciiRenderOptions {
+        fail_on_unroutable: Some(true),
+        ..Default::default()
+    };
+    assert_eq!(
+        render_mermaid_ascii(diagram, &options).unwrap(),
+        render_mermaid_ascii(diagram, &AsciiRenderOptions::default()).unwrap()
+    );
+}
+
+#[test]
+fn outcome_serde_shape() {
+    let edge: AsciiRenderMetaEdge =
+        serde_json::from_str(r#"{"from":"A","to":"B","label":"","path":[]}"#).unwrap();
+    assert_eq!(edge.outcome, AsciiRouteOutcome::Routed);
+    assert_eq!(edge.reason, None);
+
+    let edge = AsciiRenderMetaEdge {
+        outcome: AsciiRouteOutcome::RoutedWithFallback,
+        reason: Some(AsciiRouteReason::RelaxedRules),
+        ..edge
+    };
+    let json = serde_json::to_value(&edge).unwrap();
+    assert_eq!(json["outcome"], "routedWithFallback");
+    assert_eq!(json["reason"], "relaxedRules");
+
+    let meta: AsciiRenderMeta = serde_json::from_str(r#"{"nodes":[],"edges":[]}"#).unwrap();
+    assert_eq!(meta.backend, Backend::Js);
+    let json = serde_json::to_value(AsciiRenderMeta {
+        backend: Backend::Native,
+        ..meta
+    })
+    .unwrap();
+    assert_eq!(json["backend"], "native");
+}
@@ -0,0 +1,252 @@
+// ============================================================================
+// 走线统计（`RoutingGrid::last_stats` / `render_mermaid_ascii_with_stats`）回归测试
+//
+// 说明：
+// - 统计只是旁路记录：带统计渲染的字符画必须与 native `render_mermaid_ascii` 逐字节相同；
+// - 单次搜索：展开格子数 / 堆峰值 / 路径代价与 `Route` 一致，被 blocked / `┼` 规则拒绝的单步有计数；
+// - 按边汇总：走通的边有路径代价，复用平行边路径的边没有自己的搜索；
+// - 没有 A* 走线的图类型（sequence 等）统计为空。
+// ============================================================================
+
+#![cfg(feature = "native-backend")]
+
+use beautiful_mermaid_rs::routing::{GridPoint, RouteConstraints, RouteRules, RoutingGrid};
+use beautiful_mermaid_rs::{
+    AsciiRenderOptions, AsciiRouting, Backend, render_mermaid_ascii,
+    render_mermaid_ascii_with_stats,
+};
+use std::fs;
+use std::path::Path;
+
+fn p(x: u32, y: u32) -> GridPoint {
+    GridPoint::new(x, y)
+}
+
+fn options(use_ascii: bool, routing: AsciiRouting) -> AsciiRenderOptions {
+    AsciiRenderOptions {
+        use_ascii: Some(use_ascii),
+        routing: Some(routing),
+        backend: Some(Backend::Native),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn grid_records_last_search() {
+    // 7×5 网格，x=3 这一列只有最下面一格是通的。
+    let mut grid = RoutingGrid::new(7, 5);
+    grid.block_rect(p(3, 0), 1, 4);
+    let route = grid
+        .route(p(0, 0), p(6, 0), &RouteConstraints::default())
+        .expect("绕过障碍应当可达");
+    let stats = grid.last_stats();
+    assert_eq!(stats.cost, Some(route.cost));
+    assert!(
+        stats.expansions >= route.points.len() as u64 - 1,
+        "{stats:?}"
+    );
+    assert!(stats.heap_peak > 0, "{stats:?}");
+    assert!(
+        stats.rejections.blocked > 0,
+        "贴着障碍走必然被拒: {stats:?}"
+    );
+    assert_eq!(stats.rejections.total(), stats.rejections.blocked);
+
+    // 整列堵死：走不通。终点一侧只剩 3×5 格，封闭探测看完就判定走不通，A* 一格都不展开。
+    grid.block_rect(p(3, 4), 1, 1);
+    assert!(
+        grid.route(p(0, 0), p(6, 0), &RouteConstraints::default())
+            .is_none()
+    );
+    let stats = grid.last_stats();
+    assert_eq!(stats.cost, None);
+    assert_eq!(stats.expansions, 0, "{stats:?}");
+    assert_eq!(stats.probe_cells, 15, "{stats:?}");
+    assert!(stats.rejections.blocked > 0, "撞上那一列: {stats:?}");
+}
+
+#[test]
+fn turn_search_gives_up_after_its_budget() {
+    // 终点关在 12×12 的围墙里（内部 100 格，超出封闭探测的 64 格），墙外是 200×200 的空地：
+    // 走不通，只能由 A* 扫完墙外才知道。
+    let mut grid = RoutingGrid::new(200, 200);
+    for (origin, width, height) in [
+        (p(100, 100), 12, 1),
+        (p(100, 111), 12, 1),
+        (p(100, 101), 1, 10),
+        (p(111, 101), 1, 10),
+    ] {
+        grid.block_rect(origin, width, height);
+    }
+    let (from, to) = (p(0, 0), p(105, 105));
+
+    // 逐格搜索与 TS 一致，没有预算：墙外 39856 格全部展开
+    assert!(grid.route(from, to, &RouteConstraints::default()).is_none());
+    let stats = grid.last_stats();
+    assert_eq!(stats.expansions, 200 * 200 - 144, "{stats:?}");
+    assert!(!stats.budget_exhausted, "{stats:?}");
+
+    // 方向感知搜索的状态数是格子的五倍，展开 8192 个状态就放弃
+    let turns = RouteConstraints {
+        turn_penalty: 2,
+        ..Default::default()
+    };
+    assert!(grid.route(from, to, &turns).is_none());
+    let stats = grid.last_stats();
+    assert_eq!(stats.expansions, 8_192, "{stats:?}");
+    assert!(stats.budget_exhausted && stats.cost.is_none(), "{stats:?}");
+
+    // 墙上开个口：走得通的搜索远用不完预算
+    grid.set_blocked(p(105, 100), false);
+    let route = grid.route(from, to, &turns).expect("墙上有口");
+    let stats = grid.last_stats();
+    assert_eq!(stats.cost, Some(route.cost));
+    assert!(!stats.budget_exhausted, "{stats:?}");
+}
+
+#[test]
+fn grid_counts_strict_crossing_rejections() {
+    // 横线 + 从上方接到 (2,2) 的竖线形成 `┴`；从下方再接到 (2,2) 会补出 `┼`。
+    let strict = |source_id, target_id| RouteConstraints {
+        rules: RouteRules::Strict,
+        source_id,
+        target_id,
+        ..Default::default()
+    };
+    let mut grid = RoutingGrid::new(5, 5);
+    grid.set_blocked(p(0, 2), true);
+    grid.set_blocked(p(4, 2), true);
+    let wall = grid.route(p(0, 2), p(4, 2), &strict(1, 2)).unwrap();
+    grid.commit(&wall);
+    let stub = grid.route(p(2, 0), p(2, 2), &strict(3, 4)).unwrap();
+    grid.commit(&stub);
+
+    assert!(grid.route(p(2, 4), p(2, 2), &strict(5, 6)).is_none());
+    let stats = grid.last_stats();
+    assert_eq!(stats.cost, None);
+    assert!(
+        stats.rejections.crossing + stats.rejections.segment > 0,
+        "{stats:?}"
+    );
+
+    let relaxed = grid
+        .route(
+            p(2, 4),
+            p(2, 2),
+            &RouteConstraints {
+                rules: RouteRules::Relaxed,
+                ..strict(5, 6)
+            },
+        )
+        .expect("relaxed 允许交叉");
+    assert_eq!(grid.last_stats().cost, Some(relaxed.cost));
+}
+
+#[test]
+fn stats_do_not_change_output() {
+    for (dir, use_ascii) in [
+        ("tests/testdata/ascii", true),
+        ("tests/testdata/unicode", false),
+    ] {
+        let mut entries: Vec<_> = fs::read_dir(Path::new(dir))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        entries.sort();
+        for path in entries {
+            let raw = fs::read_to_string(&path).unwrap().replace("\r\n", "\n");
+            let diagram = raw.split_once("\n---\n").unwrap().0;
+            // 跳过 `paddingX=2` 之类的配置行
+            let diagram: String = diagram
+                .lines()
+                .skip_while(|line| line.contains('='))
+                .map(|line| format!("{line}\n"))
+                .collect();
+            if !diagram.starts_with("graph ") && !diagram.starts_with("flowchart ") {
+                continue;
+            }
+            for routing in [
+                AsciiRouting::Strict,
+                AsciiRouting::Relaxed,
+                AsciiRouting::Global,
+            ] {
+                let options = options(use_ascii, routing);
+                let rendered = render_mermaid_ascii_with_stats(&diagram, &options).unwrap();
+                assert_eq!(
+                    rendered.text,
+                    render_mermaid_ascii(&diagram, &options).unwrap(),
+                    "path={path:?}, routing={routing:?}"
+                );
+
+                let stats = &rendered.stats;
+                assert!(
+                    (1..=5).contains(&stats.layout_attempts),
+                    "path={path:?}, routing={routing:?}"
+                );
+                for edge in &stats.edges {
+                    assert!(edge.unreachable <= edge.searches, "{edge:?}");
+                    if !edge.routed {
+                        // 5 次布局尝试都没走通（strict 下偶有发生）：每次搜索都失败
+                        assert_eq!(edge.unreachable, edge.searches, "{edge:?}");
+                        assert_eq!(edge.path_cost, None, "{edge:?}");
+                    } else if edge.shared_path {
+                        assert_eq!(edge.searches, 0, "{edge:?}");
+                        assert_eq!(edge.path_cost, None, "{edge:?}");
+                    } else if edge.from != edge.to {
+                        assert!(edge.searches > edge.unreachable, "{edge:?}");
+                        assert!(edge.path_cost.is_some(), "{edge:?}");
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn edges_are_reported_in_declaration_order() {
+    let diagram = "flowchart LR
+  A --> B
+  A -->|again| B
+  B --> C
+  C --> C
+";
+    let rendered =
+        render_mermaid_ascii_with_stats(diagram, &options(false, AsciiRouting::Relaxed)).unwrap();
+    let edges = &rendered.stats.edges;
+    let names: Vec<_> = edges
+        .iter()
+        .map(|edge| (edge.from.as_str(), edge.to.as_str(), edge.label.as_str()))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            ("A", "B", ""),
+            ("A", "B", "again"),
+            ("B", "C", ""),
+            ("C", "C", "")
+        ]
+    );
+    assert_eq!(rendered.stats.layout_attempts, 1);
+
+    // relaxed + Unicode：同向平行边复用第一条的路径
+    assert!(!edges[0].shared_path && edges[0].searches > 0);
+    assert!(edges[1].shared_path && edges[1].searches == 0);
+    assert!(edges.iter().all(|edge| edge.routed && !edge.unconstrained));
+    let expansions: u64 = edges.iter().map(|edge| edge.expansions).sum();
+    assert!(expansions > 0);
+}
+
+#[test]
+fn diagrams_without_routing_have_empty_stats() {
+    let diagram = "sequenceDiagram
+  Alice->>Bob: hello
+";
+    let options = options(false, AsciiRouting::Relaxed);
+    let rendered = render_mermaid_ascii_with_stats(diagram, &options).unwrap();
+    assert_eq!(
+        rendered.text,
+        render_mermaid_ascii(diagram, &options).unwrap()
+    );
+    assert_eq!(rendered.stats.layout_attempts, 0);
+    assert!(rendered.stats.edges.is_empty());
+}
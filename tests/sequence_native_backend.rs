@@ -0,0 +1,184 @@
+// ============================================================================
+// `Backend::Native` sequence diagram 回归测试
+//
+// 说明：
+// - sequence 的布局是固定行高/列距的纯算术，native 按 JS 的运算顺序移植，
+//   所以 ASCII（text + meta）与 SVG 都要求与 JS 后端逐字节一致；
+// - SVG meta 的浮点数允许 1e-9 的误差：JS 侧的 meta 经 JSON 往返，
+//   `serde_json` 解析浮点时可能差最后一位；
+// - ASCII golden（tests/testdata 里的 seq_*）由 `ascii_native_backend.rs` 覆盖。
+// ============================================================================
+
+#![cfg(all(feature = "js-backend", feature = "native-backend"))]
+
+use beautiful_mermaid_rs::{
+    AsciiRenderOptions, Backend, RenderOptions, render_mermaid, render_mermaid_ascii,
+    render_mermaid_ascii_with_meta, render_mermaid_with_meta,
+};
+
+const PARITY_DIAGRAMS: &[&str] = &[
+    "sequenceDiagram\n  Alice->>Bob: Hello\n  Bob-->>Alice: Hi\n",
+    "sequenceDiagram\n  participant A as Alice\n  actor B as Bob\n  A->>B: request\n  A->>A: think\n  A-)B: async\n  B--)A: back\n  A-xB: cross\n  A--xB: dashed cross\n  A->B: plain\n  A-->B: dashed plain\n",
+    "sequenceDiagram\n  A->>+B: request\n  B->>+C: query\n  C-->>-B: rows\n  B-->>-A: response\n  A->>+B: left open\n",
+    "sequenceDiagram\n  loop Every minute\n    A->>B: ping\n  end\n  alt ok\n    B->>A: yes\n  else failure case with a long label\n    B->>A: no way this is a long message\n  end\n  opt maybe\n    C->>C: self\n  end\n  par one\n    A->>B: x\n  and two\n    A->>C: y\n  end\n",
+    "sequenceDiagram\n  critical crit\n    A->>B: c\n  option other\n    B->>A: d\n  end\n  break stop\n    A->>B: e\n  end\n  rect rgb(0,0,0)\n    A->>B: in rect\n  end\n",
+    "sequenceDiagram\n  A->>B: x\n  Note left of A: left note\n  Note right of B: right note with longer text here\n  Note over A,B: spanning\n  Note over B: over b\n",
+    "sequenceDiagram\n  Note left of VeryLongParticipantName: a very long note text that overflows to the left\n  VeryLongParticipantName->>B: x; B->>VeryLongParticipantName: y\n",
+    "sequenceDiagram\n  accTitle: Orders\n  accDescr: Order flow\n  Client->>Server: x & <y>\n  Server-->>Client: \"ok\"\n",
+    "sequenceDiagram\n  alt unbalanced\n    A->>B: never closed\n",
+];
+
+fn assert_close(native: &serde_json::Value, js: &serde_json::Value, path: &str) {
+    match (native, js) {
+        (serde_json::Value::Number(a), serde_json::Value::Number(b)) => {
+            let (a, b) = (a.as_f64().unwrap(), b.as_f64().unwrap());
+            assert!((a - b).abs() < 1e-9, "{path}: native={a} js={b}");
+        }
+        (serde_json::Value::Array(a), serde_json::Value::Array(b)) => {
+            assert_eq!(a.len(), b.len(), "{path}: 数组长度不一致");
+            for (index, (a, b)) in a.iter().zip(b).enumerate() {
+                assert_close(a, b, &format!("{path}[{index}]"));
+            }
+        }
+        (serde_json::Value::Object(a), serde_json::Value::Object(b)) => {
+            assert_eq!(
+                a.keys().collect::<Vec<_>>(),
+                b.keys().collect::<Vec<_>>(),
+                "{path}: 字段不一致"
+            );
+            for (key, value) in a {
+                assert_close(value, &b[key], &format!("{path}.{key}"));
+            }
+        }
+        _ => assert_eq!(native, js, "{path}"),
+    }
+}
+
+#[test]
+fn native_svg_matches_js() {
+    let option_sets = [
+        RenderOptions::default(),
+        RenderOptions {
+            bg: Some("#101010".to_string()),
+            fg: Some("#EEEEEE".to_string()),
+            accent: Some("#FF0066".to_string()),
+            font: Some("IBM Plex Sans".to_string()),
+            transparent: Some(true),
+            id_prefix: Some("seq-".to_string()),
+            title: Some("Custom title".to_string()),
+            ..Default::default()
+        },
+    ];
+    for diagram in PARITY_DIAGRAMS {
+        for js_options in &option_sets {
+            let native_options = RenderOptions {
+                backend: Some(Backend::Native),
+                ..js_options.clone()
+            };
+            let what = format!("diagram={diagram:?} id_prefix={:?}", js_options.id_prefix);
+
+            let js = render_mermaid(diagram, js_options)
+                .unwrap_or_else(|err| panic!("JS 渲染失败: {what}: {err}"));
+            let native = render_mermaid(diagram, &native_options)
+                .unwrap_or_else(|err| panic!("native 渲染失败: {what}: {err}"));
+            assert_eq!(native, js, "SVG 不一致: {what}");
+
+            let js = render_mermaid_with_meta(diagram, js_options)
+                .unwrap_or_else(|err| panic!("JS 渲染失败: {what}: {err}"));
+            let native = render_mermaid_with_meta(diagram, &native_options)
+                .unwrap_or_else(|err| panic!("native 渲染失败: {what}: {err}"));
+            assert_eq!(native.svg, js.svg, "meta 不能影响渲染: {what}");
+            assert_close(
+                &serde_json::to_value(native.view_box).unwrap(),
+                &serde_json::to_value(js.view_box).unwrap(),
+                "view_box",
+            );
+            assert_close(
+                &serde_json::to_value(&native.meta).unwrap(),
+                &serde_json::to_value(&js.meta).unwrap(),
+                "meta",
+            );
+        }
+    }
+}
+
+#[test]
+fn native_ascii_matches_js() {
+    for diagram in PARITY_DIAGRAMS {
+        for use_ascii in [true, false] {
+            let js_options = AsciiRenderOptions {
+                use_ascii: Some(use_ascii),
+                ..Default::default()
+            };
+            let native_options = AsciiRenderOptions {
+                backend: Some(Backend::Native),
+                ..js_options.clone()
+            };
+            let what = format!("diagram={diagram:?} use_ascii={use_ascii}");
+
+            let js = render_mermaid_ascii_with_meta(diagram, &js_options)
+                .unwrap_or_else(|err| panic!("JS 渲染失败: {what}: {err}"));
+            let native = render_mermaid_ascii_with_meta(diagram, &native_options)
+                .unwrap_or_else(|err| panic!("native 渲染失败: {what}: {err}"));
+            assert_eq!(native.text, js.text, "text 不一致: {what}");
+            assert_eq!(native.meta, js.meta, "meta 不一致: {what}");
+        }
+    }
+}
+
+#[test]
+fn native_svg_is_accessible() {
+    let diagram = "sequenceDiagram\n  participant A as Alice\n  A->>B: Hello\n  B-->>A: Hi\n";
+    let svg = render_mermaid(
+        diagram,
+        &RenderOptions {
+            backend: Some(Backend::Native),
+            ..Default::default()
+        },
+    )
+    .expect("native sequence 渲染应当成功");
+
+    let root = svg.lines().next().expect("svg root line");
+    assert!(
+        root.contains(r#"aria-roledescription="sequence diagram""#),
+        "root: {root}"
+    );
+    assert!(svg.contains(
+        "<desc id=\"desc\">Sequence diagram with 2 participants and 2 messages. \
+         Participants: Alice; B. Messages: Alice → B: Hello; B → Alice: Hi.</desc>"
+    ));
+    assert!(
+        svg.contains(r#"<g id="edge-A-B-0" class="edge" data-edge-from="A" data-edge-to="B">"#)
+    );
+    assert!(svg.contains(r#"<g id="node-A" class="node" data-node-id="A">"#));
+}
+
+#[test]
+fn native_renders_empty_diagram() {
+    let svg_options = RenderOptions {
+        backend: Some(Backend::Native),
+        ..Default::default()
+    };
+    let result =
+        render_mermaid_with_meta("sequenceDiagram\n", &svg_options).expect("空图也应能渲染");
+    assert!(result.svg.contains(r#"viewBox="0 0 0 0""#));
+    assert_eq!(result.view_box.width, 0.0);
+    assert_eq!(
+        result.svg,
+        render_mermaid("sequenceDiagram\n", &RenderOptions::default()).expect("JS 渲染失败")
+    );
+
+    let ascii = render_mermaid_ascii(
+        "sequenceDiagram\n",
+        &AsciiRenderOptions {
+            backend: Some(Backend::Native),
+            ..Default::default()
+        },
+    )
+    .expect("空图也应能渲染");
+    assert_eq!(
+        ascii,
+        render_mermaid_ascii("sequenceDiagram\n", &AsciiRenderOptions::default())
+            .expect("JS 渲染失败")
+    );
+}
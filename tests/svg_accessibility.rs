@@ -0,0 +1,128 @@
+// ============================================================================
+// SVG 无障碍（accessibility）测试
+//
+// 目的：
+// - `accTitle` / `accDescr` 指令输出为 `<title>` / `<desc>`，根节点带 role/aria；
+// - `RenderOptions.title/description` 优先于指令；
+// - 没有 `accDescr` 时自动生成“节点 + 边”的文字描述（给屏幕阅读器）；
+// - 指令本身不会被当成节点画出来（SVG 与 ASCII 都是）。
+// ============================================================================
+
+#![cfg(feature = "js-backend")]
+
+use beautiful_mermaid_rs::{
+    AsciiRenderOptions, RenderOptions, render_mermaid, render_mermaid_ascii,
+    render_mermaid_ascii_with_meta,
+};
+
+#[test]
+fn acc_directives_become_title_and_desc() {
+    let diagram = "graph TD\n  accTitle: Login <flow>\n  accDescr: How a user signs in\n  A[Start] --> B[Done]\n";
+    let svg = render_mermaid(diagram, &RenderOptions::default()).expect("SVG 渲染应当成功");
+
+    let root = svg.lines().next().expect("svg root line");
+    assert!(root.starts_with("<svg role=\"img\""), "root: {root}");
+    assert!(root.contains(r#"aria-roledescription="flowchart diagram""#));
+    assert!(root.contains(r#"aria-labelledby="title""#));
+    assert!(root.contains(r#"aria-describedby="desc""#));
+
+    assert!(svg.contains(r#"<title id="title">Login &lt;flow&gt;</title>"#));
+    assert!(svg.contains(r#"<desc id="desc">How a user signs in</desc>"#));
+    assert!(
+        !svg.contains("data-node-id=\"accTitle\"") && !svg.contains("data-node-id=\"accDescr\""),
+        "无障碍指令不应被渲染成节点"
+    );
+}
+
+#[test]
+fn options_override_directives_and_use_id_prefix() {
+    let diagram = "sequenceDiagram\n  accTitle: From diagram\n  A->>B: hi\n";
+    let options = RenderOptions {
+        title: Some("From options".to_string()),
+        description: Some("Custom description".to_string()),
+        id_prefix: Some("seq1-".to_string()),
+        ..Default::default()
+    };
+    let svg = render_mermaid(diagram, &options).expect("SVG 渲染应当成功");
+
+    assert!(svg.contains(r#"aria-labelledby="seq1-title""#));
+    assert!(svg.contains(r#"<title id="seq1-title">From options</title>"#));
+    assert!(svg.contains(r#"<desc id="seq1-desc">Custom description</desc>"#));
+    assert!(!svg.contains("From diagram"));
+}
+
+#[test]
+fn generated_description_lists_nodes_and_edges() {
+    let flowchart = render_mermaid(
+        "graph LR\n  A[Start] -->|go| B{Check}\n  B --> C[End]\n",
+        &RenderOptions::default(),
+    )
+    .expect("SVG 渲染应当成功");
+    // 没有 title 时用 desc 作为 label
+    assert!(flowchart.contains(r#"aria-labelledby="desc""#));
+    assert!(!flowchart.contains("<title"));
+    assert!(flowchart.contains(
+        "<desc id=\"desc\">Flowchart with 3 nodes and 2 edges. Nodes: Start; Check; End. \
+         Edges: Start → Check (go); Check → End.</desc>"
+    ));
+
+    let sequence = render_mermaid(
+        "sequenceDiagram\n  participant A as Alice\n  A->>B: Hello\n",
+        &RenderOptions::default(),
+    )
+    .expect("SVG 渲染应当成功");
+    assert!(sequence.contains(
+        "Sequence diagram with 2 participants and 1 message. Participants: Alice; B. \
+         Messages: Alice → B: Hello."
+    ));
+
+    let er = render_mermaid(
+        "erDiagram\n  CUSTOMER ||--o{ ORDER : places\n",
+        &RenderOptions::default(),
+    )
+    .expect("SVG 渲染应当成功");
+    assert!(er.contains(r#"aria-roledescription="entity relationship diagram""#));
+    assert!(er.contains("CUSTOMER → ORDER (places, one to zero-many)"));
+}
+
+#[test]
+fn multiline_acc_descr_block() {
+    let diagram = "classDiagram\n  accDescr {\n    Animals\n    and dogs\n  }\n  Animal <|-- Dog\n";
+    let svg = render_mermaid(diagram, &RenderOptions::default()).expect("SVG 渲染应当成功");
+    assert!(svg.contains("<desc id=\"desc\">Animals\nand dogs</desc>"));
+    assert!(!svg.contains("data-node-id=\"Animals\""));
+}
+
+#[test]
+fn ascii_ignores_acc_directives() {
+    let options = AsciiRenderOptions::default();
+    let pairs = [
+        (
+            "graph LR\n  A --> B\n",
+            "graph LR\n  accTitle: T\n  A --> B\n",
+        ),
+        (
+            "classDiagram\n  Animal <|-- Dog\n",
+            "classDiagram\n  accTitle: T\n  accDescr {\n    multi\n  }\n  Animal <|-- Dog\n",
+        ),
+        (
+            "erDiagram\n  A ||--o{ B : has\n",
+            "erDiagram\n  accDescr: D\n  A ||--o{ B : has\n",
+        ),
+    ];
+
+    for (plain, with_acc) in pairs {
+        assert_eq!(
+            render_mermaid_ascii(with_acc, &options).expect("ASCII 渲染应当成功"),
+            render_mermaid_ascii(plain, &options).expect("ASCII 渲染应当成功"),
+        );
+        assert_eq!(
+            render_mermaid_ascii_with_meta(with_acc, &options)
+                .expect("ASCII meta 渲染应当成功")
+                .meta,
+            render_mermaid_ascii_with_meta(plain, &options)
+                .expect("ASCII meta 渲染应当成功")
+                .meta,
+        );
+    }
+}
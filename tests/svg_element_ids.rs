@@ -0,0 +1,143 @@
+// ============================================================================
+// SVG 元素 id / data 属性测试
+//
+// 目的：
+// - 节点/边的 `<g>` 带稳定 id 与 `data-node-id` / `data-edge-from` / `data-edge-to`；
+// - Unicode 节点 ID（见 unicode_id_smoke.rs）转义后仍是合法、可预测的 id；
+// - `id_prefix` 能让同一页面上的多张图互不冲突（包括箭头 marker）；
+// - meta 里的 `element_id` 与 SVG 里的 id 一一对应（Rust 与 JS 的转义规则一致）。
+// ============================================================================
+
+#![cfg(feature = "js-backend")]
+
+use beautiful_mermaid_rs::{
+    RenderOptions, SvgDiagramMeta, render_mermaid, render_mermaid_with_meta,
+};
+use std::collections::HashSet;
+
+fn element_ids(svg: &str) -> Vec<String> {
+    svg.split(" id=\"")
+        .skip(1)
+        .filter_map(|rest| rest.split('"').next())
+        .map(str::to_string)
+        .collect()
+}
+
+fn meta_element_ids(meta: &SvgDiagramMeta) -> Vec<String> {
+    match meta {
+        SvgDiagramMeta::Flowchart(flow) => flow
+            .nodes
+            .iter()
+            .map(|n| n.element_id.clone())
+            .chain(flow.edges.iter().map(|e| e.element_id.clone()))
+            .collect(),
+        SvgDiagramMeta::Sequence(seq) => seq
+            .participants
+            .iter()
+            .map(|p| p.element_id.clone())
+            .chain(seq.messages.iter().map(|m| m.element_id.clone()))
+            .collect(),
+        SvgDiagramMeta::Class(class) => class
+            .classes
+            .iter()
+            .map(|c| c.element_id.clone())
+            .chain(class.relationships.iter().map(|r| r.element_id.clone()))
+            .collect(),
+        SvgDiagramMeta::Er(er) => er
+            .entities
+            .iter()
+            .map(|e| e.element_id.clone())
+            .chain(er.relationships.iter().map(|r| r.element_id.clone()))
+            .collect(),
+    }
+}
+
+#[test]
+fn flowchart_nodes_and_edges_have_stable_ids() {
+    let diagram = "graph LR\n  A --> B\n  A --> B\n  开始 --> my_node\n";
+    let svg = render_mermaid(diagram, &RenderOptions::default()).expect("SVG 渲染应当成功");
+
+    assert!(svg.contains(r#"<g id="node-A" class="node" data-node-id="A">"#));
+    assert!(svg.contains(r#"class="edge" data-edge-from="A" data-edge-to="B""#));
+    assert!(svg.contains(r#"id="edge-A-B-0""#));
+
+    // Unicode / 下划线：转义成 `_{码点}_` / `__`，data 属性保留原始 id
+    assert!(svg.contains(r#"<g id="node-_5f00__59cb_" class="node" data-node-id="开始">"#));
+    assert!(svg.contains(r#"<g id="node-my__node" class="node" data-node-id="my_node">"#));
+
+    // 同一份输入多次渲染，id 完全一致
+    let again = render_mermaid(diagram, &RenderOptions::default()).expect("SVG 渲染应当成功");
+    assert_eq!(element_ids(&svg), element_ids(&again));
+
+    let ids = element_ids(&svg);
+    let unique: HashSet<_> = ids.iter().collect();
+    assert_eq!(ids.len(), unique.len(), "SVG 内 id 不应重复: {ids:?}");
+}
+
+#[test]
+fn id_prefix_keeps_multiple_diagrams_apart() {
+    let diagram = "graph TD\n  A --> B\n";
+    let render = |prefix: &str| {
+        render_mermaid(
+            diagram,
+            &RenderOptions {
+                id_prefix: Some(prefix.to_string()),
+                ..Default::default()
+            },
+        )
+        .expect("SVG 渲染应当成功")
+    };
+
+    let first = render("d1-");
+    let second = render("d2-");
+
+    assert!(first.contains(r#"id="d1-node-A""#));
+    assert!(first.contains(r#"<marker id="d1-arrowhead""#));
+    assert!(first.contains(r#"marker-end="url(#d1-arrowhead)""#));
+
+    let first_ids: HashSet<_> = element_ids(&first).into_iter().collect();
+    let second_ids: HashSet<_> = element_ids(&second).into_iter().collect();
+    assert!(
+        first_ids.is_disjoint(&second_ids),
+        "不同前缀的两张图不应有相同 id"
+    );
+
+    // 默认（无前缀）保持原来的 marker id
+    let plain = render_mermaid(diagram, &RenderOptions::default()).expect("SVG 渲染应当成功");
+    assert!(plain.contains(r#"<marker id="arrowhead""#));
+}
+
+#[test]
+fn meta_element_ids_match_svg_for_all_diagram_types() {
+    let diagrams = [
+        "graph TD\n  开始 --> B\n  B --> C\n  B --> C\n",
+        "sequenceDiagram\n  participant 甲\n  甲->>B: hi\n  B-->>甲: ok\n  B->>B: self\n",
+        "classDiagram\n  Animal <|-- Dog : extends\n  Animal <|-- Cat\n",
+        "erDiagram\n  CUSTOMER ||--o{ ORDER : places\n  ORDER ||--|{ LINE_ITEM : contains\n",
+    ];
+    let options = RenderOptions {
+        id_prefix: Some("chart-".to_string()),
+        ..Default::default()
+    };
+
+    for diagram in diagrams {
+        let result = render_mermaid_with_meta(diagram, &options).expect("渲染应当成功");
+        let svg_ids: HashSet<_> = element_ids(&result.svg).into_iter().collect();
+        let meta_ids = meta_element_ids(&result.meta);
+
+        assert!(!meta_ids.is_empty(), "{diagram}: meta 应有元素");
+        for id in &meta_ids {
+            assert!(id.starts_with("chart-"), "{diagram}: id 应带前缀: {id}");
+            assert!(
+                svg_ids.contains(id),
+                "{diagram}: meta element_id {id} 应出现在 SVG 中"
+            );
+        }
+        let unique: HashSet<_> = meta_ids.iter().collect();
+        assert_eq!(
+            unique.len(),
+            meta_ids.len(),
+            "{diagram}: element_id 不应重复"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+// ============================================================================
+// SVG meta API smoke test
+//
+// 目的：
+// - 确认 `render_mermaid_with_meta` 的 svg 与 `render_mermaid` 完全一致；
+// - 确认 meta 的像素坐标能在 SVG 里找到对应元素（node rect、edge polyline、viewBox）；
+// - 四类图（flowchart/sequence/class/ER）都能拿到非空 meta。
+// ============================================================================
+
+#![cfg(feature = "js-backend")]
+
+use beautiful_mermaid_rs::{
+    RenderOptions, SvgDiagramMeta, render_mermaid, render_mermaid_with_meta,
+};
+
+#[test]
+fn flowchart_meta_matches_svg_elements() {
+    let diagram = "graph TD\n  A[Start] -->|go| B{Check}\n  B --> C((End))\n  subgraph S [Group]\n    C\n  end\n";
+    let options = RenderOptions::default();
+
+    let plain = render_mermaid(diagram, &options).expect("render_mermaid should work");
+    let result =
+        render_mermaid_with_meta(diagram, &options).expect("render_mermaid_with_meta should work");
+
+    // svg 必须与旧 API 一致（meta 不能影响渲染）。
+    assert_eq!(result.svg, plain);
+
+    let view_box = format!(
+        "viewBox=\"0 0 {} {}\"",
+        result.view_box.width, result.view_box.height
+    );
+    assert!(
+        result.svg.contains(&view_box),
+        "viewBox should match: {view_box}"
+    );
+
+    let meta = result.meta.as_flowchart().expect("flowchart meta");
+    assert_eq!(meta.nodes.len(), 3);
+    assert_eq!(meta.groups.len(), 1);
+    assert_eq!(meta.groups[0].label, "Group");
+
+    let start = meta.nodes.iter().find(|n| n.id == "A").expect("node A");
+    assert_eq!(start.shape, "rectangle");
+    let rect = format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"",
+        start.box_rect.x, start.box_rect.y, start.box_rect.width, start.box_rect.height
+    );
+    assert!(
+        result.svg.contains(&rect),
+        "node A rect should be in svg: {rect}"
+    );
+
+    let edge = meta
+        .edges
+        .iter()
+        .find(|e| e.from == "A" && e.to == "B")
+        .expect("edge A->B");
+    assert_eq!(edge.label.as_deref(), Some("go"));
+    assert!(edge.has_arrow_end);
+    let points = edge
+        .points
+        .iter()
+        .map(|p| format!("{},{}", p.x, p.y))
+        .collect::<Vec<_>>()
+        .join(" ");
+    assert!(
+        result.svg.contains(&format!("points=\"{points}\"")),
+        "edge polyline should be in svg: {points}"
+    );
+
+    let label = edge.label_position.expect("labelled edge has a position");
+    assert!(
+        result
+            .svg
+            .contains(&format!("<text x=\"{}\" y=\"{}\"", label.x, label.y)),
+        "edge label position should match svg text"
+    );
+}
+
+#[test]
+fn non_flowchart_diagrams_have_meta() {
+    let options = RenderOptions::default();
+
+    let sequence = render_mermaid_with_meta(
+        "sequenceDiagram\n  participant A as Alice\n  A->>+B: Hi\n  B-->>-A: ok\n  B->>B: self\n",
+        &options,
+    )
+    .expect("sequence should render");
+    let SvgDiagramMeta::Sequence(seq) = &sequence.meta else {
+        panic!("expected sequence meta");
+    };
+    assert_eq!(seq.participants.len(), 2);
+    assert_eq!(seq.participants[0].label, "Alice");
+    assert_eq!(seq.messages.len(), 3);
+    assert_eq!(seq.messages[2].points.len(), 4, "self message is a loop");
+    assert_eq!(seq.activations.len(), 1);
+    let alice = &seq.participants[0].box_rect;
+    assert!(
+        sequence.svg.contains(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"",
+            alice.x, alice.y, alice.width, alice.height
+        )),
+        "participant box should be in svg"
+    );
+
+    let class = render_mermaid_with_meta(
+        "classDiagram\n  Animal <|-- Dog : extends\n  class Dog {\n    +String name\n  }\n",
+        &options,
+    )
+    .expect("class should render");
+    let class_meta = class.meta.as_class().expect("class meta");
+    assert_eq!(class_meta.classes.len(), 2);
+    assert_eq!(class_meta.relationships[0].relationship_type, "inheritance");
+    assert!(class_meta.relationships[0].label_position.is_some());
+
+    let er = render_mermaid_with_meta("erDiagram\n  CUSTOMER ||--o{ ORDER : places\n", &options)
+        .expect("er should render");
+    let er_meta = er.meta.as_er().expect("er meta");
+    assert_eq!(er_meta.entities.len(), 2);
+    assert_eq!(er_meta.relationships[0].from_cardinality, "one");
+    assert_eq!(er_meta.relationships[0].to_cardinality, "zero-many");
+    assert!(er_meta.relationships[0].points.len() >= 2);
+}
+
+#[test]
+fn empty_subgraph_has_no_group_meta() {
+    // 空 subgraph 在 JS 布局里没有坐标（x/y 是 NaN），meta 里跳过它，其余照常
+    let rendered = render_mermaid_with_meta(
+        "graph LR\nsubgraph empty\nend\nsubgraph outer\nA --> B\nend\nC --> A\n",
+        &RenderOptions::default(),
+    )
+    .expect("empty subgraph should render");
+    let flow = rendered.meta.as_flowchart().expect("flowchart meta");
+    assert_eq!(flow.nodes.len(), 3);
+    assert_eq!(flow.groups.len(), 1);
+    assert_eq!(flow.groups[0].id, "outer");
+    assert!(flow.groups[0].children.is_empty());
+}
@@ -0,0 +1,254 @@
+// ============================================================================
+// `Backend::Native` SVG（纯 Rust flowchart/state 渲染）回归测试
+//
+// 说明：
+// - native SVG 的布局是 Rust 自己算的，坐标不与 JS（dagre）逐字节对齐；
+//   这里只锁定“结构”：根节点属性、CSS 变量、箭头 marker、元素 id、无障碍信息；
+// - 几何上检查不变量：节点互不重叠、簇成员在框内、非成员不压框、边都是正交折线；
+// - meta 必须与 SVG 一致（`render_mermaid_with_meta` 的 svg == `render_mermaid`）；
+// - sequence 见 `sequence_native_backend.rs`，class/ER 见 `class_er_svg_native_backend.rs`。
+// ============================================================================
+
+#![cfg(all(feature = "js-backend", feature = "native-backend"))]
+
+use beautiful_mermaid_rs::{
+    Backend, RenderOptions, SvgDiagramMeta, SvgRect, render_mermaid, render_mermaid_with_meta,
+};
+
+fn native() -> RenderOptions {
+    RenderOptions {
+        backend: Some(Backend::Native),
+        ..Default::default()
+    }
+}
+
+fn overlaps(a: &SvgRect, b: &SvgRect) -> bool {
+    a.x < b.x + b.width && b.x < a.x + a.width && a.y < b.y + b.height && b.y < a.y + a.height
+}
+
+fn contains(outer: &SvgRect, inner: &SvgRect) -> bool {
+    outer.x <= inner.x
+        && outer.y <= inner.y
+        && inner.x + inner.width <= outer.x + outer.width
+        && inner.y + inner.height <= outer.y + outer.height
+}
+
+#[test]
+fn native_svg_has_same_structure_as_js() {
+    let diagram = "graph TD\n  A[Start] -->|go| B{Check}\n  B -.-> C((End))\n  B ==> D[(Store)]\n";
+    let svg = render_mermaid(diagram, &native()).expect("native SVG 渲染应当成功");
+
+    let root = svg.lines().next().expect("svg root line");
+    assert!(root.starts_with("<svg role=\"img\""), "root: {root}");
+    assert!(root.contains("--bg:#FFFFFF;--fg:#27272A"), "root: {root}");
+    assert!(svg.contains("--_node-fill:"), "应输出派生 CSS 变量");
+    assert!(svg.contains("<marker id=\"arrowhead\""));
+    assert!(svg.contains("<marker id=\"arrowhead-start\""));
+
+    for id in ["node-A", "node-B", "node-C", "node-D"] {
+        assert!(svg.contains(&format!("<g id=\"{id}\"")), "缺少 {id}");
+    }
+    assert!(
+        svg.contains(r#"<g id="edge-A-B-0" class="edge" data-edge-from="A" data-edge-to="B">"#)
+    );
+    assert!(svg.contains(r#"<g id="edge-A-B-0-label" class="edge-label""#));
+    assert!(svg.contains("stroke-dasharray"), "虚线边应带 dasharray");
+    assert!(svg.contains("<circle "), "圆形节点应输出 circle");
+    assert!(svg.trim_end().ends_with("</svg>"));
+}
+
+#[test]
+fn native_meta_matches_svg() {
+    let diagram = "graph LR\n  subgraph S [Group]\n    A --> B\n  end\n  B -->|yes| C\n  C --> A\n";
+    let options = native();
+
+    let plain = render_mermaid(diagram, &options).expect("render_mermaid should work");
+    let result =
+        render_mermaid_with_meta(diagram, &options).expect("render_mermaid_with_meta should work");
+    assert_eq!(result.svg, plain, "meta 不能影响渲染");
+
+    let view_box = format!(
+        "viewBox=\"0 0 {} {}\"",
+        result.view_box.width, result.view_box.height
+    );
+    assert!(
+        result.svg.contains(&view_box),
+        "viewBox should match: {view_box}"
+    );
+
+    let SvgDiagramMeta::Flowchart(meta) = &result.meta else {
+        panic!("应为 flowchart meta");
+    };
+    assert_eq!(meta.nodes.len(), 3);
+    assert_eq!(meta.edges.len(), 3);
+    assert_eq!(meta.groups.len(), 1);
+
+    for node in &meta.nodes {
+        assert!(
+            result
+                .svg
+                .contains(&format!("<g id=\"{}\"", node.element_id)),
+            "node {} 的 element_id 应出现在 SVG 中",
+            node.id
+        );
+    }
+    for edge in &meta.edges {
+        let points = edge
+            .points
+            .iter()
+            .map(|p| format!("{},{}", p.x, p.y))
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert!(
+            result.svg.contains(&format!("points=\"{points}\"")),
+            "edge polyline should be in svg: {points}"
+        );
+        for pair in edge.points.windows(2) {
+            assert!(
+                pair[0].x == pair[1].x || pair[0].y == pair[1].y,
+                "边 {}->{} 应只有水平/竖直线段",
+                edge.from,
+                edge.to
+            );
+        }
+    }
+}
+
+#[test]
+fn native_layout_keeps_nodes_and_groups_apart() {
+    let diagram = "graph TD
+  subgraph frontend [Frontend]
+    ui[Web UI] --> api_client[API Client]
+  end
+  subgraph backend [Backend]
+    api[API] --> db[(Database)]
+    api --> cache[(Cache)]
+  end
+  api_client -->|HTTP| api
+  worker[Worker] --> db
+  worker --> worker
+";
+    let result = render_mermaid_with_meta(diagram, &native()).expect("native 渲染应当成功");
+    let SvgDiagramMeta::Flowchart(meta) = &result.meta else {
+        panic!("应为 flowchart meta");
+    };
+
+    for (i, a) in meta.nodes.iter().enumerate() {
+        for b in &meta.nodes[i + 1..] {
+            assert!(
+                !overlaps(&a.box_rect, &b.box_rect),
+                "{} 与 {} 重叠",
+                a.id,
+                b.id
+            );
+        }
+    }
+    assert_eq!(meta.groups.len(), 2);
+    assert!(!overlaps(
+        &meta.groups[0].box_rect,
+        &meta.groups[1].box_rect
+    ));
+
+    let members = [
+        ("frontend", ["ui", "api_client", ""]),
+        ("backend", ["api", "db", "cache"]),
+    ];
+    for (group_id, ids) in members {
+        let group = meta
+            .groups
+            .iter()
+            .find(|g| g.id == group_id)
+            .expect("group");
+        for node in &meta.nodes {
+            if ids.contains(&node.id.as_str()) {
+                assert!(
+                    contains(&group.box_rect, &node.box_rect),
+                    "{} 应在 {group_id} 内",
+                    node.id
+                );
+            } else {
+                assert!(
+                    !overlaps(&group.box_rect, &node.box_rect),
+                    "{} 不应压在 {group_id} 上",
+                    node.id
+                );
+            }
+        }
+    }
+
+    let self_loop = meta
+        .edges
+        .iter()
+        .find(|e| e.from == "worker" && e.to == "worker")
+        .expect("self loop");
+    assert!(self_loop.points.len() >= 4, "自环应绕出节点外");
+}
+
+#[test]
+fn native_svg_is_accessible() {
+    let diagram = "stateDiagram-v2\n  accTitle: Door\n  [*] --> Closed\n  Closed --> Open : push\n  Open --> [*]\n";
+    let svg = render_mermaid(diagram, &native()).expect("native state 渲染应当成功");
+
+    let root = svg.lines().next().expect("svg root line");
+    assert!(
+        root.contains(r#"aria-roledescription="state diagram""#),
+        "root: {root}"
+    );
+    assert!(svg.contains(r#"<title id="title">Door</title>"#));
+    assert!(
+        svg.contains("<desc id=\"desc\">State diagram"),
+        "应自动生成描述"
+    );
+
+    let options = RenderOptions {
+        id_prefix: Some("door-".to_string()),
+        description: Some("Custom".to_string()),
+        ..native()
+    };
+    let svg = render_mermaid(diagram, &options).expect("native state 渲染应当成功");
+    assert!(svg.contains(r#"<desc id="door-desc">Custom</desc>"#));
+    assert!(svg.contains(r#"<marker id="door-arrowhead""#));
+}
+
+#[test]
+fn parallel_edges_collapse_like_dagre() {
+    // dagre 不是 multigraph：端点相同的边（含经 subgraph 换算后相同的）只画最后一条
+    for diagram in [
+        "graph TD\n  A -->|x| B\n  A -->|y| B\n  B --> A\n",
+        "graph TD\n  subgraph one\n    A1 --> A2\n  end\n  subgraph two\n    B1 --> B2\n  end\n  one --> two\n  A2 --> B1\n",
+    ] {
+        let native = render_mermaid_with_meta(diagram, &native()).expect("native SVG 渲染应当成功");
+        let js =
+            render_mermaid_with_meta(diagram, &RenderOptions::default()).expect("JS 渲染应当成功");
+        let edges = |meta: &SvgDiagramMeta| -> Vec<(String, String, Option<String>, String)> {
+            meta.as_flowchart()
+                .expect("flowchart meta")
+                .edges
+                .iter()
+                .map(|e| {
+                    (
+                        e.from.clone(),
+                        e.to.clone(),
+                        e.label.clone(),
+                        e.element_id.clone(),
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(edges(&native.meta), edges(&js.meta), "{diagram}");
+        assert_eq!(
+            native.svg.matches("<polyline").count(),
+            js.svg.matches("<polyline").count(),
+            "{diagram}"
+        );
+    }
+}
+
+#[test]
+fn native_backend_reports_parse_errors() {
+    let err = render_mermaid("graph XY\n  A --> B\n", &native()).expect_err("非法方向应报错");
+    assert!(
+        err.to_string().contains("Invalid mermaid header"),
+        "err: {err}"
+    );
+}
@@ -6,6 +6,8 @@
 // - 顺便验证 bg/fg 等选项能正确写入 SVG 的 CSS variables
 // ============================================================================
 
+#![cfg(feature = "js-backend")]
+
 use beautiful_mermaid_rs::{RenderOptions, render_mermaid};
 
 #[test]
@@ -0,0 +1,160 @@
+// ============================================================================
+// native pathfinder 与纯 JS A* 的逐字节对照（TS 兼容模式）
+//
+// 说明：
+// - JS 后端默认把 `__bm_getPath*` 交给 native A*；`EngineConfig::native_pathfinder(false)` 时
+//   bundle 用自己的 A*（即 TS 原版）。两者在每个 testdata 文件上必须输出相同；
+// - 引擎按线程配置：纯 JS 一侧放到新线程里，先 `set_js_engine_config` 再渲染；
+// - 纯 JS A* 在 QuickJS 里很慢：默认跳过 `SLOW`，其余 testdata 在 strict 与 relaxed 下各对照一遍；
+// - 全量（含 `SLOW`）标了 `#[ignore]`，由 CI 的 `pathfinder-parity` job（`.github/workflows/ci.yml`）
+//   跑 `cargo test --release --test ts_compatible_pathfinder -- --ignored`，本地用 `make pathfinder-parity`。
+// ============================================================================
+
+#![cfg(feature = "js-backend")]
+
+use beautiful_mermaid_rs::{
+    AsciiRenderOptions, AsciiRouting, Backend, EngineConfig, render_mermaid_ascii,
+    set_js_engine_config,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 纯 JS A* 下要跑几秒到几分钟（release）的用例。
+const SLOW: &[&str] = &[
+    "ascii/preserve_order_of_definition.txt",
+    "unicode/ampersand_lhs_and_rhs.txt",
+    "unicode/preserve_order_of_definition.txt",
+    "unicode/user_repro_case.txt",
+];
+
+/// (相对路径, Mermaid 文本, use_ascii)；跳过 `paddingX=2` 之类的配置行。
+fn testdata() -> Vec<(String, String, bool)> {
+    let mut cases = Vec::new();
+    for (dir, use_ascii) in [("ascii", true), ("unicode", false)] {
+        let mut entries: Vec<PathBuf> = fs::read_dir(Path::new("tests/testdata").join(dir))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        entries.sort();
+        for path in entries {
+            let raw = fs::read_to_string(&path).unwrap().replace("\r\n", "\n");
+            let diagram: String = raw
+                .split_once("\n---\n")
+                .unwrap()
+                .0
+                .lines()
+                .skip_while(|line| line.contains('='))
+                .map(|line| format!("{line}\n"))
+                .collect();
+            let name = format!("{dir}/{}", path.file_name().unwrap().to_string_lossy());
+            cases.push((name, diagram, use_ascii));
+        }
+    }
+    cases
+}
+
+fn render_all(cases: &[(String, String, AsciiRenderOptions)]) -> Vec<String> {
+    cases
+        .iter()
+        .map(|(name, diagram, options)| {
+            render_mermaid_ascii(diagram, options).unwrap_or_else(|err| format!("{name}: {err}"))
+        })
+        .collect()
+}
+
+/// 当前线程（native pathfinder）与新线程（纯 JS A*）各渲染一遍，逐个对比。
+fn assert_matches_pure_js(cases: Vec<(String, String, AsciiRenderOptions)>) {
+    set_js_engine_config(&EngineConfig::new().native_pathfinder(true)).unwrap();
+    let native = render_all(&cases);
+
+    let (cases, pure_js) = std::thread::spawn(move || {
+        set_js_engine_config(&EngineConfig::new().native_pathfinder(false)).unwrap();
+        let pure_js = render_all(&cases);
+        (cases, pure_js)
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(native.len(), pure_js.len());
+    for ((name, _, options), (native, pure_js)) in cases.iter().zip(native.iter().zip(&pure_js)) {
+        assert_eq!(
+            native, pure_js,
+            "{name}, routing={:?}: native pathfinder 与纯 JS A* 不一致",
+            options.routing
+        );
+    }
+}
+
+fn js_options(use_ascii: bool) -> AsciiRenderOptions {
+    AsciiRenderOptions {
+        use_ascii: Some(use_ascii),
+        backend: Some(Backend::Js),
+        ..Default::default()
+    }
+}
+
+/// 每个用例在 strict 与 relaxed 下各一份。
+fn both_routings(
+    cases: impl IntoIterator<Item = (String, String, bool)>,
+) -> Vec<(String, String, AsciiRenderOptions)> {
+    let mut out = Vec::new();
+    for (name, diagram, use_ascii) in cases {
+        for routing in [AsciiRouting::Strict, AsciiRouting::Relaxed] {
+            let options = AsciiRenderOptions {
+                routing: Some(routing),
+                ..js_options(use_ascii)
+            };
+            out.push((name.clone(), diagram.clone(), options));
+        }
+    }
+    out
+}
+
+#[test]
+fn native_pathfinder_matches_pure_js_on_small_testdata() {
+    let cases = testdata()
+        .into_iter()
+        .filter(|(name, _, _)| !SLOW.contains(&name.as_str()));
+    assert_matches_pure_js(both_routings(cases));
+}
+
+#[test]
+#[ignore = "纯 JS A* 很慢（release 下约 8 分钟）：CI 的 pathfinder-parity job 用 --ignored 运行"]
+fn native_pathfinder_matches_pure_js_on_all_testdata() {
+    assert_matches_pure_js(both_routings(testdata()));
+}
+
+#[test]
+fn ts_compatible_pins_rust_only_pathfinder_options() {
+    let mut changed = 0;
+    for (name, diagram, use_ascii) in testdata() {
+        let mut backends = vec![Backend::Js];
+        if cfg!(feature = "native-backend") {
+            backends.push(Backend::Native);
+        }
+        for backend in backends {
+            let baseline = AsciiRenderOptions {
+                backend: Some(backend),
+                ..js_options(use_ascii)
+            };
+            let tuned = AsciiRenderOptions {
+                turn_penalty: Some(4),
+                ..baseline.clone()
+            };
+            let pinned = AsciiRenderOptions {
+                ts_compatible: Some(true),
+                ..tuned.clone()
+            };
+            let expected = render_mermaid_ascii(&diagram, &baseline).unwrap();
+            assert_eq!(
+                render_mermaid_ascii(&diagram, &pinned).unwrap(),
+                expected,
+                "{name}, backend={backend:?}"
+            );
+            if render_mermaid_ascii(&diagram, &tuned).unwrap() != expected {
+                changed += 1;
+            }
+        }
+    }
+    assert!(changed > 0, "turn_penalty 应当至少改变一个用例的走线");
+}
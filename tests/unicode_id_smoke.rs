@@ -6,6 +6,8 @@
 // - 这是 Rust 侧的“端到端验证”：最终跑的是 vendor 的 JS bundle，而不是 TS 源码
 // ============================================================================
 
+#![cfg(feature = "js-backend")]
+
 use beautiful_mermaid_rs::{
     AsciiRenderOptions, RenderOptions, render_mermaid, render_mermaid_ascii,
 };
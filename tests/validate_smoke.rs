@@ -6,6 +6,8 @@
 // - 避免未来改动 validator 后端/规则时把校验能力悄悄弄坏
 // ============================================================================
 
+#![cfg(feature = "validate")]
+
 use beautiful_mermaid_rs::validate_mermaid;
 
 #[test]
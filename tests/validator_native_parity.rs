@@ -0,0 +1,157 @@
+// ============================================================================
+// `validate_mermaid` 与 `Backend::Native` 对照：接受与拒绝同样的输入
+//
+// 背景：
+// - validator 用 selkie parser，native 渲染器用自己的 parser（与 JS 同构）；
+// - native 渲染先过 selkie（`native::check_syntax`），`validate_mermaid` 再跑一遍 native parser，
+//   两边对“什么是合法输入”的判断必须一致：validator 说有效 <=> native 渲染不报 `Parse`。
+// - 语料：testdata 里的全部图 + 每种图类型一组有效 / 无效的片段。
+// ============================================================================
+
+#![cfg(all(feature = "validate", feature = "native-backend"))]
+
+use beautiful_mermaid_rs::{
+    AsciiRenderOptions, Backend, BeautifulMermaidError, RenderOptions, render_mermaid,
+    render_mermaid_ascii, validate_mermaid,
+};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// native 渲染是否接受：解析失败必须是 `Parse`，不能是别的错误。
+fn accepted<T>(name: &str, output: &str, result: Result<T, BeautifulMermaidError>) -> bool {
+    match result {
+        Ok(_) => true,
+        Err(BeautifulMermaidError::Parse { .. }) => false,
+        Err(err) => panic!("{name} ({output}): 应当是 Parse 错误: {err:?}"),
+    }
+}
+
+/// 断言 validator 与 native 的 ASCII / SVG 渲染结论一致，返回 validator 的结论。
+fn assert_agree(name: &str, diagram: &str) -> bool {
+    let validation = validate_mermaid(diagram).expect("validate_mermaid 不返回 Err");
+    let ascii = render_mermaid_ascii(
+        diagram,
+        &AsciiRenderOptions {
+            backend: Some(Backend::Native),
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        accepted(name, "ASCII", ascii),
+        validation.is_valid,
+        "{name}: validator 与 native ASCII 结论不同: {validation:?}\n{diagram}"
+    );
+    let svg = render_mermaid(
+        diagram,
+        &RenderOptions {
+            backend: Some(Backend::Native),
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        accepted(name, "SVG", svg),
+        validation.is_valid,
+        "{name}: validator 与 native SVG 结论不同: {validation:?}\n{diagram}"
+    );
+    if !validation.is_valid {
+        assert!(
+            validation
+                .error
+                .is_some_and(|error| !error.trim().is_empty()),
+            "{name}: 无效输入要带错误信息"
+        );
+    }
+    validation.is_valid
+}
+
+/// testdata 里的全部图（ascii/unicode 里重复的只跑一次）。
+fn testdata_diagrams() -> Vec<(String, String)> {
+    let mut seen = BTreeSet::new();
+    let mut diagrams = Vec::new();
+    for dir in ["ascii", "unicode"] {
+        let mut entries: Vec<_> = fs::read_dir(Path::new("tests/testdata").join(dir))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        entries.sort();
+        for path in entries {
+            let raw = fs::read_to_string(&path).unwrap().replace("\r\n", "\n");
+            let diagram: String = raw
+                .split_once("\n---\n")
+                .unwrap()
+                .0
+                .lines()
+                .skip_while(|line| line.contains('=') && !line.contains("--"))
+                .map(|line| format!("{line}\n"))
+                .collect();
+            if seen.insert(diagram.clone()) {
+                diagrams.push((path.display().to_string(), diagram));
+            }
+        }
+    }
+    diagrams
+}
+
+#[test]
+fn validator_and_native_agree_on_testdata() {
+    let diagrams = testdata_diagrams();
+    assert!(diagrams.len() > 30);
+    for (name, diagram) in diagrams {
+        assert_agree(&name, &diagram);
+    }
+}
+
+#[test]
+fn flowchart_accept_and_reject_the_same_inputs() {
+    for diagram in [
+        "graph LR\nA --> B\n",
+        "flowchart TD\nA[Start] -->|go| B{Check}\nB -.-> C((End))\n",
+        "stateDiagram-v2\n[*] --> Still\nStill --> [*]\n",
+        "graph LR\naccTitle: Title\nA --> B\n",
+    ] {
+        assert!(assert_agree("valid", diagram), "应当有效: {diagram}");
+    }
+    for diagram in [
+        // selkie 拒绝：native 渲染也报错
+        "flowchart LR\nA --> B\nB -->\n",
+        // native parser 拒绝（方向不对）：validator 也判无效
+        "graph XY\nA --> B\n",
+        "   \n",
+    ] {
+        assert!(!assert_agree("invalid", diagram), "应当无效: {diagram}");
+    }
+}
+
+#[test]
+fn sequence_accept_and_reject_the_same_inputs() {
+    for diagram in [
+        "sequenceDiagram\n  Alice->>Bob: Hello\n  Bob-->>Alice: Hi\n",
+        "sequenceDiagram\n  participant A as Alice\n  loop every minute\n    A->>B: ping\n  end\n",
+        "sequenceDiagram\n  A->>B: first; B->>A: second\n",
+    ] {
+        assert!(assert_agree("valid", diagram), "应当有效: {diagram}");
+    }
+    // selkie 拒绝：native ASCII 与 SVG 都报错，而不是把这行当成 participant 照画
+    let diagram = "sequenceDiagram\n  Alice->>Bob: Hello\n  Bob->>\n";
+    assert!(!assert_agree("invalid", diagram), "应当无效: {diagram}");
+}
+
+#[test]
+fn class_er_accept_and_reject_the_same_inputs() {
+    for diagram in [
+        "classDiagram\n  class Animal {\n    +String name\n  }\n  Animal <|-- Dog\n",
+        "classDiagram\n  A \"1\" *-- \"many\" B : has\n",
+        "erDiagram\n  CUSTOMER ||--o{ ORDER : places\n",
+        "erDiagram\n  CUSTOMER {\n    string name PK\n  }\n",
+    ] {
+        assert!(assert_agree("valid", diagram), "应当有效: {diagram}");
+    }
+    // selkie 拒绝：native ASCII 与 SVG 都报错，而不是跳过这行照画
+    for diagram in [
+        "classDiagram\n  A <|-- B\n  B -->\n",
+        "erDiagram\n  A ||--o{ B : has\n  B -->\n",
+    ] {
+        assert!(!assert_agree("invalid", diagram), "应当无效: {diagram}");
+    }
+}
@@ -0,0 +1,105 @@
+// ============================================================================
+// vendor bundle 扩展守卫
+//
+// 背景：
+// - vendor bundle 里有几处上游 TS 还没有的扩展（README“同步上游 bundle”列了清单），
+//   `scripts/sync-vendor-bundle.sh` 整体覆盖 bundle，上游缺扩展时这些功能会静默消失；
+// - 同步脚本已经先跑 `scripts/check-bundle-extensions.js`，这里在 Rust 侧再守一道：
+//   直接加载 vendor 下的文件（与内嵌的是同一份），缺哪项扩展就在对应用例里失败，并指明原因。
+// ============================================================================
+
+#![cfg(feature = "js-backend")]
+
+use beautiful_mermaid_rs::{EngineConfig, JsEngine, RenderOptions};
+
+const VENDORED_BUNDLE: &str = "vendor/beautiful-mermaid/beautiful-mermaid.browser.global.js";
+
+/// 每种 SVG 图类型一个最小用例：(图类型, Mermaid 文本)，都只有节点 A、B 和一条 A → B 的边。
+const DIAGRAMS: &[(&str, &str)] = &[
+    ("flowchart", "graph TD\nA --> B"),
+    ("sequence", "sequenceDiagram\nA->>B: hi"),
+    ("class", "classDiagram\nA <|-- B"),
+    ("er", "erDiagram\nA ||--o{ B : has"),
+];
+
+fn vendored_engine() -> JsEngine {
+    EngineConfig::new()
+        .bundle_path(VENDORED_BUNDLE)
+        .build()
+        .expect("加载 vendor bundle")
+}
+
+#[test]
+fn vendored_bundle_emits_element_ids_and_data_attributes() {
+    let engine = vendored_engine();
+    let options = RenderOptions {
+        id_prefix: Some("p-".to_string()),
+        ..Default::default()
+    };
+    for (kind, diagram) in DIAGRAMS {
+        let svg = engine.render_mermaid_svg(diagram, &options).unwrap();
+        let mut expected = vec![
+            r#"<g id="p-node-A" class="node" data-node-id="A">"#,
+            r#"<g id="p-edge-A-B-0" class="edge" data-edge-from="A" data-edge-to="B">"#,
+        ];
+        if *kind != "er" {
+            expected.push("url(#p-"); // 箭头 marker 也带前缀（ER 的边没有 marker）
+        }
+        for expected in expected {
+            assert!(
+                svg.contains(expected),
+                "{kind}: vendor bundle 缺少元素 id / data-* 扩展（`{expected}`）。\
+                 是不是从缺扩展的上游重新同步了？见 README“同步上游 bundle”"
+            );
+        }
+    }
+}
+
+#[test]
+fn vendored_bundle_emits_accessible_svg() {
+    let engine = vendored_engine();
+    let options = RenderOptions {
+        id_prefix: Some("p-".to_string()),
+        ..Default::default()
+    };
+    for ((kind, diagram), description) in DIAGRAMS.iter().zip([
+        "Flowchart with 2 nodes",
+        "Sequence diagram with 2 participants",
+        "Class diagram with 2 classes",
+        "Entity relationship diagram with 2 entities",
+    ]) {
+        // accTitle 要被 bundle 去掉（否则会被当成节点），换成 <title>
+        let svg = engine
+            .render_mermaid_svg(&format!("accTitle: Probe\n{diagram}"), &options)
+            .unwrap_or_else(|err| {
+                panic!("{kind}: vendor bundle 不认识 accTitle（无障碍扩展缺失？）: {err}")
+            });
+        for expected in [
+            r#"<svg role="img""#,
+            r#"<title id="p-title">Probe</title>"#,
+            &format!(r#"<desc id="p-desc">{description}"#),
+        ] {
+            assert!(
+                svg.contains(expected),
+                "{kind}: vendor bundle 缺少无障碍扩展（`{expected}`）。\
+                 是不是从缺扩展的上游重新同步了？见 README“同步上游 bundle”"
+            );
+        }
+    }
+
+    let svg = engine
+        .render_mermaid_svg(
+            DIAGRAMS[0].1,
+            &RenderOptions {
+                title: Some("T".to_string()),
+                description: Some("D".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert!(
+        svg.contains(r#"<title id="title">T</title>"#)
+            && svg.contains(r#"<desc id="desc">D</desc>"#),
+        "vendor bundle 忽略了 title / description 选项（无障碍扩展缺失）"
+    );
+}